@@ -1,28 +1,227 @@
 use std::{path::PathBuf, str::FromStr as _};
 
 use anyhow::anyhow;
+use fj_export::{Unit, View};
 use fj_host::Parameters;
-use fj_kernel::algorithms::Tolerance;
-use fj_math::Scalar;
+use fj_interop::axes::UpAxis;
+use fj_kernel::algorithms::{DecimationTarget, DraftCheck, Tolerance};
+use fj_math::{Scalar, Vector};
 
 /// Fornjot - Experimental CAD System
 #[derive(clap::Parser)]
 pub struct Args {
-    /// The model to open
-    #[clap(short, long)]
-    pub model: Option<PathBuf>,
+    /// What to do with the model; opens it in a window, by default
+    #[clap(subcommand)]
+    pub command: Option<Command>,
 
-    /// Export model to this path
-    #[clap(short, long)]
-    pub export: Option<PathBuf>,
+    /// The model to open
+    ///
+    /// Can be passed more than once, to view each model in its own window.
+    #[clap(short, long, global = true)]
+    pub model: Vec<PathBuf>,
 
     /// Parameters for the model, each in the form `key=value`
-    #[clap(short, long, parse(try_from_str = parse_parameters))]
+    #[clap(short, long, global = true, parse(try_from_str = parse_parameters))]
     pub parameters: Option<Parameters>,
 
     /// Model deviation tolerance
-    #[clap[short, long, parse(try_from_str = parse_tolerance)]]
+    ///
+    /// Either in model units (`0.01`), relative to the size of the model
+    /// (`0.1%`), or the sum of both (`0.01+0.1%`). Append a maximum angle per
+    /// segment of curves, like `0.1%,15deg`. Overrides the tolerance from the
+    /// configuration.
+    #[clap[short, long, global = true]]
     pub tolerance: Option<Tolerance>,
+
+    /// Simplify the mesh to at most this number of triangles
+    #[clap(long, global = true, conflicts_with = "max-error")]
+    pub max_triangles: Option<usize>,
+
+    /// Simplify the mesh, as long as it deviates less than this
+    #[clap(long, global = true, parse(try_from_str = parse_max_error))]
+    pub max_error: Option<Scalar>,
+
+    /// Produce bit-identical output for identical models
+    #[clap(long, global = true)]
+    pub deterministic: bool,
+
+    /// Report walls that are thinner than this
+    ///
+    /// The viewer highlights them as debug info, in the analysis category.
+    #[clap(long, global = true, parse(try_from_str = parse_min_wall_thickness))]
+    pub min_wall_thickness: Option<Scalar>,
+
+    /// Check the draft of the model, for this pull direction, like `0,0,1`
+    ///
+    /// The viewer highlights faces without draft and undercuts as debug info,
+    /// in the analysis category.
+    #[clap(long, global = true, parse(try_from_str = parse_pull_direction))]
+    pub pull_direction: Option<Vector<3>>,
+
+    /// The smallest acceptable draft angle, in degrees
+    #[clap(
+        long,
+        global = true,
+        default_value = "1",
+        requires = "pull-direction",
+        parse(try_from_str = parse_min_draft_angle)
+    )]
+    pub min_draft_angle: Scalar,
+
+    /// Print the topology of the model, instead of running the command
+    ///
+    /// Lists the faces of the model's boundary representation, with their
+    /// cycles, edges, and vertices. Please include this when reporting a bug
+    /// in the kernel.
+    #[clap(long, global = true)]
+    pub dump_topology: bool,
+
+    /// Print the object graph of the model, instead of running the command
+    ///
+    /// The graph is in the DOT format of Graphviz. Objects that are referred
+    /// to, without being part of the model's shape, are drawn in red.
+    #[clap(long, global = true, conflicts_with = "dump-topology")]
+    pub dump_graph: bool,
+
+    /// Log more details; can be repeated, up to three times
+    ///
+    /// `RUST_LOG` takes precedence, if it is defined.
+    #[clap(short, long, global = true, parse(from_occurrences))]
+    pub verbose: u8,
+}
+
+/// The commands that the application supports
+#[derive(clap::Subcommand)]
+pub enum Command {
+    /// Open the model in a window, reloading it whenever it changes
+    ///
+    /// This is the default, if no command is given.
+    View(ViewArgs),
+
+    /// Export the model to a file
+    Export(ExportArgs),
+
+    /// Evaluate the model and check it for problems, without opening a window
+    ///
+    /// Exits with an error, if there are any problems. Suitable for running
+    /// in the CI of a repository of models.
+    Check(CheckArgs),
+
+    /// List the parameters that the model accepts, with their defaults
+    Params,
+
+    /// Write the bill of materials of the model, listing its parts
+    ///
+    /// Each member of a group is a part. Parts are named using `fj::Named`.
+    Bom(BomArgs),
+
+    /// Run without a window, serving JSON-RPC requests
+    Serve(ServeArgs),
+
+    /// Create a new model, ready to be opened
+    New(NewArgs),
+}
+
+/// Options of the `view` command
+#[derive(clap::Args, Default)]
+pub struct ViewArgs {
+    /// Compare the model to an older version of it
+    ///
+    /// Either the path of another model, or a git revision of the repository
+    /// that contains the model, like `HEAD~1`. Volume that has been added is
+    /// shown in green, volume that has been removed in red.
+    #[clap(long, value_name = "MODEL_OR_REVISION")]
+    pub diff: Option<String>,
+}
+
+/// Options of the `export` command
+#[derive(clap::Args)]
+pub struct ExportArgs {
+    /// The file to export to; its extension determines the format
+    #[clap(required_unless_present = "list-formats")]
+    pub path: Option<PathBuf>,
+
+    /// List the formats that models can be exported to, then exit
+    #[clap(long)]
+    pub list_formats: bool,
+
+    /// Height of the cross-section, when exporting to DXF
+    ///
+    /// Defaults to the bottom of the model.
+    #[clap(long, value_name = "HEIGHT")]
+    pub section: Option<f64>,
+
+    /// View to draw, when exporting to SVG or PDF
+    ///
+    /// One of `top`, `front`, `right`, or `isometric`. Defaults to `front`.
+    #[clap(long)]
+    pub view: Option<View>,
+
+    /// Unit of the model's coordinates
+    ///
+    /// One of `micron`, `millimeter`, `centimeter`, `inch`, `foot`, or `meter`,
+    /// or their abbreviations (`um`, `mm`, `cm`, `in`, `ft`, `m`). Formats that
+    /// can record the unit do so. Others convert the coordinates to the unit
+    /// they use. Defaults to `millimeter`.
+    #[clap(long, visible_alias = "units")]
+    pub unit: Option<Unit>,
+
+    /// Scale the model by this factor
+    #[clap(long, value_name = "FACTOR")]
+    pub export_scale: Option<f64>,
+
+    /// The axis that points up, when exporting to a 3D format
+    ///
+    /// Either `z`, like in Fornjot and most CAD applications, or `y`, like in
+    /// many graphics applications and game engines. Defaults to `z`.
+    #[clap(long, value_name = "AXIS")]
+    pub up_axis: Option<UpAxis>,
+}
+
+/// Options of the `check` command
+#[derive(clap::Args)]
+pub struct CheckArgs {
+    /// Treat warnings, like a mesh that is not watertight, as errors
+    #[clap(long)]
+    pub deny_warnings: bool,
+}
+
+/// Options of the `bom` command
+#[derive(clap::Args)]
+pub struct BomArgs {
+    /// The file to write to; its extension determines the format
+    ///
+    /// Either `csv` or `json`. The bill of materials is printed as CSV, if no
+    /// file is given.
+    pub path: Option<PathBuf>,
+
+    /// Density of parts without a material in g/cm³
+    ///
+    /// Masses are computed from the densities of the parts' materials, and
+    /// assume that the model is in millimeters. Parts without a material only
+    /// get a mass, if this is given.
+    #[clap(long)]
+    pub density: Option<f64>,
+}
+
+/// Options of the `serve` command
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    /// The address to serve JSON-RPC requests on
+    ///
+    /// Must be a loopback address, like `127.0.0.1:0`. Use port 0 to let the
+    /// operating system choose a free port. The address that is actually used
+    /// is printed on startup.
+    pub address: String,
+}
+
+/// Options of the `new` command
+#[derive(clap::Args)]
+pub struct NewArgs {
+    /// The directory to create the model in
+    ///
+    /// The model is named after the directory.
+    pub path: PathBuf,
 }
 
 impl Args {
@@ -33,6 +232,36 @@ impl Args {
     pub fn parse() -> Self {
         <Self as clap::Parser>::parse()
     }
+
+    /// Determine the mesh decimation target, if any was specified
+    pub fn decimation(&self) -> Option<DecimationTarget> {
+        if let Some(count) = self.max_triangles {
+            return Some(DecimationTarget::TriangleCount(count));
+        }
+        if let Some(max_error) = self.max_error {
+            return Some(DecimationTarget::MaxError(max_error));
+        }
+
+        None
+    }
+
+    /// Determine the parameters of the draft analysis, if it was requested
+    pub fn draft(&self) -> Option<DraftCheck> {
+        self.pull_direction.map(|pull_direction| DraftCheck {
+            pull_direction,
+            min_angle: self.min_draft_angle,
+        })
+    }
+
+    /// Determine the log level, based on the number of `--verbose` flags
+    pub fn log_level(&self) -> &'static str {
+        match self.verbose {
+            0 => "WARN",
+            1 => "INFO",
+            2 => "DEBUG",
+            _ => "TRACE",
+        }
+    }
 }
 
 fn parse_parameters(input: &str) -> anyhow::Result<Parameters> {
@@ -56,10 +285,46 @@ fn parse_parameters(input: &str) -> anyhow::Result<Parameters> {
     Ok(parameters)
 }
 
-fn parse_tolerance(input: &str) -> anyhow::Result<Tolerance> {
-    let tolerance = f64::from_str(input)?;
-    let tolerance = Scalar::from_f64(tolerance);
-    let tolerance = Tolerance::from_scalar(tolerance)?;
+fn parse_max_error(input: &str) -> anyhow::Result<Scalar> {
+    let max_error = f64::from_str(input)?;
+    if max_error.is_nan() || max_error < 0. {
+        return Err(anyhow!("Maximum error must not be negative"));
+    }
+
+    Ok(Scalar::from_f64(max_error))
+}
+
+fn parse_min_wall_thickness(input: &str) -> anyhow::Result<Scalar> {
+    let thickness = f64::from_str(input)?;
+    if thickness.is_nan() || thickness <= 0. {
+        return Err(anyhow!("Minimum wall thickness must be positive"));
+    }
+
+    Ok(Scalar::from_f64(thickness))
+}
+
+fn parse_pull_direction(input: &str) -> anyhow::Result<Vector<3>> {
+    let components = input
+        .split(',')
+        .map(|component| f64::from_str(component.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let components: [f64; 3] = components
+        .try_into()
+        .map_err(|_| anyhow!("Pull direction must have three components"))?;
+    let direction = Vector::from(components);
+    if direction.magnitude() == Scalar::ZERO {
+        return Err(anyhow!("Pull direction must not be zero"));
+    }
+
+    Ok(direction)
+}
+
+fn parse_min_draft_angle(input: &str) -> anyhow::Result<Scalar> {
+    let angle = f64::from_str(input)?;
+    if angle.is_nan() || !(0. ..90.).contains(&angle) {
+        return Err(anyhow!("Minimum draft angle must be between 0 and 90"));
+    }
 
-    Ok(tolerance)
+    Ok(Scalar::from_f64(angle.to_radians()))
 }
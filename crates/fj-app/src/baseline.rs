@@ -0,0 +1,207 @@
+//! Loading an older version of a model, to compare it to
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::{anyhow, Context as _};
+use fj_host::{Model, Parameters};
+use fj_operations::shape_processor::{ProcessedShape, ShapeProcessor};
+
+/// Load and process the baseline that the model at `path` is compared to
+///
+/// `baseline` is either the path of another model, or a git revision. In the
+/// latter case, the repository that contains the model is checked out at that
+/// revision into a temporary worktree, and the model is loaded from there.
+///
+/// The baseline must be built against the same version of `fj` as this
+/// application, as the shapes it returns are passed to it as is. If the
+/// repository contains the source of `fj`, like a checkout of the Fornjot
+/// repository does, the worktree's copy of it is replaced with the one this
+/// application was built from.
+pub fn load_baseline(
+    path: &Path,
+    baseline: &str,
+    target_dir: Option<PathBuf>,
+    parameters: &Parameters,
+    shape_processor: &ShapeProcessor,
+) -> anyhow::Result<ProcessedShape> {
+    let worktree;
+    let (path, target_dir) = if Path::new(baseline).exists() {
+        (PathBuf::from(baseline), target_dir)
+    } else {
+        worktree = Worktree::add(path, baseline)?;
+        let target_dir = target_dir.map(|dir| worktree.translate(&dir));
+        (worktree.translate(path), target_dir)
+    };
+
+    println!("Loading baseline from {}", path.display());
+
+    let model =
+        Model::from_path(path.clone(), target_dir).with_context(|| {
+            format!("Failed to load baseline: {}", path.display())
+        })?;
+    let shape = model
+        .load_once(parameters)
+        .context("Failed to load baseline")?;
+
+    Ok(shape_processor.process(&shape))
+}
+
+/// A temporary checkout of a git repository, that is removed when dropped
+struct Worktree {
+    repository: PathBuf,
+    path: PathBuf,
+}
+
+impl Worktree {
+    /// Check out the repository that contains `model`, at `revision`
+    fn add(model: &Path, revision: &str) -> anyhow::Result<Self> {
+        let model = model.canonicalize().with_context(|| {
+            format!("Failed to find model: {}", model.display())
+        })?;
+        let dir = if model.is_dir() {
+            model.as_path()
+        } else {
+            // Can't panic. A canonical path to a file has a parent.
+            model.parent().unwrap()
+        };
+
+        let repository =
+            git(dir, ["rev-parse", "--show-toplevel"]).map_err(|err| {
+                anyhow!(
+                    "`{}` is neither a model, nor a revision of the \
+                    repository that contains the model: {}",
+                    revision,
+                    err
+                )
+            })?;
+        let repository = PathBuf::from(repository).canonicalize()?;
+
+        let path =
+            env::temp_dir().join(format!("fj-baseline-{}", process::id()));
+        git(
+            &repository,
+            [
+                OsStr::new("worktree"),
+                OsStr::new("add"),
+                OsStr::new("--detach"),
+                path.as_os_str(),
+                OsStr::new(revision),
+            ],
+        )
+        .with_context(|| format!("Failed to check out `{}`", revision))?;
+
+        let worktree = Self { repository, path };
+        worktree.use_host_fj()?;
+
+        Ok(worktree)
+    }
+
+    /// Replace the worktree's copy of `fj` with the one this application was
+    /// built from
+    ///
+    /// Does nothing, if that is not part of the repository. The model then
+    /// depends on the same `fj` in the worktree as outside of it.
+    fn use_host_fj(&self) -> anyhow::Result<()> {
+        let host_fj = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fj");
+        let host_fj = match host_fj.canonicalize() {
+            Ok(host_fj) => host_fj,
+            Err(_) => return Ok(()),
+        };
+
+        let fj = self.translate(&host_fj);
+        if fj == host_fj {
+            return Ok(());
+        }
+
+        if fj.exists() {
+            fs::remove_dir_all(&fj)?;
+        }
+        copy_dir(&host_fj, &fj).with_context(|| {
+            format!("Failed to copy `fj` into worktree: {}", fj.display())
+        })
+    }
+
+    /// Translate a path within the repository to the same path within the
+    /// worktree
+    ///
+    /// Paths outside of the repository are returned unchanged.
+    fn translate(&self, path: &Path) -> PathBuf {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+
+        match path.strip_prefix(&self.repository) {
+            Ok(relative) => self.path.join(relative),
+            Err(_) => path,
+        }
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let removed = git(
+            &self.repository,
+            [
+                OsStr::new("worktree"),
+                OsStr::new("remove"),
+                OsStr::new("--force"),
+                self.path.as_os_str(),
+            ],
+        );
+
+        if let Err(err) = removed {
+            eprintln!("Failed to remove worktree: {}", err);
+
+            // Leave at least no files behind. Git cleans up its records of
+            // worktrees that don't exist anymore, eventually.
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// Copy the directory `from` to `to`, leaving out build output
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let (from, to) = (entry.path(), to.join(entry.file_name()));
+
+        if entry.file_type()?.is_dir() {
+            if entry.file_name() != "target" {
+                copy_dir(&from, &to)?;
+            }
+        } else {
+            fs::copy(&from, &to)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run git in `dir`, returning its output
+fn git<I, S>(dir: &Path, args: I) -> anyhow::Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
@@ -0,0 +1,96 @@
+//! Output of bills of materials, as CSV or JSON
+
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context as _};
+use fj_operations::bom::BillOfMaterials;
+use serde_json::json;
+
+/// Write the bill of materials to the file at the given path
+///
+/// The format is picked by the file extension, either `csv` or `json`. If no
+/// path is given, the bill of materials is printed as CSV.
+pub fn write_bom(
+    bom: &BillOfMaterials,
+    path: Option<&Path>,
+) -> anyhow::Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            print!("{}", to_csv(bom));
+            return Ok(());
+        }
+    };
+
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+    let output = match extension.as_deref() {
+        Some("csv") => to_csv(bom),
+        Some("json") => serde_json::to_string_pretty(&to_json(bom))?,
+        _ => {
+            return Err(anyhow!(
+                "Unsupported format for bill of materials: {}; expected .csv \
+                or .json",
+                path.display()
+            ))
+        }
+    };
+
+    fs::write(path, output)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn to_csv(bom: &BillOfMaterials) -> String {
+    let mut csv = String::from("name,material,count,volume,area,mass\n");
+
+    for entry in &bom.entries {
+        let name = entry.name.as_deref().unwrap_or("");
+        let material = entry.material.as_deref().unwrap_or("");
+        let mass = entry.mass.map(|mass| mass.to_string()).unwrap_or_default();
+
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(name),
+            csv_field(material),
+            entry.count,
+            entry.volume,
+            entry.area,
+            mass
+        ));
+    }
+
+    csv
+}
+
+/// Quote a field, if it contains characters that have a meaning in CSV
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn to_json(bom: &BillOfMaterials) -> serde_json::Value {
+    let entries: Vec<_> = bom
+        .entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "name": entry.name,
+                "material": entry.material,
+                "count": entry.count,
+                "volume": entry.volume,
+                "area": entry.area,
+                "mass": entry.mass,
+            })
+        })
+        .collect();
+
+    json!({
+        "parts": entries,
+        "total_mass": bom.total_mass(),
+    })
+}
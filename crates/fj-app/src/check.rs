@@ -0,0 +1,69 @@
+//! Evaluation of models without a window, for use in CI
+
+use anyhow::{anyhow, Context as _};
+use fj_host::{Model, Parameters};
+use fj_operations::shape_processor::ShapeProcessor;
+
+use crate::diagnostics::{self, Severity};
+
+/// Evaluate the model and print any problems with it
+///
+/// Returns an error, if the model can't be evaluated, or if there are any
+/// problems that `deny_warnings` says are errors.
+pub fn check(
+    model: &Model,
+    parameters: &Parameters,
+    shape_processor: &ShapeProcessor,
+    deny_warnings: bool,
+) -> anyhow::Result<()> {
+    let mut load_error = None;
+    let diagnostics = diagnostics::evaluate(
+        || {
+            model
+                .load_once(parameters)
+                .map_err(|err| load_error = Some(err))
+                .ok()
+        },
+        shape_processor,
+    );
+    if let Some(err) = load_error {
+        return Err(err).context("Failed to load model");
+    }
+
+    for diagnostic in &diagnostics {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        if diagnostic.operations.is_empty() {
+            println!("{}: {}", severity, diagnostic.message);
+        } else {
+            println!(
+                "{}: {} (in {})",
+                severity,
+                diagnostic.message,
+                diagnostic.operations.join(" > ")
+            );
+        }
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    if errors > 0 || (deny_warnings && warnings > 0) {
+        return Err(anyhow!(
+            "Found {} error(s) and {} warning(s)",
+            errors,
+            warnings
+        ));
+    }
+    if diagnostics.is_empty() {
+        println!("No problems found");
+    }
+
+    Ok(())
+}
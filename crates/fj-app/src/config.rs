@@ -1,25 +1,141 @@
-use std::path::PathBuf;
+use std::{env, fmt::Display, path::PathBuf, str::FromStr};
 
 use anyhow::Context as _;
 use figment::{
     providers::{Env, Format as _, Toml},
     Figment,
 };
-use serde::Deserialize;
+use fj_export::{Unit, View};
+use fj_interop::axes::UpAxis;
+use fj_kernel::algorithms::Tolerance;
+use fj_viewer::{
+    graphics::{Backend, ThemeConfig},
+    input,
+};
+use serde::{de, Deserialize, Deserializer};
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub default_path: Option<PathBuf>,
     pub default_model: Option<PathBuf>,
     pub target_dir: Option<PathBuf>,
+    pub author: Option<String>,
+
+    /// The tolerance that is used, if none is passed on the command line
+    #[serde(default, deserialize_with = "parse_tolerance")]
+    pub tolerance: Option<Tolerance>,
+
+    /// The graphics API of the viewer; picked automatically, if not set
+    pub backend: Option<Backend>,
+
+    #[serde(default)]
+    pub export: ExportConfig,
+
+    #[serde(default)]
+    pub input: input::Config,
+
+    #[serde(default)]
+    pub theme: ThemeConfig,
 }
 
 impl Config {
+    /// Load the configuration
+    ///
+    /// The per-user configuration file is loaded first, if there is one. The
+    /// `fj.toml` in the current directory overrides it, and environment
+    /// variables override both.
     pub fn load() -> Result<Self, anyhow::Error> {
-        Figment::new()
+        let mut figment = Figment::new();
+        if let Some(path) = user_config_path() {
+            figment = figment.merge(Toml::file(path));
+        }
+
+        figment
             .merge(Toml::file("fj.toml"))
             .merge(Env::prefixed("FJ_"))
             .extract()
             .context("Error loading configuration")
     }
 }
+
+/// Defaults for the options of the `export` command
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportConfig {
+    #[serde(default, deserialize_with = "parse")]
+    pub unit: Option<Unit>,
+
+    pub scale: Option<f64>,
+
+    #[serde(default, deserialize_with = "parse")]
+    pub up_axis: Option<UpAxis>,
+
+    #[serde(default, deserialize_with = "parse")]
+    pub view: Option<View>,
+}
+
+/// The location of the per-user configuration file
+///
+/// Follows each platform's convention for where configuration goes. Returns
+/// `None`, if the home directory can't be determined.
+fn user_config_path() -> Option<PathBuf> {
+    let dir = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })
+    };
+
+    dir.map(|dir| dir.join("fornjot").join("fj.toml"))
+}
+
+/// Deserialize a value from a string, using its `FromStr` implementation
+///
+/// This accepts exactly what the corresponding command-line argument accepts.
+fn parse<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Deserialize a tolerance from a number, or from a string like `0.1%`
+///
+/// A number is an absolute tolerance. Strings are parsed like the
+/// `--tolerance` argument.
+fn parse_tolerance<'de, D>(
+    deserializer: D,
+) -> Result<Option<Tolerance>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(f64),
+        String(String),
+    }
+
+    match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Number(value)) => Tolerance::from_scalar(value)
+            .map(Some)
+            .map_err(de::Error::custom),
+        Some(Value::String(value)) => {
+            value.parse().map(Some).map_err(de::Error::custom)
+        }
+        None => Ok(None),
+    }
+}
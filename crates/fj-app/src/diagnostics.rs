@@ -0,0 +1,177 @@
+//! Feedback about a model, for display in an editor
+
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+use fj_host::LoadedShape;
+use fj_interop::topology::Topology;
+use fj_kernel::algorithms::Draft;
+use fj_operations::{assertions, context, shape_processor::ShapeProcessor};
+use serde::Serialize;
+
+/// A problem with a model
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    /// How bad the problem is
+    pub severity: Severity,
+
+    /// A description of the problem
+    pub message: String,
+
+    /// The operations in which the problem occurred, outermost first
+    ///
+    /// Empty, if the problem doesn't relate to a specific operation.
+    pub operations: Vec<&'static str>,
+}
+
+impl Diagnostic {
+    fn error(message: String, operations: Vec<&'static str>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+            operations,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message,
+            operations: Vec::new(),
+        }
+    }
+}
+
+/// How bad a problem is
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The model could not be evaluated, or the result is invalid
+    Error,
+
+    /// The model could be evaluated, but the result is probably not what its
+    /// author intended
+    Warning,
+}
+
+/// Load and process a model, collecting any problems with it
+///
+/// `load` is expected to return `None`, if the model failed to compile. An
+/// empty list means there's nothing to report.
+pub fn evaluate(
+    load: impl FnOnce() -> Option<LoadedShape>,
+    shape_processor: &ShapeProcessor,
+) -> Vec<Diagnostic> {
+    let shape = match panic::catch_unwind(AssertUnwindSafe(load)) {
+        Ok(Some(shape)) => shape,
+        Ok(None) => {
+            return vec![Diagnostic::error(
+                "Model failed to compile; see compiler output".into(),
+                Vec::new(),
+            )];
+        }
+        Err(payload) => {
+            return vec![Diagnostic::error(
+                format!("Error loading model: {}", panic_message(&*payload)),
+                Vec::new(),
+            )];
+        }
+    };
+
+    let shape = match panic::catch_unwind(AssertUnwindSafe(|| {
+        shape_processor.process(&shape)
+    })) {
+        Ok(shape) => shape,
+        Err(payload) => {
+            return vec![Diagnostic::error(
+                panic_message(&*payload),
+                context::take_failed_operations(),
+            )];
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+
+    if let Err(err) = &shape.orientation {
+        diagnostics.push(Diagnostic::error(err.to_string(), Vec::new()));
+    }
+
+    // Which problems a mesh can have depends on what it's meant to represent.
+    // Open edges are fine for a sheet, for example, while a wire shouldn't
+    // have any faces at all.
+    let is_empty = shape.mesh.triangles().next().is_none();
+    let report = &shape.weld_report;
+    match shape.topology {
+        Topology::Solid => {
+            if is_empty {
+                diagnostics.push(Diagnostic::warning("Model is empty".into()));
+            }
+            if !report.is_watertight() {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "Mesh is not watertight ({} boundary edges, {} \
+                    non-manifold edges)",
+                    report.boundary_edges, report.non_manifold_edges,
+                )));
+            }
+        }
+        Topology::Sheet => {
+            if is_empty {
+                diagnostics.push(Diagnostic::warning("Model is empty".into()));
+            }
+            if report.non_manifold_edges > 0 {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "Sheet has {} non-manifold edges",
+                    report.non_manifold_edges,
+                )));
+            }
+        }
+        Topology::Wire => {
+            if !is_empty {
+                diagnostics.push(Diagnostic::warning(
+                    "Model is marked as a wire, but has faces".into(),
+                ));
+            }
+        }
+    }
+
+    for thin_wall in &shape.thin_walls {
+        let [x, y, z] = thin_wall.position.coords.components;
+        diagnostics.push(Diagnostic::warning(format!(
+            "Wall is only {} thick, near ({}, {}, {})",
+            thin_wall.thickness, x, y, z,
+        )));
+    }
+
+    if let Some(draft) = &shape.draft {
+        for (face, &face_draft) in &draft.faces {
+            let problem = match face_draft {
+                Draft::Positive => continue,
+                Draft::Vertical => "has insufficient draft",
+                Draft::Negative => "is an undercut",
+            };
+            diagnostics.push(Diagnostic::warning(format!(
+                "Face {} {}, relative to the pull direction",
+                face.0, problem,
+            )));
+        }
+    }
+
+    for failed in assertions::check(&shape) {
+        diagnostics.push(Diagnostic::error(failed.to_string(), Vec::new()));
+    }
+
+    diagnostics
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return (*message).to_owned();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+
+    "Unknown error".to_owned()
+}
@@ -0,0 +1,74 @@
+//! Export of shapes, in the format that the file name asks for
+
+use std::path::Path;
+
+use anyhow::anyhow;
+use fj_export::{Options, Registry};
+use fj_operations::{assertions, shape_processor::ProcessedShape};
+
+/// Export the shape to the file at the given path
+///
+/// The format is picked by the file extension, from the formats that
+/// [`Registry::default`] supports. The application that created the file is
+/// recorded, unless the options already name one. So are the kind of object the
+/// shape represents, and the names of its materials.
+///
+/// Returns an error without writing the file, if the shape fails any of the
+/// assertions the model attached to it.
+pub fn export(
+    shape: &ProcessedShape,
+    path: &Path,
+    mut options: Options,
+) -> anyhow::Result<()> {
+    let failed = assertions::check(shape);
+    if !failed.is_empty() {
+        let messages: Vec<_> =
+            failed.iter().map(|failed| failed.to_string()).collect();
+        return Err(anyhow!(
+            "Shape doesn't satisfy the model's assertions; no file was \
+            written\n{}",
+            messages.join("\n")
+        ));
+    }
+
+    options.metadata.application.get_or_insert_with(|| {
+        format!("Fornjot {}", env!("CARGO_PKG_VERSION"))
+    });
+    options.metadata.topology = shape.topology;
+
+    for triangle in shape.mesh.triangles() {
+        let material = triangle
+            .face
+            .and_then(|face| shape.face_attributes.get(face.0 as usize))
+            .and_then(|attributes| attributes.material());
+
+        // Textures can change the colors of a material, and those shouldn't
+        // be named after it.
+        if let Some(material) = material {
+            if material.color == triangle.color {
+                options
+                    .metadata
+                    .material_names
+                    .entry(triangle.color)
+                    .or_insert_with(|| material.name.clone());
+            }
+        }
+    }
+
+    Registry::default()
+        .export(&shape.mesh, path, &options)
+        .map_err(|err| anyhow!(err))
+}
+
+/// Print the supported export formats, one per line
+pub fn list_formats() {
+    for exporter in Registry::default().exporters() {
+        let extensions: Vec<_> = exporter
+            .extensions()
+            .iter()
+            .map(|extension| format!(".{}", extension))
+            .collect();
+
+        println!("{:<6} {}", exporter.name(), extensions.join(", "));
+    }
+}
@@ -0,0 +1,48 @@
+//! Handling of Ctrl-C
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use fj_interop::progress::Progress;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Cancel `progress`, once the user presses Ctrl-C
+///
+/// This replaces the default behavior of terminating the process right away.
+/// The operation that `progress` belongs to can then stop at a point of its
+/// choosing, and no file is left half-written.
+///
+/// Not all operations check `progress`, and those that do might only check it
+/// now and then. To not leave the user stuck, the default behavior is restored
+/// after the first Ctrl-C, so pressing it a second time terminates the process.
+pub fn cancel_on_interrupt(progress: Progress) {
+    extern "C" fn handle_interrupt(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+
+        // This is sound, as `signal` is async-signal-safe.
+        unsafe {
+            libc::signal(libc::SIGINT, libc::SIG_DFL);
+        }
+    }
+
+    // This is sound, as the handler only stores to an atomic and restores the
+    // default handler, both of which are safe to do from within a signal
+    // handler.
+    unsafe {
+        libc::signal(libc::SIGINT, handle_interrupt as libc::sighandler_t);
+    }
+
+    // Cancelling `progress` from within the signal handler would not be safe.
+    // Let's do it from a thread instead.
+    thread::spawn(move || {
+        while !INTERRUPTED.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        progress.cancel();
+    });
+}
@@ -13,65 +13,342 @@
 //! [Fornjot repository]: https://github.com/hannobraun/Fornjot
 
 mod args;
+mod baseline;
+mod bom;
+mod check;
 mod config;
+mod diagnostics;
+mod export;
+mod interrupt;
+mod new;
+mod server;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context as _};
-use fj_export::export;
+use fj_export::{Metadata, Options};
 use fj_host::{Model, Parameters};
-use fj_operations::shape_processor::ShapeProcessor;
-use fj_viewer::run::run;
-use tracing_subscriber::fmt::format;
-use tracing_subscriber::EnvFilter;
+use fj_interop::progress::Progress;
+use fj_operations::{bom::BillOfMaterials, shape_processor::ShapeProcessor};
+use fj_viewer::{
+    profiler::Timings,
+    run::{run, Models, ViewerWindow},
+    view_state,
+};
+use tracing_subscriber::fmt::{format, format::FmtSpan};
+use tracing_subscriber::{prelude::*, EnvFilter};
 
-use crate::{args::Args, config::Config};
+use crate::{
+    args::{
+        Args, BomArgs, CheckArgs, Command, ExportArgs, NewArgs, ServeArgs,
+        ViewArgs,
+    },
+    baseline::load_baseline,
+    bom::write_bom,
+    check::check,
+    config::Config,
+    export::{export, list_formats},
+    interrupt::cancel_on_interrupt,
+    new::new,
+    server::Server,
+};
 
 fn main() -> anyhow::Result<()> {
-    // Respect `RUST_LOG`. If that's not defined or erroneous, log warnings and
-    // above.
+    let mut args = Args::parse();
+
+    // Respect `RUST_LOG`. If that's not defined or erroneous, log according to
+    // `--verbose`, which defaults to warnings and above.
     //
     // It would be better to fail, if `RUST_LOG` is erroneous, but I don't know
     // how to distinguish between that and the "not defined" case.
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| EnvFilter::new("WARN")),
+    //
+    // Closing spans are logged, along with the time spent in them, so it's
+    // possible to see where time is spent.
+    //
+    // The filter only applies to the log output. The profiler overlay of the
+    // viewer gets to see all spans.
+    let timings = Timings::new();
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_span_events(FmtSpan::CLOSE)
+                .event_format(format().pretty())
+                .with_filter(
+                    EnvFilter::try_from_default_env()
+                        .unwrap_or_else(|_| EnvFilter::new(args.log_level())),
+                ),
         )
-        .event_format(format().pretty())
+        .with(timings.layer())
         .init();
 
-    let args = Args::parse();
+    let command = args
+        .command
+        .take()
+        .unwrap_or_else(|| Command::View(ViewArgs::default()));
+
+    if let Command::Export(ExportArgs {
+        list_formats: true, ..
+    }) = command
+    {
+        list_formats();
+        return Ok(());
+    }
+    if let Command::New(NewArgs { path }) = &command {
+        return new(path);
+    }
+
     let config = Config::load()?;
 
-    let mut path = config.default_path.unwrap_or_else(|| PathBuf::from(""));
-    let model = args.model.or(config.default_model).ok_or_else(|| {
-        anyhow!(
-            "No model specified, and no default model configured.\n\
-                Specify a model by passing `--model path/to/model`."
-        )
-    })?;
-    path.push(model);
+    let tolerance = args.tolerance.or(config.tolerance);
 
-    let model = Model::from_path(path.clone(), config.target_dir)
-        .with_context(|| format!("Failed to load model: {}", path.display()))?;
+    let shape_processor = ShapeProcessor {
+        tolerance,
+        decimation: args.decimation(),
+        deterministic: args.deterministic,
+        min_wall_thickness: args.min_wall_thickness,
+        draft: args.draft(),
+    };
+
+    let default_path = config.default_path.unwrap_or_else(|| PathBuf::from(""));
     let parameters = args.parameters.unwrap_or_else(Parameters::empty);
 
-    let shape_processor = ShapeProcessor {
-        tolerance: args.tolerance,
+    if args.model.len() > 1 && !matches!(command, Command::View(_)) {
+        return Err(anyhow!("Only the `view` command accepts multiple models"));
+    }
+
+    // The server doesn't need a model to start with, as clients can load one
+    // later. The default model is not loaded, to not surprise them.
+    if let Command::Serve(ServeArgs { address }) = command {
+        let mut server = Server::new(
+            default_path.clone(),
+            config.target_dir.clone(),
+            shape_processor,
+        );
+
+        if let Some(model) = args.model.first() {
+            let path = default_path.join(model);
+            let model = Model::from_path(path.clone(), config.target_dir)
+                .with_context(|| {
+                    format!("Failed to load model: {}", path.display())
+                })?;
+
+            server.set_model(model, parameters);
+        }
+
+        return server.serve(&address);
+    }
+
+    let models = if args.model.is_empty() {
+        let model = config.default_model.ok_or_else(|| {
+            anyhow!(
+                "No model specified, and no default model configured.\n\
+                    Specify a model by passing `--model path/to/model`."
+            )
+        })?;
+        vec![model]
+    } else {
+        args.model
     };
+    let mut paths: Vec<_> = models
+        .into_iter()
+        .map(|model| default_path.join(model))
+        .collect();
+
+    if args.dump_topology || args.dump_graph {
+        let path = match paths.as_slice() {
+            [path] => path,
+            _ => {
+                return Err(anyhow!(
+                    "The topology of only a single model can be dumped"
+                ))
+            }
+        };
 
-    if let Some(path) = args.export {
+        let model = Model::from_path(path.clone(), config.target_dir)
+            .with_context(|| {
+                format!("Failed to load model: {}", path.display())
+            })?;
         let shape = model.load_once(&parameters)?;
-        let shape = shape_processor.process(&shape);
 
-        export(&shape.mesh, &path)?;
+        let shape = shape_processor.to_shape(&shape);
+        if args.dump_graph {
+            print!("{}", shape.to_dot());
+        } else {
+            print!("{}", shape.dump());
+        }
+        return Ok(());
+    }
+
+    // Each model is opened in its own window.
+    if let Command::View(ViewArgs { diff }) = command {
+        let windows = match diff {
+            Some(baseline) => {
+                let path = match paths.as_slice() {
+                    [path] => path,
+                    _ => {
+                        return Err(anyhow!(
+                            "Only a single model can be compared to a baseline"
+                        ))
+                    }
+                };
+
+                vec![diff_window(
+                    path,
+                    &baseline,
+                    config.target_dir.clone(),
+                    &parameters,
+                    &shape_processor,
+                )?]
+            }
+            None => paths
+                .iter()
+                .map(|path| {
+                    viewer_window(path, config.target_dir.clone(), &parameters)
+                })
+                .collect::<anyhow::Result<_>>()?,
+        };
+
+        run(
+            windows,
+            shape_processor,
+            timings,
+            config.input,
+            config.theme,
+            config.backend,
+        )?;
 
         return Ok(());
     }
 
-    let watcher = model.load_and_watch(parameters)?;
-    run(watcher, shape_processor)?;
+    // Can't panic. Commands other than `view` have exactly one model, as
+    // checked above.
+    let path = paths.pop().unwrap();
+
+    let model = Model::from_path(path.clone(), config.target_dir)
+        .with_context(|| format!("Failed to load model: {}", path.display()))?;
+
+    match command {
+        Command::Export(export_args) => {
+            let name = path.file_stem().map(ToOwned::to_owned);
+            let shape = model.load_once(&parameters)?;
+
+            let progress = Progress::new();
+            cancel_on_interrupt(progress.clone());
+
+            let shape = shape_processor
+                .process_with_progress(&shape, &progress)
+                .context("Export interrupted; no file was written")?;
+
+            let options = Options {
+                metadata: Metadata {
+                    name: name.map(|name| name.to_string_lossy().into_owned()),
+                    author: config.author,
+                    application: None,
+                    unit: export_args
+                        .unit
+                        .or(config.export.unit)
+                        .unwrap_or_default(),
+                    ..Metadata::default()
+                },
+                scale: export_args
+                    .export_scale
+                    .or(config.export.scale)
+                    .unwrap_or(1.),
+                up_axis: export_args
+                    .up_axis
+                    .or(config.export.up_axis)
+                    .unwrap_or_default(),
+                section: export_args.section,
+                view: export_args
+                    .view
+                    .or(config.export.view)
+                    .unwrap_or_default(),
+            };
+
+            // Can't panic. Clap makes sure there's a path, unless formats are
+            // listed, which has been handled above.
+            let path = export_args.path.unwrap();
+            export(&shape, &path, options)?;
+        }
+        Command::Check(CheckArgs { deny_warnings }) => {
+            check(&model, &parameters, &shape_processor, deny_warnings)?;
+        }
+        Command::Params => {
+            let model_parameters = model.parameters()?;
+            if model_parameters.is_empty() {
+                println!("Model doesn't declare any parameters");
+            }
+            for parameter in model_parameters {
+                println!("{}={}", parameter.name, parameter.default);
+            }
+        }
+        Command::Bom(BomArgs { path, density }) => {
+            let shape = model.load_once(&parameters)?;
+            let shape = shape_processor.process(&shape);
+
+            let bom = BillOfMaterials::new(&shape, density);
+            write_bom(&bom, path.as_deref())?;
+        }
+        Command::View(_) | Command::Serve(_) | Command::New(_) => {
+            unreachable!("Command has been handled above")
+        }
+    }
 
     Ok(())
 }
+
+/// Prepare a window of the viewer for the model at `path`
+///
+/// A directory that isn't a model crate itself might contain several models,
+/// which the user can switch between in the window.
+fn viewer_window(
+    path: &Path,
+    target_dir: Option<PathBuf>,
+    parameters: &Parameters,
+) -> anyhow::Result<ViewerWindow> {
+    let store = view_state::Store::for_model(path);
+
+    let models = if path.is_dir() && !path.join("Cargo.toml").exists() {
+        let models = Model::find_all(path, target_dir).with_context(|| {
+            format!("Failed to find models in {}", path.display())
+        })?;
+        Models::Workspace(models, parameters.clone())
+    } else {
+        let model = Model::from_path(path.to_owned(), target_dir)
+            .with_context(|| {
+                format!("Failed to load model: {}", path.display())
+            })?;
+        Models::Single(model.load_and_watch(parameters.clone())?)
+    };
+
+    Ok(ViewerWindow { models, store })
+}
+
+/// Prepare a window of the viewer, that compares the model at `path` to a
+/// baseline
+///
+/// See [`load_baseline`] for what the baseline can be.
+fn diff_window(
+    path: &Path,
+    baseline: &str,
+    target_dir: Option<PathBuf>,
+    parameters: &Parameters,
+    shape_processor: &ShapeProcessor,
+) -> anyhow::Result<ViewerWindow> {
+    let baseline = load_baseline(
+        path,
+        baseline,
+        target_dir.clone(),
+        parameters,
+        shape_processor,
+    )?;
+
+    let model = Model::from_path(path.to_owned(), target_dir)
+        .with_context(|| format!("Failed to load model: {}", path.display()))?;
+    let watcher = model.load_and_watch(parameters.clone())?;
+
+    Ok(ViewerWindow {
+        models: Models::Diff(watcher, Box::new(baseline)),
+        store: view_state::Store::for_model(path),
+    })
+}
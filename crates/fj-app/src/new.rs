@@ -0,0 +1,177 @@
+//! Creation of new models
+
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context as _};
+
+/// Create a new model crate at the given path
+///
+/// The crate is named after the last component of the path, as that is where
+/// the host application expects to find the compiled model. The directory must
+/// not exist yet, or be empty.
+pub fn new(path: &Path) -> anyhow::Result<()> {
+    let name =
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| {
+                anyhow!("Can't name model after path: {}", path.display())
+            })?;
+    check_name(name)?;
+
+    if path.exists() && fs::read_dir(path)?.next().is_some() {
+        return Err(anyhow!(
+            "Can't create model in {}; directory is not empty",
+            path.display()
+        ));
+    }
+
+    let files = [
+        ("Cargo.toml", cargo_toml(name)),
+        ("src/lib.rs", LIB_RS.to_owned()),
+        (".gitignore", GITIGNORE.to_owned()),
+    ];
+
+    fs::create_dir_all(path.join("src"))?;
+    for (file, contents) in files {
+        let file = path.join(file);
+        fs::write(&file, contents)
+            .with_context(|| format!("Failed to write {}", file.display()))?;
+    }
+
+    println!("Created model `{}` in {}", name, path.display());
+
+    Ok(())
+}
+
+/// Make sure the name can be used as the name of a package
+fn check_name(name: &str) -> anyhow::Result<()> {
+    let starts_with_letter = name
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic());
+    let is_valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !starts_with_letter || !is_valid {
+        return Err(anyhow!(
+            "Invalid model name `{}`; names must start with a letter, and \
+            only contain letters, digits, `-`, and `_`",
+            name
+        ));
+    }
+
+    Ok(())
+}
+
+fn cargo_toml(name: &str) -> String {
+    format!(
+        r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "2021"
+
+# The host application loads models as dynamic libraries.
+[lib]
+crate-type = ["cdylib"]
+
+# The model must be built against the same version of `fj` as the host
+# application, as the shapes it returns are passed between them as is.
+[dependencies]
+fj = {}
+
+# The parameters that the model reads, with their defaults
+[package.metadata.fj.parameters]
+size = "1.0"
+
+# The model is a workspace of its own, so it also builds when it's created
+# inside another one, like a checkout of the Fornjot repository.
+[workspace]
+"#,
+        name,
+        fj_dependency(),
+    )
+}
+
+/// The dependency on `fj`, matching the one that this application was built
+/// with
+///
+/// Releases of `fj` don't track changes to the layout of shapes, so depending
+/// on the released version of the same number is not good enough. If the
+/// source of `fj` that this application was built from is still around, which
+/// is the case when running from a checkout of the repository, the model
+/// depends on that directly.
+fn fj_dependency() -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fj");
+
+    match path.canonicalize() {
+        Ok(path) => {
+            let path = path
+                .display()
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            format!("{{ path = \"{}\" }}", path)
+        }
+        Err(_) => format!("\"{}\"", env!("CARGO_PKG_VERSION")),
+    }
+}
+
+const LIB_RS: &str = r#"use std::collections::HashMap;
+
+// The host application calls this function to get the shape of the model.
+#[no_mangle]
+pub extern "C" fn model(args: &HashMap<String, String>) -> fj::Shape {
+    let size: f64 = args
+        .get("size")
+        .map(|size| size.parse().expect("`size` must be a number"))
+        .unwrap_or(1.0);
+
+    let square = fj::Sketch::from_points(vec![
+        [0., 0.],
+        [size, 0.],
+        [size, size],
+        [0., size],
+    ]);
+    let cube = fj::Sweep::from_path(square.into(), [0., 0., size]);
+
+    cube.into()
+}
+"#;
+
+const GITIGNORE: &str = "target/
+Cargo.lock
+";
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process::Command};
+
+    use super::new;
+
+    // This builds `fj` from scratch, which takes too long to do on every test
+    // run. CI runs it with `--ignored`.
+    #[test]
+    #[ignore]
+    fn new_model_builds() {
+        let path = env::temp_dir()
+            .join(format!("fj-new-{}", std::process::id()))
+            .join("my-model");
+
+        new(&path).unwrap();
+
+        // The model gets its own target directory, or cargo would block on the
+        // lock that the test run itself is holding.
+        let status = Command::new(env!("CARGO"))
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(path.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(path.join("target"))
+            .status()
+            .unwrap();
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert!(status.success());
+    }
+}
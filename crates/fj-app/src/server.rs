@@ -0,0 +1,516 @@
+//! Headless mode, for driving Fornjot from other programs
+//!
+//! The server listens on a local TCP socket. Each line received over a
+//! connection is a [JSON-RPC 2.0] request, and each response is sent back as a
+//! single line.
+//!
+//! Requests can load models from any path and write files to any path, so the
+//! server only accepts loopback addresses, to not expose that to the network.
+//! That doesn't keep out web pages, which can make the browser post to the
+//! server. The connection is closed on the first line that isn't a JSON-RPC
+//! 2.0 request, so the headers of such a post end it, before its body is read.
+//!
+//! Connections are served one after the other. While a client is connected,
+//! any other client that connects has to wait until the first one has
+//! disconnected, before any of its requests are answered.
+//!
+//! The following methods are supported:
+//!
+//! - `load_model`, with a `path` parameter: Load the model at that path.
+//!   Relative paths are resolved like the `--model` argument.
+//! - `set_parameters`, with a `parameters` object: Replace the parameters that
+//!   are passed to the model. All values must be strings.
+//! - `get_mesh`: Evaluate the model and return its triangle mesh, as a list of
+//!   `vertices` and a list of `triangles` that index into them.
+//! - `export`, with a `path` parameter: Evaluate the model and export it to
+//!   the file at that path, in the format that its extension asks for. If the
+//!   path ends in `.dxf`, a cross-section is exported, at the height given by
+//!   the optional `section` parameter, or at the bottom of the model. If it
+//!   ends in `.svg` or `.pdf`, a drawing is exported, from the optional `view`
+//!   parameter (`top`, `front`, `right`, or `isometric`; defaults to
+//!   `front`). The optional `unit` parameter gives the unit of the model's
+//!   coordinates (like `mm` or `in`), and `scale` scales the model first.
+//!   For 3D formats, `up_axis` can be `y` instead of the default `z`.
+//! - `watch`, with a `path` parameter: Load the model at that path, like
+//!   `load_model`, then keep watching it for changes. Whenever the model has
+//!   been re-evaluated, a `diagnostics` notification is sent, with a list of
+//!   the problems that were found (an empty list, if there were none). This
+//!   is meant for editor integrations.
+//!
+//! [JSON-RPC 2.0]: https://www.jsonrpc.org/specification
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead as _, BufReader, Write as _},
+    net::{TcpListener, TcpStream, ToSocketAddrs as _},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use anyhow::anyhow;
+use fj_export::{Metadata, Options, Unit, View};
+use fj_host::{Model, Parameters};
+use fj_interop::axes::UpAxis;
+use fj_math::Scalar;
+use fj_operations::shape_processor::{ProcessedShape, ShapeProcessor};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{diagnostics, export::export};
+
+/// A connection to a client, shared with the thread that sends notifications
+type Connection = Arc<Mutex<TcpStream>>;
+
+/// Serves requests to load, evaluate, and export models
+pub struct Server {
+    default_path: PathBuf,
+    target_dir: Option<PathBuf>,
+    shape_processor: ShapeProcessor,
+
+    model: Option<Model>,
+    parameters: Parameters,
+
+    /// Set to stop the thread that is watching the model, if any
+    stop_watching: Option<Arc<AtomicBool>>,
+}
+
+impl Server {
+    /// Create a server without a loaded model
+    ///
+    /// `default_path` and `target_dir` are used to load models, as configured
+    /// for the rest of the application.
+    pub fn new(
+        default_path: PathBuf,
+        target_dir: Option<PathBuf>,
+        shape_processor: ShapeProcessor,
+    ) -> Self {
+        Self {
+            default_path,
+            target_dir,
+            shape_processor,
+            model: None,
+            parameters: Parameters::empty(),
+            stop_watching: None,
+        }
+    }
+
+    /// Load a model, before any requests are received
+    pub fn set_model(&mut self, model: Model, parameters: Parameters) {
+        self.model = Some(model);
+        self.parameters = parameters;
+    }
+
+    /// Listen on the given address, and serve requests until the process is
+    /// terminated
+    ///
+    /// Returns an error, if `address` is not a loopback address. See module
+    /// documentation.
+    pub fn serve(mut self, address: &str) -> anyhow::Result<()> {
+        let addresses = address.to_socket_addrs()?.collect::<Vec<_>>();
+        if let Some(address) =
+            addresses.iter().find(|address| !address.ip().is_loopback())
+        {
+            return Err(anyhow!(
+                "Refusing to serve on {}, which is not a loopback address",
+                address
+            ));
+        }
+
+        let listener = TcpListener::bind(&addresses[..])?;
+
+        // Print the actual address, as the port might have been chosen by the
+        // operating system. Whoever started the server needs to know it.
+        println!("Listening on {}", listener.local_addr()?);
+
+        for stream in listener.incoming() {
+            // A broken connection shouldn't take down the server. The client
+            // can just connect again.
+            if let Err(err) =
+                stream.and_then(|stream| self.serve_client(stream))
+            {
+                eprintln!("Connection error: {}", err);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn serve_client(&mut self, stream: TcpStream) -> io::Result<()> {
+        let reader = BufReader::new(stream.try_clone()?);
+        let connection = Arc::new(Mutex::new(stream));
+
+        let result = self.serve_lines(reader, &connection);
+
+        // Nobody is left to receive the diagnostics.
+        self.stop_watching();
+
+        result
+    }
+
+    fn serve_lines(
+        &mut self,
+        reader: BufReader<TcpStream>,
+        connection: &Connection,
+    ) -> io::Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // If a line isn't a JSON-RPC request, whatever sent it isn't a
+            // JSON-RPC client. It might be a web page that makes the browser
+            // post to the server, to smuggle requests in through the body.
+            // Closing the connection makes sure none of those are run.
+            let request: Request = match serde_json::from_str(&line) {
+                Ok(request) if request.jsonrpc == "2.0" => request,
+                Ok(_) => {
+                    let err = Error::invalid_request("Expected JSON-RPC 2.0");
+                    send(connection, &Response::error(Value::Null, err))?;
+                    break;
+                }
+                Err(err) => {
+                    send(
+                        connection,
+                        &Response::error(Value::Null, Error::parse(err)),
+                    )?;
+                    break;
+                }
+            };
+
+            if let Some(response) = self.handle_request(request, connection) {
+                send(connection, &response)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single request
+    ///
+    /// Returns `None` for notifications, which must not be responded to.
+    fn handle_request(
+        &mut self,
+        request: Request,
+        connection: &Connection,
+    ) -> Option<Response> {
+        let result =
+            self.handle_method(&request.method, request.params, connection);
+        let id = request.id?;
+
+        let response = match result {
+            Ok(result) => Response::result(id, result),
+            Err(err) => Response::error(id, err),
+        };
+        Some(response)
+    }
+
+    fn handle_method(
+        &mut self,
+        method: &str,
+        params: Value,
+        connection: &Connection,
+    ) -> Result<Value, Error> {
+        match method {
+            "load_model" => {
+                let PathParams { path } = parse_params(params)?;
+
+                let path = self.default_path.join(path);
+                let model = Model::from_path(path, self.target_dir.clone())
+                    .map_err(Error::server)?;
+
+                self.model = Some(model);
+                Ok(Value::Null)
+            }
+            "set_parameters" => {
+                let SetParametersParams { parameters } = parse_params(params)?;
+
+                self.parameters = Parameters(parameters);
+                Ok(Value::Null)
+            }
+            "get_mesh" => {
+                let shape = self.process()?;
+
+                let vertices: Vec<_> = shape
+                    .mesh
+                    .vertices()
+                    .map(|vertex| {
+                        vertex.coords.components.map(Scalar::into_f64)
+                    })
+                    .collect();
+                let indices: Vec<_> = shape.mesh.indices().collect();
+                let triangles: Vec<_> = indices.chunks(3).collect();
+
+                Ok(json!({
+                    "vertices": vertices,
+                    "triangles": triangles,
+                }))
+            }
+            "export" => {
+                let ExportParams {
+                    path,
+                    section,
+                    view,
+                    unit,
+                    scale,
+                    up_axis,
+                } = parse_params(params)?;
+                let view = match view {
+                    Some(view) => view.parse().map_err(Error::server)?,
+                    None => View::default(),
+                };
+                let unit = match unit {
+                    Some(unit) => unit.parse().map_err(Error::server)?,
+                    None => Unit::default(),
+                };
+                let up_axis = match up_axis {
+                    Some(up_axis) => up_axis.parse().map_err(Error::server)?,
+                    None => UpAxis::default(),
+                };
+
+                let shape = self.process()?;
+                let options = Options {
+                    metadata: Metadata {
+                        unit,
+                        ..Metadata::default()
+                    },
+                    scale: scale.unwrap_or(1.),
+                    up_axis,
+                    section,
+                    view,
+                };
+                export(&shape, &path, options).map_err(Error::server)?;
+
+                Ok(Value::Null)
+            }
+            "watch" => {
+                let PathParams { path } = parse_params(params)?;
+
+                let path = self.default_path.join(path);
+                self.watch(path, connection)?;
+
+                Ok(Value::Null)
+            }
+            method => Err(Error::method_not_found(method)),
+        }
+    }
+
+    /// Load the model at `path`, and send diagnostics whenever it changes
+    ///
+    /// Replaces any model that is currently being watched.
+    fn watch(
+        &mut self,
+        path: PathBuf,
+        connection: &Connection,
+    ) -> Result<(), Error> {
+        // `Model::load_and_watch` consumes the model, but it should still be
+        // available for the other requests. Creating another one is cheap.
+        let model = Model::from_path(path.clone(), self.target_dir.clone())
+            .map_err(Error::server)?;
+        let watched = Model::from_path(path, self.target_dir.clone())
+            .map_err(Error::server)?;
+
+        let parameters = Parameters(self.parameters.0.clone());
+        let watcher =
+            watched.load_and_watch(parameters).map_err(Error::server)?;
+
+        self.stop_watching();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let shape_processor = self.shape_processor;
+        let connection = connection.clone();
+        let stopped = stop.clone();
+        thread::spawn(move || loop {
+            watcher.wait_for_change();
+            if stopped.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let diagnostics =
+                diagnostics::evaluate(|| watcher.reload(), &shape_processor);
+
+            // The model might have been replaced while it was evaluated.
+            if stopped.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let notification = Notification::new(
+                "diagnostics",
+                json!({ "diagnostics": diagnostics }),
+            );
+            if send(&connection, &notification).is_err() {
+                break;
+            }
+        });
+
+        self.model = Some(model);
+        self.stop_watching = Some(stop);
+
+        Ok(())
+    }
+
+    fn stop_watching(&mut self) {
+        if let Some(stop) = self.stop_watching.take() {
+            // The thread only notices this after the next change, but it
+            // won't send anything anymore.
+            stop.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn process(&self) -> Result<ProcessedShape, Error> {
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| Error::server("No model loaded"))?;
+
+        let shape = model.load_once(&self.parameters).map_err(Error::server)?;
+        Ok(self.shape_processor.process(&shape))
+    }
+}
+
+fn send(connection: &Connection, message: &impl Serialize) -> io::Result<()> {
+    // If another thread panicked while holding the lock, the worst that could
+    // have happened is a partially written message. Nothing to recover here.
+    let mut stream = connection.lock().unwrap_or_else(|err| err.into_inner());
+
+    serde_json::to_writer(&mut *stream, message)?;
+    stream.write_all(b"\n")?;
+    stream.flush()
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, Error> {
+    serde_json::from_value(params).map_err(Error::invalid_params)
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+
+    /// The request ID; missing for notifications
+    #[serde(default)]
+    id: Option<Value>,
+
+    method: String,
+
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct PathParams {
+    path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct ExportParams {
+    path: PathBuf,
+    section: Option<f64>,
+    view: Option<String>,
+    unit: Option<String>,
+    scale: Option<f64>,
+    up_axis: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SetParametersParams {
+    parameters: HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Error>,
+}
+
+impl Response {
+    fn result(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn error(id: Value, error: Error) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// A message sent by the server, that isn't a response to a request
+#[derive(Serialize)]
+struct Notification {
+    jsonrpc: &'static str,
+    method: &'static str,
+    params: Value,
+}
+
+impl Notification {
+    fn new(method: &'static str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            method,
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC error object
+#[derive(Serialize)]
+struct Error {
+    code: i64,
+    message: String,
+}
+
+impl Error {
+    fn parse(err: serde_json::Error) -> Self {
+        Self {
+            code: -32700,
+            message: format!("Parse error: {}", err),
+        }
+    }
+
+    fn invalid_request(message: &str) -> Self {
+        Self {
+            code: -32600,
+            message: format!("Invalid request: {}", message),
+        }
+    }
+
+    fn method_not_found(method: &str) -> Self {
+        Self {
+            code: -32601,
+            message: format!("Method not found: {}", method),
+        }
+    }
+
+    fn invalid_params(err: serde_json::Error) -> Self {
+        Self {
+            code: -32602,
+            message: format!("Invalid params: {}", err),
+        }
+    }
+
+    /// An error that occurred while performing the request
+    fn server(err: impl ToString) -> Self {
+        Self {
+            code: -32000,
+            message: err.to_string(),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Export to AMF
+//!
+//! An AMF file is an XML description of the model, as defined by the
+//! [ISO/ASTM 52915] standard. Some printing pipelines still prefer it over 3MF.
+//!
+//! [ISO/ASTM 52915]: https://www.iso.org/standard/74640.html
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::mesh::Mesh;
+use fj_math::Point;
+
+use crate::{
+    model::{escape, Model},
+    ExportError, Exporter, Metadata, Options, Unit,
+};
+
+/// Export the mesh to an AMF file, along with the given metadata
+///
+/// Parts of the mesh that are not connected to each other, like the shapes in
+/// a group, become separate objects. The triangles of each object are grouped
+/// into one volume per color, which refers to a material of that color.
+pub fn export_amf(
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    path: &Path,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write_amf(&mut file, mesh, metadata)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Exports meshes to AMF
+///
+/// See [`export_amf`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AmfExporter;
+
+impl Exporter for AmfExporter {
+    fn name(&self) -> &str {
+        "AMF"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["amf"]
+    }
+
+    fn export(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        options: &Options,
+        mut writer: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        write_amf(&mut writer, mesh, &options.metadata)?;
+        Ok(())
+    }
+}
+
+fn write_amf(
+    file: &mut impl Write,
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+) -> io::Result<()> {
+    let model = Model::new(mesh);
+
+    // AMF doesn't know about centimeters, so those are converted.
+    let (unit, scale) = match metadata.unit {
+        Unit::Micron => ("micron", 1.),
+        Unit::Millimeter => ("millimeter", 1.),
+        Unit::Centimeter => ("millimeter", 10.),
+        Unit::Inch => ("inch", 1.),
+        Unit::Foot => ("feet", 1.),
+        Unit::Meter => ("meter", 1.),
+    };
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(file, r#"<amf unit="{}" version="1.1">"#, unit)?;
+
+    for (name, value) in [
+        ("name", &metadata.name),
+        ("author", &metadata.author),
+        ("cad", &metadata.application),
+    ] {
+        if let Some(value) = value {
+            writeln!(
+                file,
+                r#"<metadata type="{}">{}</metadata>"#,
+                name,
+                escape(value)
+            )?;
+        }
+    }
+
+    // Material IDs start at 1, as 0 is reserved for the void.
+    for (i, &color) in model.materials.iter().enumerate() {
+        writeln!(file, r#"<material id="{}">"#, i + 1)?;
+        writeln!(
+            file,
+            r#"<metadata type="name">{}</metadata>"#,
+            escape(&model.material_name(metadata, i))
+        )?;
+        write_color(file, color)?;
+        writeln!(file, "</material>")?;
+    }
+
+    for (i, object) in model.objects.iter().enumerate() {
+        writeln!(file, r#"<object id="{}">"#, i)?;
+        writeln!(
+            file,
+            r#"<metadata type="name">{}</metadata>"#,
+            escape(&model.object_name(metadata, i))
+        )?;
+        writeln!(file, "<mesh>")?;
+
+        writeln!(file, "<vertices>")?;
+        for vertex in &object.vertices {
+            let [x, y, z] = vertex.coords.components;
+            writeln!(
+                file,
+                "<vertex><coordinates>\
+                <x>{}</x><y>{}</y><z>{}</z>\
+                </coordinates></vertex>",
+                x.into_f64() * scale,
+                y.into_f64() * scale,
+                z.into_f64() * scale
+            )?;
+        }
+        writeln!(file, "</vertices>")?;
+
+        for (material, triangles) in object.volumes() {
+            writeln!(file, r#"<volume materialid="{}">"#, material + 1)?;
+
+            // Not all applications look up the material, so the volume gets
+            // its color directly too.
+            write_color(file, model.materials[material])?;
+
+            for [v1, v2, v3] in triangles {
+                writeln!(
+                    file,
+                    "<triangle><v1>{}</v1><v2>{}</v2><v3>{}</v3></triangle>",
+                    v1, v2, v3
+                )?;
+            }
+
+            writeln!(file, "</volume>")?;
+        }
+
+        writeln!(file, "</mesh>")?;
+        writeln!(file, "</object>")?;
+    }
+
+    writeln!(file, "</amf>")?;
+
+    Ok(())
+}
+
+fn write_color(file: &mut impl Write, [r, g, b, a]: [u8; 4]) -> io::Result<()> {
+    let [r, g, b, a] = [r, g, b, a].map(|channel| f64::from(channel) / 255.);
+    writeln!(
+        file,
+        "<color><r>{}</r><g>{}</g><b>{}</b><a>{}</a></color>",
+        r, g, b, a
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    use crate::{Metadata, Unit};
+
+    use super::write_amf;
+
+    #[test]
+    fn volume_per_color() {
+        let mut mesh = Mesh::new();
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+                .map(Point::from);
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+        mesh.push_triangle([a, c, d], [0, 0, 255, 255]);
+
+        let metadata = Metadata {
+            name: Some("Cup & saucer".to_owned()),
+            unit: Unit::Centimeter,
+            ..Metadata::default()
+        };
+        let mut xml = Vec::new();
+        write_amf(&mut xml, &mesh, &metadata).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+
+        assert!(xml.contains(r#"<amf unit="millimeter" version="1.1">"#));
+        assert!(xml.contains(r#"<metadata type="name">Cup &amp; saucer<"#));
+        assert!(!xml.contains(r#"type="author""#));
+
+        assert_eq!(xml.matches("<material ").count(), 2);
+        assert!(xml.contains(r#"<volume materialid="1">"#));
+        assert!(xml.contains(r#"<volume materialid="2">"#));
+        assert!(xml.contains("<r>1</r><g>0</g><b>0</b><a>1</a>"));
+        assert!(xml.contains("<x>10</x><y>0</y><z>0</z>"));
+        assert!(xml.contains("<v1>0</v1><v2>2</v2><v3>3</v3>"));
+    }
+}
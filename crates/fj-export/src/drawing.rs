@@ -0,0 +1,832 @@
+//! Export of 2D drawings to SVG and PDF
+//!
+//! A drawing shows a model from one side, like a technical drawing does. The
+//! mesh is projected onto the drawing plane, and only the edges that are
+//! visible from that side are drawn. Overall dimensions are added along the
+//! bottom and left sides, which is often enough to document a part.
+//!
+//! Drawings are true to scale. By default, the model's units are taken to be
+//! millimeters. Use [`Drawing::with_unit`] for other units.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use fj_interop::mesh::{FaceId, Mesh};
+use fj_math::{Point, Scalar};
+
+use crate::{ExportError, Exporter, Options, Unit};
+
+/// Export a drawing of the mesh, as seen from the given view
+///
+/// The format, SVG or PDF, is determined by the extension of the path.
+pub fn export_drawing(
+    mesh: &Mesh<Point<3>>,
+    view: View,
+    path: &Path,
+) -> Result<(), DrawingError> {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let pdf = match extension {
+        Some("svg") => false,
+        Some("pdf") => true,
+        _ => {
+            return Err(DrawingError::UnsupportedFormat(
+                path.display().to_string(),
+            ))
+        }
+    };
+
+    let drawing = Drawing::project(mesh, view);
+
+    let mut file = BufWriter::new(File::create(path)?);
+    if pdf {
+        drawing.write_pdf(&mut file)?;
+    } else {
+        drawing.write_svg(&mut file)?;
+    }
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Exports drawings of meshes to SVG
+///
+/// The mesh is drawn from the side given by [`Options::view`]. See
+/// [`export_drawing`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SvgExporter;
+
+impl Exporter for SvgExporter {
+    fn name(&self) -> &str {
+        "SVG"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn is_3d(&self) -> bool {
+        false
+    }
+
+    fn export(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        options: &Options,
+        mut writer: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        Drawing::project(mesh, options.view)
+            .with_unit(options.metadata.unit)
+            .write_svg(&mut writer)?;
+        Ok(())
+    }
+}
+
+/// Exports drawings of meshes to PDF
+///
+/// The mesh is drawn from the side given by [`Options::view`]. See
+/// [`export_drawing`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PdfExporter;
+
+impl Exporter for PdfExporter {
+    fn name(&self) -> &str {
+        "PDF"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pdf"]
+    }
+
+    fn is_3d(&self) -> bool {
+        false
+    }
+
+    fn export(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        options: &Options,
+        mut writer: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        Drawing::project(mesh, options.view)
+            .with_unit(options.metadata.unit)
+            .write_pdf(&mut writer)?;
+        Ok(())
+    }
+}
+
+/// An error that can occur when exporting a drawing
+#[derive(Debug, thiserror::Error)]
+pub enum DrawingError {
+    /// The path doesn't end in `.svg` or `.pdf`
+    #[error("Can't export drawing to `{0}`; expected an SVG or PDF file")]
+    UnsupportedFormat(String),
+
+    /// The file could not be written
+    #[error("Error writing drawing")]
+    Io(#[from] io::Error),
+}
+
+/// The side that a model is viewed from
+///
+/// Defaults to [`View::Front`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum View {
+    /// View from above, looking down the z-axis
+    Top,
+
+    /// View from the front, looking along the y-axis
+    Front,
+
+    /// View from the right, looking against the x-axis
+    Right,
+
+    /// View from the front, right, and top, at equal angles
+    Isometric,
+}
+
+impl View {
+    /// The direction towards the viewer, and the direction that points up in
+    /// the drawing
+    fn directions(&self) -> [[f64; 3]; 2] {
+        match self {
+            Self::Top => [[0., 0., 1.], [0., 1., 0.]],
+            Self::Front => [[0., -1., 0.], [0., 0., 1.]],
+            Self::Right => [[1., 0., 0.], [0., 0., 1.]],
+            Self::Isometric => {
+                let toward = normalize([1., -1., 1.]);
+
+                // Up is the z-axis, tilted towards the viewer.
+                let up = normalize(sub(
+                    [0., 0., 1.],
+                    scale(toward, dot([0., 0., 1.], toward)),
+                ));
+
+                [toward, up]
+            }
+        }
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::Front
+    }
+}
+
+impl FromStr for View {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(Self::Top),
+            "front" => Ok(Self::Front),
+            "right" => Ok(Self::Right),
+            "isometric" => Ok(Self::Isometric),
+            _ => Err(format!(
+                "Unknown view `{}`; expected `top`, `front`, `right`, or \
+                `isometric`",
+                s
+            )),
+        }
+    }
+}
+
+/// The visible edges of a model, projected onto the drawing plane
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drawing {
+    lines: Vec<[[f64; 2]; 2]>,
+    unit: Unit,
+}
+
+impl Drawing {
+    /// Project the mesh, removing hidden lines
+    ///
+    /// The edges that are drawn are those between faces, and the silhouette,
+    /// where the surface of the model turns away from the viewer. Parts of
+    /// these edges that are hidden behind other triangles are removed.
+    pub fn project(mesh: &Mesh<Point<3>>, view: View) -> Self {
+        let [toward, up] = view.directions();
+        let right = cross(up, toward);
+
+        let triangles: Vec<_> = mesh
+            .triangles()
+            .map(|triangle| {
+                let points = triangle
+                    .points
+                    .map(|point| point.coords.components.map(Scalar::into_f64));
+                (points, triangle.face)
+            })
+            .collect();
+
+        let extent = triangles
+            .iter()
+            .flat_map(|(points, _)| points.iter().flatten())
+            .fold(0., |extent: f64, c| extent.max(c.abs()));
+        let epsilon = extent.max(1.) * 1e-9;
+
+        let project = |p: [f64; 3]| [dot(p, right), dot(p, up), dot(p, toward)];
+        let occluders = Occluders::new(
+            triangles
+                .iter()
+                .map(|(points, _)| points.map(project))
+                .collect(),
+            epsilon,
+        );
+
+        let mut lines = Vec::new();
+        for edge in feature_edges(&triangles, toward) {
+            if !edge.is_visible() {
+                continue;
+            }
+
+            let [a, b] = edge.points.map(project);
+            lines.extend(occluders.visible_parts(a, b));
+        }
+
+        Self {
+            lines,
+            unit: Unit::Millimeter,
+        }
+    }
+
+    /// Set the unit that the coordinates of the model are in
+    pub fn with_unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Access the visible lines
+    pub fn lines(&self) -> &[[[f64; 2]; 2]] {
+        &self.lines
+    }
+
+    /// Write the drawing as SVG
+    pub fn write_svg(&self, file: &mut impl Write) -> io::Result<()> {
+        let layout = Layout::new(&self.lines);
+        let [min, max] = layout.page();
+
+        // The page size is in millimeters, as SVG doesn't know about all units
+        // that models can be in.
+        let millimeters = self.unit.millimeters();
+
+        // SVG's y-axis points down, so all y coordinates are negated.
+        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            file,
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" "#,
+                r#"width="{pw}mm" height="{ph}mm" "#,
+                r#"viewBox="{x} {y} {w} {h}">"#,
+            ),
+            pw = number((max[0] - min[0]) * millimeters),
+            ph = number((max[1] - min[1]) * millimeters),
+            x = number(min[0]),
+            y = number(-max[1]),
+            w = number(max[0] - min[0]),
+            h = number(max[1] - min[1]),
+        )?;
+        writeln!(
+            file,
+            concat!(
+                r#"<g fill="none" stroke="black" "#,
+                r#"stroke-width="{}" stroke-linecap="round">"#,
+            ),
+            number(layout.line_width),
+        )?;
+        for [a, b] in self.lines.iter().chain(&layout.dimension_lines()) {
+            writeln!(
+                file,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                number(a[0]),
+                number(-a[1]),
+                number(b[0]),
+                number(-b[1]),
+            )?;
+        }
+        writeln!(file, "</g>")?;
+
+        for label in layout.labels() {
+            let [x, y] = label.position;
+            let rotation = if label.vertical {
+                format!(
+                    r#" transform="rotate(-90 {} {})""#,
+                    number(x),
+                    number(-y)
+                )
+            } else {
+                String::new()
+            };
+
+            writeln!(
+                file,
+                concat!(
+                    r#"<text x="{}" y="{}" font-family="sans-serif" "#,
+                    r#"font-size="{}" text-anchor="middle"{}>{}</text>"#,
+                ),
+                number(x),
+                number(-y),
+                number(layout.font_size),
+                rotation,
+                label.text,
+            )?;
+        }
+
+        writeln!(file, "</svg>")?;
+
+        Ok(())
+    }
+
+    /// Write the drawing as a single-page PDF
+    pub fn write_pdf(&self, file: &mut impl Write) -> io::Result<()> {
+        let layout = Layout::new(&self.lines);
+        let [min, max] = layout.page();
+
+        // PDF units are points, which are 1/72 of an inch.
+        let points_per_unit = 72. / 25.4 * self.unit.millimeters();
+
+        let mut content = format!(
+            "{k} 0 0 {k} {x} {y} cm\n{w} w 1 J\n",
+            k = number(points_per_unit),
+            x = number(-min[0] * points_per_unit),
+            y = number(-min[1] * points_per_unit),
+            w = number(layout.line_width),
+        );
+        for [a, b] in self.lines.iter().chain(&layout.dimension_lines()) {
+            content.push_str(&format!(
+                "{} {} m {} {} l S\n",
+                number(a[0]),
+                number(a[1]),
+                number(b[0]),
+                number(b[1]),
+            ));
+        }
+        for label in layout.labels() {
+            // Helvetica doesn't come with metrics here, but its digits are all
+            // the same width, which is good enough for centering.
+            let width = label.text.len() as f64 * 0.556 * layout.font_size;
+            let offset = width / 2.;
+            let [x, y] = label.position;
+            let matrix = if label.vertical {
+                [0., 1., -1., 0., x, y - offset]
+            } else {
+                [1., 0., 0., 1., x - offset, y]
+            };
+
+            content.push_str(&format!(
+                "BT /F1 {} Tf {} Tm ({}) Tj ET\n",
+                number(layout.font_size),
+                matrix.map(number).join(" "),
+                label.text,
+            ));
+        }
+
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_owned(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_owned(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >>",
+                number((max[0] - min[0]) * points_per_unit),
+                number((max[1] - min[1]) * points_per_unit),
+            ),
+            format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_owned(),
+        ];
+
+        // The cross-reference table needs the byte offset of each object, so
+        // the whole file is assembled first.
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+
+        let xref = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+
+        file.write_all(pdf.as_bytes())
+    }
+}
+
+/// An edge of the mesh, and which of the triangles next to it face the viewer
+struct Edge {
+    points: [[f64; 3]; 2],
+    front: usize,
+    back: usize,
+    crease: bool,
+}
+
+impl Edge {
+    fn is_visible(&self) -> bool {
+        // Edges at the boundary of an open mesh are always drawn. Otherwise,
+        // an edge must be next to a triangle that faces the viewer, and
+        // either be a crease or part of the silhouette.
+        let boundary = self.front + self.back == 1;
+        let silhouette = self.front > 0 && self.back > 0;
+
+        boundary || (self.front > 0 && (silhouette || self.crease))
+    }
+}
+
+/// Find the edges of the mesh, and the triangles on their sides
+fn feature_edges(
+    triangles: &[([[f64; 3]; 3], Option<FaceId>)],
+    toward: [f64; 3],
+) -> Vec<Edge> {
+    // Between faces, an edge is an edge. Where the face is not known, an edge
+    // is considered a crease, if the surface bends sharply enough.
+    let min_cos = 30_f64.to_radians().cos();
+
+    let mut edges = BTreeMap::new();
+    for &(points, face) in triangles {
+        let [a, b, c] = points;
+        let normal = cross(sub(b, a), sub(c, a));
+        if dot(normal, normal) == 0. {
+            continue;
+        }
+        let normal = normalize(normal);
+
+        for i in 0..3 {
+            let a = points[i];
+            let b = points[(i + 1) % 3];
+
+            let edge = if key(a) < key(b) {
+                (key(a), key(b))
+            } else {
+                (key(b), key(a))
+            };
+            edges
+                .entry(edge)
+                .or_insert_with(|| ([a, b], Vec::new()))
+                .1
+                .push((normal, face));
+        }
+    }
+
+    edges
+        .into_values()
+        .map(|(points, neighbors)| {
+            let front = neighbors
+                .iter()
+                .filter(|(normal, _)| dot(*normal, toward) > 1e-9)
+                .count();
+
+            let crease = neighbors.iter().enumerate().any(|(i, (n1, f1))| {
+                neighbors[i + 1..].iter().any(|(n2, f2)| match (f1, f2) {
+                    (Some(f1), Some(f2)) => f1 != f2,
+                    _ => dot(*n1, *n2) < min_cos,
+                })
+            });
+
+            Edge {
+                points,
+                front,
+                back: neighbors.len() - front,
+                crease,
+            }
+        })
+        .collect()
+}
+
+/// Projected triangles that could hide parts of edges
+///
+/// Points are projected into drawing coordinates, with a third component that
+/// is larger, the closer the point is to the viewer.
+struct Occluders {
+    triangles: Vec<[[f64; 3]; 3]>,
+    epsilon: f64,
+}
+
+impl Occluders {
+    fn new(triangles: Vec<[[f64; 3]; 3]>, epsilon: f64) -> Self {
+        // Triangles that are seen edge-on can't hide anything.
+        let triangles = triangles
+            .into_iter()
+            .filter(|[a, b, c]| {
+                cross_2d(sub_2d(*b, *a), sub_2d(*c, *a)).abs()
+                    > epsilon * epsilon
+            })
+            .collect();
+
+        Self { triangles, epsilon }
+    }
+
+    /// Determine the parts of the edge from `a` to `b` that are not hidden
+    fn visible_parts(&self, a: [f64; 3], b: [f64; 3]) -> Vec<[[f64; 2]; 2]> {
+        let ab = sub_2d(b, a);
+        if ab[0].abs() <= self.epsilon && ab[1].abs() <= self.epsilon {
+            // The edge points towards the viewer.
+            return Vec::new();
+        }
+
+        // Visibility can only change where the edge crosses the outline of a
+        // triangle, so that's where the edge is split.
+        let mut splits = vec![0., 1.];
+        for triangle in &self.triangles {
+            for i in 0..3 {
+                let c = triangle[i];
+                let d = triangle[(i + 1) % 3];
+                let cd = sub_2d(d, c);
+
+                let denominator = cross_2d(ab, cd);
+                if denominator.abs() <= self.epsilon * self.epsilon {
+                    continue;
+                }
+
+                let ac = sub_2d(c, a);
+                let t = cross_2d(ac, cd) / denominator;
+                let u = cross_2d(ac, ab) / denominator;
+                if t > 0. && t < 1. && (0. ..=1.).contains(&u) {
+                    splits.push(t);
+                }
+            }
+        }
+        splits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let point_at = |t: f64| {
+            [
+                a[0] + t * (b[0] - a[0]),
+                a[1] + t * (b[1] - a[1]),
+                a[2] + t * (b[2] - a[2]),
+            ]
+        };
+
+        // The ranges of the edge that are visible, as pairs of parameters
+        let mut ranges = Vec::new();
+        let mut visible_from = None;
+        for pair in splits.windows(2) {
+            let [t0, t1] = [pair[0], pair[1]];
+            if t1 - t0 <= 1e-12 {
+                continue;
+            }
+
+            let visible = !self.hides(point_at((t0 + t1) / 2.));
+            match (visible, visible_from) {
+                (true, None) => visible_from = Some(t0),
+                (false, Some(t)) => {
+                    ranges.push([t, t0]);
+                    visible_from = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(t) = visible_from {
+            ranges.push([t, 1.]);
+        }
+
+        ranges
+            .into_iter()
+            .map(|range| {
+                range.map(|t| {
+                    let [x, y, _] = point_at(t);
+                    [x, y]
+                })
+            })
+            .collect()
+    }
+
+    /// Indicate whether any triangle is in front of the point
+    fn hides(&self, p: [f64; 3]) -> bool {
+        self.triangles.iter().any(|&[a, b, c]| {
+            let area = cross_2d(sub_2d(b, a), sub_2d(c, a));
+            let weights = [
+                cross_2d(sub_2d(c, b), sub_2d(p, b)) / area,
+                cross_2d(sub_2d(a, c), sub_2d(p, c)) / area,
+                cross_2d(sub_2d(b, a), sub_2d(p, a)) / area,
+            ];
+
+            // The point must be strictly within the triangle. Otherwise, the
+            // edges of the triangle itself would be hidden.
+            if weights.iter().any(|&weight| weight <= 1e-9) {
+                return false;
+            }
+
+            let depth =
+                weights[0] * a[2] + weights[1] * b[2] + weights[2] * c[2];
+            depth > p[2] + self.epsilon
+        })
+    }
+}
+
+/// Where the parts of a drawing go
+struct Layout {
+    min: [f64; 2],
+    max: [f64; 2],
+    gap: f64,
+    font_size: f64,
+    line_width: f64,
+}
+
+impl Layout {
+    fn new(lines: &[[[f64; 2]; 2]]) -> Self {
+        let mut min = [f64::INFINITY; 2];
+        let mut max = [f64::NEG_INFINITY; 2];
+        for &[x, y] in lines.iter().flatten() {
+            min = [min[0].min(x), min[1].min(y)];
+            max = [max[0].max(x), max[1].max(y)];
+        }
+        if lines.is_empty() {
+            min = [0.; 2];
+            max = [0.; 2];
+        }
+
+        let mut extent = (max[0] - min[0]).max(max[1] - min[1]);
+        if extent == 0. {
+            extent = 1.;
+        }
+
+        Self {
+            min,
+            max,
+            gap: extent * 0.08,
+            font_size: extent * 0.05,
+            line_width: extent * 0.004,
+        }
+    }
+
+    /// The area of the page, with room for the dimensions
+    fn page(&self) -> [[f64; 2]; 2] {
+        let margin = self.gap * 2. + self.font_size * 1.5;
+        [
+            [self.min[0] - margin, self.min[1] - margin],
+            [self.max[0] + self.gap, self.max[1] + self.gap],
+        ]
+    }
+
+    /// The lines of the dimensions along the bottom and left sides
+    fn dimension_lines(&self) -> Vec<[[f64; 2]; 2]> {
+        let [min, max] = [self.min, self.max];
+        let tick = self.font_size / 3.;
+
+        let y = min[1] - self.gap;
+        let x = min[0] - self.gap;
+
+        vec![
+            [[min[0], y], [max[0], y]],
+            [[min[0], y - tick], [min[0], y + tick]],
+            [[max[0], y - tick], [max[0], y + tick]],
+            [[x, min[1]], [x, max[1]]],
+            [[x - tick, min[1]], [x + tick, min[1]]],
+            [[x - tick, max[1]], [x + tick, max[1]]],
+        ]
+    }
+
+    fn labels(&self) -> [Label; 2] {
+        let [min, max] = [self.min, self.max];
+        let offset = self.gap + self.font_size * 1.2;
+
+        [
+            Label {
+                text: number(max[0] - min[0]),
+                position: [(min[0] + max[0]) / 2., min[1] - offset],
+                vertical: false,
+            },
+            Label {
+                text: number(max[1] - min[1]),
+                position: [
+                    min[0] - self.gap - self.font_size * 0.4,
+                    (min[1] + max[1]) / 2.,
+                ],
+                vertical: true,
+            },
+        ]
+    }
+}
+
+/// The text of a dimension
+///
+/// The position is the center of the text's baseline. Vertical text reads
+/// from bottom to top.
+struct Label {
+    text: String,
+    position: [f64; 2],
+    vertical: bool,
+}
+
+/// Format a number for output, without needless digits
+fn number(value: f64) -> String {
+    let value = format!("{:.4}", value);
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+
+    match value {
+        "-0" => "0".to_owned(),
+        value => value.to_owned(),
+    }
+}
+
+fn key(p: [f64; 3]) -> (i64, i64, i64) {
+    // Round to a grid, so points that are shared by triangles still match, if
+    // their coordinates are slightly off.
+    let [x, y, z] = p.map(|c| (c / 1e-7).round() as i64);
+    (x, y, z)
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], s: f64) -> [f64; 3] {
+    a.map(|c| c * s)
+}
+
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    scale(a, 1. / dot(a, a).sqrt())
+}
+
+/// Subtract the drawing coordinates of two projected points
+fn sub_2d(a: [f64; 3], b: [f64; 3]) -> [f64; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn cross_2d(a: [f64; 2], b: [f64; 2]) -> f64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    use super::{Drawing, View};
+
+    fn push_cuboid(mesh: &mut Mesh<Point<3>>, min: [f64; 3], max: [f64; 3]) {
+        let [x, y, z] = [0, 1, 2].map(|i| [min[i], max[i]]);
+        let corner =
+            |(i, j, k): (usize, usize, usize)| Point::from([x[i], y[j], z[k]]);
+
+        // The corners of each side, counter-clockwise as seen from outside.
+        let sides = [
+            [(0, 0, 0), (0, 0, 1), (0, 1, 1), (0, 1, 0)],
+            [(1, 0, 0), (1, 1, 0), (1, 1, 1), (1, 0, 1)],
+            [(0, 0, 0), (1, 0, 0), (1, 0, 1), (0, 0, 1)],
+            [(0, 1, 0), (0, 1, 1), (1, 1, 1), (1, 1, 0)],
+            [(0, 0, 0), (0, 1, 0), (1, 1, 0), (1, 0, 0)],
+            [(0, 0, 1), (1, 0, 1), (1, 1, 1), (0, 1, 1)],
+        ];
+        for side in sides {
+            let [a, b, c, d] = side.map(corner);
+            mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+            mesh.push_triangle([a, c, d], [255, 0, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn visible_edges() {
+        let mut mesh = Mesh::new();
+        push_cuboid(&mut mesh, [0., 0., 0.], [1., 1., 1.]);
+
+        assert_eq!(Drawing::project(&mesh, View::Front).lines().len(), 4);
+        assert_eq!(Drawing::project(&mesh, View::Isometric).lines().len(), 9);
+    }
+
+    #[test]
+    fn hidden_lines() {
+        let mut mesh = Mesh::new();
+        push_cuboid(&mut mesh, [-1., 0., -1.], [2., 1., 2.]);
+
+        // This one is partially hidden behind the first.
+        push_cuboid(&mut mesh, [1., 5., 0.], [3., 6., 1.]);
+
+        let drawing = Drawing::project(&mesh, View::Front);
+
+        assert_eq!(drawing.lines().len(), 4 + 3);
+        assert!(drawing.lines().iter().any(|line| {
+            line.iter().all(|point| point[1] == 1.)
+                && line[0][0].min(line[1][0]) == 2.
+                && line[0][0].max(line[1][0]) == 3.
+        }));
+    }
+}
@@ -0,0 +1,332 @@
+//! Export of cross-sections to DXF
+//!
+//! Laser cutters and CNC routers work with 2D outlines, which are usually
+//! exchanged as DXF files. The outline is computed by intersecting the
+//! triangle mesh with a horizontal plane. If that plane coincides with a
+//! planar face, like the bottom of an extruded profile, the outline of that
+//! face is exported.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Point, Scalar};
+
+use crate::{ExportError, Exporter, Options, Unit};
+
+/// Export the cross-section of a mesh at the given height to a DXF file
+///
+/// The cross-section is taken in the xy-plane, at the given z coordinate. Each
+/// outline is written as a polyline. Outlines that are not closed, because the
+/// mesh isn't watertight, are written too.
+pub fn export_dxf(
+    mesh: &Mesh<Point<3>>,
+    height: f64,
+    path: &Path,
+) -> Result<(), DxfError> {
+    let polylines = cross_section(mesh, height);
+    if polylines.is_empty() {
+        return Err(DxfError::EmptySection(height));
+    }
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write_dxf(&mut file, &polylines, Unit::Millimeter)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Exports cross-sections of meshes to DXF
+///
+/// The cross-section is taken at the height from [`Options::section`], or at
+/// the bottom of the mesh. See [`export_dxf`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DxfExporter;
+
+impl Exporter for DxfExporter {
+    fn name(&self) -> &str {
+        "DXF"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["dxf"]
+    }
+
+    fn is_3d(&self) -> bool {
+        false
+    }
+
+    fn export(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        options: &Options,
+        mut writer: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        let height = options.section.unwrap_or_else(|| {
+            mesh.vertices()
+                .map(|vertex| vertex.z.into_f64())
+                .fold(f64::INFINITY, f64::min)
+        });
+
+        let polylines = cross_section(mesh, height);
+        if polylines.is_empty() {
+            return Err(DxfError::EmptySection(height).into());
+        }
+
+        write_dxf(&mut writer, &polylines, options.metadata.unit)?;
+
+        Ok(())
+    }
+}
+
+/// An error that can occur when exporting to DXF
+#[derive(Debug, thiserror::Error)]
+pub enum DxfError {
+    /// The plane doesn't intersect the mesh
+    #[error("Model has no cross-section at height {0}")]
+    EmptySection(f64),
+
+    /// The file could not be written
+    #[error("Error writing DXF file")]
+    Io(#[from] io::Error),
+}
+
+/// Points that are closer than this are considered identical
+const EPSILON: f64 = 1e-9;
+
+struct Polyline {
+    points: Vec<[f64; 2]>,
+    closed: bool,
+}
+
+fn cross_section(mesh: &Mesh<Point<3>>, height: f64) -> Vec<Polyline> {
+    let mut segments = Vec::new();
+
+    // Edges that lie in the plane are shared between two triangles. They are
+    // only added once.
+    let mut edges_in_plane = HashSet::new();
+
+    for triangle in mesh.triangles() {
+        let points = triangle.points.map(|point| {
+            let [x, y, z] = point.coords.components.map(Scalar::into_f64);
+            [x, y, z - height]
+        });
+
+        let on_plane: Vec<_> = points
+            .iter()
+            .filter(|[_, _, z]| z.abs() <= EPSILON)
+            .collect();
+        let above = points.iter().filter(|[_, _, z]| *z > EPSILON).count();
+        let below = points.iter().filter(|[_, _, z]| *z < -EPSILON).count();
+
+        match on_plane[..] {
+            // The triangle lies within the plane. Its outline is made up of
+            // the edges of its neighbors, which are handled below.
+            [_, _, _] => {}
+            [a, b] => {
+                let a = [a[0], a[1]];
+                let b = [b[0], b[1]];
+
+                let edge = if key(a) < key(b) {
+                    (key(a), key(b))
+                } else {
+                    (key(b), key(a))
+                };
+                if edges_in_plane.insert(edge) {
+                    segments.push([a, b]);
+                }
+            }
+            [a] if above == 1 && below == 1 => {
+                let off_plane: Vec<_> = points
+                    .into_iter()
+                    .filter(|[_, _, z]| z.abs() > EPSILON)
+                    .collect();
+                let crossing = intersect(off_plane[0], off_plane[1]);
+                segments.push([[a[0], a[1]], crossing]);
+            }
+            [] if above > 0 && below > 0 => {
+                let mut crossings = Vec::new();
+                for i in 0..3 {
+                    let p = points[i];
+                    let q = points[(i + 1) % 3];
+                    if (p[2] > 0.) != (q[2] > 0.) {
+                        crossings.push(intersect(p, q));
+                    }
+                }
+                segments.push([crossings[0], crossings[1]]);
+            }
+            // The triangle only touches the plane, or doesn't reach it.
+            _ => {}
+        }
+    }
+
+    chain(segments)
+}
+
+/// The point where the edge from `p` to `q` crosses the plane
+fn intersect(p: [f64; 3], q: [f64; 3]) -> [f64; 2] {
+    let t = p[2] / (p[2] - q[2]);
+    [p[0] + t * (q[0] - p[0]), p[1] + t * (q[1] - p[1])]
+}
+
+fn key([x, y]: [f64; 2]) -> (i64, i64) {
+    // Round to a grid, so points that were computed from different triangles
+    // still match.
+    let grid = 1e-7;
+    ((x / grid).round() as i64, (y / grid).round() as i64)
+}
+
+/// Connect segments with shared end points into polylines
+fn chain(segments: Vec<[[f64; 2]; 2]>) -> Vec<Polyline> {
+    let mut segments_at: HashMap<_, Vec<usize>> = HashMap::new();
+    for (i, [a, b]) in segments.iter().enumerate() {
+        segments_at.entry(key(*a)).or_default().push(i);
+        segments_at.entry(key(*b)).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut polylines = Vec::new();
+
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+
+        let [a, b] = segments[start];
+        let mut points = vec![a, b];
+
+        // Extend the polyline at its end, then at its start.
+        for _ in 0..2 {
+            loop {
+                let end = *points.last().unwrap();
+                let next =
+                    segments_at[&key(end)].iter().copied().find(|&i| !used[i]);
+
+                let next = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                used[next] = true;
+
+                let [a, b] = segments[next];
+                points.push(if key(a) == key(end) { b } else { a });
+            }
+
+            points.reverse();
+        }
+
+        let closed =
+            points.len() > 2 && key(points[0]) == key(*points.last().unwrap());
+        if closed {
+            points.pop();
+        }
+
+        polylines.push(Polyline { points, closed });
+    }
+
+    polylines
+}
+
+/// Write polylines as a minimal DXF R12 file, which pretty much anything can
+/// read
+fn write_dxf(
+    file: &mut impl Write,
+    polylines: &[Polyline],
+    unit: Unit,
+) -> io::Result<()> {
+    let mut group =
+        |code: u32, value: &str| writeln!(file, "{}\n{}", code, value);
+
+    // Strictly speaking, `$INSUNITS` was only added after R12. Applications
+    // that don't know it ignore it.
+    let units = match unit {
+        Unit::Inch => "1",
+        Unit::Foot => "2",
+        Unit::Millimeter => "4",
+        Unit::Centimeter => "5",
+        Unit::Meter => "6",
+        Unit::Micron => "13",
+    };
+    group(0, "SECTION")?;
+    group(2, "HEADER")?;
+    group(9, "$INSUNITS")?;
+    group(70, units)?;
+    group(0, "ENDSEC")?;
+
+    group(0, "SECTION")?;
+    group(2, "ENTITIES")?;
+
+    for polyline in polylines {
+        group(0, "POLYLINE")?;
+        group(8, "0")?;
+        group(66, "1")?;
+        group(70, if polyline.closed { "1" } else { "0" })?;
+
+        for [x, y] in &polyline.points {
+            group(0, "VERTEX")?;
+            group(8, "0")?;
+            group(10, &x.to_string())?;
+            group(20, &y.to_string())?;
+            group(30, "0.0")?;
+        }
+
+        group(0, "SEQEND")?;
+        group(8, "0")?;
+    }
+
+    group(0, "ENDSEC")?;
+    group(0, "EOF")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    use super::cross_section;
+
+    /// A tetrahedron with its base in the xy-plane
+    fn tetrahedron() -> Mesh<Point<3>> {
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        let mut mesh = Mesh::new();
+        for triangle in [[a, c, b], [a, b, d], [b, c, d], [c, a, d]] {
+            mesh.push_triangle(triangle, [255, 0, 0, 255]);
+        }
+
+        mesh
+    }
+
+    #[test]
+    fn closed_cross_section() {
+        let polylines = cross_section(&tetrahedron(), 0.5);
+
+        assert_eq!(polylines.len(), 1);
+        assert!(polylines[0].closed);
+        assert_eq!(polylines[0].points.len(), 3);
+    }
+
+    #[test]
+    fn cross_section_of_planar_faces() {
+        let polylines = cross_section(&tetrahedron(), 0.);
+
+        assert_eq!(polylines.len(), 1);
+        assert!(polylines[0].closed);
+        assert_eq!(polylines[0].points.len(), 3);
+    }
+
+    #[test]
+    fn cross_section_outside_of_mesh() {
+        assert!(cross_section(&tetrahedron(), 2.).is_empty());
+    }
+}
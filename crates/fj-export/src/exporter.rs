@@ -0,0 +1,90 @@
+use std::io::Write;
+
+use fj_interop::{axes::UpAxis, mesh::Mesh};
+use fj_math::Point;
+
+use crate::{Metadata, View};
+
+/// Exports meshes to a file format
+///
+/// Implemented for all formats that this library supports. Other crates can
+/// implement it too, and add their exporters to a [`Registry`].
+///
+/// [`Registry`]: crate::Registry
+pub trait Exporter {
+    /// The name of the format, like `3MF`
+    fn name(&self) -> &str;
+
+    /// The file extensions of the format, without the leading dot
+    fn extensions(&self) -> &[&str];
+
+    /// Indicate whether the format is 3D
+    ///
+    /// Meshes are only converted to another up axis for 3D formats. 2D formats,
+    /// like cross-sections and drawings, are derived from the mesh in Fornjot's
+    /// own convention.
+    fn is_3d(&self) -> bool {
+        true
+    }
+
+    /// Export the mesh to the given writer
+    fn export(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        options: &Options,
+        writer: &mut dyn Write,
+    ) -> Result<(), ExportError>;
+}
+
+/// An error that can occur when exporting
+///
+/// Each exporter can return its own kind of error.
+pub type ExportError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Options for exporting
+///
+/// Every format uses the options that apply to it, and ignores the rest.
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Information about the model, for formats that can record it
+    ///
+    /// Formats that can't record the unit of the model, like SVG and PDF,
+    /// convert the model's coordinates to the unit they use instead.
+    pub metadata: Metadata,
+
+    /// The factor that the mesh is scaled by, before it is exported
+    ///
+    /// This is applied by [`Registry::export`], which rejects scales that
+    /// aren't finite and positive. Exporters don't need to handle it
+    /// themselves.
+    ///
+    /// [`Registry::export`]: crate::Registry::export
+    pub scale: f64,
+
+    /// The axis that points up in the exported mesh, for 3D formats
+    ///
+    /// Like [`Options::scale`], this is applied by [`Registry::export`].
+    ///
+    /// [`Registry::export`]: crate::Registry::export
+    pub up_axis: UpAxis,
+
+    /// Height of the cross-section, for 2D formats like DXF
+    ///
+    /// Defaults to the bottom of the mesh.
+    pub section: Option<f64>,
+
+    /// The side that drawings are drawn from, for SVG and PDF
+    pub view: View,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            metadata: Metadata::default(),
+            scale: 1.,
+            up_axis: UpAxis::Z,
+            section: None,
+            view: View::default(),
+        }
+    }
+}
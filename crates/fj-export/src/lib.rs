@@ -14,6 +14,14 @@
 
 #![deny(missing_docs)]
 
+mod amf;
+mod drawing;
+mod dxf;
+mod exporter;
+mod model;
+mod registry;
+mod three_mf;
+
 use std::path::Path;
 
 use fj_interop::mesh::Mesh;
@@ -21,31 +29,23 @@ use fj_math::Point;
 
 /// Export the provided mesh to the file at the given path
 ///
-/// Currently only 3MF is supported as an export format. The file extension of
-/// the provided path is ignored.
+/// The mesh is exported as 3MF, without any metadata. The file extension of the
+/// provided path is ignored. To add metadata, use [`export_3mf`]. For AMF, use
+/// [`export_amf`]. To export a 2D cross-section instead, use [`export_dxf`].
+/// For a drawing, use [`export_drawing`]. To pick the format by file extension,
+/// use a [`Registry`].
 pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let vertices = mesh.vertices().map(|vertex| vertex.into()).collect();
-
-    let indices: Vec<_> = mesh.indices().collect();
-    let triangles = indices
-        .chunks(3)
-        .map(|triangle| {
-            [
-                triangle[0] as usize,
-                triangle[1] as usize,
-                triangle[2] as usize,
-            ]
-        })
-        .collect();
-
-    let mesh = threemf::TriangleMesh {
-        vertices,
-        triangles,
-    };
-
-    threemf::write(path, &mesh)?;
-
-    Ok(())
+    export_3mf(mesh, &Metadata::default(), path)
 }
 
-pub use threemf::Error;
+pub use self::{
+    amf::{export_amf, AmfExporter},
+    drawing::{
+        export_drawing, Drawing, DrawingError, PdfExporter, SvgExporter, View,
+    },
+    dxf::{export_dxf, DxfError, DxfExporter},
+    exporter::{ExportError, Exporter, Options},
+    model::{Metadata, Unit},
+    registry::{InvalidScale, Registry, UnsupportedFormat},
+    three_mf::{export_3mf, Error, ThreeMfExporter},
+};
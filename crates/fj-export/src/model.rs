@@ -0,0 +1,342 @@
+//! Preparation of meshes for export to 3D file formats
+
+use std::{collections::HashMap, str::FromStr};
+
+use fj_interop::{
+    mesh::{Color, Mesh},
+    topology::Topology,
+};
+use fj_math::Point;
+
+/// Information about a model, that is exported along with its mesh
+#[derive(Clone, Debug, Default)]
+pub struct Metadata {
+    /// The name of the model
+    ///
+    /// Used as the title of the model, and to name its objects.
+    pub name: Option<String>,
+
+    /// The person who designed the model
+    pub author: Option<String>,
+
+    /// The application that created the file
+    pub application: Option<String>,
+
+    /// The unit that the coordinates of the mesh are in
+    pub unit: Unit,
+
+    /// The kind of object the mesh represents
+    ///
+    /// Formats that distinguish between solids and surfaces, like 3MF, use
+    /// this to tell consumers whether the mesh is expected to be watertight.
+    pub topology: Topology,
+
+    /// The names of the materials that the colors of the mesh stand for
+    ///
+    /// Formats that name their materials, like 3MF and AMF, use these instead
+    /// of numbering the colors.
+    pub material_names: HashMap<Color, String>,
+}
+
+/// A unit of length, as supported by 3MF and AMF
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Unit {
+    /// Micrometers
+    Micron,
+
+    /// Millimeters
+    Millimeter,
+
+    /// Centimeters
+    Centimeter,
+
+    /// Inches
+    Inch,
+
+    /// Feet
+    Foot,
+
+    /// Meters
+    Meter,
+}
+
+impl Unit {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Micron => "micron",
+            Self::Millimeter => "millimeter",
+            Self::Centimeter => "centimeter",
+            Self::Inch => "inch",
+            Self::Foot => "foot",
+            Self::Meter => "meter",
+        }
+    }
+
+    /// The usual abbreviation of the unit
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Self::Micron => "um",
+            Self::Millimeter => "mm",
+            Self::Centimeter => "cm",
+            Self::Inch => "in",
+            Self::Foot => "ft",
+            Self::Meter => "m",
+        }
+    }
+
+    /// The length of the unit, in millimeters
+    pub fn millimeters(&self) -> f64 {
+        match self {
+            Self::Micron => 0.001,
+            Self::Millimeter => 1.,
+            Self::Centimeter => 10.,
+            Self::Inch => 25.4,
+            Self::Foot => 304.8,
+            Self::Meter => 1000.,
+        }
+    }
+}
+
+impl Default for Unit {
+    fn default() -> Self {
+        Self::Millimeter
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Micron,
+            Self::Millimeter,
+            Self::Centimeter,
+            Self::Inch,
+            Self::Foot,
+            Self::Meter,
+        ]
+        .into_iter()
+        .find(|unit| unit.name() == s || unit.abbreviation() == s)
+        .ok_or_else(|| {
+            format!(
+                "Unknown unit `{}`; expected `micron`, `millimeter`, \
+                `centimeter`, `inch`, `foot`, or `meter` (or `um`, `mm`, \
+                `cm`, `in`, `ft`, or `m`)",
+                s
+            )
+        })
+    }
+}
+
+/// A mesh, split into objects and materials
+///
+/// This is the form that file formats with support for multiple objects and
+/// materials, like 3MF and AMF, need.
+pub(crate) struct Model {
+    /// The distinct colors of the mesh, in order of their first appearance
+    pub materials: Vec<Color>,
+
+    /// The parts of the mesh that are not connected to each other
+    pub objects: Vec<Object>,
+}
+
+impl Model {
+    pub fn new(mesh: &Mesh<Point<3>>) -> Self {
+        let vertices: Vec<_> = mesh.vertices().collect();
+        let indices: Vec<_> =
+            mesh.indices().map(|index| index as usize).collect();
+        let colors: Vec<_> =
+            mesh.triangles().map(|triangle| triangle.color).collect();
+
+        // All colors go into one material group. There are usually very few.
+        let mut materials: Vec<Color> = Vec::new();
+        for &color in &colors {
+            if !materials.contains(&color) {
+                materials.push(color);
+            }
+        }
+
+        let objects = objects(&indices, vertices.len())
+            .into_iter()
+            .map(|triangles| {
+                // Each object only contains the vertices it uses.
+                let mut object = Object {
+                    vertices: Vec::new(),
+                    triangles: Vec::new(),
+                };
+                let mut local_indices = HashMap::new();
+
+                for triangle in triangles {
+                    let points = [0, 1, 2].map(|corner| {
+                        let index = indices[triangle * 3 + corner];
+                        *local_indices.entry(index).or_insert_with(|| {
+                            object.vertices.push(vertices[index]);
+                            object.vertices.len() - 1
+                        })
+                    });
+                    let material = materials
+                        .iter()
+                        .position(|&color| color == colors[triangle])
+                        // Can't panic. All colors have been added above.
+                        .unwrap();
+
+                    object.triangles.push(Triangle { points, material });
+                }
+
+                object
+            })
+            .collect();
+
+        Self { materials, objects }
+    }
+
+    /// Compute the name of the material with the given index
+    pub fn material_name(&self, metadata: &Metadata, i: usize) -> String {
+        match metadata.material_names.get(&self.materials[i]) {
+            Some(name) => name.clone(),
+            None => format!("Color {}", i + 1),
+        }
+    }
+
+    /// Compute the name of the object with the given index
+    pub fn object_name(&self, metadata: &Metadata, i: usize) -> String {
+        match (&metadata.name, self.objects.len()) {
+            (Some(name), 1) => name.clone(),
+            (Some(name), _) => format!("{} {}", name, i + 1),
+            (None, _) => format!("Object {}", i + 1),
+        }
+    }
+}
+
+/// A part of a mesh, with its own vertices
+pub(crate) struct Object {
+    pub vertices: Vec<Point<3>>,
+    pub triangles: Vec<Triangle>,
+}
+
+impl Object {
+    /// The material of the object's first triangle
+    ///
+    /// Objects are never empty, as each one is made up of connected triangles.
+    pub fn material(&self) -> usize {
+        self.triangles[0].material
+    }
+
+    /// Group the triangles by material
+    ///
+    /// Groups are ordered by the first triangle of each material.
+    pub fn volumes(&self) -> Vec<(usize, Vec<[usize; 3]>)> {
+        let mut volumes: Vec<(usize, Vec<[usize; 3]>)> = Vec::new();
+
+        for triangle in &self.triangles {
+            let volume = volumes
+                .iter()
+                .position(|&(material, _)| material == triangle.material)
+                .unwrap_or_else(|| {
+                    volumes.push((triangle.material, Vec::new()));
+                    volumes.len() - 1
+                });
+
+            volumes[volume].1.push(triangle.points);
+        }
+
+        volumes
+    }
+}
+
+/// A triangle of an object
+pub(crate) struct Triangle {
+    /// The indices of the triangle's vertices in its object
+    pub points: [usize; 3],
+
+    /// The index of the triangle's material
+    pub material: usize,
+}
+
+/// Group the triangles into objects, that don't share any vertices
+///
+/// Returns the indices of the triangles in each object, ordered by their first
+/// triangle.
+fn objects(indices: &[usize], num_vertices: usize) -> Vec<Vec<usize>> {
+    // Find connected vertices, using a union-find structure.
+    let mut parents: Vec<_> = (0..num_vertices).collect();
+
+    for triangle in indices.chunks(3) {
+        for &index in &triangle[1..] {
+            let a = root(&mut parents, triangle[0]);
+            let b = root(&mut parents, index);
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+
+    let mut objects: Vec<Vec<usize>> = Vec::new();
+    let mut objects_by_root = HashMap::new();
+    for (i, triangle) in indices.chunks(3).enumerate() {
+        let root = root(&mut parents, triangle[0]);
+        let object = *objects_by_root.entry(root).or_insert_with(|| {
+            objects.push(Vec::new());
+            objects.len() - 1
+        });
+
+        objects[object].push(i);
+    }
+
+    objects
+}
+
+/// Find the root of the set that the vertex belongs to
+fn root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Escape text for use in XML
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    use super::{objects, Model};
+
+    #[test]
+    fn vertices_per_object() {
+        // Two triangles that share an edge, and one on its own.
+        let indices = [0, 1, 2, 2, 1, 3, 4, 5, 6];
+
+        assert_eq!(objects(&indices, 7), vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn triangles_by_material() {
+        let mut mesh = Mesh::new();
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+                .map(Point::from);
+        let [red, blue] = [[255, 0, 0, 255], [0, 0, 255, 255]];
+        mesh.push_triangle([a, b, c], red);
+        mesh.push_triangle([a, c, d], blue);
+        mesh.push_triangle([a, d, b], red);
+
+        let model = Model::new(&mesh);
+        assert_eq!(model.materials, vec![red, blue]);
+        assert_eq!(model.objects.len(), 1);
+
+        let object = &model.objects[0];
+        assert_eq!(object.vertices, vec![a, b, c, d]);
+        assert_eq!(object.material(), 0);
+        assert_eq!(
+            object.volumes(),
+            vec![(0, vec![[0, 1, 2], [0, 3, 1]]), (1, vec![[0, 2, 3]])]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::{axes::UpAxis, mesh::Mesh};
+use fj_math::Point;
+
+use crate::{
+    AmfExporter, DxfExporter, ExportError, Exporter, Options, PdfExporter,
+    SvgExporter, ThreeMfExporter,
+};
+
+/// A collection of exporters, that picks one by file extension
+///
+/// The default registry contains the exporters for all formats that this
+/// library supports. Exporters for more formats can be added with
+/// [`Registry::register`].
+pub struct Registry {
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl Registry {
+    /// Create a registry without any exporters
+    pub fn empty() -> Self {
+        Self {
+            exporters: Vec::new(),
+        }
+    }
+
+    /// Add an exporter to the registry
+    ///
+    /// If it shares an extension with an exporter that was added earlier, it
+    /// takes precedence for that extension.
+    pub fn register(&mut self, exporter: impl Exporter + 'static) {
+        self.exporters.push(Box::new(exporter));
+    }
+
+    /// Iterate over the exporters, in the order they were added
+    pub fn exporters(&self) -> impl Iterator<Item = &dyn Exporter> {
+        self.exporters.iter().map(|exporter| exporter.as_ref())
+    }
+
+    /// Find the exporter for the given file extension
+    ///
+    /// Extensions are compared without regard to case.
+    pub fn find(&self, extension: &str) -> Option<&dyn Exporter> {
+        self.exporters
+            .iter()
+            .rev()
+            .map(|exporter| exporter.as_ref())
+            .find(|exporter| {
+                exporter
+                    .extensions()
+                    .iter()
+                    .any(|e| e.eq_ignore_ascii_case(extension))
+            })
+    }
+
+    /// Export the mesh to the file at the given path
+    ///
+    /// The format is picked by the extension of the path. The mesh, and the
+    /// height of the cross-section, are scaled by [`Options::scale`] first.
+    /// Then the mesh is converted to [`Options::up_axis`], if the format is 3D.
+    /// If the export fails, no file is left behind.
+    ///
+    /// Returns [`InvalidScale`], if the scale isn't finite and positive. A
+    /// negative scale would mirror the mesh, which turns it inside out.
+    pub fn export(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        path: &Path,
+        options: &Options,
+    ) -> Result<(), ExportError> {
+        let exporter = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.find(extension))
+            .ok_or_else(|| UnsupportedFormat(path.display().to_string()))?;
+
+        if !options.scale.is_finite() || options.scale <= 0. {
+            return Err(InvalidScale(options.scale).into());
+        }
+
+        // 2D formats are derived from the mesh in Fornjot's own convention, so
+        // they stay upright.
+        let up_axis = if exporter.is_3d() {
+            options.up_axis
+        } else {
+            UpAxis::Z
+        };
+
+        let converted;
+        let mesh = if options.scale == 1. && up_axis == UpAxis::Z {
+            mesh
+        } else {
+            converted = mesh
+                .map_points(|point| up_axis.from_z_up(point * options.scale));
+            &converted
+        };
+        let options = Options {
+            scale: 1.,
+            up_axis: UpAxis::Z,
+            section: options.section.map(|height| height * options.scale),
+            ..options.clone()
+        };
+
+        let mut file = BufWriter::new(File::create(path)?);
+        let result =
+            exporter.export(mesh, &options, &mut file).and_then(|()| {
+                file.flush()?;
+                Ok(())
+            });
+
+        if result.is_err() {
+            // The error that matters has already happened.
+            let _ = fs::remove_file(path);
+        }
+
+        result
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register(ThreeMfExporter);
+        registry.register(AmfExporter);
+        registry.register(DxfExporter);
+        registry.register(SvgExporter);
+        registry.register(PdfExporter);
+
+        registry
+    }
+}
+
+/// No exporter has been registered for the extension of a path
+#[derive(Debug, thiserror::Error)]
+#[error("No export format for `{0}`; expected one of the registered formats")]
+pub struct UnsupportedFormat(pub String);
+
+/// The scale to export with is not finite and positive
+#[derive(Debug, thiserror::Error)]
+#[error("Can't export with a scale of {0}; expected a finite, positive number")]
+pub struct InvalidScale(pub f64);
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use fj_interop::{axes::UpAxis, mesh::Mesh};
+    use fj_math::Point;
+
+    use crate::{ExportError, Exporter, Options};
+
+    use super::{InvalidScale, Registry};
+
+    /// Writes the largest coordinates of the mesh, and the section height
+    struct Extent;
+
+    impl Exporter for Extent {
+        fn name(&self) -> &str {
+            "Extent"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["extent"]
+        }
+
+        fn export(
+            &self,
+            mesh: &Mesh<Point<3>>,
+            options: &Options,
+            writer: &mut dyn Write,
+        ) -> Result<(), ExportError> {
+            let max = mesh.vertices().fold([f64::NEG_INFINITY; 3], |max, v| {
+                let [x, y, z] = v.coords.components.map(|c| c.into_f64());
+                [max[0].max(x), max[1].max(y), max[2].max(z)]
+            });
+            write!(writer, "{:?} {:?}", max, options.section)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn find_by_extension() {
+        let mut registry = Registry::default();
+        assert!(registry.find("extent").is_none());
+        assert_eq!(registry.find("3MF").map(|e| e.name()), Some("3MF"));
+
+        registry.register(Extent);
+        assert_eq!(registry.find("extent").map(|e| e.name()), Some("Extent"));
+    }
+
+    fn export_extent(options: &Options) -> Result<String, ExportError> {
+        let mut registry = Registry::empty();
+        registry.register(Extent);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle(
+            [[0., 0., 0.], [1., 0., 0.], [0., 2., 0.]].map(Point::from),
+            [255, 0, 0, 255],
+        );
+
+        let path = std::env::temp_dir().join(format!(
+            "fj-export-test-{}-{:?}.extent",
+            options.scale, options.up_axis
+        ));
+        registry.export(&mesh, &path, options)?;
+        let written = fs::read_to_string(&path)?;
+        fs::remove_file(&path)?;
+
+        Ok(written)
+    }
+
+    #[test]
+    fn scale_before_export() -> Result<(), ExportError> {
+        let options = Options {
+            scale: 10.,
+            section: Some(0.5),
+            ..Options::default()
+        };
+
+        assert_eq!(export_extent(&options)?, "[10.0, 20.0, 0.0] Some(5.0)");
+        Ok(())
+    }
+
+    #[test]
+    fn convert_up_axis() -> Result<(), ExportError> {
+        let options = Options {
+            up_axis: UpAxis::Y,
+            ..Options::default()
+        };
+
+        assert_eq!(export_extent(&options)?, "[1.0, 0.0, 0.0] None");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_scale() {
+        for scale in [0., -1., f64::NAN, f64::INFINITY] {
+            let options = Options {
+                scale,
+                ..Options::default()
+            };
+
+            let err = export_extent(&options).unwrap_err();
+            assert!(err.is::<InvalidScale>());
+        }
+    }
+}
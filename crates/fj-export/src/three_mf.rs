@@ -0,0 +1,310 @@
+//! Export to 3MF
+//!
+//! A 3MF file is a ZIP archive that contains an XML description of the model,
+//! as defined by the [3MF Core Specification].
+//!
+//! [3MF Core Specification]: https://github.com/3MFConsortium/spec_core
+
+use std::{
+    fs::File,
+    io::{self, Cursor, Seek, Write},
+    path::Path,
+};
+
+use fj_interop::{mesh::Mesh, topology::Topology};
+use fj_math::Point;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    model::{escape, Model},
+    ExportError, Exporter, Metadata, Options,
+};
+
+/// Export the mesh to a 3MF file, along with the given metadata
+///
+/// Parts of the mesh that are not connected to each other, like the shapes in
+/// a group, become separate objects.
+pub fn export_3mf(
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    path: &Path,
+) -> Result<(), Error> {
+    write_3mf(File::create(path)?, mesh, metadata)
+}
+
+/// Exports meshes to 3MF
+///
+/// See [`export_3mf`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreeMfExporter;
+
+impl Exporter for ThreeMfExporter {
+    fn name(&self) -> &str {
+        "3MF"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["3mf"]
+    }
+
+    fn export(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        options: &Options,
+        writer: &mut dyn Write,
+    ) -> Result<(), ExportError> {
+        // Writing a ZIP archive requires seeking, which the writer might not
+        // support.
+        let mut buffer = Cursor::new(Vec::new());
+        write_3mf(&mut buffer, mesh, &options.metadata)?;
+        writer.write_all(buffer.get_ref())?;
+
+        Ok(())
+    }
+}
+
+fn write_3mf(
+    writer: impl Write + Seek,
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+) -> Result<(), Error> {
+    let mut archive = ZipWriter::new(writer);
+    let options =
+        FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    archive.start_file("[Content_Types].xml", options)?;
+    archive.write_all(CONTENT_TYPES.as_bytes())?;
+
+    archive.start_file("_rels/.rels", options)?;
+    archive.write_all(RELATIONSHIPS.as_bytes())?;
+
+    archive.start_file("3D/3dmodel.model", options)?;
+    write_model(&mut archive, mesh, metadata)?;
+
+    archive.finish()?;
+
+    Ok(())
+}
+
+/// An error that can occur when exporting to 3MF
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The file could not be written
+    #[error("Error writing 3MF file")]
+    Io(#[from] io::Error),
+
+    /// The archive could not be written
+    #[error("Error writing 3MF archive")]
+    Zip(#[from] zip::result::ZipError),
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+/// Write the XML description of the model
+fn write_model(
+    file: &mut impl Write,
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+) -> io::Result<()> {
+    let model = Model::new(mesh);
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        file,
+        concat!(
+            r#"<model unit="{}" xml:lang="en-US" "#,
+            r#"xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">"#,
+        ),
+        metadata.unit.name()
+    )?;
+
+    for (name, value) in [
+        ("Title", &metadata.name),
+        ("Designer", &metadata.author),
+        ("Application", &metadata.application),
+    ] {
+        if let Some(value) = value {
+            writeln!(
+                file,
+                r#"<metadata name="{}">{}</metadata>"#,
+                name,
+                escape(value)
+            )?;
+        }
+    }
+
+    writeln!(file, "<resources>")?;
+    if !model.materials.is_empty() {
+        writeln!(file, r#"<basematerials id="1">"#)?;
+    }
+    for (i, [r, g, b, a]) in model.materials.iter().enumerate() {
+        writeln!(
+            file,
+            concat!(
+                r#"<base name="{}" "#,
+                r##"displaycolor="#{:02X}{:02X}{:02X}{:02X}"/>"##,
+            ),
+            escape(&model.material_name(metadata, i)),
+            r,
+            g,
+            b,
+            a
+        )?;
+    }
+    if !model.materials.is_empty() {
+        writeln!(file, "</basematerials>")?;
+    }
+
+    // Objects of type `model` must be closed and manifold. Surfaces don't
+    // have to be, and anything else is of type `other`.
+    let object_type = match metadata.topology {
+        Topology::Solid => "model",
+        Topology::Sheet => "surface",
+        Topology::Wire => "other",
+    };
+
+    for (i, object) in model.objects.iter().enumerate() {
+        // Objects get the color of their first triangle. Triangles only need
+        // their own, if it's a different one.
+        let default_material = object.material();
+
+        writeln!(
+            file,
+            r#"<object id="{}" type="{}" name="{}" pid="1" pindex="{}">"#,
+            i + 2,
+            object_type,
+            escape(&model.object_name(metadata, i)),
+            default_material,
+        )?;
+        writeln!(file, "<mesh>")?;
+
+        writeln!(file, "<vertices>")?;
+        for vertex in &object.vertices {
+            let [x, y, z] = vertex.coords.components;
+            writeln!(
+                file,
+                r#"<vertex x="{}" y="{}" z="{}"/>"#,
+                x.into_f64(),
+                y.into_f64(),
+                z.into_f64()
+            )?;
+        }
+        writeln!(file, "</vertices>")?;
+
+        writeln!(file, "<triangles>")?;
+        for triangle in &object.triangles {
+            let [v1, v2, v3] = triangle.points;
+            write!(file, r#"<triangle v1="{}" v2="{}" v3="{}""#, v1, v2, v3)?;
+
+            if triangle.material != default_material {
+                write!(file, r#" pid="1" p1="{}""#, triangle.material)?;
+            }
+
+            writeln!(file, "/>")?;
+        }
+        writeln!(file, "</triangles>")?;
+
+        writeln!(file, "</mesh>")?;
+        writeln!(file, "</object>")?;
+    }
+    writeln!(file, "</resources>")?;
+
+    writeln!(file, "<build>")?;
+    for i in 0..model.objects.len() {
+        writeln!(file, r#"<item objectid="{}"/>"#, i + 2)?;
+    }
+    writeln!(file, "</build>")?;
+    writeln!(file, "</model>")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{mesh::Mesh, topology::Topology};
+    use fj_math::Point;
+
+    use crate::Metadata;
+
+    use super::write_model;
+
+    #[test]
+    fn metadata_and_colors() {
+        let mut mesh = Mesh::new();
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+                .map(Point::from);
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+        mesh.push_triangle([a, c, d], [0, 0, 255, 255]);
+
+        let metadata = Metadata {
+            name: Some("Cup & saucer".to_owned()),
+            application: Some("Fornjot".to_owned()),
+            ..Metadata::default()
+        };
+        let mut xml = Vec::new();
+        write_model(&mut xml, &mesh, &metadata).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+
+        assert!(xml.contains(r#"unit="millimeter""#));
+        assert!(xml.contains(r#"<metadata name="Title">Cup &amp; saucer<"#));
+        assert!(xml.contains(r#"<metadata name="Application">Fornjot<"#));
+        assert!(!xml.contains("Designer"));
+
+        assert!(xml.contains(r##"displaycolor="#FF0000FF""##));
+        assert!(xml.contains(r#"<base name="Color 2" "#));
+        assert!(xml.contains(r#"name="Cup &amp; saucer" pid="1" pindex="0""#));
+        assert!(xml.contains(r#"v1="0" v2="2" v3="3" pid="1" p1="1"/>"#));
+        assert_eq!(xml.matches("<vertex ").count(), 4);
+        assert!(xml.contains(r#"type="model""#));
+    }
+
+    #[test]
+    fn sheet_as_surface() {
+        let mut mesh = Mesh::new();
+        let [a, b, c] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from);
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+
+        let metadata = Metadata {
+            topology: Topology::Sheet,
+            ..Metadata::default()
+        };
+        let mut xml = Vec::new();
+        write_model(&mut xml, &mesh, &metadata).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+
+        assert!(xml.contains(r#"type="surface""#));
+        assert!(!xml.contains(r#"type="model""#));
+    }
+
+    #[test]
+    fn material_names() {
+        let mut mesh = Mesh::new();
+        let [a, b, c] =
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from);
+        mesh.push_triangle([a, b, c], [240, 240, 240, 255]);
+
+        let mut metadata = Metadata::default();
+        metadata
+            .material_names
+            .insert([240, 240, 240, 255], "PLA".to_owned());
+        let mut xml = Vec::new();
+        write_model(&mut xml, &mesh, &metadata).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+
+        assert!(xml.contains(r#"<base name="PLA" "#));
+        assert!(!xml.contains("Color 1"));
+    }
+}
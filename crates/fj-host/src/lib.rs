@@ -15,13 +15,18 @@
 
 #![deny(missing_docs)]
 
+#[cfg(feature = "rhai")]
+pub mod script;
+
+mod library;
 mod platform;
 
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsStr,
-    io,
-    path::PathBuf,
+    fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
     process::Command,
     sync::mpsc,
     thread,
@@ -29,14 +34,37 @@ use std::{
 
 use notify::Watcher as _;
 use thiserror::Error;
+use tracing::{error, info_span};
 
-use self::platform::HostPlatform;
+use self::{library::Library, platform::HostPlatform};
 
 /// Represents a Fornjot model
 pub struct Model {
+    /// The path that the model was initialized from
+    path: PathBuf,
+
+    /// The path that is watched for changes
     src_path: PathBuf,
-    lib_path: PathBuf,
-    manifest_path: PathBuf,
+    kind: Kind,
+}
+
+enum Kind {
+    /// A Rust crate, that is compiled to a dynamic library
+    Library {
+        lib_path: PathBuf,
+        manifest_path: PathBuf,
+    },
+
+    /// A Rhai script, located at `src_path`
+    #[cfg(feature = "rhai")]
+    Script,
+
+    /// A Python file, located at `src_path`
+    #[cfg(feature = "python")]
+    Python,
+
+    /// An OpenSCAD file, located at `src_path`
+    OpenScad,
 }
 
 impl Model {
@@ -45,10 +73,52 @@ impl Model {
     /// Optionally, the target directory where plugin files are compiled to can
     /// be provided. If it is not provided, the target directory is assumed to
     /// be located within the model path.
+    ///
+    /// The path can also point to an OpenSCAD file (a file with the `scad`
+    /// extension), which is converted using [`fj_openscad`]. If the `rhai`
+    /// feature is enabled, it can point to a Rhai script (a file with the
+    /// `rhai` extension). See [`script`]. If the `python` feature is enabled,
+    /// it can point to a Python file (a file with the `py` extension), which
+    /// is evaluated using [`fornjot::model`].
     pub fn from_path(
         path: PathBuf,
         target_dir: Option<PathBuf>,
     ) -> io::Result<Self> {
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "scad")
+        {
+            return Ok(Self {
+                src_path: path.canonicalize()?,
+                path,
+                kind: Kind::OpenScad,
+            });
+        }
+
+        #[cfg(feature = "rhai")]
+        if script::is_script(&path) {
+            // Make sure the error is reported here, like it is for libraries,
+            // instead of when the model is loaded.
+            let src_path = path.canonicalize()?;
+
+            return Ok(Self {
+                path,
+                src_path,
+                kind: Kind::Script,
+            });
+        }
+
+        #[cfg(feature = "python")]
+        if fornjot::model::is_python_model(&path) {
+            let src_path = path.canonicalize()?;
+
+            return Ok(Self {
+                path,
+                src_path,
+                kind: Kind::Python,
+            });
+        }
+
         let name = {
             // Can't panic. It only would, if the path ends with "..", and we
             // are canonicalizing it here to prevent that.
@@ -69,12 +139,53 @@ impl Model {
         let manifest_path = path.join("Cargo.toml");
 
         Ok(Self {
+            path,
             src_path,
-            lib_path,
-            manifest_path,
+            kind: Kind::Library {
+                lib_path,
+                manifest_path,
+            },
         })
     }
 
+    /// Access the path that the model was initialized from
+    ///
+    /// That's the directory of a Rust crate, or the file of a script or
+    /// OpenSCAD model.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Find the models in the subdirectories of the given directory
+    ///
+    /// Every subdirectory that contains a `Cargo.toml` is considered to be a
+    /// model. The models are returned along with their names, which are the
+    /// names of their directories, sorted by name. The target directory is
+    /// treated like in [`Model::from_path`].
+    pub fn find_all(
+        path: &Path,
+        target_dir: Option<PathBuf>,
+    ) -> io::Result<Vec<(String, Self)>> {
+        let mut models = Vec::new();
+
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            if !path.join("Cargo.toml").is_file() {
+                continue;
+            }
+
+            // Can't panic. `read_dir` doesn't return entries for `..`.
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let model = Self::from_path(path, target_dir.clone())?;
+
+            models.push((name, model));
+        }
+
+        models.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(models)
+    }
+
     /// Load the model once
     ///
     /// The passed arguments are provided to the model. Returns the shape that
@@ -85,41 +196,92 @@ impl Model {
     pub fn load_once(
         &self,
         arguments: &Parameters,
-    ) -> Result<fj::Shape, Error> {
-        let manifest_path = self.manifest_path.display().to_string();
+    ) -> Result<LoadedShape, Error> {
+        let shape = match &self.kind {
+            Kind::Library {
+                lib_path,
+                manifest_path,
+            } => return load_library(lib_path, manifest_path, arguments),
+            #[cfg(feature = "rhai")]
+            Kind::Script => script::evaluate(&self.src_path, arguments)?,
+            #[cfg(feature = "python")]
+            Kind::Python => {
+                let path = self.src_path.display().to_string();
+                let _span = info_span!("load_model", model = %path).entered();
+
+                info_span!("evaluate_model")
+                    .in_scope(|| {
+                        fornjot::model::evaluate(&self.src_path, &arguments.0)
+                    })
+                    .map_err(Error::Python)?
+            }
+            Kind::OpenScad => {
+                let path = self.src_path.display().to_string();
+                let _span = info_span!("load_model", model = %path).entered();
 
-        let status = Command::new("cargo")
-            .arg("build")
-            .args(["--manifest-path", &manifest_path])
-            .status()?;
+                let source = fs::read_to_string(&self.src_path)?;
+                info_span!("evaluate_model")
+                    .in_scope(|| fj_openscad::convert(&source, &arguments.0))?
+            }
+        };
 
-        if !status.success() {
-            return Err(Error::Compile);
-        }
+        Ok(LoadedShape {
+            shape,
+            _library: None,
+        })
+    }
 
-        // So, strictly speaking this is all unsound:
-        // - `Library::new` requires us to abide by the arbitrary requirements
-        //   of any library initialization or termination routines.
-        // - `Library::get` requires us to specify the correct type for the
-        //   model function.
-        // - The model function itself is `unsafe`, because it is a function
-        //   from across an FFI interface.
-        //
-        // Typical models won't have initialization or termination routines (I
-        // think), should abide by the `ModelFn` signature, and might not do
-        // anything unsafe. But we have no way to know that the library the user
-        // told us to load actually does (I think).
-        //
-        // I don't know of a way to fix this. We should take this as motivation
-        // to switch to a better technique:
-        // https://github.com/hannobraun/Fornjot/issues/71
-        let shape = unsafe {
-            let lib = libloading::Library::new(&self.lib_path)?;
-            let model: libloading::Symbol<ModelFn> = lib.get(b"model")?;
-            model(arguments)
+    /// List the parameters that the model accepts
+    ///
+    /// Models written in Rust declare their parameters in the
+    /// `[package.metadata.fj.parameters]` table of their `Cargo.toml`, mapping
+    /// each name to its default value. Models that don't declare any have no
+    /// parameters, as far as this method is concerned.
+    ///
+    /// The parameters of an OpenSCAD file are its top-level variables. Scripts
+    /// and Python models are evaluated, to find the parameters they read.
+    pub fn parameters(&self) -> Result<Vec<Parameter>, Error> {
+        let parameters = match &self.kind {
+            Kind::Library { manifest_path, .. } => {
+                let manifest: toml::Value =
+                    toml::from_str(&fs::read_to_string(manifest_path)?)?;
+                let parameters = manifest
+                    .get("package")
+                    .and_then(|package| package.get("metadata"))
+                    .and_then(|metadata| metadata.get("fj"))
+                    .and_then(|fj| fj.get("parameters"))
+                    .and_then(|parameters| parameters.as_table());
+
+                parameters
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, default)| Parameter {
+                        name: name.clone(),
+                        default: match default {
+                            toml::Value::String(default) => default.clone(),
+                            default => default.to_string(),
+                        },
+                    })
+                    .collect()
+            }
+            #[cfg(feature = "rhai")]
+            Kind::Script => script::parameters(&self.src_path)?,
+            #[cfg(feature = "python")]
+            Kind::Python => fornjot::model::parameters(&self.src_path)
+                .map_err(Error::Python)?
+                .into_iter()
+                .map(|(name, default)| Parameter { name, default })
+                .collect(),
+            Kind::OpenScad => {
+                let source = fs::read_to_string(&self.src_path)?;
+                fj_openscad::parameters(&source)?
+                    .into_iter()
+                    .map(|(name, default)| Parameter { name, default })
+                    .collect()
+            }
         };
 
-        Ok(shape)
+        Ok(parameters)
     }
 
     /// Load the model, then watch it for changes
@@ -207,38 +369,93 @@ impl Model {
     }
 }
 
+fn load_library(
+    lib_path: &Path,
+    manifest_path: &Path,
+    arguments: &Parameters,
+) -> Result<LoadedShape, Error> {
+    let manifest_path = manifest_path.display().to_string();
+    let _span = info_span!("load_model", model = %manifest_path).entered();
+
+    let status = info_span!("compile_model").in_scope(|| {
+        Command::new("cargo")
+            .arg("build")
+            .args(["--manifest-path", &manifest_path])
+            .status()
+    })?;
+
+    if !status.success() {
+        return Err(Error::Compile);
+    }
+
+    // So, strictly speaking this is all unsound:
+    // - `Library::load` requires us to abide by the arbitrary requirements
+    //   of any library initialization or termination routines.
+    // - `Library::model` requires the library to export a model function with
+    //   the correct type.
+    // - The model function itself is `unsafe`, because it is a function
+    //   from across an FFI interface.
+    //
+    // Typical models won't have initialization or termination routines (I
+    // think), should abide by the `ModelFn` signature, and might not do
+    // anything unsafe. But we have no way to know that the library the user
+    // told us to load actually does (I think).
+    //
+    // I don't know of a way to fix this. We should take this as motivation
+    // to switch to a better technique:
+    // https://github.com/hannobraun/Fornjot/issues/71
+    info_span!("evaluate_model").in_scope(|| unsafe {
+        let library = Library::load(lib_path)?;
+        let shape = library.model(arguments)?;
+
+        Ok(LoadedShape {
+            shape,
+            _library: Some(library),
+        })
+    })
+}
+
+/// A shape that a model created
+///
+/// Dereferences to the [`fj::Shape`]. Shapes can refer to code of the model,
+/// like the distance functions of [`fj::Implicit`]. If the model was loaded from
+/// a dynamic library, the library stays loaded until this is dropped.
+pub struct LoadedShape {
+    // Fields are dropped in order, so the shape goes first, while the code it
+    // might still call into is available.
+    shape: fj::Shape,
+    _library: Option<Library>,
+}
+
+impl Deref for LoadedShape {
+    type Target = fj::Shape;
+
+    fn deref(&self) -> &Self::Target {
+        &self.shape
+    }
+}
+
 /// Watches a model for changes, reloading it continually
 pub struct Watcher {
-    _watcher: Box<dyn notify::Watcher>,
+    _watcher: Box<dyn notify::Watcher + Send>,
     channel: mpsc::Receiver<()>,
     model: Model,
     parameters: Parameters,
 }
 
 impl Watcher {
+    /// Access the model that is being watched
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+
     /// Receive an updated shape that the reloaded model created
     ///
     /// Returns `None`, if the model has not changed since the last time this
     /// method was called.
-    pub fn receive(&self) -> Option<fj::Shape> {
+    pub fn receive(&self) -> Option<LoadedShape> {
         match self.channel.try_recv() {
-            Ok(()) => {
-                let shape = match self.model.load_once(&self.parameters) {
-                    Ok(shape) => shape,
-                    Err(Error::Compile) => {
-                        // It would be better to display an error in the UI,
-                        // where the user can actually see it. Issue:
-                        // https://github.com/hannobraun/fornjot/issues/30
-                        println!("Error compiling model");
-                        return None;
-                    }
-                    Err(err) => {
-                        panic!("Error reloading model: {:?}", err);
-                    }
-                };
-
-                Some(shape)
-            }
+            Ok(()) => self.reload(),
             Err(mpsc::TryRecvError::Empty) => {
                 // Nothing to receive from the channel.
                 None
@@ -251,9 +468,72 @@ impl Watcher {
             }
         }
     }
+
+    /// Block until the model has changed
+    ///
+    /// Returns immediately, if the model has changed since the last time this
+    /// method, or [`Watcher::receive`], was called. Call [`Watcher::reload`]
+    /// afterwards, to get the updated shape.
+    pub fn wait_for_change(&self) {
+        if self.channel.recv().is_err() {
+            // The other end has disconnected. See comment in `receive`.
+            panic!();
+        }
+    }
+
+    /// Reload the model, returning the shape it creates
+    ///
+    /// Returns `None`, if the model could not be compiled or loaded, or a
+    /// script, Python, or OpenSCAD model could not be evaluated. The error is
+    /// logged.
+    pub fn reload(&self) -> Option<LoadedShape> {
+        match self.model.load_once(&self.parameters) {
+            Ok(shape) => Some(shape),
+            Err(Error::Compile) => {
+                // Callers can report the failure, but the compiler output
+                // itself is only printed to the terminal. Issue:
+                // https://github.com/hannobraun/fornjot/issues/30
+                error!("Error compiling model");
+                None
+            }
+            Err(Error::OpenScad(err)) => {
+                error!("Error converting OpenSCAD model: {}", err);
+                None
+            }
+            #[cfg(feature = "rhai")]
+            Err(err @ Error::Script(_)) => {
+                // Like a compile error, this is a mistake in the model that
+                // the user can fix. No reason to give up.
+                error!("{}", err);
+                None
+            }
+            #[cfg(feature = "python")]
+            Err(err @ Error::Python(_)) => {
+                error!("{}", err);
+                None
+            }
+            Err(err) => {
+                // Whatever went wrong, it might be fixed by the next change to
+                // the model. Giving up here would take the caller down with us.
+                error!("Error reloading model: {:?}", err);
+                None
+            }
+        }
+    }
+}
+
+/// A parameter that a model accepts
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parameter {
+    /// The name of the parameter
+    pub name: String,
+
+    /// The value that the model uses, if the parameter is not passed
+    pub default: String,
 }
 
 /// Parameters that are passed to a model
+#[derive(Clone)]
 pub struct Parameters(pub HashMap<String, String>);
 
 impl Parameters {
@@ -278,9 +558,27 @@ pub enum Error {
     #[error("Error loading model from dynamic library")]
     LibLoading(#[from] libloading::Error),
 
+    /// The model's `Cargo.toml` could not be parsed
+    #[error("Error parsing model manifest")]
+    Manifest(#[from] toml::de::Error),
+
     /// Error while watching the model code for changes
     #[error("Error watching model for changes")]
     Notify(#[from] notify::Error),
+
+    /// Error while converting an OpenSCAD model
+    #[error("Error converting OpenSCAD model")]
+    OpenScad(#[from] fj_openscad::Error),
+
+    /// Error while evaluating a script model
+    #[cfg(feature = "rhai")]
+    #[error("Error evaluating script: {0}")]
+    Script(String),
+
+    /// Error while evaluating a Python model
+    #[cfg(feature = "python")]
+    #[error("Error evaluating Python model: {0}")]
+    Python(String),
 }
 
 type ModelFn = unsafe extern "C" fn(args: &Parameters) -> fj::Shape;
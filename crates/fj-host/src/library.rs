@@ -0,0 +1,94 @@
+//! Loading of models that are compiled to dynamic libraries
+
+use std::{
+    fs,
+    mem::ManuallyDrop,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{Error, ModelFn};
+
+/// A model that has been loaded from a dynamic library
+///
+/// Shapes can refer to code in the library, so the library needs to stay
+/// loaded as long as they exist. See [`LoadedShape`].
+///
+/// While a library is loaded, loading the same path again might just return
+/// the library that is already loaded, instead of the one that has been
+/// compiled since. To get around that, each library is loaded from a copy of
+/// the compiled file, which is removed again, once the library is unloaded.
+///
+/// [`LoadedShape`]: crate::LoadedShape
+pub struct Library {
+    library: ManuallyDrop<libloading::Library>,
+    path: PathBuf,
+}
+
+impl Library {
+    /// Load the library at the given path
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization routines, which can do
+    /// anything. See [`libloading::Library::new`].
+    pub unsafe fn load(lib_path: &Path) -> Result<Self, Error> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        // The copy needs to keep the extension, or some platforms won't load
+        // it. It's placed next to the original, as the temporary directory
+        // might not allow executing code from it.
+        let mut file_name =
+            lib_path.file_stem().unwrap_or_default().to_os_string();
+        file_name.push(format!("-loaded-{}-{}", process::id(), id));
+        let mut path = lib_path.with_file_name(file_name);
+        if let Some(extension) = lib_path.extension() {
+            path.set_extension(extension);
+        }
+
+        fs::copy(lib_path, &path)?;
+
+        let library = match libloading::Library::new(&path) {
+            Ok(library) => library,
+            Err(err) => {
+                let _ = fs::remove_file(&path);
+                return Err(err.into());
+            }
+        };
+
+        Ok(Self {
+            library: ManuallyDrop::new(library),
+            path,
+        })
+    }
+
+    /// Call the model function of the library
+    ///
+    /// # Safety
+    ///
+    /// The library must export a `model` function of the right signature. The
+    /// function itself might do anything.
+    pub unsafe fn model(
+        &self,
+        arguments: &crate::Parameters,
+    ) -> Result<fj::Shape, Error> {
+        let model: libloading::Symbol<ModelFn> = self.library.get(b"model")?;
+        Ok(model(arguments))
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        // This is sound, as the library is never used again. Whoever made the
+        // shapes that refer to it, made sure they are dropped before.
+        unsafe {
+            ManuallyDrop::drop(&mut self.library);
+        }
+
+        // Nothing to be done about an error here. The worst case is that a
+        // copy of the library is left in the target directory.
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,374 @@
+//! Models written as [Rhai] scripts
+//!
+//! Unlike models written in Rust, scripts don't need to be compiled, which
+//! makes them well-suited for quick experiments and for teaching. A script
+//! evaluates to the shape it defines, for example:
+//!
+//! ``` rhai
+//! let outer = parameters.float_or("outer", 50.0);
+//! let inner = parameters.float_or("inner", 25.0);
+//!
+//! let footprint = difference(circle(outer), circle(inner));
+//! sweep(footprint, [0, 0, 10])
+//! ```
+//!
+//! The following functions are available, mirroring the [`fj`] crate:
+//!
+//! - `circle(radius)` and `sketch(points)` create 2D shapes, which can be
+//!   colored with `with_color(shape, [r, g, b, a])`.
+//! - `difference(a, b)` subtracts one 2D shape from another.
+//! - `minkowski(a, b)` computes the Minkowski sum of two convex 2D shapes.
+//! - `sweep(shape, path)` sweeps a 2D shape into a 3D shape.
+//! - `group(a, b)` groups two disjoint 3D shapes.
+//! - `rounded(shape, radius)` grows a 3D shape by a sphere, which rounds its
+//!   edges and corners.
+//! - `transform(shape, axis, angle, offset)` rotates a 3D shape around an
+//!   axis, by an angle in radians, then translates it.
+//!
+//! Points and vectors are arrays of numbers. The model's parameters are
+//! available as the `parameters` map, which has string values.
+//! `parameters.float_or(name, default)` parses a parameter as a number.
+//!
+//! [Rhai]: https://rhai.rs/
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope, FLOAT};
+use tracing::info_span;
+
+use crate::{Error, Parameter, Parameters};
+
+type Result<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+/// Indicate whether the path points to a script
+pub fn is_script(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == "rhai")
+}
+
+/// Evaluate the script at the given path, returning the shape it defines
+pub(crate) fn evaluate(
+    path: &Path,
+    arguments: &Parameters,
+) -> std::result::Result<fj::Shape, Error> {
+    let _span = info_span!("load_model", model = %path.display()).entered();
+
+    let mut parameters = Map::new();
+    for (name, value) in &arguments.0 {
+        parameters.insert(name.as_str().into(), value.clone().into());
+    }
+
+    let mut scope = Scope::new();
+    scope.push_constant("parameters", parameters);
+
+    let shape = info_span!("evaluate_model").in_scope(|| {
+        engine(Recorder::default())
+            .eval_file_with_scope::<Dynamic>(&mut scope, path.to_owned())
+    });
+    let shape = shape.map_err(|err| Error::Script(err.to_string()))?;
+
+    if shape.is::<fj::Shape3d>() {
+        return Ok(shape.cast::<fj::Shape3d>().into());
+    }
+    if shape.is::<fj::Shape2d>() {
+        return Ok(fj::Shape::Shape2d(shape.cast()));
+    }
+
+    Err(Error::Script(format!(
+        "Script must evaluate to a shape, not `{}`",
+        shape.type_name()
+    )))
+}
+
+/// Find the parameters that the script at the given path reads
+///
+/// The script is evaluated without any parameters. Parameters that are only
+/// read in branches that aren't taken then are not found.
+pub(crate) fn parameters(
+    path: &Path,
+) -> std::result::Result<Vec<Parameter>, Error> {
+    let mut scope = Scope::new();
+    scope.push_constant("parameters", Map::new());
+
+    let recorder = Recorder::default();
+    engine(recorder.clone())
+        .eval_file_with_scope::<Dynamic>(&mut scope, path.to_owned())
+        .map_err(|err| Error::Script(err.to_string()))?;
+
+    // Can't panic. The lock is only held while recording a single parameter.
+    let parameters = recorder.lock().unwrap().clone();
+    Ok(parameters)
+}
+
+/// The parameters that a script has read, in the order it read them
+type Recorder = Arc<Mutex<Vec<Parameter>>>;
+
+fn engine(recorder: Recorder) -> Engine {
+    let mut engine = Engine::new();
+
+    let float_or =
+        move |parameters: Map, name: &str, default: FLOAT| -> Result<FLOAT> {
+            let mut recorded = recorder.lock().unwrap();
+            if !recorded.iter().any(|parameter| parameter.name == name) {
+                recorded.push(Parameter {
+                    name: name.to_owned(),
+                    default: format!("{:?}", default),
+                });
+            }
+
+            read_float(&parameters, name, default)
+        };
+
+    engine
+        .register_type_with_name::<fj::Shape2d>("Shape2d")
+        .register_type_with_name::<fj::Shape3d>("Shape3d")
+        .register_result_fn("circle", circle)
+        .register_result_fn("sketch", sketch)
+        .register_result_fn("with_color", with_color)
+        .register_fn("difference", difference)
+        .register_fn("minkowski", minkowski)
+        .register_result_fn("sweep", sweep)
+        .register_fn("group", group)
+        .register_result_fn("rounded", rounded)
+        .register_result_fn("transform", transform)
+        .register_result_fn("float_or", float_or);
+
+    engine
+}
+
+fn circle(radius: Dynamic) -> Result<fj::Shape2d> {
+    let radius = number(&radius)?;
+    if !radius.is_finite() || radius <= 0. {
+        return Err("Radius must be positive".into());
+    }
+
+    Ok(fj::Circle::from_radius(radius).into())
+}
+
+fn sketch(points: Array) -> Result<fj::Shape2d> {
+    if points.len() < 3 {
+        return Err("A sketch needs at least 3 points".into());
+    }
+
+    let points = points
+        .into_iter()
+        .map(|point| {
+            let point = point
+                .try_cast::<Array>()
+                .ok_or("Points must be arrays of numbers")?;
+            vector::<2>(point)
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(fj::Sketch::from_points(points).into())
+}
+
+fn with_color(shape: fj::Shape2d, color: Array) -> Result<fj::Shape2d> {
+    let color = vector::<4>(color)?;
+    if color.iter().any(|&c| !(0. ..=255.).contains(&c)) {
+        return Err("Color components must be between 0 and 255".into());
+    }
+
+    Ok(shape.with_color(color.map(|c| c as u8)))
+}
+
+fn difference(a: fj::Shape2d, b: fj::Shape2d) -> fj::Shape2d {
+    fj::Difference2d::from_shapes([a, b]).into()
+}
+
+fn minkowski(a: fj::Shape2d, b: fj::Shape2d) -> Result<fj::Shape2d> {
+    let minkowski =
+        fj::Minkowski2d::from_shapes([a, b]).map_err(|err| err.to_string())?;
+    Ok(minkowski.into())
+}
+
+fn sweep(shape: fj::Shape2d, path: Array) -> Result<fj::Shape3d> {
+    Ok(fj::Sweep::from_path(shape, vector(path)?).into())
+}
+
+fn group(a: fj::Shape3d, b: fj::Shape3d) -> fj::Shape3d {
+    fj::Group { a, b }.into()
+}
+
+fn rounded(shape: fj::Shape3d, radius: Dynamic) -> Result<fj::Shape3d> {
+    let radius = number(&radius)?;
+    if !radius.is_finite() || radius <= 0. {
+        return Err("Radius must be positive".into());
+    }
+
+    Ok(fj::Rounded::from_shape(shape, radius).into())
+}
+
+fn transform(
+    shape: fj::Shape3d,
+    axis: Array,
+    angle: Dynamic,
+    offset: Array,
+) -> Result<fj::Shape3d> {
+    Ok(fj::Transform {
+        shape,
+        axis: vector(axis)?,
+        angle: number(&angle)?,
+        offset: vector(offset)?,
+    }
+    .into())
+}
+
+fn read_float(parameters: &Map, name: &str, default: FLOAT) -> Result<FLOAT> {
+    match parameters.get(name) {
+        Some(value) => value.clone().into_string()?.parse().map_err(|_| {
+            format!("Parameter `{}` is not a number", name).into()
+        }),
+        None => Ok(default),
+    }
+}
+
+/// Convert a number, which may be an integer, as scripts tend to write `10`
+/// instead of `10.0`
+fn number(value: &Dynamic) -> Result<f64> {
+    if let Ok(value) = value.as_float() {
+        return Ok(value);
+    }
+    if let Ok(value) = value.as_int() {
+        return Ok(value as f64);
+    }
+
+    Err(format!("Expected a number, not `{}`", value.type_name()).into())
+}
+
+fn vector<const D: usize>(components: Array) -> Result<[f64; D]> {
+    if components.len() != D {
+        return Err(format!(
+            "Expected {} components, not {}",
+            D,
+            components.len()
+        )
+        .into());
+    }
+
+    let mut vector = [0.; D];
+    for (component, value) in vector.iter_mut().zip(&components) {
+        *component = number(value)?;
+    }
+
+    Ok(vector)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        env, fs,
+        path::PathBuf,
+        process,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    use crate::{Parameter, Parameters};
+
+    use super::{evaluate, is_script, parameters};
+
+    const SPACER: &str = "
+        let outer = parameters.float_or(\"outer\", 2.0);
+        let inner = parameters.float_or(\"inner\", 1.0);
+
+        let footprint = difference(circle(outer), circle(inner));
+        sweep(footprint, [0, 0, 1])
+    ";
+
+    #[test]
+    fn evaluate_script() {
+        let path = script_file(SPACER);
+        assert!(is_script(&path));
+
+        let arguments = parameters_from([("outer", "3.0")]);
+        let shape = evaluate(&path, &arguments).unwrap();
+        assert!(matches!(shape, fj::Shape::Shape3d(_)));
+
+        let shape = script_file("circle(1.0)");
+        let shape = evaluate(&shape, &Parameters::empty()).unwrap();
+        assert!(matches!(shape, fj::Shape::Shape2d(_)));
+    }
+
+    #[test]
+    fn float_or() {
+        let path = script_file("circle(parameters.float_or(\"radius\", 1.0))");
+
+        for radius in ["2", "2.5", "1e1"] {
+            let arguments = parameters_from([("radius", radius)]);
+            assert!(evaluate(&path, &arguments).is_ok());
+        }
+
+        let arguments = parameters_from([("radius", "two")]);
+        assert!(evaluate(&path, &arguments).is_err());
+    }
+
+    #[test]
+    fn find_parameters() {
+        let path = script_file(SPACER);
+
+        assert_eq!(
+            parameters(&path).unwrap(),
+            [
+                Parameter {
+                    name: "outer".to_owned(),
+                    default: "2.0".to_owned(),
+                },
+                Parameter {
+                    name: "inner".to_owned(),
+                    default: "1.0".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_scripts() {
+        for source in [
+            "1 + 1",
+            "circle(0.0)",
+            "circle(-1.0)",
+            "circle(0.0 / 0.0)",
+            "circle(\"one\")",
+            "sketch([[0, 0], [1, 0]])",
+            "sketch([[0, 0], [1, 0], [1]])",
+            "with_color(circle(1.0), [0, 0, 256, 255])",
+            "sweep(circle(1.0), [0, 1])",
+            "rounded(sweep(circle(1.0), [0, 0, 1]), 0.0)",
+            "undefined(1.0)",
+        ] {
+            let path = script_file(source);
+            assert!(
+                evaluate(&path, &Parameters::empty()).is_err(),
+                "{}",
+                source
+            );
+        }
+    }
+
+    fn parameters_from<const N: usize>(
+        parameters: [(&str, &str); N],
+    ) -> Parameters {
+        let parameters = parameters
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()));
+        Parameters(HashMap::from_iter(parameters))
+    }
+
+    fn script_file(source: &str) -> PathBuf {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        let path = env::temp_dir().join(format!(
+            "fj-script-{}-{}.rhai",
+            process::id(),
+            id
+        ));
+        fs::write(&path, source).unwrap();
+
+        path
+    }
+}
@@ -0,0 +1,83 @@
+//! Conventions for the orientation of coordinate systems
+
+use std::str::FromStr;
+
+use fj_math::{Point, Scalar};
+
+/// The axis that points up, in a right-handed coordinate system
+///
+/// Fornjot, like most CAD applications, uses [`UpAxis::Z`]. Graphics
+/// applications and game engines often use [`UpAxis::Y`] instead. Exporters
+/// and importers use this to convert between the two, so models arrive upright.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UpAxis {
+    /// The y-axis points up, and the z-axis towards the viewer
+    Y,
+
+    /// The z-axis points up, and the y-axis away from the viewer
+    Z,
+}
+
+impl UpAxis {
+    /// Convert a point from Fornjot's convention into this one
+    pub fn from_z_up(&self, point: Point<3>) -> Point<3> {
+        match self {
+            Self::Y => Point::from([point.x, point.z, negate(point.y)]),
+            Self::Z => point,
+        }
+    }
+
+    /// Convert a point from this convention into Fornjot's
+    pub fn to_z_up(&self, point: Point<3>) -> Point<3> {
+        match self {
+            Self::Y => Point::from([point.x, negate(point.z), point.y]),
+            Self::Z => point,
+        }
+    }
+}
+
+/// Negate a coordinate, without turning zero into negative zero
+///
+/// Negative zero wouldn't hash like zero, so vertices that should be shared in
+/// a mesh might not be.
+fn negate(coordinate: Scalar) -> Scalar {
+    Scalar::ZERO - coordinate
+}
+
+impl Default for UpAxis {
+    fn default() -> Self {
+        Self::Z
+    }
+}
+
+impl FromStr for UpAxis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "y" | "Y" => Ok(Self::Y),
+            "z" | "Z" => Ok(Self::Z),
+            _ => Err(format!("Unknown up axis `{}`; expected `y` or `z`", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use super::UpAxis;
+
+    #[test]
+    fn y_up() {
+        let up = Point::from([0., 0., 1.]);
+        let back = Point::from([0., 1., 0.]);
+
+        assert_eq!(UpAxis::Y.from_z_up(up), Point::from([0., 1., 0.]));
+        assert_eq!(UpAxis::Y.from_z_up(back), Point::from([0., 0., -1.]));
+
+        for point in [up, back, Point::from([1., 2., 3.])] {
+            assert_eq!(UpAxis::Y.to_z_up(UpAxis::Y.from_z_up(point)), point);
+        }
+    }
+}
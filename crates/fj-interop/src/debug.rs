@@ -11,6 +11,9 @@ use fj_math::{Point, Segment};
 pub struct DebugInfo {
     /// Rays being used during face triangulation
     pub triangle_edge_checks: Vec<TriangleEdgeCheck>,
+
+    /// Geometry emitted by algorithms, to help with debugging them
+    pub geometry: Vec<DebugGeometry>,
 }
 
 impl DebugInfo {
@@ -26,6 +29,171 @@ impl DebugInfo {
     /// allocations.
     pub fn clear(&mut self) {
         self.triangle_edge_checks.clear();
+        self.geometry.clear();
+    }
+
+    /// Emit a point
+    pub fn point(
+        &mut self,
+        category: DebugCategory,
+        point: impl Into<Point<3>>,
+    ) -> &mut DebugGeometry {
+        self.emit(category, DebugGeometryKind::Point(point.into()))
+    }
+
+    /// Emit a line segment
+    pub fn segment(
+        &mut self,
+        category: DebugCategory,
+        segment: impl Into<Segment<3>>,
+    ) -> &mut DebugGeometry {
+        self.emit(category, DebugGeometryKind::Segment(segment.into()))
+    }
+
+    /// Emit a polyline
+    ///
+    /// Set `closed` to connect the last point with the first one.
+    pub fn polyline(
+        &mut self,
+        category: DebugCategory,
+        points: impl IntoIterator<Item = Point<3>>,
+        closed: bool,
+    ) -> &mut DebugGeometry {
+        let points = points.into_iter().collect();
+        self.emit(category, DebugGeometryKind::Polyline { points, closed })
+    }
+
+    /// Emit a text annotation at the given position
+    pub fn text(
+        &mut self,
+        category: DebugCategory,
+        position: impl Into<Point<3>>,
+        text: impl Into<String>,
+    ) -> &mut DebugGeometry {
+        self.emit(category, DebugGeometryKind::Text(position.into()))
+            .with_label(text)
+    }
+
+    fn emit(
+        &mut self,
+        category: DebugCategory,
+        kind: DebugGeometryKind,
+    ) -> &mut DebugGeometry {
+        self.geometry.push(DebugGeometry {
+            category,
+            kind,
+            label: None,
+        });
+
+        // Can't panic. We just pushed an element.
+        self.geometry.last_mut().unwrap()
+    }
+}
+
+/// A piece of geometry emitted for debugging purposes
+///
+/// Created through the methods of [`DebugInfo`].
+pub struct DebugGeometry {
+    /// The category of the geometry
+    pub category: DebugCategory,
+
+    /// The geometry itself
+    pub kind: DebugGeometryKind,
+
+    /// An optional label that is displayed next to the geometry
+    pub label: Option<String>,
+}
+
+impl DebugGeometry {
+    /// Attach a label to the geometry
+    pub fn with_label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The position, at which the label should be displayed
+    pub fn anchor(&self) -> Option<Point<3>> {
+        match &self.kind {
+            DebugGeometryKind::Point(point) => Some(*point),
+            DebugGeometryKind::Segment(segment) => Some(segment.center()),
+            DebugGeometryKind::Polyline { points, .. } => {
+                points.first().copied()
+            }
+            DebugGeometryKind::Text(position) => Some(*position),
+        }
+    }
+}
+
+/// The kind of geometry, that a [`DebugGeometry`] represents
+pub enum DebugGeometryKind {
+    /// A single point
+    Point(Point<3>),
+
+    /// A line segment
+    Segment(Segment<3>),
+
+    /// A connected sequence of line segments
+    Polyline {
+        /// The points of the polyline
+        points: Vec<Point<3>>,
+
+        /// Whether the last point is connected to the first one
+        closed: bool,
+    },
+
+    /// A text annotation
+    ///
+    /// The text itself is the label of the [`DebugGeometry`].
+    Text(Point<3>),
+}
+
+/// The category of debug geometry
+///
+/// Used to toggle the display of related debug geometry as a group.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum DebugCategory {
+    /// Curve, edge, and face approximation
+    Approximation,
+
+    /// Face triangulation
+    Triangulation,
+
+    /// Intersection tests
+    Intersection,
+
+    /// Sweeping of shapes
+    Sweep,
+
+    /// Analysis of the resulting shape, like thin walls
+    Analysis,
+
+    /// Anything that doesn't fit into the other categories
+    Other,
+}
+
+impl DebugCategory {
+    /// Return all categories
+    pub fn all() -> [Self; 6] {
+        [
+            Self::Approximation,
+            Self::Triangulation,
+            Self::Intersection,
+            Self::Sweep,
+            Self::Other,
+            Self::Analysis,
+        ]
+    }
+
+    /// Return a human-readable name of the category
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Approximation => "approximation",
+            Self::Triangulation => "triangulation",
+            Self::Intersection => "intersection",
+            Self::Sweep => "sweep",
+            Self::Analysis => "analysis",
+            Self::Other => "other",
+        }
     }
 }
 
@@ -14,5 +14,8 @@
 
 #![deny(missing_docs)]
 
+pub mod axes;
 pub mod debug;
 pub mod mesh;
+pub mod progress;
+pub mod topology;
@@ -1,8 +1,11 @@
 //! A triangle mesh
 
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
 
-use fj_math::Point;
+use fj_math::{Point, Scalar, Vector};
 
 /// A triangle mesh
 pub struct Mesh<V> {
@@ -11,6 +14,8 @@ pub struct Mesh<V> {
 
     indices_by_vertex: HashMap<V, Index>,
     triangles: Vec<Triangle>,
+
+    channels: BTreeMap<Attribute, Channel>,
 }
 
 impl<V> Mesh<V>
@@ -23,7 +28,12 @@ where
     }
 
     /// Add a vertex to the mesh
+    ///
+    /// Removes all attribute channels, as those would no longer match the
+    /// vertices of the mesh.
     pub fn push_vertex(&mut self, vertex: V) {
+        self.channels.clear();
+
         let index =
             *self.indices_by_vertex.entry(vertex).or_insert_with(|| {
                 let index = self.vertices.len();
@@ -65,16 +75,213 @@ where
     pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
         self.triangles.iter().copied()
     }
+
+    /// Access the triangles that were created from the face with the given ID
+    pub fn triangles_of_face(
+        &self,
+        face: FaceId,
+    ) -> impl Iterator<Item = Triangle> + '_ {
+        self.triangles()
+            .filter(move |triangle| triangle.face == Some(face))
+    }
+
+    /// Set an attribute channel, replacing any previous channel
+    ///
+    /// Channels are removed again, once any more vertices are added to the
+    /// mesh. They should be set after the mesh is complete.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the channel doesn't have one value per entry in
+    /// [`Mesh::indices`].
+    pub fn set_channel(&mut self, attribute: Attribute, channel: Channel) {
+        assert_eq!(
+            channel.len(),
+            self.indices.len(),
+            "Channel for {:?} doesn't match mesh",
+            attribute,
+        );
+
+        self.channels.insert(attribute, channel);
+    }
+
+    /// Access the attribute channel, if it has been set
+    pub fn channel(&self, attribute: &Attribute) -> Option<&Channel> {
+        self.channels.get(attribute)
+    }
+
+    /// Access all attribute channels
+    pub fn channels(&self) -> impl Iterator<Item = (&Attribute, &Channel)> {
+        self.channels.iter()
+    }
 }
 
 impl Mesh<Point<3>> {
     /// Add a triangle to the mesh
     pub fn push_triangle(&mut self, points: [Point<3>; 3], color: Color) {
+        self.push_triangle_with_face(points, color, None);
+    }
+
+    /// Add a triangle to the mesh, recording the face it was created from
+    pub fn push_triangle_with_face(
+        &mut self,
+        points: [Point<3>; 3],
+        color: Color,
+        face: Option<FaceId>,
+    ) {
         for point in points {
             self.push_vertex(point);
         }
 
-        self.triangles.push(Triangle { points, color });
+        self.triangles.push(Triangle {
+            points,
+            color,
+            face,
+        });
+    }
+
+    /// Create a new mesh, with each point of this one mapped through `f`
+    ///
+    /// The triangles keep their colors and faces. Attribute channels are not
+    /// carried over, as they might no longer match.
+    pub fn map_points(&self, f: impl Fn(Point<3>) -> Point<3>) -> Self {
+        let mut mesh = Self::new();
+        for triangle in &self.triangles {
+            mesh.push_triangle_with_face(
+                triangle.points.map(&f),
+                triangle.color,
+                triangle.face,
+            );
+        }
+
+        mesh
+    }
+
+    /// Set the [`Attribute::Normal`] channel from [`Mesh::normals`]
+    pub fn set_normals(&mut self, normals: Normals) {
+        let normals = self.normals(normals).into_iter().flatten().collect();
+        self.set_channel(Attribute::Normal, Channel::Vectors(normals));
+    }
+
+    /// Set the [`Attribute::Color`] channel from the triangle colors
+    pub fn set_colors(&mut self) {
+        let colors = self
+            .triangles
+            .iter()
+            .flat_map(|triangle| [triangle.color; 3])
+            .collect();
+        self.set_channel(Attribute::Color, Channel::Colors(colors));
+    }
+
+    /// Set the [`Attribute::FaceId`] channel from the triangle faces
+    ///
+    /// Triangles that have not been created from a face get `u64::MAX`.
+    pub fn set_face_ids(&mut self) {
+        let ids = self
+            .triangles
+            .iter()
+            .flat_map(|triangle| {
+                [triangle.face.map(|face| face.0).unwrap_or(u64::MAX); 3]
+            })
+            .collect();
+        self.set_channel(Attribute::FaceId, Channel::Ids(ids));
+    }
+
+    /// Compute the normals of the mesh
+    ///
+    /// Returns one normal per triangle corner, in the same order as
+    /// [`Mesh::triangles`]. See [`Normals`] for the available modes.
+    pub fn normals(&self, normals: Normals) -> Vec<[Vector<3>; 3]> {
+        let face_normals: Vec<_> = self
+            .triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.points;
+                (b - a).cross(&(c - a))
+            })
+            .collect();
+
+        let crease_angle = match normals {
+            Normals::Flat => {
+                return face_normals
+                    .into_iter()
+                    .map(|normal| [normal.normalize(); 3])
+                    .collect();
+            }
+            Normals::Smooth { crease_angle } => crease_angle,
+        };
+        let min_cos = crease_angle.cos();
+
+        let mut triangles_by_point = HashMap::new();
+        for (i, triangle) in self.triangles.iter().enumerate() {
+            for point in triangle.points {
+                triangles_by_point
+                    .entry(point)
+                    .or_insert_with(Vec::new)
+                    .push(i);
+            }
+        }
+
+        self.triangles
+            .iter()
+            .enumerate()
+            .map(|(t, triangle)| {
+                let face_normal = face_normals[t].normalize();
+
+                triangle.points.map(|point| {
+                    // The magnitude of a face normal is proportional to the
+                    // area of the triangle, so summing them up weighs each
+                    // triangle by its area.
+                    let mut normal = Vector::from([0., 0., 0.]);
+                    for &i in &triangles_by_point[&point] {
+                        let neighbor = face_normals[i];
+                        if neighbor.magnitude() == Scalar::ZERO {
+                            continue;
+                        }
+
+                        // The triangle itself is always included, regardless
+                        // of any floating-point inaccuracy.
+                        if i == t
+                            || neighbor.normalize().dot(&face_normal) >= min_cos
+                        {
+                            normal = normal + neighbor;
+                        }
+                    }
+
+                    normal.normalize()
+                })
+            })
+            .collect()
+    }
+}
+
+/// The different ways to compute the normals of a mesh
+///
+/// See [`Mesh::normals`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Normals {
+    /// Each triangle uses its own normal for all of its corners
+    ///
+    /// This makes every triangle visible as a flat facet.
+    Flat,
+
+    /// Normals are averaged over all triangles that share a vertex
+    ///
+    /// Curved surfaces look smooth this way. To keep sharp edges sharp,
+    /// triangles are only averaged, if the angle between their normals is not
+    /// larger than `crease_angle` (in radians).
+    Smooth {
+        /// The maximum angle between triangles that are smoothed together
+        crease_angle: Scalar,
+    },
+}
+
+impl Normals {
+    /// Smooth normals, with a crease angle that keeps the edges of boxes sharp
+    pub fn smooth() -> Self {
+        Self::Smooth {
+            crease_angle: Scalar::PI / 4.,
+        }
     }
 }
 
@@ -87,6 +294,7 @@ impl<V> Default for Mesh<V> {
             indices: Default::default(),
             indices_by_vertex: Default::default(),
             triangles: Default::default(),
+            channels: Default::default(),
         }
     }
 }
@@ -96,7 +304,8 @@ pub type Index = u32;
 
 /// A triangle
 ///
-/// Extension of [`fj_math::Triangle`] that also includes a color.
+/// Extension of [`fj_math::Triangle`] that also includes a color, and the face
+/// that the triangle was created from.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Triangle {
     /// The points of the triangle
@@ -104,7 +313,203 @@ pub struct Triangle {
 
     /// The color of the triangle
     pub color: Color,
+
+    /// The face that the triangle was created from, if known
+    pub face: Option<FaceId>,
+}
+
+/// Identifies the face of a shape that triangles were created from
+///
+/// The IDs are assigned during triangulation, and are only unique within a
+/// single mesh.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct FaceId(pub u64);
+
+/// A vertex attribute that can be stored in a [`Mesh`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Attribute {
+    /// Vertex normals, usually stored as [`Channel::Vectors`]
+    Normal,
+
+    /// Vertex colors, usually stored as [`Channel::Colors`]
+    Color,
+
+    /// Texture coordinates, usually stored as [`Channel::Points`]
+    Uv,
+
+    /// The ID of the face a vertex belongs to, usually [`Channel::Ids`]
+    FaceId,
+
+    /// How far a triangle deviates from the surface it approximates
+    ///
+    /// Usually stored as [`Channel::Scalars`], with the same value for all
+    /// corners of a triangle.
+    SurfaceDeviation,
+
+    /// How far a vertex is from another mesh, that the mesh is compared to
+    ///
+    /// Usually stored as [`Channel::Scalars`], with the same value for all
+    /// corners at the same vertex.
+    Deviation,
+
+    /// Any other attribute, identified by name
+    Custom(String),
+}
+
+/// The values of a vertex attribute
+///
+/// Every channel contains one value per triangle corner, in the order of
+/// [`Mesh::indices`]. This allows the same vertex to have different attribute
+/// values in different triangles, as is necessary for flat normals, or for
+/// face IDs along the edges between faces.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Channel {
+    /// A channel of 3-dimensional vectors
+    Vectors(Vec<Vector<3>>),
+
+    /// A channel of 2-dimensional points
+    Points(Vec<Point<2>>),
+
+    /// A channel of colors
+    Colors(Vec<Color>),
+
+    /// A channel of scalars
+    Scalars(Vec<Scalar>),
+
+    /// A channel of integer IDs
+    Ids(Vec<u64>),
+}
+
+impl Channel {
+    /// The number of values in the channel
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Vectors(values) => values.len(),
+            Self::Points(values) => values.len(),
+            Self::Colors(values) => values.len(),
+            Self::Scalars(values) => values.len(),
+            Self::Ids(values) => values.len(),
+        }
+    }
+
+    /// Indicate whether the channel is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Select the values that belong to the provided triangles
+    ///
+    /// Can be used to carry over a channel to a new mesh, that was created by
+    /// removing triangles from the original mesh.
+    pub fn select_triangles(&self, triangles: &[usize]) -> Self {
+        fn select<T: Copy>(values: &[T], triangles: &[usize]) -> Vec<T> {
+            triangles
+                .iter()
+                .flat_map(|&i| values[i * 3..i * 3 + 3].iter().copied())
+                .collect()
+        }
+
+        match self {
+            Self::Vectors(values) => Self::Vectors(select(values, triangles)),
+            Self::Points(values) => Self::Points(select(values, triangles)),
+            Self::Colors(values) => Self::Colors(select(values, triangles)),
+            Self::Scalars(values) => Self::Scalars(select(values, triangles)),
+            Self::Ids(values) => Self::Ids(select(values, triangles)),
+        }
+    }
+
+    /// Select the values that belong to the provided triangle corners
+    ///
+    /// Corners are indexed like [`Mesh::indices`]. Can be used to carry over a
+    /// channel to a new mesh, whose triangles have been rearranged.
+    pub fn select_corners(&self, corners: &[usize]) -> Self {
+        fn select<T: Copy>(values: &[T], corners: &[usize]) -> Vec<T> {
+            corners.iter().map(|&i| values[i]).collect()
+        }
+
+        match self {
+            Self::Vectors(values) => Self::Vectors(select(values, corners)),
+            Self::Points(values) => Self::Points(select(values, corners)),
+            Self::Colors(values) => Self::Colors(select(values, corners)),
+            Self::Scalars(values) => Self::Scalars(select(values, corners)),
+            Self::Ids(values) => Self::Ids(select(values, corners)),
+        }
+    }
 }
 
 /// RGBA color
 pub type Color = [u8; 4];
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar, Vector};
+
+    use super::{Attribute, Channel, FaceId, Mesh, Normals};
+
+    #[test]
+    fn normals() {
+        let color = [255, 0, 0, 255];
+
+        // Two triangles that meet at a shallow angle, and one that meets them
+        // at a right angle.
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([1., 1., 0.1]);
+        let e = Point::from([0., 0., -1.]);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], color);
+        mesh.push_triangle([b, d, c], color);
+        mesh.push_triangle([a, e, b], color);
+
+        let flat = mesh.normals(Normals::Flat);
+        assert_eq!(flat[0], [Vector::from([0., 0., 1.]); 3]);
+
+        let smooth = mesh.normals(Normals::smooth());
+
+        // The first corner is only shared with the third triangle, which is
+        // beyond the crease angle.
+        assert_eq!(smooth[0][0], flat[0][0]);
+
+        // The corners shared with the second triangle are smoothed, but the
+        // third triangle is beyond the crease angle.
+        assert_ne!(smooth[0][1], flat[0][1]);
+        assert_eq!(smooth[0][1], smooth[1][0]);
+        assert_eq!(smooth[2], flat[2]);
+
+        let sharp = mesh.normals(Normals::Smooth {
+            crease_angle: Scalar::ZERO,
+        });
+        assert_eq!(sharp, flat);
+    }
+
+    #[test]
+    fn channels() {
+        let color = [255, 0, 0, 255];
+        let face = FaceId(1);
+
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle_with_face([a, b, c], color, Some(face));
+        mesh.push_triangle([a, d, b], color);
+
+        assert_eq!(mesh.triangles_of_face(face).count(), 1);
+
+        mesh.set_normals(Normals::Flat);
+        mesh.set_face_ids();
+        assert_eq!(
+            mesh.channel(&Attribute::FaceId),
+            Some(&Channel::Ids(vec![1, 1, 1, u64::MAX, u64::MAX, u64::MAX]))
+        );
+        assert_eq!(mesh.channels().count(), 2);
+
+        // Adding more triangles invalidates the channels.
+        mesh.push_triangle([b, d, c], color);
+        assert_eq!(mesh.channels().count(), 0);
+    }
+}
@@ -0,0 +1,122 @@
+//! Progress reporting and cancellation
+//!
+//! Long-running operations take a [`Progress`], which they use to report how
+//! far along they are, and to check whether they should stop early.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Tracks the progress of an operation, and allows for cancelling it
+///
+/// Cloning a `Progress` yields a handle to the same progress. This allows one
+/// thread to run an operation, while another one displays its progress and
+/// cancels it, if necessary.
+///
+/// Progress is tracked in units of work, which are added by the operation as
+/// it learns about them, and completed as it goes along. The meaning of a unit
+/// of work is up to the operation.
+#[derive(Clone, Debug, Default)]
+pub struct Progress {
+    inner: Arc<Inner>,
+}
+
+impl Progress {
+    /// Construct a new instance of `Progress`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request that the operation is cancelled
+    ///
+    /// The operation will stop at the next opportunity, and return
+    /// [`Cancelled`].
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Indicate whether the operation has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Return an error, if the operation has been cancelled
+    ///
+    /// Meant to be called with `?` by the operation, wherever it can stop.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+
+        Ok(())
+    }
+
+    /// Add units of work that need to be completed
+    pub fn add_work(&self, units: usize) {
+        self.inner.total.fetch_add(units, Ordering::SeqCst);
+    }
+
+    /// Mark units of work as completed
+    pub fn complete_work(&self, units: usize) {
+        self.inner.completed.fetch_add(units, Ordering::SeqCst);
+    }
+
+    /// The fraction of work that has been completed, between `0` and `1`
+    ///
+    /// Returns `None`, if no work has been added yet.
+    pub fn fraction(&self) -> Option<f64> {
+        let total = self.inner.total.load(Ordering::SeqCst);
+        let completed = self.inner.completed.load(Ordering::SeqCst);
+
+        if total == 0 {
+            return None;
+        }
+
+        Some((completed as f64 / total as f64).min(1.))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    completed: AtomicUsize,
+    total: AtomicUsize,
+}
+
+/// Error returned by operations that have been cancelled
+///
+/// See [`Progress::cancel`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Operation was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cancelled, Progress};
+
+    #[test]
+    fn progress() {
+        let progress = Progress::new();
+        assert_eq!(progress.fraction(), None);
+
+        progress.add_work(4);
+        progress.complete_work(1);
+        assert_eq!(progress.fraction(), Some(0.25));
+
+        let handle = progress.clone();
+        assert_eq!(progress.check(), Ok(()));
+        handle.cancel();
+        assert_eq!(progress.check(), Err(Cancelled));
+    }
+}
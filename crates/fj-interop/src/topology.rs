@@ -0,0 +1,23 @@
+//! The kind of object that a shape represents
+
+/// What kind of object a shape is meant to be
+///
+/// Determines which rules apply to the shape, when it is checked or exported.
+/// Most shapes are solids, but a flat sketch, for example, is fine as it is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Topology {
+    /// A closed volume, whose boundary must be watertight
+    Solid,
+
+    /// A surface that doesn't enclose a volume, and may have open edges
+    Sheet,
+
+    /// Curves, without any faces
+    Wire,
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
@@ -0,0 +1,162 @@
+//! Benchmarks for the kernel algorithms
+//!
+//! Run them with `cargo bench -p fj-kernel`. All benchmarks are based on a
+//! synthetic model, a plate with a configurable number of holes, which makes it
+//! easy to see how the algorithms scale with the complexity of the model.
+
+use criterion::{
+    criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion,
+};
+use fj_interop::{debug::DebugInfo, progress::Progress};
+use fj_kernel::{
+    algorithms::{
+        sweep_shape, triangulate, ApproxCache, FaceApprox, Tolerance,
+    },
+    geometry::Surface,
+    shape::Shape,
+    topology::{Face, Vertex},
+};
+use fj_math::{Scalar, Vector};
+
+/// The numbers of holes per side of the plate, that each benchmark is run with
+const HOLES: [usize; 3] = [1, 4, 8];
+
+/// Build a square plate with `n` by `n` square holes
+///
+/// The plate is located in the xy-plane, with a side length of `n` units. Each
+/// hole is placed in the center of its own unit square.
+fn plate_with_holes(n: usize) -> Shape {
+    let mut shape = Shape::new();
+
+    let size = n as f64;
+    let exterior = [
+        [0., 0., 0.],
+        [size, 0., 0.],
+        [size, size, 0.],
+        [0., size, 0.],
+    ];
+
+    let mut face = Face::builder(Surface::xy_plane(), &mut shape)
+        .with_exterior_polygon(exterior);
+    for i in 0..n {
+        for j in 0..n {
+            let (x, y) = (i as f64, j as f64);
+            let (min, max) = (0.25, 0.75);
+            face = face.with_interior_polygon([
+                [x + min, y + min, 0.],
+                [x + max, y + min, 0.],
+                [x + max, y + max, 0.],
+                [x + min, y + max, 0.],
+            ]);
+        }
+    }
+    face.build().expect("Failed to build plate");
+
+    shape
+}
+
+fn tolerance() -> Tolerance {
+    Tolerance::from_scalar(Scalar::from_f64(0.001)).unwrap()
+}
+
+fn construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+    for n in HOLES {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| plate_with_holes(n))
+        });
+    }
+    group.finish();
+}
+
+fn validation(c: &mut Criterion) {
+    // Inserting a vertex validates it against all vertices that are already in
+    // the shape, so this measures the cost of validation on its own.
+    let mut group = c.benchmark_group("validation");
+    for n in HOLES {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter_batched(
+                || plate_with_holes(n),
+                |mut shape| {
+                    Vertex::builder(&mut shape)
+                        .build_from_point([0.5, 0.5, 1.])
+                        .unwrap()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn approximation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("approximation");
+    for n in HOLES {
+        let shape = plate_with_holes(n);
+        let faces: Vec<_> =
+            shape.faces().map(|face| face.get().clone()).collect();
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter(|| {
+                let mut cache = ApproxCache::new();
+                for face in &faces {
+                    FaceApprox::new(face, tolerance(), &mut cache);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn triangulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("triangulation");
+    for n in HOLES {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter_batched(
+                || plate_with_holes(n),
+                |shape| {
+                    triangulate(
+                        shape,
+                        tolerance(),
+                        &mut DebugInfo::new(),
+                        &Progress::new(),
+                    )
+                    .unwrap()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn sweep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sweep");
+    for n in HOLES {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter_batched(
+                || plate_with_holes(n),
+                |shape| {
+                    sweep_shape(
+                        shape,
+                        Vector::from([0., 0., 1.]),
+                        tolerance(),
+                        [255, 0, 0, 255],
+                    )
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    construction,
+    validation,
+    approximation,
+    triangulation,
+    sweep
+);
+criterion_main!(benches);
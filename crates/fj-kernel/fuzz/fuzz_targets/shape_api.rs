@@ -0,0 +1,129 @@
+//! Fuzz target for sequences of calls to the `Shape` API
+//!
+//! Run with `cargo fuzz run shape_api`, from the `fj-kernel` directory.
+//!
+//! Invalid input must be reported as an error. Any panic is a bug.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use fj_interop::{debug::DebugInfo, progress::Progress};
+use fj_kernel::{
+    algorithms::{sweep_shape, triangulate, Tolerance},
+    geometry::Surface,
+    shape::Shape,
+    topology::{Cycle, Edge, Face, Vertex},
+};
+use fj_math::{Point, Scalar, Transform, Vector};
+use libfuzzer_sys::fuzz_target;
+
+/// A single call to the `Shape` API
+#[derive(Arbitrary, Debug)]
+enum Call {
+    InsertPoint(Coords),
+    BuildVertex(Coords),
+    BuildLineSegment([Coords; 2]),
+    BuildCircle(u16),
+    BuildPolygon(Vec<Coords>),
+    BuildFace {
+        exterior: Vec<Coords>,
+        interiors: Vec<Vec<Coords>>,
+    },
+    Transform {
+        translation: Coords,
+        axis_angle: Coords,
+    },
+    Sweep(Coords),
+    Triangulate,
+}
+
+/// The coordinates of a point or vector
+///
+/// Integers are used, and converted into floating-point numbers on a fixed
+/// grid. This keeps non-finite numbers out, which `Scalar` rejects by design.
+#[derive(Arbitrary, Clone, Copy, Debug)]
+struct Coords([i16; 3]);
+
+impl Coords {
+    fn point(self) -> Point<3> {
+        Point::from(self.components())
+    }
+
+    fn vector(self) -> Vector<3> {
+        Vector::from(self.components())
+    }
+
+    fn components(self) -> [f64; 3] {
+        self.0.map(|c| f64::from(c) / 16.)
+    }
+}
+
+fuzz_target!(|calls: Vec<Call>| {
+    let tolerance = Tolerance::from_scalar(Scalar::from_f64(0.1)).unwrap();
+    let mut shape = Shape::new();
+
+    for call in calls {
+        // Errors are fine. We're only looking for panics.
+        match call {
+            Call::InsertPoint(point) => {
+                let _ = shape.insert(point.point());
+            }
+            Call::BuildVertex(point) => {
+                let _ =
+                    Vertex::builder(&mut shape).build_from_point(point.point());
+            }
+            Call::BuildLineSegment(points) => {
+                let _ = Edge::builder(&mut shape)
+                    .build_line_segment_from_points(points.map(Coords::point));
+            }
+            Call::BuildCircle(radius) => {
+                let radius = Scalar::from_f64(f64::from(radius) / 16.);
+                let _ = Edge::builder(&mut shape).build_circle(radius);
+            }
+            Call::BuildPolygon(points) => {
+                let _ = Cycle::builder(&mut shape)
+                    .build_polygon(points.into_iter().map(Coords::point));
+            }
+            Call::BuildFace {
+                exterior,
+                interiors,
+            } => {
+                let mut face = Face::builder(Surface::xy_plane(), &mut shape)
+                    .with_exterior_polygon(
+                        exterior.into_iter().map(Coords::point),
+                    );
+                for interior in interiors {
+                    face = face.with_interior_polygon(
+                        interior.into_iter().map(Coords::point),
+                    );
+                }
+                let _ = face.build();
+            }
+            Call::Transform {
+                translation,
+                axis_angle,
+            } => {
+                shape.transform(
+                    &(Transform::translation(translation.vector())
+                        * Transform::rotation(axis_angle.vector())),
+                );
+            }
+            Call::Sweep(path) => {
+                shape = sweep_shape(
+                    shape,
+                    path.vector(),
+                    tolerance,
+                    [255, 0, 0, 255],
+                );
+            }
+            Call::Triangulate => {
+                let _ = triangulate(
+                    shape.clone(),
+                    tolerance,
+                    &mut DebugInfo::new(),
+                    &Progress::new(),
+                );
+            }
+        }
+    }
+});
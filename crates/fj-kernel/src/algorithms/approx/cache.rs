@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use fj_math::Point;
+
+use crate::{geometry::Curve, shape::Handle, topology::Edge};
+
+use super::{
+    curves::{approx_arc, approx_curve},
+    edges::approximate_edge,
+    Tolerance,
+};
+
+/// A cache for edge approximations
+///
+/// Edges can be shared between multiple faces (for example, the edges on the
+/// side of a swept shape). If each of those faces computed its own
+/// approximation of such an edge, there's no guarantee that those
+/// approximations would be identical, which would lead to cracks in the
+/// resulting mesh.
+///
+/// Sharing an `ApproxCache` between the approximation of all faces of a shape
+/// makes sure that each edge is approximated only once, and that all faces that
+/// refer to it use the exact same points.
+#[derive(Debug, Default)]
+pub struct ApproxCache {
+    edges: HashMap<(Handle<Edge>, Tolerance), Vec<Point<3>>>,
+}
+
+impl ApproxCache {
+    /// Construct an empty instance of `ApproxCache`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute the approximation of an edge, or access the cached one
+    pub fn edge(
+        &mut self,
+        edge: &Handle<Edge>,
+        tolerance: Tolerance,
+    ) -> Vec<Point<3>> {
+        self.edges
+            .entry((edge.clone(), tolerance))
+            .or_insert_with(|| {
+                let edge = edge.get();
+
+                let mut points = Vec::new();
+                match (edge.curve(), edge.vertices()) {
+                    // A circle that is bounded by vertices is an arc.
+                    (Curve::Circle(circle), Some([a, b])) => approx_arc(
+                        &circle,
+                        [a.point(), b.point()],
+                        tolerance,
+                        &mut points,
+                    ),
+                    (curve, _) => approx_curve(&curve, tolerance, &mut points),
+                }
+
+                approximate_edge(points, edge.vertices())
+            })
+            .clone()
+    }
+
+    /// Return the number of edge approximations in the cache
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Indicate whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{shape::Shape, topology::Edge};
+
+    use super::{ApproxCache, Tolerance};
+
+    #[test]
+    fn edge() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let a = Tolerance::from_scalar(Scalar::ONE).unwrap();
+        let b = Tolerance::from_scalar(Scalar::from_f64(0.1)).unwrap();
+
+        let circle = Edge::builder(&mut shape).build_circle(Scalar::ONE)?;
+        let line = Edge::builder(&mut shape)
+            .build_line_segment_from_points([[0., 0., 0.], [1., 0., 0.]])?;
+
+        let mut cache = ApproxCache::new();
+        assert!(cache.is_empty());
+
+        let circle_a = cache.edge(&circle, a);
+        assert_eq!(cache.edge(&circle, a), circle_a);
+        assert_eq!(cache.len(), 1);
+
+        // Different tolerance or different edge must result in a separate
+        // cache entry.
+        let circle_b = cache.edge(&circle, b);
+        assert!(circle_b.len() > circle_a.len());
+        cache.edge(&line, a);
+        assert_eq!(cache.len(), 3);
+
+        Ok(())
+    }
+}
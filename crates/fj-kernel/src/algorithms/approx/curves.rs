@@ -1,4 +1,4 @@
-use std::cmp::max;
+use std::{cmp::max, f64::consts::PI};
 
 use fj_math::{Point, Scalar};
 
@@ -20,6 +20,7 @@ use super::Tolerance;
 ///
 /// The `approximate_between` methods of the curves then need to make sure to
 /// only return points in between those vertices, not the vertices themselves.
+/// [`approx_arc`] already does that for circles.
 pub fn approx_curve(
     curve: &Curve,
     tolerance: Tolerance,
@@ -42,13 +43,10 @@ pub fn approx_circle(
 ) {
     let radius = circle.a.magnitude();
 
-    // To approximate the circle, we use a regular polygon for which
-    // the circle is the circumscribed circle. The `tolerance`
-    // parameter is the maximum allowed distance between the polygon
-    // and the circle. This is the same as the difference between
-    // the circumscribed circle and the incircle.
-
-    let n = number_of_vertices_for_circle(tolerance, radius);
+    // To approximate the circle, we use a regular polygon for which the circle
+    // is the circumscribed circle. Anything with fewer than 3 vertices isn't a
+    // polygon, even if the tolerance would allow it.
+    let n = max(number_of_segments(radius, Scalar::PI * 2., tolerance), 3);
 
     for i in 0..n {
         let angle = Scalar::PI * 2. / n as f64 * i as f64;
@@ -57,47 +55,197 @@ pub fn approx_circle(
     }
 }
 
-fn number_of_vertices_for_circle(tolerance: Tolerance, radius: Scalar) -> u64 {
-    let n = (Scalar::PI / (Scalar::ONE - (tolerance.inner() / radius)).acos())
-        .ceil()
-        .into_u64();
+/// Approximate the arc of a circle between two points
+///
+/// The arc runs from `start` to `end`, in the direction of the circle, from
+/// `a` towards `b`. Like [`approx_curve`], this only returns the points in
+/// between, not `start` and `end` themselves.
+pub fn approx_arc(
+    circle: &Circle,
+    [start, end]: [Point<3>; 2],
+    tolerance: Tolerance,
+    out: &mut Vec<Point<3>>,
+) {
+    let radius = circle.a.magnitude();
+
+    let angle_of = |point: Point<3>| {
+        let v = point - circle.center;
+        let angle = Scalar::atan2(v.dot(&circle.b), v.dot(&circle.a));
+        if angle >= Scalar::ZERO {
+            angle
+        } else {
+            angle + Scalar::PI * 2.
+        }
+    };
+
+    let start = angle_of(start);
+    let mut end = angle_of(end);
+    if end <= start {
+        end += Scalar::PI * 2.;
+    }
 
-    max(n, 3)
+    let angle = end - start;
+    let n = number_of_segments(radius, angle, tolerance);
+
+    for i in 1..n {
+        let angle = start + angle / n as f64 * i as f64;
+        let point = circle.point_curve_to_model(&Point::from([angle]));
+        out.push(point);
+    }
+}
+
+/// Compute the number of segments that approximate an arc
+///
+/// The arc has the given `radius`, and spans `angle` radians. It's approximated
+/// by chords of equal length. Each chord deviates most from the arc at its
+/// center, by its sagitta. This returns the smallest number of chords, whose
+/// sagitta is within `tolerance`, and that each span no more than its maximum
+/// angle, if it has one.
+///
+/// All approximations of circles and arcs use this, so edges that are shared
+/// between faces, and the faces of swept or sliced shapes, always agree on
+/// how they are subdivided.
+pub fn number_of_segments(
+    radius: Scalar,
+    angle: Scalar,
+    tolerance: Tolerance,
+) -> u64 {
+    let radius = radius.into_f64().abs();
+    let angle = angle.into_f64().abs();
+    let max_angle_of_tolerance = tolerance.max_angle().map(Scalar::into_f64);
+    let tolerance = tolerance.inner().into_f64();
+
+    let sagitta = |n: u64| radius * (1. - (angle / 2. / n as f64).cos());
+
+    // A chord that spans more than half a circle is further from the arc than
+    // the radius, so no tolerance allows that.
+    let max_angle = if tolerance < radius {
+        2. * (1. - tolerance / radius).acos()
+    } else {
+        PI
+    };
+    let mut n = max((angle / max_angle).ceil() as u64, 1);
+
+    // Rounding errors can put the result off by one, close to a whole number
+    // of segments. Checking the sagitta directly settles that.
+    if n > 1 && sagitta(n - 1) <= tolerance {
+        n -= 1;
+    }
+    if sagitta(n) > tolerance {
+        n += 1;
+    }
+
+    // The tolerance can also limit the angle of each segment, which matters
+    // for curves that are small, compared to the deviation.
+    if let Some(max_angle) = max_angle_of_tolerance {
+        let mut m = max((angle / max_angle).ceil() as u64, 1);
+        if m > 1 && angle / (m - 1) as f64 <= max_angle {
+            m -= 1;
+        }
+        n = max(n, m);
+    }
+
+    n
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_math::Scalar;
+    use std::f64::consts::PI;
 
-    use crate::algorithms::Tolerance;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{algorithms::Tolerance, geometry::Circle};
+
+    use super::{approx_arc, number_of_segments};
 
     #[test]
-    fn number_of_vertices_for_circle() {
-        verify_result(50., 100., 3);
-        verify_result(10., 100., 7);
-        verify_result(1., 100., 23);
-
-        fn verify_result(
-            tolerance: impl Into<Tolerance>,
-            radius: impl Into<Scalar>,
-            n: u64,
-        ) {
-            let tolerance = tolerance.into();
-            let radius = radius.into();
-
-            assert_eq!(
-                n,
-                super::number_of_vertices_for_circle(tolerance, radius)
-            );
+    fn number_of_segments_for_circle() {
+        verify_result(50., 100., 2. * PI, 3);
+        verify_result(10., 100., 2. * PI, 7);
+        verify_result(1., 100., 2. * PI, 23);
+        verify_result(300., 100., 2. * PI, 1);
+    }
+
+    #[test]
+    fn number_of_segments_for_arc() {
+        verify_result(1., 100., PI / 2., 6);
+        verify_result(10., 100., PI, 4);
+
+        // A quarter of a square's circumcircle, with the tolerance exactly at
+        // the sagitta of one of its sides.
+        let sagitta = 100. * (1. - (PI / 4.).cos());
+        verify_result(sagitta, 100., PI / 2., 1);
+    }
+
+    #[test]
+    fn number_of_segments_with_max_angle() {
+        let tolerance = Tolerance::from_scalar(300.)
+            .and_then(|tolerance| tolerance.with_max_angle(PI / 8.))
+            .unwrap();
+        let radius = Scalar::from_f64(100.);
+
+        // The angle limits the number of segments, as the deviation doesn't.
+        let n = number_of_segments(radius, Scalar::PI * 2., tolerance);
+        assert_eq!(n, 16);
+        let n = number_of_segments(radius, Scalar::PI / 2., tolerance);
+        assert_eq!(n, 4);
+
+        // The deviation still applies, if it needs more segments.
+        let tolerance = Tolerance::from_scalar(1.)
+            .and_then(|tolerance| tolerance.with_max_angle(PI / 8.))
+            .unwrap();
+        let n = number_of_segments(radius, Scalar::PI * 2., tolerance);
+        assert_eq!(n, 23);
+    }
 
-            assert!(calculate_error(radius, n) <= tolerance.inner());
-            if n > 3 {
-                assert!(calculate_error(radius, n - 1) >= tolerance.inner());
-            }
+    #[test]
+    fn approx_arc_direction() {
+        // A circle that runs clockwise, as seen from above.
+        let circle = Circle {
+            center: Point::origin(),
+            a: Vector::from([100., 0., 0.]),
+            b: Vector::from([0., -100., 0.]),
+        };
+
+        let mut points = Vec::new();
+        approx_arc(
+            &circle,
+            [Point::from([100., 0., 0.]), Point::from([0., -100., 0.])],
+            Tolerance::from_scalar(1.).unwrap(),
+            &mut points,
+        );
+
+        // That's a quarter of the circle, which needs 6 segments.
+        assert_eq!(points.len(), 5);
+        for point in points {
+            let distance = (point - circle.center).magnitude();
+            assert!(
+                (distance - Scalar::from_f64(100.)).abs()
+                    < Scalar::from_f64(1e-9)
+            );
+            assert!(point.x > Scalar::ZERO && point.y < Scalar::ZERO);
         }
+    }
 
-        fn calculate_error(radius: Scalar, n: u64) -> Scalar {
-            radius - radius * (Scalar::PI / Scalar::from_u64(n)).cos()
+    fn verify_result(
+        tolerance: impl Into<Tolerance>,
+        radius: impl Into<Scalar>,
+        angle: f64,
+        n: u64,
+    ) {
+        let tolerance = tolerance.into();
+        let radius = radius.into();
+        let angle = Scalar::from_f64(angle);
+
+        assert_eq!(n, number_of_segments(radius, angle, tolerance));
+
+        assert!(calculate_error(radius, angle, n) <= tolerance.inner());
+        if n > 1 {
+            assert!(calculate_error(radius, angle, n - 1) > tolerance.inner());
         }
     }
+
+    fn calculate_error(radius: Scalar, angle: Scalar, n: u64) -> Scalar {
+        radius - radius * (angle / 2. / Scalar::from_u64(n)).cos()
+    }
 }
@@ -2,10 +2,10 @@ use fj_math::{Point, Segment};
 
 use crate::topology::Cycle;
 
-use super::{curves::approx_curve, edges::approximate_edge, Tolerance};
+use super::{ApproxCache, Tolerance};
 
 /// An approximation of a [`Cycle`]
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct CycleApprox {
     /// The points that approximate the cycle
     pub points: Vec<Point<3>>,
@@ -16,14 +16,18 @@ impl CycleApprox {
     ///
     /// `tolerance` defines how far the approximation is allowed to deviate from
     /// the actual face.
-    pub fn new(cycle: &Cycle, tolerance: Tolerance) -> Self {
+    ///
+    /// The edge approximations are taken from `cache`, if available. Please
+    /// refer to [`ApproxCache`] for more information.
+    pub fn new(
+        cycle: &Cycle,
+        tolerance: Tolerance,
+        cache: &mut ApproxCache,
+    ) -> Self {
         let mut points = Vec::new();
 
-        for edge in cycle.edges() {
-            let mut edge_points = Vec::new();
-            approx_curve(&edge.curve(), tolerance, &mut edge_points);
-
-            points.extend(approximate_edge(edge_points, edge.vertices()));
+        for edge in &cycle.edges {
+            points.extend(cache.edge(edge, tolerance));
         }
 
         points.dedup();
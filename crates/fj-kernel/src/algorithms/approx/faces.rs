@@ -1,10 +1,10 @@
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 
 use fj_math::Point;
 
 use crate::topology::Face;
 
-use super::{CycleApprox, Tolerance};
+use super::{ApproxCache, CycleApprox, Tolerance};
 
 /// An approximation of a [`Face`]
 #[derive(Debug, PartialEq)]
@@ -13,13 +13,16 @@ pub struct FaceApprox {
     ///
     /// These could be actual vertices from the model, points that approximate
     /// an edge, or points that approximate a face.
-    pub points: HashSet<Point<3>>,
+    ///
+    /// This is an ordered set, so the order in which the points reach the
+    /// triangulation doesn't vary between runs.
+    pub points: BTreeSet<Point<3>>,
 
     /// Approximation of the exterior cycle
     pub exterior: CycleApprox,
 
     /// Approximations of the interior cycles
-    pub interiors: HashSet<CycleApprox>,
+    pub interiors: BTreeSet<CycleApprox>,
 }
 
 impl FaceApprox {
@@ -27,7 +30,14 @@ impl FaceApprox {
     ///
     /// `tolerance` defines how far the approximation is allowed to deviate from
     /// the actual face.
-    pub fn new(face: &Face, tolerance: Tolerance) -> Self {
+    ///
+    /// Edge approximations are shared with other faces through `cache`. Please
+    /// refer to [`ApproxCache`] for more information.
+    pub fn new(
+        face: &Face,
+        tolerance: Tolerance,
+        cache: &mut ApproxCache,
+    ) -> Self {
         // Curved faces whose curvature is not fully defined by their edges
         // are not supported yet. For that reason, we can fully ignore `face`'s
         // `surface` field and just pass the edges to `Self::for_edges`.
@@ -41,18 +51,18 @@ impl FaceApprox {
         // would need to provide its own approximation, as the edges that bound
         // it have nothing to do with its curvature.
 
-        let mut points = HashSet::new();
+        let mut points = BTreeSet::new();
         let mut exteriors = Vec::new();
-        let mut interiors = HashSet::new();
+        let mut interiors = BTreeSet::new();
 
         for cycle in face.exteriors() {
-            let cycle = CycleApprox::new(&cycle, tolerance);
+            let cycle = CycleApprox::new(&cycle, tolerance, cache);
 
             points.extend(cycle.points.iter().copied());
             exteriors.push(cycle);
         }
         for cycle in face.interiors() {
-            let cycle = CycleApprox::new(&cycle, tolerance);
+            let cycle = CycleApprox::new(&cycle, tolerance, cache);
 
             points.extend(cycle.points.iter().copied());
             interiors.insert(cycle);
@@ -80,12 +90,13 @@ impl FaceApprox {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
     use fj_math::{Point, Scalar};
-    use map_macro::set;
 
     use crate::{geometry::Surface, shape::Shape, topology::Face};
 
-    use super::{CycleApprox, FaceApprox, Tolerance};
+    use super::{ApproxCache, CycleApprox, FaceApprox, Tolerance};
 
     #[test]
     fn for_face_closed() -> anyhow::Result<()> {
@@ -111,15 +122,15 @@ mod tests {
             .build()?;
 
         assert_eq!(
-            FaceApprox::new(&face.get(), tolerance),
+            FaceApprox::new(&face.get(), tolerance, &mut ApproxCache::new()),
             FaceApprox {
-                points: set![a, b, c, d, e, f, g, h],
+                points: BTreeSet::from([a, b, c, d, e, f, g, h]),
                 exterior: CycleApprox {
                     points: vec![a, b, c, d, a],
                 },
-                interiors: set![CycleApprox {
+                interiors: BTreeSet::from([CycleApprox {
                     points: vec![e, f, g, h, e],
-                }],
+                }]),
             }
         );
 
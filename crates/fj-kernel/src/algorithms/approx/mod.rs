@@ -1,7 +1,11 @@
+mod cache;
 mod curves;
 mod cycles;
 mod edges;
 mod faces;
 mod tolerance;
 
-pub use self::{cycles::CycleApprox, faces::FaceApprox, tolerance::Tolerance};
+pub use self::{
+    cache::ApproxCache, curves::number_of_segments, cycles::CycleApprox,
+    faces::FaceApprox, tolerance::Tolerance,
+};
@@ -1,3 +1,5 @@
+use std::{f64::consts::PI, str::FromStr};
+
 use fj_math::Scalar;
 
 /// A tolerance value
@@ -5,7 +7,13 @@ use fj_math::Scalar;
 /// A tolerance value is used during approximation. It defines the maximum
 /// allowed deviation of the approximation from the actual shape.
 ///
-/// The `Tolerance` type enforces that the tolerance value is always larger than
+/// The deviation is made up of an absolute part, in model units, and a part
+/// that is relative to the size of the model. The relative part needs to be
+/// resolved by [`Tolerance::for_size`], before the tolerance can be used.
+/// Approximations of curves can additionally be limited to a maximum angle per
+/// segment, so small features don't end up with just a few segments.
+///
+/// The `Tolerance` type enforces that the deviation is always larger than
 /// zero, which is an attribute that the approximation code relies on.
 ///
 /// # Failing [`From`]/[`Into`] implementation
@@ -18,27 +26,144 @@ use fj_math::Scalar;
 /// documentation doesn't provide any actual reasoning for this requirement, I'm
 /// feeling free to just ignore it.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
-pub struct Tolerance(Scalar);
+pub struct Tolerance {
+    absolute: Scalar,
+    relative: Scalar,
+    max_angle: Option<Scalar>,
+}
 
 impl Tolerance {
     /// Construct a `Tolerance` from a [`Scalar`]
     ///
-    /// Returns an error, if the passed scalar is not larger than zero.
+    /// The scalar is the absolute part of the tolerance. Returns an error, if
+    /// it is not larger than zero.
     pub fn from_scalar(
         scalar: impl Into<Scalar>,
     ) -> Result<Self, InvalidTolerance> {
-        let scalar = scalar.into();
+        Self::new(scalar, Scalar::ZERO)
+    }
+
+    /// Construct a `Tolerance` that is relative to the size of the model
+    ///
+    /// `fraction` is the part of the size, so `0.001` is 0.1% of it. Returns an
+    /// error, if it is not larger than zero, or not smaller than one.
+    pub fn relative(
+        fraction: impl Into<Scalar>,
+    ) -> Result<Self, InvalidTolerance> {
+        Self::new(Scalar::ZERO, fraction)
+    }
+
+    /// Construct a `Tolerance` from an absolute and a relative part
+    ///
+    /// The deviation is the sum of both parts. Either of them can be zero, but
+    /// not both. Please refer to [`Tolerance::from_scalar`] and
+    /// [`Tolerance::relative`] for the valid values of each.
+    pub fn new(
+        absolute: impl Into<Scalar>,
+        relative: impl Into<Scalar>,
+    ) -> Result<Self, InvalidTolerance> {
+        let absolute = absolute.into();
+        let relative = relative.into();
 
-        if scalar <= Scalar::ZERO {
-            return Err(InvalidTolerance(scalar));
+        if absolute < Scalar::ZERO || !absolute.into_f64().is_finite() {
+            return Err(InvalidTolerance::Absolute(absolute));
+        }
+        if relative < Scalar::ZERO || relative >= Scalar::ONE {
+            return Err(InvalidTolerance::Relative(relative));
+        }
+        if absolute == Scalar::ZERO && relative == Scalar::ZERO {
+            return Err(InvalidTolerance::Absolute(absolute));
         }
 
-        Ok(Self(scalar))
+        Ok(Self {
+            absolute,
+            relative,
+            max_angle: None,
+        })
+    }
+
+    /// Limit the angle that each segment of an approximated curve may span
+    ///
+    /// `angle` is in radians. Returns an error, if it is not larger than zero,
+    /// or larger than half a circle.
+    pub fn with_max_angle(
+        self,
+        angle: impl Into<Scalar>,
+    ) -> Result<Self, InvalidTolerance> {
+        let angle = angle.into();
+
+        if angle <= Scalar::ZERO || angle > Scalar::PI {
+            return Err(InvalidTolerance::Angle(angle));
+        }
+
+        Ok(Self {
+            max_angle: Some(angle),
+            ..self
+        })
+    }
+
+    /// Resolve the relative part of the tolerance
+    ///
+    /// Returns a tolerance with only an absolute part, which is the sum of the
+    /// absolute part, and the relative part of `size`. The maximum angle is
+    /// kept.
+    pub fn for_size(self, size: Scalar) -> Self {
+        let absolute = self.absolute + self.relative * size.abs();
+
+        // A model without any size would lead to a deviation of zero. Nothing
+        // about it can be approximated, so any value will do.
+        let absolute = if absolute > Scalar::ZERO {
+            absolute
+        } else {
+            self.relative.max(self.absolute)
+        };
+
+        Self {
+            absolute,
+            relative: Scalar::ZERO,
+            max_angle: self.max_angle,
+        }
     }
 
     /// Return the [`Scalar`] that defines the tolerance
+    ///
+    /// That's the maximum deviation, in model units.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the tolerance has a relative part that hasn't been resolved
+    /// by [`Tolerance::for_size`].
     pub fn inner(&self) -> Scalar {
-        self.0
+        assert!(
+            self.relative == Scalar::ZERO,
+            "Relative tolerance must be resolved, before it can be used"
+        );
+
+        self.absolute
+    }
+
+    /// Return the absolute part of the tolerance
+    pub fn absolute(&self) -> Scalar {
+        self.absolute
+    }
+
+    /// Return the relative part of the tolerance
+    pub fn relative_part(&self) -> Scalar {
+        self.relative
+    }
+
+    /// Return the maximum angle per segment, if there is one
+    pub fn max_angle(&self) -> Option<Scalar> {
+        self.max_angle
+    }
+}
+
+impl Default for Tolerance {
+    /// 0.1% of the size of the model, with at least 16 segments per circle
+    fn default() -> Self {
+        Self::relative(0.001)
+            .and_then(|tolerance| tolerance.with_max_angle(PI / 8.))
+            .unwrap()
     }
 }
 
@@ -51,6 +176,127 @@ where
     }
 }
 
+/// Parses tolerances like `0.01`, `0.1%`, `0.01+0.1%`, or `0.1%,15deg`
+///
+/// The part before the comma is the deviation. Numbers with a `%` are relative
+/// to the size of the model. The optional part after the comma is the maximum
+/// angle, in degrees.
+impl FromStr for Tolerance {
+    type Err = ParseToleranceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (deviation, angle) = match s.split_once(',') {
+            Some((deviation, angle)) => (deviation, Some(angle)),
+            None => (s, None),
+        };
+
+        let number = |s: &str| {
+            f64::from_str(s.trim())
+                .map(Scalar::from_f64)
+                .map_err(|_| ParseToleranceError::Syntax(s.trim().to_owned()))
+        };
+
+        let mut absolute = Scalar::ZERO;
+        let mut relative = Scalar::ZERO;
+        for part in deviation.split('+') {
+            match part.trim().strip_suffix('%') {
+                Some(percent) => relative += number(percent)? / 100.,
+                None => absolute += number(part)?,
+            }
+        }
+
+        let tolerance = Self::new(absolute, relative)?;
+        let tolerance = match angle {
+            Some(angle) => {
+                let degrees =
+                    angle.trim().strip_suffix("deg").ok_or_else(|| {
+                        ParseToleranceError::Syntax(angle.trim().to_owned())
+                    })?;
+                let radians = number(degrees)? * Scalar::PI / 180.;
+                tolerance.with_max_angle(radians)?
+            }
+            None => tolerance,
+        };
+
+        Ok(tolerance)
+    }
+}
+
+/// Returned by the constructors of [`Tolerance`], if a value doesn't make sense
 #[derive(Debug, thiserror::Error)]
-#[error("Invalid tolerance ({0}); must be above zero")]
-pub struct InvalidTolerance(Scalar);
+pub enum InvalidTolerance {
+    /// The absolute part is negative or not finite, or both parts are zero
+    #[error("Invalid tolerance ({0}); must be above zero")]
+    Absolute(Scalar),
+
+    /// The relative part is not between zero and one
+    #[error("Invalid relative tolerance ({0}); must be between 0% and 100%")]
+    Relative(Scalar),
+
+    /// The maximum angle is not between zero and half a circle
+    #[error("Invalid angular tolerance ({0} rad); must be between 0 and 180°")]
+    Angle(Scalar),
+}
+
+/// Returned by the [`FromStr`] implementation of [`Tolerance`]
+#[derive(Debug, thiserror::Error)]
+pub enum ParseToleranceError {
+    /// Part of the input couldn't be parsed
+    #[error("Can't parse tolerance: `{0}`")]
+    Syntax(String),
+
+    /// The input was parsed, but doesn't describe a valid tolerance
+    #[error(transparent)]
+    Invalid(#[from] InvalidTolerance),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use approx::assert_abs_diff_eq;
+    use fj_math::Scalar;
+
+    use super::Tolerance;
+
+    #[test]
+    fn nonsensical_values() {
+        assert!(Tolerance::from_scalar(0.).is_err());
+        assert!(Tolerance::from_scalar(-1.).is_err());
+        assert!(Tolerance::from_scalar(f64::INFINITY).is_err());
+        assert!(Tolerance::relative(1.).is_err());
+        assert!(Tolerance::new(0.01, -0.1).is_err());
+        assert!(Tolerance::from(0.01).with_max_angle(0.).is_err());
+        assert!(Tolerance::from(0.01).with_max_angle(4.).is_err());
+    }
+
+    #[test]
+    fn resolve_relative_part() {
+        let tolerance = Tolerance::new(0.5, 0.25).unwrap();
+        let resolved = tolerance.for_size(Scalar::from_f64(2.));
+
+        assert_eq!(resolved.inner(), Scalar::ONE);
+        assert_eq!(resolved.relative_part(), Scalar::ZERO);
+
+        // Without a size, there's nothing to be relative to.
+        let tolerance = Tolerance::relative(0.001).unwrap();
+        assert!(tolerance.for_size(Scalar::ZERO).inner() > Scalar::ZERO);
+    }
+
+    #[test]
+    fn parse_tolerance() {
+        let tolerance: Tolerance = "0.5+25%,22.5deg".parse().unwrap();
+
+        assert_eq!(tolerance.absolute(), Scalar::from_f64(0.5));
+        assert_eq!(tolerance.relative_part(), Scalar::from_f64(0.25));
+        assert_abs_diff_eq!(
+            tolerance.max_angle().unwrap(),
+            Scalar::from_f64(PI / 8.)
+        );
+
+        assert_eq!("0.5".parse::<Tolerance>().unwrap(), Tolerance::from(0.5));
+        assert!("0.1%,15".parse::<Tolerance>().is_err());
+        assert!("abc".parse::<Tolerance>().is_err());
+        assert!("0%".parse::<Tolerance>().is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use fj_math::{Aabb, Point, Scalar};
+
+use super::voxel::closest_point;
+
+/// A bounding volume hierarchy over the triangles of a mesh
+///
+/// Answers closest-point queries without testing every triangle. The
+/// hierarchy is a binary tree of bounding boxes, built by splitting the
+/// triangles at the median of their centers, along the longest axis of the
+/// box that contains them.
+pub(super) struct Bvh {
+    triangles: Vec<[Point<3>; 3]>,
+    nodes: Vec<Node>,
+}
+
+impl Bvh {
+    /// Build a hierarchy over the given triangles
+    ///
+    /// The triangles must not be degenerate.
+    pub fn new(triangles: impl IntoIterator<Item = [Point<3>; 3]>) -> Self {
+        let mut bvh = Self {
+            triangles: triangles.into_iter().collect(),
+            nodes: Vec::new(),
+        };
+
+        if !bvh.triangles.is_empty() {
+            bvh.build(0, bvh.triangles.len());
+        }
+
+        bvh
+    }
+
+    /// Indicate whether the hierarchy contains no triangles
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Find the point of any triangle that is closest to `point`
+    ///
+    /// Returns `None`, if the hierarchy contains no triangles.
+    pub fn closest_point(&self, point: Point<3>) -> Option<Point<3>> {
+        let mut closest = None;
+        let mut closest_distance = Scalar::MAX;
+
+        // The root is the first node.
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if distance_to_aabb(point, &node.aabb) >= closest_distance {
+                continue;
+            }
+
+            match node.kind {
+                Kind::Leaf { start, end } => {
+                    for &triangle in &self.triangles[start..end] {
+                        let candidate = closest_point(point, triangle);
+                        let distance = (point - candidate).magnitude();
+
+                        if distance < closest_distance {
+                            closest = Some(candidate);
+                            closest_distance = distance;
+                        }
+                    }
+                }
+                Kind::Branch { left, right } => {
+                    // Visit the nearer child first, as that's more likely to
+                    // allow skipping the other one.
+                    let left_distance =
+                        distance_to_aabb(point, &self.nodes[left].aabb);
+                    let right_distance =
+                        distance_to_aabb(point, &self.nodes[right].aabb);
+
+                    if left_distance < right_distance {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Build the node for the triangles in `start..end`, returning its index
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let triangles = &mut self.triangles[start..end];
+        let aabb = Aabb::<3>::from_points(triangles.iter().flatten().copied());
+
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            aabb,
+            kind: Kind::Leaf { start, end },
+        });
+
+        if triangles.len() <= LEAF_SIZE {
+            return index;
+        }
+
+        let size = aabb.size();
+        let axis = (0..3)
+            .max_by_key(|&axis| size.components[axis])
+            // Can't panic. The range is not empty.
+            .unwrap();
+
+        let middle = triangles.len() / 2;
+        triangles.select_nth_unstable_by_key(middle, |&[a, b, c]| {
+            a.coords.components[axis]
+                + b.coords.components[axis]
+                + c.coords.components[axis]
+        });
+
+        let left = self.build(start, start + middle);
+        let right = self.build(start + middle, end);
+        self.nodes[index].kind = Kind::Branch { left, right };
+
+        index
+    }
+}
+
+/// The maximum number of triangles in a leaf
+const LEAF_SIZE: usize = 4;
+
+struct Node {
+    aabb: Aabb<3>,
+    kind: Kind,
+}
+
+enum Kind {
+    /// A node that refers to a range of triangles
+    Leaf { start: usize, end: usize },
+
+    /// A node that refers to two other nodes, by their indices
+    Branch { left: usize, right: usize },
+}
+
+/// The distance from a point to an AABB, which is zero inside of it
+fn distance_to_aabb(point: Point<3>, aabb: &Aabb<3>) -> Scalar {
+    let mut distance_squared = Scalar::ZERO;
+
+    for axis in 0..3 {
+        let coord = point.coords.components[axis];
+        let outside = (aabb.min.coords.components[axis] - coord)
+            .max(coord - aabb.max.coords.components[axis])
+            .max(Scalar::ZERO);
+
+        distance_squared += outside * outside;
+    }
+
+    Scalar::from_f64(distance_squared.into_f64().sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::algorithms::voxel::closest_point;
+
+    use super::Bvh;
+
+    #[test]
+    fn find_closest_point() {
+        // A row of triangles along the x-axis, enough to require several
+        // levels of nodes.
+        let triangles: Vec<_> = (0..50)
+            .map(|i| {
+                let x = i as f64;
+                [
+                    Point::from([x, 0., 0.]),
+                    Point::from([x + 1., 0., 0.]),
+                    Point::from([x, 1., 0.]),
+                ]
+            })
+            .collect();
+        let bvh = Bvh::new(triangles.iter().copied());
+
+        for point in [
+            Point::from([-3., 0.5, 1.]),
+            Point::from([12.3, 0.2, -2.]),
+            Point::from([25.5, 4., 0.5]),
+            Point::from([60., -1., 0.]),
+        ] {
+            let expected = triangles
+                .iter()
+                .map(|&triangle| {
+                    (point - closest_point(point, triangle)).magnitude()
+                })
+                .min()
+                .unwrap();
+
+            let closest = bvh.closest_point(point).unwrap();
+            assert_eq!((point - closest).magnitude(), expected);
+        }
+    }
+
+    #[test]
+    fn empty_bvh() {
+        let bvh = Bvh::new([]);
+        assert_eq!(bvh.closest_point(Point::from([0., 0., 0.])), None);
+    }
+}
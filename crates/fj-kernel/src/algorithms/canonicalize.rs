@@ -0,0 +1,97 @@
+use fj_interop::mesh::{Mesh, Triangle};
+use fj_math::{Point, Scalar};
+
+/// Bring a mesh into a canonical form, for bit-identical output
+///
+/// Vertex coordinates are rounded to a fixed grid, and triangles are sorted by
+/// their points. As long as two meshes consist of the same triangles, they end
+/// up identical, regardless of the order in which the triangles were created,
+/// or tiny differences in floating-point results between platforms.
+///
+/// Attribute channels are reordered along with their triangles.
+///
+/// # Implementation note
+///
+/// Rounding can still produce different results, if a coordinate lands very
+/// close to the boundary between two grid cells. The grid is fine enough to
+/// make this unlikely.
+pub fn canonicalize_mesh(mesh: &Mesh<Point<3>>) -> Mesh<Point<3>> {
+    let triangles: Vec<_> = mesh
+        .triangles()
+        .map(|triangle| Triangle {
+            points: triangle.points.map(round_to_grid),
+            ..triangle
+        })
+        .collect();
+
+    let mut order: Vec<_> = (0..triangles.len()).collect();
+    order.sort_by_key(|&i| triangles[i]);
+
+    let mut canonical = Mesh::new();
+    for &i in &order {
+        let triangle = triangles[i];
+        canonical.push_triangle_with_face(
+            triangle.points,
+            triangle.color,
+            triangle.face,
+        );
+    }
+
+    for (attribute, channel) in mesh.channels() {
+        canonical
+            .set_channel(attribute.clone(), channel.select_triangles(&order));
+    }
+
+    canonical
+}
+
+/// The size of the grid that coordinates are rounded to (1 nm)
+const GRID: f64 = 1e-9;
+
+fn round_to_grid(point: Point<3>) -> Point<3> {
+    let coords = point
+        .coords
+        .components
+        .map(|c| Scalar::from_f64((c.into_f64() / GRID).round() * GRID));
+
+    Point::from(coords)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    #[test]
+    fn canonicalize_mesh() {
+        let color = [255, 0, 0, 255];
+
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        // Slightly off copy of `d`, which must end up identical to it.
+        let d2 = Point::from([0., 0., 1. + 1e-12]);
+
+        let mut mesh_a = Mesh::new();
+        mesh_a.push_triangle([a, c, b], color);
+        mesh_a.push_triangle([a, b, d], color);
+
+        let mut mesh_b = Mesh::new();
+        mesh_b.push_triangle([a, b, d2], color);
+        mesh_b.push_triangle([a, c, b], color);
+
+        let mesh_a = super::canonicalize_mesh(&mesh_a);
+        let mesh_b = super::canonicalize_mesh(&mesh_b);
+
+        assert_eq!(
+            mesh_a.vertices().collect::<Vec<_>>(),
+            mesh_b.vertices().collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            mesh_a.indices().collect::<Vec<_>>(),
+            mesh_b.indices().collect::<Vec<_>>(),
+        );
+    }
+}
@@ -0,0 +1,477 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap},
+};
+
+use fj_interop::{
+    mesh::{Color, FaceId, Mesh},
+    progress::{Cancelled, Progress},
+};
+use fj_math::{Point, Scalar};
+use tracing::debug_span;
+
+/// Simplify a mesh by collapsing edges
+///
+/// Uses the quadric error metric, as described in Surface Simplification Using
+/// Quadric Error Metrics by Michael Garland and Paul S. Heckbert. Edges are
+/// collapsed in order of the error that collapsing them would introduce, until
+/// the provided [`DecimationTarget`] is reached.
+///
+/// Collapses that would flip the orientation of a triangle, or that would make
+/// the mesh non-manifold, are rejected. The result might not reach the target,
+/// if there are no more edges that could be collapsed safely.
+///
+/// Returns early, if `progress` has been cancelled.
+///
+/// Attribute channels of the mesh are not carried over, as their values would
+/// no longer match the simplified triangles.
+///
+/// # Implementation note
+///
+/// No special care is taken to preserve open boundaries of the mesh, which
+/// means those can be eroded. This doesn't matter for the closed meshes that
+/// the kernel produces, but it might become a problem, once 2D sketches can be
+/// exported.
+pub fn decimate_mesh(
+    mesh: &Mesh<Point<3>>,
+    target: DecimationTarget,
+    progress: &Progress,
+) -> Result<Mesh<Point<3>>, Cancelled> {
+    let _span = debug_span!("decimate_mesh").entered();
+
+    let mut decimation = Decimation::new(mesh);
+
+    let mut candidates = BinaryHeap::new();
+    for edge in decimation.edges() {
+        if let Some(candidate) = decimation.candidate(edge) {
+            candidates.push(Reverse(candidate));
+        }
+    }
+
+    // The number of collapses can't be known in advance. Let's count each
+    // triangle as a unit of work, and complete all of them at the end.
+    let num_triangles = decimation.num_triangles;
+    progress.add_work(num_triangles);
+
+    while let Some(Reverse(candidate)) = candidates.pop() {
+        progress.check()?;
+
+        let done = match target {
+            DecimationTarget::TriangleCount(count) => {
+                decimation.num_triangles <= count
+            }
+            DecimationTarget::MaxError(max_error) => {
+                // The quadric error is a sum of squared distances, so we need
+                // to compare it against the square of the maximum error.
+                candidate.cost > max_error * max_error
+            }
+        };
+        if done {
+            break;
+        }
+
+        if !decimation.is_current(&candidate) {
+            continue;
+        }
+
+        let num_triangles_before = decimation.num_triangles;
+        if !decimation.collapse(&candidate) {
+            continue;
+        }
+        progress.complete_work(num_triangles_before - decimation.num_triangles);
+
+        let a = candidate.edge[0];
+        for neighbor in decimation.neighbors(a) {
+            if let Some(candidate) = decimation.candidate([a, neighbor]) {
+                candidates.push(Reverse(candidate));
+            }
+        }
+    }
+
+    progress.complete_work(decimation.num_triangles);
+
+    Ok(decimation.into_mesh())
+}
+
+/// The target of a mesh decimation
+///
+/// See [`decimate_mesh`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecimationTarget {
+    /// Decimate until the mesh has no more than this number of triangles
+    TriangleCount(usize),
+
+    /// Decimate as long as the error introduced stays below this value
+    ///
+    /// The value is measured in model units. It is the (approximate) maximum
+    /// distance that is allowed between the original and the simplified mesh.
+    MaxError(Scalar),
+}
+
+struct Decimation {
+    vertices: Vec<[f64; 3]>,
+    quadrics: Vec<Quadric>,
+    versions: Vec<u64>,
+    triangles_by_vertex: Vec<Vec<usize>>,
+
+    triangles: Vec<Option<([usize; 3], Color, Option<FaceId>)>>,
+    num_triangles: usize,
+}
+
+impl Decimation {
+    fn new(mesh: &Mesh<Point<3>>) -> Self {
+        let vertices: Vec<_> = mesh
+            .vertices()
+            .map(|vertex| vertex.coords.components.map(Scalar::into_f64))
+            .collect();
+
+        let mut quadrics = vec![Quadric::default(); vertices.len()];
+        let mut triangles_by_vertex = vec![Vec::new(); vertices.len()];
+        let mut triangles = Vec::new();
+
+        let indices: Vec<_> = mesh.indices().collect();
+        for (triangle, indices) in mesh.triangles().zip(indices.chunks(3)) {
+            let indices = [indices[0], indices[1], indices[2]]
+                .map(|index| index as usize);
+
+            if let Some(quadric) =
+                Quadric::from_triangle(indices.map(|i| vertices[i]))
+            {
+                for i in indices {
+                    quadrics[i] = quadrics[i].add(&quadric);
+                }
+            }
+
+            for i in indices {
+                triangles_by_vertex[i].push(triangles.len());
+            }
+            triangles.push(Some((indices, triangle.color, triangle.face)));
+        }
+
+        Self {
+            versions: vec![0; vertices.len()],
+            vertices,
+            quadrics,
+            triangles_by_vertex,
+            num_triangles: triangles.len(),
+            triangles,
+        }
+    }
+
+    fn edges(&self) -> BTreeSet<[usize; 2]> {
+        let mut edges = BTreeSet::new();
+
+        for ([a, b, c], _, _) in self.triangles.iter().flatten() {
+            for [a, b] in [[*a, *b], [*b, *c], [*c, *a]] {
+                edges.insert(if a < b { [a, b] } else { [b, a] });
+            }
+        }
+
+        edges
+    }
+
+    fn neighbors(&self, vertex: usize) -> BTreeSet<usize> {
+        self.triangles_by_vertex[vertex]
+            .iter()
+            .filter_map(|&t| self.triangles[t])
+            .flat_map(|(indices, _, _)| indices)
+            .filter(|&i| i != vertex)
+            .collect()
+    }
+
+    fn candidate(&self, edge: [usize; 2]) -> Option<Candidate> {
+        let [a, b] = edge;
+
+        let quadric = self.quadrics[a].add(&self.quadrics[b]);
+
+        let [pa, pb] = [self.vertices[a], self.vertices[b]];
+        let center = [0, 1, 2].map(|i| (pa[i] + pb[i]) / 2.);
+
+        // Due to floating-point inaccuracy, the error can end up slightly
+        // negative. It can't actually be, so let's clamp it to zero.
+        let (cost, position) = [pa, pb, center]
+            .into_iter()
+            .map(|position| {
+                let cost = quadric.error(position).max(0.);
+                (Scalar::from_f64(cost), position.map(Scalar::from_f64))
+            })
+            .min()?;
+
+        Some(Candidate {
+            cost,
+            edge,
+            versions: [self.versions[a], self.versions[b]],
+            position,
+        })
+    }
+
+    fn is_current(&self, candidate: &Candidate) -> bool {
+        let [a, b] = candidate.edge;
+        candidate.versions == [self.versions[a], self.versions[b]]
+    }
+
+    /// Collapse the edge, moving `b` into `a`
+    ///
+    /// Returns `false`, if the collapse was rejected.
+    fn collapse(&mut self, candidate: &Candidate) -> bool {
+        let [a, b] = candidate.edge;
+        let position = candidate.position.map(Scalar::into_f64);
+
+        let mut affected = self.triangles_by_vertex[a].clone();
+        affected.extend(self.triangles_by_vertex[b].iter().copied());
+        affected.sort_unstable();
+        affected.dedup();
+
+        // Check the link condition: The only vertices that are neighbors of
+        // both `a` and `b` must be the ones opposite the edge. Otherwise, the
+        // collapse would pinch the mesh and make it non-manifold.
+        let opposite: BTreeSet<_> = affected
+            .iter()
+            .filter_map(|&t| self.triangles[t])
+            .map(|(indices, _, _)| indices)
+            .filter(|indices| indices.contains(&a) && indices.contains(&b))
+            .flatten()
+            .filter(|&i| i != a && i != b)
+            .collect();
+        let shared: BTreeSet<_> = self
+            .neighbors(a)
+            .intersection(&self.neighbors(b))
+            .copied()
+            .collect();
+        if shared != opposite {
+            return false;
+        }
+
+        // Check that the collapse doesn't flip any of the remaining triangles.
+        for &t in &affected {
+            let (indices, _, _) = match self.triangles[t] {
+                Some(triangle) => triangle,
+                None => continue,
+            };
+            if indices.contains(&a) && indices.contains(&b) {
+                // This triangle is removed by the collapse.
+                continue;
+            }
+
+            let before = indices.map(|i| self.vertices[i]);
+            let after = indices.map(|i| {
+                if i == a || i == b {
+                    position
+                } else {
+                    self.vertices[i]
+                }
+            });
+
+            let n_before = normal(before);
+            let n_after = normal(after);
+            if dot(n_before, n_after) <= 0. || dot(n_after, n_after) == 0. {
+                return false;
+            }
+        }
+
+        for &t in &affected {
+            let triangle = match &mut self.triangles[t] {
+                Some(triangle) => triangle,
+                None => continue,
+            };
+
+            if triangle.0.contains(&a) && triangle.0.contains(&b) {
+                self.triangles[t] = None;
+                self.num_triangles -= 1;
+                continue;
+            }
+
+            for i in &mut triangle.0 {
+                if *i == b {
+                    *i = a;
+                }
+            }
+        }
+
+        let triangles_of_b = std::mem::take(&mut self.triangles_by_vertex[b]);
+        self.triangles_by_vertex[a].extend(triangles_of_b);
+        self.triangles_by_vertex[a].sort_unstable();
+        self.triangles_by_vertex[a].dedup();
+
+        self.vertices[a] = position;
+        self.quadrics[a] = self.quadrics[a].add(&self.quadrics[b]);
+
+        self.versions[a] += 1;
+        self.versions[b] += 1;
+
+        true
+    }
+
+    fn into_mesh(self) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+
+        for (indices, color, face) in self.triangles.into_iter().flatten() {
+            let points = indices.map(|i| Point::from(self.vertices[i]));
+            mesh.push_triangle_with_face(points, color, face);
+        }
+
+        mesh
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Candidate {
+    cost: Scalar,
+    edge: [usize; 2],
+    versions: [u64; 2],
+    position: [Scalar; 3],
+}
+
+/// A symmetric 4x4 matrix, storing only the upper triangle
+#[derive(Clone, Copy, Debug, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_triangle(points: [[f64; 3]; 3]) -> Option<Self> {
+        let n = normal(points);
+        let length = dot(n, n).sqrt();
+        if length == 0. {
+            return None;
+        }
+
+        let [a, b, c] = n.map(|n| n / length);
+        let d = -dot([a, b, c], points[0]);
+
+        Some(Self([
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ]))
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut sum = self.0;
+        for (s, o) in sum.iter_mut().zip(other.0) {
+            *s += o;
+        }
+        Self(sum)
+    }
+
+    fn error(&self, [x, y, z]: [f64; 3]) -> f64 {
+        let q = &self.0;
+
+        q[0] * x * x
+            + 2. * q[1] * x * y
+            + 2. * q[2] * x * z
+            + 2. * q[3] * x
+            + q[4] * y * y
+            + 2. * q[5] * y * z
+            + 2. * q[6] * y
+            + q[7] * z * z
+            + 2. * q[8] * z
+            + q[9]
+    }
+}
+
+fn normal([a, b, c]: [[f64; 3]; 3]) -> [f64; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+
+    [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{mesh::Mesh, progress::Progress};
+    use fj_math::{Point, Scalar};
+
+    use super::{decimate_mesh, Decimation, DecimationTarget};
+
+    #[test]
+    fn decimate_to_triangle_count() {
+        let mesh = subdivided_plane(8);
+        assert_eq!(mesh.triangles().count(), 128);
+
+        let decimated = decimate_mesh(
+            &mesh,
+            DecimationTarget::TriangleCount(20),
+            &Progress::new(),
+        )
+        .unwrap();
+        assert!(decimated.triangles().count() <= 20);
+
+        // Decimating a plane must not move any vertices out of the plane.
+        for vertex in decimated.vertices() {
+            assert_eq!(vertex.z, Scalar::ZERO);
+        }
+    }
+
+    #[test]
+    fn decimate_to_max_error() {
+        let mesh = subdivided_plane(4);
+
+        // A plane can be simplified without introducing any error.
+        let decimated = decimate_mesh(
+            &mesh,
+            DecimationTarget::MaxError(Scalar::ZERO),
+            &Progress::new(),
+        )
+        .unwrap();
+        assert!(decimated.triangles().count() < mesh.triangles().count());
+    }
+
+    #[test]
+    fn link_condition() {
+        let mut mesh = Mesh::new();
+        let color = [255, 0, 0, 255];
+
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0.5, 1., 0.]);
+        let d = Point::from([-0.5, -1.5, 0.]);
+        let e = Point::from([1.5, -1.5, 0.]);
+        let v = Point::from([0.5, -1., 0.]);
+
+        // `v` is a neighbor of both `a` and `b`, but not opposite their edge.
+        // Collapsing the edge would join the two lower triangles at `v`.
+        mesh.push_triangle([a, b, c], color);
+        mesh.push_triangle([a, d, v], color);
+        mesh.push_triangle([b, v, e], color);
+
+        let mut decimation = Decimation::new(&mesh);
+        let candidate = decimation.candidate([0, 1]).unwrap();
+        assert!(!decimation.collapse(&candidate));
+        assert_eq!(decimation.num_triangles, 3);
+    }
+
+    fn subdivided_plane(n: usize) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+        let color = [255, 0, 0, 255];
+
+        let point = |i: usize, j: usize| Point::from([i as f64, j as f64, 0.]);
+
+        for i in 0..n {
+            for j in 0..n {
+                let a = point(i, j);
+                let b = point(i + 1, j);
+                let c = point(i + 1, j + 1);
+                let d = point(i, j + 1);
+
+                mesh.push_triangle([a, b, c], color);
+                mesh.push_triangle([a, c, d], color);
+            }
+        }
+
+        mesh
+    }
+}
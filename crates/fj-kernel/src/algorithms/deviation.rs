@@ -0,0 +1,199 @@
+use fj_interop::mesh::{Attribute, Channel, Mesh};
+use fj_math::{Point, Scalar};
+
+use super::bvh::Bvh;
+
+/// Measure how far a mesh deviates from a reference mesh
+///
+/// The deviation is sampled at each vertex of `mesh`, as the distance to the
+/// closest point of `reference`. That can be used to render a heatmap of the
+/// deviation, via [`Deviation::set_channel`], or to check that an imported or
+/// modified mesh still matches the original, via the summary statistics.
+///
+/// Returns `None`, if `reference` has no triangles to measure the distance to.
+///
+/// # Implementation note
+///
+/// Only the vertices are sampled, so a large triangle that bulges away from the
+/// reference between its vertices is not detected. The deviation is also not
+/// symmetric. To catch parts of the reference that `mesh` lacks, measure the
+/// deviation the other way around too.
+///
+/// The closest points are found using a bounding volume hierarchy over the
+/// triangles of `reference`, so this takes time roughly proportional to the
+/// number of vertices times the logarithm of the number of triangles.
+pub fn mesh_deviation(
+    mesh: &Mesh<Point<3>>,
+    reference: &Mesh<Point<3>>,
+) -> Option<Deviation> {
+    let bvh = Bvh::new(
+        reference
+            .triangles()
+            .map(|triangle| triangle.points)
+            // Degenerate triangles are covered by their neighbors.
+            .filter(|&[a, b, c]| {
+                (b - a).cross(&(c - a)).magnitude() != Scalar::ZERO
+            }),
+    );
+
+    if bvh.is_empty() {
+        return None;
+    }
+
+    let distances = mesh
+        .vertices()
+        .map(|vertex| {
+            // Can't panic. The hierarchy is not empty.
+            let closest = bvh.closest_point(vertex).unwrap();
+            (vertex - closest).magnitude()
+        })
+        .collect();
+
+    Some(Deviation::from_distances(distances))
+}
+
+/// The result of [`mesh_deviation`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deviation {
+    /// The distance of each vertex to the reference mesh
+    ///
+    /// In the order of [`Mesh::vertices`].
+    pub distances: Vec<Scalar>,
+
+    /// The smallest distance
+    pub min: Scalar,
+
+    /// The largest distance
+    pub max: Scalar,
+
+    /// The mean of the distances
+    pub mean: Scalar,
+
+    /// The root mean square of the distances
+    ///
+    /// Unlike the mean, it weighs large deviations more than small ones.
+    pub rms: Scalar,
+}
+
+impl Deviation {
+    fn from_distances(distances: Vec<Scalar>) -> Self {
+        let min = distances.iter().copied().min().unwrap_or(Scalar::ZERO);
+        let max = distances.iter().copied().max().unwrap_or(Scalar::ZERO);
+
+        let (mean, rms) = if distances.is_empty() {
+            (Scalar::ZERO, Scalar::ZERO)
+        } else {
+            let n = distances.len() as f64;
+
+            let sum = distances
+                .iter()
+                .fold(Scalar::ZERO, |sum, &distance| sum + distance);
+            let sum_of_squares = distances
+                .iter()
+                .fold(Scalar::ZERO, |sum, &distance| sum + distance * distance);
+
+            (
+                sum / n,
+                Scalar::from_f64((sum_of_squares / n).into_f64().sqrt()),
+            )
+        };
+
+        Self {
+            distances,
+            min,
+            max,
+            mean,
+            rms,
+        }
+    }
+
+    /// Set the [`Attribute::Deviation`] channel of the mesh that was measured
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the mesh doesn't have one vertex per distance, which means
+    /// it's not the mesh that was measured.
+    pub fn set_channel(&self, mesh: &mut Mesh<Point<3>>) {
+        assert_eq!(
+            mesh.vertices().count(),
+            self.distances.len(),
+            "Deviation doesn't match mesh",
+        );
+
+        let values = mesh
+            .indices()
+            .map(|index| self.distances[index as usize])
+            .collect();
+        mesh.set_channel(Attribute::Deviation, Channel::Scalars(values));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::{Attribute, Channel, Mesh};
+    use fj_math::{Point, Scalar};
+
+    use super::mesh_deviation;
+
+    #[test]
+    fn identical_meshes() {
+        let mesh = square(0.);
+
+        let deviation = mesh_deviation(&mesh, &mesh).unwrap();
+
+        assert_eq!(deviation.distances, vec![Scalar::ZERO; 4]);
+        assert_eq!(deviation.max, Scalar::ZERO);
+        assert_eq!(deviation.rms, Scalar::ZERO);
+    }
+
+    #[test]
+    fn deviation_at_vertices() {
+        let reference = square(0.);
+        let mut mesh = square(0.5);
+
+        // Move one corner further away.
+        let mut triangles: Vec<_> = mesh.triangles().collect();
+        for triangle in &mut triangles {
+            for point in &mut triangle.points {
+                if *point == Point::from([1., 1., 0.5]) {
+                    *point = Point::from([1., 1., 2.]);
+                }
+            }
+        }
+        mesh = Mesh::new();
+        for triangle in triangles {
+            mesh.push_triangle(triangle.points, triangle.color);
+        }
+
+        let deviation = mesh_deviation(&mesh, &reference).unwrap();
+
+        assert_eq!(deviation.min, Scalar::from_f64(0.5));
+        assert_eq!(deviation.max, Scalar::from_f64(2.));
+        assert_eq!(deviation.mean, Scalar::from_f64(0.875));
+
+        deviation.set_channel(&mut mesh);
+        let values = match mesh.channel(&Attribute::Deviation) {
+            Some(Channel::Scalars(values)) => values.clone(),
+            channel => panic!("Unexpected channel: {:?}", channel),
+        };
+        for (index, value) in mesh.indices().zip(values) {
+            assert_eq!(deviation.distances[index as usize], value);
+        }
+    }
+
+    #[test]
+    fn empty_reference() {
+        assert_eq!(mesh_deviation(&square(0.), &Mesh::new()), None);
+    }
+
+    /// A unit square at the given height, made from two triangles
+    fn square(z: f64) -> Mesh<Point<3>> {
+        let [a, b, c, d] = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+            .map(|[x, y]| Point::from([x, y, z]));
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+        mesh.push_triangle([a, c, d], [255, 0, 0, 255]);
+        mesh
+    }
+}
@@ -0,0 +1,229 @@
+use fj_interop::mesh::{Color, Mesh, Triangle};
+use fj_math::{Point, Scalar};
+
+use super::{bvh::Bvh, voxel::is_inside, Tolerance};
+
+/// The color of the surfaces around volume that has been added
+pub const ADDED_COLOR: Color = [40, 160, 60, 255];
+
+/// The color of the surfaces around volume that has been removed
+pub const REMOVED_COLOR: Color = [200, 50, 50, 255];
+
+/// Compare two versions of a mesh
+///
+/// The returned mesh overlays both versions. Surfaces that both of them share,
+/// within `tolerance`, keep the colors of the new version. Volume that only the
+/// new version encloses is surrounded by surfaces of [`ADDED_COLOR`], volume
+/// that only the old version encloses by surfaces of [`REMOVED_COLOR`].
+///
+/// Both meshes must be closed, and face outward, as they do after
+/// [`orient_mesh`]. The result is meaningless otherwise.
+///
+/// # Implementation note
+///
+/// Each triangle is classified by its center, as being on the surface of the
+/// other mesh, inside of it, or outside. So the boundary between changed and
+/// unchanged surfaces follows the triangles, and large triangles that are only
+/// partly changed can end up on the wrong side of it.
+///
+/// The distance to the other mesh is found using a bounding volume hierarchy,
+/// but telling inside from outside still tests every triangle of the other
+/// mesh. So this takes time proportional to the product of the numbers of
+/// triangles, for the triangles that have changed.
+///
+/// [`orient_mesh`]: super::orient_mesh
+pub fn diff_meshes(
+    old: &Mesh<Point<3>>,
+    new: &Mesh<Point<3>>,
+    tolerance: Tolerance,
+) -> MeshDiff {
+    let old = triangles(old);
+    let new = triangles(new);
+
+    let old_points: Vec<_> =
+        old.iter().map(|triangle| triangle.points).collect();
+    let new_points: Vec<_> =
+        new.iter().map(|triangle| triangle.points).collect();
+
+    let old_bvh = Bvh::new(old_points.iter().copied());
+    let new_bvh = Bvh::new(new_points.iter().copied());
+
+    let mut mesh = Mesh::new();
+
+    // Six times the volumes, as that's what comes out of the triple product.
+    let mut added = Scalar::ZERO;
+    let mut removed = Scalar::ZERO;
+
+    for triangle in &new {
+        let points = triangle.points;
+        let volume = signed_volume(points);
+
+        match locate(points, &old_points, &old_bvh, tolerance) {
+            Location::Surface => mesh.push_triangle(points, triangle.color),
+            Location::Outside => {
+                mesh.push_triangle(points, ADDED_COLOR);
+                added += volume;
+            }
+            Location::Inside => {
+                // The triangle faces out of the removed volume, so it counts
+                // negatively towards it.
+                mesh.push_triangle(points, REMOVED_COLOR);
+                removed = removed - volume;
+            }
+        }
+    }
+
+    for points in old_points {
+        let volume = signed_volume(points);
+
+        // Shared surfaces are already part of the mesh, from the new version.
+        match locate(points, &new_points, &new_bvh, tolerance) {
+            Location::Surface => {}
+            Location::Outside => {
+                mesh.push_triangle(points, REMOVED_COLOR);
+                removed += volume;
+            }
+            Location::Inside => {
+                mesh.push_triangle(points, ADDED_COLOR);
+                added = added - volume;
+            }
+        }
+    }
+
+    MeshDiff {
+        mesh,
+        added: added / 6.,
+        removed: removed / 6.,
+    }
+}
+
+/// The result of [`diff_meshes`]
+pub struct MeshDiff {
+    /// Both versions of the mesh, overlaid
+    pub mesh: Mesh<Point<3>>,
+
+    /// The volume that only the new version encloses
+    pub added: Scalar,
+
+    /// The volume that only the old version encloses
+    pub removed: Scalar,
+}
+
+enum Location {
+    Surface,
+    Inside,
+    Outside,
+}
+
+/// Determine where a triangle is, relative to a closed mesh
+fn locate(
+    [a, b, c]: [Point<3>; 3],
+    mesh: &[[Point<3>; 3]],
+    bvh: &Bvh,
+    tolerance: Tolerance,
+) -> Location {
+    let center = a + ((b - a) + (c - a)) / 3.;
+
+    let distance = bvh
+        .closest_point(center)
+        .map(|closest| (center - closest).magnitude())
+        .unwrap_or(Scalar::MAX);
+    if distance <= tolerance.inner() {
+        return Location::Surface;
+    }
+
+    if is_inside(center, mesh, tolerance) {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Collect the triangles of a mesh, leaving out degenerate ones
+///
+/// Degenerate triangles don't enclose anything, and are covered by their
+/// neighbors.
+fn triangles(mesh: &Mesh<Point<3>>) -> Vec<Triangle> {
+    mesh.triangles()
+        .filter(|triangle| {
+            let [a, b, c] = triangle.points;
+            (b - a).cross(&(c - a)).magnitude() != Scalar::ZERO
+        })
+        .collect()
+}
+
+/// Six times the signed volume of the tetrahedron between a triangle and the
+/// origin
+fn signed_volume(points: [Point<3>; 3]) -> Scalar {
+    let [a, b, c] = points.map(|point| point.coords);
+    a.dot(&b.cross(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::{Point, Scalar};
+
+    use crate::{algorithms::Tolerance, testing::fixtures::box_mesh_at};
+
+    use super::{diff_meshes, ADDED_COLOR, REMOVED_COLOR};
+
+    #[test]
+    fn identical_meshes() {
+        let mesh = boxes(&[[0., 0., 0.]]);
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let diff = diff_meshes(&mesh, &mesh, tolerance);
+
+        assert_eq!(diff.added, Scalar::ZERO);
+        assert_eq!(diff.removed, Scalar::ZERO);
+        assert_eq!(diff.mesh.triangles().count(), 12);
+        assert!(diff
+            .mesh
+            .triangles()
+            .all(|triangle| triangle.color == [255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn added_and_removed_volume() {
+        let old = boxes(&[[0., 0., 0.]]);
+        let new = boxes(&[[0., 0., 0.], [2., 0., 0.]]);
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let added = diff_meshes(&old, &new, tolerance);
+        assert_eq!(added.added, Scalar::ONE);
+        assert_eq!(added.removed, Scalar::ZERO);
+        assert_eq!(
+            added
+                .mesh
+                .triangles()
+                .filter(|triangle| triangle.color == ADDED_COLOR)
+                .count(),
+            12
+        );
+
+        let removed = diff_meshes(&new, &old, tolerance);
+        assert_eq!(removed.added, Scalar::ZERO);
+        assert_eq!(removed.removed, Scalar::ONE);
+        assert_eq!(
+            removed
+                .mesh
+                .triangles()
+                .filter(|triangle| triangle.color == REMOVED_COLOR)
+                .count(),
+            12
+        );
+    }
+
+    /// Unit cubes at the given corners, facing outward
+    fn boxes(corners: &[[f64; 3]]) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+        for &min in corners {
+            for triangle in box_mesh_at(min, [1., 1., 1.]).triangles() {
+                mesh.push_triangle(triangle.points, triangle.color);
+            }
+        }
+
+        mesh
+    }
+}
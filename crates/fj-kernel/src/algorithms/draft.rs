@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::{FaceId, Mesh};
+use fj_math::{Point, Scalar, Vector};
+
+/// Classify the faces of a mesh by their draft
+///
+/// The draft angle of a triangle is the angle between the triangle and the
+/// pull direction, positive if the triangle faces along the pull direction.
+/// Triangles whose draft angle is smaller than `check.min_angle`, in either
+/// direction, are considered to be [`Draft::Vertical`].
+///
+/// A face is classified by its worst triangle, so a single triangle without
+/// draft makes the whole face [`Draft::Vertical`]. The mesh is expected to
+/// face outward, as it does after [`orient_mesh`].
+///
+/// [`orient_mesh`]: super::orient_mesh
+pub fn analyze_draft(
+    mesh: &Mesh<Point<3>>,
+    check: DraftCheck,
+) -> DraftAnalysis {
+    let pull_direction = check.pull_direction.normalize();
+    let (min_sin, _) = check.min_angle.sin_cos();
+
+    let mut triangles = Vec::new();
+    let mut faces = BTreeMap::new();
+
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.points;
+        let normal = (b - a).cross(&(c - a));
+
+        // Degenerate triangles have no area that could get stuck in the mold.
+        let draft = if normal.magnitude() == Scalar::ZERO {
+            Draft::Positive
+        } else {
+            // The normal is perpendicular to the triangle, so this is the
+            // sine of the draft angle.
+            let sin = normal.normalize().dot(&pull_direction);
+
+            if sin >= min_sin {
+                Draft::Positive
+            } else if sin <= -min_sin {
+                Draft::Negative
+            } else {
+                Draft::Vertical
+            }
+        };
+
+        triangles.push(draft);
+
+        if let Some(face) = triangle.face {
+            let worst = faces.entry(face).or_insert(draft);
+            *worst = draft.max(*worst);
+        }
+    }
+
+    DraftAnalysis { triangles, faces }
+}
+
+/// The parameters of a draft analysis
+///
+/// Used by [`analyze_draft`].
+#[derive(Clone, Copy, Debug)]
+pub struct DraftCheck {
+    /// The direction in which the part is pulled out of the mold
+    pub pull_direction: Vector<3>,
+
+    /// The smallest draft angle that is acceptable, in radians
+    ///
+    /// This should be larger than zero, or faces that are meant to be
+    /// vertical end up classified arbitrarily, due to rounding errors.
+    pub min_angle: Scalar,
+}
+
+/// The draft of a triangle or face, relative to the pull direction
+///
+/// Ordered from best to worst.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Draft {
+    /// The face faces along the pull direction, and will release from the mold
+    Positive,
+
+    /// The face is parallel to the pull direction, or nearly so
+    ///
+    /// It will drag against the mold as the part is pulled out.
+    Vertical,
+
+    /// The face faces against the pull direction
+    ///
+    /// Unless it is formed by the opposite half of the mold, this is an
+    /// undercut, which locks the part in the mold.
+    Negative,
+}
+
+/// The result of a draft analysis
+///
+/// Returned by [`analyze_draft`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DraftAnalysis {
+    /// The draft of each triangle
+    ///
+    /// This is in the order in which `Mesh::triangles` returns them.
+    pub triangles: Vec<Draft>,
+
+    /// The draft of each face, which is that of its worst triangle
+    ///
+    /// Triangles that don't belong to a face are not included.
+    pub faces: BTreeMap<FaceId, Draft>,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::{FaceId, Mesh};
+    use fj_math::{Point, Scalar, Vector};
+
+    use super::{analyze_draft, Draft, DraftCheck};
+
+    #[test]
+    fn classify_draft() {
+        let points = [
+            Point::from([0., 0., 0.]),
+            Point::from([1., 0., 0.]),
+            Point::from([0., 1., 0.]),
+            Point::from([0., 0., 1.]),
+        ];
+        let [a, b, c, d] = points;
+
+        let mut mesh = Mesh::new();
+        let color = [255, 0, 0, 255];
+
+        // The first triangle faces sideways, the second one diagonally up.
+        mesh.push_triangle_with_face([a, b, d], color, Some(FaceId(0)));
+        mesh.push_triangle_with_face([b, c, d], color, Some(FaceId(0)));
+        // Faces sideways.
+        mesh.push_triangle_with_face([a, d, c], color, Some(FaceId(1)));
+        // Faces down, against the pull direction.
+        mesh.push_triangle_with_face([a, c, b], color, Some(FaceId(2)));
+
+        let analysis = analyze_draft(
+            &mesh,
+            DraftCheck {
+                pull_direction: Vector::from([0., 0., 2.]),
+                min_angle: Scalar::from_f64(1_f64.to_radians()),
+            },
+        );
+
+        assert_eq!(
+            analysis.triangles,
+            [
+                Draft::Vertical,
+                Draft::Positive,
+                Draft::Vertical,
+                Draft::Negative,
+            ]
+        );
+        assert_eq!(
+            analysis.faces.into_iter().collect::<Vec<_>>(),
+            [
+                (FaceId(0), Draft::Vertical),
+                (FaceId(1), Draft::Vertical),
+                (FaceId(2), Draft::Negative),
+            ]
+        );
+    }
+}
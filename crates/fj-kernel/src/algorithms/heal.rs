@@ -0,0 +1,713 @@
+use std::collections::{BTreeMap, HashMap};
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Color, FaceId, Mesh},
+    progress::Progress,
+};
+use fj_math::{Point, Scalar, Triangle, Vector};
+use tracing::debug_span;
+
+use crate::{
+    geometry::{Curve, Line, Surface, SweptCurve},
+    shape::{Handle, Shape},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::{triangulate, weld::VertexGrid, Tolerance};
+
+/// Repair small defects of a shape
+///
+/// Creates a new shape from `source`, in which
+///
+/// - vertices that are closer to each other than `tolerance` are merged, which
+///   also closes small gaps between the edges of a cycle,
+/// - holes in the shell are closed with a new face, if the edges around them
+///   lie within a plane, and they are no larger than `max_hole_size`, and
+/// - faces that point into the shape are reversed, so they point outward.
+///
+/// Edges that collapse into a single point, because their vertices are merged,
+/// are removed. Returns the repaired shape, as well as a [`HealReport`] about
+/// what was repaired.
+///
+/// # Implementation note
+///
+/// To find out whether a face points outward, a ray is cast from it, and the
+/// number of times it hits the rest of the shape is counted. This only gives
+/// the right answer for closed shells, so faces of shapes that still have holes
+/// after healing might be reversed wrongly.
+pub fn heal_shape(
+    source: Shape,
+    tolerance: Tolerance,
+    max_hole_size: Scalar,
+) -> (Shape, HealReport) {
+    let _span = debug_span!("heal_shape").entered();
+
+    let mut report = HealReport::default();
+    let mut target = Shape::new();
+
+    // Nothing else has access to this `Progress`, so nothing can cancel it.
+    let mesh = triangulate(
+        source.clone(),
+        tolerance,
+        &mut DebugInfo::new(),
+        &Progress::new(),
+    )
+    .expect("Triangulation was cancelled, but nothing could cancel it");
+
+    let vertices = merge_vertices(&source, &mut target, tolerance, &mut report);
+    let edges = copy_edges(&source, &mut target, &vertices, &mut report);
+    let cycles = copy_cycles(&source, &mut target, &edges, &mut report);
+
+    // Faces are inserted last, once it's known which ones need to be reversed.
+    let mut faces: Vec<_> = source
+        .faces()
+        .enumerate()
+        .filter_map(|(i, face)| {
+            NewFace::from_source(&face.get(), &cycles, &mesh, FaceId(i as u64))
+        })
+        .collect();
+
+    let num_healed = faces.len();
+    let caps = close_holes(&faces, &mut target, tolerance, max_hole_size);
+    report.closed_holes = caps.len();
+    faces.extend(caps);
+
+    let reverse: Vec<_> = (0..faces.len())
+        .map(|i| points_inward(&faces, i, tolerance))
+        .collect();
+
+    for (i, (face, reverse)) in faces.into_iter().zip(reverse).enumerate() {
+        // Caps are new, so they can't have been reversed.
+        if reverse && i < num_healed {
+            report.reversed_faces += 1;
+        }
+
+        let face = match face.kind {
+            Kind::Face {
+                surface,
+                exteriors,
+                interiors,
+                color,
+            } => {
+                let surface = if reverse { surface.reverse() } else { surface };
+
+                Face::Face {
+                    surface: target.insert(surface).unwrap(),
+                    exteriors,
+                    interiors,
+                    color,
+                }
+            }
+            Kind::Triangles(mut triangles) => {
+                if reverse {
+                    for (triangle, _) in &mut triangles {
+                        let [a, b, c] = triangle.points();
+                        *triangle = Triangle::from_points([a, c, b]);
+                    }
+                }
+
+                Face::Triangles(triangles)
+            }
+        };
+
+        // Can't panic. The cycles of the face have been inserted above.
+        target.insert(face).unwrap();
+    }
+
+    (target, report)
+}
+
+/// Statistics produced by [`heal_shape`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HealReport {
+    /// The number of vertices that were merged into another vertex
+    pub merged_vertices: usize,
+
+    /// The number of gaps between edges of a cycle that merging closed
+    pub closed_gaps: usize,
+
+    /// The number of edges that were removed, because they collapsed
+    pub collapsed_edges: usize,
+
+    /// The number of faces that were reversed, to point outward
+    pub reversed_faces: usize,
+
+    /// The number of holes that were closed with a new face
+    pub closed_holes: usize,
+}
+
+impl HealReport {
+    /// Indicate whether anything was repaired
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A face of the healed shape, before it is inserted
+struct NewFace {
+    kind: Kind,
+
+    /// Triangles that approximate the face, for casting rays against it
+    triangles: Vec<[Point<3>; 3]>,
+
+    /// Points on the face, along with the direction the face points to there
+    samples: Vec<(Point<3>, Vector<3>)>,
+}
+
+enum Kind {
+    Face {
+        surface: Surface,
+        exteriors: Vec<Handle<Cycle>>,
+        interiors: Vec<Handle<Cycle>>,
+        color: [u8; 4],
+    },
+    Triangles(Vec<(Triangle<3>, Color)>),
+}
+
+impl NewFace {
+    /// Create a face from a face of the source shape
+    ///
+    /// Returns `None`, if all exterior cycles of the face have collapsed.
+    fn from_source(
+        face: &Face,
+        cycles: &HashMap<Handle<Cycle>, Handle<Cycle>>,
+        mesh: &Mesh<Point<3>>,
+        id: FaceId,
+    ) -> Option<Self> {
+        let kind = match face {
+            Face::Face {
+                surface,
+                exteriors,
+                interiors,
+                color,
+            } => {
+                let map = |source: &[Handle<Cycle>]| -> Vec<_> {
+                    source
+                        .iter()
+                        .filter_map(|cycle| cycles.get(cycle).cloned())
+                        .collect()
+                };
+
+                let exteriors = map(exteriors);
+                if exteriors.is_empty() {
+                    return None;
+                }
+
+                Kind::Face {
+                    surface: surface.get(),
+                    exteriors,
+                    interiors: map(interiors),
+                    color: *color,
+                }
+            }
+            Face::Triangles(triangles) => Kind::Triangles(triangles.clone()),
+        };
+
+        let triangles: Vec<_> = mesh
+            .triangles_of_face(id)
+            .map(|triangle| triangle.points)
+            .collect();
+
+        // Rays are cast from the largest triangles, and vote on the result.
+        // That way, a single ray that happens to graze the shell at an
+        // unfortunate spot can't decide it.
+        let mut largest = triangles.clone();
+        largest.sort_by_key(|&[a, b, c]| {
+            Scalar::ZERO - (b - a).cross(&(c - a)).magnitude()
+        });
+        let samples = largest
+            .into_iter()
+            .take(3)
+            .map(|[a, b, c]| {
+                let center = a + ((b - a) + (c - a)) / 3.;
+                (center, (b - a).cross(&(c - a)))
+            })
+            .collect();
+
+        Some(Self {
+            kind,
+            triangles,
+            samples,
+        })
+    }
+}
+
+fn merge_vertices(
+    source: &Shape,
+    target: &mut Shape,
+    tolerance: Tolerance,
+    report: &mut HealReport,
+) -> HashMap<Handle<Vertex>, Handle<Vertex>> {
+    let mut grid = VertexGrid::new(tolerance.inner());
+    let mut merged = HashMap::new();
+    let mut vertices = HashMap::new();
+
+    for vertex in source.vertices() {
+        let (point, was_merged) = grid.weld(vertex.get().point());
+        if was_merged {
+            report.merged_vertices += 1;
+        }
+
+        let merged = merged
+            .entry(point)
+            .or_insert_with(|| {
+                // Can't panic. Merged vertices are further apart than the
+                // vertices of the source shape, which are valid.
+                let point = target.insert(point).unwrap();
+                target.insert(Vertex { point }).unwrap()
+            })
+            .clone();
+        vertices.insert(vertex, merged);
+    }
+
+    vertices
+}
+
+fn copy_edges(
+    source: &Shape,
+    target: &mut Shape,
+    vertices: &HashMap<Handle<Vertex>, Handle<Vertex>>,
+    report: &mut HealReport,
+) -> HashMap<Handle<Edge>, Handle<Edge>> {
+    let mut edges = HashMap::new();
+
+    for edge_source in source.edges() {
+        let edge = edge_source.get();
+        let mut curve = edge.curve();
+
+        let vertices = match &edge.vertices {
+            Some(vertices_source) => {
+                let [a, b] = vertices_source
+                    .clone()
+                    .map(|vertex| vertices[&vertex].clone());
+                if a == b {
+                    report.collapsed_edges += 1;
+                    continue;
+                }
+
+                // The line needs to go through the vertices, if they have
+                // been moved. Other curves are left alone, as the vertices
+                // are close enough to them anyway.
+                let points = [a.get().point(), b.get().point()];
+                let moved = points
+                    != vertices_source
+                        .clone()
+                        .map(|vertex| vertex.get().point());
+                if let (Curve::Line(_), true) = (curve, moved) {
+                    curve = Curve::Line(Line::from_points(points));
+                }
+
+                Some([a, b])
+            }
+            None => None,
+        };
+
+        // Can't panic. Curves are always valid, and the vertices have been
+        // inserted above.
+        let curve = target.insert(curve).unwrap();
+        let edge = target.insert(Edge { curve, vertices }).unwrap();
+
+        edges.insert(edge_source, edge);
+    }
+
+    edges
+}
+
+fn copy_cycles(
+    source: &Shape,
+    target: &mut Shape,
+    edges: &HashMap<Handle<Edge>, Handle<Edge>>,
+    report: &mut HealReport,
+) -> HashMap<Handle<Cycle>, Handle<Cycle>> {
+    let mut cycles = HashMap::new();
+
+    for cycle_source in source.cycles() {
+        let edges_source = cycle_source.get().edges;
+        let edges_target: Vec<_> = edges_source
+            .iter()
+            .filter_map(|edge| edges.get(edge).cloned())
+            .collect();
+
+        if edges_target.is_empty() {
+            continue;
+        }
+
+        // Each gap leaves two ends open.
+        let ends_before = open_ends(edges_source.iter());
+        let ends_after = open_ends(edges_target.iter());
+        report.closed_gaps += ends_before.saturating_sub(ends_after) / 2;
+
+        // Can't panic. The edges have been inserted above.
+        let cycle = target
+            .insert(Cycle {
+                edges: edges_target,
+            })
+            .unwrap();
+
+        cycles.insert(cycle_source, cycle);
+    }
+
+    cycles
+}
+
+/// Count the vertices that only one of the edges connects to
+fn open_ends<'r>(edges: impl Iterator<Item = &'r Handle<Edge>>) -> usize {
+    let mut connections = HashMap::new();
+    for vertices in edges.filter_map(|edge| edge.get().vertices()) {
+        for vertex in vertices {
+            *connections.entry(vertex.point()).or_insert(0) += 1;
+        }
+    }
+
+    connections.into_values().filter(|&n| n == 1).count()
+}
+
+/// Create faces that close the small, planar holes in the shell
+fn close_holes(
+    faces: &[NewFace],
+    target: &mut Shape,
+    tolerance: Tolerance,
+    max_hole_size: Scalar,
+) -> Vec<NewFace> {
+    // Edges are identified by their vertices, as neighboring faces don't
+    // necessarily share the same edge object.
+    let mut uses: BTreeMap<[Point<3>; 2], (Handle<Edge>, [u8; 4], usize)> =
+        BTreeMap::new();
+    for face in faces {
+        if let Kind::Face {
+            exteriors,
+            interiors,
+            color,
+            ..
+        } = &face.kind
+        {
+            for cycle in exteriors.iter().chain(interiors) {
+                for edge in cycle.get().edges {
+                    if let Some([a, b]) = edge.get().vertices() {
+                        let [a, b] = [a.point(), b.point()];
+                        let key = if a < b { [a, b] } else { [b, a] };
+
+                        uses.entry(key).or_insert((edge, *color, 0)).2 += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    let boundary: Vec<_> = uses
+        .into_iter()
+        .filter(|(_, (_, _, uses))| *uses == 1)
+        .map(|(points, (edge, color, _))| (points, edge, color))
+        .collect();
+
+    let mut edges_at: BTreeMap<Point<3>, Vec<usize>> = BTreeMap::new();
+    for (i, (points, _, _)) in boundary.iter().enumerate() {
+        for &point in points {
+            edges_at.entry(point).or_default().push(i);
+        }
+    }
+
+    let mut used = vec![false; boundary.len()];
+    let mut caps = Vec::new();
+
+    for start in 0..boundary.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+
+        let [first, mut end] = boundary[start].0;
+        let mut points = vec![first];
+        let mut edges = vec![boundary[start].1.clone()];
+
+        let closed = loop {
+            if end == first {
+                break true;
+            }
+            points.push(end);
+
+            let next = edges_at[&end].iter().copied().find(|&i| !used[i]);
+            let next = match next {
+                Some(next) => next,
+                None => break false,
+            };
+            used[next] = true;
+
+            let [p, q] = boundary[next].0;
+            end = if p == end { q } else { p };
+            edges.push(boundary[next].1.clone());
+        };
+
+        if !closed || points.len() < 3 {
+            continue;
+        }
+
+        let color = boundary[start].2;
+        if let Some(cap) =
+            cap(&points, edges, color, target, tolerance, max_hole_size)
+        {
+            caps.push(cap);
+        }
+    }
+
+    caps
+}
+
+/// Create a face that closes the hole bounded by the given points and edges
+///
+/// Returns `None`, if the hole is too large, or not planar.
+fn cap(
+    points: &[Point<3>],
+    edges: Vec<Handle<Edge>>,
+    color: [u8; 4],
+    target: &mut Shape,
+    tolerance: Tolerance,
+    max_hole_size: Scalar,
+) -> Option<NewFace> {
+    for (i, &p) in points.iter().enumerate() {
+        for &q in &points[i + 1..] {
+            if (q - p).magnitude() > max_hole_size {
+                return None;
+            }
+        }
+    }
+
+    // Pick the points that span the plane best, to get its normal as exactly
+    // as possible.
+    let a = points[0];
+    let b = *points
+        .iter()
+        .max_by_key(|&&point| (point - a).magnitude())
+        .unwrap();
+    let c = *points
+        .iter()
+        .max_by_key(|&&point| (b - a).cross(&(point - a)).magnitude())
+        .unwrap();
+
+    let normal = (b - a).cross(&(c - a));
+    if normal.magnitude() == Scalar::ZERO {
+        return None;
+    }
+    let unit_normal = normal.normalize();
+    if points
+        .iter()
+        .any(|&point| unit_normal.dot(&(point - a)).abs() > tolerance.inner())
+    {
+        return None;
+    }
+
+    let sum = points
+        .iter()
+        .fold(Vector::from([0., 0., 0.]), |sum, point| sum + point.coords);
+    let center = Point::origin() + sum / points.len() as f64;
+    let triangles = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(&p, &q)| [p, q, center])
+        .collect();
+
+    // Can't panic. The edges are part of the target shape.
+    let cycle = target.insert(Cycle { edges }).unwrap();
+
+    Some(NewFace {
+        kind: Kind::Face {
+            surface: Surface::SweptCurve(SweptCurve::plane_from_points([
+                a, b, c,
+            ])),
+            exteriors: vec![cycle],
+            interiors: Vec::new(),
+            color,
+        },
+        triangles,
+        samples: vec![(center, normal)],
+    })
+}
+
+/// Indicate whether the face at index `i` points into the shape
+///
+/// A ray that leaves a closed shell has to cross it an even number of times.
+fn points_inward(faces: &[NewFace], i: usize, tolerance: Tolerance) -> bool {
+    let samples = &faces[i].samples;
+
+    let inward = samples
+        .iter()
+        .filter(|&&(origin, direction)| {
+            let direction = direction.normalize();
+
+            let mut hits: Vec<_> = faces
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .flat_map(|(_, face)| &face.triangles)
+                .filter_map(|&triangle| ray_hit(origin, direction, triangle))
+                .collect();
+
+            // A ray that goes through an edge hits both triangles next to it,
+            // but only crosses the shell once.
+            hits.sort();
+            hits.dedup_by(|a, b| (*a - *b).abs() <= tolerance.inner());
+
+            hits.len() % 2 == 1
+        })
+        .count();
+
+    inward * 2 > samples.len()
+}
+
+/// Compute where a ray hits a triangle, as a distance along the ray
+///
+/// This is the Möller–Trumbore algorithm.
+pub(super) fn ray_hit(
+    origin: Point<3>,
+    direction: Vector<3>,
+    [a, b, c]: [Point<3>; 3],
+) -> Option<Scalar> {
+    let ab = b - a;
+    let ac = c - a;
+
+    let p = direction.cross(&ac);
+    let determinant = ab.dot(&p);
+    if determinant == Scalar::ZERO {
+        // The ray is parallel to the triangle.
+        return None;
+    }
+
+    let to_origin = origin - a;
+    let u = to_origin.dot(&p) / determinant;
+    if u < Scalar::ZERO || u > Scalar::ONE {
+        return None;
+    }
+
+    let q = to_origin.cross(&ab);
+    let v = direction.dot(&q) / determinant;
+    if v < Scalar::ZERO || u + v > Scalar::ONE {
+        return None;
+    }
+
+    let t = ac.dot(&q) / determinant;
+    if t <= Scalar::ZERO {
+        return None;
+    }
+
+    Some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar, Transform};
+
+    use crate::{
+        algorithms::Tolerance, geometry::Surface, shape::Shape, topology::Face,
+    };
+
+    use super::heal_shape;
+
+    #[test]
+    fn valid_shape() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        build_box(&mut shape, Orientation::Outward, true, F)?;
+
+        let (healed, report) = heal_shape(shape, tolerance(), Scalar::ONE);
+
+        assert!(report.is_empty(), "{:?}", report);
+        assert_eq!(healed.faces().count(), 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_box() -> anyhow::Result<()> {
+        let f = [1.001, 0., 1.];
+
+        let mut shape = Shape::new();
+        build_box(&mut shape, Orientation::Mixed, false, f)?;
+
+        let (healed, report) =
+            heal_shape(shape, tolerance(), Scalar::from_f64(2.));
+
+        assert_eq!(report.merged_vertices, 1);
+        assert_eq!(report.closed_holes, 1);
+        assert_eq!(report.reversed_faces, 3);
+        assert_eq!(healed.faces().count(), 6);
+        assert!(!healed
+            .vertices()
+            .any(|vertex| vertex.get().point() == Point::from(f)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_holes() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        build_box(&mut shape, Orientation::Outward, false, F)?;
+
+        let (healed, report) = heal_shape(shape, tolerance(), Scalar::ONE);
+
+        assert_eq!(report.closed_holes, 0);
+        assert_eq!(healed.faces().count(), 5);
+
+        Ok(())
+    }
+
+    const F: [f64; 3] = [1., 0., 1.];
+
+    enum Orientation {
+        Outward,
+
+        /// The bottom, back, and left faces point inward
+        Mixed,
+    }
+
+    /// Build a unit cube, optionally without its top face
+    ///
+    /// `f` is the corner at x = 1, y = 0, z = 1, as seen by the right face.
+    fn build_box(
+        shape: &mut Shape,
+        orientation: Orientation,
+        top: bool,
+        f: [f64; 3],
+    ) -> anyhow::Result<()> {
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+        let [e, g, h] = [[0., 0., 1.], [1., 1., 1.], [0., 1., 1.]];
+
+        // Unless reversed, the surfaces of these faces point to the inside.
+        let reverse = |surface: Surface| match orientation {
+            Orientation::Outward => surface.reverse(),
+            Orientation::Mixed => surface,
+        };
+        let offset = |surface: Surface, offset: [f64; 3]| {
+            surface.transform(&Transform::translation(offset))
+        };
+
+        let mut faces = vec![
+            (reverse(Surface::xy_plane()), [a, b, c, d]),
+            (Surface::xz_plane(), [a, b, F, e]),
+            (
+                reverse(offset(Surface::xz_plane(), [0., 1., 0.])),
+                [d, c, g, h],
+            ),
+            (reverse(Surface::yz_plane()), [a, d, h, e]),
+            (offset(Surface::yz_plane(), [1., 0., 0.]), [b, c, g, f]),
+        ];
+        if top {
+            faces.push((
+                offset(Surface::xy_plane(), [0., 0., 1.]),
+                [e, F, g, h],
+            ));
+        }
+
+        for (surface, polygon) in faces {
+            Face::builder(surface, shape)
+                .with_exterior_polygon(polygon)
+                .build()?;
+        }
+
+        Ok(())
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(Scalar::from_f64(0.01)).unwrap()
+    }
+}
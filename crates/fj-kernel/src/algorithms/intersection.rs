@@ -37,7 +37,7 @@ pub fn surface(a: &Surface, b: &Surface) -> Option<Curve> {
 /// Extract a plane in constant-normal form from a `Surface`
 ///
 /// Panics, if the given `Surface` is not a plane.
-fn extract_plane(surface: &Surface) -> (Vector<3>, Scalar) {
+pub(crate) fn extract_plane(surface: &Surface) -> (Vector<3>, Scalar) {
     let Surface::SweptCurve(surface) = surface;
     let line = match surface.curve {
         Curve::Line(line) => line,
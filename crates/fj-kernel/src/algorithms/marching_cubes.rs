@@ -0,0 +1,185 @@
+use fj_math::{Point, Scalar, Vector};
+
+use super::VoxelGrid;
+
+/// Reconstruct the surface of a signed distance field as triangles
+///
+/// The surface is where the distance is zero. Values within the grid are
+/// interpolated linearly, so the result is only as precise as the resolution
+/// of the grid. A shape that reaches the boundary of the grid is cut open
+/// there, which the padding of [`VoxelGrid::sample`] prevents.
+///
+/// The triangles face outward, towards positive distances. Degenerate
+/// triangles, which result from samples that are exactly zero, are left out.
+///
+/// # Implementation note
+///
+/// Instead of the lookup table of the classic marching cubes algorithm, each
+/// cube is split into six tetrahedra, which have only three cases each. This
+/// creates more triangles, but avoids the ambiguous cases of the table, which
+/// can leave holes in the surface.
+pub fn marching_cubes(grid: &VoxelGrid<Scalar>) -> Vec<[Point<3>; 3]> {
+    let mut triangles = Vec::new();
+
+    let [size_x, size_y, size_z] = grid.size;
+    for z in 0..size_z.saturating_sub(1) {
+        for y in 0..size_y.saturating_sub(1) {
+            for x in 0..size_x.saturating_sub(1) {
+                let corners = CORNERS.map(|[dx, dy, dz]| {
+                    let index = [x + dx, y + dy, z + dz];
+
+                    // Can't panic, as we don't go past the last voxel.
+                    let value = *grid.get(index).unwrap();
+                    (grid.center(index), value)
+                });
+
+                for tetrahedron in TETRAHEDRA {
+                    march_tetrahedron(
+                        tetrahedron.map(|i| corners[i]),
+                        &mut triangles,
+                    );
+                }
+            }
+        }
+    }
+
+    triangles
+}
+
+/// The corners of a cube, relative to its first corner
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [1, 1, 0],
+    [0, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [1, 1, 1],
+    [0, 1, 1],
+];
+
+/// The tetrahedra that make up a cube, as indices into [`CORNERS`]
+///
+/// All of them share the diagonal from the first to the seventh corner. That
+/// splits the sides of neighboring cubes along the same diagonals, so the
+/// surface doesn't have any gaps between cubes.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 5, 1, 6],
+    [0, 1, 2, 6],
+    [0, 2, 3, 6],
+    [0, 3, 7, 6],
+    [0, 7, 4, 6],
+    [0, 4, 5, 6],
+];
+
+fn march_tetrahedron(
+    corners: [(Point<3>, Scalar); 4],
+    triangles: &mut Vec<[Point<3>; 3]>,
+) {
+    let (inside, outside): (Vec<_>, Vec<_>) = corners
+        .into_iter()
+        .partition(|&(_, value)| value < Scalar::ZERO);
+
+    let points = match (inside.as_slice(), outside.as_slice()) {
+        ([], _) | (_, []) => return,
+        ([a], [b, c, d]) | ([b, c, d], [a]) => {
+            vec![[crossing(*a, *b), crossing(*a, *c), crossing(*a, *d)]]
+        }
+        ([a, b], [c, d]) => {
+            let quad = [
+                crossing(*a, *c),
+                crossing(*a, *d),
+                crossing(*b, *d),
+                crossing(*b, *c),
+            ];
+            vec![[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]]
+        }
+        _ => unreachable!("A tetrahedron has four corners"),
+    };
+
+    // The direction from the inside to the outside of the shape
+    let outward = centroid(&outside) - centroid(&inside);
+
+    for [a, b, c] in points {
+        let normal = (b - a).cross(&(c - a));
+        if normal.magnitude() == Scalar::ZERO {
+            continue;
+        }
+
+        if normal.dot(&outward) < Scalar::ZERO {
+            triangles.push([a, c, b]);
+        } else {
+            triangles.push([a, b, c]);
+        }
+    }
+}
+
+/// Find the point between two samples, where the distance is zero
+fn crossing(
+    (a, value_a): (Point<3>, Scalar),
+    (b, value_b): (Point<3>, Scalar),
+) -> Point<3> {
+    // Both sides of an edge need to compute the same point, so the surface
+    // doesn't have gaps due to rounding. Hence, the order is normalized.
+    let ((a, value_a), (b, value_b)) = if a < b {
+        ((a, value_a), (b, value_b))
+    } else {
+        ((b, value_b), (a, value_a))
+    };
+
+    // The values have different signs, so this can't divide by zero.
+    let t = value_a / (value_a - value_b);
+    a + (b - a) * t
+}
+
+fn centroid(corners: &[(Point<3>, Scalar)]) -> Point<3> {
+    let sum = corners
+        .iter()
+        .fold(Vector::from([0., 0., 0.]), |sum, (point, _)| {
+            sum + point.coords
+        });
+
+    Point::origin() + sum / Scalar::from_f64(corners.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_interop::mesh::Mesh;
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::algorithms::{mesh_volume, VoxelGrid};
+
+    use super::marching_cubes;
+
+    #[test]
+    fn sphere() {
+        let radius = 1.;
+        let resolution = 0.1;
+
+        let aabb = Aabb {
+            min: Point::from([-radius; 3]),
+            max: Point::from([radius; 3]),
+        };
+        let grid =
+            VoxelGrid::sample(&aabb, Scalar::from_f64(resolution), |point| {
+                point.coords.magnitude() - Scalar::from_f64(radius)
+            });
+
+        let mut mesh = Mesh::new();
+        for triangle in marching_cubes(&grid) {
+            for point in triangle {
+                let error = point.coords.magnitude() - Scalar::from_f64(radius);
+                assert!(error.abs() < Scalar::from_f64(resolution));
+            }
+
+            mesh.push_triangle(triangle, [255, 0, 0, 255]);
+        }
+
+        // The volume is only correct, if the triangles face outward.
+        let volume = mesh_volume(&mesh).into_f64();
+        let expected = 4. / 3. * PI * radius.powi(3);
+        assert!((volume - expected).abs() < expected * 0.02);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Point, Scalar, Vector};
+
+use super::{heal::ray_hit, Tolerance};
+
+/// Compute the volume that a mesh encloses
+///
+/// The mesh must be closed, and its triangles must be wound consistently, as
+/// they are after [`orient_mesh`]. The result is meaningless otherwise.
+///
+/// [`orient_mesh`]: super::orient_mesh
+pub fn mesh_volume(mesh: &Mesh<Point<3>>) -> Scalar {
+    let volume = mesh
+        .triangles()
+        .map(|triangle| {
+            let [a, b, c] = triangle.points.map(|point| point.coords);
+            a.dot(&b.cross(&c))
+        })
+        .fold(Scalar::ZERO, |sum, volume| sum + volume);
+
+    volume / 6.
+}
+
+/// Estimate the thickness of the thinnest wall of a mesh
+///
+/// Returns `None`, if no wall thickness can be measured, which can happen for
+/// open meshes. Please refer to [`find_thin_walls`] for how thickness is
+/// measured.
+pub fn mesh_wall_thickness(
+    mesh: &Mesh<Point<3>>,
+    tolerance: Tolerance,
+) -> Option<Scalar> {
+    let triangles: Vec<_> =
+        mesh.triangles().map(|triangle| triangle.points).collect();
+
+    wall_thicknesses(&triangles, tolerance)
+        .into_iter()
+        .flatten()
+        .min()
+}
+
+/// Find the regions of a mesh where the wall is thinner than `threshold`
+///
+/// From the center of each triangle, a ray is cast into the material, opposite
+/// the direction the triangle faces. The distance to the next triangle it hits
+/// is the thickness of the wall at that point. Hits closer than `tolerance` are
+/// ignored, as those come from neighboring triangles.
+///
+/// Triangles that are too thin and share a vertex are collected into a single
+/// [`ThinWall`]. The mesh is expected to face outward, as it does after
+/// [`orient_mesh`].
+///
+/// # Implementation note
+///
+/// Each ray is tested against every triangle, so this takes quadratic time in
+/// the number of triangles. That's fine for checking a model once, but would
+/// be too slow to do continuously.
+///
+/// Thickness is only measured at the center of each triangle, so a wall that
+/// is thin only where large triangles meet can go unnoticed.
+///
+/// [`orient_mesh`]: super::orient_mesh
+pub fn find_thin_walls(
+    mesh: &Mesh<Point<3>>,
+    threshold: Scalar,
+    tolerance: Tolerance,
+) -> Vec<ThinWall> {
+    let triangles: Vec<_> =
+        mesh.triangles().map(|triangle| triangle.points).collect();
+    let thicknesses = wall_thicknesses(&triangles, tolerance);
+
+    let is_thin = |i: usize| {
+        thicknesses[i].map_or(false, |thickness| thickness < threshold)
+    };
+
+    let mut triangles_at_point: BTreeMap<Point<3>, Vec<usize>> =
+        BTreeMap::new();
+    for (i, triangle) in triangles.iter().enumerate() {
+        if is_thin(i) {
+            for &point in triangle {
+                triangles_at_point.entry(point).or_default().push(i);
+            }
+        }
+    }
+
+    let mut visited = vec![false; triangles.len()];
+    let mut thin_walls = Vec::new();
+
+    for start in 0..triangles.len() {
+        if visited[start] || !is_thin(start) {
+            continue;
+        }
+        visited[start] = true;
+
+        let mut region = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(i) = queue.pop_front() {
+            region.push(i);
+
+            for point in &triangles[i] {
+                for &j in &triangles_at_point[point] {
+                    if !visited[j] {
+                        visited[j] = true;
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+
+        // Can't panic. The region contains at least `start`, and only thin
+        // triangles, which have a thickness.
+        let thinnest = region
+            .iter()
+            .copied()
+            .min_by_key(|&i| thicknesses[i].unwrap())
+            .unwrap();
+
+        region.sort_unstable();
+        thin_walls.push(ThinWall {
+            triangles: region,
+            thickness: thicknesses[thinnest].unwrap(),
+            position: center(triangles[thinnest]),
+        });
+    }
+
+    thin_walls
+}
+
+/// A region of a mesh where the wall is too thin
+///
+/// Returned by [`find_thin_walls`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThinWall {
+    /// The indices of the triangles that make up the region
+    ///
+    /// This refers to the order in which `Mesh::triangles` returns them.
+    pub triangles: Vec<usize>,
+
+    /// The thickness at the thinnest point of the region
+    pub thickness: Scalar,
+
+    /// The thinnest point of the region, on the surface of the mesh
+    pub position: Point<3>,
+}
+
+/// Measure the wall thickness at the center of each triangle
+fn wall_thicknesses(
+    triangles: &[[Point<3>; 3]],
+    tolerance: Tolerance,
+) -> Vec<Option<Scalar>> {
+    triangles
+        .iter()
+        .enumerate()
+        .map(|(i, &[a, b, c])| {
+            let normal = (b - a).cross(&(c - a));
+            if normal.magnitude() == Scalar::ZERO {
+                return None;
+            }
+
+            let origin = center([a, b, c]);
+            let direction: Vector<3> = -normal.normalize();
+
+            triangles
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .filter_map(|(_, &triangle)| {
+                    ray_hit(origin, direction, triangle)
+                })
+                .filter(|&distance| distance > tolerance.inner())
+                .min()
+        })
+        .collect()
+}
+
+fn center([a, b, c]: [Point<3>; 3]) -> Point<3> {
+    Point::origin() + (a.coords + b.coords + c.coords) / Scalar::from_f64(3.)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{algorithms::Tolerance, testing::fixtures::box_mesh};
+
+    use super::{find_thin_walls, mesh_volume, mesh_wall_thickness};
+
+    #[test]
+    fn measure_box() {
+        let mesh = box_mesh([1., 2., 3.]);
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        assert_eq!(mesh_volume(&mesh), Scalar::from_f64(6.));
+
+        let thickness = mesh_wall_thickness(&mesh, tolerance).unwrap();
+        assert!((thickness - Scalar::ONE).abs() < Scalar::from_f64(1e-9));
+    }
+
+    #[test]
+    fn thin_walls() {
+        let mesh = box_mesh([1., 2., 3.]);
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        // Only the rays cast from the two sides that are 1 apart are short
+        // enough. Those sides don't touch, so they form separate regions.
+        let thin_walls =
+            find_thin_walls(&mesh, Scalar::from_f64(1.5), tolerance);
+        assert_eq!(thin_walls.len(), 2);
+        for thin_wall in &thin_walls {
+            assert_eq!(thin_wall.triangles.len(), 2);
+            assert!(
+                (thin_wall.thickness - Scalar::ONE).abs()
+                    < Scalar::from_f64(1e-9)
+            );
+        }
+
+        assert!(
+            find_thin_walls(&mesh, Scalar::from_f64(0.5), tolerance).is_empty()
+        );
+    }
+}
@@ -4,13 +4,44 @@
 //! on their respective purpose.
 
 mod approx;
+mod bvh;
+mod canonicalize;
+mod decimate;
+mod deviation;
+mod diff;
+mod draft;
+mod heal;
+mod marching_cubes;
+mod measure;
+mod offset;
+mod orient;
+mod slice;
 mod sweep;
+mod texture;
 mod triangulation;
+mod voxel;
+mod weld;
 
 pub mod intersection;
 
 pub use self::{
-    approx::{CycleApprox, FaceApprox, Tolerance},
+    approx::{
+        number_of_segments, ApproxCache, CycleApprox, FaceApprox, Tolerance,
+    },
+    canonicalize::canonicalize_mesh,
+    decimate::{decimate_mesh, DecimationTarget},
+    deviation::{mesh_deviation, Deviation},
+    diff::{diff_meshes, MeshDiff, ADDED_COLOR, REMOVED_COLOR},
+    draft::{analyze_draft, Draft, DraftAnalysis, DraftCheck},
+    heal::{heal_shape, HealReport},
+    marching_cubes::marching_cubes,
+    measure::{find_thin_walls, mesh_volume, mesh_wall_thickness, ThinWall},
+    offset::offset_mesh,
+    orient::{orient_mesh, NonOrientable, OrientReport},
+    slice::{slice, Polygon},
     sweep::sweep_shape,
+    texture::{texture_mesh, Pattern, Texture},
     triangulation::triangulate,
+    voxel::{signed_distance_field, voxelize, VoxelGrid},
+    weld::{weld_mesh, WeldReport},
 };
@@ -0,0 +1,107 @@
+use fj_interop::mesh::Mesh;
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use super::{marching_cubes, voxel::sample_signed_distance, Tolerance};
+
+/// Grow a mesh by `radius` in every direction
+///
+/// This is the Minkowski sum of the mesh with a sphere: Every point within
+/// `radius` of the mesh is part of the result. Flat faces move outward, while
+/// convex edges and corners become round. Concave edges stay sharp.
+///
+/// The signed distance to the mesh is sampled on a grid, as it is by
+/// [`signed_distance_field`], and the surface where it equals `radius` is
+/// reconstructed by [`marching_cubes`]. So `resolution` is the edge length of
+/// the voxels, and the result is only as precise as that. Detail that is
+/// smaller than `resolution` gets lost. The mesh must be closed.
+///
+/// # Implementation note
+///
+/// Like [`signed_distance_field`], this takes time proportional to the number
+/// of voxels times the number of triangles of the mesh.
+///
+/// # Panics
+///
+/// Panics, if `radius` or `resolution` is not positive.
+///
+/// [`signed_distance_field`]: super::signed_distance_field
+pub fn offset_mesh(
+    mesh: &Mesh<Point<3>>,
+    radius: Scalar,
+    resolution: Scalar,
+    tolerance: Tolerance,
+) -> Vec<[Point<3>; 3]> {
+    assert!(radius > Scalar::ZERO, "Offset radius must be positive");
+
+    let aabb = Aabb::<3>::from_points(mesh.vertices());
+    let margin = Vector::from([radius; 3]);
+    let aabb = Aabb {
+        min: aabb.min - margin,
+        max: aabb.max + margin,
+    };
+
+    let mut grid = sample_signed_distance(mesh, &aabb, resolution, tolerance);
+    for distance in &mut grid.values {
+        *distance = *distance - radius;
+    }
+
+    marching_cubes(&grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_interop::mesh::Mesh;
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{mesh_volume, Tolerance},
+        testing::fixtures::box_mesh,
+    };
+
+    use super::offset_mesh;
+
+    #[test]
+    fn offset_box() {
+        let [a, b, c] = [1., 2., 3.];
+        let radius = 0.5;
+        let resolution = 0.1;
+
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+        let triangles = offset_mesh(
+            &box_mesh([a, b, c]),
+            Scalar::from_f64(radius),
+            Scalar::from_f64(resolution),
+            tolerance,
+        );
+
+        let mut mesh = Mesh::new();
+        for triangle in triangles {
+            for point in triangle {
+                // The distance from the point to the box
+                let distance = [a, b, c]
+                    .into_iter()
+                    .zip(point.coords.components)
+                    .map(|(size, coord)| {
+                        let coord = coord.into_f64();
+                        (coord - coord.clamp(0., size)).powi(2)
+                    })
+                    .sum::<f64>()
+                    .sqrt();
+                assert!((distance - radius).abs() < resolution);
+            }
+
+            mesh.push_triangle(triangle, [255, 0, 0, 255]);
+        }
+
+        // The box, plus a slab on each side, a quarter cylinder along each
+        // edge, and an eighth of a sphere at each corner
+        let volume = mesh_volume(&mesh).into_f64();
+        let expected = a * b * c
+            + 2. * radius * (a * b + b * c + c * a)
+            + PI * radius.powi(2) * (a + b + c)
+            + 4. / 3. * PI * radius.powi(3);
+        assert!((volume - expected).abs() < expected * 0.02);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use fj_interop::mesh::{Attribute, Channel, Mesh};
+use fj_math::{Point, Scalar};
+
+/// Make the winding of a mesh's triangles consistent
+///
+/// Triangles that share an edge must run along that edge in opposite
+/// directions, if they are wound consistently. This is used to propagate the
+/// orientation of one triangle to all triangles that are connected to it,
+/// flipping those that don't fit.
+///
+/// Each set of connected triangles (a shell) is oriented as a whole, after
+/// that. Closed shells are oriented to face outward, meaning they enclose a
+/// positive volume. Open shells keep the orientation of most of their
+/// triangles.
+///
+/// Returns the oriented mesh, as well as an [`OrientReport`] with some
+/// statistics about the orientation. Attribute channels are carried over, and
+/// normals of flipped triangles are reversed.
+///
+/// Returns [`NonOrientable`], if a shell can't be oriented consistently, like
+/// a Möbius strip.
+///
+/// # Implementation note
+///
+/// Orientation is only propagated across edges that are shared by exactly two
+/// triangles. Triangles that only connect via non-manifold edges end up in
+/// separate shells.
+pub fn orient_mesh(
+    mesh: &Mesh<Point<3>>,
+) -> Result<(Mesh<Point<3>>, OrientReport), NonOrientable> {
+    let triangles: Vec<_> = mesh.triangles().collect();
+
+    // For each edge, the triangles that contain it, and whether each triangle
+    // runs along it in the same direction as the key.
+    let mut edges: BTreeMap<[Point<3>; 2], Vec<(usize, bool)>> =
+        BTreeMap::new();
+    for (i, triangle) in triangles.iter().enumerate() {
+        let [a, b, c] = triangle.points;
+
+        for [a, b] in [[a, b], [b, c], [c, a]] {
+            let (edge, forward) = if a < b {
+                ([a, b], true)
+            } else {
+                ([b, a], false)
+            };
+            edges.entry(edge).or_default().push((i, forward));
+        }
+    }
+
+    let mut neighbors = vec![Vec::new(); triangles.len()];
+    let mut is_closed = vec![true; triangles.len()];
+    for (edge, uses) in &edges {
+        match uses.as_slice() {
+            &[(i, forward_i), (j, forward_j)] => {
+                // The triangles are consistent, if they run along the edge in
+                // opposite directions. If they don't, one must be flipped.
+                let flip = forward_i == forward_j;
+
+                neighbors[i].push((j, flip, *edge));
+                neighbors[j].push((i, flip, *edge));
+            }
+            &[(i, _)] => is_closed[i] = false,
+            _ => {}
+        }
+    }
+
+    let mut report = OrientReport::default();
+    let mut flipped: Vec<Option<bool>> = vec![None; triangles.len()];
+
+    for start in 0..triangles.len() {
+        if flipped[start].is_some() {
+            continue;
+        }
+        report.shells += 1;
+
+        let mut shell = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        flipped[start] = Some(false);
+
+        while let Some(i) = queue.pop_front() {
+            shell.push(i);
+
+            // Can't panic. Triangles are assigned a value, before they are
+            // added to the queue.
+            let flipped_i = flipped[i].unwrap();
+
+            for &(j, flip, edge) in &neighbors[i] {
+                let flipped_j = flipped_i != flip;
+
+                match flipped[j] {
+                    Some(flipped) if flipped != flipped_j => {
+                        return Err(NonOrientable { edge });
+                    }
+                    Some(_) => {}
+                    None => {
+                        flipped[j] = Some(flipped_j);
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+
+        let reverse = if shell.iter().all(|&i| is_closed[i]) {
+            let volume: Scalar = shell
+                .iter()
+                .map(|&i| {
+                    let [a, b, c] = triangles[i].points.map(|p| p.coords);
+                    let volume = a.dot(&b.cross(&c));
+
+                    // Can't panic. All triangles of the shell are assigned a
+                    // value above.
+                    if flipped[i].unwrap() {
+                        -volume
+                    } else {
+                        volume
+                    }
+                })
+                .fold(Scalar::ZERO, |sum, volume| sum + volume);
+
+            volume < Scalar::ZERO
+        } else {
+            let num_flipped =
+                shell.iter().filter(|&&i| flipped[i] == Some(true)).count();
+
+            num_flipped * 2 > shell.len()
+        };
+
+        if reverse {
+            for &i in &shell {
+                flipped[i] = flipped[i].map(|flipped| !flipped);
+            }
+        }
+    }
+
+    let flipped: Vec<_> = flipped.into_iter().map(Option::unwrap).collect();
+
+    let mut oriented = Mesh::new();
+    let mut corners = Vec::new();
+    for (i, triangle) in triangles.iter().enumerate() {
+        let [a, b, c] = triangle.points;
+
+        if flipped[i] {
+            report.flipped_triangles += 1;
+
+            oriented.push_triangle_with_face(
+                [a, c, b],
+                triangle.color,
+                triangle.face,
+            );
+            corners.extend([i * 3, i * 3 + 2, i * 3 + 1]);
+        } else {
+            oriented.push_triangle_with_face(
+                [a, b, c],
+                triangle.color,
+                triangle.face,
+            );
+            corners.extend([i * 3, i * 3 + 1, i * 3 + 2]);
+        }
+    }
+
+    for (attribute, channel) in mesh.channels() {
+        let mut channel = channel.select_corners(&corners);
+
+        if let (Attribute::Normal, Channel::Vectors(normals)) =
+            (attribute, &mut channel)
+        {
+            for (corner, normal) in normals.iter_mut().enumerate() {
+                if flipped[corner / 3] {
+                    *normal = -*normal;
+                }
+            }
+        }
+
+        oriented.set_channel(attribute.clone(), channel);
+    }
+
+    Ok((oriented, report))
+}
+
+/// Statistics produced by [`orient_mesh`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OrientReport {
+    /// The number of sets of connected triangles in the mesh
+    pub shells: usize,
+
+    /// The number of triangles whose winding was reversed
+    pub flipped_triangles: usize,
+}
+
+/// Returned by [`orient_mesh`], if a mesh can't be oriented consistently
+#[derive(Clone, Copy, Debug, thiserror::Error)]
+#[error(
+    "Mesh can't be oriented consistently; triangles along edge from {:?} to \
+    {:?} contradict each other",
+    .edge[0],
+    .edge[1]
+)]
+pub struct NonOrientable {
+    /// An edge, across which the orientation contradicts itself
+    pub edge: [Point<3>; 2],
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    use super::orient_mesh;
+
+    #[test]
+    fn inconsistent_triangle() {
+        let [a, b, c, d] = points();
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, c, b], COLOR);
+        mesh.push_triangle([a, b, d], COLOR);
+        mesh.push_triangle([a, c, d], COLOR); // wrong way around
+        mesh.push_triangle([b, c, d], COLOR);
+
+        let (oriented, report) = orient_mesh(&mesh).unwrap();
+
+        assert_eq!(report.shells, 1);
+        assert_eq!(report.flipped_triangles, 1);
+        assert!(contains_wound(&oriented, [a, d, c]));
+    }
+
+    #[test]
+    fn closed_shell() {
+        let [a, b, c, d] = points();
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], COLOR);
+        mesh.push_triangle([a, d, b], COLOR);
+        mesh.push_triangle([a, c, d], COLOR);
+        mesh.push_triangle([b, d, c], COLOR);
+
+        let (oriented, report) = orient_mesh(&mesh).unwrap();
+
+        assert_eq!(report.flipped_triangles, 4);
+        assert!(contains_wound(&oriented, [a, c, b]));
+        assert!(contains_wound(&oriented, [b, c, d]));
+    }
+
+    #[test]
+    fn moebius_strip() {
+        let n = 8;
+
+        // The edge of the strip at angle `t`. It makes half a turn around the
+        // center line, as `t` goes around the circle once.
+        let edges = |t: f64| {
+            let center = [t.cos() * 2., t.sin() * 2., 0.];
+            let offset = [
+                t.cos() * (t / 2.).cos(),
+                t.sin() * (t / 2.).cos(),
+                (t / 2.).sin(),
+            ];
+
+            let [p, q] = [1., -1.].map(|sign: f64| {
+                Point::from([0, 1, 2].map(|i| center[i] + sign * offset[i]))
+            });
+            [p, q]
+        };
+
+        let mut mesh = Mesh::new();
+        for i in 0..n {
+            let [p0, q0] = edges(2. * PI * i as f64 / n as f64);
+
+            // At the end, the strip connects to its start, upside down.
+            let [p1, q1] = if i + 1 < n {
+                edges(2. * PI * (i + 1) as f64 / n as f64)
+            } else {
+                let [p, q] = edges(0.);
+                [q, p]
+            };
+
+            mesh.push_triangle([p0, q0, q1], COLOR);
+            mesh.push_triangle([p0, q1, p1], COLOR);
+        }
+
+        assert!(orient_mesh(&mesh).is_err());
+    }
+
+    const COLOR: [u8; 4] = [255, 0, 0, 255];
+
+    /// Like `Mesh::contains_triangle`, but the winding must match too
+    fn contains_wound(mesh: &Mesh<Point<3>>, [a, b, c]: [Point<3>; 3]) -> bool {
+        mesh.triangles().any(|triangle| {
+            [[a, b, c], [b, c, a], [c, a, b]].contains(&triangle.points)
+        })
+    }
+
+    fn points() -> [Point<3>; 4] {
+        [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+            .map(Point::from)
+    }
+}
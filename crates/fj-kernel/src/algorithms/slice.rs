@@ -0,0 +1,366 @@
+use std::collections::BTreeMap;
+
+use fj_interop::{debug::DebugInfo, mesh::Mesh, progress::Progress};
+use fj_math::{Point, Scalar, Vector};
+use tracing::debug_span;
+
+use crate::{geometry::Surface, shape::Shape};
+
+use super::{intersection::extract_plane, triangulate, Tolerance};
+
+/// Compute the cross-section of a shape with a plane
+///
+/// The shape is approximated within the given tolerance, then intersected with
+/// the plane. Each closed contour of the cross-section is returned as a
+/// [`Polygon`]. Exterior contours are counter-clockwise, and the contours of
+/// holes are clockwise, when looking at the plane from the side its normal
+/// points to.
+///
+/// Faces that lie within the plane are part of the cross-section, if the shape
+/// is on the side of the plane that its normal points to. Slicing a shape at
+/// its bottom returns the outline of its bottom face, while slicing it at its
+/// top returns nothing. This makes sure that stacking slices doesn't count
+/// anything twice.
+///
+/// Contours that are not closed, because the shape is not watertight, are
+/// dropped.
+///
+/// # Panics
+///
+/// Panics, if `plane` is not a plane.
+pub fn slice(
+    shape: &Shape,
+    plane: &Surface,
+    tolerance: Tolerance,
+) -> Vec<Polygon> {
+    let _span = debug_span!("slice").entered();
+
+    // Nothing else has access to this `Progress`, so nothing can cancel it.
+    let mesh = triangulate(
+        shape.clone(),
+        tolerance,
+        &mut DebugInfo::new(),
+        &Progress::new(),
+    )
+    .expect("Triangulation was cancelled, but nothing could cancel it");
+
+    slice_mesh(&mesh, plane)
+}
+
+/// A closed contour within a plane
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Polygon {
+    surface: Surface,
+    points: Vec<Point<3>>,
+}
+
+impl Polygon {
+    /// Access the plane that the polygon lies in
+    pub fn surface(&self) -> &Surface {
+        &self.surface
+    }
+
+    /// Access the points of the polygon, in model coordinates
+    ///
+    /// The polygon is closed, so its last point connects back to the first.
+    pub fn points(&self) -> &[Point<3>] {
+        &self.points
+    }
+
+    /// Compute the points of the polygon, in surface coordinates
+    pub fn surface_points(&self) -> Vec<Point<2>> {
+        self.points
+            .iter()
+            .map(|&point| self.surface.point_model_to_surface(point).native())
+            .collect()
+    }
+
+    /// Indicate whether the polygon is the contour of a hole
+    pub fn is_hole(&self) -> bool {
+        let points = self.surface_points();
+
+        let mut area = Scalar::ZERO;
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            area += a.u * b.v - b.u * a.v;
+        }
+
+        area < Scalar::ZERO
+    }
+}
+
+/// A line segment where the plane intersects a triangle
+struct Segment {
+    points: [Point<3>; 2],
+
+    /// The direction that keeps the shape on the left, within the plane
+    direction: Vector<3>,
+}
+
+fn slice_mesh(mesh: &Mesh<Point<3>>, plane: &Surface) -> Vec<Polygon> {
+    let (normal, distance) = extract_plane(plane);
+
+    // Points this close to the plane are considered to be on it.
+    let epsilon = Scalar::from_f64(1e-9) * distance.abs().max(Scalar::ONE);
+    let distance_to = |point: &Point<3>| {
+        let d = normal.dot(&point.coords) - distance;
+        if d.abs() <= epsilon {
+            Scalar::ZERO
+        } else {
+            d
+        }
+    };
+
+    let mut segments = Vec::new();
+
+    for triangle in mesh.triangles() {
+        let points = triangle.points;
+        let distances = [
+            distance_to(&points[0]),
+            distance_to(&points[1]),
+            distance_to(&points[2]),
+        ];
+
+        let [a, b, c] = points;
+        let triangle_normal = (b - a).cross(&(c - a));
+        if triangle_normal.magnitude() == Scalar::ZERO {
+            continue;
+        }
+        let direction = normal.cross(&triangle_normal);
+
+        let on: Vec<_> =
+            (0..3).filter(|&i| distances[i] == Scalar::ZERO).collect();
+        let above = distances.iter().filter(|&&d| d > Scalar::ZERO).count();
+        let below = distances.iter().filter(|&&d| d < Scalar::ZERO).count();
+
+        let segment = match on[..] {
+            // The triangle lies within the plane. Whether its outline is part
+            // of the cross-section is decided by its neighbors.
+            [_, _, _] => None,
+            [i, j] if above == 1 => Some([points[i], points[j]]),
+            [i] if above == 1 && below == 1 => {
+                let [j, k] = [(i + 1) % 3, (i + 2) % 3];
+                let crossing = crossing(
+                    (points[j], distances[j]),
+                    (points[k], distances[k]),
+                );
+                Some([points[i], crossing])
+            }
+            [] if above > 0 && below > 0 => {
+                let mut crossings = (0..3).filter_map(|i| {
+                    let j = (i + 1) % 3;
+                    if (distances[i] > Scalar::ZERO)
+                        == (distances[j] > Scalar::ZERO)
+                    {
+                        return None;
+                    }
+
+                    Some(crossing(
+                        (points[i], distances[i]),
+                        (points[j], distances[j]),
+                    ))
+                });
+
+                // A triangle that crosses the plane has exactly two edges that
+                // do so.
+                crossings.next().zip(crossings.next()).map(|(a, b)| [a, b])
+            }
+            // The triangle only touches the plane, or doesn't reach it.
+            _ => None,
+        };
+
+        if let Some(points) = segment {
+            segments.push(Segment { points, direction });
+        }
+    }
+
+    chain(segments)
+        .into_iter()
+        .map(|points| Polygon {
+            surface: *plane,
+            points,
+        })
+        .collect()
+}
+
+/// Compute the point where the plane crosses the edge between two points
+fn crossing(p: (Point<3>, Scalar), q: (Point<3>, Scalar)) -> Point<3> {
+    // Neighboring triangles share edges. Always computing the crossing in the
+    // same order makes sure they get the same result, to the last bit.
+    let ((p, p_distance), (q, q_distance)) =
+        if p.0 < q.0 { (p, q) } else { (q, p) };
+
+    let t = p_distance / (p_distance - q_distance);
+    p + (q - p) * t
+}
+
+/// Connect segments with shared points into closed contours
+fn chain(segments: Vec<Segment>) -> Vec<Vec<Point<3>>> {
+    let mut segments_at: BTreeMap<_, Vec<usize>> = BTreeMap::new();
+    for (i, segment) in segments.iter().enumerate() {
+        for point in segment.points {
+            segments_at.entry(point).or_default().push(i);
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut contours = Vec::new();
+
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+
+        let [a, b] = segments[start].points;
+        let mut points = vec![a, b];
+
+        // The triangle meshes that make up a shape aren't necessarily wound
+        // consistently, so every segment gets a vote on the direction of the
+        // contour.
+        let mut votes = (b - a).dot(&segments[start].direction);
+
+        let closed = loop {
+            let end = *points.last().unwrap();
+            if points.len() > 2 && end == a {
+                break true;
+            }
+
+            let next = segments_at[&end].iter().copied().find(|&i| !used[i]);
+            let next = match next {
+                Some(next) => next,
+                None => break false,
+            };
+            used[next] = true;
+
+            let segment = &segments[next];
+            let [p, q] = segment.points;
+            let point = if p == end { q } else { p };
+
+            votes += (point - end).dot(&segment.direction);
+            points.push(point);
+        };
+
+        if !closed {
+            continue;
+        }
+
+        points.pop();
+        let mut points = remove_collinear(points);
+        if points.len() < 3 {
+            continue;
+        }
+        if votes < Scalar::ZERO {
+            points.reverse();
+        }
+
+        contours.push(points);
+    }
+
+    contours
+}
+
+/// Remove points that lie on a straight line between their neighbors
+///
+/// Faces are cut into triangles, so the cross-section of a single face is
+/// usually made up of many segments.
+fn remove_collinear(points: Vec<Point<3>>) -> Vec<Point<3>> {
+    let n = points.len();
+
+    (0..n)
+        .filter(|&i| {
+            let prev = points[(i + n - 1) % n];
+            let next = points[(i + 1) % n];
+            let point = points[i];
+
+            let u = point - prev;
+            let v = next - point;
+
+            let is_collinear = u.cross(&v).magnitude()
+                <= u.magnitude() * v.magnitude() * Scalar::from_f64(1e-9)
+                && u.dot(&v) > Scalar::ZERO;
+            !is_collinear
+        })
+        .map(|i| points[i])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar, Transform, Vector};
+
+    use crate::{
+        algorithms::{sweep_shape, Tolerance},
+        geometry::Surface,
+        shape::Shape,
+        topology::Face,
+    };
+
+    use super::slice;
+
+    fn frame() -> anyhow::Result<Shape> {
+        let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
+
+        let mut sketch = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut sketch)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [4., 0., 0.],
+                [4., 4., 0.],
+                [0., 4., 0.],
+            ])
+            .with_interior_polygon([
+                [1., 1., 0.],
+                [3., 1., 0.],
+                [3., 3., 0.],
+                [1., 3., 0.],
+            ])
+            .build()?;
+
+        Ok(sweep_shape(
+            sketch,
+            Vector::from([0., 0., 1.]),
+            tolerance,
+            [255, 0, 0, 255],
+        ))
+    }
+
+    fn xy_plane_at(z: f64) -> Surface {
+        Surface::xy_plane().transform(&Transform::translation([0., 0., z]))
+    }
+
+    #[test]
+    fn exterior_and_hole() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
+
+        let mut polygons = slice(&frame()?, &xy_plane_at(0.5), tolerance);
+        polygons.sort_by_key(|polygon| polygon.is_hole());
+
+        assert_eq!(polygons.len(), 2);
+        assert!(!polygons[0].is_hole());
+        assert!(polygons[1].is_hole());
+
+        for polygon in &polygons {
+            assert_eq!(polygon.points().len(), 4);
+            for point in polygon.points() {
+                assert_eq!(point.z, Scalar::from_f64(0.5));
+            }
+        }
+        assert!(polygons[0].points().contains(&Point::from([4., 4., 0.5])));
+        assert!(polygons[1].points().contains(&Point::from([3., 3., 0.5])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn faces_in_plane() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
+        let shape = frame()?;
+
+        assert_eq!(slice(&shape, &xy_plane_at(0.), tolerance).len(), 2);
+        assert!(slice(&shape, &xy_plane_at(1.), tolerance).is_empty());
+        assert!(slice(&shape, &xy_plane_at(2.), tolerance).is_empty());
+
+        Ok(())
+    }
+}
@@ -1,22 +1,39 @@
 use std::collections::HashMap;
 
 use fj_math::{Transform, Triangle, Vector};
+use tracing::debug_span;
 
 use crate::{
     geometry::{Surface, SweptCurve},
-    shape::{Handle, Shape},
+    shape::{Attributes, Handle, Shape},
     topology::{Cycle, Edge, Face, Vertex},
 };
 
-use super::{CycleApprox, Tolerance};
+use super::{ApproxCache, CycleApprox, Tolerance};
 
 /// Create a new shape by sweeping an existing one
+///
+/// Attributes are carried over from the source shape. The bottom and top faces
+/// and edges get the attributes of the objects they were created from. Side
+/// faces get the attributes of the source edge they were swept from, or those
+/// of the source face, if that edge has none.
+///
+/// # Implementation note
+///
+/// Side faces are created one edge at a time, on a single thread. Each of them
+/// needs to be inserted into the target shape, which requires mutable access,
+/// so all that could be done in parallel is the approximation of continuous
+/// edges. That's too little work to justify the overhead, but this should be
+/// revisited, once side faces are no longer created from approximations, or
+/// the shape supports concurrent insertion.
 pub fn sweep_shape(
     source: Shape,
     path: Vector<3>,
     tolerance: Tolerance,
     color: [u8; 4],
 ) -> Shape {
+    let _span = debug_span!("sweep_shape").entered();
+
     let mut target = Shape::new();
 
     let translation = Transform::translation(path);
@@ -65,6 +82,10 @@ pub fn sweep_shape(
             })
             .unwrap();
 
+        let attributes = edge_source.attributes();
+        target.set_attributes(&edge_bottom, attributes.clone());
+        target.set_attributes(&edge_top, attributes);
+
         source_to_bottom
             .edges
             .insert(edge_source.clone(), edge_bottom);
@@ -89,8 +110,27 @@ pub fn sweep_shape(
         source_to_top.cycles.insert(cycle_source, cycle_top);
     }
 
+    // The attributes of the face each cycle belongs to, as a fallback for the
+    // side faces.
+    let mut face_attributes_for_cycle = HashMap::new();
+
     // Create top faces.
-    for face_source in source.faces().values() {
+    for face_source in source.faces() {
+        let attributes = face_source.attributes();
+        let face_source = face_source.get();
+
+        if let Face::Face {
+            exteriors,
+            interiors,
+            ..
+        } = &face_source
+        {
+            for cycle in exteriors.iter().chain(interiors) {
+                face_attributes_for_cycle
+                    .insert(cycle.clone(), attributes.clone());
+            }
+        }
+
         let surface = face_source.surface();
 
         let surface_bottom = target.insert(surface.reverse()).unwrap();
@@ -104,7 +144,7 @@ pub fn sweep_shape(
         let exteriors_top = source_to_top.exteriors_for_face(&face_source);
         let interiors_top = source_to_top.interiors_for_face(&face_source);
 
-        target
+        let face_bottom = target
             .insert(Face::Face {
                 surface: surface_bottom,
                 exteriors: exteriors_bottom,
@@ -112,7 +152,7 @@ pub fn sweep_shape(
                 color,
             })
             .unwrap();
-        target
+        let face_top = target
             .insert(Face::Face {
                 surface: surface_top,
                 exteriors: exteriors_top,
@@ -120,8 +160,25 @@ pub fn sweep_shape(
                 color,
             })
             .unwrap();
+
+        target.set_attributes(&face_bottom, attributes.clone());
+        target.set_attributes(&face_top, attributes);
     }
 
+    let side_face_attributes = |edge: &Handle<Edge>, cycle: &Handle<Cycle>| {
+        let attributes = edge.attributes();
+        if attributes.is_empty() {
+            face_attributes_for_cycle
+                .get(cycle)
+                .cloned()
+                .unwrap_or_else(Attributes::new)
+        } else {
+            attributes
+        }
+    };
+
+    let mut cache = ApproxCache::new();
+
     for cycle_source in source.cycles() {
         if cycle_source.get().edges.len() == 1 {
             // If there's only one edge in the cycle, it must be a continuous
@@ -136,7 +193,8 @@ pub fn sweep_shape(
             // This is the last piece of code that still uses the triangle
             // representation.
 
-            let approx = CycleApprox::new(&cycle_source.get(), tolerance);
+            let approx =
+                CycleApprox::new(&cycle_source.get(), tolerance, &mut cache);
 
             let mut quads = Vec::new();
             for segment in approx.segments() {
@@ -156,7 +214,14 @@ pub fn sweep_shape(
                 side_face.push(([v0, v2, v3].into(), color));
             }
 
-            target.insert(Face::Triangles(side_face)).unwrap();
+            let face = target.insert(Face::Triangles(side_face)).unwrap();
+            target.set_attributes(
+                &face,
+                side_face_attributes(
+                    &cycle_source.get().edges[0],
+                    &cycle_source,
+                ),
+            );
         } else {
             // If there's no continuous edge, we can create the non-
             // continuous faces using boundary representation.
@@ -231,7 +296,7 @@ pub fn sweep_shape(
                     })
                     .unwrap();
 
-                target
+                let face = target
                     .insert(Face::Face {
                         surface,
                         exteriors: vec![cycle],
@@ -239,6 +304,10 @@ pub fn sweep_shape(
                         color,
                     })
                     .unwrap();
+                target.set_attributes(
+                    &face,
+                    side_face_attributes(edge_source, &cycle_source),
+                );
             }
         }
     }
@@ -319,7 +388,7 @@ mod tests {
     use crate::{
         algorithms::Tolerance,
         geometry::{Surface, SweptCurve},
-        shape::{Handle, Shape},
+        shape::{Attribute, Handle, Shape},
         topology::{Cycle, Edge, Face},
     };
 
@@ -371,6 +440,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn carry_over_attributes() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
+
+        let mut sketch =
+            Triangle::new([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]], false)?;
+
+        let face = sketch.face.clone();
+        sketch
+            .shape
+            .insert_attribute(&face, Attribute::Name("sketch".into()));
+
+        let edge = sketch.shape.edges().next().unwrap();
+        sketch
+            .shape
+            .insert_attribute(&edge, Attribute::Tag("edge".into()));
+
+        let swept = sweep_shape(
+            sketch.shape,
+            Vector::from([0., 0., 1.]),
+            tolerance,
+            [255, 0, 0, 255],
+        );
+
+        let named = swept
+            .faces()
+            .filter(|face| face.attributes().name() == Some("sketch"))
+            .count();
+        let tagged = swept
+            .faces()
+            .filter(|face| face.attributes().has_tag("edge"))
+            .count();
+
+        // Bottom, top, and the side faces of the untagged edges.
+        assert_eq!(named, 4);
+        assert_eq!(tagged, 1);
+
+        Ok(())
+    }
+
     pub struct Triangle {
         shape: Shape,
         face: Handle<Face>,
@@ -0,0 +1,349 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    f64::consts::PI,
+};
+
+use fj_interop::mesh::{FaceId, Mesh, Triangle};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::geometry::{Curve, Surface, SweptCurve};
+
+/// Apply textures to faces of a triangle mesh
+///
+/// `faces` maps each face that should be textured to its texture, and to the
+/// surface that the face was created from. The pattern is laid out in the
+/// coordinates of that surface, scaled to their length in the model.
+///
+/// The triangles of textured faces are split, until none of their edges is
+/// longer than an eighth of the pitch. Neighboring triangles are split along
+/// the same edges. Then each vertex is displaced along its normal, by the
+/// amplitude times the value of the pattern at that vertex.
+///
+/// Vertices on the boundary of a textured face are not displaced, so the mesh
+/// stays watertight. The boundary is found through shared vertices, so the mesh
+/// needs to be welded and oriented, as it is after [`weld_mesh`] and
+/// [`orient_mesh`].
+///
+/// # Implementation note
+///
+/// Splitting stops early, once the mesh has reached about a million triangles.
+/// A texture whose pitch is very small compared to the size of its face ends
+/// up coarser than intended, as a result.
+///
+/// [`weld_mesh`]: super::weld_mesh
+/// [`orient_mesh`]: super::orient_mesh
+pub fn texture_mesh(
+    mesh: &Mesh<Point<3>>,
+    faces: &BTreeMap<FaceId, (Texture, Surface)>,
+) -> Mesh<Point<3>> {
+    let textured = |triangle: &Triangle| {
+        triangle
+            .face
+            .and_then(|face| faces.get(&face))
+            .filter(|(texture, _)| texture.pitch > Scalar::ZERO)
+    };
+
+    let mut triangles: Vec<_> = mesh.triangles().collect();
+    while triangles.len() < MAX_TRIANGLES {
+        let mut midpoints = HashMap::new();
+
+        for triangle in &triangles {
+            let (texture, _) = match textured(triangle) {
+                Some(textured) => textured,
+                None => continue,
+            };
+            let max_length =
+                texture.pitch / Scalar::from_f64(SAMPLES_PER_PITCH);
+
+            for [a, b] in edges(triangle.points) {
+                if (b - a).magnitude() > max_length {
+                    midpoints.insert([a, b], midpoint([a, b]));
+                }
+            }
+        }
+
+        if midpoints.is_empty() {
+            break;
+        }
+
+        triangles = triangles
+            .into_iter()
+            .flat_map(|triangle| split_triangle(triangle, &midpoints))
+            .collect();
+    }
+
+    // A vertex can only be displaced, if all triangles around it belong to the
+    // same textured face. Otherwise, it would tear the mesh open.
+    let mut vertex_faces = HashMap::new();
+    let mut normals = HashMap::new();
+
+    for triangle in &triangles {
+        let face = triangle.face.filter(|_| textured(triangle).is_some());
+
+        let [a, b, c] = triangle.points;
+        let normal = (b - a).cross(&(c - a));
+
+        for point in triangle.points {
+            let vertex_face = vertex_faces.entry(point).or_insert(face);
+            if *vertex_face != face {
+                *vertex_face = None;
+            }
+
+            // The cross product is weighted by the area of the triangle, so
+            // small triangles don't skew the normal.
+            let sum = normals
+                .entry(point)
+                .or_insert_with(|| Vector::from([0., 0., 0.]));
+            *sum = *sum + normal;
+        }
+    }
+
+    let displace = |point: Point<3>| {
+        let (texture, surface) = match vertex_faces[&point] {
+            Some(face) => &faces[&face],
+            None => return point,
+        };
+
+        let normal: Vector<3> = normals[&point];
+        if normal.magnitude() == Scalar::ZERO {
+            return point;
+        }
+
+        let displacement = texture.amplitude * texture.value_at(surface, point);
+        point + normal.normalize() * displacement
+    };
+
+    let mut textured_mesh = Mesh::new();
+    for triangle in triangles {
+        textured_mesh.push_triangle_with_face(
+            triangle.points.map(&displace),
+            triangle.color,
+            triangle.face,
+        );
+    }
+
+    textured_mesh
+}
+
+/// A repeating pattern, that is applied to a face by displacing its surface
+///
+/// Used by [`texture_mesh`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Texture {
+    /// The shape of the pattern
+    pub pattern: Pattern,
+
+    /// How far the surface is displaced, at the highest point of the pattern
+    ///
+    /// Positive values raise the pattern out of the surface, negative values
+    /// cut it into the surface.
+    pub amplitude: Scalar,
+
+    /// The distance over which the pattern repeats
+    ///
+    /// On faces that wrap around, like the side of a cylinder, the pitch is
+    /// adjusted slightly, so the pattern lines up with itself at the seam.
+    /// Textures without a positive pitch are ignored.
+    pub pitch: Scalar,
+}
+
+impl Texture {
+    /// Compute the value of the pattern at a point, between `0` and `1`
+    fn value_at(&self, surface: &Surface, point: Point<3>) -> Scalar {
+        let scale = [[1., 0.], [0., 1.]].map(|direction| {
+            surface
+                .vector_surface_to_model(&Vector::from(direction))
+                .magnitude()
+        });
+
+        let pitch = match surface {
+            Surface::SweptCurve(SweptCurve {
+                curve: Curve::Circle(_),
+                ..
+            }) => {
+                // A full turn of the circle has to be a multiple of the pitch,
+                // or there'd be a step in the pattern where the turn ends.
+                let circumference = scale[0] * Scalar::PI * 2.;
+                let repetitions =
+                    (circumference / self.pitch).round().max(Scalar::ONE);
+                circumference / repetitions
+            }
+            _ => self.pitch,
+        };
+
+        let point = surface.point_model_to_surface(point).native();
+        let [s, t] = [0, 1].map(|i| {
+            (point.coords.components[i] * scale[i] / pitch).into_f64()
+        });
+
+        let value = match self.pattern {
+            Pattern::Knurl => triangle_wave(s + t).min(triangle_wave(s - t)),
+            Pattern::Ripple => 0.5 - 0.5 * (s * PI * 2.).cos(),
+        };
+
+        Scalar::from_f64(value)
+    }
+}
+
+/// The shape of a [`Texture`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pattern {
+    /// A diamond knurl, made of pyramids
+    ///
+    /// The grooves between the pyramids run diagonally, relative to the
+    /// coordinates of the surface.
+    Knurl,
+
+    /// Parallel ridges with a smooth profile
+    ///
+    /// The ridges run along the second coordinate of the surface. On the side
+    /// of a swept circle, they run along the direction of the sweep.
+    Ripple,
+}
+
+/// The number of vertices per pitch, along each edge of a textured face
+const SAMPLES_PER_PITCH: f64 = 8.;
+
+/// Triangles are no longer split, once the mesh has this many
+const MAX_TRIANGLES: usize = 1_000_000;
+
+/// A wave that rises linearly from `0` to `1` and back, with a period of `1`
+fn triangle_wave(x: f64) -> f64 {
+    1. - (2. * (x - x.floor()) - 1.).abs()
+}
+
+/// The edges of a triangle, with their points in a canonical order
+fn edges([a, b, c]: [Point<3>; 3]) -> [[Point<3>; 2]; 3] {
+    [[a, b], [b, c], [c, a]].map(|mut edge| {
+        edge.sort();
+        edge
+    })
+}
+
+/// The midpoint of an edge, whose points are in canonical order
+///
+/// The triangles on both sides of an edge need to compute exactly the same
+/// midpoint, or the mesh would be torn open.
+fn midpoint([a, b]: [Point<3>; 2]) -> Point<3> {
+    Point::origin() + (a.coords + b.coords) / Scalar::TWO
+}
+
+/// Split a triangle at the midpoints of those of its edges that are split
+///
+/// The new triangles keep the orientation, color, and face of the original one.
+fn split_triangle(
+    triangle: Triangle,
+    midpoints: &HashMap<[Point<3>; 2], Point<3>>,
+) -> Vec<Triangle> {
+    let points = triangle.points;
+    let splits = edges(points).map(|edge| midpoints.get(&edge).copied());
+
+    // Rotate the points, so the first edge is the one at index `i`.
+    let rotate = |i: usize| {
+        let points = [0, 1, 2].map(|j| points[(i + j) % 3]);
+        let splits = [0, 1, 2].map(|j| splits[(i + j) % 3]);
+        (points, splits)
+    };
+
+    let num_splits = splits.iter().flatten().count();
+    let new_points = match num_splits {
+        0 => return vec![triangle],
+        1 => {
+            // Can't panic, as there's one split.
+            let i = splits.iter().position(Option::is_some).unwrap();
+            let ([a, b, c], [m_ab, _, _]) = rotate(i);
+            let m_ab = m_ab.unwrap();
+
+            vec![[a, m_ab, c], [m_ab, b, c]]
+        }
+        2 => {
+            // The edge that isn't split needs to come last. Can't panic, as
+            // there's one such edge.
+            let i = splits.iter().position(Option::is_none).unwrap();
+            let ([a, b, c], [m_ab, m_bc, _]) = rotate((i + 1) % 3);
+            let [m_ab, m_bc] = [m_ab.unwrap(), m_bc.unwrap()];
+
+            // The remaining quadrilateral is split along its shorter diagonal,
+            // which leaves better-shaped triangles.
+            if (m_bc - a).magnitude() <= (c - m_ab).magnitude() {
+                vec![[m_ab, b, m_bc], [a, m_ab, m_bc], [a, m_bc, c]]
+            } else {
+                vec![[m_ab, b, m_bc], [a, m_ab, c], [m_ab, m_bc, c]]
+            }
+        }
+        _ => {
+            let ([a, b, c], [m_ab, m_bc, m_ca]) = rotate(0);
+            let [m_ab, m_bc, m_ca] =
+                [m_ab.unwrap(), m_bc.unwrap(), m_ca.unwrap()];
+
+            vec![
+                [a, m_ab, m_ca],
+                [m_ab, b, m_bc],
+                [m_ca, m_bc, c],
+                [m_ab, m_bc, m_ca],
+            ]
+        }
+    };
+
+    new_points
+        .into_iter()
+        .map(|points| Triangle { points, ..triangle })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use fj_interop::mesh::FaceId;
+    use fj_math::Scalar;
+
+    use crate::{geometry::Surface, testing::fixtures::box_mesh_with_faces};
+
+    use super::{edges, texture_mesh, Pattern, Texture};
+
+    #[test]
+    fn watertight() {
+        // A unit cube, with one face per side. Face 1 is the top.
+        let mesh = box_mesh_with_faces([1., 1., 1.]);
+
+        // Texture the top face.
+        let texture = Texture {
+            pattern: Pattern::Ripple,
+            amplitude: Scalar::from_f64(0.1),
+            pitch: Scalar::from_f64(0.5),
+        };
+        let faces =
+            BTreeMap::from([(FaceId(1), (texture, Surface::xy_plane()))]);
+
+        let textured = texture_mesh(&mesh, &faces);
+        assert!(textured.triangles().count() > mesh.triangles().count());
+
+        let mut edge_count = HashMap::new();
+        for triangle in textured.triangles() {
+            for edge in edges(triangle.points) {
+                *edge_count.entry(edge).or_insert(0) += 1;
+            }
+        }
+        assert!(edge_count.values().all(|&count| count == 2));
+
+        let mut max_z = Scalar::ZERO;
+        for point in
+            textured.triangles_of_face(FaceId(1)).flat_map(|t| t.points)
+        {
+            // The boundary of the face stays where it was.
+            let [x, y, z] = point.coords.components;
+            if [x, y]
+                .iter()
+                .any(|&c| c == Scalar::ZERO || c == Scalar::ONE)
+            {
+                assert_eq!(z, Scalar::ONE);
+            }
+
+            assert!(z >= Scalar::ONE);
+            max_z = max_z.max(z);
+        }
+        assert!(max_z > Scalar::from_f64(1.05));
+        assert!(max_z <= Scalar::from_f64(1.1 + 1e-9));
+    }
+}
@@ -2,29 +2,63 @@ mod delaunay;
 mod polygon;
 mod ray;
 
-use fj_interop::{debug::DebugInfo, mesh::Mesh};
-use fj_math::Point;
+use fj_interop::{
+    debug::{DebugCategory, DebugInfo},
+    mesh::{Attribute, Channel, FaceId, Mesh},
+    progress::{Cancelled, Progress},
+};
+use fj_math::{Point, Scalar};
+use tracing::debug_span;
 
 use crate::{shape::Shape, topology::Face};
 
 use self::polygon::Polygon;
 
-use super::{FaceApprox, Tolerance};
+use super::{ApproxCache, FaceApprox, Tolerance};
 
 /// Triangulate a shape
+///
+/// Reports one unit of work per face to `progress`, and returns early, if it
+/// has been cancelled.
 pub fn triangulate(
     shape: Shape,
     tolerance: Tolerance,
     debug_info: &mut DebugInfo,
-) -> Mesh<Point<3>> {
+    progress: &Progress,
+) -> Result<Mesh<Point<3>>, Cancelled> {
+    let _span = debug_span!("triangulate").entered();
+
     let mut mesh = Mesh::new();
 
-    for face in shape.faces() {
+    progress.add_work(shape.faces().count());
+
+    // All faces share the same cache, to make sure that edges shared between
+    // faces are approximated identically, and the resulting mesh is watertight.
+    let mut cache = ApproxCache::new();
+
+    // How far each triangle deviates from the surface it approximates, for
+    // each of its corners.
+    let mut deviations = Vec::new();
+
+    for (i, face) in shape.faces().enumerate() {
+        progress.check()?;
+
+        let face_id = Some(FaceId(i as u64));
         let face = face.get();
         match &face {
             Face::Face { surface, color, .. } => {
                 let surface = surface.get();
-                let approx = FaceApprox::new(&face, tolerance);
+                let approx = FaceApprox::new(&face, tolerance, &mut cache);
+
+                for cycle in
+                    Some(&approx.exterior).into_iter().chain(&approx.interiors)
+                {
+                    debug_info.polyline(
+                        DebugCategory::Approximation,
+                        cycle.points.iter().copied(),
+                        true,
+                    );
+                }
 
                 let points: Vec<_> = approx
                     .points
@@ -64,23 +98,48 @@ pub fn triangulate(
 
                 for triangle in triangles {
                     let points = triangle.map(|point| point.canonical());
-                    mesh.push_triangle(points, *color);
+                    mesh.push_triangle_with_face(points, *color, face_id);
+
+                    // Measure the deviation at the center of the triangle.
+                    // That's not necessarily where it's largest, but it's a
+                    // good enough estimate.
+                    let [a, b, c] = triangle.map(|point| point.native());
+                    let center_native = a + ((b - a) + (c - a)) / 3.;
+                    let [a, b, c] = points;
+                    let center_canonical = a + ((b - a) + (c - a)) / 3.;
+                    let deviation = (surface
+                        .point_surface_to_model(&center_native)
+                        - center_canonical)
+                        .magnitude();
+
+                    deviations.extend([deviation; 3]);
                 }
             }
             Face::Triangles(triangles) => {
                 for &(triangle, color) in triangles {
-                    mesh.push_triangle(triangle.points(), color);
+                    mesh.push_triangle_with_face(
+                        triangle.points(),
+                        color,
+                        face_id,
+                    );
+
+                    // These triangles don't approximate anything.
+                    deviations.extend([Scalar::ZERO; 3]);
                 }
             }
         }
+
+        progress.complete_work(1);
     }
 
-    mesh
+    mesh.set_channel(Attribute::SurfaceDeviation, Channel::Scalars(deviations));
+
+    Ok(mesh)
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_interop::{debug::DebugInfo, mesh::Mesh};
+    use fj_interop::{debug::DebugInfo, mesh::Mesh, progress::Progress};
     use fj_math::{Point, Scalar};
 
     use crate::{
@@ -148,6 +207,7 @@ mod tests {
         let tolerance = Tolerance::from_scalar(Scalar::ONE).unwrap();
 
         let mut debug_info = DebugInfo::new();
-        super::triangulate(shape, tolerance, &mut debug_info)
+        super::triangulate(shape, tolerance, &mut debug_info, &Progress::new())
+            .unwrap()
     }
 }
@@ -0,0 +1,296 @@
+use fj_interop::mesh::Mesh;
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use super::{heal::ray_hit, Tolerance};
+
+/// Determine which voxels of a grid are inside of a mesh
+///
+/// The grid covers the bounding box of the mesh, plus one voxel on each side.
+/// `resolution` is the edge length of each voxel. A voxel is inside, if its
+/// center is.
+///
+/// Whether a point is inside is determined by casting a ray from it, and
+/// counting how often the ray crosses the mesh. Hits within `tolerance` of each
+/// other are counted once, as those come from a ray that goes through an edge.
+/// The mesh must be closed, or the result is meaningless.
+///
+/// # Panics
+///
+/// Panics, if `resolution` is not positive.
+pub fn voxelize(
+    mesh: &Mesh<Point<3>>,
+    resolution: Scalar,
+    tolerance: Tolerance,
+) -> VoxelGrid<bool> {
+    let triangles: Vec<_> =
+        mesh.triangles().map(|triangle| triangle.points).collect();
+
+    let aabb = Aabb::<3>::from_points(mesh.vertices());
+    VoxelGrid::sample(&aabb, resolution, |point| {
+        is_inside(point, &triangles, tolerance)
+    })
+}
+
+/// Compute a signed distance field of a mesh
+///
+/// Each voxel holds the distance from its center to the nearest point of the
+/// mesh, which is negative inside of the mesh. Please refer to [`voxelize`]
+/// for the extent of the grid, and how inside and outside are told apart.
+///
+/// # Implementation note
+///
+/// The distance to every triangle is computed for every voxel, so this takes
+/// time proportional to the number of voxels times the number of triangles.
+/// Halving the resolution makes it eight times slower.
+///
+/// # Panics
+///
+/// Panics, if `resolution` is not positive.
+pub fn signed_distance_field(
+    mesh: &Mesh<Point<3>>,
+    resolution: Scalar,
+    tolerance: Tolerance,
+) -> VoxelGrid<Scalar> {
+    let aabb = Aabb::<3>::from_points(mesh.vertices());
+    sample_signed_distance(mesh, &aabb, resolution, tolerance)
+}
+
+/// Sample the signed distance to a mesh on a grid that covers `aabb`
+///
+/// See [`signed_distance_field`].
+pub(super) fn sample_signed_distance(
+    mesh: &Mesh<Point<3>>,
+    aabb: &Aabb<3>,
+    resolution: Scalar,
+    tolerance: Tolerance,
+) -> VoxelGrid<Scalar> {
+    let triangles: Vec<_> = mesh
+        .triangles()
+        .map(|triangle| triangle.points)
+        // Degenerate triangles are covered by their neighbors.
+        .filter(|&[a, b, c]| {
+            (b - a).cross(&(c - a)).magnitude() != Scalar::ZERO
+        })
+        .collect();
+
+    VoxelGrid::sample(aabb, resolution, |point| {
+        let distance = triangles
+            .iter()
+            .map(|&triangle| {
+                (point - closest_point(point, triangle)).magnitude()
+            })
+            .min()
+            .unwrap_or(Scalar::MAX);
+
+        if is_inside(point, &triangles, tolerance) {
+            -distance
+        } else {
+            distance
+        }
+    })
+}
+
+/// A regular grid of voxels, holding a value each
+///
+/// Returned by [`voxelize`] and [`signed_distance_field`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoxelGrid<T> {
+    /// The corner of the grid with the smallest coordinates
+    pub min: Point<3>,
+
+    /// The edge length of each voxel
+    pub resolution: Scalar,
+
+    /// The number of voxels along each axis
+    pub size: [usize; 3],
+
+    /// The value of each voxel
+    ///
+    /// Stored in x-major order, meaning the voxel at `[x, y, z]` is at index
+    /// `x + size[0] * (y + size[1] * z)`.
+    pub values: Vec<T>,
+}
+
+impl<T> VoxelGrid<T> {
+    /// Sample a function at the center of each voxel of a grid
+    ///
+    /// The grid covers `aabb`, plus one voxel on each side. `resolution` is the
+    /// edge length of each voxel.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `resolution` is not positive.
+    pub fn sample(
+        aabb: &Aabb<3>,
+        resolution: Scalar,
+        mut f: impl FnMut(Point<3>) -> T,
+    ) -> Self {
+        assert!(
+            resolution > Scalar::ZERO,
+            "Voxel resolution must be positive"
+        );
+
+        let padding = Vector::from([resolution; 3]);
+        let size = aabb
+            .size()
+            .components
+            .map(|extent| (extent / resolution).ceil().into_f64() as usize + 2);
+
+        let mut grid = Self {
+            min: aabb.min - padding,
+            resolution,
+            size,
+            values: Vec::new(),
+        };
+        grid.values =
+            grid.indices().map(|index| f(grid.center(index))).collect();
+
+        grid
+    }
+
+    /// Access the value of the voxel at the given index
+    ///
+    /// Returns `None`, if the index is outside of the grid.
+    pub fn get(&self, [x, y, z]: [usize; 3]) -> Option<&T> {
+        let [size_x, size_y, size_z] = self.size;
+        if x >= size_x || y >= size_y || z >= size_z {
+            return None;
+        }
+
+        self.values.get(x + size_x * (y + size_y * z))
+    }
+
+    /// Compute the center of the voxel at the given index
+    pub fn center(&self, index: [usize; 3]) -> Point<3> {
+        let offset = index.map(|i| Scalar::from_f64(i as f64 + 0.5));
+        self.min + Vector::from(offset) * self.resolution
+    }
+
+    /// Iterate over the indices of all voxels, in the order of `values`
+    pub fn indices(&self) -> impl Iterator<Item = [usize; 3]> {
+        let [size_x, size_y, size_z] = self.size;
+
+        (0..size_z).flat_map(move |z| {
+            (0..size_y).flat_map(move |y| (0..size_x).map(move |x| [x, y, z]))
+        })
+    }
+}
+
+/// Indicate whether a point is inside of a closed mesh
+///
+/// A ray that leaves a closed shell has to cross it an odd number of times.
+pub(super) fn is_inside(
+    point: Point<3>,
+    triangles: &[[Point<3>; 3]],
+    tolerance: Tolerance,
+) -> bool {
+    let direction = Vector::from([1., 0., 0.]);
+
+    let mut hits: Vec<_> = triangles
+        .iter()
+        .filter_map(|&triangle| ray_hit(point, direction, triangle))
+        .collect();
+
+    hits.sort();
+    hits.dedup_by(|a, b| (*a - *b).abs() <= tolerance.inner());
+
+    hits.len() % 2 == 1
+}
+
+/// Find the point of a triangle that is closest to `point`
+///
+/// The triangle must not be degenerate. This is the algorithm from "Real-Time
+/// Collision Detection" by Christer Ericson, which determines first, which
+/// region of the triangle the point projects into.
+pub(super) fn closest_point(
+    point: Point<3>,
+    [a, b, c]: [Point<3>; 3],
+) -> Point<3> {
+    let ab = b - a;
+    let ac = c - a;
+
+    let ap = point - a;
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= Scalar::ZERO && d2 <= Scalar::ZERO {
+        return a;
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= Scalar::ZERO && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= Scalar::ZERO && d1 >= Scalar::ZERO && d3 <= Scalar::ZERO {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= Scalar::ZERO && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= Scalar::ZERO && d2 >= Scalar::ZERO && d6 <= Scalar::ZERO {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= Scalar::ZERO && d4 >= d3 && d5 >= d6 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    // The point projects into the inside of the triangle.
+    let denominator = va + vb + vc;
+    a + ab * (vb / denominator) + ac * (vc / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{algorithms::Tolerance, testing::fixtures::box_mesh};
+
+    use super::{signed_distance_field, voxelize};
+
+    #[test]
+    fn voxelize_box() {
+        let mesh = box_mesh([1., 2., 3.]);
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let grid = voxelize(&mesh, Scalar::from_f64(0.5), tolerance);
+
+        // The box is 2 by 4 by 6 voxels, with one more on each side.
+        assert_eq!(grid.size, [4, 6, 8]);
+        assert_eq!(grid.values.iter().filter(|&&inside| inside).count(), 48);
+        assert_eq!(grid.get([1, 1, 1]), Some(&true));
+        assert_eq!(grid.get([0, 1, 1]), Some(&false));
+        assert_eq!(grid.get([4, 1, 1]), None);
+    }
+
+    #[test]
+    fn signed_distance_inside() {
+        let mesh = box_mesh([1., 2., 3.]);
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let grid =
+            signed_distance_field(&mesh, Scalar::from_f64(0.5), tolerance);
+
+        assert_eq!(grid.center([1, 1, 1]), Point::from([0.25, 0.25, 0.25]));
+        let distance = |index| *grid.get(index).unwrap();
+        let approx_eq = |a: Scalar, b: f64| {
+            (a - Scalar::from_f64(b)).abs() < Scalar::from_f64(1e-9)
+        };
+
+        assert!(approx_eq(distance([1, 1, 1]), -0.25));
+        assert!(approx_eq(distance([0, 1, 1]), 0.25));
+
+        // Outside of the box, diagonally from a corner.
+        assert!(approx_eq(distance([0, 0, 0]), 0.1875_f64.sqrt()));
+    }
+}
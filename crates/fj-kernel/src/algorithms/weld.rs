@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Point, Scalar};
+
+use super::Tolerance;
+
+/// Weld the vertices of a mesh, making it watertight
+///
+/// Vertices that are closer to each other than `tolerance` are merged into a
+/// single vertex. Triangles that degenerate as a result of this (or were
+/// degenerate to begin with) are removed.
+///
+/// Returns the welded mesh, as well as a [`WeldReport`] that contains some
+/// statistics about the welding, and the edge-manifoldness of the result.
+///
+/// # Implementation note
+///
+/// Triangulation shares edge approximations between neighboring faces, so
+/// most vertices that are supposed to be identical already are. This pass is a
+/// safety net for any cases that this doesn't cover.
+pub fn weld_mesh(
+    mesh: &Mesh<Point<3>>,
+    tolerance: Tolerance,
+) -> (Mesh<Point<3>>, WeldReport) {
+    let mut report = WeldReport::default();
+
+    let mut grid = VertexGrid::new(tolerance.inner());
+    let mut welded = Mesh::new();
+    let mut kept = Vec::new();
+
+    for (i, triangle) in mesh.triangles().enumerate() {
+        let points = triangle.points.map(|point| {
+            let (welded, was_welded) = grid.weld(point);
+            if was_welded {
+                report.welded_vertices += 1;
+            }
+            welded
+        });
+
+        if is_degenerate(points) {
+            report.degenerate_triangles += 1;
+            continue;
+        }
+
+        welded.push_triangle_with_face(points, triangle.color, triangle.face);
+        kept.push(i);
+    }
+
+    for (attribute, channel) in mesh.channels() {
+        welded.set_channel(attribute.clone(), channel.select_triangles(&kept));
+    }
+
+    let mut edges = HashMap::new();
+    for triangle in welded.triangles() {
+        let [a, b, c] = triangle.points;
+
+        for [a, b] in [[a, b], [b, c], [c, a]] {
+            // Edges are counted regardless of their direction.
+            let edge = if a < b { [a, b] } else { [b, a] };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for num_triangles in edges.into_values() {
+        match num_triangles {
+            1 => report.boundary_edges += 1,
+            2 => {}
+            _ => report.non_manifold_edges += 1,
+        }
+    }
+
+    (welded, report)
+}
+
+/// Statistics produced by [`weld_mesh`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WeldReport {
+    /// The number of triangle vertices that were moved onto another vertex
+    pub welded_vertices: usize,
+
+    /// The number of triangles that were removed, because they are degenerate
+    pub degenerate_triangles: usize,
+
+    /// The number of edges that are only referenced by a single triangle
+    pub boundary_edges: usize,
+
+    /// The number of edges that are referenced by more than two triangles
+    pub non_manifold_edges: usize,
+}
+
+impl WeldReport {
+    /// Indicate whether the welded mesh is watertight
+    ///
+    /// A mesh is considered watertight, if every edge is shared by exactly two
+    /// triangles.
+    pub fn is_watertight(&self) -> bool {
+        self.boundary_edges == 0 && self.non_manifold_edges == 0
+    }
+}
+
+/// Spatial grid for finding vertices close to a given point
+///
+/// Cells are as large as the weld distance, so any vertex within the weld
+/// distance of a point must be located in the point's cell, or one of the
+/// neighboring cells.
+pub(super) struct VertexGrid {
+    distance: Scalar,
+    cells: HashMap<[i64; 3], Vec<Point<3>>>,
+}
+
+impl VertexGrid {
+    pub(super) fn new(distance: Scalar) -> Self {
+        Self {
+            distance,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Return the vertex a point is welded to
+    ///
+    /// Also returns whether that vertex is different from the point.
+    pub(super) fn weld(&mut self, point: Point<3>) -> (Point<3>, bool) {
+        let cell = self.cell(point);
+
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    let neighbor = [cell[0] + x, cell[1] + y, cell[2] + z];
+
+                    for &vertex in
+                        self.cells.get(&neighbor).into_iter().flatten()
+                    {
+                        if vertex == point {
+                            return (vertex, false);
+                        }
+                        if (vertex - point).magnitude() <= self.distance {
+                            return (vertex, true);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.cells.entry(cell).or_default().push(point);
+        (point, false)
+    }
+
+    fn cell(&self, point: Point<3>) -> [i64; 3] {
+        point
+            .coords
+            .components
+            .map(|c| (c.into_f64() / self.distance.into_f64()).floor() as i64)
+    }
+}
+
+fn is_degenerate([a, b, c]: [Point<3>; 3]) -> bool {
+    (b - a).cross(&(c - a)).magnitude() == Scalar::ZERO
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::{Point, Scalar};
+
+    use crate::algorithms::Tolerance;
+
+    #[test]
+    fn weld_mesh() {
+        let tolerance = Tolerance::from_scalar(Scalar::from_f64(0.01)).unwrap();
+        let color = [255, 0, 0, 255];
+
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        // Slightly off copy of `a`, which must be welded to it.
+        let a2 = Point::from([0.001, 0., 0.]);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, c, b], color);
+        mesh.push_triangle([a2, b, d], color);
+        mesh.push_triangle([a, d, c], color);
+        mesh.push_triangle([b, c, d], color);
+
+        // Degenerate triangle, which must be removed.
+        mesh.push_triangle([a, a2, b], color);
+
+        let (welded, report) = super::weld_mesh(&mesh, tolerance);
+
+        assert_eq!(report.welded_vertices, 2);
+        assert_eq!(report.degenerate_triangles, 1);
+        assert!(report.is_watertight());
+
+        assert_eq!(welded.triangles().count(), 4);
+        assert!(welded.contains_triangle([a, b, d]));
+        assert!(!welded.vertices().any(|vertex| vertex == a2));
+    }
+
+    #[test]
+    fn weld_mesh_with_boundary() {
+        let tolerance = Tolerance::from_scalar(Scalar::from_f64(0.01)).unwrap();
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from),
+            [255, 0, 0, 255],
+        );
+
+        let (_, report) = super::weld_mesh(&mesh, tolerance);
+
+        assert_eq!(report.boundary_edges, 3);
+        assert!(!report.is_watertight());
+    }
+}
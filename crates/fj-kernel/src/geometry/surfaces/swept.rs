@@ -14,7 +14,6 @@ pub struct SweptCurve {
 
 impl SweptCurve {
     /// Construct a plane from 3 points
-    #[cfg(test)]
     pub fn plane_from_points([a, b, c]: [Point<3>; 3]) -> Self {
         use crate::geometry::Line;
 
@@ -91,3 +91,6 @@ pub mod algorithms;
 pub mod geometry;
 pub mod shape;
 pub mod topology;
+
+#[cfg(test)]
+mod testing;
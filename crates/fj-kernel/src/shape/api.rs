@@ -6,17 +6,35 @@ use crate::{
 };
 
 use super::{
+    journal::{Checkpoint, Group, Insert, Journal, Remove, Replace},
     stores::{
         Curves, Cycles, Edges, Faces, Points, Stores, Surfaces, Vertices,
     },
-    Handle, Iter, Object, ValidationResult,
+    Attribute, Attributes, Handle, Iter, Object, ValidationResult,
 };
 
 /// The boundary representation of a shape
+///
+/// # Concurrency
+///
+/// `Shape` is `Send` and `Sync`, so a shared reference to it can be used to
+/// read it from multiple threads at the same time, for example to process its
+/// faces in parallel. Handles can be resolved from any thread too.
+///
+/// Cloning a shape doesn't copy its objects. All clones share the same
+/// objects, and changes made through any of them are visible through all
+/// others. All methods that modify the shape require `&mut self`, but as
+/// anyone with a shared reference can clone the shape, that doesn't guarantee
+/// that the shape doesn't change while it is being read.
+///
+/// Each clone has its own history, however. Undoing a change only works on the
+/// shape it was made through, and a [`Snapshot`] can't be restored into a
+/// clone.
 #[derive(Clone, Debug)]
 pub struct Shape {
     min_distance: Scalar,
     stores: Stores,
+    journal: Journal,
 }
 
 impl Shape {
@@ -38,6 +56,7 @@ impl Shape {
                 cycles: Cycles::new(),
                 faces: Faces::new(),
             },
+            journal: Journal::new(),
         }
     }
 
@@ -58,6 +77,14 @@ impl Shape {
         self
     }
 
+    /// Access the minimum distance of this shape
+    ///
+    /// Vertices must be at least this far apart from each other. Builders also
+    /// use it, to decide whether points are identical.
+    pub fn min_distance(&self) -> Scalar {
+        self.min_distance
+    }
+
     /// Insert an object into the shape
     ///
     /// Validates the object, and returns an error if it is not valid. See the
@@ -68,9 +95,151 @@ impl Shape {
     {
         object.validate(self.min_distance, &self.stores)?;
         let handle = self.stores.get::<T>().insert(object);
+
+        self.journal.record(Insert {
+            object: handle.clone(),
+        });
+
         Ok(handle)
     }
 
+    /// Replace an object of the shape with another
+    ///
+    /// Validates the new object, and returns an error if it is not valid. Any
+    /// objects that refer to the replaced object, refer to the new one
+    /// afterwards, through the same handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object is not part of this shape.
+    pub fn replace<T>(
+        &mut self,
+        object: &Handle<T>,
+        new: T,
+    ) -> ValidationResult<T>
+    where
+        T: Object,
+    {
+        let mut store = self.stores.get::<T>();
+
+        // The object that is being replaced must not take part in the
+        // validation of its replacement. Otherwise, a vertex could never be
+        // replaced by one that is close to it, for example.
+        store.remove(object);
+        let result = new.validate(self.min_distance, &self.stores);
+        store.restore(object);
+        result?;
+
+        let old = store.replace(object, new.clone());
+        self.journal.record(Replace {
+            object: object.clone(),
+            old,
+            new,
+        });
+
+        Ok(object.clone())
+    }
+
+    /// Remove an object from the shape
+    ///
+    /// The handle of the object can still be used to access it, but the object
+    /// is no longer part of the shape. Objects that refer to it are not
+    /// updated, and new objects that refer to it won't pass validation.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object is not part of this shape.
+    pub fn remove<T>(&mut self, object: &Handle<T>)
+    where
+        T: Object,
+    {
+        self.stores.get::<T>().remove(object);
+        self.journal.record(Remove {
+            object: object.clone(),
+        });
+    }
+
+    /// Make multiple changes to the shape, all or nothing
+    ///
+    /// Calls `f` with the shape. If `f` returns an error, all changes it made
+    /// are reverted, and the objects it inserted are no longer part of the
+    /// shape. Otherwise, the changes are kept, and undone as one by
+    /// [`Shape::undo`].
+    ///
+    /// Each object is still validated when it is inserted, so objects that are
+    /// inserted later in the transaction can refer to those inserted earlier.
+    /// Transactions can be nested.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `f` undoes changes that were made before the transaction.
+    pub fn transaction<F, R, E>(&mut self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&mut Self) -> Result<R, E>,
+    {
+        let checkpoint = self.journal.checkpoint();
+
+        match f(self) {
+            Ok(value) => {
+                self.journal.commit(checkpoint);
+                Ok(value)
+            }
+            Err(err) => {
+                self.journal.rollback(&checkpoint, &mut self.stores);
+                Err(err)
+            }
+        }
+    }
+
+    /// Take a snapshot of the shape, to return to later
+    ///
+    /// This doesn't copy any objects, so it's cheap enough to take a snapshot
+    /// before trying something speculative, like one of multiple strategies
+    /// for an algorithm. Use [`Shape::restore`] to discard the changes made
+    /// since, or just drop the snapshot to keep them.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            checkpoint: self.journal.checkpoint(),
+        }
+    }
+
+    /// Return the shape to the state of a snapshot
+    ///
+    /// Reverts all changes that were made since the snapshot was taken. Like
+    /// [`Shape::transaction`], this doesn't add to the changes that can be
+    /// undone. The same snapshot can be restored repeatedly.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if changes that were made before the snapshot was taken have
+    /// been undone since, even if other changes have been made in their place.
+    /// The snapshot must have been taken of this shape, not of a copy of it.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.journal
+            .rollback(&snapshot.checkpoint, &mut self.stores);
+    }
+
+    /// Undo the most recent change to the shape
+    ///
+    /// Insertions, replacements, removals, and transformations can be undone.
+    /// Handles stay valid: undoing an insertion removes the object, undoing a
+    /// removal restores it. Returns `false`, if there is nothing to undo.
+    ///
+    /// Changes to attributes are not recorded, and can't be undone. Copies of
+    /// a shape share its objects, so undoing a change through one copy also
+    /// affects the others.
+    pub fn undo(&mut self) -> bool {
+        self.journal.undo(&mut self.stores)
+    }
+
+    /// Redo the change that was most recently undone
+    ///
+    /// Making any other change to the shape discards the changes that could
+    /// be redone. Returns `false`, if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        self.journal.redo(&mut self.stores)
+    }
+
     /// Access the handle of an object
     ///
     /// Returns the handle that refers to the given object, if it is part of the
@@ -110,31 +279,50 @@ impl Shape {
         self.insert(object)
     }
 
+    /// Attach an attribute to an object of the shape
+    ///
+    /// The attributes of an object can be accessed through its handle, using
+    /// [`Handle::attributes`]. See [`Attributes::insert`], regarding which
+    /// attributes replace each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object is not part of this shape.
+    pub fn insert_attribute<T>(
+        &mut self,
+        object: &Handle<T>,
+        attribute: Attribute,
+    ) where
+        T: Object,
+    {
+        self.stores.get::<T>().insert_attribute(object, attribute);
+    }
+
+    /// Replace all attributes of an object of the shape
+    ///
+    /// This is useful for carrying over the attributes of an object, when it
+    /// is copied into another shape.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object is not part of this shape.
+    pub fn set_attributes<T>(
+        &mut self,
+        object: &Handle<T>,
+        attributes: Attributes,
+    ) where
+        T: Object,
+    {
+        self.stores.get::<T>().set_attributes(object, attributes);
+    }
+
     /// Transform the geometry of the shape
     ///
     /// Since the topological types refer to geometry, and don't contain any
     /// geometry themselves, this transforms the whole shape.
     pub fn transform(&mut self, transform: &Transform) {
-        self.stores
-            .points
-            .update(|point| *point = transform.transform_point(point));
-        self.stores
-            .curves
-            .update(|curve| *curve = curve.transform(transform));
-        self.stores
-            .surfaces
-            .update(|surface| *surface = surface.transform(transform));
-
-        // While some faces use triangle representation, we need this weird
-        // workaround here.
-        self.stores.faces.update(|mut face| {
-            use std::ops::DerefMut as _;
-            if let Face::Triangles(triangles) = face.deref_mut() {
-                for (triangle, _) in triangles {
-                    *triangle = transform.transform_triangle(triangle);
-                }
-            }
-        });
+        let changes = self.stores.transform(transform);
+        self.journal.record(Group { changes });
     }
 
     /// Access an iterator over all points
@@ -193,15 +381,27 @@ impl Default for Shape {
     }
 }
 
+/// A snapshot of a [`Shape`]
+///
+/// Returned by [`Shape::snapshot`].
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    checkpoint: Checkpoint,
+}
+
 #[cfg(test)]
 mod tests {
-    use std::ops::{Deref, DerefMut};
+    use std::{
+        ops::{Deref, DerefMut},
+        sync::Arc,
+        thread,
+    };
 
-    use fj_math::{Point, Scalar};
+    use fj_math::{Point, Scalar, Transform};
 
     use crate::{
         geometry::{Curve, Surface},
-        shape::{Handle, Shape, ValidationError},
+        shape::{Attribute, Handle, Provenance, Shape, Step, ValidationError},
         topology::{Cycle, Edge, Face, Vertex},
     };
 
@@ -263,6 +463,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn attributes() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let a = shape.insert(Point::from([0., 0., 0.]))?;
+        let b = shape.insert(Point::from([1., 0., 0.]))?;
+        let a = shape.insert(Vertex { point: a })?;
+        let b = shape.insert(Vertex { point: b })?;
+
+        shape.insert_attribute(&a, Attribute::Name("corner".into()));
+        shape.insert_attribute(&a, Attribute::Tag("fillet".into()));
+
+        assert_eq!(a.attributes().name(), Some("corner"));
+        assert!(a.attributes().has_tag("fillet"));
+        assert!(b.attributes().is_empty());
+
+        // Copies of a shape share its objects, and their attributes.
+        let mut copy = shape.clone();
+        let provenance = Provenance::new(Step::new("Sketch"));
+        copy.insert_attribute(&b, Attribute::Provenance(provenance.clone()));
+        assert_eq!(b.attributes().provenance(), Some(&provenance));
+
+        Ok(())
+    }
+
     #[test]
     fn add_vertex() -> anyhow::Result<()> {
         let mut shape = Shape::new().with_min_distance(MIN_DISTANCE);
@@ -378,6 +603,272 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn replace() -> anyhow::Result<()> {
+        let mut shape = Shape::new().with_min_distance(MIN_DISTANCE);
+        let mut other = Shape::new();
+
+        let a = shape.insert(Point::from([0., 0., 0.]))?;
+        let b = shape.insert(Point::from([5e-8, 0., 0.]))?;
+        let vertex = shape.insert(Vertex { point: a })?;
+
+        // A vertex can be replaced by one that is close to it.
+        shape.replace(&vertex, Vertex { point: b.clone() })?;
+        assert_eq!(vertex.get().point, b);
+
+        // Should fail, as `point` is not part of the shape.
+        let point = other.insert(Point::from([1., 0., 0.]))?;
+        let result = shape.replace(&vertex, Vertex { point });
+        assert!(matches!(result, Err(ValidationError::Structural(_))));
+        assert_eq!(vertex.get().point, b);
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let point = shape.insert(Point::from([0., 0., 0.]))?;
+        shape.remove(&point);
+
+        assert_eq!(shape.points().count(), 0);
+        assert_eq!(point.get(), Point::from([0., 0., 0.]));
+
+        // Should fail, as `point` is no longer part of the shape.
+        let result = shape.insert(Vertex { point });
+        assert!(matches!(result, Err(ValidationError::Structural(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_redo() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        assert!(!shape.undo());
+
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+
+        let point = shape.insert(a)?;
+        shape.replace(&point, b)?;
+        let vertex = shape.insert(Vertex {
+            point: point.clone(),
+        })?;
+        shape.remove(&vertex);
+
+        assert!(shape.undo());
+        assert_eq!(shape.vertices().count(), 1);
+        assert!(shape.undo());
+        assert_eq!(shape.vertices().count(), 0);
+        assert!(shape.undo());
+        assert_eq!(point.get(), a);
+        assert!(shape.undo());
+        assert_eq!(shape.points().count(), 0);
+        assert!(!shape.undo());
+
+        // Undone changes are redone with the same handles.
+        assert!(shape.redo());
+        assert!(shape.redo());
+        assert_eq!(shape.get_handle(&b), Some(point.clone()));
+        assert!(shape.redo());
+        assert_eq!(shape.get_handle(&vertex.get()), Some(vertex));
+
+        // A new change discards the changes that could be redone.
+        shape.insert(Curve::x_axis())?;
+        assert!(!shape.redo());
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_transform() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let a = Point::from([1., 2., 3.]);
+        let point = shape.insert(a)?;
+        let curve = shape.insert(Curve::x_axis())?;
+
+        // Undoing a transform restores the geometry exactly, even if the
+        // transform can't be inverted.
+        let flatten = Transform::from_data([
+            1., 0., 0., 0., //
+            0., 1., 0., 0., //
+            0., 0., 0., 0., //
+            0., 0., 0., 1., //
+        ]);
+        for transform in [Transform::rotation([0.1, 0.2, 0.3]), flatten] {
+            shape.transform(&transform);
+            assert_ne!(point.get(), a);
+
+            assert!(shape.undo());
+            assert_eq!(point.get(), a);
+            assert_eq!(curve.get(), Curve::x_axis());
+
+            assert!(shape.redo());
+            assert_eq!(point.get(), transform.transform_point(&a));
+            assert!(shape.undo());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn transaction() -> anyhow::Result<()> {
+        let mut shape = Shape::new().with_min_distance(MIN_DISTANCE);
+
+        let result = shape.transaction(|shape| {
+            let point = shape.insert(Point::from([0., 0., 0.]))?;
+            shape.insert(Vertex { point })?;
+
+            // Too close to the other vertex. Should fail.
+            let point = shape.insert(Point::from([5e-8, 0., 0.]))?;
+            shape.insert(Vertex { point })
+        });
+        assert!(matches!(result, Err(ValidationError::Uniqueness)));
+        assert_eq!(shape.points().count(), 0);
+        assert_eq!(shape.vertices().count(), 0);
+        assert!(!shape.undo());
+
+        shape.transaction(|shape| {
+            let point = shape.insert(Point::from([0., 0., 0.]))?;
+            shape.insert(Vertex { point })
+        })?;
+        assert_eq!(shape.vertices().count(), 1);
+
+        // The whole transaction is undone at once.
+        assert!(shape.undo());
+        assert_eq!(shape.points().count(), 0);
+        assert!(!shape.undo());
+
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+
+        let point = shape.insert(a)?;
+        let snapshot = shape.snapshot();
+
+        // Try one modification, and discard it.
+        shape.replace(&point, b)?;
+        shape.insert(Vertex {
+            point: point.clone(),
+        })?;
+        shape.restore(&snapshot);
+
+        assert_eq!(point.get(), a);
+        assert_eq!(shape.vertices().count(), 0);
+
+        // Try another one, and discard that too.
+        shape.remove(&point);
+        shape.restore(&snapshot);
+        assert_eq!(shape.points().count(), 1);
+
+        // Restoring a snapshot doesn't count as a change.
+        assert!(shape.undo());
+        assert_eq!(shape.points().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn undo_in_transaction() {
+        let mut shape = Shape::new();
+        shape.insert(Point::from([0., 0., 0.])).unwrap();
+
+        let _ = shape.transaction(|shape| {
+            // This change is from before the transaction.
+            shape.undo();
+            shape.insert(Point::from([1., 0., 0.]))
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn restore_after_undo() {
+        let mut shape = Shape::new();
+
+        shape.insert(Point::from([0., 0., 0.])).unwrap();
+        let snapshot = shape.snapshot();
+
+        // There are as many changes as at the snapshot, but not the same ones.
+        shape.undo();
+        shape.insert(Point::from([1., 0., 0.])).unwrap();
+
+        shape.restore(&snapshot);
+    }
+
+    #[test]
+    #[should_panic]
+    fn restore_into_copy() {
+        let mut shape = Shape::new();
+        let snapshot = shape.snapshot();
+
+        let mut copy = shape.clone();
+        shape.insert(Point::from([0., 0., 0.])).unwrap();
+
+        copy.restore(&snapshot);
+    }
+
+    #[test]
+    fn read_from_multiple_threads() -> anyhow::Result<()> {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Shape>();
+        assert_send_sync::<Handle<Face>>();
+
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()?;
+
+        let shape = Arc::new(shape);
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let shape = shape.clone();
+                thread::spawn(move || {
+                    shape
+                        .edges()
+                        .filter(|edge| edge.get().vertices.is_some())
+                        .count()
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            // Can't panic, unless the thread panicked.
+            assert_eq!(thread.join().unwrap(), 3);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn failed_builder() {
+        let mut shape = Shape::new().with_min_distance(MIN_DISTANCE);
+
+        // The vertex of the interior is too close to one of the exterior.
+        let result = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .with_interior_polygon([
+                [5e-8, 0., 0.],
+                [0.5, 0., 0.],
+                [0., 0.5, 0.],
+            ])
+            .build();
+        assert!(matches!(result, Err(ValidationError::Uniqueness)));
+
+        assert_eq!(shape.points().count(), 0);
+        assert_eq!(shape.surfaces().count(), 0);
+        assert_eq!(shape.edges().count(), 0);
+        assert_eq!(shape.cycles().count(), 0);
+    }
+
     struct TestShape {
         inner: Shape,
         next_point: Point<3>,
@@ -0,0 +1,320 @@
+use std::{fmt, mem};
+
+use fj_math::{Scalar, Vector};
+
+use crate::algorithms::Texture;
+
+/// An attribute that can be attached to an object of a [`Shape`]
+///
+/// Attributes don't affect the geometry or topology of a shape. They carry
+/// information for the operations that create and modify shapes, and for
+/// consumers of the shape, like the viewer or exporters.
+///
+/// [`Shape`]: super::Shape
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Attribute {
+    /// A name, for display to the user
+    Name(String),
+
+    /// A color, for objects that don't define their own
+    Color([u8; 4]),
+
+    /// A free-form tag, for anyone that wants to find the object later
+    Tag(String),
+
+    /// The `fj` operations that led to the object
+    Provenance(Provenance),
+
+    /// A texture, for faces that should get one when they are triangulated
+    Texture(Texture),
+
+    /// The direction an object moves in, when its assembly is exploded
+    Explosion(Vector<3>),
+
+    /// The material an object is made of
+    Material(Material),
+}
+
+/// The attributes attached to an object of a [`Shape`]
+///
+/// An object can have any number of tags, but only one attribute of each other
+/// kind.
+///
+/// [`Shape`]: super::Shape
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Attributes {
+    attributes: Vec<Attribute>,
+}
+
+impl Attributes {
+    /// Construct an empty set of attributes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an attribute
+    ///
+    /// Replaces the attribute of the same kind, unless it's a tag. Tags are
+    /// only added, if the object doesn't have the same tag already.
+    pub fn insert(&mut self, attribute: Attribute) {
+        let existing = self.attributes.iter().position(|existing| {
+            if let Attribute::Tag(_) = attribute {
+                existing == &attribute
+            } else {
+                mem::discriminant(existing) == mem::discriminant(&attribute)
+            }
+        });
+
+        match existing {
+            Some(i) => self.attributes[i] = attribute,
+            None => self.attributes.push(attribute),
+        }
+    }
+
+    /// Access all attributes
+    pub fn iter(&self) -> impl Iterator<Item = &Attribute> {
+        self.attributes.iter()
+    }
+
+    /// Indicate whether no attributes are attached
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    /// Access the name, if there is one
+    pub fn name(&self) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Name(name) => Some(name.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Access the color, if there is one
+    pub fn color(&self) -> Option<[u8; 4]> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Color(color) => Some(*color),
+                _ => None,
+            })
+    }
+
+    /// Access the tags
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.attributes
+            .iter()
+            .filter_map(|attribute| match attribute {
+                Attribute::Tag(tag) => Some(tag.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Indicate whether the given tag is attached
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags().any(|t| t == tag)
+    }
+
+    /// Access the operations that led to the object, if they are known
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Provenance(provenance) => Some(provenance),
+                _ => None,
+            })
+    }
+
+    /// Access the texture, if there is one
+    pub fn texture(&self) -> Option<Texture> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Texture(texture) => Some(*texture),
+                _ => None,
+            })
+    }
+
+    /// Access the material, if one has been assigned
+    pub fn material(&self) -> Option<&Material> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Material(material) => Some(material),
+                _ => None,
+            })
+    }
+
+    /// Access the direction of the explosion, if one has been specified
+    pub fn explosion(&self) -> Option<Vector<3>> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Explosion(direction) => Some(*direction),
+                _ => None,
+            })
+    }
+}
+
+/// A material that an object is made of
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Material {
+    /// The name of the material
+    pub name: String,
+
+    /// The density of the material, in g/cm³
+    pub density: Scalar,
+
+    /// The color of the material, as RGBA
+    pub color: [u8; 4],
+
+    /// How metallic the material is, from `0` to `1`
+    pub metallic: Scalar,
+
+    /// How rough the surface of the material is, from `0` to `1`
+    pub roughness: Scalar,
+}
+
+/// The `fj` operations that led to an object
+///
+/// This is the path through the tree of operations, from the outermost
+/// operation to the one that created the original object. A side face of a
+/// swept difference, for example, could originate from `Sweep >
+/// Difference2d[1] > Sketch`, meaning it was created by sweeping an edge of the
+/// sketch that was subtracted.
+///
+/// Since operations are processed from the inside out, steps are added at the
+/// front.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Provenance {
+    steps: Vec<Step>,
+}
+
+impl Provenance {
+    /// Construct a provenance that consists of a single step
+    pub fn new(step: Step) -> Self {
+        Self { steps: vec![step] }
+    }
+
+    /// Record that the object went through another operation
+    ///
+    /// The operation becomes the outermost step.
+    pub fn push_outer(&mut self, step: Step) {
+        self.steps.insert(0, step);
+    }
+
+    /// Access the steps, outermost first
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Access the operation that created the original object
+    pub fn source(&self) -> Option<&Step> {
+        self.steps.last()
+    }
+
+    /// Indicate whether the object originates from the given path
+    ///
+    /// Returns `true`, if the steps of the provenance start with `path`. An
+    /// empty path matches everything.
+    pub fn starts_with(&self, path: &[Step]) -> bool {
+        self.steps.starts_with(path)
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                write!(f, " > ")?;
+            }
+            write!(f, "{}", step)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A step of a [`Provenance`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Step {
+    /// The name of the `fj` operation, like `Sweep`
+    pub operation: String,
+
+    /// The input of the operation that the object came from
+    ///
+    /// Only set for operations that have more than one input, like
+    /// `Difference2d` or `Group`.
+    pub input: Option<usize>,
+}
+
+impl Step {
+    /// Construct a step through an operation with a single input
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            input: None,
+        }
+    }
+
+    /// Construct a step through one input of an operation
+    pub fn with_input(operation: impl Into<String>, input: usize) -> Self {
+        Self {
+            operation: operation.into(),
+            input: Some(input),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.operation)?;
+        if let Some(input) = self.input {
+            write!(f, "[{}]", input)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attribute, Attributes, Provenance, Step};
+
+    #[test]
+    fn replace_attribute_of_same_kind() {
+        let mut attributes = Attributes::new();
+
+        attributes.insert(Attribute::Name("a".into()));
+        attributes.insert(Attribute::Tag("x".into()));
+        attributes.insert(Attribute::Name("b".into()));
+        attributes.insert(Attribute::Tag("y".into()));
+        attributes.insert(Attribute::Tag("x".into()));
+
+        assert_eq!(attributes.name(), Some("b"));
+        assert_eq!(attributes.tags().collect::<Vec<_>>(), ["x", "y"]);
+        assert_eq!(attributes.color(), None);
+        assert_eq!(attributes.iter().count(), 3);
+    }
+
+    #[test]
+    fn provenance() {
+        let mut provenance = Provenance::new(Step::new("Sketch"));
+        provenance.push_outer(Step::with_input("Difference2d", 1));
+        provenance.push_outer(Step::new("Sweep"));
+
+        assert_eq!(provenance.to_string(), "Sweep > Difference2d[1] > Sketch");
+        assert_eq!(provenance.source(), Some(&Step::new("Sketch")));
+
+        assert!(provenance.starts_with(&[]));
+        assert!(provenance.starts_with(&[
+            Step::new("Sweep"),
+            Step::with_input("Difference2d", 1),
+        ]));
+        assert!(!provenance.starts_with(&[
+            Step::new("Sweep"),
+            Step::with_input("Difference2d", 0),
+        ]));
+    }
+}
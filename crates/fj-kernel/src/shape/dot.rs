@@ -0,0 +1,240 @@
+use std::fmt::Write as _;
+
+use crate::{
+    geometry::{Curve, Surface},
+    topology::Face,
+};
+
+use super::{Handle, Iter, Shape};
+
+impl Shape {
+    /// Export the object graph of the shape, in the DOT format of Graphviz
+    ///
+    /// Every object of the shape is a node, and every handle that an object
+    /// holds is an edge to the object it refers to. Objects that are referred
+    /// to, without being part of the shape, are drawn in red. Those are what
+    /// structural validation complains about.
+    ///
+    /// The graph can be rendered using Graphviz, for example with
+    /// `dot -Tsvg shape.dot -o shape.svg`.
+    pub fn to_dot(&self) -> String {
+        let mut points = Nodes::new("Point", self.points());
+        let mut curves = Nodes::new("Curve", self.curves());
+        let mut surfaces = Nodes::new("Surface", self.surfaces());
+        let mut vertices = Nodes::new("Vertex", self.vertices());
+        let mut edges = Nodes::new("Edge", self.edges());
+        let mut cycles = Nodes::new("Cycle", self.cycles());
+        let mut faces = Nodes::new("Face", self.faces());
+
+        let mut dot = String::from("digraph shape {\n    node [shape=box];\n");
+
+        // Can't fail. Writing to a `String` doesn't.
+        for face in faces.objects() {
+            let id = faces.id(&face);
+            match face.get() {
+                Face::Face {
+                    surface,
+                    exteriors,
+                    interiors,
+                    ..
+                } => {
+                    writeln!(dot, "    {id};").unwrap();
+                    writeln!(dot, "    {id} -> {};", surfaces.id(&surface))
+                        .unwrap();
+
+                    for cycle in exteriors {
+                        let cycle = cycles.id(&cycle);
+                        writeln!(dot, "    {id} -> {cycle} [label=exterior];")
+                            .unwrap();
+                    }
+                    for cycle in interiors {
+                        let cycle = cycles.id(&cycle);
+                        writeln!(dot, "    {id} -> {cycle} [label=interior];")
+                            .unwrap();
+                    }
+                }
+                Face::Triangles(triangles) => {
+                    let label = faces.label(&face);
+                    let n = triangles.len();
+                    writeln!(
+                        dot,
+                        "    {id} [label=\"{label}\\n{n} triangles\"];"
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        for cycle in cycles.objects() {
+            let id = cycles.id(&cycle);
+            writeln!(dot, "    {id};").unwrap();
+
+            for edge in cycle.get().edges {
+                writeln!(dot, "    {id} -> {};", edges.id(&edge)).unwrap();
+            }
+        }
+
+        for edge in edges.objects() {
+            let id = edges.id(&edge);
+            let edge = edge.get();
+            writeln!(dot, "    {id} -> {};", curves.id(&edge.curve)).unwrap();
+
+            for vertex in edge.vertices.into_iter().flatten() {
+                writeln!(dot, "    {id} -> {};", vertices.id(&vertex)).unwrap();
+            }
+        }
+
+        for vertex in vertices.objects() {
+            let id = vertices.id(&vertex);
+            let point = points.id(&vertex.get().point);
+            writeln!(dot, "    {id} -> {point};").unwrap();
+        }
+
+        for point in points.objects() {
+            let (id, label) = (points.id(&point), points.label(&point));
+            let coords = format!("{:?}", point.get());
+            writeln!(dot, "    {id} [label=\"{label}\\n{coords}\"];").unwrap();
+        }
+
+        for curve in curves.objects() {
+            let (id, label) = (curves.id(&curve), curves.label(&curve));
+            let kind = curve_kind(&curve.get());
+            writeln!(dot, "    {id} [label=\"{label}\\n{kind}\"];").unwrap();
+        }
+
+        for surface in surfaces.objects() {
+            let (id, label) = (surfaces.id(&surface), surfaces.label(&surface));
+            let kind = match surface.get() {
+                Surface::SweptCurve(surface) => {
+                    format!("swept {}", curve_kind(&surface.curve))
+                }
+            };
+            writeln!(dot, "    {id} [label=\"{label}\\n{kind}\"];").unwrap();
+        }
+
+        points.write_foreign(&mut dot);
+        curves.write_foreign(&mut dot);
+        surfaces.write_foreign(&mut dot);
+        vertices.write_foreign(&mut dot);
+        edges.write_foreign(&mut dot);
+        cycles.write_foreign(&mut dot);
+        faces.write_foreign(&mut dot);
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// The nodes of the objects of one type
+struct Nodes<T> {
+    kind: &'static str,
+
+    /// The objects that are part of the shape
+    ///
+    /// Sorted, to make the output easier to compare between runs.
+    objects: Vec<Handle<T>>,
+
+    /// The objects that are referred to, but not part of the shape
+    ///
+    /// Their keys can clash with those of objects that are part of the shape,
+    /// so they are numbered separately.
+    foreign: Vec<Handle<T>>,
+}
+
+impl<T> Nodes<T>
+where
+    T: Clone,
+{
+    fn new(kind: &'static str, objects: Iter<T>) -> Self {
+        let mut objects: Vec<_> = objects.collect();
+        objects.sort_by_key(|handle| handle.key());
+
+        Self {
+            kind,
+            objects,
+            foreign: Vec::new(),
+        }
+    }
+
+    fn objects(&self) -> Vec<Handle<T>> {
+        self.objects.clone()
+    }
+
+    /// The label of an object that is part of the shape
+    fn label(&self, handle: &Handle<T>) -> String {
+        format!("{} {}", self.kind, handle)
+    }
+
+    /// The quoted ID of the node of an object
+    fn id(&mut self, handle: &Handle<T>) -> String {
+        let is_known = self
+            .objects
+            .first()
+            .map_or(false, |object| object.store().contains(handle));
+        if is_known {
+            return format!("\"{}\"", self.label(handle));
+        }
+
+        let index = match self.foreign.iter().position(|foreign| {
+            foreign.key() == handle.key() && foreign.store() == handle.store()
+        }) {
+            Some(index) => index,
+            None => {
+                self.foreign.push(handle.clone());
+                self.foreign.len() - 1
+            }
+        };
+
+        format!("\"{} {} (foreign {})\"", self.kind, handle, index)
+    }
+
+    fn write_foreign(&mut self, dot: &mut String) {
+        for handle in self.foreign.clone() {
+            let id = self.id(&handle);
+            let label = format!("{} {}", self.kind, handle);
+
+            // Can't fail. Writing to a `String` doesn't.
+            writeln!(
+                dot,
+                "    {id} [color=red, fontcolor=red, \
+                label=\"{label}\\nnot part of the shape\"];"
+            )
+            .unwrap();
+        }
+    }
+}
+
+fn curve_kind(curve: &Curve) -> &'static str {
+    match curve {
+        Curve::Circle(_) => "circle",
+        Curve::Line(_) => "line",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{geometry::Surface, shape::Shape, topology::Face};
+
+    #[test]
+    fn all_references() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()?;
+
+        let dot = shape.to_dot();
+
+        assert!(dot.starts_with("digraph shape {"));
+        assert!(dot.ends_with("}\n"));
+
+        // A face refers to its surface and cycle, the cycle to three edges,
+        // each edge to a curve and two vertices, and each vertex to a point.
+        assert_eq!(
+            dot.lines().filter(|line| line.contains(" -> ")).count(),
+            17
+        );
+        assert!(!dot.contains("not part of the shape"));
+
+        Ok(())
+    }
+}
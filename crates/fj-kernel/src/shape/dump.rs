@@ -0,0 +1,178 @@
+use std::fmt;
+
+use crate::{
+    geometry::{Curve, Surface},
+    topology::{Cycle, Face},
+};
+
+use super::{Handle, Shape};
+
+impl Shape {
+    /// Format the topology of the shape as a readable tree
+    ///
+    /// Lists every face, with the cycles that bound it, the edges of those
+    /// cycles, and the vertices of those edges, with their coordinates. Objects
+    /// are identified by their handles, so objects that are shared, like the
+    /// edges between neighboring faces, can be recognized as such.
+    ///
+    /// This is the same as the [`fmt::Display`] implementation. Please include
+    /// it when reporting a bug in the kernel.
+    pub fn dump(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Shape: {} faces, {} cycles, {} edges, {} vertices",
+            self.faces().count(),
+            self.cycles().count(),
+            self.edges().count(),
+            self.vertices().count(),
+        )?;
+
+        // The order of the stores is unspecified. Sorting makes the output
+        // easier to compare between runs.
+        let mut faces: Vec<_> = self.faces().collect();
+        faces.sort();
+
+        for face in faces {
+            match face.get() {
+                Face::Face {
+                    surface,
+                    exteriors,
+                    interiors,
+                    ..
+                } => {
+                    writeln!(f, "Face {} on {}", face, SurfaceDump(&surface))?;
+
+                    for cycle in exteriors {
+                        write_cycle(f, "Exterior", &cycle)?;
+                    }
+                    for cycle in interiors {
+                        write_cycle(f, "Interior", &cycle)?;
+                    }
+                }
+                Face::Triangles(triangles) => {
+                    writeln!(
+                        f,
+                        "Face {}, made of {} triangles",
+                        face,
+                        triangles.len()
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn write_cycle(
+    f: &mut fmt::Formatter,
+    kind: &str,
+    cycle: &Handle<Cycle>,
+) -> fmt::Result {
+    writeln!(f, "  {} cycle {}", kind, cycle)?;
+
+    for edge in cycle.get().edges {
+        let curve = &edge.get().curve;
+        writeln!(f, "    Edge {} on {}", edge, CurveDump(&curve.get()))?;
+
+        match edge.get().vertices {
+            Some(vertices) => {
+                for vertex in vertices {
+                    writeln!(
+                        f,
+                        "      Vertex {} at {:?}",
+                        vertex,
+                        vertex.get().point()
+                    )?;
+                }
+            }
+            None => writeln!(f, "      No vertices, the edge is continuous")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats a curve, as part of a dump
+struct CurveDump<'r>(&'r Curve);
+
+impl fmt::Display for CurveDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Curve::Circle(circle) => write!(
+                f,
+                "circle around {:?}, with radii {:?} and {:?}",
+                circle.center, circle.a, circle.b
+            ),
+            Curve::Line(line) => write!(
+                f,
+                "line through {:?}, with direction {:?}",
+                line.origin, line.direction
+            ),
+        }
+    }
+}
+
+/// Formats a surface, as part of a dump
+struct SurfaceDump<'r>(&'r Handle<Surface>);
+
+impl fmt::Display for SurfaceDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.get() {
+            Surface::SweptCurve(surface) => write!(
+                f,
+                "{} swept along {:?}",
+                CurveDump(&surface.curve),
+                surface.path
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{geometry::Surface, shape::Shape, topology::Face};
+
+    #[test]
+    fn list_topology() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()?;
+
+        let dump = shape.dump();
+        let lines: Vec<_> = dump.lines().collect();
+
+        assert_eq!(lines[0], "Shape: 1 faces, 1 cycles, 3 edges, 3 vertices");
+        assert!(lines[1].starts_with("Face #"));
+        assert!(lines[2].starts_with("  Exterior cycle #"));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("    Edge #"))
+                .count(),
+            3
+        );
+
+        // Each vertex is referred to by the two edges it bounds.
+        let point = format!("{:?}", Point::from([1., 0., 0.]));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("      Vertex #")
+                    && line.ends_with(&point))
+                .count(),
+            2
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use super::{stores::Stores, Handle, Object};
+
+/// The changes that have been made to a shape, in order
+///
+/// Every change knows how to revert itself, which is what undo and redo are
+/// built on.
+#[derive(Debug)]
+pub struct Journal {
+    /// Identifies the journal, to tell its checkpoints apart from those of
+    /// other journals
+    id: u64,
+
+    done: Vec<Entry>,
+
+    /// The changes that have been undone, the most recently undone one last
+    ///
+    /// Any new change discards those, as they might conflict with it.
+    undone: Vec<Entry>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self {
+            id: next_id(),
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Record a change that has just been applied to the stores
+    pub fn record(&mut self, change: impl Change + 'static) {
+        self.done.push(Entry::new(Arc::new(change)));
+        self.undone.clear();
+    }
+
+    /// Revert the most recent change
+    ///
+    /// Returns `false`, if there is no change to revert.
+    pub fn undo(&mut self, stores: &mut Stores) -> bool {
+        match self.done.pop() {
+            Some(entry) => {
+                entry.change.revert(stores);
+                self.undone.push(entry);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Mark the current state, to commit or roll back to later
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            journal: self.id,
+            done: self.done.len(),
+            last: self.done.last().map(|entry| entry.id),
+            undone: self.undone.clone(),
+        }
+    }
+
+    /// Combine all changes since the checkpoint, so they're undone as one
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Journal::rollback`].
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        self.check(&checkpoint);
+
+        let mut entries = self.done.split_off(checkpoint.done);
+
+        match entries.len() {
+            0 => {}
+            1 => self.done.append(&mut entries),
+            _ => {
+                let changes =
+                    entries.into_iter().map(|entry| entry.change).collect();
+                self.done.push(Entry::new(Arc::new(Group { changes })));
+            }
+        }
+    }
+
+    /// Revert all changes since the checkpoint, and forget about them
+    ///
+    /// The changes that could be redone at the checkpoint can be redone again
+    /// afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the checkpoint is from another journal, or if changes that
+    /// were made before the checkpoint have been undone since. That includes
+    /// changes that have been undone and replaced by other changes, even if
+    /// there are as many changes as there were at the checkpoint.
+    pub fn rollback(&mut self, checkpoint: &Checkpoint, stores: &mut Stores) {
+        self.check(checkpoint);
+
+        while self.done.len() > checkpoint.done {
+            // Can't panic. We just checked that there are more changes.
+            let entry = self.done.pop().unwrap();
+            entry.change.revert(stores);
+        }
+
+        self.undone = checkpoint.undone.clone();
+    }
+
+    /// Make sure that all changes up to the checkpoint are still done
+    fn check(&self, checkpoint: &Checkpoint) {
+        assert!(
+            checkpoint.journal == self.id,
+            "Checkpoint is from another journal"
+        );
+        assert!(
+            self.done.len() >= checkpoint.done
+                && self.done[..checkpoint.done].last().map(|entry| entry.id)
+                    == checkpoint.last,
+            "Changes from before the checkpoint have been undone"
+        );
+    }
+
+    /// Apply the most recently reverted change again
+    ///
+    /// Returns `false`, if there is no change to apply.
+    pub fn redo(&mut self, stores: &mut Stores) -> bool {
+        match self.undone.pop() {
+            Some(entry) => {
+                entry.change.apply(stores);
+                self.done.push(entry);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// A copy of a journal has its own identity, so checkpoints of the original
+// can't be used to roll back the copy, or the other way around.
+impl Clone for Journal {
+    fn clone(&self) -> Self {
+        Self {
+            id: next_id(),
+            done: self.done.clone(),
+            undone: self.undone.clone(),
+        }
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The state of a journal at some point, returned by [`Journal::checkpoint`]
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    journal: u64,
+    done: usize,
+
+    /// The most recent change at the checkpoint, if any
+    last: Option<u64>,
+
+    undone: Vec<Entry>,
+}
+
+/// A change, as recorded in a journal
+///
+/// The ID stays the same when the change is undone and redone, but changes
+/// that are made instead of an undone one get a new one.
+#[derive(Clone, Debug)]
+struct Entry {
+    id: u64,
+    change: Arc<dyn Change>,
+}
+
+impl Entry {
+    fn new(change: Arc<dyn Change>) -> Self {
+        Self {
+            id: next_id(),
+            change,
+        }
+    }
+}
+
+/// Generate an ID that is unique within the process
+fn next_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A change to a shape, that can be reverted
+pub trait Change: fmt::Debug + Send + Sync {
+    fn apply(&self, stores: &mut Stores);
+    fn revert(&self, stores: &mut Stores);
+}
+
+/// Multiple changes that have been made as one
+#[derive(Debug)]
+pub struct Group {
+    pub changes: Vec<Arc<dyn Change>>,
+}
+
+impl Change for Group {
+    fn apply(&self, stores: &mut Stores) {
+        for change in &self.changes {
+            change.apply(stores);
+        }
+    }
+
+    fn revert(&self, stores: &mut Stores) {
+        for change in self.changes.iter().rev() {
+            change.revert(stores);
+        }
+    }
+}
+
+/// An object has been inserted
+#[derive(Debug)]
+pub struct Insert<T> {
+    pub object: Handle<T>,
+}
+
+impl<T> Change for Insert<T>
+where
+    T: Object,
+{
+    fn apply(&self, stores: &mut Stores) {
+        stores.get::<T>().restore(&self.object);
+    }
+
+    fn revert(&self, stores: &mut Stores) {
+        stores.get::<T>().remove(&self.object);
+    }
+}
+
+/// An object has been replaced with another
+#[derive(Debug)]
+pub struct Replace<T> {
+    pub object: Handle<T>,
+    pub old: T,
+    pub new: T,
+}
+
+impl<T> Change for Replace<T>
+where
+    T: Object,
+{
+    fn apply(&self, stores: &mut Stores) {
+        stores.get::<T>().replace(&self.object, self.new.clone());
+    }
+
+    fn revert(&self, stores: &mut Stores) {
+        stores.get::<T>().replace(&self.object, self.old.clone());
+    }
+}
+
+/// An object has been removed
+#[derive(Debug)]
+pub struct Remove<T> {
+    pub object: Handle<T>,
+}
+
+impl<T> Change for Remove<T>
+where
+    T: Object,
+{
+    fn apply(&self, stores: &mut Stores) {
+        stores.get::<T>().remove(&self.object);
+    }
+
+    fn revert(&self, stores: &mut Stores) {
+        stores.get::<T>().restore(&self.object);
+    }
+}
@@ -3,12 +3,17 @@
 //! See [`Shape`], which is the main entry point to this API.
 
 mod api;
+mod attributes;
+mod dot;
+mod dump;
+mod journal;
 mod object;
 mod stores;
 mod validate;
 
 pub use self::{
-    api::Shape,
+    api::{Shape, Snapshot},
+    attributes::{Attribute, Attributes, Material, Provenance, Step},
     object::Object,
     stores::{Handle, Iter},
     validate::{StructuralIssues, ValidationError, ValidationResult},
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use fj_math::Point;
 
 use crate::{
@@ -9,7 +11,14 @@ use super::validate::Validate;
 
 /// Marker trait for geometric and topological objects
 pub trait Object:
-    'static + Clone + PartialEq + Validate + private::Sealed
+    'static
+    + Clone
+    + fmt::Debug
+    + PartialEq
+    + Send
+    + Sync
+    + Validate
+    + private::Sealed
 {
 }
 
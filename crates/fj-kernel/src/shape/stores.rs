@@ -1,19 +1,23 @@
 use std::{
+    fmt,
     hash::{Hash, Hasher},
     sync::Arc,
 };
 
 use anymap::AnyMap;
-use fj_math::Point;
+use fj_math::{Point, Transform};
 use parking_lot::{RwLock, RwLockReadGuard};
-use slotmap::{DefaultKey, SlotMap};
+use slotmap::{DefaultKey, Key as _, SecondaryMap, SlotMap};
 
 use crate::{
     geometry::{Curve, Surface},
     topology::{Cycle, Edge, Face, Vertex},
 };
 
-use super::Object;
+use super::{
+    journal::{Change, Replace},
+    Attribute, Attributes, Object,
+};
 
 #[derive(Clone, Debug)]
 pub struct Stores {
@@ -49,6 +53,41 @@ impl Stores {
             // for all types of objects above.
             .expect("Invalid object type")
     }
+
+    /// Transform all geometry, returning the changes that were made
+    ///
+    /// The changes record the geometry from before the transform, so it can be
+    /// restored exactly, even if the transform is not invertible.
+    pub fn transform(&mut self, transform: &Transform) -> Vec<Arc<dyn Change>> {
+        let mut changes = Vec::new();
+
+        self.points.update(
+            |point| *point = transform.transform_point(point),
+            &mut changes,
+        );
+        self.curves
+            .update(|curve| *curve = curve.transform(transform), &mut changes);
+        self.surfaces.update(
+            |surface| *surface = surface.transform(transform),
+            &mut changes,
+        );
+
+        // While some faces use triangle representation, we need this weird
+        // workaround here.
+        self.faces.update(
+            |mut face| {
+                use std::ops::DerefMut as _;
+                if let Face::Triangles(triangles) = face.deref_mut() {
+                    for (triangle, _) in triangles {
+                        *triangle = transform.transform_triangle(triangle);
+                    }
+                }
+            },
+            &mut changes,
+        );
+
+        changes
+    }
 }
 
 pub type Points = Store<Point<3>>;
@@ -63,12 +102,21 @@ pub type Faces = Store<Face>;
 #[derive(Debug)]
 pub struct Store<T> {
     objects: Arc<RwLock<Objects<T>>>,
+    attributes: Arc<RwLock<SecondaryMap<DefaultKey, Attributes>>>,
+
+    /// The objects that have been removed from the shape
+    ///
+    /// Removed objects stay in `objects`, so handles to them can still be
+    /// resolved, and the removal can be undone without invalidating them.
+    removed: Arc<RwLock<SecondaryMap<DefaultKey, ()>>>,
 }
 
 impl<T> Store<T> {
     pub fn new() -> Self {
         Self {
             objects: Arc::new(RwLock::new(SlotMap::new())),
+            attributes: Arc::new(RwLock::new(SecondaryMap::new())),
+            removed: Arc::new(RwLock::new(SecondaryMap::new())),
         }
     }
 
@@ -78,7 +126,29 @@ impl<T> Store<T> {
     }
 
     pub fn contains(&self, object: &Handle<T>) -> bool {
-        object.store() == self && self.objects.read().contains_key(object.key())
+        object.store() == self
+            && self.objects.read().contains_key(object.key())
+            && !self.removed.read().contains_key(object.key())
+    }
+
+    pub fn replace(&mut self, object: &Handle<T>, new: T) -> T {
+        assert!(self.contains(object), "Object is not part of the shape");
+
+        let mut objects = self.objects.write();
+        // Can't panic. We just checked that the object is there.
+        let old = objects.get_mut(object.key()).unwrap();
+        std::mem::replace(old, new)
+    }
+
+    pub fn remove(&mut self, object: &Handle<T>) {
+        assert!(self.contains(object), "Object is not part of the shape");
+        self.removed.write().insert(object.key(), ());
+    }
+
+    pub fn restore(&mut self, object: &Handle<T>) {
+        let was_removed = object.store() == self
+            && self.removed.write().remove(object.key()).is_some();
+        assert!(was_removed, "Object has not been removed from the shape");
     }
 
     pub fn read(&self) -> RwLockReadGuard<Objects<T>> {
@@ -90,22 +160,68 @@ impl<T> Store<T> {
         // this turns into a performance issue, it should be possible to avoid
         // it by adding methods to `Store`, that are geared towards implementing
         // iterators.
+        let removed = self.removed.read();
         Iter {
             elements: self
                 .objects
                 .read()
                 .iter()
+                .filter(|(key, _)| !removed.contains_key(*key))
                 .map(|(key, _)| Handle::new(key, self.clone()))
                 .collect(),
         }
     }
 
-    pub fn update<F>(&mut self, mut f: F)
+    pub fn insert_attribute(
+        &mut self,
+        object: &Handle<T>,
+        attribute: Attribute,
+    ) {
+        assert!(self.contains(object), "Object is not part of the shape");
+
+        let mut attributes = self.attributes.write();
+        match attributes.get_mut(object.key()) {
+            Some(attributes) => attributes.insert(attribute),
+            None => {
+                let mut new = Attributes::new();
+                new.insert(attribute);
+                attributes.insert(object.key(), new);
+            }
+        }
+    }
+
+    pub fn set_attributes(
+        &mut self,
+        object: &Handle<T>,
+        attributes: Attributes,
+    ) {
+        assert!(self.contains(object), "Object is not part of the shape");
+
+        self.attributes.write().insert(object.key(), attributes);
+    }
+
+    /// Update all objects, recording a [`Replace`] for each one that changed
+    pub fn update<F>(&mut self, mut f: F, changes: &mut Vec<Arc<dyn Change>>)
     where
         F: FnMut(&mut T),
+        T: Object,
     {
-        for (_, object) in self.objects.write().iter_mut() {
+        let removed = self.removed.read();
+        for (key, object) in self.objects.write().iter_mut() {
+            if removed.contains_key(key) {
+                continue;
+            }
+
+            let old = object.clone();
             f(object);
+
+            if *object != old {
+                changes.push(Arc::new(Replace {
+                    object: Handle::new(key, self.clone()),
+                    old,
+                    new: object.clone(),
+                }));
+            }
         }
     }
 
@@ -121,6 +237,8 @@ impl<T> Clone for Store<T> {
     fn clone(&self) -> Self {
         Self {
             objects: self.objects.clone(),
+            attributes: self.attributes.clone(),
+            removed: self.removed.clone(),
         }
     }
 }
@@ -182,7 +300,7 @@ pub type Objects<T> = SlotMap<DefaultKey, T>;
 /// The equality of [`Handle`] is very strictly defined in terms of identity.
 /// Two [`Handle`]s are considered equal, if they refer to objects in the same
 /// memory location.
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct Handle<T> {
     key: DefaultKey,
     store: Store<T>,
@@ -210,11 +328,42 @@ impl<T> Handle<T> {
             .read()
             .get(self.key)
             // Can't panic, unless the handle was invalid in the first place.
-            // Objects are never removed from `Store`, so if we have a handle
-            // pointing to it, it should be there.
+            // Objects are never removed from `Store`, only marked as removed,
+            // so if we have a handle pointing to it, it should be there.
             .unwrap()
             .clone()
     }
+
+    /// Access the attributes attached to the object
+    ///
+    /// See [`Shape::insert_attribute`].
+    ///
+    /// [`Shape::insert_attribute`]: super::Shape::insert_attribute
+    pub fn attributes(&self) -> Attributes {
+        self.store
+            .attributes
+            .read()
+            .get(self.key)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+// The derived implementation would print the whole store, which makes any
+// error message that includes a handle unreadable. The key is enough to tell
+// objects apart.
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Handle").field(&self.key.data()).finish()
+    }
+}
+
+// Used to identify objects in a dump of the shape, in the same format as the
+// `Debug` implementation above.
+impl<T> fmt::Display for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:?}", self.key.data())
+    }
 }
 
 /// An iterator over geometric or topological objects
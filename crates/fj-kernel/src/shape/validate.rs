@@ -177,7 +177,7 @@ pub enum ValidationError {
     ///
     /// Structural validation verifies, that all the object that an object
     /// refers to are already part of the shape.
-    #[error("Structural validation failed")]
+    #[error("Structural validation failed: {0:?}")]
     Structural(StructuralIssues),
 
     /// Uniqueness validation failed
@@ -194,9 +194,10 @@ pub enum ValidationError {
     /// Geometric validation checks, that various geometric constraints of an
     /// object are upheld. For example, edges or faces might not be allowed to
     /// intersect.
-    #[error("Geometric validation failed")]
-    #[allow(unused)]
-    Geometric,
+    ///
+    /// Contains a description of the constraint that is violated.
+    #[error("Geometric validation failed: {0}")]
+    Geometric(String),
 }
 
 impl ValidationError {
@@ -0,0 +1,61 @@
+//! Fixed shapes and meshes, that are shared between tests
+
+use fj_interop::mesh::{FaceId, Mesh};
+use fj_math::Point;
+
+/// A box from the origin to `size`, facing outward
+pub fn box_mesh(size: [f64; 3]) -> Mesh<Point<3>> {
+    box_mesh_at([0., 0., 0.], size)
+}
+
+/// A box like [`box_mesh`], with its smallest corner at `min`
+pub fn box_mesh_at(min: [f64; 3], size: [f64; 3]) -> Mesh<Point<3>> {
+    let mut mesh = Mesh::new();
+    for [a, b, c, d] in box_sides(min, size) {
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+        mesh.push_triangle([a, c, d], [255, 0, 0, 255]);
+    }
+
+    mesh
+}
+
+/// A box like [`box_mesh`], with each of its sides being a face of its own
+///
+/// The sides are numbered from 0 to 5: first the near and the far side on the
+/// z-axis, then the ones on the y-axis, then the ones on the x-axis.
+pub fn box_mesh_with_faces(size: [f64; 3]) -> Mesh<Point<3>> {
+    let sides = box_sides([0., 0., 0.], size);
+
+    let mut mesh = Mesh::new();
+    for (i, [a, b, c, d]) in sides.into_iter().enumerate() {
+        let face = Some(FaceId(i as u64));
+
+        mesh.push_triangle_with_face([a, b, c], [255, 0, 0, 255], face);
+        mesh.push_triangle_with_face([a, c, d], [255, 0, 0, 255], face);
+    }
+
+    mesh
+}
+
+/// The corners of each side of a box, counter-clockwise when seen from outside
+fn box_sides(min: [f64; 3], size: [f64; 3]) -> [[Point<3>; 4]; 6] {
+    // The corner at index `i` is at the far side of the box on the x-axis, if
+    // bit 0 of `i` is set, and so on for bits 1 and 2.
+    let corners: Vec<_> = (0..8)
+        .map(|i| {
+            let [x, y, z] = [0, 1, 2]
+                .map(|axis| min[axis] + ((i >> axis) & 1) as f64 * size[axis]);
+            Point::from([x, y, z])
+        })
+        .collect();
+
+    [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ]
+    .map(|side| side.map(|i| corners[i]))
+}
@@ -0,0 +1,17 @@
+//! Property-based tests for the kernel
+//!
+//! The [`strategies`] module contains [proptest] strategies, that generate
+//! random, but valid, sketches and solids. The properties that the kernel must
+//! uphold for all of those are tested in the `properties` module.
+//!
+//! To extend this, add a strategy for the kind of shape you need, if none of
+//! the existing ones fit, and a property test that uses it.
+//!
+//! Meshes that multiple unit tests use are in the [`fixtures`] module.
+//!
+//! [proptest]: https://docs.rs/proptest
+
+pub mod fixtures;
+pub mod strategies;
+
+mod properties;
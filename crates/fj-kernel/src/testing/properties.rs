@@ -0,0 +1,91 @@
+use fj_interop::{debug::DebugInfo, progress::Progress};
+use fj_math::{Scalar, Vector};
+use proptest::prelude::*;
+
+use crate::algorithms::{sweep_shape, triangulate, weld_mesh};
+
+use super::strategies::{
+    convex_polygon, polygon_sketch, sketch, solid, tolerance, transform,
+};
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn sketch_is_valid(points in convex_polygon()) {
+        let num_vertices = points.len();
+
+        // Building the sketch validates it, and would panic, if it weren't.
+        let shape = polygon_sketch(points);
+
+        prop_assert_eq!(shape.faces().count(), 1);
+        prop_assert_eq!(shape.vertices().count(), num_vertices);
+    }
+
+    #[test]
+    fn sweep_is_valid(points in convex_polygon(), height in 0.1..10.) {
+        let num_edges = points.len();
+
+        // Sweeping validates all the objects it creates, and would panic, if
+        // any of them weren't valid.
+        let solid = sweep_shape(
+            polygon_sketch(points),
+            Vector::from([0., 0., height]),
+            tolerance(),
+            [255, 0, 0, 255],
+        );
+
+        // Bottom, top, and one side face per edge of the sketch.
+        prop_assert_eq!(solid.faces().count(), num_edges + 2);
+        prop_assert_eq!(solid.vertices().count(), num_edges * 2);
+    }
+
+    #[test]
+    fn triangulated_sketch_is_manifold(sketch in sketch()) {
+        let mesh = triangulate(
+            sketch,
+            tolerance(),
+            &mut DebugInfo::new(),
+            &Progress::new(),
+        )
+        .unwrap();
+        let (_, report) = weld_mesh(&mesh, tolerance());
+
+        prop_assert_eq!(report.degenerate_triangles, 0);
+        prop_assert_eq!(report.non_manifold_edges, 0);
+    }
+
+    #[test]
+    fn triangulated_solid_is_watertight(solid in solid()) {
+        let mesh = triangulate(
+            solid,
+            tolerance(),
+            &mut DebugInfo::new(),
+            &Progress::new(),
+        )
+        .unwrap();
+        let (_, report) = weld_mesh(&mesh, tolerance());
+
+        prop_assert!(report.is_watertight(), "{:?}", report);
+    }
+
+    #[test]
+    fn transform_round_trips(mut solid in solid(), transform in transform()) {
+        let original: Vec<_> =
+            solid.vertices().map(|vertex| vertex.get().point()).collect();
+
+        solid.transform(&transform);
+        solid.transform(&transform.inverse());
+
+        let round_tripped =
+            solid.vertices().map(|vertex| vertex.get().point());
+        for (a, b) in original.into_iter().zip(round_tripped) {
+            prop_assert!(
+                (a - b).magnitude() < Scalar::from_f64(1e-9),
+                "{:?} != {:?}",
+                a,
+                b
+            );
+        }
+    }
+}
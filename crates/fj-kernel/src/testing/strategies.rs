@@ -0,0 +1,97 @@
+//! Strategies for generating random, valid shapes
+
+use std::f64::consts::TAU;
+
+use fj_math::{Point, Scalar, Transform, Vector};
+use proptest::prelude::*;
+
+use crate::{
+    algorithms::{sweep_shape, Tolerance},
+    geometry::Surface,
+    shape::Shape,
+    topology::Face,
+};
+
+/// The tolerance used for all operations on generated shapes
+pub fn tolerance() -> Tolerance {
+    Tolerance::from_scalar(Scalar::from_f64(0.01)).unwrap()
+}
+
+/// Generate a convex polygon in the xy-plane
+///
+/// The vertices of the polygon are located on a circle, in counter-clockwise
+/// order. The angle between neighboring vertices is never smaller than a
+/// tenth of the average, so vertices are never too close to each other.
+///
+/// With up to 11 vertices, the smallest possible angle is a 101st of a full
+/// turn, so neighboring vertices are at least `radius * 2 * sin(TAU / 202)`
+/// apart, which is more than 6% of the radius. The minimum radius makes sure
+/// that this is still far above the [`tolerance`], or vertices could end up
+/// being welded together.
+pub fn convex_polygon() -> impl Strategy<Value = Vec<Point<3>>> {
+    let num_vertices = 3..12usize;
+    let center = [-10. ..10., -10. ..10.];
+    let radius = 2. ..10.;
+
+    num_vertices
+        .prop_flat_map(|n| prop::collection::vec(1. ..10., n))
+        .prop_flat_map(move |weights| {
+            (Just(weights), center.clone(), radius.clone())
+        })
+        .prop_map(|(weights, [x, y], radius)| {
+            let total: f64 = weights.iter().sum();
+
+            let mut angle = 0f64;
+            weights
+                .into_iter()
+                .map(|weight| {
+                    let point = [
+                        x + radius * angle.cos(),
+                        y + radius * angle.sin(),
+                        0.,
+                    ];
+                    angle += weight / total * TAU;
+                    Point::from(point)
+                })
+                .collect()
+        })
+}
+
+/// Generate a sketch, consisting of a single convex polygon
+pub fn sketch() -> impl Strategy<Value = Shape> {
+    convex_polygon().prop_map(polygon_sketch)
+}
+
+/// Generate a solid, by sweeping a [`sketch`] along the z-axis
+pub fn solid() -> impl Strategy<Value = Shape> {
+    (sketch(), 0.1..10.).prop_map(|(sketch, height)| {
+        sweep_shape(
+            sketch,
+            Vector::from([0., 0., height]),
+            tolerance(),
+            [255, 0, 0, 255],
+        )
+    })
+}
+
+/// Generate a rigid transform, consisting of a rotation and a translation
+pub fn transform() -> impl Strategy<Value = Transform> {
+    let axis_angle = [-3. ..3., -3. ..3., -3. ..3.];
+    let translation = [-100. ..100., -100. ..100., -100. ..100.];
+
+    (axis_angle, translation).prop_map(|(axis_angle, translation)| {
+        Transform::translation(translation) * Transform::rotation(axis_angle)
+    })
+}
+
+/// Build a sketch from the points of a polygon in the xy-plane
+///
+/// Panics, if the polygon is not valid.
+pub fn polygon_sketch(points: Vec<Point<3>>) -> Shape {
+    let mut shape = Shape::new();
+    Face::builder(Surface::xy_plane(), &mut shape)
+        .with_exterior_polygon(points)
+        .build()
+        .unwrap();
+    shape
+}
@@ -2,7 +2,7 @@ use fj_math::{Point, Scalar, Vector};
 
 use crate::{
     geometry::{Circle, Curve, Line, Surface},
-    shape::{Handle, Shape, ValidationResult},
+    shape::{Handle, Shape, ValidationError, ValidationResult},
 };
 
 use super::{Cycle, Edge, Face, Vertex};
@@ -27,10 +27,12 @@ impl<'r> VertexBuilder<'r> {
         self,
         point: impl Into<Point<3>>,
     ) -> ValidationResult<Vertex> {
-        let point = self.shape.get_handle_or_insert(point.into())?;
-        let vertex = self.shape.get_handle_or_insert(Vertex { point })?;
+        self.shape.transaction(|shape| {
+            let point = shape.get_handle_or_insert(point.into())?;
+            let vertex = shape.get_handle_or_insert(Vertex { point })?;
 
-        Ok(vertex)
+            Ok(vertex)
+        })
     }
 }
 
@@ -48,17 +50,81 @@ impl<'r> EdgeBuilder<'r> {
 
     /// Build a circle from a radius
     pub fn build_circle(self, radius: Scalar) -> ValidationResult<Edge> {
-        let curve = self.shape.insert(Curve::Circle(Circle {
-            center: Point::origin(),
-            a: Vector::from([radius, Scalar::ZERO, Scalar::ZERO]),
-            b: Vector::from([Scalar::ZERO, radius, Scalar::ZERO]),
-        }))?;
-        let edge = self.shape.insert(Edge {
-            curve,
-            vertices: None,
-        })?;
+        self.shape.transaction(|shape| {
+            let curve = shape.insert(Curve::Circle(Circle {
+                center: Point::origin(),
+                a: Vector::from([radius, Scalar::ZERO, Scalar::ZERO]),
+                b: Vector::from([Scalar::ZERO, radius, Scalar::ZERO]),
+            }))?;
+            let edge = shape.insert(Edge {
+                curve,
+                vertices: None,
+            })?;
+
+            Ok(edge)
+        })
+    }
+
+    /// Build an arc from three points
+    ///
+    /// The arc starts at the first point, passes through the second, and ends
+    /// at the last. If identical vertices are already part of the shape, those
+    /// are re-used for the start and end.
+    ///
+    /// Returns [`ValidationError::Geometric`], if the arc starts and ends at
+    /// the same point, or if the points are on a straight line. Full circles
+    /// can be built using [`EdgeBuilder::build_circle`].
+    pub fn build_arc_from_points(
+        self,
+        points: [impl Into<Point<3>>; 3],
+    ) -> ValidationResult<Edge> {
+        let [start, through, end] = points.map(Into::into);
+        let min_distance = self.shape.min_distance();
+
+        let u = through - start;
+        let v = end - start;
+        let normal = u.cross(&v);
+
+        if v.magnitude() < min_distance {
+            return Err(ValidationError::Geometric(format!(
+                "Arc starts and ends at the same point, {start:?}. Use \
+                `EdgeBuilder::build_circle` to build a full circle."
+            )));
+        }
+        // That's the distance of `through` from the line through the others.
+        if normal.magnitude() / v.magnitude() < min_distance {
+            return Err(ValidationError::Geometric(format!(
+                "Can't build an arc through {start:?}, {through:?}, and \
+                {end:?}, as they are on a straight line"
+            )));
+        }
 
-        Ok(edge)
+        // The center of the circle that passes through all three points
+        let center = start
+            + (v.cross(&normal) * u.dot(&u) + normal.cross(&u) * v.dot(&v))
+                / (normal.dot(&normal) * 2.);
+
+        // Rotating `a` towards `b` is rotating around the normal, which leads
+        // from `start` through `through` to `end`.
+        let a = start - center;
+        let b = normal.normalize().cross(&a);
+
+        self.shape.transaction(|shape| {
+            let vertices = [start, end]
+                .map(|point| Vertex::builder(shape).build_from_point(point));
+            let vertices = match vertices {
+                [Ok(a), Ok(b)] => Ok([a, b]),
+                [Err(err), _] | [_, Err(err)] => Err(err),
+            }?;
+
+            let curve = shape.insert(Curve::Circle(Circle { center, a, b }))?;
+            let edge = shape.insert(Edge {
+                curve,
+                vertices: Some(vertices),
+            })?;
+
+            Ok(edge)
+        })
     }
 
     /// Build a line segment from two points
@@ -66,18 +132,21 @@ impl<'r> EdgeBuilder<'r> {
         self,
         vertices: [impl Into<Point<3>>; 2],
     ) -> ValidationResult<Edge> {
-        // Can be cleaned up with `try_map`, once that is stable:
-        // https://doc.rust-lang.org/std/primitive.array.html#method.try_map
-        let vertices = vertices
-            .map(|point| Vertex::builder(self.shape).build_from_point(point));
-        let vertices = match vertices {
-            [Ok(a), Ok(b)] => Ok([a, b]),
-            [Err(err), _] | [_, Err(err)] => Err(err),
-        }?;
-
-        let edge = self.build_line_segment_from_vertices(vertices)?;
-
-        Ok(edge)
+        self.shape.transaction(|shape| {
+            // Can be cleaned up with `try_map`, once that is stable:
+            // https://doc.rust-lang.org/std/primitive.array.html#method.try_map
+            let vertices = vertices
+                .map(|point| Vertex::builder(shape).build_from_point(point));
+            let vertices = match vertices {
+                [Ok(a), Ok(b)] => Ok([a, b]),
+                [Err(err), _] | [_, Err(err)] => Err(err),
+            }?;
+
+            let edge = Edge::builder(shape)
+                .build_line_segment_from_vertices(vertices)?;
+
+            Ok(edge)
+        })
     }
 
     /// Build a line segment from two vertices
@@ -85,15 +154,17 @@ impl<'r> EdgeBuilder<'r> {
         self,
         vertices: [Handle<Vertex>; 2],
     ) -> ValidationResult<Edge> {
-        let curve = self.shape.insert(Curve::Line(Line::from_points(
-            vertices.clone().map(|vertex| vertex.get().point()),
-        )))?;
-        let edge = self.shape.insert(Edge {
-            curve,
-            vertices: Some(vertices),
-        })?;
-
-        Ok(edge)
+        self.shape.transaction(|shape| {
+            let curve = shape.insert(Curve::Line(Line::from_points(
+                vertices.clone().map(|vertex| vertex.get().point()),
+            )))?;
+            let edge = shape.insert(Edge {
+                curve,
+                vertices: Some(vertices),
+            })?;
+
+            Ok(edge)
+        })
     }
 }
 
@@ -121,19 +192,170 @@ impl<'r> CycleBuilder<'r> {
             points.push(point);
         }
 
-        let mut edges = Vec::new();
-        for ab in points.windows(2) {
-            // Can't panic, as we passed `2` to `windows`.
-            //
-            // Can be cleaned up, once `array_windows` is stable.
-            let points = [ab[0], ab[1]];
+        self.shape.transaction(|shape| {
+            let mut edges = Vec::new();
+            for ab in points.windows(2) {
+                // Can't panic, as we passed `2` to `windows`.
+                //
+                // Can be cleaned up, once `array_windows` is stable.
+                let points = [ab[0], ab[1]];
+
+                let edge = Edge::builder(shape)
+                    .build_line_segment_from_points(points)?;
+                edges.push(edge);
+            }
+
+            shape.insert(Cycle { edges })
+        })
+    }
+
+    /// Build a cycle from a wire
+    ///
+    /// Builds one edge for each segment of the wire. Returns
+    /// [`ValidationError::Geometric`], if the wire isn't closed, or any of its
+    /// segments is degenerate.
+    pub fn build_wire(self, wire: Wire) -> ValidationResult<Cycle> {
+        let min_distance = self.shape.min_distance();
+
+        if wire.segments.is_empty() {
+            return Err(ValidationError::Geometric(format!(
+                "Wire that starts at {:?} has no segments",
+                wire.start
+            )));
+        }
+        if (wire.end() - wire.start).magnitude() >= min_distance {
+            return Err(ValidationError::Geometric(format!(
+                "Wire isn't closed. It starts at {:?}, but ends at {:?}. Use \
+                `Wire::close` to connect its ends.",
+                wire.start,
+                wire.end()
+            )));
+        }
+
+        self.shape.transaction(|shape| {
+            let mut start = wire.start;
+            let mut edges = Vec::new();
+
+            for segment in wire.segments {
+                let edge = match segment {
+                    WireSegment::Line { end } => {
+                        if (end - start).magnitude() < min_distance {
+                            return Err(ValidationError::Geometric(format!(
+                                "Line segment of wire starts and ends at the \
+                                same point, {start:?}"
+                            )));
+                        }
+
+                        Edge::builder(shape)
+                            .build_line_segment_from_points([start, end])?
+                    }
+                    WireSegment::Arc { through, end } => {
+                        Edge::builder(shape)
+                            .build_arc_from_points([start, through, end])?
+                    }
+                };
+
+                edges.push(edge);
+                start = segment.end();
+            }
+
+            shape.insert(Cycle { edges })
+        })
+    }
+}
+
+/// A closed sequence of line segments and arcs
+///
+/// Describes a cycle, without having to build its edges one by one. Start a
+/// wire using [`Wire::from_point`], add segments, and connect its end to its
+/// start using [`Wire::close`]. The wire can then be built, using
+/// [`CycleBuilder::build_wire`], or used as the boundary of a face, using
+/// [`FaceBuilder`].
+#[derive(Clone, Debug)]
+pub struct Wire {
+    start: Point<3>,
+    segments: Vec<WireSegment>,
+}
+
+impl Wire {
+    /// Start a wire at a point
+    pub fn from_point(start: impl Into<Point<3>>) -> Self {
+        Self {
+            start: start.into(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Add a line segment from the current end of the wire to `end`
+    pub fn line_to(mut self, end: impl Into<Point<3>>) -> Self {
+        self.segments.push(WireSegment::Line { end: end.into() });
+        self
+    }
 
-            let edge = Edge::builder(self.shape)
-                .build_line_segment_from_points(points)?;
-            edges.push(edge);
+    /// Add a polyline, made of a line segment to each of the points in turn
+    pub fn lines_to(
+        self,
+        points: impl IntoIterator<Item = impl Into<Point<3>>>,
+    ) -> Self {
+        points
+            .into_iter()
+            .fold(self, |wire, point| wire.line_to(point))
+    }
+
+    /// Add an arc from the current end of the wire, through `through`, to `end`
+    pub fn arc_to(
+        mut self,
+        through: impl Into<Point<3>>,
+        end: impl Into<Point<3>>,
+    ) -> Self {
+        self.segments.push(WireSegment::Arc {
+            through: through.into(),
+            end: end.into(),
+        });
+        self
+    }
+
+    /// Close the wire, by adding a line segment from its end to its start
+    ///
+    /// Does nothing, if the wire already ends at its start.
+    pub fn close(self) -> Self {
+        if self.end() == self.start {
+            return self;
         }
 
-        self.shape.insert(Cycle { edges })
+        let start = self.start;
+        self.line_to(start)
+    }
+
+    /// The point that the wire currently ends at
+    fn end(&self) -> Point<3> {
+        self.segments
+            .last()
+            .map_or(self.start, |segment| segment.end())
+    }
+
+    /// All points that define the wire
+    fn points(&self) -> impl Iterator<Item = Point<3>> + '_ {
+        Some(self.start)
+            .into_iter()
+            .chain(self.segments.iter().flat_map(|segment| match *segment {
+                WireSegment::Line { end } => vec![end],
+                WireSegment::Arc { through, end } => vec![through, end],
+            }))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum WireSegment {
+    Line { end: Point<3> },
+    Arc { through: Point<3>, end: Point<3> },
+}
+
+impl WireSegment {
+    fn end(&self) -> Point<3> {
+        match self {
+            Self::Line { end } | Self::Arc { end, .. } => *end,
+        }
     }
 }
 
@@ -141,8 +363,8 @@ impl<'r> CycleBuilder<'r> {
 #[must_use]
 pub struct FaceBuilder<'r> {
     surface: Surface,
-    exterior: Option<Vec<Point<3>>>,
-    interiors: Vec<Vec<Point<3>>>,
+    exterior: Option<Boundary>,
+    interiors: Vec<Boundary>,
 
     shape: &'r mut Shape,
 }
@@ -167,7 +389,17 @@ impl<'r> FaceBuilder<'r> {
         let points = points.into_iter().map(Into::into).collect();
 
         Self {
-            exterior: Some(points),
+            exterior: Some(Boundary::Polygon(points)),
+            ..self
+        }
+    }
+
+    /// Make a wire the exterior of the face
+    ///
+    /// The wire must lie in the surface of the face. See [`FaceBuilder::build`].
+    pub fn with_exterior_wire(self, wire: Wire) -> Self {
+        Self {
+            exterior: Some(Boundary::Wire(wire)),
             ..self
         }
     }
@@ -180,34 +412,191 @@ impl<'r> FaceBuilder<'r> {
         let points = points.into_iter().map(Into::into).collect();
 
         let mut interiors = self.interiors;
-        interiors.push(points);
+        interiors.push(Boundary::Polygon(points));
+
+        Self { interiors, ..self }
+    }
+
+    /// Add an interior wire to the face
+    ///
+    /// The wire must lie in the surface of the face. See [`FaceBuilder::build`].
+    pub fn with_interior_wire(self, wire: Wire) -> Self {
+        let mut interiors = self.interiors;
+        interiors.push(Boundary::Wire(wire));
 
         Self { interiors, ..self }
     }
 
     /// Build the face
+    ///
+    /// If building any part of the face fails, none of the objects that have
+    /// been inserted into the shape up to that point remain part of it.
+    ///
+    /// Returns [`ValidationError::Geometric`], if any of the points that
+    /// define a wire aren't on the surface of the face. Points in between, on
+    /// arcs for example, are not checked.
     pub fn build(self) -> ValidationResult<Face> {
-        let surface = self.shape.insert(self.surface)?;
+        let Self {
+            surface,
+            exterior,
+            interiors,
+            shape,
+        } = self;
+
+        shape.transaction(|shape| {
+            let surface = shape.insert(surface)?;
+
+            let exteriors = match exterior {
+                Some(boundary) => {
+                    let cycle = boundary.build("exterior", &surface, shape)?;
+                    vec![cycle]
+                }
+                None => Vec::new(),
+            };
+
+            let mut cycles = Vec::new();
+            for boundary in interiors {
+                let cycle = boundary.build("interior", &surface, shape)?;
+                cycles.push(cycle);
+            }
+
+            shape.insert(Face::Face {
+                surface,
+                exteriors,
+                interiors: cycles,
+                color: [255, 0, 0, 255],
+            })
+        })
+    }
+}
+
+/// A boundary of a face, as it was passed to [`FaceBuilder`]
+enum Boundary {
+    Polygon(Vec<Point<3>>),
+    Wire(Wire),
+}
 
-        let exteriors = match self.exterior {
-            Some(points) => {
-                let cycle = Cycle::builder(self.shape).build_polygon(points)?;
-                vec![cycle]
+impl Boundary {
+    fn build(
+        self,
+        kind: &str,
+        surface: &Handle<Surface>,
+        shape: &mut Shape,
+    ) -> ValidationResult<Cycle> {
+        match self {
+            Self::Polygon(points) => {
+                Cycle::builder(shape).build_polygon(points)
             }
-            None => Vec::new(),
+            Self::Wire(wire) => {
+                let surface = surface.get();
+
+                for point in wire.points() {
+                    let native = surface.point_model_to_surface(point).native();
+                    let distance = (surface.point_surface_to_model(&native)
+                        - point)
+                        .magnitude();
+
+                    if distance >= shape.min_distance() {
+                        return Err(ValidationError::Geometric(format!(
+                            "The {kind} wire of the face isn't on its surface. \
+                            Point {point:?} is {distance} away from it."
+                        )));
+                    }
+                }
+
+                Cycle::builder(shape).build_wire(wire)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        geometry::{Curve, Surface},
+        shape::{Shape, ValidationError},
+        topology::{Cycle, Edge, Face},
+    };
+
+    use super::Wire;
+
+    #[test]
+    fn arc_through_points() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let edge = Edge::builder(&mut shape).build_arc_from_points([
+            [2., 0., 0.],
+            [1., 1., 0.],
+            [0., 0., 0.],
+        ])?;
+
+        let circle = match edge.get().curve() {
+            Curve::Circle(circle) => circle,
+            curve => panic!("Expected circle, got {curve:?}"),
         };
+        assert!(
+            (circle.center - Point::from([1., 0., 0.])).magnitude()
+                < Scalar::from_f64(1e-12)
+        );
+        assert_eq!(circle.a.magnitude(), Scalar::ONE);
+
+        // The arc runs counter-clockwise, so it passes through the middle
+        // point at a quarter of a circle.
+        let through =
+            circle.point_curve_to_model(&Point::from([Scalar::PI / 2.]));
+        assert!(
+            (through - Point::from([1., 1., 0.])).magnitude()
+                < Scalar::from_f64(1e-12)
+        );
+
+        Ok(())
+    }
 
-        let mut interiors = Vec::new();
-        for points in self.interiors {
-            let cycle = Cycle::builder(self.shape).build_polygon(points)?;
-            interiors.push(cycle);
-        }
+    #[test]
+    fn arc_through_points_on_a_line() {
+        let mut shape = Shape::new();
+
+        let result = Edge::builder(&mut shape).build_arc_from_points([
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [2., 0., 0.],
+        ]);
+        assert!(matches!(result, Err(ValidationError::Geometric(_))));
+        assert_eq!(shape.vertices().count(), 0);
+    }
 
-        self.shape.insert(Face::Face {
-            surface,
-            exteriors,
-            interiors,
-            color: [255, 0, 0, 255],
-        })
+    #[test]
+    fn closed_wire() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let wire = Wire::from_point([0., 0., 0.])
+            .line_to([2., 0., 0.])
+            .arc_to([3., 1., 0.], [2., 2., 0.])
+            .line_to([0., 2., 0.]);
+
+        let result = Cycle::builder(&mut shape).build_wire(wire.clone());
+        assert!(matches!(result, Err(ValidationError::Geometric(_))));
+
+        let cycle = Cycle::builder(&mut shape).build_wire(wire.close())?;
+        assert_eq!(cycle.get().edges.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn face_wire_on_surface() {
+        let mut shape = Shape::new();
+
+        let wire = Wire::from_point([0., 0., 0.])
+            .lines_to([[1., 0., 0.], [0., 1., 1.]])
+            .close();
+
+        let result = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_wire(wire)
+            .build();
+        assert!(matches!(result, Err(ValidationError::Geometric(_))));
+        assert_eq!(shape.surfaces().count(), 0);
     }
 }
@@ -1,6 +1,9 @@
-use parry3d_f64::bounding_volume::BoundingVolume as _;
+use parry3d_f64::{
+    bounding_volume::BoundingVolume as _,
+    query::{Ray, RayCast as _},
+};
 
-use super::{Point, Vector};
+use super::{Point, Scalar, Vector};
 
 /// An axis-aligned bounding box (AABB)
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -85,6 +88,24 @@ impl Aabb<3> {
     pub fn merged(&self, other: &Self) -> Self {
         self.to_parry().merged(&other.to_parry()).into()
     }
+
+    /// Cast a ray against the AABB
+    pub fn cast_local_ray(
+        &self,
+        origin: Point<3>,
+        dir: Vector<3>,
+        max_toi: f64,
+        solid: bool,
+    ) -> Option<Scalar> {
+        let ray = Ray {
+            origin: origin.to_na(),
+            dir: dir.to_na(),
+        };
+
+        self.to_parry()
+            .cast_local_ray(&ray, max_toi, solid)
+            .map(|f| f.into())
+    }
 }
 
 impl From<parry2d_f64::bounding_volume::AABB> for Aabb<2> {
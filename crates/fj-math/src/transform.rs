@@ -111,10 +111,52 @@ impl Transform {
         }
     }
 
+    /// Interpolate between this transform and another one
+    ///
+    /// Returns `self` for a `t` of `0`, and `other` for a `t` of `1`. The
+    /// rotation is interpolated spherically, the translation linearly.
+    ///
+    /// # Implementation note
+    ///
+    /// Both transforms are assumed to consist only of rotation and
+    /// translation. Any scaling or shearing is lost.
+    pub fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let [a, b] = [self, other].map(|transform| {
+            let matrix = transform.0.matrix();
+
+            let rotation = nalgebra::UnitQuaternion::from_matrix(
+                &matrix.fixed_slice::<3, 3>(0, 0).into_owned(),
+            );
+            let translation: nalgebra::Vector3<f64> =
+                matrix.fixed_slice::<3, 1>(0, 3).into_owned();
+
+            (rotation, translation)
+        });
+
+        let rotation = a.0.slerp(&b.0, t);
+        let translation = a.1.lerp(&b.1, t);
+
+        let isometry = nalgebra::Isometry3::from_parts(
+            nalgebra::Translation3::from(translation),
+            rotation,
+        );
+
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            isometry.to_homogeneous(),
+        ))
+    }
+
     /// Exposes the data of this Transform as a slice of f64.
     pub fn data(&self) -> &[f64] {
         self.0.matrix().data.as_slice()
     }
+
+    /// Construct a transform from data, as returned by [`Transform::data`]
+    pub fn from_data(data: [f64; 16]) -> Self {
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            nalgebra::Matrix4::from_column_slice(&data),
+        ))
+    }
 }
 
 impl ops::Mul<Self> for Transform {
@@ -124,3 +166,34 @@ impl ops::Mul<Self> for Transform {
         Self(self.0.mul(rhs.0))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::Point;
+
+    use super::Transform;
+
+    #[test]
+    fn interpolate() {
+        let a = Transform::identity();
+        let b = Transform::translation([2., 0., 0.])
+            * Transform::rotation([0., 0., FRAC_PI_2]);
+
+        let point = Point::from([1., 0., 0.]);
+
+        assert_abs_diff_eq!(
+            a.interpolate(&b, 0.5).transform_point(&point),
+            Point::from([1. + FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.]),
+            epsilon = 1e-9
+        );
+        assert_abs_diff_eq!(
+            a.interpolate(&b, 1.).transform_point(&point),
+            b.transform_point(&point),
+            epsilon = 1e-9
+        );
+    }
+}
@@ -54,6 +54,28 @@ impl<const D: usize> Triangle<D> {
         self.points.sort();
         self
     }
+
+    /// Compute the area of the triangle
+    pub fn area(&self) -> Scalar {
+        let [a, b, c] = self.points.map(Point::to_xyz);
+        (b - a).cross(&(c - a)).magnitude() / 2.
+    }
+
+    /// Compute the aspect ratio of the triangle
+    ///
+    /// This is the ratio of the circumradius to twice the inradius. It is `1`
+    /// for an equilateral triangle, and grows as the triangle becomes more
+    /// slender.
+    pub fn aspect_ratio(&self) -> Scalar {
+        let [a, b, c] = self.points;
+        let [la, lb, lc] =
+            [[b, c], [c, a], [a, b]].map(|[p1, p2]| Point::distance(&p1, &p2));
+
+        let semi_perimeter = (la + lb + lc) / 2.;
+        let area = self.area();
+
+        la * lb * lc * semi_perimeter / (area * area * 8.)
+    }
 }
 
 impl Triangle<2> {
@@ -118,7 +140,9 @@ impl From<Orientation> for Winding {
 
 #[cfg(test)]
 mod tests {
-    use crate::Point;
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar};
 
     use super::Triangle;
 
@@ -138,6 +162,28 @@ mod tests {
         let _triangle = Triangle::from([a, b, c]);
     }
 
+    #[test]
+    fn aspect_ratio() {
+        let equilateral = Triangle::from([
+            Point::from([0.0, 0.0]),
+            Point::from([1.0, 0.0]),
+            Point::from([0.5, 3.0_f64.sqrt() / 2.0]),
+        ]);
+        assert_abs_diff_eq!(
+            equilateral.aspect_ratio(),
+            Scalar::ONE,
+            epsilon = Scalar::from_f64(1e-12)
+        );
+
+        let slender = Triangle::from([
+            Point::from([0.0, 0.0]),
+            Point::from([10.0, 0.0]),
+            Point::from([5.0, 0.1]),
+        ]);
+        assert!(slender.aspect_ratio() > Scalar::from_f64(10.0));
+        assert_eq!(slender.area(), Scalar::from_f64(0.5));
+    }
+
     #[test]
     #[should_panic]
     fn invalid_triangle_2d() {
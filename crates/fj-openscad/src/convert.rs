@@ -0,0 +1,1199 @@
+//! Conversion of parsed OpenSCAD code into [`fj`] operations
+
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::PI,
+    fmt,
+};
+
+use crate::{
+    parser::{Call, Expression, Operator, Statement},
+    Error,
+};
+
+/// A value that an expression evaluates to
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Bool(bool),
+    Vector(Vec<Value>),
+    Undef,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{}", number),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Vector(components) => {
+                write!(f, "[")?;
+                for (i, component) in components.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", component)?;
+                }
+                write!(f, "]")
+            }
+            Self::Undef => write!(f, "undef"),
+        }
+    }
+}
+
+/// An object that a module instantiation creates
+#[derive(Clone, Debug)]
+pub enum Object {
+    Shape2d(fj::Shape2d),
+    Solid(Solid),
+}
+
+/// A 3-dimensional object
+///
+/// `fj` has no 3-dimensional difference. Most differences in OpenSCAD code
+/// subtract extrusions from other extrusions though, which can be expressed
+/// as an extrusion of a 2-dimensional difference. To make that possible,
+/// extrusions are kept around until they are needed as [`fj::Shape3d`].
+#[derive(Clone, Debug)]
+pub enum Solid {
+    /// A 2-dimensional shape, extruded along the z-axis
+    Extrusion {
+        footprint: fj::Shape2d,
+        bottom: f64,
+        top: f64,
+    },
+
+    /// A sphere
+    ///
+    /// This is kept apart from other shapes, as spheres are what the Minkowski
+    /// sum of a solid rounds it with.
+    Sphere { center: [f64; 3], radius: f64 },
+
+    /// Any other 3-dimensional shape
+    Shape(fj::Shape3d),
+}
+
+impl Solid {
+    pub fn into_shape(self) -> fj::Shape3d {
+        match self {
+            Self::Extrusion {
+                footprint,
+                bottom,
+                top,
+            } => {
+                let sweep: fj::Shape3d =
+                    fj::Sweep::from_path(footprint, [0., 0., top - bottom])
+                        .into();
+
+                if bottom == 0. {
+                    return sweep;
+                }
+
+                translate_3d(sweep, [0., 0., bottom])
+            }
+            Self::Sphere { center, radius } => {
+                let [min, max] = sphere_bounds(center, radius);
+                fj::Implicit::from_fn(min, max, move |point| {
+                    let distance = (0..3)
+                        .map(|i| (point[i] - center[i]).powi(2))
+                        .sum::<f64>()
+                        .sqrt();
+                    distance - radius
+                })
+                .into()
+            }
+            Self::Shape(shape) => shape,
+        }
+    }
+}
+
+impl Solid {
+    /// Compute the axis-aligned bounding box, as its minimum and maximum
+    ///
+    /// Returns `None` for shapes that this module doesn't create, which only
+    /// happens for rotated shapes.
+    fn bounds(&self) -> Option<[[f64; 3]; 2]> {
+        match self {
+            Self::Extrusion {
+                footprint,
+                bottom,
+                top,
+            } => {
+                let [[x0, y0], [x1, y1]] = bounds_2d(footprint);
+                Some([[x0, y0, *bottom], [x1, y1, *top]])
+            }
+            Self::Sphere { center, radius } => {
+                Some(sphere_bounds(*center, *radius))
+            }
+            Self::Shape(shape) => bounds_3d(shape),
+        }
+    }
+}
+
+fn sphere_bounds(center: [f64; 3], radius: f64) -> [[f64; 3]; 2] {
+    [
+        center.map(|coord| coord - radius),
+        center.map(|coord| coord + radius),
+    ]
+}
+
+fn bounds_2d(shape: &fj::Shape2d) -> [[f64; 2]; 2] {
+    match shape {
+        fj::Shape2d::Circle(circle) => {
+            let r = circle.radius();
+            [[-r, -r], [r, r]]
+        }
+        fj::Shape2d::Sketch(sketch) => {
+            let mut bounds = [[f64::INFINITY; 2], [f64::NEG_INFINITY; 2]];
+            for point in sketch.to_points() {
+                for i in 0..2 {
+                    bounds[0][i] = bounds[0][i].min(point[i]);
+                    bounds[1][i] = bounds[1][i].max(point[i]);
+                }
+            }
+            bounds
+        }
+        // Subtracting from a shape can only make it smaller.
+        fj::Shape2d::Difference(difference) => {
+            bounds_2d(&difference.shapes()[0])
+        }
+        fj::Shape2d::Minkowski(minkowski) => {
+            let [a, b] =
+                minkowski.shapes().clone().map(|shape| bounds_2d(&shape));
+            [
+                [a[0][0] + b[0][0], a[0][1] + b[0][1]],
+                [a[1][0] + b[1][0], a[1][1] + b[1][1]],
+            ]
+        }
+    }
+}
+
+fn bounds_3d(shape: &fj::Shape3d) -> Option<[[f64; 3]; 2]> {
+    match shape {
+        fj::Shape3d::Sweep(sweep) => {
+            let [[x0, y0], [x1, y1]] = bounds_2d(sweep.shape());
+            let path = sweep.path();
+
+            let mut bounds = [[x0, y0, 0.], [x1, y1, 0.]];
+            for i in 0..3 {
+                bounds[0][i] += path[i].min(0.);
+                bounds[1][i] += path[i].max(0.);
+            }
+            Some(bounds)
+        }
+        fj::Shape3d::Group(group) => {
+            let a = bounds_3d(&group.a)?;
+            let b = bounds_3d(&group.b)?;
+
+            Some([
+                [0, 1, 2].map(|i| a[0][i].min(b[0][i])),
+                [0, 1, 2].map(|i| a[1][i].max(b[1][i])),
+            ])
+        }
+        fj::Shape3d::Implicit(implicit) => {
+            Some([implicit.min(), implicit.max()])
+        }
+        fj::Shape3d::Rounded(rounded) => {
+            let [min, max] = bounds_3d(rounded.shape())?;
+            let radius = rounded.radius();
+
+            Some([min.map(|c| c - radius), max.map(|c| c + radius)])
+        }
+        fj::Shape3d::Transform(transform) if transform.angle == 0. => {
+            let [min, max] = bounds_3d(&transform.shape)?;
+            let offset = transform.offset;
+
+            Some([
+                [0, 1, 2].map(|i| min[i] + offset[i]),
+                [0, 1, 2].map(|i| max[i] + offset[i]),
+            ])
+        }
+        _ => None,
+    }
+}
+
+/// The variables that are visible to a statement
+#[derive(Clone, Default)]
+pub struct Context {
+    variables: HashMap<String, Value>,
+
+    /// Variables that have been overridden, and ignore assignments
+    overridden: HashSet<String>,
+}
+
+impl Context {
+    /// Create a context with some variables overridden
+    pub fn with_overrides(overrides: HashMap<String, Value>) -> Self {
+        Self {
+            overridden: overrides.keys().cloned().collect(),
+            variables: overrides,
+        }
+    }
+
+    /// Create the context for the children of a module instantiation
+    ///
+    /// Overrides only apply to the top level, like they do in OpenSCAD.
+    fn child(&self) -> Self {
+        Self {
+            variables: self.variables.clone(),
+            overridden: HashSet::new(),
+        }
+    }
+}
+
+/// Evaluate statements into the objects they create
+pub fn objects(
+    statements: &[Statement],
+    context: &mut Context,
+) -> Result<Vec<Object>, Error> {
+    let mut objects = Vec::new();
+
+    for statement in statements {
+        match statement {
+            Statement::Assignment { name, value, line } => {
+                if context.overridden.contains(name) {
+                    continue;
+                }
+
+                let value = evaluate(value, context, *line)?;
+                context.variables.insert(name.clone(), value);
+            }
+            Statement::Call(call) => {
+                objects.extend(instantiate(call, context)?);
+            }
+        }
+    }
+
+    Ok(objects)
+}
+
+/// Evaluate the top-level assignments, without instantiating any modules
+///
+/// Returns each variable with its final value, in the order of their first
+/// assignment.
+pub fn variables(
+    statements: &[Statement],
+) -> Result<Vec<(String, Value)>, Error> {
+    let mut context = Context::default();
+    let mut names: Vec<String> = Vec::new();
+
+    for statement in statements {
+        if let Statement::Assignment { name, value, line } = statement {
+            let value = evaluate(value, &context, *line)?;
+            context.variables.insert(name.clone(), value);
+
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let value = context.variables.remove(&name).unwrap_or(Value::Undef);
+            (name, value)
+        })
+        .collect())
+}
+
+/// Combine objects into one, like OpenSCAD does for a module's children
+pub fn union(
+    objects: Vec<Object>,
+    line: usize,
+) -> Result<Option<Object>, Error> {
+    let mut objects = objects.into_iter();
+
+    let first = match objects.next() {
+        Some(object) => object,
+        None => return Ok(None),
+    };
+
+    objects
+        .try_fold(first, |a, b| match (a, b) {
+            (Object::Solid(a), Object::Solid(b)) => {
+                // A group is not a real union, but it's the same thing, as
+                // long as the objects don't touch. Anything else would result
+                // in a mesh that isn't manifold.
+                let disjoint = match (a.bounds(), b.bounds()) {
+                    (Some(a), Some(b)) => {
+                        (0..3).any(|i| a[1][i] < b[0][i] || b[1][i] < a[0][i])
+                    }
+                    _ => false,
+                };
+                if !disjoint {
+                    return Err(Error::evaluation(
+                        line,
+                        "Union is only supported between objects whose \
+                        bounding boxes don't touch",
+                    ));
+                }
+
+                let group = fj::Group {
+                    a: a.into_shape(),
+                    b: b.into_shape(),
+                };
+                Ok(Object::Solid(Solid::Shape(group.into())))
+            }
+            (Object::Shape2d(_), Object::Shape2d(_)) => Err(Error::evaluation(
+                line,
+                "Union of 2D shapes is not supported",
+            )),
+            _ => Err(Error::evaluation(line, "Can't mix 2D and 3D objects")),
+        })
+        .map(Some)
+}
+
+fn instantiate(
+    call: &Call,
+    context: &Context,
+) -> Result<Option<Object>, Error> {
+    let arguments = Arguments::new(call, context)?;
+    let line = call.line;
+
+    let children = || {
+        let objects = objects(&call.children, &mut context.child())?;
+        union(objects, line)
+    };
+
+    let object = match call.name.as_str() {
+        "square" => Object::Shape2d(square(&arguments)?),
+        "circle" => {
+            let radius = arguments.radius(Some(0), "r", "d")?.unwrap_or(1.);
+            Object::Shape2d(circle(radius, line)?)
+        }
+        "polygon" => {
+            if arguments.get(Some(1), "paths").is_some() {
+                return Err(Error::evaluation(
+                    line,
+                    "Polygons with paths are not supported",
+                ));
+            }
+
+            let points = arguments
+                .get(Some(0), "points")
+                .ok_or_else(|| Error::evaluation(line, "Missing points"))?;
+            let points = match points {
+                Value::Vector(points) => points
+                    .iter()
+                    .map(|point| vector::<2>(point, line))
+                    .collect::<Result<_, _>>()?,
+                _ => {
+                    return Err(Error::evaluation(
+                        line,
+                        "Points must be a vector",
+                    ))
+                }
+            };
+
+            Object::Shape2d(sketch(points, line)?)
+        }
+        "cube" => {
+            let size = match arguments.get(Some(0), "size") {
+                Some(size) => size_of::<3>(size, line)?,
+                None => [1.; 3],
+            };
+            let center = arguments.bool(Some(1), "center")?;
+
+            let [x, y, z] = size;
+            let footprint = rectangle([x, y], center, line)?;
+            Object::Solid(extrusion(footprint, z, center))
+        }
+        "sphere" => {
+            let radius = arguments.radius(Some(0), "r", "d")?.unwrap_or(1.);
+            if !radius.is_finite() || radius <= 0. {
+                return Err(Error::evaluation(line, "Radius must be positive"));
+            }
+
+            Object::Solid(Solid::Sphere {
+                center: [0.; 3],
+                radius,
+            })
+        }
+        "cylinder" => {
+            let height = arguments.number(Some(0), "h")?.unwrap_or(1.);
+            let center = arguments.bool(Some(3), "center")?;
+
+            let radius = match arguments.radius(None, "r", "d")? {
+                Some(radius) => radius,
+                None => {
+                    let r1 = arguments.radius(Some(1), "r1", "d1")?;
+                    let r2 = arguments.radius(Some(2), "r2", "d2")?;
+
+                    match (r1, r2) {
+                        (Some(r1), Some(r2)) if r1 != r2 => {
+                            return Err(Error::evaluation(
+                                line,
+                                "Cones are not supported",
+                            ))
+                        }
+                        (r1, r2) => r1.or(r2).unwrap_or(1.),
+                    }
+                }
+            };
+
+            let footprint = circle(radius, line)?;
+            Object::Solid(extrusion(footprint, height, center))
+        }
+        "linear_extrude" => {
+            for unsupported in ["twist", "scale"] {
+                if arguments.get(None, unsupported).is_some() {
+                    return Err(Error::evaluation(
+                        line,
+                        format!("`{}` is not supported", unsupported),
+                    ));
+                }
+            }
+
+            let height = arguments.number(Some(0), "height")?.unwrap_or(100.);
+            let center = arguments.bool(Some(1), "center")?;
+
+            match children()? {
+                Some(Object::Shape2d(footprint)) => {
+                    Object::Solid(extrusion(footprint, height, center))
+                }
+                Some(Object::Solid(_)) => {
+                    return Err(Error::evaluation(
+                        line,
+                        "Only 2D shapes can be extruded",
+                    ))
+                }
+                None => return Ok(None),
+            }
+        }
+        "translate" => {
+            let offset = arguments
+                .get(Some(0), "v")
+                .ok_or_else(|| Error::evaluation(line, "Missing vector"))?;
+            let offset = match offset {
+                Value::Vector(components) if components.len() == 2 => {
+                    let [x, y] = vector::<2>(offset, line)?;
+                    [x, y, 0.]
+                }
+                offset => vector::<3>(offset, line)?,
+            };
+
+            match children()? {
+                Some(object) => translate(object, offset, context),
+                None => return Ok(None),
+            }
+        }
+        "difference" => {
+            let objects = objects(&call.children, &mut context.child())?;
+            let mut objects = objects.into_iter();
+
+            let first = match objects.next() {
+                Some(object) => object,
+                None => return Ok(None),
+            };
+
+            objects.try_fold(first, |a, b| difference(a, b, line))?
+        }
+        "minkowski" => {
+            let objects = objects(&call.children, &mut context.child())?;
+            let mut objects = objects.into_iter();
+
+            let first = match objects.next() {
+                Some(object) => object,
+                None => return Ok(None),
+            };
+
+            objects.try_fold(first, |a, b| minkowski(a, b, line))?
+        }
+        "union" => match children()? {
+            Some(object) => object,
+            None => return Ok(None),
+        },
+        name => {
+            return Err(Error::evaluation(
+                line,
+                format!("Module `{}` is not supported", name),
+            ))
+        }
+    };
+
+    Ok(Some(object))
+}
+
+fn square(arguments: &Arguments) -> Result<fj::Shape2d, Error> {
+    let size = match arguments.get(Some(0), "size") {
+        Some(size) => size_of::<2>(size, arguments.line)?,
+        None => [1.; 2],
+    };
+    let center = arguments.bool(Some(1), "center")?;
+
+    rectangle(size, center, arguments.line)
+}
+
+fn rectangle(
+    [x, y]: [f64; 2],
+    center: bool,
+    line: usize,
+) -> Result<fj::Shape2d, Error> {
+    let [x0, y0] = if center { [-x / 2., -y / 2.] } else { [0., 0.] };
+    let [x1, y1] = [x0 + x, y0 + y];
+
+    sketch(vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]], line)
+}
+
+fn circle(radius: f64, line: usize) -> Result<fj::Shape2d, Error> {
+    if radius.is_nan() || radius <= 0. {
+        return Err(Error::evaluation(line, "Radius must be positive"));
+    }
+
+    Ok(fj::Circle::from_radius(radius).into())
+}
+
+fn sketch(
+    mut points: Vec<[f64; 2]>,
+    line: usize,
+) -> Result<fj::Shape2d, Error> {
+    if points.len() < 3 {
+        return Err(Error::evaluation(
+            line,
+            "A polygon needs at least 3 points",
+        ));
+    }
+
+    // OpenSCAD accepts polygons in either orientation. The kernel expects them
+    // to be counter-clockwise.
+    if signed_area(&points) < 0. {
+        points.reverse();
+    }
+
+    Ok(fj::Sketch::from_points(points).into())
+}
+
+fn signed_area(points: &[[f64; 2]]) -> f64 {
+    let mut area = 0.;
+
+    for (i, [x0, y0]) in points.iter().enumerate() {
+        let [x1, y1] = points[(i + 1) % points.len()];
+        area += x0 * y1 - x1 * y0;
+    }
+
+    area / 2.
+}
+
+fn extrusion(footprint: fj::Shape2d, height: f64, center: bool) -> Solid {
+    let bottom = if center { -height / 2. } else { 0. };
+
+    Solid::Extrusion {
+        footprint,
+        bottom,
+        top: bottom + height,
+    }
+}
+
+fn translate(object: Object, [x, y, z]: [f64; 3], context: &Context) -> Object {
+    match object {
+        // Like OpenSCAD, ignore the z-component for 2D shapes.
+        Object::Shape2d(shape) => {
+            Object::Shape2d(translate_2d(shape, [x, y], context))
+        }
+        Object::Solid(Solid::Extrusion {
+            footprint,
+            bottom,
+            top,
+        }) => Object::Solid(Solid::Extrusion {
+            footprint: translate_2d(footprint, [x, y], context),
+            bottom: bottom + z,
+            top: top + z,
+        }),
+        Object::Solid(Solid::Sphere { center, radius }) => {
+            Object::Solid(Solid::Sphere {
+                center: [center[0] + x, center[1] + y, center[2] + z],
+                radius,
+            })
+        }
+        Object::Solid(Solid::Shape(shape)) => {
+            Object::Solid(Solid::Shape(translate_3d(shape, [x, y, z])))
+        }
+    }
+}
+
+fn translate_2d(
+    shape: fj::Shape2d,
+    [x, y]: [f64; 2],
+    context: &Context,
+) -> fj::Shape2d {
+    if x == 0. && y == 0. {
+        return shape;
+    }
+
+    match shape {
+        fj::Shape2d::Circle(circle) => {
+            // Circles in `fj` are always centered on the origin. OpenSCAD
+            // renders circles as polygons anyway, so that's what we do for the
+            // ones that are moved.
+            let radius = circle.radius();
+            let n = fragments(radius, context);
+
+            let points = (0..n)
+                .map(|i| {
+                    let angle = 2. * PI * i as f64 / n as f64;
+                    [x + radius * angle.cos(), y + radius * angle.sin()]
+                })
+                .collect();
+
+            fj::Sketch::from_points(points)
+                .with_color(circle.color())
+                .into()
+        }
+        fj::Shape2d::Sketch(sketch) => {
+            let points = sketch
+                .to_points()
+                .into_iter()
+                .map(|[px, py]| [px + x, py + y])
+                .collect();
+
+            fj::Sketch::from_points(points)
+                .with_color(sketch.color())
+                .into()
+        }
+        fj::Shape2d::Difference(difference) => {
+            let [a, b] = difference.shapes().clone();
+
+            fj::Difference2d::from_shapes([
+                translate_2d(a, [x, y], context),
+                translate_2d(b, [x, y], context),
+            ])
+            .into()
+        }
+        fj::Shape2d::Minkowski(minkowski) => {
+            // Moving either shape moves the whole sum.
+            let [a, b] = minkowski.shapes().clone();
+            let a = translate_2d(a, [x, y], context);
+
+            // Can't panic. Moving a shape doesn't change whether it is convex,
+            // and both shapes were convex before.
+            fj::Minkowski2d::from_shapes([a, b]).unwrap().into()
+        }
+    }
+}
+
+fn translate_3d(shape: fj::Shape3d, offset: [f64; 3]) -> fj::Shape3d {
+    fj::Transform {
+        shape,
+        axis: [0., 0., 1.],
+        angle: 0.,
+        offset,
+    }
+    .into()
+}
+
+/// The number of segments that OpenSCAD would use for a circle
+///
+/// Respects `$fn`, `$fa`, and `$fs`, if they are set.
+fn fragments(radius: f64, context: &Context) -> usize {
+    let special = |name: &str, default: f64| match context.variables.get(name) {
+        Some(Value::Number(value)) => *value,
+        _ => default,
+    };
+
+    let fn_ = special("$fn", 0.);
+    let fa = special("$fa", 12.);
+    let fs = special("$fs", 2.);
+
+    if fn_ > 0. {
+        return (fn_ as usize).max(3);
+    }
+
+    (360. / fa).min(radius * 2. * PI / fs).max(5.).ceil() as usize
+}
+
+fn difference(a: Object, b: Object, line: usize) -> Result<Object, Error> {
+    match (a, b) {
+        (Object::Shape2d(a), Object::Shape2d(b)) => Ok(Object::Shape2d(
+            fj::Difference2d::from_shapes([a, b]).into(),
+        )),
+        (
+            Object::Solid(Solid::Extrusion {
+                footprint: a,
+                bottom,
+                top,
+            }),
+            Object::Solid(Solid::Extrusion {
+                footprint: b,
+                bottom: b_bottom,
+                top: b_top,
+            }),
+        ) => {
+            // Extrusions that don't overlap don't affect each other.
+            if b_top <= bottom || b_bottom >= top {
+                return Ok(Object::Solid(Solid::Extrusion {
+                    footprint: a,
+                    bottom,
+                    top,
+                }));
+            }
+
+            if b_bottom > bottom || b_top < top {
+                return Err(Error::evaluation(
+                    line,
+                    "Difference is only supported, if the subtracted \
+                    extrusion is at least as high as the other one",
+                ));
+            }
+
+            Ok(Object::Solid(Solid::Extrusion {
+                footprint: fj::Difference2d::from_shapes([a, b]).into(),
+                bottom,
+                top,
+            }))
+        }
+        (Object::Solid(_), Object::Solid(_)) => Err(Error::evaluation(
+            line,
+            "Difference is only supported between extrusions",
+        )),
+        _ => Err(Error::evaluation(line, "Can't mix 2D and 3D objects")),
+    }
+}
+
+fn minkowski(a: Object, b: Object, line: usize) -> Result<Object, Error> {
+    match (a, b) {
+        (Object::Shape2d(a), Object::Shape2d(b)) => {
+            let sum = fj::Minkowski2d::from_shapes([a, b])
+                .map_err(|err| Error::evaluation(line, err.to_string()))?;
+            Ok(Object::Shape2d(sum.into()))
+        }
+        (
+            Object::Solid(Solid::Extrusion {
+                footprint: a,
+                bottom,
+                top,
+            }),
+            Object::Solid(Solid::Extrusion {
+                footprint: b,
+                bottom: b_bottom,
+                top: b_top,
+            }),
+        ) => {
+            // The sum of two extrusions is the extrusion of the sum of their
+            // footprints, as high as both of them together.
+            let footprint = fj::Minkowski2d::from_shapes([a, b])
+                .map_err(|err| Error::evaluation(line, err.to_string()))?;
+
+            Ok(Object::Solid(Solid::Extrusion {
+                footprint: footprint.into(),
+                bottom: bottom + b_bottom,
+                top: top + b_top,
+            }))
+        }
+        (
+            Object::Solid(Solid::Sphere { center: a, radius }),
+            Object::Solid(Solid::Sphere {
+                center: b,
+                radius: b_radius,
+            }),
+        ) => Ok(Object::Solid(Solid::Sphere {
+            center: [a[0] + b[0], a[1] + b[1], a[2] + b[2]],
+            radius: radius + b_radius,
+        })),
+        (
+            Object::Solid(solid),
+            Object::Solid(Solid::Sphere { center, radius }),
+        )
+        | (
+            Object::Solid(Solid::Sphere { center, radius }),
+            Object::Solid(solid),
+        ) => {
+            // Summing with a sphere that is not at the origin also moves the
+            // solid by the sphere's center.
+            let rounded =
+                fj::Rounded::from_shape(solid.into_shape(), radius).into();
+            let shape = if center == [0.; 3] {
+                rounded
+            } else {
+                translate_3d(rounded, center)
+            };
+
+            Ok(Object::Solid(Solid::Shape(shape)))
+        }
+        (Object::Solid(_), Object::Solid(_)) => Err(Error::evaluation(
+            line,
+            "Minkowski sum is only supported between extrusions, or with a \
+            sphere",
+        )),
+        _ => Err(Error::evaluation(line, "Can't mix 2D and 3D objects")),
+    }
+}
+
+pub fn evaluate(
+    expression: &Expression,
+    context: &Context,
+    line: usize,
+) -> Result<Value, Error> {
+    let value = match expression {
+        Expression::Number(number) => Value::Number(*number),
+        Expression::Bool(value) => Value::Bool(*value),
+        Expression::Undef => Value::Undef,
+        // Like OpenSCAD, treat unknown variables as undefined.
+        Expression::Variable(name) => {
+            context.variables.get(name).cloned().unwrap_or(Value::Undef)
+        }
+        Expression::Vector(components) => Value::Vector(
+            components
+                .iter()
+                .map(|component| evaluate(component, context, line))
+                .collect::<Result<_, _>>()?,
+        ),
+        Expression::Negate(expression) => {
+            let value = evaluate(expression, context, line)?;
+            multiply(value, Value::Number(-1.), line)?
+        }
+        Expression::Binary(operator, a, b) => {
+            let a = evaluate(a, context, line)?;
+            let b = evaluate(b, context, line)?;
+
+            match operator {
+                Operator::Add => add(a, b, 1., line)?,
+                Operator::Subtract => add(a, b, -1., line)?,
+                Operator::Multiply => multiply(a, b, line)?,
+                Operator::Divide => match b {
+                    Value::Number(b) => {
+                        multiply(a, Value::Number(1. / b), line)?
+                    }
+                    _ => return Err(invalid_operands(line)),
+                },
+            }
+        }
+    };
+
+    Ok(value)
+}
+
+/// Compute `a + sign * b`
+fn add(a: Value, b: Value, sign: f64, line: usize) -> Result<Value, Error> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + sign * b)),
+        (Value::Vector(a), Value::Vector(b)) if a.len() == b.len() => {
+            let sum = a
+                .into_iter()
+                .zip(b)
+                .map(|(a, b)| add(a, b, sign, line))
+                .collect::<Result<_, _>>()?;
+            Ok(Value::Vector(sum))
+        }
+        _ => Err(invalid_operands(line)),
+    }
+}
+
+fn multiply(a: Value, b: Value, line: usize) -> Result<Value, Error> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
+        (Value::Vector(v), Value::Number(s))
+        | (Value::Number(s), Value::Vector(v)) => {
+            let product = v
+                .into_iter()
+                .map(|c| multiply(c, Value::Number(s), line))
+                .collect::<Result<_, _>>()?;
+            Ok(Value::Vector(product))
+        }
+        _ => Err(invalid_operands(line)),
+    }
+}
+
+fn invalid_operands(line: usize) -> Error {
+    Error::evaluation(line, "Invalid operands")
+}
+
+/// The arguments of a module instantiation, evaluated
+struct Arguments<'r> {
+    positional: Vec<Value>,
+    named: HashMap<&'r str, Value>,
+    line: usize,
+}
+
+impl<'r> Arguments<'r> {
+    fn new(call: &'r Call, context: &Context) -> Result<Self, Error> {
+        let mut positional = Vec::new();
+        let mut named = HashMap::new();
+
+        for argument in &call.arguments {
+            let value = evaluate(&argument.value, context, call.line)?;
+
+            match &argument.name {
+                Some(name) => {
+                    named.insert(name.as_str(), value);
+                }
+                None => positional.push(value),
+            }
+        }
+
+        Ok(Self {
+            positional,
+            named,
+            line: call.line,
+        })
+    }
+
+    /// Access an argument by name, or by position if it's not named
+    ///
+    /// Undefined arguments are treated as missing.
+    fn get(&self, position: Option<usize>, name: &str) -> Option<&Value> {
+        let value = self.named.get(name).or_else(|| {
+            position.and_then(|position| self.positional.get(position))
+        })?;
+
+        if value == &Value::Undef {
+            return None;
+        }
+
+        Some(value)
+    }
+
+    fn number(
+        &self,
+        position: Option<usize>,
+        name: &str,
+    ) -> Result<Option<f64>, Error> {
+        match self.get(position, name) {
+            Some(Value::Number(number)) => Ok(Some(*number)),
+            Some(_) => Err(Error::evaluation(
+                self.line,
+                format!("`{}` must be a number", name),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn bool(&self, position: Option<usize>, name: &str) -> Result<bool, Error> {
+        match self.get(position, name) {
+            Some(Value::Bool(value)) => Ok(*value),
+            Some(_) => Err(Error::evaluation(
+                self.line,
+                format!("`{}` must be `true` or `false`", name),
+            )),
+            None => Ok(false),
+        }
+    }
+
+    /// Access a radius, that might be given as a diameter instead
+    fn radius(
+        &self,
+        position: Option<usize>,
+        radius: &str,
+        diameter: &str,
+    ) -> Result<Option<f64>, Error> {
+        if let Some(diameter) = self.number(None, diameter)? {
+            return Ok(Some(diameter / 2.));
+        }
+
+        self.number(position, radius)
+    }
+}
+
+/// Convert a size, which may be given as a single number for all dimensions
+fn size_of<const D: usize>(
+    value: &Value,
+    line: usize,
+) -> Result<[f64; D], Error> {
+    match value {
+        Value::Number(size) => Ok([*size; D]),
+        value => vector(value, line),
+    }
+}
+
+fn vector<const D: usize>(
+    value: &Value,
+    line: usize,
+) -> Result<[f64; D], Error> {
+    let error = || {
+        Error::evaluation(line, format!("Expected a vector of {} numbers", D))
+    };
+
+    let components = match value {
+        Value::Vector(components) if components.len() == D => components,
+        _ => return Err(error()),
+    };
+
+    let mut vector = [0.; D];
+    for (component, value) in vector.iter_mut().zip(components) {
+        *component = match value {
+            Value::Number(number) => *number,
+            _ => return Err(error()),
+        };
+    }
+
+    Ok(vector)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::parser::parse;
+
+    use super::{objects, union, Context, Object, Solid};
+
+    fn convert(source: &str) -> Object {
+        let statements = parse(source).unwrap();
+        let objects = objects(&statements, &mut Context::default()).unwrap();
+        union(objects, 0).unwrap().unwrap()
+    }
+
+    #[test]
+    fn difference_of_extrusions() {
+        let object = convert(
+            "difference() { \
+                cube([10, 10, 5]); \
+                translate([5, 5, -1]) cylinder(h = 7, r = 2); \
+            }",
+        );
+
+        match object {
+            Object::Solid(Solid::Extrusion {
+                footprint: fj::Shape2d::Difference(difference),
+                bottom,
+                top,
+            }) => {
+                assert_eq!([bottom, top], [0., 5.]);
+
+                // The circle has been moved, so it must have become a polygon.
+                let [_, hole] = difference.shapes();
+                assert!(matches!(hole, fj::Shape2d::Sketch(_)));
+            }
+            object => panic!("Unexpected object: {:?}", object),
+        }
+    }
+
+    #[test]
+    fn minkowski_of_extrusions() {
+        let object = convert(
+            "minkowski() { \
+                cube([10, 10, 1], center = true); \
+                cylinder(h = 2, r = 1); \
+            }",
+        );
+
+        match object {
+            Object::Solid(Solid::Extrusion {
+                footprint: fj::Shape2d::Minkowski(_),
+                bottom,
+                top,
+            }) => {
+                assert_eq!([bottom, top], [-0.5, 2.5]);
+            }
+            object => panic!("Unexpected object: {:?}", object),
+        }
+    }
+
+    #[test]
+    fn minkowski_with_sphere() {
+        let object = convert(
+            "minkowski() { \
+                cube(10); \
+                translate([0, 0, 1]) sphere(r = 2); \
+            }",
+        );
+
+        match object {
+            Object::Solid(Solid::Shape(fj::Shape3d::Transform(transform))) => {
+                assert_eq!(transform.offset, [0., 0., 1.]);
+
+                let rounded = match &transform.shape {
+                    fj::Shape3d::Rounded(rounded) => rounded,
+                    shape => panic!("Unexpected shape: {:?}", shape),
+                };
+                assert_eq!(rounded.radius(), 2.);
+            }
+            object => panic!("Unexpected object: {:?}", object),
+        }
+
+        let object = convert(
+            "minkowski() { \
+                sphere(1); \
+                translate([1, 2, 3]) sphere(d = 4); \
+            }",
+        );
+        assert!(matches!(
+            object,
+            Object::Solid(Solid::Sphere {
+                center: [1., 2., 3.],
+                radius,
+            }) if radius == 3.
+        ));
+    }
+
+    #[test]
+    fn union_of_disjoint_objects() {
+        let object = convert(
+            "union() { \
+                cube(10); \
+                translate([11, 0, 0]) cylinder(h = 10, r = 1); \
+            }",
+        );
+        assert!(matches!(
+            object,
+            Object::Solid(Solid::Shape(fj::Shape3d::Group(_)))
+        ));
+
+        for source in [
+            "cube(10); translate([5, 5, 5]) cube(10);",
+            "cube(10); translate([10, 0, 0]) cube(10);",
+            "cube(10); translate([5, 5, 9]) cylinder(h = 2, r = 1);",
+        ] {
+            let statements = parse(source).unwrap();
+            let objects =
+                objects(&statements, &mut Context::default()).unwrap();
+            assert!(union(objects, 0).is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn difference_with_partial_overlap() {
+        let statements =
+            parse("difference() { cube(10); translate([0, 0, 5]) cube(10); }")
+                .unwrap();
+
+        assert!(objects(&statements, &mut Context::default()).is_err());
+    }
+
+    #[test]
+    fn overrides_and_assignments() {
+        let statements = parse("h = 1; cylinder(h = h * 2, r = 1);").unwrap();
+
+        let overrides =
+            HashMap::from([("h".to_owned(), super::Value::Number(3.))]);
+        let mut context = Context::with_overrides(overrides);
+        let objects = objects(&statements, &mut context).unwrap();
+
+        match &objects[..] {
+            [Object::Solid(Solid::Extrusion { top, .. })] => {
+                assert_eq!(*top, 6.)
+            }
+            objects => panic!("Unexpected objects: {:?}", objects),
+        }
+    }
+
+    #[test]
+    fn polygon_orientation() {
+        let object = convert(
+            "linear_extrude(height = 1) \
+                polygon([[0, 0], [0, 1], [1, 1], [1, 0]]);",
+        );
+
+        match object {
+            Object::Solid(Solid::Extrusion {
+                footprint: fj::Shape2d::Sketch(sketch),
+                ..
+            }) => {
+                assert!(super::signed_area(&sketch.to_points()) > 0.);
+            }
+            object => panic!("Unexpected object: {:?}", object),
+        }
+    }
+
+    #[test]
+    fn variable_order() {
+        let statements = parse("h = 1; size = [h, 2]; cube(size);").unwrap();
+
+        let variables: Vec<_> = super::variables(&statements)
+            .unwrap()
+            .into_iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect();
+
+        assert_eq!(variables, ["h = 1", "size = [1, 2]"]);
+    }
+}
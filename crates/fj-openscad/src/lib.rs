@@ -0,0 +1,146 @@
+//! # Fornjot OpenSCAD Import
+//!
+//! This library is part of the [Fornjot] ecosystem. Fornjot is an open-source,
+//! code-first CAD application; and collection of libraries that make up the CAD
+//! application, but can be used independently.
+//!
+//! This library converts [OpenSCAD] code into the operations of the [`fj`]
+//! crate, to make it easier to migrate existing models. Only a practical subset
+//! of the language is supported:
+//!
+//! - The 2D primitives `square`, `circle`, and `polygon` (without `paths`).
+//! - The 3D primitives `cube`, `sphere`, and `cylinder` (but not cones).
+//! - `linear_extrude` (without `twist` or `scale`).
+//! - `translate`, `union`, `difference`, and `minkowski` (of convex shapes, or
+//!   with a sphere).
+//! - Variables, numbers, vectors, and arithmetic.
+//!
+//! Since the kernel has no 3D boolean operations yet, a 3D `difference` is
+//! computed from the footprints of extrusions. This only works, if each
+//! subtracted object is an extrusion that covers the full height of the first
+//! one, which is how holes are usually made. Likewise, a `union` of 3D objects
+//! is a group, which is only correct, if the objects don't touch. Unions of
+//! objects whose bounding boxes touch are rejected, to be safe. A 3D
+//! `minkowski` is only supported between extrusions, or with a `sphere`, which
+//! rounds all edges and corners. Spheres, and the objects rounded by them, are
+//! reconstructed from samples of their distance function, like
+//! [`fj::Implicit`] shapes.
+//!
+//! Modules, functions, loops, conditionals, and strings are not supported.
+//!
+//! [Fornjot]: https://www.fornjot.app/
+//! [OpenSCAD]: https://openscad.org/
+//! [`fj`]: https://crates.io/crates/fj
+
+#![deny(missing_docs)]
+
+mod convert;
+mod parser;
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use self::convert::{Context, Object};
+
+/// Convert OpenSCAD code into a shape
+///
+/// `parameters` override the top-level variables of the same name, like the
+/// `-D` option of OpenSCAD. Their values are OpenSCAD expressions.
+pub fn convert(
+    source: &str,
+    parameters: &HashMap<String, String>,
+) -> Result<fj::Shape, Error> {
+    let mut overrides = HashMap::new();
+    for (name, value) in parameters {
+        let value = parser::parse_expression(value)
+            .and_then(|value| convert::evaluate(&value, &Context::default(), 1))
+            .map_err(|err| Error::Parameter {
+                name: name.clone(),
+                message: err.to_string(),
+            })?;
+
+        overrides.insert(name.clone(), value);
+    }
+
+    let statements = parser::parse(source)?;
+    let objects =
+        convert::objects(&statements, &mut Context::with_overrides(overrides))?;
+
+    // Like OpenSCAD, combine all top-level objects. The line doesn't matter
+    // much, as an error here relates to the whole file.
+    match convert::union(objects, 1)? {
+        Some(Object::Shape2d(shape)) => Ok(fj::Shape::Shape2d(shape)),
+        Some(Object::Solid(solid)) => Ok(solid.into_shape().into()),
+        None => Err(Error::Empty),
+    }
+}
+
+/// List the top-level variables of OpenSCAD code, with their values
+///
+/// These are the variables that can be overridden by the `parameters` of
+/// [`convert`]. Values are formatted like OpenSCAD would, for example `[1, 2]`
+/// for a vector.
+pub fn parameters(source: &str) -> Result<Vec<(String, String)>, Error> {
+    let statements = parser::parse(source)?;
+    let variables = convert::variables(&statements)?;
+
+    Ok(variables
+        .into_iter()
+        .map(|(name, value)| (name, value.to_string()))
+        .collect())
+}
+
+/// An error that can occur while converting OpenSCAD code
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The code could not be parsed
+    #[error("Syntax error on line {line}: {message}")]
+    Syntax {
+        /// The line on which the error occurred
+        line: usize,
+
+        /// A description of the error
+        message: String,
+    },
+
+    /// The code could be parsed, but not converted
+    #[error("Error on line {line}: {message}")]
+    Evaluation {
+        /// The line on which the error occurred
+        line: usize,
+
+        /// A description of the error
+        message: String,
+    },
+
+    /// The value of a parameter is invalid
+    #[error("Invalid value for parameter `{name}`: {message}")]
+    Parameter {
+        /// The name of the parameter
+        name: String,
+
+        /// A description of the error
+        message: String,
+    },
+
+    /// The code doesn't create any objects
+    #[error("No objects to convert")]
+    Empty,
+}
+
+impl Error {
+    fn syntax(line: usize, message: impl Into<String>) -> Self {
+        Self::Syntax {
+            line,
+            message: message.into(),
+        }
+    }
+
+    fn evaluation(line: usize, message: impl Into<String>) -> Self {
+        Self::Evaluation {
+            line,
+            message: message.into(),
+        }
+    }
+}
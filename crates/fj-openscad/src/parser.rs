@@ -0,0 +1,552 @@
+//! Parser for the supported subset of the OpenSCAD language
+
+use std::{iter::Peekable, str::Chars};
+
+use crate::Error;
+
+/// A statement, as it appears in the source file
+#[derive(Debug, PartialEq)]
+pub enum Statement {
+    /// Assignment of a value to a variable
+    Assignment {
+        name: String,
+        value: Expression,
+        line: usize,
+    },
+
+    /// Instantiation of a module, like `cube` or `translate`
+    Call(Call),
+}
+
+/// Instantiation of a module
+#[derive(Debug, PartialEq)]
+pub struct Call {
+    pub name: String,
+    pub arguments: Vec<Argument>,
+    pub children: Vec<Statement>,
+    pub line: usize,
+}
+
+/// An argument of a module instantiation
+#[derive(Debug, PartialEq)]
+pub struct Argument {
+    /// The name of the argument, if it is passed by name
+    pub name: Option<String>,
+    pub value: Expression,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    Bool(bool),
+    Undef,
+    Variable(String),
+    Vector(Vec<Expression>),
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// Parse a source file
+pub fn parse(source: &str) -> Result<Vec<Statement>, Error> {
+    let mut parser = Parser::new(source)?;
+
+    let mut statements = Vec::new();
+    while parser.peek() != &Token::End {
+        parser.statement(&mut statements)?;
+    }
+
+    Ok(statements)
+}
+
+/// Parse a single expression, like the value of an overridden variable
+pub fn parse_expression(source: &str) -> Result<Expression, Error> {
+    let mut parser = Parser::new(source)?;
+
+    let expression = parser.expression()?;
+    parser.expect(&Token::End)?;
+
+    Ok(expression)
+}
+
+/// Keywords of language features that are not supported
+const UNSUPPORTED: &[&str] =
+    &["module", "function", "include", "use", "for", "if", "let"];
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, Error> {
+        Ok(Self {
+            tokens: tokenize(source)?,
+            position: 0,
+        })
+    }
+
+    /// Parse a statement, adding it to `statements`
+    ///
+    /// Blocks are flattened into their contents, and statements that are
+    /// disabled with a modifier are dropped. Both have no effect on the
+    /// resulting geometry.
+    fn statement(
+        &mut self,
+        statements: &mut Vec<Statement>,
+    ) -> Result<(), Error> {
+        let line = self.line();
+
+        match self.next() {
+            Token::Symbol(';') => Ok(()),
+            Token::Symbol('{') => {
+                while self.peek() != &Token::Symbol('}') {
+                    if self.peek() == &Token::End {
+                        return Err(Error::syntax(line, "Unterminated block"));
+                    }
+                    self.statement(statements)?;
+                }
+                self.next();
+
+                Ok(())
+            }
+            // Debug and root modifiers only affect the preview in OpenSCAD.
+            Token::Symbol('#' | '!') => self.statement(statements),
+            // Disable and background modifiers exclude objects from the
+            // rendered result.
+            Token::Symbol('*' | '%') => self.statement(&mut Vec::new()),
+            Token::Identifier(name) => {
+                if UNSUPPORTED.contains(&name.as_str()) {
+                    return Err(Error::syntax(
+                        line,
+                        format!("`{}` is not supported", name),
+                    ));
+                }
+
+                if self.peek() == &Token::Symbol('=') {
+                    self.next();
+                    let value = self.expression()?;
+                    self.expect(&Token::Symbol(';'))?;
+
+                    statements.push(Statement::Assignment {
+                        name,
+                        value,
+                        line,
+                    });
+                    return Ok(());
+                }
+
+                self.expect(&Token::Symbol('('))?;
+                let arguments = self.arguments()?;
+
+                let mut children = Vec::new();
+                self.statement(&mut children)?;
+
+                statements.push(Statement::Call(Call {
+                    name,
+                    arguments,
+                    children,
+                    line,
+                }));
+                Ok(())
+            }
+            token => Err(Error::syntax(
+                line,
+                format!("Expected statement, found {}", token),
+            )),
+        }
+    }
+
+    /// Parse arguments, after the opening parenthesis
+    fn arguments(&mut self) -> Result<Vec<Argument>, Error> {
+        let mut arguments = Vec::new();
+
+        if self.peek() == &Token::Symbol(')') {
+            self.next();
+            return Ok(arguments);
+        }
+
+        loop {
+            let name = match (self.peek(), self.peek_second()) {
+                (Token::Identifier(name), Token::Symbol('=')) => {
+                    let name = name.clone();
+                    self.next();
+                    self.next();
+                    Some(name)
+                }
+                _ => None,
+            };
+            let value = self.expression()?;
+
+            arguments.push(Argument { name, value });
+
+            let line = self.line();
+            match self.next() {
+                Token::Symbol(',') => continue,
+                Token::Symbol(')') => return Ok(arguments),
+                token => {
+                    return Err(Error::syntax(
+                        line,
+                        format!("Expected `,` or `)`, found {}", token),
+                    ))
+                }
+            }
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.term()?;
+
+        loop {
+            let operator = match self.peek() {
+                Token::Symbol('+') => Operator::Add,
+                Token::Symbol('-') => Operator::Subtract,
+                _ => return Ok(expression),
+            };
+            self.next();
+
+            let rhs = self.term()?;
+            expression = Expression::Binary(
+                operator,
+                Box::new(expression),
+                Box::new(rhs),
+            );
+        }
+    }
+
+    fn term(&mut self) -> Result<Expression, Error> {
+        let mut expression = self.factor()?;
+
+        loop {
+            let operator = match self.peek() {
+                Token::Symbol('*') => Operator::Multiply,
+                Token::Symbol('/') => Operator::Divide,
+                _ => return Ok(expression),
+            };
+            self.next();
+
+            let rhs = self.factor()?;
+            expression = Expression::Binary(
+                operator,
+                Box::new(expression),
+                Box::new(rhs),
+            );
+        }
+    }
+
+    fn factor(&mut self) -> Result<Expression, Error> {
+        let line = self.line();
+
+        match self.next() {
+            Token::Number(number) => Ok(Expression::Number(number)),
+            Token::Symbol('-') => {
+                Ok(Expression::Negate(Box::new(self.factor()?)))
+            }
+            Token::Symbol('+') => self.factor(),
+            Token::Symbol('(') => {
+                let expression = self.expression()?;
+                self.expect(&Token::Symbol(')'))?;
+                Ok(expression)
+            }
+            Token::Symbol('[') => {
+                let mut components = Vec::new();
+
+                if self.peek() != &Token::Symbol(']') {
+                    loop {
+                        components.push(self.expression()?);
+
+                        let line = self.line();
+                        match self.next() {
+                            Token::Symbol(',') => continue,
+                            Token::Symbol(']') => break,
+                            Token::Symbol(':') => {
+                                return Err(Error::syntax(
+                                    line,
+                                    "Ranges are not supported",
+                                ))
+                            }
+                            token => {
+                                return Err(Error::syntax(
+                                    line,
+                                    format!(
+                                        "Expected `,` or `]`, found {}",
+                                        token
+                                    ),
+                                ))
+                            }
+                        }
+                    }
+                } else {
+                    self.next();
+                }
+
+                Ok(Expression::Vector(components))
+            }
+            Token::Identifier(name) => match name.as_str() {
+                "true" => Ok(Expression::Bool(true)),
+                "false" => Ok(Expression::Bool(false)),
+                "undef" => Ok(Expression::Undef),
+                _ if self.peek() == &Token::Symbol('(') => Err(Error::syntax(
+                    line,
+                    format!("Function calls are not supported (`{}`)", name),
+                )),
+                _ => Ok(Expression::Variable(name)),
+            },
+            token => Err(Error::syntax(
+                line,
+                format!("Expected expression, found {}", token),
+            )),
+        }
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), Error> {
+        let line = self.line();
+
+        let token = self.next();
+        if &token != expected {
+            return Err(Error::syntax(
+                line,
+                format!("Expected {}, found {}", expected, token),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn next(&mut self) -> Token {
+        let (token, _) = &self.tokens[self.position];
+
+        // The last token is always `End`. Don't move past it.
+        if self.position < self.tokens.len() - 1 {
+            self.position += 1;
+        }
+
+        token.clone()
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn peek_second(&self) -> &Token {
+        let position = (self.position + 1).min(self.tokens.len() - 1);
+        &self.tokens[position].0
+    }
+
+    /// The line of the next token
+    fn line(&self) -> usize {
+        self.tokens[self.position].1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Symbol(char),
+    End,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "`{}`", number),
+            Self::Identifier(name) => write!(f, "`{}`", name),
+            Self::Symbol(symbol) => write!(f, "`{}`", symbol),
+            Self::End => write!(f, "end of file"),
+        }
+    }
+}
+
+/// Split the source into tokens, each with the line it appears on
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            '\n' => {
+                line += 1;
+                chars.next();
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '/' => {
+                chars.next();
+                match chars.peek() {
+                    Some('/') => {
+                        while chars.peek().map_or(false, |&c| c != '\n') {
+                            chars.next();
+                        }
+                    }
+                    Some('*') => {
+                        let start = line;
+                        chars.next();
+
+                        loop {
+                            match chars.next() {
+                                Some('*') if chars.peek() == Some(&'/') => {
+                                    chars.next();
+                                    break;
+                                }
+                                Some('\n') => line += 1,
+                                Some(_) => {}
+                                None => {
+                                    return Err(Error::syntax(
+                                        start,
+                                        "Unterminated comment",
+                                    ))
+                                }
+                            }
+                        }
+                    }
+                    _ => tokens.push((Token::Symbol('/'), line)),
+                }
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let number = number(&mut chars);
+                let number = number.parse().map_err(|_| {
+                    Error::syntax(line, format!("Invalid number `{}`", number))
+                })?;
+
+                tokens.push((Token::Number(number), line));
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut identifier = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '$') {
+                        break;
+                    }
+                    identifier.push(c);
+                    chars.next();
+                }
+
+                tokens.push((Token::Identifier(identifier), line));
+            }
+            '"' => {
+                return Err(Error::syntax(line, "Strings are not supported"));
+            }
+            c if "()[]{},;:=+-*%!#".contains(c) => {
+                tokens.push((Token::Symbol(c), line));
+                chars.next();
+            }
+            c => {
+                return Err(Error::syntax(
+                    line,
+                    format!("Unexpected character `{}`", c),
+                ));
+            }
+        }
+    }
+
+    tokens.push((Token::End, line));
+    Ok(tokens)
+}
+
+fn number(chars: &mut Peekable<Chars>) -> String {
+    let mut number = String::new();
+
+    while let Some(&c) = chars.peek() {
+        if !(c.is_ascii_digit() || c == '.') {
+            break;
+        }
+        number.push(c);
+        chars.next();
+    }
+
+    if let Some(&e @ ('e' | 'E')) = chars.peek() {
+        number.push(e);
+        chars.next();
+
+        if let Some(&sign @ ('+' | '-')) = chars.peek() {
+            number.push(sign);
+            chars.next();
+        }
+        while let Some(&c) = chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            number.push(c);
+            chars.next();
+        }
+    }
+
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Argument, Call, Expression, Operator, Statement};
+
+    #[test]
+    fn calls_with_children() {
+        let statements =
+            parse("translate([1, 0, 0]) cube(size = 2 * 3);").unwrap();
+
+        assert_eq!(
+            statements,
+            vec![Statement::Call(Call {
+                name: "translate".into(),
+                arguments: vec![Argument {
+                    name: None,
+                    value: Expression::Vector(vec![
+                        Expression::Number(1.),
+                        Expression::Number(0.),
+                        Expression::Number(0.),
+                    ]),
+                }],
+                children: vec![Statement::Call(Call {
+                    name: "cube".into(),
+                    arguments: vec![Argument {
+                        name: Some("size".into()),
+                        value: Expression::Binary(
+                            Operator::Multiply,
+                            Box::new(Expression::Number(2.)),
+                            Box::new(Expression::Number(3.)),
+                        ),
+                    }],
+                    children: vec![],
+                    line: 1,
+                })],
+                line: 1,
+            })]
+        );
+    }
+
+    #[test]
+    fn comments_and_disabled_statements() {
+        let statements = parse(
+            "// comment\n\
+            /* multi-line\n\
+            comment */\n\
+            *cube(1);\n\
+            h = 2;",
+        )
+        .unwrap();
+
+        assert_eq!(
+            statements,
+            vec![Statement::Assignment {
+                name: "h".into(),
+                value: Expression::Number(2.),
+                line: 5,
+            }]
+        );
+    }
+
+    #[test]
+    fn unsupported_features() {
+        assert!(parse("module foo() { cube(1); }").is_err());
+        assert!(parse("x = sqrt(2);").is_err());
+        assert!(parse("cube(1)").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Checking of the assertions that models attach to their shapes
+//!
+//! Please refer to [`fj::Assertion`] for how models declare assertions.
+
+use std::fmt;
+
+use fj::Assertion;
+use fj_kernel::algorithms::{mesh_volume, mesh_wall_thickness};
+use fj_math::{Aabb, Scalar};
+
+use crate::shape_processor::ProcessedShape;
+
+/// Check the assertions of a processed shape
+///
+/// Assertions are checked against the triangle mesh, so the results are only
+/// as precise as the tolerance that the mesh was created with. Returns the
+/// assertions that failed, in the order they were declared in.
+pub fn check(shape: &ProcessedShape) -> Vec<FailedAssertion> {
+    let mut failed = Vec::new();
+
+    for &assertion in &shape.assertions {
+        match assertion {
+            Assertion::MinWallThickness(min) => {
+                let thickness =
+                    mesh_wall_thickness(&shape.mesh, shape.tolerance);
+
+                if let Some(thickness) = thickness {
+                    let thickness = thickness.into_f64();
+                    if thickness < min {
+                        failed.push(FailedAssertion::WallTooThin {
+                            min,
+                            thickness,
+                        });
+                    }
+                }
+            }
+            Assertion::MaxSize(max) => {
+                let size = Aabb::<3>::from_points(shape.mesh.vertices()).size();
+                let size = size.components.map(Scalar::into_f64);
+
+                if size.iter().zip(max).any(|(&size, max)| size > max) {
+                    failed.push(FailedAssertion::TooLarge { max, size });
+                }
+            }
+            Assertion::VolumeRange { min, max } => {
+                let volume = mesh_volume(&shape.mesh).into_f64();
+
+                if volume < min || volume > max {
+                    failed.push(FailedAssertion::VolumeOutOfRange {
+                        min,
+                        max,
+                        volume,
+                    });
+                }
+            }
+        }
+    }
+
+    failed
+}
+
+/// An assertion that the shape doesn't satisfy
+#[derive(Clone, Copy, Debug)]
+pub enum FailedAssertion {
+    /// A wall is thinner than [`Assertion::MinWallThickness`] allows
+    WallTooThin {
+        /// The minimum thickness
+        min: f64,
+
+        /// The thickness of the thinnest wall
+        thickness: f64,
+    },
+
+    /// The shape is larger than [`Assertion::MaxSize`] allows
+    TooLarge {
+        /// The maximum size
+        max: [f64; 3],
+
+        /// The size of the shape's bounding box
+        size: [f64; 3],
+    },
+
+    /// The volume is outside of the [`Assertion::VolumeRange`]
+    VolumeOutOfRange {
+        /// The minimum volume
+        min: f64,
+
+        /// The maximum volume
+        max: f64,
+
+        /// The volume of the shape
+        volume: f64,
+    },
+}
+
+impl fmt::Display for FailedAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::WallTooThin { min, thickness } => write!(
+                f,
+                "Assertion failed: Wall is {} thick, but must be at least {}",
+                thickness, min
+            ),
+            Self::TooLarge { max, size } => write!(
+                f,
+                "Assertion failed: Size is {:?}, but must not exceed {:?}",
+                size, max
+            ),
+            Self::VolumeOutOfRange { min, max, volume } => write!(
+                f,
+                "Assertion failed: Volume is {}, but must be between {} and {}",
+                volume, min, max
+            ),
+        }
+    }
+}
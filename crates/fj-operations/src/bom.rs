@@ -0,0 +1,163 @@
+//! Bills of materials of assemblies
+//!
+//! The parts of an assembly are found by [`ProcessedShape::parts`]. Parts can
+//! be given names using [`fj::Named`], and materials using
+//! [`fj::WithMaterial`].
+
+use fj_interop::mesh::Mesh;
+use fj_kernel::algorithms::mesh_volume;
+use fj_math::{Point, Scalar, Triangle};
+
+use crate::shape_processor::ProcessedShape;
+
+/// A bill of materials, listing the parts of an assembly
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BillOfMaterials {
+    /// One entry for each kind of part, in the order they first appear
+    pub entries: Vec<Entry>,
+}
+
+impl BillOfMaterials {
+    /// Create the bill of materials of a processed shape
+    ///
+    /// Parts with the same name, material, volume, and surface area are
+    /// assumed to be copies of each other, and are listed as a single entry.
+    ///
+    /// Masses are computed from the density of each part's material. `density`
+    /// is used for parts without a material, in g/cm³. Masses assume that the
+    /// coordinates of the shape are in millimeters.
+    pub fn new(shape: &ProcessedShape, density: Option<f64>) -> Self {
+        let mut entries: Vec<Entry> = Vec::new();
+
+        for part in shape.parts() {
+            let attributes: Vec<_> = part
+                .faces
+                .iter()
+                .filter_map(|&face| shape.attributes_of(face))
+                .collect();
+            let name = attributes
+                .iter()
+                .find_map(|attributes| attributes.name())
+                .map(ToOwned::to_owned);
+            let material = attributes
+                .iter()
+                .find_map(|attributes| attributes.material());
+
+            // The faces of a part are sorted, as they were added in order.
+            let mut mesh = Mesh::new();
+            for triangle in shape.mesh.triangles() {
+                let in_part = triangle.face.map_or(false, |face| {
+                    part.faces.binary_search(&face).is_ok()
+                });
+                if in_part {
+                    mesh.push_triangle_with_face(
+                        triangle.points,
+                        triangle.color,
+                        triangle.face,
+                    );
+                }
+            }
+
+            // Faces that didn't result in any triangles aren't a part anyone
+            // could build.
+            if mesh.triangles().next().is_none() {
+                continue;
+            }
+
+            let volume = mesh_volume(&mesh).into_f64();
+            let area = mesh
+                .triangles()
+                .map(|triangle| Triangle::from_points(triangle.points).area())
+                .fold(Scalar::ZERO, |sum, area| sum + area)
+                .into_f64();
+
+            let existing = entries.iter_mut().find(|entry| {
+                entry.name == name
+                    && entry.material.as_ref() == material.map(|m| &m.name)
+                    && same(entry.volume, volume)
+                    && same(entry.area, area)
+            });
+            match existing {
+                Some(entry) => entry.count += 1,
+                None => {
+                    let density = material
+                        .map(|material| material.density.into_f64())
+                        .or(density);
+
+                    entries.push(Entry {
+                        name,
+                        material: material.map(|m| m.name.clone()),
+                        count: 1,
+                        volume,
+                        area,
+                        // cm³ are a thousand mm³.
+                        mass: density.map(|density| volume / 1000. * density),
+                    });
+                }
+            }
+        }
+
+        Self { entries }
+    }
+
+    /// Compute the mass of the whole assembly, in grams
+    ///
+    /// Returns `None`, if the mass of any part is unknown, or if there are no
+    /// parts.
+    pub fn total_mass(&self) -> Option<f64> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        self.entries
+            .iter()
+            .map(|entry| Some(entry.mass? * entry.count as f64))
+            .sum()
+    }
+
+    /// Compute the volume of the whole assembly
+    pub fn total_volume(&self) -> f64 {
+        self.entries
+            .iter()
+            .map(|entry| entry.volume * entry.count as f64)
+            .sum()
+    }
+
+    /// Compute the surface area of the whole assembly
+    pub fn total_area(&self) -> f64 {
+        self.entries
+            .iter()
+            .map(|entry| entry.area * entry.count as f64)
+            .sum()
+    }
+}
+
+/// An entry of a [`BillOfMaterials`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// The name of the part, if it has one
+    pub name: Option<String>,
+
+    /// The name of the material the part is made of, if it has one
+    pub material: Option<String>,
+
+    /// The number of copies of the part in the assembly
+    pub count: usize,
+
+    /// The volume of a single copy of the part
+    pub volume: f64,
+
+    /// The surface area of a single copy of the part
+    pub area: f64,
+
+    /// The mass of a single copy of the part, in grams, if it is known
+    pub mass: Option<f64>,
+}
+
+/// Indicate whether two measurements of parts are the same
+///
+/// Copies of a part are triangulated at different positions, so their
+/// measurements are not exactly the same.
+fn same(a: f64, b: f64) -> bool {
+    (a - b).abs() <= a.abs().max(b.abs()) * 1e-3
+}
@@ -7,7 +7,7 @@ use fj_kernel::{
 };
 use fj_math::{Aabb, Point, Scalar};
 
-use super::ToShape;
+use super::{record_step, ToShape};
 
 impl ToShape for fj::Circle {
     fn to_shape(&self, _: Tolerance, _: &mut DebugInfo) -> Shape {
@@ -32,6 +32,7 @@ impl ToShape for fj::Circle {
             })
             .unwrap();
 
+        record_step(&mut shape, "Circle", None);
         shape
     }
 
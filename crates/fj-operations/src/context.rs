@@ -0,0 +1,48 @@
+//! Tracking of the operation that is currently being processed
+//!
+//! Panics while processing a shape usually originate deep within the kernel,
+//! and say nothing about which part of the model caused them. This module
+//! keeps track of the operations that are being processed, so the model's
+//! author can be told where a problem is located.
+
+use std::{cell::RefCell, thread};
+
+thread_local! {
+    static OPERATIONS: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+}
+
+/// Take the operations that were being processed, when a panic occurred
+///
+/// Returns the names of the operations, outermost first, as they appear in the
+/// [`fj`] crate (for example, `["Group", "Sweep", "Sketch"]`). Call this on
+/// the thread that was processing the shape, after catching the panic.
+///
+/// Returns an empty list, if processing didn't panic.
+pub fn take_failed_operations() -> Vec<&'static str> {
+    OPERATIONS.with(|operations| operations.take())
+}
+
+/// Forget about operations that a previous panic interrupted
+pub(crate) fn reset() {
+    OPERATIONS.with(|operations| operations.borrow_mut().clear());
+}
+
+/// Mark an operation as being processed, until the returned guard is dropped
+pub(crate) fn enter(operation: &'static str) -> OperationGuard {
+    OPERATIONS.with(|operations| operations.borrow_mut().push(operation));
+    OperationGuard
+}
+
+pub(crate) struct OperationGuard;
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        // If we're unwinding, the operation is left in place, so whoever
+        // catches the panic can find out where it came from.
+        if thread::panicking() {
+            return;
+        }
+
+        OPERATIONS.with(|operations| operations.borrow_mut().pop());
+    }
+}
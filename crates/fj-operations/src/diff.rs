@@ -0,0 +1,50 @@
+//! Comparing two versions of a shape
+//!
+//! Like a diff of source code, the comparison shows what has been added and
+//! removed. Please refer to [`diff_meshes`] for how the meshes are compared.
+
+use fj_kernel::algorithms::diff_meshes;
+use fj_math::Scalar;
+
+use crate::shape_processor::ProcessedShape;
+
+/// The comparison of two versions of a shape
+pub struct ShapeDiff {
+    /// Both versions of the shape, overlaid
+    ///
+    /// Its mesh is colored by [`diff_meshes`]. Apart from the tolerance that the
+    /// versions were compared within, and the topology of the new version, it
+    /// is empty like the result of [`ProcessedShape::from_mesh`]. The faces of
+    /// either version don't mean anything for the overlaid mesh.
+    pub shape: ProcessedShape,
+
+    /// The volume that only the new version encloses
+    pub added: Scalar,
+
+    /// The volume that only the old version encloses
+    pub removed: Scalar,
+}
+
+/// Compare two versions of a shape
+///
+/// The meshes are compared within the larger of their tolerances, so
+/// differences that are just due to approximation aren't shown.
+pub fn diff_shapes(old: &ProcessedShape, new: &ProcessedShape) -> ShapeDiff {
+    let tolerance = if old.tolerance.inner() > new.tolerance.inner() {
+        old.tolerance
+    } else {
+        new.tolerance
+    };
+
+    let diff = diff_meshes(&old.mesh, &new.mesh, tolerance);
+
+    let mut shape = ProcessedShape::from_mesh(diff.mesh);
+    shape.tolerance = tolerance;
+    shape.topology = new.topology;
+
+    ShapeDiff {
+        shape,
+        added: diff.added,
+        removed: diff.removed,
+    }
+}
@@ -8,7 +8,7 @@ use fj_kernel::{
 };
 use fj_math::Aabb;
 
-use super::ToShape;
+use super::{record_step, ToShape};
 
 impl ToShape for fj::Difference2d {
     fn to_shape(
@@ -46,6 +46,9 @@ impl ToShape for fj::Difference2d {
             }
         }
 
+        record_step(&mut a, "Difference2d", Some(0));
+        record_step(&mut b, "Difference2d", Some(1));
+
         // Can't panic, as we just verified that both shapes have one cycle.
         let [cycle_a, cycle_b] =
             [&mut a, &mut b].map(|shape| shape.cycles().next().unwrap());
@@ -62,16 +65,16 @@ impl ToShape for fj::Difference2d {
         interiors.push(cycle_b);
 
         // Can't panic, as we just verified that both shapes have one face.
-        let [face_a, face_b] = [&mut a, &mut b]
-            .map(|shape| shape.faces().values().next().unwrap());
+        let [face_a, face_b] =
+            [&mut a, &mut b].map(|shape| shape.faces().next().unwrap());
 
         assert!(
-            face_a.surface() == face_b.surface(),
+            face_a.get().surface() == face_b.get().surface(),
             "Trying to subtract sketches with different surfaces."
         );
-        let surface = shape.insert(face_a.surface()).unwrap();
+        let surface = shape.insert(face_a.get().surface()).unwrap();
 
-        shape
+        let face = shape
             .insert(Face::Face {
                 surface,
                 exteriors,
@@ -80,6 +83,9 @@ impl ToShape for fj::Difference2d {
             })
             .unwrap();
 
+        // The result takes the place of the face that is subtracted from.
+        shape.set_attributes(&face, face_a.attributes());
+
         shape
     }
 
@@ -98,7 +104,9 @@ fn add_cycle(
     reverse: bool,
 ) -> Handle<Cycle> {
     let mut edges = Vec::new();
-    for edge in cycle.get().edges() {
+    for edge_orig in &cycle.get().edges {
+        let edge = edge_orig.get();
+
         let curve = edge.curve();
         let curve = if reverse { curve.reverse() } else { curve };
         let curve = shape.insert(curve).unwrap();
@@ -122,6 +130,7 @@ fn add_cycle(
         });
 
         let edge = shape.insert(Edge { curve, vertices }).unwrap();
+        shape.set_attributes(&edge, edge_orig.attributes());
         edges.push(edge);
     }
 
@@ -0,0 +1,35 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Attribute, Shape},
+};
+use fj_math::{Aabb, Vector};
+
+use super::{record_step, ToShape};
+
+impl ToShape for fj::Exploded {
+    fn to_shape(
+        &self,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Shape {
+        let mut shape = self.shape.to_shape(tolerance, debug_info);
+
+        // Faces that are exploded within this shape move along with it.
+        let direction = Vector::from(self.direction);
+        for face in shape.faces() {
+            let explosion = face.attributes().explosion();
+            let direction =
+                explosion.map_or(direction, |inner| inner + direction);
+            shape.insert_attribute(&face, Attribute::Explosion(direction));
+        }
+
+        record_step(&mut shape, "Exploded", None);
+        shape
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // The explosion only affects the exploded view, not the shape itself.
+        self.shape.bounding_volume()
+    }
+}
@@ -0,0 +1,124 @@
+//! Exploded views of assemblies
+//!
+//! Groups are treated as assemblies. Each of their members is a part, which
+//! moves away from the center of the assembly, when it is exploded. Please
+//! refer to [`fj::Exploded`] for how models specify the direction themselves.
+
+use fj_interop::mesh::{FaceId, Mesh};
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use crate::shape_processor::ProcessedShape;
+
+/// The directions that the faces of a shape move in, when it is exploded
+///
+/// Faces that have been exploded by the model move in the direction that it
+/// specified. All other faces move from the center of the shape to the center
+/// of their part, as found by [`ProcessedShape::parts`]. Faces that are not
+/// part of any group don't move, unless they're exploded by the model.
+#[derive(Clone, Debug, Default)]
+pub struct Explosion {
+    directions: Vec<Vector<3>>,
+}
+
+impl Explosion {
+    /// Compute the directions of the faces of a processed shape
+    pub fn new(shape: &ProcessedShape) -> Self {
+        let parts = shape.parts();
+
+        let mut part_of_face = vec![0; shape.face_attributes.len()];
+        for (i, part) in parts.iter().enumerate() {
+            for face in &part.faces {
+                part_of_face[face.0 as usize] = i;
+            }
+        }
+
+        // The center of each part is weighted by the area of its triangles, so
+        // it doesn't depend on how finely the part was triangulated.
+        let mut centers =
+            vec![(Vector::from([0., 0., 0.]), Scalar::ZERO); parts.len()];
+        for triangle in shape.mesh.triangles() {
+            let part = triangle
+                .face
+                .and_then(|face| part_of_face.get(face.0 as usize));
+            let part = match part {
+                Some(&part) => part,
+                None => continue,
+            };
+
+            let [a, b, c] = triangle.points;
+            let area = Triangle::from_points([a, b, c]).area();
+            let center = (a.coords + b.coords + c.coords) / 3.;
+
+            let (sum, total) = &mut centers[part];
+            *sum = *sum + center * area;
+            *total = *total + area;
+        }
+
+        // A shape that isn't made up of several parts has nothing to explode.
+        let origin = shape.aabb.center();
+        let auto = |part: usize| {
+            let (sum, total) = centers[part];
+            if parts.len() > 1
+                && !parts[part].path.is_empty()
+                && total > Scalar::ZERO
+            {
+                Point {
+                    coords: sum / total,
+                } - origin
+            } else {
+                Vector::from([0., 0., 0.])
+            }
+        };
+
+        let directions = shape
+            .face_attributes
+            .iter()
+            .zip(part_of_face)
+            .map(|(attributes, part)| {
+                attributes.explosion().unwrap_or_else(|| auto(part))
+            })
+            .collect();
+
+        Self { directions }
+    }
+
+    /// Access the direction that a face moves in, when fully exploded
+    ///
+    /// Unknown faces don't move.
+    pub fn direction_of(&self, face: FaceId) -> Vector<3> {
+        self.directions
+            .get(face.0 as usize)
+            .copied()
+            .unwrap_or_else(|| Vector::from([0., 0., 0.]))
+    }
+
+    /// Indicate whether exploding the shape would move any of its faces
+    pub fn is_empty(&self) -> bool {
+        self.directions
+            .iter()
+            .all(|direction| direction.magnitude() == Scalar::ZERO)
+    }
+
+    /// Create an exploded version of a triangle mesh
+    ///
+    /// The mesh must have been created from the same shape as the explosion.
+    /// `factor` scales the directions, so `0.` leaves the mesh as it is, and
+    /// `1.` is the fully exploded view.
+    pub fn apply(&self, mesh: &Mesh<Point<3>>, factor: f64) -> Mesh<Point<3>> {
+        let mut exploded = Mesh::new();
+        for triangle in mesh.triangles() {
+            let offset = triangle
+                .face
+                .map(|face| self.direction_of(face) * factor)
+                .unwrap_or_else(|| Vector::from([0., 0., 0.]));
+
+            exploded.push_triangle_with_face(
+                triangle.points.map(|point| point + offset),
+                triangle.color,
+                triangle.face,
+            );
+        }
+
+        exploded
+    }
+}
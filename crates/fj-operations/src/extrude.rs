@@ -0,0 +1,211 @@
+//! Extruding profiles with holes into solids
+//!
+//! See [`extrude_with_holes`].
+
+use fj_kernel::{
+    algorithms::{sweep_shape, Tolerance},
+    geometry::Surface,
+    shape::{Shape, ValidationError},
+    topology::Face,
+};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::record_step;
+
+/// Extrude a profile with holes into a solid
+///
+/// `exterior` and each of the `holes` are polygons, given in the coordinates
+/// of `surface`. The resulting face is swept along `path`, which must not be
+/// parallel to the surface.
+///
+/// The polygons can be given in either orientation. The exterior is made to
+/// run counter-clockwise around `path`, and the holes clockwise, which is what
+/// the caps and side faces of the solid need to face outwards. Building the
+/// face and sweeping it by hand requires getting all of that right.
+///
+/// The holes must be within the exterior, and must not overlap each other.
+/// That is not checked.
+///
+/// Returns [`ValidationError::Geometric`], if any of the polygons has fewer
+/// than 3 points, or no area, or if `path` is parallel to the surface.
+pub fn extrude_with_holes(
+    surface: Surface,
+    exterior: &[Point<2>],
+    holes: &[Vec<Point<2>>],
+    path: impl Into<Vector<3>>,
+    tolerance: Tolerance,
+    color: [u8; 4],
+) -> Result<Shape, ValidationError> {
+    let path = path.into();
+
+    let to_model = |points: &[Point<2>]| -> Vec<Point<3>> {
+        points
+            .iter()
+            .map(|point| surface.point_surface_to_model(point))
+            .collect()
+    };
+    let exterior = to_model(exterior);
+    let holes: Vec<_> = holes.iter().map(|hole| to_model(hole)).collect();
+
+    // The bottom cap of the swept solid faces the opposite way from the
+    // surface, the top cap the same way. For both of them to face outwards,
+    // the surface needs to face along the path.
+    let normal = surface
+        .vector_surface_to_model(&Vector::from([1., 0.]))
+        .cross(&surface.vector_surface_to_model(&Vector::from([0., 1.])));
+    let alignment = normal.dot(&path);
+    if alignment == Scalar::ZERO {
+        return Err(ValidationError::Geometric(format!(
+            "Can't extrude along {path:?}, as it is parallel to the surface"
+        )));
+    }
+    let surface = if alignment < Scalar::ZERO {
+        surface.reverse()
+    } else {
+        surface
+    };
+
+    let exterior = oriented(exterior, &surface, true, "exterior")?;
+    let holes = holes
+        .into_iter()
+        .map(|hole| oriented(hole, &surface, false, "hole"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut shape = Shape::new();
+    let mut face =
+        Face::builder(surface, &mut shape).with_exterior_polygon(exterior);
+    for hole in holes {
+        face = face.with_interior_polygon(hole);
+    }
+    face.build()?;
+
+    let mut shape = sweep_shape(shape, path, tolerance, color);
+    record_step(&mut shape, "ExtrudeWithHoles", None);
+
+    Ok(shape)
+}
+
+/// Orient a polygon counter-clockwise or clockwise, within `surface`
+fn oriented(
+    mut points: Vec<Point<3>>,
+    surface: &Surface,
+    counter_clockwise: bool,
+    kind: &str,
+) -> Result<Vec<Point<3>>, ValidationError> {
+    if points.len() < 3 {
+        return Err(ValidationError::Geometric(format!(
+            "Polygon of {kind} needs at least 3 points, but has {}",
+            points.len()
+        )));
+    }
+
+    // Shoelace formula. This is twice the signed area, which is positive, if
+    // the polygon is counter-clockwise.
+    let native: Vec<_> = points
+        .iter()
+        .map(|&point| surface.point_model_to_surface(point).native())
+        .collect();
+    let mut area = Scalar::ZERO;
+    for (i, a) in native.iter().enumerate() {
+        let b = native[(i + 1) % native.len()];
+        area += a.u * b.v - b.u * a.v;
+    }
+
+    if area == Scalar::ZERO {
+        return Err(ValidationError::Geometric(format!(
+            "Polygon of {kind} has no area: {points:?}"
+        )));
+    }
+    if (area > Scalar::ZERO) != counter_clockwise {
+        points.reverse();
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{debug::DebugInfo, progress::Progress};
+    use fj_kernel::{
+        algorithms::{mesh_volume, triangulate, weld_mesh, Tolerance},
+        geometry::Surface,
+        shape::ValidationError,
+    };
+    use fj_math::{Point, Scalar};
+
+    use super::extrude_with_holes;
+
+    #[test]
+    fn extrude_in_any_orientation() {
+        let tolerance = Tolerance::from_scalar(Scalar::from_f64(0.1)).unwrap();
+
+        let exterior = square(2.);
+        let hole = square(1.);
+
+        for reverse_exterior in [false, true] {
+            for reverse_hole in [false, true] {
+                for direction in [1., -1.] {
+                    let exterior = reversed(&exterior, reverse_exterior);
+                    let hole = reversed(&hole, reverse_hole);
+
+                    let shape = extrude_with_holes(
+                        Surface::xy_plane(),
+                        &exterior,
+                        &[hole],
+                        [0., 0., direction],
+                        tolerance,
+                        [255, 0, 0, 255],
+                    )
+                    .unwrap();
+
+                    let mesh = triangulate(
+                        shape,
+                        tolerance,
+                        &mut DebugInfo::new(),
+                        &Progress::new(),
+                    )
+                    .unwrap();
+                    let (mesh, report) = weld_mesh(&mesh, tolerance);
+
+                    // A 4x4 square, with a 2x2 hole, extruded by 1.
+                    assert!(report.is_watertight());
+                    assert!(
+                        (mesh_volume(&mesh) - Scalar::from_f64(12.)).abs()
+                            < Scalar::from_f64(1e-9)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extrude_parallel_to_surface() {
+        let tolerance = Tolerance::from_scalar(Scalar::from_f64(0.1)).unwrap();
+
+        let result = extrude_with_holes(
+            Surface::xy_plane(),
+            &square(2.),
+            &[square(1.)],
+            [1., 0., 0.],
+            tolerance,
+            [255, 0, 0, 255],
+        );
+
+        assert!(matches!(result, Err(ValidationError::Geometric(_))));
+    }
+
+    /// A counter-clockwise square around the origin
+    fn square(half_size: f64) -> Vec<Point<2>> {
+        [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]]
+            .map(|[x, y]| Point::from([x * half_size, y * half_size]))
+            .to_vec()
+    }
+
+    fn reversed(points: &[Point<2>], reverse: bool) -> Vec<Point<2>> {
+        let mut points = points.to_vec();
+        if reverse {
+            points.reverse();
+        }
+        points
+    }
+}
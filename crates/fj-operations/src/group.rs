@@ -8,7 +8,7 @@ use fj_kernel::{
 };
 use fj_math::Aabb;
 
-use super::ToShape;
+use super::{record_step, ToShape};
 
 impl ToShape for fj::Group {
     fn to_shape(
@@ -18,8 +18,11 @@ impl ToShape for fj::Group {
     ) -> Shape {
         let mut shape = Shape::new();
 
-        let a = self.a.to_shape(tolerance, debug_info);
-        let b = self.b.to_shape(tolerance, debug_info);
+        let mut a = self.a.to_shape(tolerance, debug_info);
+        let mut b = self.b.to_shape(tolerance, debug_info);
+
+        record_step(&mut a, "Group", Some(0));
+        record_step(&mut b, "Group", Some(1));
 
         copy_shape(a, &mut shape);
         copy_shape(b, &mut shape);
@@ -46,14 +49,17 @@ fn copy_shape(orig: Shape, target: &mut Shape) {
 
     for point_orig in orig.points() {
         let point = target.insert(point_orig.get()).unwrap();
+        target.set_attributes(&point, point_orig.attributes());
         points.insert(point_orig, point);
     }
     for curve_orig in orig.curves() {
         let curve = target.insert(curve_orig.get()).unwrap();
+        target.set_attributes(&curve, curve_orig.attributes());
         curves.insert(curve_orig, curve);
     }
     for surface_orig in orig.surfaces() {
         let surface = target.insert(surface_orig.get()).unwrap();
+        target.set_attributes(&surface, surface_orig.attributes());
         surfaces.insert(surface_orig, surface);
     }
 
@@ -63,6 +69,7 @@ fn copy_shape(orig: Shape, target: &mut Shape) {
                 point: points[&vertex_orig.get().point].clone(),
             })
             .unwrap();
+        target.set_attributes(&vertex, vertex_orig.attributes());
         vertices.insert(vertex_orig, vertex);
     }
     for edge_orig in orig.edges() {
@@ -74,6 +81,7 @@ fn copy_shape(orig: Shape, target: &mut Shape) {
                 }),
             })
             .unwrap();
+        target.set_attributes(&edge, edge_orig.attributes());
         edges.insert(edge_orig, edge);
     }
     for cycle_orig in orig.cycles() {
@@ -87,35 +95,33 @@ fn copy_shape(orig: Shape, target: &mut Shape) {
                     .collect(),
             })
             .unwrap();
+        target.set_attributes(&cycle, cycle_orig.attributes());
         cycles.insert(cycle_orig, cycle);
     }
 
     for face_orig in orig.faces() {
-        match face_orig.get() {
+        let face = match face_orig.get() {
             Face::Face {
                 surface,
                 exteriors,
                 interiors,
                 color,
-            } => {
-                target
-                    .insert(Face::Face {
-                        surface: surfaces[&surface].clone(),
-                        exteriors: exteriors
-                            .iter()
-                            .map(|cycle| cycles[cycle].clone())
-                            .collect(),
-                        interiors: interiors
-                            .iter()
-                            .map(|cycle| cycles[cycle].clone())
-                            .collect(),
-                        color,
-                    })
-                    .unwrap();
-            }
-            face @ Face::Triangles(_) => {
-                target.insert(face.clone()).unwrap();
-            }
-        }
+            } => target
+                .insert(Face::Face {
+                    surface: surfaces[&surface].clone(),
+                    exteriors: exteriors
+                        .iter()
+                        .map(|cycle| cycles[cycle].clone())
+                        .collect(),
+                    interiors: interiors
+                        .iter()
+                        .map(|cycle| cycles[cycle].clone())
+                        .collect(),
+                    color,
+                })
+                .unwrap(),
+            face @ Face::Triangles(_) => target.insert(face).unwrap(),
+        };
+        target.set_attributes(&face, face_orig.attributes());
     }
 }
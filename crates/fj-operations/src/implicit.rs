@@ -0,0 +1,39 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{marching_cubes, Tolerance, VoxelGrid},
+    shape::Shape,
+    topology::Face,
+};
+use fj_math::{Aabb, Point, Scalar, Triangle};
+
+use super::{record_step, ToShape};
+
+impl ToShape for fj::Implicit {
+    fn to_shape(&self, _: Tolerance, _: &mut DebugInfo) -> Shape {
+        let mut shape = Shape::new();
+
+        // The resolution is up to the model, as sampling the function at the
+        // tolerance would be prohibitively slow for most shapes.
+        let resolution = Scalar::from_f64(self.resolution());
+        let grid =
+            VoxelGrid::sample(&self.bounding_volume(), resolution, |point| {
+                Scalar::from_f64(self.distance(point.into()))
+            });
+
+        let triangles = marching_cubes(&grid)
+            .into_iter()
+            .map(|points| (Triangle::from(points), self.color()))
+            .collect();
+        shape.insert(Face::Triangles(triangles)).unwrap();
+
+        record_step(&mut shape, "Implicit", None);
+        shape
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        Aabb {
+            min: Point::from(self.min()),
+            max: Point::from(self.max()),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Parts of an assembly that are copies of each other
+//!
+//! Assemblies often contain the same part more than once, like the screws of a
+//! pattern. Each copy has its own triangles in the mesh of the shape, but they
+//! only differ by a rigid transform. Viewers can draw the triangles of one
+//! copy for all of them, using the transforms from [`find_instances`].
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::{FaceId, Triangle};
+use fj_math::{Point, Scalar, Transform, Vector};
+use tracing::debug_span;
+
+use crate::shape_processor::ProcessedShape;
+
+/// A part, and the copies of it
+#[derive(Clone, Debug)]
+pub struct Instances {
+    /// The faces of the part that the copies are made from
+    pub faces: Vec<FaceId>,
+
+    /// The copies of the part
+    pub copies: Vec<Instance>,
+}
+
+/// A copy of a part
+#[derive(Clone, Debug)]
+pub struct Instance {
+    /// The faces of the copy
+    ///
+    /// Each of them corresponds to the face of the part at the same position
+    /// in [`Instances::faces`].
+    pub faces: Vec<FaceId>,
+
+    /// The transform from the part to the copy
+    pub transform: Transform,
+}
+
+/// Find the parts of a shape that are copies of another part
+///
+/// Parts are found by [`ProcessedShape::parts`]. A part is a copy of another,
+/// if it has the same faces, in the same order, with the same triangles,
+/// colors, and materials, and if a rigid transform moves each of its points
+/// within the tolerance of the shape. That's the case for parts that are
+/// transformed copies of the same shape, as long as the mesh hasn't been
+/// decimated or canonicalized.
+///
+/// Faces that are not part of any group are never instanced.
+pub fn find_instances(shape: &ProcessedShape) -> Vec<Instances> {
+    let _span = debug_span!("find_instances").entered();
+
+    let mut triangles_of_face: BTreeMap<FaceId, Vec<Triangle>> =
+        BTreeMap::new();
+    for triangle in shape.mesh.triangles() {
+        if let Some(face) = triangle.face {
+            triangles_of_face.entry(face).or_default().push(triangle);
+        }
+    }
+
+    let tolerance = shape.tolerance.inner();
+
+    // The parts that others can be copies of, with their triangles by face.
+    let mut found: Vec<(Instances, Vec<&[Triangle]>)> = Vec::new();
+
+    'parts: for part in shape.parts() {
+        if part.path.is_empty() {
+            continue;
+        }
+
+        let triangles: Vec<_> = part
+            .faces
+            .iter()
+            .map(|face| {
+                triangles_of_face.get(face).map_or(&[][..], Vec::as_slice)
+            })
+            .collect();
+
+        for (instances, original) in &mut found {
+            if !is_similar(shape, &instances.faces, &part.faces)
+                || !triangles
+                    .iter()
+                    .zip(original.iter())
+                    .all(|(a, b)| is_similar_face(a, b))
+            {
+                continue;
+            }
+
+            let original = original.iter().flat_map(|face| face.iter());
+            let copy = triangles.iter().flat_map(|face| face.iter());
+
+            if let Some(transform) = match_copy(original, copy, tolerance) {
+                instances.copies.push(Instance {
+                    faces: part.faces,
+                    transform,
+                });
+                continue 'parts;
+            }
+        }
+
+        found.push((
+            Instances {
+                faces: part.faces,
+                copies: Vec::new(),
+            },
+            triangles,
+        ));
+    }
+
+    found
+        .into_iter()
+        .map(|(instances, _)| instances)
+        .filter(|instances| !instances.copies.is_empty())
+        .collect()
+}
+
+/// Whether two parts have the same number of faces, with the same materials
+fn is_similar(shape: &ProcessedShape, a: &[FaceId], b: &[FaceId]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(&a, &b)| {
+            let material = |face| {
+                shape
+                    .attributes_of(face)
+                    .and_then(|attributes| attributes.material())
+            };
+            material(a) == material(b)
+        })
+}
+
+/// Whether two faces have the same number of triangles, with the same colors
+fn is_similar_face(a: &[Triangle], b: &[Triangle]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.color == b.color)
+}
+
+/// Find the rigid transform that moves each triangle onto its copy
+///
+/// The transform is derived from the first triangle that isn't degenerate,
+/// then checked against all others. Returns `None`, if any point ends up
+/// further than `tolerance` from where it should be.
+fn match_copy<'r>(
+    original: impl Iterator<Item = &'r Triangle> + Clone,
+    copy: impl Iterator<Item = &'r Triangle> + Clone,
+    tolerance: Scalar,
+) -> Option<Transform> {
+    let (a, b) = original
+        .clone()
+        .zip(copy.clone())
+        .find(|(a, _)| frame(a.points).is_some())?;
+    let transform = rigid_transform(frame(a.points)?, frame(b.points)?);
+
+    let matches = original.zip(copy).all(|(a, b)| {
+        a.points.iter().zip(b.points).all(|(a, b)| {
+            Point::distance(&transform.transform_point(a), &b) <= tolerance
+        })
+    });
+
+    matches.then(|| transform)
+}
+
+/// An orthonormal frame at the first point of a triangle
+///
+/// Returns `None`, if the triangle is degenerate.
+fn frame([a, b, c]: [Point<3>; 3]) -> Option<(Point<3>, [Vector<3>; 3])> {
+    let normal = (b - a).cross(&(c - a));
+    if normal.magnitude() == Scalar::ZERO {
+        return None;
+    }
+
+    let x = (b - a).normalize();
+    let z = normal.normalize();
+    let y = z.cross(&x);
+
+    Some((a, [x, y, z]))
+}
+
+/// The rigid transform that moves one frame onto another
+fn rigid_transform(
+    (a, from): (Point<3>, [Vector<3>; 3]),
+    (b, to): (Point<3>, [Vector<3>; 3]),
+) -> Transform {
+    // The rotation is `to * from^T`, as the frames are orthonormal.
+    let rotation = |row: usize, column: usize| {
+        (0..3)
+            .map(|k| {
+                to[k].components[row].into_f64()
+                    * from[k].components[column].into_f64()
+            })
+            .sum::<f64>()
+    };
+
+    let mut data = [0.; 16];
+    for (i, value) in data.iter_mut().enumerate() {
+        let (column, row) = (i / 4, i % 4);
+        *value = match (column, row) {
+            (3, 3) => 1.,
+            (3, _) | (_, 3) => 0.,
+            _ => rotation(row, column),
+        };
+    }
+    let rotation = Transform::from_data(data);
+
+    Transform::translation(b - rotation.transform_point(&a)) * rotation
+}
@@ -0,0 +1,83 @@
+//! Simplified versions of a triangle mesh, for displaying it from afar
+//!
+//! Models with millions of triangles are expensive to draw, but most of that
+//! detail isn't visible, unless the camera is close. Viewers can draw one of
+//! the levels of detail from [`levels_of_detail`] instead, if its error is
+//! smaller than a pixel on screen.
+
+use fj_interop::{
+    mesh::Mesh,
+    progress::{Cancelled, Progress},
+};
+use fj_kernel::algorithms::{decimate_mesh, DecimationTarget};
+use fj_math::{Aabb, Point, Scalar};
+use tracing::debug_span;
+
+/// Meshes with fewer triangles than this are always drawn in full detail
+const MIN_TRIANGLES: usize = 100_000;
+
+/// The maximum errors of the levels, relative to the size of the mesh
+const ERRORS: [f64; 3] = [0.0005, 0.002, 0.008];
+
+/// A simplified version of a triangle mesh
+pub struct LevelOfDetail {
+    /// The simplified mesh
+    ///
+    /// Its triangles keep the colors and faces of the triangles they replace.
+    pub mesh: Mesh<Point<3>>,
+
+    /// The approximate maximum distance from the original mesh
+    pub error: Scalar,
+}
+
+/// Simplify a mesh into levels of increasing error
+///
+/// Each level is decimated from the previous one, using [`decimate_mesh`], so
+/// its error includes the errors of all previous levels. Levels that don't
+/// have at most half the triangles of the previous one aren't worth drawing
+/// instead, and are left out. Meshes with fewer than 100,000 triangles don't
+/// get any levels.
+///
+/// Returns early, if `progress` has been cancelled.
+pub fn levels_of_detail(
+    mesh: &Mesh<Point<3>>,
+    progress: &Progress,
+) -> Result<Vec<LevelOfDetail>, Cancelled> {
+    let _span = debug_span!("levels_of_detail").entered();
+
+    let mut num_triangles = mesh.triangles().count();
+    if num_triangles < MIN_TRIANGLES {
+        return Ok(Vec::new());
+    }
+
+    let size = Aabb::<3>::from_points(mesh.vertices()).size().magnitude();
+
+    let mut levels: Vec<LevelOfDetail> = Vec::new();
+    for error in ERRORS {
+        let error = size * Scalar::from_f64(error);
+        let (previous, previous_error) = match levels.last() {
+            Some(level) => (&level.mesh, level.error),
+            None => (mesh, Scalar::ZERO),
+        };
+
+        let simplified = decimate_mesh(
+            previous,
+            DecimationTarget::MaxError(error),
+            progress,
+        )?;
+
+        let num_simplified = simplified.triangles().count();
+        if num_simplified * 2 > num_triangles {
+            continue;
+        }
+        num_triangles = num_simplified;
+
+        // The errors of the decimations add up, at worst.
+        levels.push(LevelOfDetail {
+            mesh: simplified,
+            error: previous_error + error,
+        });
+    }
+
+    Ok(levels)
+}
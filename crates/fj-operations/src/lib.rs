@@ -16,17 +16,36 @@
 
 #![deny(missing_docs)]
 
+pub mod assertions;
+pub mod bom;
+pub mod context;
+pub mod diff;
+pub mod explosion;
+pub mod extrude;
+pub mod instances;
+pub mod level_of_detail;
+pub mod selection;
 pub mod shape_processor;
 
 mod circle;
 mod difference_2d;
+mod exploded;
 mod group;
+mod implicit;
+mod minkowski_2d;
+mod named;
+mod rounded;
 mod sketch;
 mod sweep;
+mod textured;
 mod transform;
+mod with_material;
 
 use fj_interop::debug::DebugInfo;
-use fj_kernel::{algorithms::Tolerance, shape::Shape};
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Attribute, Provenance, Shape, Step},
+};
 use fj_math::Aabb;
 
 /// Implemented for all operations from the [`fj`] crate
@@ -45,6 +64,40 @@ pub trait ToShape {
     fn bounding_volume(&self) -> Aabb<3>;
 }
 
+/// Record that the faces and edges of a shape went through an operation
+///
+/// Operations call this on their result, or on each of their inputs, if they
+/// have more than one. `input` is the index of that input.
+///
+/// The step is added to the provenance of objects that already have one. All
+/// other objects are assumed to have been created by the operation.
+fn record_step(shape: &mut Shape, operation: &str, input: Option<usize>) {
+    let step = Step {
+        operation: operation.to_owned(),
+        input,
+    };
+
+    for face in shape.faces() {
+        let provenance = with_step(face.attributes().provenance(), &step);
+        shape.insert_attribute(&face, Attribute::Provenance(provenance));
+    }
+    for edge in shape.edges() {
+        let provenance = with_step(edge.attributes().provenance(), &step);
+        shape.insert_attribute(&edge, Attribute::Provenance(provenance));
+    }
+}
+
+fn with_step(provenance: Option<&Provenance>, step: &Step) -> Provenance {
+    match provenance {
+        Some(provenance) => {
+            let mut provenance = provenance.clone();
+            provenance.push_outer(step.clone());
+            provenance
+        }
+        None => Provenance::new(step.clone()),
+    }
+}
+
 macro_rules! dispatch {
     ($($method:ident($($arg_name:ident: $arg_ty:ty,)*) -> $ret:ty;)*) => {
         impl ToShape for fj::Shape {
@@ -53,6 +106,12 @@ macro_rules! dispatch {
                     match self {
                         Self::Shape2d(shape) => shape.$method($($arg_name,)*),
                         Self::Shape3d(shape) => shape.$method($($arg_name,)*),
+                        Self::Marked(marked) => {
+                            marked.shape.$method($($arg_name,)*)
+                        }
+                        Self::Asserted(asserted) => {
+                            asserted.shape.$method($($arg_name,)*)
+                        }
                     }
                 }
             )*
@@ -62,9 +121,22 @@ macro_rules! dispatch {
             $(
                 fn $method(&self, $($arg_name: $arg_ty,)*) -> $ret {
                     match self {
-                        Self::Circle(shape) => shape.$method($($arg_name,)*),
-                        Self::Difference(shape) => shape.$method($($arg_name,)*),
-                        Self::Sketch(shape) => shape.$method($($arg_name,)*),
+                        Self::Circle(shape) => {
+                            let _operation = context::enter("Circle");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Difference(shape) => {
+                            let _operation = context::enter("Difference2d");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Minkowski(shape) => {
+                            let _operation = context::enter("Minkowski2d");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Sketch(shape) => {
+                            let _operation = context::enter("Sketch");
+                            shape.$method($($arg_name,)*)
+                        }
                     }
                 }
             )*
@@ -74,9 +146,42 @@ macro_rules! dispatch {
             $(
                 fn $method(&self, $($arg_name: $arg_ty,)*) -> $ret {
                     match self {
-                        Self::Group(shape) => shape.$method($($arg_name,)*),
-                        Self::Sweep(shape) => shape.$method($($arg_name,)*),
-                        Self::Transform(shape) => shape.$method($($arg_name,)*),
+                        Self::Exploded(shape) => {
+                            let _operation = context::enter("Exploded");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Group(shape) => {
+                            let _operation = context::enter("Group");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Implicit(shape) => {
+                            let _operation = context::enter("Implicit");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Named(shape) => {
+                            let _operation = context::enter("Named");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Rounded(shape) => {
+                            let _operation = context::enter("Rounded");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Sweep(shape) => {
+                            let _operation = context::enter("Sweep");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Textured(shape) => {
+                            let _operation = context::enter("Textured");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::Transform(shape) => {
+                            let _operation = context::enter("Transform");
+                            shape.$method($($arg_name,)*)
+                        }
+                        Self::WithMaterial(shape) => {
+                            let _operation = context::enter("WithMaterial");
+                            shape.$method($($arg_name,)*)
+                        }
                     }
                 }
             )*
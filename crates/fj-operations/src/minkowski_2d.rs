@@ -0,0 +1,159 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{ApproxCache, CycleApprox, Tolerance},
+    geometry::Surface,
+    shape::Shape,
+    topology::Face,
+};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use super::{record_step, ToShape};
+
+impl ToShape for fj::Minkowski2d {
+    fn to_shape(
+        &self,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Shape {
+        let mut shape = Shape::new();
+
+        let [a, b] = self.shapes();
+        let a = outline(a, tolerance, debug_info);
+        let b = outline(b, tolerance, debug_info);
+
+        // The Minkowski sum of two convex polygons is the convex hull of the
+        // sums of their vertices.
+        let mut sums = Vec::new();
+        for &point_a in &a {
+            for point_b in &b {
+                sums.push(point_a + point_b.coords);
+            }
+        }
+
+        let surface = Surface::xy_plane();
+        let points = convex_hull(sums)
+            .into_iter()
+            .map(|point| surface.point_surface_to_model(&point));
+
+        Face::builder(surface, &mut shape)
+            .with_exterior_polygon(points)
+            .build()
+            .unwrap();
+
+        record_step(&mut shape, "Minkowski2d", None);
+        shape
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // The bounding box of a Minkowski sum is the sum of the bounding boxes.
+        let [a, b] = self.shapes();
+        let [a, b] = [a.bounding_volume(), b.bounding_volume()];
+
+        Aabb {
+            min: a.min + b.min.coords,
+            max: a.max + b.max.coords,
+        }
+    }
+}
+
+/// Approximate the outline of a 2-dimensional shape, which must be convex
+///
+/// [`fj::Minkowski2d`] only accepts shapes that are convex, which means each
+/// of them results in a single face without holes.
+fn outline(
+    shape: &fj::Shape2d,
+    tolerance: Tolerance,
+    debug_info: &mut DebugInfo,
+) -> Vec<Point<2>> {
+    let shape = shape.to_shape(tolerance, debug_info);
+
+    // Can't panic, as convex shapes result in a single face.
+    let face = shape.faces().next().unwrap().get();
+    let cycle = match face {
+        Face::Face { exteriors, .. } => exteriors[0].get(),
+        Face::Triangles(_) => {
+            unreachable!("Convex 2D shapes result in faces with cycles")
+        }
+    };
+
+    let approx = CycleApprox::new(&cycle, tolerance, &mut ApproxCache::new());
+    let mut points: Vec<_> = approx
+        .points
+        .into_iter()
+        .map(|point| Point::from([point.x, point.y]))
+        .collect();
+
+    // The approximation of a closed cycle ends where it starts.
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    debug_assert!(
+        is_convex(&points, tolerance),
+        "Approximation of convex shape should be convex"
+    );
+
+    points
+}
+
+/// Check whether a polygon is convex, in either orientation
+///
+/// Turns that are smaller than the tolerance are ignored, as those come from
+/// points that are collinear, but for rounding errors.
+fn is_convex(points: &[Point<2>], tolerance: Tolerance) -> bool {
+    let epsilon = tolerance.inner() * tolerance.inner();
+
+    let mut left = false;
+    let mut right = false;
+
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let c = points[(i + 2) % points.len()];
+
+        let turn = cross(b - a, c - b);
+        left |= turn > epsilon;
+        right |= turn < -epsilon;
+    }
+
+    !(left && right)
+}
+
+/// Compute the convex hull of some points, counter-clockwise
+///
+/// Uses Andrew's monotone chain algorithm. Points on the edges of the hull
+/// are left out.
+fn convex_hull(mut points: Vec<Point<2>>) -> Vec<Point<2>> {
+    points.sort();
+    points.dedup();
+
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Point<2>> = Vec::new();
+
+    // The lower half of the hull goes left to right, the upper half right to
+    // left. Both end at the first point of the other one.
+    for half in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+
+        for point in half {
+            while hull.len() >= start + 2 {
+                let [a, b] = [hull[hull.len() - 2], hull[hull.len() - 1]];
+                if cross(b - a, point - b) > Scalar::ZERO {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(point);
+        }
+
+        hull.pop();
+    }
+
+    hull
+}
+
+fn cross(a: Vector<2>, b: Vector<2>) -> Scalar {
+    a.to_xyz().cross(&b.to_xyz()).z
+}
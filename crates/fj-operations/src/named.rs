@@ -0,0 +1,33 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Attribute, Shape},
+};
+use fj_math::Aabb;
+
+use super::{record_step, ToShape};
+
+impl ToShape for fj::Named {
+    fn to_shape(
+        &self,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Shape {
+        let mut shape = self.shape.to_shape(tolerance, debug_info);
+
+        // Parts that have been named within this shape keep their names.
+        for face in shape.faces() {
+            if face.attributes().name().is_none() {
+                let name = Attribute::Name(self.name.clone());
+                shape.insert_attribute(&face, name);
+            }
+        }
+
+        record_step(&mut shape, "Named", None);
+        shape
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.shape.bounding_volume()
+    }
+}
@@ -0,0 +1,66 @@
+use fj_interop::{debug::DebugInfo, progress::Progress};
+use fj_kernel::{
+    algorithms::{offset_mesh, triangulate, Tolerance},
+    shape::Shape,
+    topology::Face,
+};
+use fj_math::{Aabb, Scalar, Triangle, Vector};
+
+use super::{record_step, ToShape};
+
+impl ToShape for fj::Rounded {
+    fn to_shape(
+        &self,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Shape {
+        let rounded = self.shape().to_shape(tolerance, debug_info);
+
+        // Nobody else has access to this `Progress`, so it can't be cancelled.
+        let progress = Progress::new();
+        let mesh = triangulate(rounded, tolerance, debug_info, &progress)
+            .expect("Triangulation was cancelled without a way to cancel it");
+
+        // Like for implicit shapes, the resolution is up to the model, as
+        // sampling at the tolerance would be prohibitively slow.
+        let resolution = match self.resolution() {
+            Some(resolution) => Scalar::from_f64(resolution),
+            None => {
+                let size = self.bounding_volume().size();
+                let extent =
+                    size.components.into_iter().fold(Scalar::ZERO, Scalar::max);
+                extent / Scalar::from_f64(64.)
+            }
+        };
+
+        let color = mesh
+            .triangles()
+            .next()
+            .map_or([255, 0, 0, 255], |triangle| triangle.color);
+        let triangles = offset_mesh(
+            &mesh,
+            Scalar::from_f64(self.radius()),
+            resolution,
+            tolerance,
+        )
+        .into_iter()
+        .map(|points| (Triangle::from(points), color))
+        .collect();
+
+        let mut shape = Shape::new();
+        shape.insert(Face::Triangles(triangles)).unwrap();
+
+        record_step(&mut shape, "Rounded", None);
+        shape
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let aabb = self.shape().bounding_volume();
+        let margin = Vector::from([self.radius(); 3]);
+
+        Aabb {
+            min: aabb.min - margin,
+            max: aabb.max + margin,
+        }
+    }
+}
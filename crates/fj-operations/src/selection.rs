@@ -0,0 +1,203 @@
+//! Resolving selections against evaluated shapes
+//!
+//! Please refer to [`fj::selection`] for how selections are described.
+
+use std::collections::HashSet;
+
+use fj::selection::{EdgeSelection, FaceSelection};
+use fj_kernel::{
+    algorithms::{ApproxCache, Tolerance},
+    geometry::{Curve, Surface},
+    shape::{Handle, Shape},
+    topology::{Edge, Face},
+};
+use fj_math::{Point, Scalar, Vector};
+
+/// Resolves selections against a shape
+///
+/// Edges are approximated to determine their length and extent, so this keeps
+/// a cache of those approximations around. Resolving multiple selections
+/// against the same shape should use the same `Selector`.
+pub struct Selector<'r> {
+    shape: &'r Shape,
+    tolerance: Tolerance,
+    cache: ApproxCache,
+}
+
+impl<'r> Selector<'r> {
+    /// Construct a `Selector` for the given shape
+    ///
+    /// `tolerance` defines how far the approximation of the shape is allowed to
+    /// deviate from the actual shape. Faces are considered to extend equally
+    /// far, if they are within `tolerance` of each other.
+    pub fn new(shape: &'r Shape, tolerance: Tolerance) -> Self {
+        Self {
+            shape,
+            tolerance,
+            cache: ApproxCache::new(),
+        }
+    }
+
+    /// Find the faces described by `selection`
+    pub fn faces(&mut self, selection: &FaceSelection) -> Vec<Handle<Face>> {
+        match selection {
+            FaceSelection::All => self.shape.faces().collect(),
+            FaceSelection::ParallelTo(faces, direction) => {
+                let direction = Vector::from(*direction);
+
+                self.faces(faces)
+                    .into_iter()
+                    .filter(|face| {
+                        normal(&face.get()).map_or(false, |normal| {
+                            is_parallel(&normal, &direction)
+                        })
+                    })
+                    .collect()
+            }
+            FaceSelection::Furthest(faces, direction) => {
+                let direction = Vector::from(*direction).normalize();
+
+                let faces = self.faces(faces);
+                let extents: Vec<_> = faces
+                    .iter()
+                    .map(|face| {
+                        self.face_points(face)
+                            .into_iter()
+                            .map(|point| point.coords.dot(&direction))
+                            .max()
+                    })
+                    .collect();
+
+                let max = match extents.iter().flatten().max() {
+                    Some(&max) => max,
+                    None => return Vec::new(),
+                };
+
+                faces
+                    .into_iter()
+                    .zip(extents)
+                    .filter(|(_, extent)| {
+                        extent.map_or(false, |extent| {
+                            extent >= max - self.tolerance.inner()
+                        })
+                    })
+                    .map(|(face, _)| face)
+                    .collect()
+            }
+        }
+    }
+
+    /// Find the edges described by `selection`
+    pub fn edges(&mut self, selection: &EdgeSelection) -> Vec<Handle<Edge>> {
+        match selection {
+            EdgeSelection::All => self.shape.edges().collect(),
+            EdgeSelection::OfFace(edges, faces) => {
+                let mut bounding = HashSet::new();
+                for face in self.faces(faces) {
+                    if let Face::Face {
+                        exteriors,
+                        interiors,
+                        ..
+                    } = face.get()
+                    {
+                        for cycle in exteriors.iter().chain(&interiors) {
+                            bounding.extend(cycle.get().edges);
+                        }
+                    }
+                }
+
+                self.edges(edges)
+                    .into_iter()
+                    .filter(|edge| bounding.contains(edge))
+                    .collect()
+            }
+            EdgeSelection::ParallelTo(edges, direction) => {
+                let direction = Vector::from(*direction);
+
+                self.edges(edges)
+                    .into_iter()
+                    .filter(|edge| match edge.get().curve() {
+                        Curve::Line(line) => {
+                            is_parallel(&line.direction, &direction)
+                        }
+                        Curve::Circle(_) => false,
+                    })
+                    .collect()
+            }
+            EdgeSelection::LengthGreaterThan(edges, length) => {
+                let length = Scalar::from_f64(*length);
+
+                self.edges(edges)
+                    .into_iter()
+                    .filter(|edge| self.edge_length(edge) > length)
+                    .collect()
+            }
+            EdgeSelection::LengthLessThan(edges, length) => {
+                let length = Scalar::from_f64(*length);
+
+                self.edges(edges)
+                    .into_iter()
+                    .filter(|edge| self.edge_length(edge) < length)
+                    .collect()
+            }
+        }
+    }
+
+    fn face_points(&mut self, face: &Handle<Face>) -> Vec<Point<3>> {
+        let mut points = Vec::new();
+
+        match face.get() {
+            Face::Face { exteriors, .. } => {
+                for cycle in exteriors {
+                    for edge in &cycle.get().edges {
+                        points.extend(self.cache.edge(edge, self.tolerance));
+                    }
+                }
+            }
+            Face::Triangles(triangles) => {
+                for (triangle, _) in triangles {
+                    points.extend(triangle.points());
+                }
+            }
+        }
+
+        points
+    }
+
+    fn edge_length(&mut self, edge: &Handle<Edge>) -> Scalar {
+        self.cache
+            .edge(edge, self.tolerance)
+            .windows(2)
+            .map(|segment| (segment[1] - segment[0]).magnitude())
+            .fold(Scalar::ZERO, |length, segment| length + segment)
+    }
+}
+
+/// The normal of a face, if it is planar
+fn normal(face: &Face) -> Option<Vector<3>> {
+    match face {
+        Face::Face { surface, .. } => match surface.get() {
+            Surface::SweptCurve(surface) => match surface.curve {
+                Curve::Line(line) => Some(line.direction.cross(&surface.path)),
+                Curve::Circle(_) => None,
+            },
+        },
+        Face::Triangles(triangles) => {
+            let mut normals = triangles.iter().map(|(triangle, _)| {
+                let [a, b, c] = triangle.points();
+                (b - a).cross(&(c - a))
+            });
+
+            let normal = normals.next()?;
+            if normals.all(|other| is_parallel(&normal, &other)) {
+                Some(normal)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn is_parallel(a: &Vector<3>, b: &Vector<3>) -> bool {
+    a.normalize().cross(&b.normalize()).magnitude() < Scalar::from_f64(1e-9)
+}
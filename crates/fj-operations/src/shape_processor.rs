@@ -1,65 +1,463 @@
 //! API for processing shapes
 
-use fj_interop::{debug::DebugInfo, mesh::Mesh};
-use fj_kernel::algorithms::{triangulate, Tolerance};
+use std::collections::BTreeMap;
+
+use fj_interop::{
+    debug::{DebugCategory, DebugInfo},
+    mesh::{FaceId, Mesh},
+    progress::{Cancelled, Progress},
+    topology::Topology,
+};
+use fj_kernel::{
+    algorithms::{
+        analyze_draft, canonicalize_mesh, decimate_mesh, find_thin_walls,
+        orient_mesh, texture_mesh, triangulate, weld_mesh, DecimationTarget,
+        Draft, DraftAnalysis, DraftCheck, NonOrientable, OrientReport,
+        ThinWall, Tolerance, WeldReport,
+    },
+    shape::{Attributes, Shape, Step},
+    topology::Face,
+};
 use fj_math::{Aabb, Point, Scalar};
+use tracing::{debug, debug_span, info_span};
 
-use crate::ToShape as _;
+use crate::{context, ToShape as _};
 
 /// Processes an [`fj::Shape`] into a [`ProcessedShape`]
+#[derive(Clone, Copy, Debug)]
 pub struct ShapeProcessor {
     /// The tolerance value used for creating the triangle mesh
+    ///
+    /// If this is `None`, [`Tolerance::default`] is used. A relative tolerance
+    /// is relative to the smallest non-zero extent of the shape.
     pub tolerance: Option<Tolerance>,
+
+    /// Simplify the triangle mesh until this target is reached
+    ///
+    /// If this is `None`, the triangle mesh is left as-is.
+    pub decimation: Option<DecimationTarget>,
+
+    /// Bring the triangle mesh into a canonical form
+    ///
+    /// If this is `true`, processing the same shape always results in a
+    /// bit-identical mesh, which makes output that is derived from it safe to
+    /// diff or cache. See [`canonicalize_mesh`].
+    pub deterministic: bool,
+
+    /// Look for walls that are thinner than this
+    ///
+    /// If this is `None`, the wall thickness isn't analyzed. See
+    /// [`find_thin_walls`].
+    pub min_wall_thickness: Option<Scalar>,
+
+    /// Check whether the shape can be pulled out of a mold
+    ///
+    /// If this is `None`, the draft isn't analyzed. See [`analyze_draft`].
+    pub draft: Option<DraftCheck>,
 }
 
 impl ShapeProcessor {
     /// Process an [`fj::Shape`] into [`ProcessedShape`]
     pub fn process(&self, shape: &fj::Shape) -> ProcessedShape {
+        // Nobody else has access to this `Progress`, so it can't be cancelled.
+        self.process_with_progress(shape, &Progress::new())
+            .expect("Processing was cancelled without a way to cancel it")
+    }
+
+    /// Compute the boundary representation of an [`fj::Shape`]
+    ///
+    /// That's what processing starts with, before the shape is triangulated.
+    /// It's computed with the same tolerance, and can be inspected using
+    /// [`Shape::dump`].
+    pub fn to_shape(&self, shape: &fj::Shape) -> Shape {
+        // A previous call might have panicked, without anyone taking the
+        // operations it left behind.
+        context::reset();
+
+        let tolerance = resolve_tolerance(
+            self.tolerance.unwrap_or_default(),
+            &shape.bounding_volume(),
+        );
+        shape.to_shape(tolerance, &mut DebugInfo::new())
+    }
+
+    /// Process an [`fj::Shape`] into [`ProcessedShape`], reporting progress
+    ///
+    /// Returns [`Cancelled`], if `progress` is cancelled before processing
+    /// finishes.
+    pub fn process_with_progress(
+        &self,
+        shape: &fj::Shape,
+        progress: &Progress,
+    ) -> Result<ProcessedShape, Cancelled> {
+        let _span = info_span!("process_shape").entered();
+
+        // A previous call might have panicked, without anyone taking the
+        // operations it left behind.
+        context::reset();
+
         let aabb = shape.bounding_volume();
+        let topology = match shape.topology() {
+            fj::Topology::Solid => Topology::Solid,
+            fj::Topology::Sheet => Topology::Sheet,
+            fj::Topology::Wire => Topology::Wire,
+        };
+        let assertions = shape.assertions();
 
-        let tolerance = match self.tolerance {
-            None => {
-                // Compute a reasonable default for the tolerance value. To do
-                // this, we just look at the smallest non-zero extent of the
-                // bounding box and divide that by some value.
-                let mut min_extent = Scalar::MAX;
-                for extent in aabb.size().components {
-                    if extent > Scalar::ZERO && extent < min_extent {
-                        min_extent = extent;
-                    }
-                }
+        let tolerance =
+            resolve_tolerance(self.tolerance.unwrap_or_default(), &aabb);
+
+        let mut debug_info = DebugInfo::new();
+        let shape = debug_span!("to_shape")
+            .in_scope(|| shape.to_shape(tolerance, &mut debug_info));
+        progress.check()?;
+
+        // Triangulation identifies faces by their position in this order.
+        let face_attributes: Vec<_> =
+            shape.faces().map(|face| face.attributes()).collect();
 
-                let tolerance = min_extent / Scalar::from_f64(1000.);
-                Tolerance::from_scalar(tolerance).unwrap()
+        // Textures are laid out on the surfaces of their faces, which are no
+        // longer known after triangulation.
+        let mut textures = BTreeMap::new();
+        for (i, face) in shape.faces().enumerate() {
+            if let (Some(texture), Face::Face { surface, .. }) =
+                (face_attributes[i].texture(), face.get())
+            {
+                textures.insert(FaceId(i as u64), (texture, surface.get()));
             }
-            Some(user_defined_tolerance) => user_defined_tolerance,
+        }
+
+        let mesh = triangulate(shape, tolerance, &mut debug_info, progress)?;
+
+        // Materials determine the color of their faces, overriding the colors
+        // of the sketches that the faces were created from.
+        let has_materials = face_attributes
+            .iter()
+            .any(|attributes| attributes.material().is_some());
+        let mesh = if has_materials {
+            apply_material_colors(&mesh, &face_attributes)
+        } else {
+            mesh
         };
 
-        let mut debug_info = DebugInfo::new();
-        let mesh = triangulate(
-            shape.to_shape(tolerance, &mut debug_info),
-            tolerance,
-            &mut debug_info,
-        );
+        // Distinct vertices can legitimately be much closer to each other than
+        // the approximation tolerance, so we need a much smaller value for
+        // welding. Otherwise we'd risk collapsing fine detail.
+        let weld_tolerance =
+            Tolerance::from_scalar(tolerance.inner() / Scalar::from_f64(1000.))
+                .unwrap();
+        let (mesh, weld_report) = debug_span!("weld_mesh")
+            .in_scope(|| weld_mesh(&mesh, weld_tolerance));
+        debug!(?weld_report, "Welded triangle mesh");
+
+        // Orientation is propagated across shared edges, so this also needs to
+        // happen after welding. A mesh that can't be oriented is left as-is.
+        let (mesh, orientation) =
+            match debug_span!("orient_mesh").in_scope(|| orient_mesh(&mesh)) {
+                Ok((oriented, orient_report)) => {
+                    debug!(?orient_report, "Oriented triangle mesh");
+                    (oriented, Ok(orient_report))
+                }
+                Err(err) => (mesh, Err(err)),
+            };
+
+        // Texturing needs to know which vertices are shared with neighboring
+        // faces, and which way they face. So it has to happen after welding
+        // and orientation.
+        let mesh = if textures.is_empty() {
+            mesh
+        } else {
+            debug_span!("texture_mesh")
+                .in_scope(|| texture_mesh(&mesh, &textures))
+        };
+
+        // Decimation needs to happen after welding, as it can only collapse
+        // edges that are actually shared between triangles.
+        let mesh = match self.decimation {
+            Some(target) => decimate_mesh(&mesh, target, progress)?,
+            None => mesh,
+        };
+
+        let mesh = if self.deterministic {
+            debug_span!("canonicalize_mesh")
+                .in_scope(|| canonicalize_mesh(&mesh))
+        } else {
+            mesh
+        };
+
+        // The analyses need to happen last, as their results refer to
+        // triangles of the final mesh.
+        let thin_walls = match self.min_wall_thickness {
+            Some(threshold) => {
+                let thin_walls = debug_span!("find_thin_walls")
+                    .in_scope(|| find_thin_walls(&mesh, threshold, tolerance));
+                highlight_thin_walls(&mesh, &thin_walls, &mut debug_info);
+                thin_walls
+            }
+            None => Vec::new(),
+        };
+        let draft = self.draft.map(|check| {
+            let draft = debug_span!("analyze_draft")
+                .in_scope(|| analyze_draft(&mesh, check));
+            highlight_draft(&mesh, &draft, &mut debug_info);
+            draft
+        });
 
-        ProcessedShape {
+        Ok(ProcessedShape {
             aabb,
+            topology,
+            assertions,
+            tolerance,
             mesh,
+            weld_report,
+            orientation,
+            thin_walls,
+            draft,
+            face_attributes,
             debug_info,
-        }
+        })
     }
 }
 
 /// A processed shape
 ///
-/// Created by [`ShapeProcessor::process`].
+/// Created by [`ShapeProcessor::process`], or from an existing triangle mesh
+/// by [`ProcessedShape::from_mesh`].
 pub struct ProcessedShape {
     /// The axis-aligned bounding box of the shape
     pub aabb: Aabb<3>,
 
+    /// The kind of object the shape is meant to be
+    ///
+    /// Determines whether the triangle mesh is expected to be watertight.
+    pub topology: Topology,
+
+    /// The assertions that the model attached to the shape
+    ///
+    /// Please refer to [`assertions::check`] for checking them.
+    ///
+    /// [`assertions::check`]: crate::assertions::check
+    pub assertions: Vec<fj::Assertion>,
+
+    /// The tolerance that the triangle mesh was created with
+    ///
+    /// This is either the tolerance configured in [`ShapeProcessor`], or the
+    /// default. Any part of it that is relative to the size of the shape has
+    /// been resolved, so [`Tolerance::inner`] can be called.
+    pub tolerance: Tolerance,
+
     /// The triangle mesh that approximates the original shape
     pub mesh: Mesh<Point<3>>,
 
+    /// Statistics about the welding of the triangle mesh
+    ///
+    /// Can be used to determine whether the mesh is watertight.
+    pub weld_report: WeldReport,
+
+    /// Statistics about the orientation of the triangle mesh
+    ///
+    /// An error, if the triangles can't be oriented consistently.
+    pub orientation: Result<OrientReport, NonOrientable>,
+
+    /// The regions of the triangle mesh where the wall is too thin
+    ///
+    /// Always empty, unless [`ShapeProcessor::min_wall_thickness`] is set.
+    pub thin_walls: Vec<ThinWall>,
+
+    /// The draft of the triangle mesh, relative to the pull direction
+    ///
+    /// Always `None`, unless [`ShapeProcessor::draft`] is set.
+    pub draft: Option<DraftAnalysis>,
+
+    /// The attributes of the faces that the triangle mesh was created from
+    ///
+    /// Indexed by the [`FaceId`]s of the mesh's triangles.
+    pub face_attributes: Vec<Attributes>,
+
     /// The debug info generated while processing the shape
     pub debug_info: DebugInfo,
 }
+
+impl ProcessedShape {
+    /// Access the attributes of the face that triangles were created from
+    ///
+    /// Returns `None`, if the face is unknown.
+    pub fn attributes_of(&self, face: FaceId) -> Option<&Attributes> {
+        self.face_attributes.get(face.0 as usize)
+    }
+
+    /// Find the faces that originate from the given operations
+    ///
+    /// `path` is matched against the start of each face's [`Provenance`], so
+    /// `[Step::with_input("Difference2d", 1)]` selects the faces that come
+    /// from the sketch that was subtracted, for example. Faces with unknown
+    /// provenance never match.
+    ///
+    /// [`Provenance`]: fj_kernel::shape::Provenance
+    pub fn faces_from(&self, path: &[Step]) -> Vec<FaceId> {
+        self.face_attributes
+            .iter()
+            .enumerate()
+            .filter(|(_, attributes)| {
+                attributes
+                    .provenance()
+                    .map_or(false, |provenance| provenance.starts_with(path))
+            })
+            .map(|(i, _)| FaceId(i as u64))
+            .collect()
+    }
+
+    /// Divide the faces into the parts of an assembly
+    ///
+    /// Groups are treated as assemblies, so each member of a group is a part.
+    /// Faces belong to the innermost group member they come from. All faces
+    /// that are not part of any group, including those with unknown
+    /// provenance, make up a single part. Parts are returned in the order their
+    /// first face appears.
+    pub fn parts(&self) -> Vec<Part> {
+        let mut parts: Vec<Part> = Vec::new();
+
+        for (i, attributes) in self.face_attributes.iter().enumerate() {
+            let steps = attributes.provenance().map_or(&[][..], |p| p.steps());
+            let end = steps
+                .iter()
+                .rposition(|step| step.operation == "Group")
+                .map_or(0, |i| i + 1);
+            let path = &steps[..end];
+
+            let face = FaceId(i as u64);
+            match parts.iter_mut().find(|part| part.path == path) {
+                Some(part) => part.faces.push(face),
+                None => parts.push(Part {
+                    path: path.to_vec(),
+                    faces: vec![face],
+                }),
+            }
+        }
+
+        parts
+    }
+
+    /// Create a processed shape from an existing triangle mesh
+    ///
+    /// This is useful for displaying meshes that weren't created by a
+    /// [`ShapeProcessor`], for example because they were loaded from a file.
+    /// The mesh is taken as-is, so the reports are empty, and there's no
+    /// debug info. It is assumed to be a solid without assertions.
+    pub fn from_mesh(mesh: Mesh<Point<3>>) -> Self {
+        let aabb = Aabb::<3>::from_points(mesh.vertices());
+
+        Self {
+            aabb,
+            topology: Topology::Solid,
+            assertions: Vec::new(),
+            tolerance: resolve_tolerance(Tolerance::default(), &aabb),
+            mesh,
+            weld_report: WeldReport::default(),
+            orientation: Ok(OrientReport::default()),
+            thin_walls: Vec::new(),
+            draft: None,
+            face_attributes: Vec::new(),
+            debug_info: DebugInfo::new(),
+        }
+    }
+}
+
+/// A part of an assembly
+///
+/// Please refer to [`ProcessedShape::parts`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Part {
+    /// The operations that lead to the part, up to the innermost group
+    ///
+    /// Empty for the faces that are not part of any group.
+    pub path: Vec<Step>,
+
+    /// The faces that make up the part
+    pub faces: Vec<FaceId>,
+}
+
+/// Color the triangles of faces that have a material, like their material
+fn apply_material_colors(
+    mesh: &Mesh<Point<3>>,
+    face_attributes: &[Attributes],
+) -> Mesh<Point<3>> {
+    let mut colored = Mesh::new();
+
+    for triangle in mesh.triangles() {
+        let material = triangle
+            .face
+            .and_then(|face| face_attributes.get(face.0 as usize))
+            .and_then(|attributes| attributes.material());
+        let color = material.map_or(triangle.color, |material| material.color);
+
+        colored.push_triangle_with_face(triangle.points, color, triangle.face);
+    }
+
+    colored
+}
+
+/// Outline the triangles of each thin wall, and label it with its thickness
+fn highlight_thin_walls(
+    mesh: &Mesh<Point<3>>,
+    thin_walls: &[ThinWall],
+    debug_info: &mut DebugInfo,
+) {
+    let triangles: Vec<_> = mesh.triangles().collect();
+
+    for thin_wall in thin_walls {
+        for &i in &thin_wall.triangles {
+            debug_info.polyline(
+                DebugCategory::Analysis,
+                triangles[i].points,
+                true,
+            );
+        }
+
+        debug_info.text(
+            DebugCategory::Analysis,
+            thin_wall.position,
+            format!("{} thick", thin_wall.thickness),
+        );
+    }
+}
+
+/// Outline the faces without positive draft, and label them with their draft
+fn highlight_draft(
+    mesh: &Mesh<Point<3>>,
+    draft: &DraftAnalysis,
+    debug_info: &mut DebugInfo,
+) {
+    for (&face, &face_draft) in &draft.faces {
+        let label = match face_draft {
+            Draft::Positive => continue,
+            Draft::Vertical => "no draft",
+            Draft::Negative => "undercut",
+        };
+
+        let triangles: Vec<_> = mesh.triangles_of_face(face).collect();
+        for triangle in &triangles {
+            debug_info.polyline(DebugCategory::Analysis, triangle.points, true);
+        }
+
+        let points = triangles.iter().flat_map(|triangle| triangle.points);
+        let center = Aabb::<3>::from_points(points).center();
+        debug_info.text(DebugCategory::Analysis, center, label);
+    }
+}
+
+/// Resolve the part of the tolerance that is relative to the size of the shape
+///
+/// The size is the smallest non-zero extent of the bounding box, so flat
+/// shapes aren't approximated much coarser than their thickness suggests.
+fn resolve_tolerance(tolerance: Tolerance, aabb: &Aabb<3>) -> Tolerance {
+    let min_extent = aabb
+        .size()
+        .components
+        .into_iter()
+        .filter(|&extent| extent > Scalar::ZERO)
+        .min()
+        .unwrap_or(Scalar::ZERO);
+
+    tolerance.for_size(min_extent)
+}
@@ -4,7 +4,7 @@ use fj_kernel::{
 };
 use fj_math::{Aabb, Point};
 
-use super::ToShape;
+use super::{record_step, ToShape};
 
 impl ToShape for fj::Sketch {
     fn to_shape(&self, _: Tolerance, _: &mut DebugInfo) -> Shape {
@@ -22,6 +22,7 @@ impl ToShape for fj::Sketch {
             .build()
             .unwrap();
 
+        record_step(&mut shape, "Sketch", None);
         shape
     }
 
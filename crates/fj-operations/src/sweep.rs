@@ -5,7 +5,7 @@ use fj_kernel::{
 };
 use fj_math::{Aabb, Point, Vector};
 
-use super::ToShape;
+use super::{record_step, ToShape};
 
 impl ToShape for fj::Sweep {
     fn to_shape(
@@ -13,12 +13,15 @@ impl ToShape for fj::Sweep {
         tolerance: Tolerance,
         debug_info: &mut DebugInfo,
     ) -> Shape {
-        sweep_shape(
+        let mut shape = sweep_shape(
             self.shape().to_shape(tolerance, debug_info),
             Vector::from(self.path()),
             tolerance,
             self.shape().color(),
-        )
+        );
+
+        record_step(&mut shape, "Sweep", None);
+        shape
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
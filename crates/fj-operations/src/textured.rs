@@ -0,0 +1,52 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{Pattern, Texture, Tolerance},
+    shape::{Attribute, Shape},
+};
+use fj_math::{Aabb, Scalar, Vector};
+
+use super::{record_step, selection::Selector, ToShape};
+
+impl ToShape for fj::Textured {
+    fn to_shape(
+        &self,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Shape {
+        let mut shape = self.shape.to_shape(tolerance, debug_info);
+
+        let faces = Selector::new(&shape, tolerance).faces(&self.faces);
+        let texture = texture(&self.texture);
+        for face in faces {
+            shape.insert_attribute(&face, Attribute::Texture(texture));
+        }
+
+        record_step(&mut shape, "Textured", None);
+        shape
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // The texture can reach out of the shape, but no further than its
+        // amplitude.
+        let aabb = self.shape.bounding_volume();
+        let margin = Vector::from([self.texture.amplitude.abs(); 3]);
+
+        Aabb {
+            min: aabb.min - margin,
+            max: aabb.max + margin,
+        }
+    }
+}
+
+fn texture(texture: &fj::Texture) -> Texture {
+    let pattern = match texture.pattern {
+        fj::Pattern::Knurl => Pattern::Knurl,
+        fj::Pattern::Ripple => Pattern::Ripple,
+    };
+
+    Texture {
+        pattern,
+        amplitude: Scalar::from_f64(texture.amplitude),
+        pitch: Scalar::from_f64(texture.pitch),
+    }
+}
@@ -1,8 +1,11 @@
 use fj_interop::debug::DebugInfo;
-use fj_kernel::{algorithms::Tolerance, shape::Shape};
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Attribute, Shape},
+};
 use fj_math::{Aabb, Transform, Vector};
 
-use super::ToShape;
+use super::{record_step, ToShape};
 
 impl ToShape for fj::Transform {
     fn to_shape(
@@ -15,6 +18,15 @@ impl ToShape for fj::Transform {
 
         shape.transform(&transform);
 
+        // Explosions are directions, so they only follow the rotation.
+        for face in shape.faces() {
+            if let Some(direction) = face.attributes().explosion() {
+                let direction = transform.transform_vector(&direction);
+                shape.insert_attribute(&face, Attribute::Explosion(direction));
+            }
+        }
+
+        record_step(&mut shape, "Transform", None);
         shape
     }
 
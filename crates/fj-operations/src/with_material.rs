@@ -0,0 +1,44 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Attribute, Material, Shape},
+};
+use fj_math::{Aabb, Scalar};
+
+use super::{record_step, ToShape};
+
+impl ToShape for fj::WithMaterial {
+    fn to_shape(
+        &self,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Shape {
+        let mut shape = self.shape.to_shape(tolerance, debug_info);
+
+        // Parts that have been assigned a material within this shape keep it.
+        let material = material(&self.material);
+        for face in shape.faces() {
+            if face.attributes().material().is_none() {
+                let material = Attribute::Material(material.clone());
+                shape.insert_attribute(&face, material);
+            }
+        }
+
+        record_step(&mut shape, "WithMaterial", None);
+        shape
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.shape.bounding_volume()
+    }
+}
+
+fn material(material: &fj::Material) -> Material {
+    Material {
+        name: material.name.clone(),
+        density: Scalar::from_f64(material.density),
+        color: material.color,
+        metallic: Scalar::from_f64(material.metallic),
+        roughness: Scalar::from_f64(material.roughness),
+    }
+}
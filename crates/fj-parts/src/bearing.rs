@@ -0,0 +1,113 @@
+use fj::{Group, Shape, Shape3d};
+
+use crate::washer::ring;
+
+/// A deep groove ball bearing, as in ISO 15
+///
+/// The bearing sits on top of the xy-plane, centered on the z-axis. It is
+/// modeled as its inner and outer ring, without the balls, which is enough to
+/// show its envelope in an assembly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bearing {
+    bore: f64,
+    outer_diameter: f64,
+    width: f64,
+}
+
+impl Bearing {
+    /// Create a deep groove ball bearing from its designation, like "608"
+    ///
+    /// Returns `None`, if the designation is not one of the known sizes.
+    pub fn deep_groove(designation: &str) -> Option<Self> {
+        let &(_, bore, outer_diameter, width) =
+            DEEP_GROOVE.iter().find(|size| size.0 == designation)?;
+
+        Some(Self {
+            bore,
+            outer_diameter,
+            width,
+        })
+    }
+
+    /// Access the diameter of the bore
+    pub fn bore(&self) -> f64 {
+        self.bore
+    }
+
+    /// Access the outer diameter
+    pub fn outer_diameter(&self) -> f64 {
+        self.outer_diameter
+    }
+
+    /// Access the width
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Create the shape of the bearing
+    pub fn to_group(&self) -> Group {
+        // Each ring takes up about a third of the space between bore and
+        // outside, which leaves a visible gap where the balls are.
+        let wall = (self.outer_diameter - self.bore) / 6.;
+
+        let inner = ring(self.bore, self.bore + wall * 2., self.width);
+        let outer = ring(
+            self.outer_diameter - wall * 2.,
+            self.outer_diameter,
+            self.width,
+        );
+
+        Group {
+            a: inner.into(),
+            b: outer.into(),
+        }
+    }
+}
+
+impl From<Bearing> for Shape {
+    fn from(bearing: Bearing) -> Self {
+        bearing.to_group().into()
+    }
+}
+
+impl From<Bearing> for Shape3d {
+    fn from(bearing: Bearing) -> Self {
+        bearing.to_group().into()
+    }
+}
+
+/// Dimensions of ISO 15 deep groove ball bearings
+///
+/// Designation, bore, outer diameter, and width.
+const DEEP_GROOVE: &[(&str, f64, f64, f64)] = &[
+    ("623", 3., 10., 4.),
+    ("624", 4., 13., 5.),
+    ("625", 5., 16., 5.),
+    ("626", 6., 19., 6.),
+    ("608", 8., 22., 7.),
+    ("6000", 10., 26., 8.),
+    ("6001", 12., 28., 8.),
+    ("6002", 15., 32., 9.),
+    ("6003", 17., 35., 10.),
+    ("6004", 20., 42., 12.),
+    ("6200", 10., 30., 9.),
+    ("6201", 12., 32., 10.),
+    ("6202", 15., 35., 11.),
+    ("6203", 17., 40., 12.),
+    ("6204", 20., 47., 14.),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::Bearing;
+
+    #[test]
+    fn lookup_by_designation() {
+        let bearing = Bearing::deep_groove("608").unwrap();
+
+        assert_eq!(bearing.bore(), 8.);
+        assert_eq!(bearing.outer_diameter(), 22.);
+        assert_eq!(bearing.width(), 7.);
+        assert_eq!(Bearing::deep_groove("608ZZ"), None);
+    }
+}
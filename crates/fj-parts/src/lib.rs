@@ -0,0 +1,37 @@
+//! # Fornjot Standard Parts
+//!
+//! This library is part of the [Fornjot] ecosystem. Fornjot is an open-source,
+//! code-first CAD application; and collection of libraries that make up the CAD
+//! application, but can be used independently.
+//!
+//! This library provides standard hardware, like screws, nuts, washers, and
+//! bearings, as [`fj`] shapes. Models can include them in assemblies, without
+//! having to model them first. Parts are looked up by their nominal size, and
+//! have the dimensions of the respective standard:
+//!
+//! ``` rust
+//! use fj_parts::{Bearing, Nut, Screw, Washer};
+//!
+//! let screw = Screw::socket_head(3., 10.).unwrap();
+//! let washer = Washer::plain(3.).unwrap();
+//! let nut = Nut::hex(3.).unwrap();
+//! let bearing = Bearing::deep_groove("608").unwrap();
+//!
+//! let shape: fj::Shape = screw.into();
+//! ```
+//!
+//! Screws and nuts have ISO metric threads, and are built as [`fj::Implicit`]
+//! shapes. Details that don't matter for an assembly, like chamfers, are left
+//! out.
+//!
+//! [Fornjot]: https://www.fornjot.app/
+
+#![deny(missing_docs)]
+
+mod bearing;
+mod nut;
+mod screw;
+mod sdf;
+mod washer;
+
+pub use self::{bearing::*, nut::*, screw::*, washer::*};
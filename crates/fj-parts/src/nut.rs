@@ -0,0 +1,124 @@
+use fj::{Implicit, Shape, Shape3d, Thread};
+
+use crate::sdf;
+
+/// A hex nut with an ISO metric coarse thread, as in ISO 4032
+///
+/// The nut sits on top of the xy-plane, centered on the z-axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Nut {
+    thread: Thread,
+    width: f64,
+    height: f64,
+}
+
+impl Nut {
+    /// Create a hex nut
+    ///
+    /// Returns `None`, if the diameter is not one of the standard sizes.
+    pub fn hex(diameter: f64) -> Option<Self> {
+        let &(_, width, height) =
+            HEX_NUT.iter().find(|size| size.0 == diameter)?;
+
+        Some(Self {
+            thread: Thread::metric_coarse(diameter)?,
+            width,
+            height,
+        })
+    }
+
+    /// Set the clearance of the thread
+    ///
+    /// See [`Thread::with_clearance`].
+    pub fn with_clearance(mut self, clearance: f64) -> Self {
+        self.thread = self.thread.with_clearance(clearance);
+        self
+    }
+
+    /// Access the thread of the nut
+    pub fn thread(&self) -> Thread {
+        self.thread
+    }
+
+    /// Access the width of the nut across its flats, which is the key size
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Access the height of the nut
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// Create the shape of the nut
+    pub fn to_implicit(&self) -> Implicit {
+        let width = self.width;
+        let height = self.height;
+
+        // The sleeve is wider than the nut, so only its thread is left after
+        // intersecting it with the hexagon.
+        let sleeve = self.thread.internal(height, width * 2.);
+
+        let radius = width / 3_f64.sqrt();
+        let min = [-radius, -radius, 0.];
+        let max = [radius, radius, height];
+
+        Implicit::from_fn(min, max, move |point| {
+            let hexagon = sdf::hexagon(point, width, 0., height);
+            hexagon.max(sleeve.distance(point))
+        })
+        .with_resolution(self.thread.pitch() / 8.)
+    }
+}
+
+impl From<Nut> for Shape {
+    fn from(nut: Nut) -> Self {
+        nut.to_implicit().into()
+    }
+}
+
+impl From<Nut> for Shape3d {
+    fn from(nut: Nut) -> Self {
+        nut.to_implicit().into()
+    }
+}
+
+/// Dimensions of ISO 4032 hex nuts
+///
+/// Diameter, width, and height.
+const HEX_NUT: &[(f64, f64, f64)] = &[
+    (3., 5.5, 2.4),
+    (4., 7., 3.2),
+    (5., 8., 4.7),
+    (6., 10., 5.2),
+    (8., 13., 6.8),
+    (10., 16., 8.4),
+    (12., 18., 10.8),
+    (16., 24., 14.8),
+    (20., 30., 18.),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::Nut;
+
+    #[test]
+    fn threaded_hole() {
+        let nut = Nut::hex(5.).unwrap();
+        assert_eq!(nut.width(), 8.);
+
+        let implicit = nut.to_implicit();
+        assert!(implicit.distance([0., 0., 2.]) > 0.);
+        assert!(implicit.distance([3.5, 0., 2.]) < 0.);
+
+        // Outside of the flats, but inside the corners of the hexagon.
+        for angle in [0_f64, 60.] {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            assert!(implicit.distance([4.2 * cos, 4.2 * sin, 2.]) > 0.);
+        }
+        for angle in [30_f64, 90.] {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            assert!(implicit.distance([4.2 * cos, 4.2 * sin, 2.]) < 0.);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use fj::{Implicit, Shape, Shape3d, Thread};
+
+use crate::sdf;
+
+/// A screw with an ISO metric coarse thread
+///
+/// The head sits on top of the xy-plane, and the shank extends down from there
+/// along the negative z-axis. The shank is threaded along its whole length.
+///
+/// # Example
+///
+/// ```
+/// use fj_parts::Screw;
+///
+/// // An M4 socket head cap screw, 12 mm long.
+/// let screw = Screw::socket_head(4., 12.).unwrap();
+/// assert_eq!(screw.thread().pitch(), 0.7);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Screw {
+    head: Head,
+    thread: Thread,
+    length: f64,
+}
+
+impl Screw {
+    /// Create a socket head cap screw, as in ISO 4762
+    ///
+    /// `length` is the length of the shank, below the head. Returns `None`, if
+    /// the diameter is not one of the standard sizes.
+    pub fn socket_head(diameter: f64, length: f64) -> Option<Self> {
+        let &(_, head_diameter, height, socket_width, socket_depth) =
+            SOCKET_HEAD.iter().find(|size| size.0 == diameter)?;
+
+        Some(Self {
+            head: Head::Socket {
+                diameter: head_diameter,
+                height,
+                socket_width,
+                socket_depth,
+            },
+            thread: Thread::metric_coarse(diameter)?,
+            length,
+        })
+    }
+
+    /// Create a hex head screw, as in ISO 4017
+    ///
+    /// `length` is the length of the shank, below the head. Returns `None`, if
+    /// the diameter is not one of the standard sizes.
+    pub fn hex_head(diameter: f64, length: f64) -> Option<Self> {
+        let &(_, width, height) =
+            HEX_HEAD.iter().find(|size| size.0 == diameter)?;
+
+        Some(Self {
+            head: Head::Hex { width, height },
+            thread: Thread::metric_coarse(diameter)?,
+            length,
+        })
+    }
+
+    /// Set the clearance of the thread
+    ///
+    /// See [`Thread::with_clearance`].
+    pub fn with_clearance(mut self, clearance: f64) -> Self {
+        self.thread = self.thread.with_clearance(clearance);
+        self
+    }
+
+    /// Access the head of the screw
+    pub fn head(&self) -> Head {
+        self.head
+    }
+
+    /// Access the thread of the screw
+    pub fn thread(&self) -> Thread {
+        self.thread
+    }
+
+    /// Access the length of the shank, below the head
+    pub fn length(&self) -> f64 {
+        self.length
+    }
+
+    /// Create the shape of the screw
+    pub fn to_implicit(&self) -> Implicit {
+        let head = self.head;
+        let length = self.length;
+        let shank = self.thread.external(length);
+
+        let radius = head.radius();
+        let min = [-radius, -radius, -length];
+        let max = [radius, radius, head.height()];
+
+        Implicit::from_fn(min, max, move |point| {
+            let [x, y, z] = point;
+            let shank = shank.distance([x, y, z + length]);
+            shank.min(head.distance(point))
+        })
+        .with_resolution(self.thread.pitch() / 8.)
+    }
+}
+
+impl From<Screw> for Shape {
+    fn from(screw: Screw) -> Self {
+        screw.to_implicit().into()
+    }
+}
+
+impl From<Screw> for Shape3d {
+    fn from(screw: Screw) -> Self {
+        screw.to_implicit().into()
+    }
+}
+
+/// The head of a [`Screw`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Head {
+    /// A round head with a hexagon socket
+    Socket {
+        /// The diameter of the head
+        diameter: f64,
+
+        /// The height of the head
+        height: f64,
+
+        /// The width of the socket across its flats, which is the key size
+        socket_width: f64,
+
+        /// The depth of the socket
+        socket_depth: f64,
+    },
+
+    /// A hexagonal head
+    Hex {
+        /// The width of the head across its flats, which is the key size
+        width: f64,
+
+        /// The height of the head
+        height: f64,
+    },
+}
+
+impl Head {
+    /// Access the height of the head
+    pub fn height(&self) -> f64 {
+        match *self {
+            Self::Socket { height, .. } | Self::Hex { height, .. } => height,
+        }
+    }
+
+    /// The radius of the smallest cylinder that contains the head
+    fn radius(&self) -> f64 {
+        match *self {
+            Self::Socket { diameter, .. } => diameter / 2.,
+            // The corners of a hexagon are further out than its flats.
+            Self::Hex { width, .. } => width / 3_f64.sqrt(),
+        }
+    }
+
+    fn distance(&self, point: [f64; 3]) -> f64 {
+        match *self {
+            Self::Socket {
+                diameter,
+                height,
+                socket_width,
+                socket_depth,
+            } => {
+                let head = sdf::cylinder(point, diameter / 2., 0., height);
+                let socket = sdf::hexagon(
+                    point,
+                    socket_width,
+                    height - socket_depth,
+                    height + 1.,
+                );
+                head.max(-socket)
+            }
+            Self::Hex { width, height } => {
+                sdf::hexagon(point, width, 0., height)
+            }
+        }
+    }
+}
+
+/// Dimensions of ISO 4762 socket head cap screws
+///
+/// Diameter, head diameter, head height, socket width, and socket depth.
+const SOCKET_HEAD: &[(f64, f64, f64, f64, f64)] = &[
+    (2., 3.8, 2., 1.5, 1.),
+    (2.5, 4.5, 2.5, 2., 1.1),
+    (3., 5.5, 3., 2.5, 1.3),
+    (4., 7., 4., 3., 2.),
+    (5., 8.5, 5., 4., 2.5),
+    (6., 10., 6., 5., 3.),
+    (8., 13., 8., 6., 4.),
+    (10., 16., 10., 8., 5.),
+    (12., 18., 12., 10., 6.),
+    (16., 24., 16., 14., 8.),
+    (20., 30., 20., 17., 10.),
+];
+
+/// Dimensions of ISO 4017 hex head screws
+///
+/// Diameter, head width, and head height.
+const HEX_HEAD: &[(f64, f64, f64)] = &[
+    (3., 5.5, 2.),
+    (4., 7., 2.8),
+    (5., 8., 3.5),
+    (6., 10., 4.),
+    (8., 13., 5.3),
+    (10., 16., 6.4),
+    (12., 18., 7.5),
+    (16., 24., 10.),
+    (20., 30., 12.5),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{Head, Screw};
+
+    #[test]
+    fn standard_dimensions() {
+        let screw = Screw::socket_head(3., 10.).unwrap();
+        assert_eq!(screw.head().height(), 3.);
+        assert_eq!(screw.thread().pitch(), 0.5);
+
+        let implicit = screw.to_implicit();
+        assert_eq!(implicit.min(), [-2.75, -2.75, -10.]);
+        assert_eq!(implicit.max(), [2.75, 2.75, 3.]);
+
+        // Head and shank are solid, the socket isn't.
+        assert!(implicit.distance([2., 0., 1.]) < 0.);
+        assert!(implicit.distance([0., 0., -5.]) < 0.);
+        assert!(implicit.distance([0., 0., 2.5]) > 0.);
+        assert!(implicit.distance([2., 0., -5.]) > 0.);
+
+        let screw = Screw::hex_head(8., 20.).unwrap();
+        assert_eq!(
+            screw.head(),
+            Head::Hex {
+                width: 13.,
+                height: 5.3,
+            }
+        );
+
+        assert_eq!(Screw::socket_head(7., 10.), None);
+    }
+}
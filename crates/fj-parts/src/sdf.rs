@@ -0,0 +1,31 @@
+//! Signed distance functions of simple shapes
+//!
+//! All shapes are centered on the z-axis, and extend from `bottom` to `top`.
+
+/// The signed distance of a point from a cylinder
+pub fn cylinder(
+    [x, y, z]: [f64; 3],
+    radius: f64,
+    bottom: f64,
+    top: f64,
+) -> f64 {
+    ((x * x + y * y).sqrt() - radius)
+        .max(bottom - z)
+        .max(z - top)
+}
+
+/// The signed distance of a point from a hexagonal prism
+///
+/// `width` is the distance between opposite flats, which are parallel to the
+/// y-axis.
+pub fn hexagon([x, y, z]: [f64; 3], width: f64, bottom: f64, top: f64) -> f64 {
+    let flats = [0_f64, 60., 120.]
+        .iter()
+        .map(|angle| {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            (x * cos + y * sin).abs()
+        })
+        .fold(f64::MIN, f64::max);
+
+    (flats - width / 2.).max(bottom - z).max(z - top)
+}
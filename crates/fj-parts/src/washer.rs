@@ -0,0 +1,87 @@
+use fj::{Circle, Difference2d, Shape, Shape3d, Sweep};
+
+/// A plain washer, as in ISO 7089
+///
+/// The washer sits on top of the xy-plane, centered on the z-axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Washer {
+    inner_diameter: f64,
+    outer_diameter: f64,
+    thickness: f64,
+}
+
+impl Washer {
+    /// Create a plain washer, for a screw of the given diameter
+    ///
+    /// Returns `None`, if the diameter is not one of the standard sizes.
+    pub fn plain(diameter: f64) -> Option<Self> {
+        let &(_, inner_diameter, outer_diameter, thickness) =
+            PLAIN_WASHER.iter().find(|size| size.0 == diameter)?;
+
+        Some(Self {
+            inner_diameter,
+            outer_diameter,
+            thickness,
+        })
+    }
+
+    /// Access the diameter of the hole
+    pub fn inner_diameter(&self) -> f64 {
+        self.inner_diameter
+    }
+
+    /// Access the outer diameter
+    pub fn outer_diameter(&self) -> f64 {
+        self.outer_diameter
+    }
+
+    /// Access the thickness
+    pub fn thickness(&self) -> f64 {
+        self.thickness
+    }
+
+    /// Create the shape of the washer
+    pub fn to_sweep(&self) -> Sweep {
+        ring(self.inner_diameter, self.outer_diameter, self.thickness)
+    }
+}
+
+impl From<Washer> for Shape {
+    fn from(washer: Washer) -> Self {
+        washer.to_sweep().into()
+    }
+}
+
+impl From<Washer> for Shape3d {
+    fn from(washer: Washer) -> Self {
+        washer.to_sweep().into()
+    }
+}
+
+/// Create a ring on top of the xy-plane, centered on the z-axis
+pub(crate) fn ring(
+    inner_diameter: f64,
+    outer_diameter: f64,
+    height: f64,
+) -> Sweep {
+    let outer = Circle::from_radius(outer_diameter / 2.);
+    let inner = Circle::from_radius(inner_diameter / 2.);
+    let ring = Difference2d::from_shapes([outer.into(), inner.into()]);
+
+    Sweep::from_path(ring.into(), [0., 0., height])
+}
+
+/// Dimensions of ISO 7089 plain washers
+///
+/// Screw diameter, inner diameter, outer diameter, and thickness.
+const PLAIN_WASHER: &[(f64, f64, f64, f64)] = &[
+    (3., 3.2, 7., 0.5),
+    (4., 4.3, 9., 0.8),
+    (5., 5.3, 10., 1.),
+    (6., 6.4, 12., 1.6),
+    (8., 8.4, 16., 1.6),
+    (10., 10.5, 20., 2.),
+    (12., 13., 24., 2.5),
+    (16., 17., 30., 3.),
+    (20., 21., 37., 3.),
+];
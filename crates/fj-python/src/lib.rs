@@ -0,0 +1,358 @@
+//! # Fornjot Python Bindings
+//!
+//! This library is part of the [Fornjot] ecosystem. Fornjot is an open-source,
+//! code-first CAD application; and collection of libraries that make up the CAD
+//! application, but can be used independently.
+//!
+//! This library makes the operations of the [`fj`] crate available to Python,
+//! so models can be defined without knowing Rust. Shapes defined in Python are
+//! processed by the same code as models written in Rust.
+//!
+//! ``` python
+//! import fornjot
+//!
+//! outer = fornjot.circle(50.0)
+//! inner = fornjot.circle(25.0)
+//! spacer = fornjot.sweep(fornjot.difference(outer, inner), (0.0, 0.0, 10.0))
+//!
+//! fornjot.export(spacer, "spacer.3mf")
+//! ```
+//!
+//! Models can also be written as Python files, which the Fornjot application
+//! loads like any other model. See [`model`].
+//!
+//! [Fornjot]: https://www.fornjot.app/
+//! [`fj`]: https://crates.io/crates/fj
+
+#![deny(missing_docs)]
+
+pub mod model;
+
+use std::path::PathBuf;
+
+use fj_kernel::algorithms::Tolerance;
+use fj_math::Scalar;
+use fj_operations::shape_processor::{ProcessedShape, ShapeProcessor};
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+};
+
+/// A 2-dimensional shape
+#[pyclass]
+#[derive(Clone)]
+pub struct Shape2d {
+    inner: fj::Shape2d,
+}
+
+#[pymethods]
+impl Shape2d {
+    /// Return a copy of the shape, with the given RGBA rendering color
+    fn with_color(&self, color: (u8, u8, u8, u8)) -> Self {
+        let (r, g, b, a) = color;
+        let inner = self.inner.clone().with_color([r, g, b, a]);
+
+        Self { inner }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.inner)
+    }
+}
+
+/// A 3-dimensional shape
+#[pyclass]
+#[derive(Clone)]
+pub struct Shape3d {
+    inner: fj::Shape3d,
+}
+
+#[pymethods]
+impl Shape3d {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.inner)
+    }
+}
+
+/// Create a circle with the given radius
+#[pyfunction]
+fn circle(radius: f64) -> PyResult<Shape2d> {
+    if !radius.is_finite() || radius <= 0. {
+        return Err(PyValueError::new_err("Radius must be positive"));
+    }
+
+    let inner = fj::Circle::from_radius(radius).into();
+    Ok(Shape2d { inner })
+}
+
+/// Create a sketch from a list of (x, y) points
+///
+/// The points are connected by straight lines, with the last point being
+/// connected to the first.
+#[pyfunction]
+fn sketch(points: Vec<(f64, f64)>) -> PyResult<Shape2d> {
+    if points.len() < 3 {
+        return Err(PyValueError::new_err("A sketch needs at least 3 points"));
+    }
+
+    let points = points.into_iter().map(|(x, y)| [x, y]).collect();
+    let inner = fj::Sketch::from_points(points).into();
+    Ok(Shape2d { inner })
+}
+
+/// Subtract one 2-dimensional shape from another
+#[pyfunction]
+fn difference(a: Shape2d, b: Shape2d) -> Shape2d {
+    let inner = fj::Difference2d::from_shapes([a.inner, b.inner]).into();
+    Shape2d { inner }
+}
+
+/// Compute the Minkowski sum of two convex 2-dimensional shapes
+#[pyfunction]
+fn minkowski(a: Shape2d, b: Shape2d) -> PyResult<Shape2d> {
+    let inner = fj::Minkowski2d::from_shapes([a.inner, b.inner])
+        .map_err(|err| PyValueError::new_err(err.to_string()))?
+        .into();
+    Ok(Shape2d { inner })
+}
+
+/// Sweep a 2-dimensional shape along a straight (x, y, z) path
+#[pyfunction]
+fn sweep(shape: Shape2d, path: (f64, f64, f64)) -> Shape3d {
+    let (x, y, z) = path;
+
+    let inner = fj::Sweep::from_path(shape.inner, [x, y, z]).into();
+    Shape3d { inner }
+}
+
+/// Group two disjoint 3-dimensional shapes
+#[pyfunction]
+fn group(a: Shape3d, b: Shape3d) -> Shape3d {
+    let inner = fj::Group {
+        a: a.inner,
+        b: b.inner,
+    }
+    .into();
+    Shape3d { inner }
+}
+
+/// Grow a 3-dimensional shape by a sphere, rounding its edges and corners
+///
+/// The result is reconstructed from samples of the distance to the shape. If
+/// no resolution is given, the distance between the samples is 1/64 of the size
+/// of the result.
+#[pyfunction]
+fn rounded(
+    shape: Shape3d,
+    radius: f64,
+    resolution: Option<f64>,
+) -> PyResult<Shape3d> {
+    if !radius.is_finite() || radius <= 0. {
+        return Err(PyValueError::new_err("Radius must be positive"));
+    }
+
+    let mut inner = fj::Rounded::from_shape(shape.inner, radius);
+    if let Some(resolution) = resolution {
+        if !resolution.is_finite() || resolution <= 0. {
+            return Err(PyValueError::new_err("Resolution must be positive"));
+        }
+        inner = inner.with_resolution(resolution);
+    }
+
+    Ok(Shape3d {
+        inner: inner.into(),
+    })
+}
+
+/// Rotate a 3-dimensional shape around an axis, then translate it
+///
+/// The angle is given in radians.
+#[pyfunction]
+fn transform(
+    shape: Shape3d,
+    axis: (f64, f64, f64),
+    angle: f64,
+    offset: (f64, f64, f64),
+) -> Shape3d {
+    let inner = fj::Transform {
+        shape: shape.inner,
+        axis: [axis.0, axis.1, axis.2],
+        angle,
+        offset: [offset.0, offset.1, offset.2],
+    }
+    .into();
+    Shape3d { inner }
+}
+
+/// Compute the triangle mesh of a shape
+///
+/// Returns a list of (x, y, z) vertices, and a list of triangles, each of
+/// which is a tuple of three vertex indices. If no tolerance is given, one is
+/// computed from the size of the shape.
+#[pyfunction]
+fn mesh(
+    shape: Shape3d,
+    tolerance: Option<f64>,
+) -> PyResult<(Vec<(f64, f64, f64)>, Vec<(u32, u32, u32)>)> {
+    let shape = process(shape, tolerance)?;
+
+    let vertices = shape
+        .mesh
+        .vertices()
+        .map(|vertex| {
+            let [x, y, z] = vertex.coords.components.map(Scalar::into_f64);
+            (x, y, z)
+        })
+        .collect();
+    let indices: Vec<_> = shape.mesh.indices().collect();
+    let triangles = indices
+        .chunks(3)
+        .map(|triangle| (triangle[0], triangle[1], triangle[2]))
+        .collect();
+
+    Ok((vertices, triangles))
+}
+
+/// Export a shape to a 3MF file
+///
+/// If no tolerance is given, one is computed from the size of the shape.
+#[pyfunction]
+fn export(
+    shape: Shape3d,
+    path: PathBuf,
+    tolerance: Option<f64>,
+) -> PyResult<()> {
+    let shape = process(shape, tolerance)?;
+
+    fj_export::export(&shape.mesh, &path)
+        .map_err(|err| PyIOError::new_err(err.to_string()))
+}
+
+fn process(shape: Shape3d, tolerance: Option<f64>) -> PyResult<ProcessedShape> {
+    let tolerance = tolerance
+        .map(|tolerance| {
+            // `Scalar` can't represent NaN, so this needs to be checked before
+            // the tolerance is constructed.
+            if !tolerance.is_finite() {
+                return Err(PyValueError::new_err("Tolerance must be finite"));
+            }
+
+            Tolerance::from_scalar(tolerance)
+                .map_err(|err| PyValueError::new_err(err.to_string()))
+        })
+        .transpose()?;
+
+    let shape_processor = ShapeProcessor {
+        tolerance,
+        decimation: None,
+        deterministic: false,
+        min_wall_thickness: None,
+        draft: None,
+    };
+
+    // Panics while processing are turned into Python exceptions by PyO3.
+    Ok(shape_processor.process(&shape.inner.into()))
+}
+
+/// The `fornjot` Python module
+#[pymodule]
+fn fornjot(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Shape2d>()?;
+    m.add_class::<Shape3d>()?;
+    m.add_class::<model::Parameters>()?;
+
+    m.add_function(wrap_pyfunction!(circle, m)?)?;
+    m.add_function(wrap_pyfunction!(sketch, m)?)?;
+    m.add_function(wrap_pyfunction!(difference, m)?)?;
+    m.add_function(wrap_pyfunction!(minkowski, m)?)?;
+    m.add_function(wrap_pyfunction!(sweep, m)?)?;
+    m.add_function(wrap_pyfunction!(group, m)?)?;
+    m.add_function(wrap_pyfunction!(rounded, m)?)?;
+    m.add_function(wrap_pyfunction!(transform, m)?)?;
+    m.add_function(wrap_pyfunction!(mesh, m)?)?;
+    m.add_function(wrap_pyfunction!(export, m)?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::{exceptions::PyValueError, PyErr, Python};
+
+    use super::{
+        circle, mesh, minkowski, process, rounded, sketch, sweep, Shape3d,
+    };
+
+    #[test]
+    fn process_cube() {
+        let shape = process(cube(), Some(0.1)).unwrap();
+        assert!(shape.mesh.triangles().count() > 0);
+
+        // Without a tolerance, one is computed from the size of the shape.
+        let shape = process(cube(), None).unwrap();
+        assert!(shape.mesh.triangles().count() > 0);
+    }
+
+    #[test]
+    fn mesh_cube() {
+        let (vertices, triangles) = mesh(cube(), Some(0.1)).unwrap();
+
+        assert_eq!(triangles.len(), 12);
+        for (a, b, c) in triangles {
+            for index in [a, b, c] {
+                assert!((index as usize) < vertices.len());
+            }
+        }
+        for (x, y, z) in vertices {
+            for component in [x, y, z] {
+                assert!((0. ..=1.).contains(&component));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        for radius in [0., -1., f64::NAN, f64::INFINITY] {
+            assert!(is_value_error(circle(radius).err()));
+        }
+
+        assert!(is_value_error(sketch(vec![(0., 0.), (1., 0.)]).err()));
+
+        for radius in [0., f64::NAN] {
+            assert!(is_value_error(rounded(cube(), radius, None).err()));
+        }
+        assert!(is_value_error(rounded(cube(), 1., Some(0.)).err()));
+
+        for tolerance in [0., -1., f64::NAN] {
+            assert!(is_value_error(process(cube(), Some(tolerance)).err()));
+            assert!(is_value_error(mesh(cube(), Some(tolerance)).err()));
+        }
+
+        let star = sketch(vec![
+            (0., 0.),
+            (2., 1.),
+            (4., 0.),
+            (3., 2.),
+            (4., 4.),
+            (2., 3.),
+            (0., 4.),
+            (1., 2.),
+        ])
+        .unwrap();
+        let circle = circle(1.).unwrap();
+        assert!(is_value_error(minkowski(star, circle).err()));
+    }
+
+    fn cube() -> Shape3d {
+        let square =
+            sketch(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]).unwrap();
+        sweep(square, (0., 0., 1.))
+    }
+
+    fn is_value_error(err: Option<PyErr>) -> bool {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            err.map_or(false, |err| err.is_instance_of::<PyValueError>(py))
+        })
+    }
+}
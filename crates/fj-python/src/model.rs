@@ -0,0 +1,218 @@
+//! Evaluating models written in Python
+//!
+//! This is how the Fornjot application loads Python models. A model is a
+//! Python file that defines a `model` function. That function is called with
+//! the model's parameters, and returns the shape it defines:
+//!
+//! ``` python
+//! import fornjot
+//!
+//! def model(parameters):
+//!     outer = parameters.float_or("outer", 50.0)
+//!     inner = parameters.float_or("inner", 25.0)
+//!
+//!     footprint = fornjot.difference(
+//!         fornjot.circle(outer),
+//!         fornjot.circle(inner),
+//!     )
+//!     return fornjot.sweep(footprint, (0.0, 0.0, 10.0))
+//! ```
+//!
+//! The Python interpreter is embedded into the host, and the `fornjot` module
+//! is made available to the model, without having to be installed.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+
+use crate::{Shape2d, Shape3d};
+
+/// Indicate whether the path points to a Python model
+pub fn is_python_model(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == "py")
+}
+
+/// Evaluate the Python model at the given path, returning its shape
+///
+/// Returns an error message, if the model can't be read, raises an exception,
+/// or doesn't return a shape.
+pub fn evaluate(
+    path: &Path,
+    arguments: &HashMap<String, String>,
+) -> Result<fj::Shape, String> {
+    let (shape, _) = call_model(path, arguments)?;
+    Ok(shape)
+}
+
+/// Find the parameters that the Python model at the given path reads
+///
+/// The model is called without any parameters. Returns their names along with
+/// their defaults, in the order they were read. Parameters that are only read
+/// in branches that aren't taken then are not found.
+pub fn parameters(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let (_, parameters) = call_model(path, &HashMap::new())?;
+    Ok(parameters)
+}
+
+fn call_model(
+    path: &Path,
+    arguments: &HashMap<String, String>,
+) -> Result<(fj::Shape, Vec<(String, String)>), String> {
+    let source = fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let result = || -> PyResult<_> {
+            let modules: &PyDict =
+                py.import("sys")?.getattr("modules")?.downcast()?;
+            if !modules.contains("fornjot")? {
+                let fornjot = PyModule::new(py, "fornjot")?;
+                crate::fornjot(py, fornjot)?;
+                modules.set_item("fornjot", fornjot)?;
+            }
+
+            let file_name = path.display().to_string();
+            let model = PyModule::from_code(py, &source, &file_name, "model")?
+                .getattr("model")?;
+
+            let parameters = Py::new(
+                py,
+                Parameters {
+                    values: arguments.clone(),
+                    read: Vec::new(),
+                },
+            )?;
+            let shape = model.call1((parameters.clone_ref(py),))?;
+
+            let shape = if let Ok(shape) = shape.extract::<Shape3d>() {
+                shape.inner.into()
+            } else if let Ok(shape) = shape.extract::<Shape2d>() {
+                fj::Shape::Shape2d(shape.inner)
+            } else {
+                return Err(PyValueError::new_err(format!(
+                    "`model` must return a shape, not `{}`",
+                    shape.get_type().name()?
+                )));
+            };
+
+            let read = parameters.borrow(py).read.clone();
+            Ok((shape, read))
+        };
+
+        result().map_err(|err| err.to_string())
+    })
+}
+
+/// The parameters that are passed to a Python model
+#[pyclass]
+pub struct Parameters {
+    values: HashMap<String, String>,
+
+    /// The parameters that have been read, with their defaults
+    read: Vec<(String, String)>,
+}
+
+#[pymethods]
+impl Parameters {
+    /// Parse the parameter with the given name as a number
+    ///
+    /// Returns `default`, if the parameter isn't set.
+    fn float_or(&mut self, name: &str, default: f64) -> PyResult<f64> {
+        if !self.read.iter().any(|(read, _)| read == name) {
+            self.read.push((name.to_owned(), format!("{:?}", default)));
+        }
+
+        match self.values.get(name) {
+            Some(value) => value.parse().map_err(|_| {
+                PyValueError::new_err(format!(
+                    "Parameter `{}` is not a number",
+                    name
+                ))
+            }),
+            None => Ok(default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        env, fs,
+        path::PathBuf,
+        process,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    use super::{evaluate, is_python_model, parameters};
+
+    const SPACER: &str = "
+import fornjot
+
+def model(parameters):
+    outer = parameters.float_or('outer', 2.0)
+    inner = parameters.float_or('inner', 1.0)
+
+    footprint = fornjot.difference(
+        fornjot.circle(outer),
+        fornjot.circle(inner),
+    )
+    return fornjot.sweep(footprint, (0.0, 0.0, 1.0))
+";
+
+    #[test]
+    fn evaluate_model() {
+        let path = model_file(SPACER);
+        assert!(is_python_model(&path));
+
+        let arguments = HashMap::from([("outer".to_owned(), "3.0".to_owned())]);
+        let shape = evaluate(&path, &arguments).unwrap();
+        assert!(matches!(shape, fj::Shape::Shape3d(_)));
+
+        let arguments = HashMap::from([("outer".to_owned(), "x".to_owned())]);
+        assert!(evaluate(&path, &arguments).is_err());
+    }
+
+    #[test]
+    fn find_parameters() {
+        let path = model_file(SPACER);
+
+        assert_eq!(
+            parameters(&path).unwrap(),
+            [
+                ("outer".to_owned(), "2.0".to_owned()),
+                ("inner".to_owned(), "1.0".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_models() {
+        let no_model = model_file("import fornjot\n");
+        assert!(evaluate(&no_model, &HashMap::new()).is_err());
+
+        let no_shape = model_file("def model(parameters):\n    return 1\n");
+        assert!(evaluate(&no_shape, &HashMap::new()).is_err());
+
+        let exception = model_file(
+            "import fornjot\n\ndef model(parameters):\n    \
+            return fornjot.circle(-1.0)\n",
+        );
+        assert!(evaluate(&exception, &HashMap::new()).is_err());
+    }
+
+    fn model_file(source: &str) -> PathBuf {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        let path = env::temp_dir().join(format!(
+            "fj-python-model-{}-{}.py",
+            process::id(),
+            id
+        ));
+        fs::write(&path, source).unwrap();
+
+        path
+    }
+}
@@ -1,11 +1,15 @@
 //! Viewer camera module
-use std::f64::consts::FRAC_PI_2;
+use std::{
+    f64::consts::{FRAC_PI_2, FRAC_PI_4},
+    time::Duration,
+};
 
-use fj_interop::mesh::Mesh;
+use fj_interop::mesh::{self, Mesh};
 use fj_math::{Aabb, Point, Scalar, Transform, Triangle, Vector};
+use instant::Instant;
 use winit::dpi::PhysicalPosition;
 
-use crate::window::Window;
+use crate::{view_state::CameraPose, viewport::Viewport};
 
 /// The camera abstraction
 ///
@@ -26,6 +30,9 @@ pub struct Camera {
 
     /// The locational part of the transform
     pub translation: Transform,
+
+    /// The transition to a new view that is currently in progress, if any
+    animation: Option<Animation>,
 }
 
 impl Camera {
@@ -34,8 +41,51 @@ impl Camera {
 
     const INITIAL_FIELD_OF_VIEW_IN_X: f64 = FRAC_PI_2; // 90 degrees
 
+    /// The duration of the transition between views
+    const ANIMATION_DURATION: Duration = Duration::from_millis(200);
+
     /// Returns a new camera aligned for viewing a bounding box
     pub fn new(aabb: &Aabb<3>) -> Self {
+        Self {
+            near_plane: Self::DEFAULT_NEAR_PLANE,
+            far_plane: Self::DEFAULT_FAR_PLANE,
+
+            rotation: Transform::identity(),
+            translation: Self::framing(aabb),
+
+            animation: None,
+        }
+    }
+
+    /// Returns a new camera at `eye`, looking at `target`
+    ///
+    /// All arguments are in model space. `up` points up in the resulting view.
+    /// If it is parallel to the view direction, a different direction is
+    /// picked, like [`Camera::look_from`] does.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `eye` and `target` are the same point.
+    pub fn look_at(eye: Point<3>, target: Point<3>, up: Vector<3>) -> Self {
+        let rotation = rotation_towards(eye - target, up);
+        let translation =
+            Transform::translation(-rotation.transform_point(&eye).coords);
+
+        Self {
+            near_plane: Self::DEFAULT_NEAR_PLANE,
+            far_plane: Self::DEFAULT_FAR_PLANE,
+
+            rotation,
+            translation,
+
+            animation: None,
+        }
+    }
+
+    /// Compute the translation that makes a bounding box fill the view
+    ///
+    /// Expects the bounding box to be already rotated into the view.
+    fn framing(aabb: &Aabb<3>) -> Transform {
         let initial_distance = {
             // Let's make sure we choose a distance, so that the model fills
             // most of the screen.
@@ -71,16 +121,102 @@ impl Camera {
             -offset
         };
 
-        Self {
-            near_plane: Self::DEFAULT_NEAR_PLANE,
-            far_plane: Self::DEFAULT_FAR_PLANE,
+        Transform::translation([
+            initial_offset.x,
+            initial_offset.y,
+            -initial_distance,
+        ])
+    }
 
-            rotation: Transform::identity(),
-            translation: Transform::translation([
-                initial_offset.x,
-                initial_offset.y,
-                -initial_distance,
-            ]),
+    /// Smoothly move the model, so it fills the view
+    ///
+    /// Keeps the current rotation.
+    pub fn zoom_to_fit(&mut self, aabb: &Aabb<3>, now: Instant) {
+        self.frame(self.rotation, aabb, now);
+    }
+
+    /// Smoothly rotate the model into a standard view, and make it fill it
+    pub fn show_view(
+        &mut self,
+        view: StandardView,
+        aabb: &Aabb<3>,
+        now: Instant,
+    ) {
+        self.frame(view.rotation(), aabb, now);
+    }
+
+    /// Smoothly rotate the model, so it is viewed from the given direction
+    ///
+    /// `direction` points from the model towards the camera. Model space's z
+    /// axis points up in the resulting view, unless the model is viewed from
+    /// straight above or below.
+    pub fn look_from(
+        &mut self,
+        direction: Vector<3>,
+        aabb: &Aabb<3>,
+        now: Instant,
+    ) {
+        let rotation = rotation_towards(direction, Vector::unit_z());
+        self.frame(rotation, aabb, now);
+    }
+
+    fn frame(&mut self, rotation: Transform, aabb: &Aabb<3>, now: Instant) {
+        let rotated = Aabb::<3>::from_points(
+            aabb.vertices()
+                .into_iter()
+                .map(|vertex| rotation.transform_point(&vertex)),
+        );
+
+        self.animate_to(rotation, Self::framing(&rotated), now);
+    }
+
+    /// Start a smooth transition from the current transform to a new one
+    ///
+    /// The transition is advanced by [`Camera::update`]. If another transition
+    /// is already in progress, the new one starts where the camera currently
+    /// is.
+    pub fn animate_to(
+        &mut self,
+        rotation: Transform,
+        translation: Transform,
+        now: Instant,
+    ) {
+        self.animation = Some(Animation {
+            start: now,
+            from: [self.rotation, self.translation],
+            to: [rotation, translation],
+        });
+    }
+
+    /// Indicate whether a transition between views is in progress
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Advance the transition between views, if one is in progress
+    ///
+    /// Needs to be called once per frame.
+    pub fn update(&mut self, now: Instant) {
+        let animation = match &self.animation {
+            Some(animation) => animation,
+            None => return,
+        };
+
+        let t = now.duration_since(animation.start).as_secs_f64()
+            / Self::ANIMATION_DURATION.as_secs_f64();
+        let t = t.min(1.);
+
+        // Ease in and out, so the transition doesn't start or stop abruptly.
+        let eased = t * t * (3. - 2. * t);
+
+        let [rotation_from, translation_from] = animation.from;
+        let [rotation_to, translation_to] = animation.to;
+
+        self.rotation = rotation_from.interpolate(&rotation_to, eased);
+        self.translation = translation_from.interpolate(&translation_to, eased);
+
+        if t >= 1. {
+            self.animation = None;
         }
     }
 
@@ -105,14 +241,46 @@ impl Camera {
             .inverse_transform_point(&Point::<3>::origin())
     }
 
+    /// Returns the direction that the camera looks in, in model space
+    pub fn direction(&self) -> Vector<3> {
+        self.camera_to_model()
+            .inverse()
+            .transform_vector(&Vector::from([0., 0., -1.]))
+    }
+
+    /// Returns the direction that points up in the view, in model space
+    pub fn up(&self) -> Vector<3> {
+        self.camera_to_model()
+            .inverse()
+            .transform_vector(&Vector::unit_y())
+    }
+
+    /// Returns the pose of the camera, which can be serialized
+    pub fn pose(&self) -> CameraPose {
+        CameraPose::from_camera(self)
+    }
+
+    /// Move the camera into a pose right away
+    ///
+    /// Any transition between views that is in progress is stopped.
+    pub fn set_pose(&mut self, pose: &CameraPose) {
+        self.animation = None;
+        pose.apply(self);
+    }
+
     /// Transform the position of the cursor on the near plane to model space.
+    ///
+    /// Expects the cursor position relative to the window, and the viewport
+    /// that this camera renders into.
     pub fn cursor_to_model_space(
         &self,
         cursor: PhysicalPosition<f64>,
-        window: &Window,
+        viewport: &Viewport,
     ) -> Point<3> {
-        let width = window.width() as f64;
-        let height = window.height() as f64;
+        let cursor = viewport.to_local(cursor);
+
+        let width = viewport.width as f64;
+        let height = viewport.height as f64;
         let aspect_ratio = width / height;
 
         // Cursor position in normalized coordinates (-1 to +1) with
@@ -129,23 +297,69 @@ impl Camera {
     }
 
     /// Compute the point on the model, that the cursor currently points to.
+    ///
+    /// If the cursor doesn't point at the model itself, this falls back to the
+    /// point on the model's bounding box that it points at. That way, the
+    /// focus point stays close to the model, even if the cursor is in a gap
+    /// between its parts.
     pub fn focus_point(
         &self,
-        window: &Window,
+        viewport: &Viewport,
         cursor: Option<PhysicalPosition<f64>>,
         mesh: &Mesh<fj_math::Point<3>>,
+        aabb: &Aabb<3>,
     ) -> FocusPoint {
         let cursor = match cursor {
             Some(cursor) => cursor,
             None => return FocusPoint::none(),
         };
 
+        if let Some(point) = self.pick(viewport, cursor, mesh) {
+            return FocusPoint(Some(point));
+        }
+
         // Transform camera and cursor positions to model space.
         let origin = self.position();
-        let cursor = self.cursor_to_model_space(cursor, window);
+        let cursor = self.cursor_to_model_space(cursor, viewport);
         let dir = (cursor - origin).normalize();
 
-        let mut min_t = None;
+        // The camera might be located within the bounding box. Treating it as
+        // hollow makes sure we get a point in front of the camera in that
+        // case, instead of the camera position itself.
+        let t = aabb.cast_local_ray(origin, dir, f64::INFINITY, false);
+
+        FocusPoint(t.map(|t| origin + dir * t))
+    }
+
+    /// Find the point on the model that the cursor points at
+    ///
+    /// Returns `None`, if the cursor doesn't point at the model.
+    pub fn pick(
+        &self,
+        viewport: &Viewport,
+        cursor: PhysicalPosition<f64>,
+        mesh: &Mesh<fj_math::Point<3>>,
+    ) -> Option<Point<3>> {
+        self.pick_triangle(viewport, cursor, mesh)
+            .map(|(point, _)| point)
+    }
+
+    /// Find the point on the model that the cursor points at, and the triangle
+    /// that it is on
+    ///
+    /// Returns `None`, if the cursor doesn't point at the model.
+    pub fn pick_triangle(
+        &self,
+        viewport: &Viewport,
+        cursor: PhysicalPosition<f64>,
+        mesh: &Mesh<fj_math::Point<3>>,
+    ) -> Option<(Point<3>, mesh::Triangle)> {
+        // Transform camera and cursor positions to model space.
+        let origin = self.position();
+        let cursor = self.cursor_to_model_space(cursor, viewport);
+        let dir = (cursor - origin).normalize();
+
+        let mut min = None;
 
         for triangle in mesh.triangles() {
             let t = Triangle::from_points(triangle.points).cast_local_ray(
@@ -156,13 +370,13 @@ impl Camera {
             );
 
             if let Some(t) = t {
-                if t <= min_t.unwrap_or(t) {
-                    min_t = Some(t);
+                if min.map_or(true, |(min_t, _)| t <= min_t) {
+                    min = Some((t, triangle));
                 }
             }
         }
 
-        FocusPoint(min_t.map(|t| origin + dir * t))
+        min.map(|(t, triangle)| (origin + dir * t, triangle))
     }
 
     /// Access the transform from camera to model space.
@@ -223,6 +437,85 @@ impl Camera {
     }
 }
 
+/// The rotation that makes the camera look against `towards_camera`
+///
+/// `towards_camera` points from the model towards the camera, and `up` points
+/// up in the view. If they are parallel, model space's y axis is used as up,
+/// or its z axis, if that is parallel too.
+fn rotation_towards(towards_camera: Vector<3>, up: Vector<3>) -> Transform {
+    let towards_camera = towards_camera.normalize();
+
+    // The axes of camera space, expressed in model space.
+    let x = [up, Vector::unit_y(), Vector::unit_z()]
+        .into_iter()
+        .map(|up| up.cross(&towards_camera))
+        .find(|x| x.magnitude() > Scalar::ZERO)
+        // At most one of y and z is parallel to `towards_camera`.
+        .unwrap()
+        .normalize();
+    let y = towards_camera.cross(&x);
+    let z = towards_camera;
+
+    // The rows of the rotation are the camera axes. The data is expected in
+    // column-major order.
+    let mut data = [0.; 16];
+    for (row, axis) in [x, y, z].into_iter().enumerate() {
+        for (column, component) in axis.components.iter().enumerate() {
+            data[column * 4 + row] = component.into_f64();
+        }
+    }
+    data[15] = 1.;
+
+    Transform::from_data(data)
+}
+
+/// A transition between two views
+///
+/// Both views consist of a rotation and a translation, in that order.
+#[derive(Debug)]
+struct Animation {
+    start: Instant,
+    from: [Transform; 2],
+    to: [Transform; 2],
+}
+
+/// A predefined direction to view the model from
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StandardView {
+    /// Look down onto the model, along the negative z axis
+    Top,
+
+    /// Look at the model along the positive y axis
+    Front,
+
+    /// Look at the model along the negative x axis
+    Right,
+
+    /// Look at the model from the front, right, and top
+    Isometric,
+}
+
+impl StandardView {
+    /// The rotation of the model that corresponds to this view
+    pub fn rotation(&self) -> Transform {
+        // Tilting the model by this angle (relative to the front view) makes
+        // all three axes appear equally foreshortened.
+        let isometric_elevation = (1. / 2f64.sqrt()).atan();
+
+        let front = Transform::rotation([-FRAC_PI_2, 0., 0.]);
+
+        match self {
+            Self::Top => Transform::identity(),
+            Self::Front => front,
+            Self::Right => Transform::rotation([0., -FRAC_PI_2, 0.]) * front,
+            Self::Isometric => {
+                Transform::rotation([isometric_elevation - FRAC_PI_2, 0., 0.])
+                    * Transform::rotation([0., 0., -FRAC_PI_4])
+            }
+        }
+    }
+}
+
 /// The point on the model that the cursor is currently pointing at.
 ///
 /// Such a point might or might not exist, depending on whether the cursor is
@@ -237,3 +530,71 @@ impl FocusPoint {
         Self(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Vector};
+
+    use super::Camera;
+
+    #[test]
+    fn look_at() {
+        let eye = Point::from([3., -4., 5.]);
+        let target = Point::from([1., 2., -1.]);
+
+        let camera = Camera::look_at(eye, target, Vector::unit_z());
+
+        assert!((camera.position() - eye).magnitude().into_f64() < 1e-9);
+        assert_parallel(camera.direction(), target - eye);
+
+        // Up is as close to the requested direction, as it can be, while
+        // still being perpendicular to the view direction.
+        let up = camera.up();
+        assert!(up.dot(&camera.direction()).into_f64().abs() < 1e-9);
+        assert!(up.dot(&Vector::unit_z()).into_f64() > 0.);
+    }
+
+    #[test]
+    fn look_at_with_up_parallel_to_view() {
+        let eye = Point::from([0., 0., 10.]);
+        let target = Point::origin();
+
+        for up in [Vector::unit_z(), -Vector::unit_z()] {
+            let camera = Camera::look_at(eye, target, up);
+
+            assert!((camera.position() - eye).magnitude().into_f64() < 1e-9);
+            assert_parallel(camera.direction(), target - eye);
+            assert!(
+                camera.up().dot(&camera.direction()).into_f64().abs() < 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn pose() {
+        let camera = Camera::look_at(
+            Point::from([3., -4., 5.]),
+            Point::origin(),
+            Vector::unit_z(),
+        );
+
+        let mut other = Camera::new(&Aabb::<3>::from_points(
+            [[0., 0., 0.], [1., 1., 1.]].map(Point::from),
+        ));
+        other.set_pose(&camera.pose());
+
+        assert_eq!(other.rotation.data(), camera.rotation.data());
+        assert_eq!(other.translation.data(), camera.translation.data());
+        assert_eq!(other.pose(), camera.pose());
+    }
+
+    fn assert_parallel(a: Vector<3>, b: Vector<3>) {
+        let cos = a.normalize().dot(&b.normalize()).into_f64();
+        assert!(
+            (cos - 1.).abs() < 1e-9,
+            "{:?} is not parallel to {:?}",
+            a,
+            b
+        );
+    }
+}
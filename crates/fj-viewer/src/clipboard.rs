@@ -0,0 +1,47 @@
+//! Copying values from the viewer to the system clipboard
+
+use tracing::warn;
+
+/// The system clipboard
+///
+/// Failing to access the clipboard is logged, rather than returned, as it's
+/// not worth interrupting the user over.
+#[derive(Default)]
+pub struct Clipboard {
+    // The clipboard is opened on first use, so viewers that never copy
+    // anything don't pay for it.
+    #[cfg(not(target_arch = "wasm32"))]
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Replace the contents of the clipboard with `text`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn copy(&mut self, text: String) {
+        let clipboard = match self.inner.take() {
+            Some(clipboard) => clipboard,
+            None => match arboard::Clipboard::new() {
+                Ok(clipboard) => clipboard,
+                Err(err) => {
+                    warn!("Failed to open clipboard: {}", err);
+                    return;
+                }
+            },
+        };
+        let clipboard = self.inner.insert(clipboard);
+
+        if let Err(err) = clipboard.set_text(text) {
+            warn!("Failed to copy to clipboard: {}", err);
+        }
+    }
+
+    /// Replace the contents of the clipboard with `text`
+    ///
+    /// Browsers only allow access to the clipboard from JavaScript, so this
+    /// isn't supported on the web.
+    #[cfg(target_arch = "wasm32")]
+    pub fn copy(&mut self, text: String) {
+        let _ = text;
+        warn!("Copying to the clipboard is not supported on the web");
+    }
+}
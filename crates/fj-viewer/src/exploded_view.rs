@@ -0,0 +1,130 @@
+//! Animation of the exploded view of a model
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Aabb, Point};
+use fj_operations::{
+    explosion::Explosion,
+    instances::{find_instances, Instances},
+    level_of_detail::LevelOfDetail,
+    shape_processor::ProcessedShape,
+};
+
+use crate::graphics::Shortcuts;
+
+/// How much of the full explosion the parts move per second
+const SPEED: f64 = 2.;
+
+/// The exploded view of the displayed model
+///
+/// The parts of the model don't jump to a new explosion right away. They move
+/// there a bit with every frame, so it's visible which part goes where.
+#[derive(Default)]
+pub struct ExplodedView {
+    explosion: Explosion,
+    factor: f64,
+
+    // The mesh and bounding box of the exploded model. `None`, while the model
+    // is assembled, or if it doesn't have any parts that move.
+    exploded: Option<(Mesh<Point<3>>, Aabb<3>)>,
+
+    // Simplified versions of the assembled model.
+    levels_of_detail: Vec<LevelOfDetail>,
+
+    // The parts of the assembled model that are copies of each other.
+    instances: Vec<Instances>,
+}
+
+impl ExplodedView {
+    /// Create the exploded view of a shape, that is already exploded by
+    /// `factor`
+    ///
+    /// `levels_of_detail` are simplified versions of the shape's mesh. See
+    /// [`ExplodedView::shortcuts`].
+    pub fn new(
+        shape: &ProcessedShape,
+        factor: f64,
+        levels_of_detail: Vec<LevelOfDetail>,
+    ) -> Self {
+        let mut view = Self {
+            explosion: Explosion::new(shape),
+            factor: 0.,
+            exploded: None,
+            levels_of_detail,
+            instances: find_instances(shape),
+        };
+        view.set_factor(shape, factor);
+
+        view
+    }
+
+    /// Move the parts towards the explosion given by `target`
+    ///
+    /// Returns whether the mesh changed.
+    pub fn update(
+        &mut self,
+        shape: &ProcessedShape,
+        target: f64,
+        delta_t: f64,
+    ) -> bool {
+        if self.factor == target || self.explosion.is_empty() {
+            return false;
+        }
+
+        let step = SPEED * delta_t;
+        let factor = if (target - self.factor).abs() <= step {
+            target
+        } else {
+            self.factor + step * (target - self.factor).signum()
+        };
+        self.set_factor(shape, factor);
+
+        true
+    }
+
+    /// Access the mesh that should be displayed
+    pub fn mesh<'r>(&'r self, shape: &'r ProcessedShape) -> &'r Mesh<Point<3>> {
+        self.exploded.as_ref().map_or(&shape.mesh, |(mesh, _)| mesh)
+    }
+
+    /// Access the faster ways to draw the mesh that should be displayed
+    ///
+    /// They only match the assembled model, so there are none while the model
+    /// is exploded.
+    pub fn shortcuts(&self) -> Shortcuts {
+        if self.exploded.is_some() {
+            return Shortcuts::default();
+        }
+
+        Shortcuts {
+            levels_of_detail: &self.levels_of_detail,
+            instances: &self.instances,
+        }
+    }
+
+    /// Give up the levels of detail, for when the shape is no longer displayed
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn into_levels_of_detail(self) -> Vec<LevelOfDetail> {
+        self.levels_of_detail
+    }
+
+    /// Whether any parts of the model are moved from where they belong
+    pub fn is_exploded(&self) -> bool {
+        self.exploded.is_some()
+    }
+
+    /// Access the bounding box of the mesh that should be displayed
+    pub fn aabb(&self, shape: &ProcessedShape) -> Aabb<3> {
+        self.exploded.as_ref().map_or(shape.aabb, |&(_, aabb)| aabb)
+    }
+
+    fn set_factor(&mut self, shape: &ProcessedShape, factor: f64) {
+        self.factor = factor;
+        self.exploded = if factor > 0. && !self.explosion.is_empty() {
+            let mesh = self.explosion.apply(&shape.mesh, factor);
+            let aabb = Aabb::<3>::from_points(mesh.vertices());
+            Some((mesh, aabb))
+        } else {
+            None
+        };
+    }
+}
@@ -0,0 +1,75 @@
+//! Deciding when the viewer draws its next frame
+//!
+//! The viewer doesn't draw continuously. It only draws, when something has
+//! changed, like the camera, the model, or the text overlay. While something
+//! keeps changing, for example during a camera animation, frames are drawn at
+//! no more than [`MAX_FPS`].
+
+use std::time::Duration;
+
+use instant::Instant;
+
+/// The highest number of frames that are drawn per second
+pub const MAX_FPS: u32 = 60;
+
+/// How often the viewer checks for changes, while nothing is being drawn
+///
+/// Input wakes the viewer up right away, but a model that has been rebuilt in
+/// the background doesn't. This is the longest it takes to show up.
+const IDLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The longest time that animations are advanced by, in a single frame
+///
+/// After the viewer has been idle, the time since the last frame can be long.
+/// Advancing animations by all of it would make them jump.
+pub(crate) const MAX_FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// When the viewer wants to draw its next frame
+///
+/// Returned by [`Viewer::schedule`]. Request a redraw of the window, if
+/// `redraw` is `true`, and don't call [`Viewer::schedule`] again before
+/// `wake_up`, unless there's a new window event.
+///
+/// [`Viewer::schedule`]: crate::viewer::Viewer::schedule
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Schedule {
+    /// Whether a frame should be drawn now
+    pub redraw: bool,
+
+    /// When the viewer should be asked again
+    pub wake_up: Instant,
+}
+
+impl Schedule {
+    /// Decide when to draw the next frame
+    ///
+    /// `changed` indicates whether the next frame would look different from
+    /// the previous one, which was drawn at `previous_frame`.
+    pub(crate) fn new(
+        changed: bool,
+        previous_frame: Instant,
+        now: Instant,
+    ) -> Self {
+        if !changed {
+            return Self {
+                redraw: false,
+                wake_up: now + IDLE_INTERVAL,
+            };
+        }
+
+        let frame_interval = Duration::from_secs(1) / MAX_FPS;
+        let next_frame = previous_frame + frame_interval;
+
+        if now >= next_frame {
+            Self {
+                redraw: true,
+                wake_up: now + frame_interval,
+            }
+        } else {
+            Self {
+                redraw: false,
+                wake_up: next_frame,
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+/// A graphics API that the renderer can use
+///
+/// By default, the renderer picks the best one that is available. Choosing one
+/// explicitly can help with drivers that don't work well.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Vulkan, on Linux, Windows, and Android
+    Vulkan,
+
+    /// Metal, on macOS and iOS
+    Metal,
+
+    /// DirectX 12, on Windows
+    Dx12,
+
+    /// DirectX 11, on Windows
+    Dx11,
+
+    /// OpenGL, or WebGL on the web
+    Gl,
+}
+
+impl Backend {
+    pub(crate) fn backends(&self) -> wgpu::Backends {
+        match self {
+            Self::Vulkan => wgpu::Backends::VULKAN,
+            Self::Metal => wgpu::Backends::METAL,
+            Self::Dx12 => wgpu::Backends::DX12,
+            Self::Dx11 => wgpu::Backends::DX11,
+            Self::Gl => wgpu::Backends::GL,
+        }
+    }
+}
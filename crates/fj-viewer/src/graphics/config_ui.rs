@@ -1,18 +1,49 @@
 use std::collections::HashMap;
 
-use fj_math::Aabb;
+use fj_interop::debug::DebugCategory;
+use fj_math::{Aabb, Point};
 use wgpu::util::StagingBelt;
 use wgpu_glyph::{
     ab_glyph::{FontArc, InvalidFont},
-    GlyphBrush, GlyphBrushBuilder, Section, Text,
+    GlyphBrush, GlyphBrushBuilder, HorizontalAlign, Layout, Section, Text,
+    VerticalAlign,
 };
 
+use crate::{input, profiler::Profile};
+
 use super::draw_config::DrawConfig;
 
+/// Text that is displayed at the bottom of the window
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Status<'r> {
+    /// The progress of rebuilding the model, if it is being rebuilt
+    pub progress: Option<&'r str>,
+
+    /// The status bar, showing information about the model
+    pub status_bar: Option<&'r str>,
+
+    /// The error of the last failed rebuild, if it hasn't been dismissed
+    pub error: Option<&'r str>,
+
+    /// The models that can be switched between, if there are several
+    pub models: Option<&'r str>,
+
+    /// The bill of materials of the model, if it should be displayed
+    pub bom: Option<&'r str>,
+
+    /// Notes to label the model with, at the points they are anchored to
+    pub annotations: &'r [(Point<3>, &'r str)],
+}
+
 pub struct ConfigUi {
     glyph_brush: GlyphBrush<()>,
     texts: HashMap<(Element, bool), String>,
     staging_belt: StagingBelt,
+    text_color: [f32; 4],
+
+    // The number of physical pixels per logical pixel. Text sizes and
+    // positions are given in logical pixels, and are scaled by this.
+    scale_factor: f32,
 }
 
 impl std::fmt::Debug for ConfigUi {
@@ -38,6 +69,10 @@ impl ConfigUi {
         let mut texts = HashMap::new();
         for element in Element::elements() {
             let (name, key) = element.name_key();
+            let name = match element {
+                Element::DebugCategory(_) => format!("{} debug info", name),
+                _ => name.to_owned(),
+            };
 
             for (enabled, word) in [(false, "disabled"), (true, "enabled")] {
                 texts.insert(
@@ -61,9 +96,157 @@ impl ConfigUi {
             glyph_brush,
             texts,
             staging_belt,
+            text_color: [0.0, 0.0, 0.0, 1.0],
+            scale_factor: 1.0,
         })
     }
 
+    /// Set the number of physical pixels per logical pixel
+    ///
+    /// Applies to anything that is queued afterwards.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// Set the color that all text is drawn with
+    ///
+    /// Applies to anything that is queued afterwards.
+    pub fn set_text_color(&mut self, color: [f32; 4]) {
+        self.text_color = color;
+    }
+
+    /// Queue labels at the given screen positions
+    ///
+    /// The labels are drawn with the next call to [`ConfigUi::draw`].
+    pub fn queue_labels<'a>(
+        &mut self,
+        labels: impl IntoIterator<Item = ([f32; 2], &'a str)>,
+    ) {
+        for ([x, y], label) in labels {
+            let text = Text::new(label)
+                .with_color(self.text_color)
+                .with_scale(self.px(20.0));
+            let section =
+                Section::new().with_screen_position((x, y)).add_text(text);
+
+            self.glyph_brush.queue(section);
+        }
+    }
+
+    /// Queue the profiler overlay at the top right of the screen
+    ///
+    /// The overlay is drawn with the next call to [`ConfigUi::draw`].
+    pub fn queue_profile(&mut self, profile: &Profile, screen_width: f32) {
+        let profile = profile.to_string();
+        let text = Text::new(&profile)
+            .with_color(self.text_color)
+            .with_scale(self.px(30.0));
+        let section = Section::new()
+            .with_screen_position((screen_width - self.px(50.0), self.px(50.0)))
+            .with_layout(Layout::default().h_align(HorizontalAlign::Right))
+            .add_text(text);
+
+        self.glyph_brush.queue(section);
+    }
+
+    /// Queue a status line at the bottom of the screen
+    ///
+    /// The status line is drawn with the next call to [`ConfigUi::draw`].
+    pub fn queue_status(&mut self, status: &str, screen_height: f32) {
+        let text = Text::new(status)
+            .with_color(self.text_color)
+            .with_scale(self.px(50.0));
+        let section = Section::new()
+            .with_screen_position((
+                self.px(50.0),
+                screen_height - self.px(100.0),
+            ))
+            .add_text(text);
+
+        self.glyph_brush.queue(section);
+    }
+
+    pub fn queue_status_bar(&mut self, status_bar: &str, screen_height: f32) {
+        let text = Text::new(status_bar)
+            .with_color(self.text_color)
+            .with_scale(self.px(20.0));
+        let section = Section::new()
+            .with_screen_position((
+                self.px(10.0),
+                screen_height - self.px(30.0),
+            ))
+            .add_text(text);
+
+        self.glyph_brush.queue(section);
+    }
+
+    /// Queue the list of models at the bottom right of the screen
+    ///
+    /// The list is drawn with the next call to [`ConfigUi::draw`].
+    pub fn queue_models(&mut self, models: &str, screen_size: [f32; 2]) {
+        let [width, height] = screen_size;
+
+        let text = Text::new(models)
+            .with_color(self.text_color)
+            .with_scale(self.px(20.0));
+        let section = Section::new()
+            .with_screen_position((
+                width - self.px(10.0),
+                height - self.px(40.0),
+            ))
+            .with_layout(
+                Layout::default()
+                    .h_align(HorizontalAlign::Right)
+                    .v_align(VerticalAlign::Bottom),
+            )
+            .add_text(text);
+
+        self.glyph_brush.queue(section);
+    }
+
+    /// Queue the bill of materials at the right of the screen
+    ///
+    /// The bill of materials is drawn with the next call to
+    /// [`ConfigUi::draw`].
+    pub fn queue_bom(&mut self, bom: &str, screen_size: [f32; 2]) {
+        let [width, height] = screen_size;
+
+        let text = Text::new(bom)
+            .with_color(self.text_color)
+            .with_scale(self.px(20.0));
+        let section = Section::new()
+            .with_screen_position((width - self.px(10.0), height / 2.0))
+            .with_layout(
+                Layout::default()
+                    .h_align(HorizontalAlign::Right)
+                    .v_align(VerticalAlign::Center),
+            )
+            .add_text(text);
+
+        self.glyph_brush.queue(section);
+    }
+
+    pub fn queue_error(&mut self, error: &str, screen_size: [f32; 2]) {
+        let [width, height] = screen_size;
+
+        // Errors are always red, regardless of the theme, so they stand out.
+        let color = [0.8, 0.1, 0.1, 1.0];
+
+        let section = Section::new()
+            .with_screen_position((self.px(50.0), height / 3.0))
+            .with_bounds((width - self.px(100.0), height))
+            .add_text(
+                Text::new("Error (dismiss with Enter)\n\n")
+                    .with_color(color)
+                    .with_scale(self.px(40.0)),
+            )
+            .add_text(
+                Text::new(error).with_color(color).with_scale(self.px(20.0)),
+            );
+
+        self.glyph_brush.queue(section);
+    }
+
     pub fn draw(
         &mut self,
         device: &wgpu::Device,
@@ -72,22 +255,67 @@ impl ConfigUi {
         surface_config: &wgpu::SurfaceConfiguration,
         aabb: &Aabb<3>,
         draw_config: &DrawConfig,
+        input: &input::Handler,
     ) -> Result<(), String> {
-        let mut section = Section::new().with_screen_position((50.0, 50.0));
+        let mut section =
+            Section::new().with_screen_position((self.px(50.0), self.px(50.0)));
 
         for element in Element::elements() {
+            if let Element::DebugCategory(_) = element {
+                if !draw_config.draw_debug {
+                    continue;
+                }
+            }
+
             let enabled = element.is_enabled(draw_config);
             let text = &self.texts[&(element, enabled)];
 
-            let alpha = if enabled { 1.0 } else { 0.75 };
+            let mut color = self.text_color;
+            if !enabled {
+                color[3] *= 0.75;
+            }
 
-            let text = Text::new(text)
-                .with_color([0.0, 0.0, 0.0, alpha])
-                .with_scale(50.0);
+            let text =
+                Text::new(text).with_color(color).with_scale(self.px(50.0));
 
             section = section.add_text(text);
         }
 
+        let quality = match draw_config.quality {
+            Some(metric) => metric.name(),
+            None => "off",
+        };
+        let face = match draw_config.selected_face {
+            Some(face) => format!("face {}", face.0),
+            None => "all faces".to_owned(),
+        };
+        let explosion = (draw_config.explosion * 10.).round() as usize;
+        let info = format!(
+            "Triangle quality: {} (cycle with 5)\n\
+            Input device: {} (switch with 7)\n\
+            Navigation: {} (switch with 8)\n\
+            Layout: {} (switch with 9)\n\
+            Theme: {} (switch with 0)\n\
+            Views: fit with F; top, front, right, isometric with numpad \
+            7, 1, 3, 0\n\
+            Showing {} (step with [ and ])\n\
+            Exploded view: [{}{}] {:.0}% (adjust with - and =, toggle with \
+            X)\n",
+            quality,
+            input.settings().device.name(),
+            input.behavior().name(),
+            draw_config.layout.name(),
+            draw_config.theme.preset.name(),
+            face,
+            "#".repeat(explosion),
+            "-".repeat(10 - explosion),
+            draw_config.explosion * 100.,
+        );
+        let text = Text::new(&info)
+            .with_color(self.text_color)
+            .with_scale(self.px(50.0));
+        section = section.add_text(text);
+
         /* Render size of model bounding box */
         let bbsize = aabb.size().components;
         let info = format!(
@@ -97,8 +325,8 @@ impl ConfigUi {
             bbsize[2].into_f32()
         );
         let text = Text::new(&info)
-            .with_color([0.0, 0.0, 0.0, 1.0])
-            .with_scale(50.0);
+            .with_color(self.text_color)
+            .with_scale(self.px(50.0));
         section = section.add_text(text);
 
         self.glyph_brush.queue(section);
@@ -115,6 +343,11 @@ impl ConfigUi {
 
         Ok(())
     }
+
+    /// Convert a length from logical to physical pixels
+    fn px(&self, logical: f32) -> f32 {
+        logical * self.scale_factor
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -122,11 +355,28 @@ enum Element {
     Model,
     Mesh,
     Debug,
+    SmoothNormals,
+    Shadows,
+    Outlines,
+    Profiler,
+    Bom,
+    DebugCategory(DebugCategory),
 }
 
 impl Element {
-    fn elements() -> [Self; 3] {
-        [Self::Model, Self::Mesh, Self::Debug]
+    fn elements() -> impl Iterator<Item = Self> {
+        [
+            Self::Model,
+            Self::Mesh,
+            Self::Debug,
+            Self::SmoothNormals,
+            Self::Shadows,
+            Self::Outlines,
+            Self::Profiler,
+            Self::Bom,
+        ]
+        .into_iter()
+        .chain(DebugCategory::all().map(Self::DebugCategory))
     }
 
     fn name_key(&self) -> (&'static str, &'static str) {
@@ -134,6 +384,14 @@ impl Element {
             Self::Model => ("model", "1"),
             Self::Mesh => ("mesh", "2"),
             Self::Debug => ("debug info", "3"),
+            Self::SmoothNormals => ("smooth", "4"),
+            Self::Shadows => ("shadows", "H"),
+            Self::Outlines => ("outlines", "O"),
+            Self::Profiler => ("profiler", "6"),
+            Self::Bom => ("bill of materials", "B"),
+            Self::DebugCategory(category) => {
+                (category.name(), debug_category_key(*category))
+            }
         }
     }
 
@@ -142,6 +400,25 @@ impl Element {
             Self::Model => config.draw_model,
             Self::Mesh => config.draw_mesh,
             Self::Debug => config.draw_debug,
+            Self::SmoothNormals => config.smooth_normals,
+            Self::Shadows => config.draw_shadows,
+            Self::Outlines => config.draw_outlines,
+            Self::Profiler => config.draw_profiler,
+            Self::Bom => config.draw_bom,
+            Self::DebugCategory(category) => {
+                config.debug_categories.contains(category)
+            }
         }
     }
 }
+
+fn debug_category_key(category: DebugCategory) -> &'static str {
+    match category {
+        DebugCategory::Approximation => "F1",
+        DebugCategory::Triangulation => "F2",
+        DebugCategory::Intersection => "F3",
+        DebugCategory::Sweep => "F4",
+        DebugCategory::Other => "F5",
+        DebugCategory::Analysis => "F6",
+    }
+}
@@ -1,5 +1,17 @@
 //! High level configuration for graphics rendering
 
+use std::collections::BTreeSet;
+
+use fj_interop::{
+    debug::DebugCategory,
+    mesh::{FaceId, Mesh, Normals},
+};
+use fj_math::Point;
+
+use crate::viewport::Layout;
+
+use super::{QualityMetric, Theme};
+
 /// High level configuration for rendering the active model
 #[derive(Debug)]
 pub struct DrawConfig {
@@ -9,6 +21,76 @@ pub struct DrawConfig {
     pub draw_mesh: bool,
     /// Toggle for displaying model debug information
     pub draw_debug: bool,
+    /// Toggle for smooth shading of curved surfaces
+    pub smooth_normals: bool,
+    /// Toggle for the shadows that the model casts onto itself
+    pub draw_shadows: bool,
+    /// Toggle for the outlines of the model's silhouette and creases
+    pub draw_outlines: bool,
+    /// Toggle for displaying the profiler overlay
+    pub draw_profiler: bool,
+    /// Toggle for displaying the bill of materials
+    pub draw_bom: bool,
+    /// The categories of debug information that are displayed
+    pub debug_categories: BTreeSet<DebugCategory>,
+    /// Color triangles by this metric, instead of their own color
+    pub quality: Option<QualityMetric>,
+    /// Only display the triangles of this face
+    pub selected_face: Option<FaceId>,
+    /// How far the parts of the model are moved apart, from `0.` to `1.`
+    pub explosion: f64,
+    /// The arrangement of viewports in the window
+    pub layout: Layout,
+    /// The colors of the background, wireframe, and text
+    pub theme: Theme,
+}
+
+impl DrawConfig {
+    /// Toggle the display of a category of debug information
+    pub fn toggle_debug_category(&mut self, category: DebugCategory) {
+        if !self.debug_categories.remove(&category) {
+            self.debug_categories.insert(category);
+        }
+    }
+
+    /// Select the next face of the mesh, or all faces after the last one
+    pub fn select_next_face(&mut self, mesh: &Mesh<Point<3>>) {
+        let faces = mesh.triangles().filter_map(|triangle| triangle.face);
+
+        self.selected_face = match self.selected_face {
+            None => faces.min(),
+            Some(selected) => faces.filter(|&face| face > selected).min(),
+        };
+    }
+
+    /// Select the previous face of the mesh, or all faces before the first one
+    pub fn select_previous_face(&mut self, mesh: &Mesh<Point<3>>) {
+        let faces = mesh.triangles().filter_map(|triangle| triangle.face);
+
+        self.selected_face = match self.selected_face {
+            None => faces.max(),
+            Some(selected) => faces.filter(|&face| face < selected).max(),
+        };
+    }
+
+    /// Explode the model fully, or assemble it again, if it is exploded
+    pub fn toggle_explosion(&mut self) {
+        self.explosion = if self.explosion > 0. { 0. } else { 1. };
+    }
+
+    /// Change how far the model is exploded, within the valid range
+    pub fn change_explosion(&mut self, delta: f64) {
+        self.explosion = (self.explosion + delta).clamp(0., 1.);
+    }
+
+    /// The normals that the model should be rendered with
+    pub fn normals(&self) -> Normals {
+        if self.smooth_normals {
+            Normals::smooth()
+        } else {
+            Normals::Flat
+        }
+    }
 }
 
 impl Default for DrawConfig {
@@ -17,6 +99,17 @@ impl Default for DrawConfig {
             draw_model: true,
             draw_mesh: false,
             draw_debug: false,
+            smooth_normals: true,
+            draw_shadows: true,
+            draw_outlines: true,
+            draw_profiler: false,
+            draw_bom: false,
+            debug_categories: DebugCategory::all().into_iter().collect(),
+            quality: None,
+            selected_face: None,
+            explosion: 0.,
+            layout: Layout::default(),
+            theme: Theme::default(),
         }
     }
 }
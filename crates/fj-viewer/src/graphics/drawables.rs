@@ -1,21 +1,42 @@
+use crate::viewport::Viewport;
+
 use super::{
     geometries::{Geometries, Geometry},
     pipelines::{Pipeline, Pipelines},
+    transform::Transform,
 };
 
 pub struct Drawables<'r> {
     pub model: Drawable<'r>,
-    pub mesh: Drawable<'r>,
+    pub normals: Drawable<'r>,
+    pub mesh: Option<Drawable<'r>>,
     pub lines: Drawable<'r>,
 }
 
 impl<'r> Drawables<'r> {
-    pub fn new(geometries: &'r Geometries, pipelines: &'r Pipelines) -> Self {
-        let model = Drawable::new(&geometries.mesh, &pipelines.model);
-        let mesh = Drawable::new(&geometries.mesh, &pipelines.mesh);
+    /// Prepare drawing the geometries
+    ///
+    /// The model is drawn from `model`, which is either the full mesh, or one
+    /// of its levels of detail. The wireframe always shows the full mesh.
+    pub fn new(
+        geometries: &'r Geometries,
+        model: &'r Geometry,
+        pipelines: &'r Pipelines,
+    ) -> Self {
+        let normals = Drawable::new(model, &pipelines.normals);
+        let model = Drawable::new(model, &pipelines.model);
+        let mesh = pipelines
+            .mesh
+            .as_ref()
+            .map(|pipeline| Drawable::new(&geometries.mesh, pipeline));
         let lines = Drawable::new(&geometries.lines, &pipelines.lines);
 
-        Self { model, mesh, lines }
+        Self {
+            model,
+            normals,
+            mesh,
+            lines,
+        }
     }
 }
 
@@ -25,7 +46,7 @@ pub struct Drawable<'r> {
 }
 
 impl<'r> Drawable<'r> {
-    fn new(geometry: &'r Geometry, pipeline: &'r Pipeline) -> Self {
+    pub fn new(geometry: &'r Geometry, pipeline: &'r Pipeline) -> Self {
         Self { geometry, pipeline }
     }
 
@@ -35,6 +56,9 @@ impl<'r> Drawable<'r> {
         color_view: &wgpu::TextureView,
         depth_view: &wgpu::TextureView,
         bind_group: &wgpu::BindGroup,
+        uniforms_offset: u32,
+        viewport: &Viewport,
+        transform: &Transform,
     ) {
         let mut render_pass =
             encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -59,14 +83,24 @@ impl<'r> Drawable<'r> {
                 ),
             });
 
-        render_pass.set_pipeline(&self.pipeline.0);
-        render_pass.set_bind_group(0, bind_group, &[]);
-        render_pass.set_vertex_buffer(0, self.geometry.vertex_buffer.slice(..));
-        render_pass.set_index_buffer(
-            self.geometry.index_buffer.slice(..),
-            wgpu::IndexFormat::Uint32,
+        render_pass.set_viewport(
+            viewport.x as f32,
+            viewport.y as f32,
+            viewport.width as f32,
+            viewport.height as f32,
+            0.,
+            1.,
+        );
+        render_pass.set_scissor_rect(
+            viewport.x,
+            viewport.y,
+            viewport.width,
+            viewport.height,
         );
 
-        render_pass.draw_indexed(0..self.geometry.num_indices, 0, 0..1);
+        render_pass.set_pipeline(&self.pipeline.0);
+        render_pass.set_bind_group(0, bind_group, &[uniforms_offset]);
+
+        self.geometry.draw(&mut render_pass, Some(transform));
     }
 }
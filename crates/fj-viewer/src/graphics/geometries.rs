@@ -1,64 +1,407 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, mem::size_of, ops::Range};
 
-use fj_math::Aabb;
-use wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+use fj_math::{Aabb, Point};
 
-use super::vertices::{Vertex, Vertices};
+use crate::{camera::Camera, viewport::Viewport};
+
+use super::{
+    transform::Transform,
+    vertices::{Instance, Vertex, Vertices},
+};
+
+/// The most indices that are drawn from a single pair of buffers
+///
+/// Larger meshes are split into chunks, which are uploaded one after the
+/// other. That way, no buffer needs to be larger than the device allows, and
+/// huge models don't need a single allocation for all of their vertices.
+///
+/// This is a multiple of 6, so chunks end on a whole triangle, as well as on a
+/// whole line.
+const CHUNK_INDICES: usize = 6 * (1 << 18);
+
+/// The largest error of a level of detail, in pixels on screen
+///
+/// A level of detail is only drawn, if it doesn't look any different from the
+/// full mesh.
+const MAX_PIXEL_ERROR: f64 = 1.;
 
 #[derive(Debug)]
 pub struct Geometries {
     pub mesh: Geometry,
+    pub levels: Vec<Level>,
     pub lines: Geometry,
+    pub labels: Vec<(Point<3>, String)>,
     pub aabb: Aabb<3>,
 }
 
 impl Geometries {
+    /// Select the mesh that is drawn with `camera`, into `viewport`
+    ///
+    /// That's the coarsest level of detail, whose error is smaller than
+    /// [`MAX_PIXEL_ERROR`] on screen, or the full mesh, if there is none. The
+    /// part of the model that's closest to the camera decides, as errors look
+    /// largest there.
+    pub fn mesh_for(&self, camera: &Camera, viewport: &Viewport) -> &Geometry {
+        let position = camera.position();
+        let distance = (0..3)
+            .map(|i| {
+                let [coord, min, max] =
+                    [position, self.aabb.min, self.aabb.max]
+                        .map(|point| point.coords.components[i].into_f64());
+
+                coord.clamp(min, max) - coord
+            })
+            .map(|d| d * d)
+            .sum::<f64>()
+            .sqrt();
+
+        // The size of a pixel, at that distance from the camera.
+        let pixel = 2. * distance * (camera.field_of_view_in_x() / 2.).tan()
+            / f64::from(viewport.width);
+
+        self.levels
+            .iter()
+            .rev()
+            .find(|level| level.error < pixel * MAX_PIXEL_ERROR)
+            .map_or(&self.mesh, |level| &level.geometry)
+    }
+}
+
+/// A simplified version of the mesh
+#[derive(Debug)]
+pub struct Level {
+    pub geometry: Geometry,
+
+    // The approximate maximum distance from the full mesh, in model units.
+    pub error: f64,
+}
+
+#[derive(Debug)]
+pub struct Geometry {
+    batches: Vec<Batch>,
+}
+
+impl Geometry {
+    /// Upload vertices and indices in chunks of [`CHUNK_INDICES`]
+    ///
+    /// If `indirect` is `true`, the chunks are drawn using indirect draws.
+    /// That requires support from the device, which isn't available with
+    /// WebGL, for example.
+    ///
+    /// The groups of `vertices` are kept, so they can be culled when drawing.
     pub fn new(
         device: &wgpu::Device,
-        mesh: &Vertices,
-        debug_info: &Vertices,
-        aabb: Aabb<3>,
+        queue: &wgpu::Queue,
+        indirect: bool,
+        vertices: &Vertices,
     ) -> Self {
-        let mesh = Geometry::new(device, mesh.vertices(), mesh.indices());
-        let lines =
-            Geometry::new(device, debug_info.vertices(), debug_info.indices());
+        let batch = Batch::new(
+            device,
+            queue,
+            indirect,
+            vertices,
+            &[fj_math::Transform::identity()],
+        );
 
-        Self { mesh, lines, aabb }
+        Self {
+            batches: vec![batch],
+        }
+    }
+
+    /// Upload batches of vertices, which are drawn once per transform
+    ///
+    /// Like [`Geometry::new`], but each batch is drawn multiple times, using
+    /// instancing. Batches without any transforms are left out.
+    pub fn instanced(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        indirect: bool,
+        batches: &[(Vertices, Vec<fj_math::Transform>)],
+    ) -> Self {
+        let batches = batches
+            .iter()
+            .filter(|(_, transforms)| !transforms.is_empty())
+            .map(|(vertices, transforms)| {
+                Batch::new(device, queue, indirect, vertices, transforms)
+            })
+            .collect();
+
+        Self { batches }
+    }
+
+    /// Draw all batches with the pipeline and bind groups that are already set
+    ///
+    /// If a `transform` is passed, groups of triangles that are outside of its
+    /// view frustum are skipped.
+    pub fn draw<'r>(
+        &'r self,
+        render_pass: &mut wgpu::RenderPass<'r>,
+        transform: Option<&Transform>,
+    ) {
+        for batch in &self.batches {
+            render_pass.set_vertex_buffer(1, batch.instance_buffer.slice(..));
+
+            for chunk in &batch.chunks {
+                chunk.draw(render_pass, transform, batch.num_instances);
+            }
+        }
     }
 }
 
 #[derive(Debug)]
-pub struct Geometry {
-    pub vertex_buffer: wgpu::Buffer,
-    pub index_buffer: wgpu::Buffer,
-    pub num_indices: u32,
+struct Batch {
+    instance_buffer: wgpu::Buffer,
+    num_instances: u32,
+    chunks: Vec<Chunk>,
 }
 
-impl Geometry {
+impl Batch {
+    fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        indirect: bool,
+        vertices: &Vertices,
+        transforms: &[fj_math::Transform],
+    ) -> Self {
+        let instances: Vec<Instance> =
+            transforms.iter().map(Instance::from).collect();
+        let instance_buffer = upload(
+            device,
+            queue,
+            bytemuck::cast_slice(&instances),
+            wgpu::BufferUsages::VERTEX,
+        );
+        let num_instances = len_u32(instances.len());
+
+        let groups = vertices.groups();
+        let indices = vertices.indices();
+        let vertices = vertices.vertices();
+
+        // Maps the index of a vertex in `vertices` to its index in the current
+        // chunk. Entries are reset after each chunk, so this doesn't need to
+        // be allocated again.
+        let mut remap = vec![u32::MAX; vertices.len()];
+
+        let mut chunk_vertices = Vec::new();
+        let mut chunk_indices = Vec::new();
+
+        let chunks = indices
+            .chunks(CHUNK_INDICES)
+            .enumerate()
+            .map(|(i, indices)| {
+                chunk_vertices.clear();
+                chunk_indices.clear();
+
+                for &index in indices {
+                    let local = &mut remap[index as usize];
+                    if *local == u32::MAX {
+                        *local = len_u32(chunk_vertices.len());
+                        chunk_vertices.push(vertices[index as usize]);
+                    }
+                    chunk_indices.push(*local);
+                }
+                for &index in indices {
+                    remap[index as usize] = u32::MAX;
+                }
+
+                // The parts of the groups that are in this chunk, relative to
+                // its start.
+                let start = len_u32(i * CHUNK_INDICES);
+                let end = start + len_u32(indices.len());
+                let pieces = if groups.is_empty() {
+                    vec![Piece {
+                        indices: 0..end - start,
+                        aabb: None,
+                    }]
+                } else {
+                    groups
+                        .iter()
+                        .filter(|group| {
+                            group.indices.start < end
+                                && group.indices.end > start
+                        })
+                        .map(|group| {
+                            // A group is only out of view, if all instances of
+                            // it are.
+                            let aabb = transforms
+                                .iter()
+                                .map(|transform| {
+                                    transform.transform_aabb(&group.aabb)
+                                })
+                                .reduce(|a, b| a.merged(&b));
+
+                            Piece {
+                                indices: group.indices.start.max(start) - start
+                                    ..group.indices.end.min(end) - start,
+                                aabb,
+                            }
+                        })
+                        .collect()
+                };
+
+                Chunk::new(
+                    device,
+                    queue,
+                    indirect,
+                    &chunk_vertices,
+                    &chunk_indices,
+                    pieces,
+                    num_instances,
+                )
+            })
+            .collect();
+
+        Self {
+            instance_buffer,
+            num_instances,
+            chunks,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Chunk {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    pieces: Vec<Piece>,
+
+    // The arguments of the draw calls, one per piece, if they're indirect.
+    indirect_buffer: Option<wgpu::Buffer>,
+}
+
+impl Chunk {
     fn new(
         device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        indirect: bool,
         vertices: &[Vertex],
         indices: &[u32],
+        pieces: Vec<Piece>,
+        num_instances: u32,
     ) -> Self {
+        let vertex_buffer = upload(
+            device,
+            queue,
+            bytemuck::cast_slice(vertices),
+            wgpu::BufferUsages::VERTEX,
+        );
+        let index_buffer = upload(
+            device,
+            queue,
+            bytemuck::cast_slice(indices),
+            wgpu::BufferUsages::INDEX,
+        );
+
+        // Buffers can't be empty, so there's always at least one piece.
+        let indirect_buffer = indirect.then(|| {
+            let args: Vec<_> = pieces
+                .iter()
+                .map(|piece| DrawIndexedIndirect {
+                    index_count: piece.indices.end - piece.indices.start,
+                    instance_count: num_instances,
+                    first_index: piece.indices.start,
+                    base_vertex: 0,
+                    first_instance: 0,
+                })
+                .collect();
+
+            upload(
+                device,
+                queue,
+                bytemuck::cast_slice(&args),
+                wgpu::BufferUsages::INDIRECT,
+            )
+        });
+
         Self {
-            vertex_buffer: device.create_buffer_init(
-                &wgpu::util::BufferInitDescriptor {
-                    label: None,
-                    contents: bytemuck::cast_slice(vertices),
-                    usage: wgpu::BufferUsages::VERTEX,
-                },
-            ),
-            index_buffer: device.create_buffer_init(
-                &wgpu::util::BufferInitDescriptor {
-                    label: None,
-                    contents: bytemuck::cast_slice(indices),
-                    usage: wgpu::BufferUsages::INDEX,
-                },
-            ),
-            num_indices: indices
-                .len()
-                .try_into()
-                .expect("`usize` couldn't be cast to `u32`"),
+            vertex_buffer,
+            index_buffer,
+            pieces,
+            indirect_buffer,
         }
     }
+
+    fn draw<'r>(
+        &'r self,
+        render_pass: &mut wgpu::RenderPass<'r>,
+        transform: Option<&Transform>,
+    ) {
+        let mut visible =
+            self.pieces.iter().enumerate().filter(|(_, piece)| {
+                match (transform, &piece.aabb) {
+                    (Some(transform), Some(aabb)) => transform.is_visible(aabb),
+                    _ => true,
+                }
+            });
+
+        // Don't bind the buffers, if there's nothing to draw from them.
+        let first = match visible.next() {
+            Some(first) => first,
+            None => return,
+        };
+
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(
+            self.index_buffer.slice(..),
+            wgpu::IndexFormat::Uint32,
+        );
+
+        for (i, piece) in Some(first).into_iter().chain(visible) {
+            match &self.indirect_buffer {
+                Some(buffer) => render_pass.draw_indexed_indirect(
+                    buffer,
+                    (i * size_of::<DrawIndexedIndirect>()) as u64,
+                ),
+                None => {
+                    render_pass.draw_indexed(piece.indices.clone(), 0, 0..1)
+                }
+            }
+        }
+    }
+}
+
+/// A range of a chunk's indices, which is drawn or culled as a whole
+#[derive(Debug)]
+struct Piece {
+    indices: Range<u32>,
+
+    // The bounding box of the triangles, if they can be culled.
+    aabb: Option<Aabb<3>>,
+}
+
+/// The arguments of an indexed indirect draw, as the GPU expects them
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct DrawIndexedIndirect {
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+}
+
+/// Create a buffer and queue the upload of `contents` to it
+///
+/// The data is copied into a staging buffer right away, so the memory it is in
+/// can be reused for the next chunk.
+fn upload(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    contents: &[u8],
+    usage: wgpu::BufferUsages,
+) -> wgpu::Buffer {
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: contents.len() as u64,
+        usage: usage | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    queue.write_buffer(&buffer, 0, contents);
+
+    buffer
+}
+
+fn len_u32(len: usize) -> u32 {
+    len.try_into().expect("`usize` couldn't be cast to `u32`")
 }
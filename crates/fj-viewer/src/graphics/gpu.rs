@@ -0,0 +1,96 @@
+use super::{backend::Backend, InitError};
+
+/// The GPU that renderers draw with
+///
+/// Each window needs its own [`Renderer`], but they can all share the same
+/// device, using [`Renderer::with_gpu`]. That saves creating a device per
+/// window, and the memory that each of them would hold on to.
+///
+/// [`Renderer`]: super::Renderer
+/// [`Renderer::with_gpu`]: super::Renderer::with_gpu
+#[derive(Debug)]
+pub struct Gpu {
+    pub(super) instance: wgpu::Instance,
+    pub(super) adapter: wgpu::Adapter,
+    pub(super) device: wgpu::Device,
+    pub(super) queue: wgpu::Queue,
+
+    pub(super) features: wgpu::Features,
+
+    // Whether the device supports indirect draws.
+    pub(super) indirect: bool,
+}
+
+impl Gpu {
+    /// Request a device that can draw to the surface that `create_surface`
+    /// creates
+    ///
+    /// If no backend is given, the best available one is used.
+    pub(super) async fn new(
+        backend: Option<Backend>,
+        create_surface: impl FnOnce(&wgpu::Instance) -> wgpu::Surface,
+    ) -> Result<(Self, wgpu::Surface), InitError> {
+        // Browsers might only support WebGL, which is not a primary backend.
+        #[cfg(not(target_arch = "wasm32"))]
+        let default_backends = wgpu::Backends::PRIMARY;
+        #[cfg(target_arch = "wasm32")]
+        let default_backends = wgpu::Backends::all();
+
+        let backends = backend
+            .map(|backend| backend.backends())
+            .unwrap_or(default_backends);
+
+        let instance = wgpu::Instance::new(backends);
+        let surface = create_surface(&instance);
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                force_fallback_adapter: false,
+                compatible_surface: Some(&surface),
+            })
+            .await
+            .ok_or(InitError::RequestAdapter)?;
+
+        // Only request this feature, if it is available. It isn't on the web,
+        // for example. Without it, the wireframe can't be drawn.
+        //
+        // See this issue:
+        // https://github.com/hannobraun/fornjot/issues/33
+        let features = adapter.features() & wgpu::Features::POLYGON_MODE_LINE;
+
+        // Indirect draws aren't available with WebGL either. Geometry is
+        // drawn directly then.
+        let indirect = adapter
+            .get_downlevel_properties()
+            .flags
+            .contains(wgpu::DownlevelFlags::INDIRECT_EXECUTION);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let limits = wgpu::Limits::default();
+        #[cfg(target_arch = "wasm32")]
+        let limits = wgpu::Limits::downlevel_webgl2_defaults();
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    features,
+                    limits,
+                },
+                None,
+            )
+            .await?;
+
+        let gpu = Self {
+            instance,
+            adapter,
+            device,
+            queue,
+            features,
+            indirect,
+        };
+
+        Ok((gpu, surface))
+    }
+}
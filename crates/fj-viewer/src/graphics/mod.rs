@@ -1,19 +1,31 @@
 //! Rendering primitives, routines, and structures.
 
+mod backend;
 mod config_ui;
 mod draw_config;
 mod drawables;
 mod geometries;
+mod gpu;
 mod pipelines;
+mod quality;
 mod renderer;
 mod shaders;
+mod theme;
 mod transform;
 mod uniforms;
 mod vertices;
 
 pub use self::{
+    backend::Backend,
+    config_ui::Status,
     draw_config::DrawConfig,
-    renderer::{DrawError, InitError, Renderer},
+    gpu::Gpu,
+    quality::QualityMetric,
+    renderer::{DrawError, InitError, Renderer, Shortcuts},
+    theme::{Background, Theme, ThemeConfig, ThemePreset},
+    vertices::Surface,
 };
 
 const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+const SHADOW_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+const NORMAL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
@@ -2,22 +2,53 @@ use std::mem::size_of;
 
 use super::{
     shaders::{Shader, Shaders},
-    vertices::Vertex,
-    DEPTH_FORMAT,
+    vertices::{Instance, Vertex},
+    DEPTH_FORMAT, NORMAL_FORMAT, SHADOW_FORMAT,
+};
+
+/// The columns of the instance transforms, after the attributes of a vertex
+const INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+    4 => Float32x4,
+    5 => Float32x4,
+    6 => Float32x4,
+    7 => Float32x4,
+];
+
+/// The second vertex buffer of all pipelines that draw geometry
+const INSTANCE_BUFFER: wgpu::VertexBufferLayout = wgpu::VertexBufferLayout {
+    array_stride: size_of::<Instance>() as u64,
+    step_mode: wgpu::VertexStepMode::Instance,
+    attributes: &INSTANCE_ATTRIBUTES,
 };
 
 #[derive(Debug)]
 pub struct Pipelines {
     pub model: Pipeline,
-    pub mesh: Pipeline,
+    pub mesh: Option<Pipeline>,
     pub lines: Pipeline,
+    pub background: Pipeline,
+    pub shadow: Pipeline,
+    pub normals: Pipeline,
+    pub outline: Pipeline,
 }
 
 impl Pipelines {
+    /// Create all pipelines
+    ///
+    /// The shadow pipeline writes to the shadow map, so it can't use the same
+    /// bind group as the other pipelines, which read from it. It gets one
+    /// with `shadow_bind_group_layout` instead, which only contains the
+    /// uniforms.
+    ///
+    /// The outline pipeline reads the normal buffer through a second bind
+    /// group, with `outline_bind_group_layout`.
     pub fn new(
         device: &wgpu::Device,
         bind_group_layout: &wgpu::BindGroupLayout,
+        shadow_bind_group_layout: &wgpu::BindGroupLayout,
+        outline_bind_group_layout: &wgpu::BindGroupLayout,
         color_format: wgpu::TextureFormat,
+        wireframe: bool,
     ) -> Self {
         let pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -25,6 +56,21 @@ impl Pipelines {
                 bind_group_layouts: &[bind_group_layout],
                 push_constant_ranges: &[],
             });
+        let shadow_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[shadow_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let outline_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[
+                    bind_group_layout,
+                    outline_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
 
         let shaders = Shaders::new(device);
 
@@ -35,22 +81,60 @@ impl Pipelines {
                 shaders.model(),
                 wgpu::PrimitiveTopology::TriangleList,
                 wgpu::PolygonMode::Fill,
+                true,
                 color_format,
             ),
-            mesh: Pipeline::new(
+            // Drawing the wireframe requires an optional feature.
+            mesh: wireframe.then(|| {
+                Pipeline::new(
+                    device,
+                    &pipeline_layout,
+                    shaders.mesh(),
+                    wgpu::PrimitiveTopology::TriangleList,
+                    wgpu::PolygonMode::Line,
+                    true,
+                    color_format,
+                )
+            }),
+            lines: Pipeline::new(
                 device,
                 &pipeline_layout,
-                shaders.mesh(),
+                shaders.lines(),
+                wgpu::PrimitiveTopology::LineList,
+                // The polygon mode has no effect on lines. This one doesn't
+                // require an optional feature.
+                wgpu::PolygonMode::Fill,
+                // Debug information is drawn on top of the model, so it
+                // doesn't get hidden inside of it.
+                false,
+                color_format,
+            ),
+            background: Pipeline::new(
+                device,
+                &pipeline_layout,
+                shaders.background(),
                 wgpu::PrimitiveTopology::TriangleList,
-                wgpu::PolygonMode::Line,
+                wgpu::PolygonMode::Fill,
+                false,
                 color_format,
             ),
-            lines: Pipeline::new(
+            shadow: Pipeline::shadow(
+                device,
+                &shadow_pipeline_layout,
+                shaders.shadow(),
+            ),
+            normals: Pipeline::normals(
                 device,
                 &pipeline_layout,
-                shaders.lines(),
-                wgpu::PrimitiveTopology::LineList,
-                wgpu::PolygonMode::Line,
+                shaders.normals(),
+            ),
+            outline: Pipeline::new(
+                device,
+                &outline_pipeline_layout,
+                shaders.outline(),
+                wgpu::PrimitiveTopology::TriangleList,
+                wgpu::PolygonMode::Fill,
+                false,
                 color_format,
             ),
         }
@@ -67,24 +151,44 @@ impl Pipeline {
         shader: Shader,
         topology: wgpu::PrimitiveTopology,
         polygon_mode: wgpu::PolygonMode,
+        depth_test: bool,
         color_format: wgpu::TextureFormat,
     ) -> Self {
+        let depth_compare = if depth_test {
+            wgpu::CompareFunction::LessEqual
+        } else {
+            wgpu::CompareFunction::Always
+        };
+
+        // Pipelines without a vertex buffer generate their vertices in the
+        // shader.
+        let vertex_buffers = [
+            wgpu::VertexBufferLayout {
+                array_stride: size_of::<Vertex>() as u64,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![
+                    0 => Float32x3,
+                    1 => Float32x3,
+                    2 => Float32x4,
+                    3 => Float32x2,
+                ],
+            },
+            INSTANCE_BUFFER,
+        ];
+        let vertex_buffers: &[_] = if shader.vertex_buffer {
+            &vertex_buffers
+        } else {
+            &[]
+        };
+
         let pipeline =
             device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 label: None,
                 layout: Some(pipeline_layout),
                 vertex: wgpu::VertexState {
                     module: shader.module,
-                    entry_point: "vertex",
-                    buffers: &[wgpu::VertexBufferLayout {
-                        array_stride: size_of::<Vertex>() as u64,
-                        step_mode: wgpu::VertexStepMode::Vertex,
-                        attributes: &wgpu::vertex_attr_array![
-                            0 => Float32x3,
-                            1 => Float32x3,
-                            2 => Float32x4,
-                        ],
-                    }],
+                    entry_point: shader.vertex_entry,
+                    buffers: vertex_buffers,
                 },
                 primitive: wgpu::PrimitiveState {
                     topology,
@@ -97,8 +201,8 @@ impl Pipeline {
                 },
                 depth_stencil: Some(wgpu::DepthStencilState {
                     format: DEPTH_FORMAT,
-                    depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    depth_write_enabled: depth_test,
+                    depth_compare,
                     stencil: wgpu::StencilState {
                         front: wgpu::StencilFaceState::IGNORE,
                         back: wgpu::StencilFaceState::IGNORE,
@@ -128,4 +232,143 @@ impl Pipeline {
 
         Self(pipeline)
     }
+
+    /// Create the pipeline that renders the model into the normal buffer
+    ///
+    /// The model has already been drawn at this point, so only the fragments
+    /// that ended up on screen pass the depth test.
+    fn normals(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        shader: Shader,
+    ) -> Self {
+        let vertex_buffers = [
+            wgpu::VertexBufferLayout {
+                array_stride: size_of::<Vertex>() as u64,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![
+                    0 => Float32x3,
+                    1 => Float32x3,
+                    2 => Float32x4,
+                    3 => Float32x2,
+                ],
+            },
+            INSTANCE_BUFFER,
+        ];
+
+        let pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: None,
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: shader.module,
+                    entry_point: shader.vertex_entry,
+                    buffers: &vertex_buffers,
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    stencil: wgpu::StencilState {
+                        front: wgpu::StencilFaceState::IGNORE,
+                        back: wgpu::StencilFaceState::IGNORE,
+                        read_mask: 0,
+                        write_mask: 0,
+                    },
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: shader.module,
+                    entry_point: shader.frag_entry,
+                    // The alpha channel holds the depth, so there must not be
+                    // any blending.
+                    targets: &[wgpu::ColorTargetState {
+                        format: NORMAL_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }],
+                }),
+                multiview: None,
+            });
+
+        Self(pipeline)
+    }
+
+    /// Create the pipeline that renders the model into the shadow map
+    fn shadow(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        (module, vertex_entry): (&wgpu::ShaderModule, &'static str),
+    ) -> Self {
+        // Only the positions are needed to determine depth.
+        let vertex_buffers = [
+            wgpu::VertexBufferLayout {
+                array_stride: size_of::<Vertex>() as u64,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+            },
+            INSTANCE_BUFFER,
+        ];
+
+        let pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: None,
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module,
+                    entry_point: vertex_entry,
+                    buffers: &vertex_buffers,
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: SHADOW_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    stencil: wgpu::StencilState {
+                        front: wgpu::StencilFaceState::IGNORE,
+                        back: wgpu::StencilFaceState::IGNORE,
+                        read_mask: 0,
+                        write_mask: 0,
+                    },
+                    // Surfaces that face the light at a steep angle would
+                    // shadow themselves otherwise.
+                    bias: wgpu::DepthBiasState {
+                        constant: 2,
+                        slope_scale: 2.0,
+                        clamp: 0.0,
+                    },
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                fragment: None,
+                multiview: None,
+            });
+
+        Self(pipeline)
+    }
 }
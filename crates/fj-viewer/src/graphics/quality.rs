@@ -0,0 +1,99 @@
+//! Coloring of triangles by their quality
+
+use fj_interop::mesh::{Attribute, Channel, Color, Mesh};
+use fj_math::{Point, Scalar, Triangle};
+
+/// A metric for the quality of a triangle
+///
+/// Used to color the triangles of the model, to make problems with the
+/// triangulation visible. Triangles are colored on a scale from green to red.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QualityMetric {
+    /// The aspect ratio of the triangle
+    ///
+    /// Equilateral triangles are green, triangles with an aspect ratio of 5 or
+    /// more are red.
+    AspectRatio,
+
+    /// The area of the triangle, relative to the largest triangle
+    Area,
+
+    /// The deviation of the triangle from the surface it approximates,
+    /// relative to the largest deviation
+    SurfaceDeviation,
+}
+
+impl QualityMetric {
+    /// Cycle through the metrics, and back to no metric at all
+    pub fn next(metric: Option<Self>) -> Option<Self> {
+        match metric {
+            None => Some(Self::AspectRatio),
+            Some(Self::AspectRatio) => Some(Self::Area),
+            Some(Self::Area) => Some(Self::SurfaceDeviation),
+            Some(Self::SurfaceDeviation) => None,
+        }
+    }
+
+    /// Return a human-readable name of the metric
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AspectRatio => "aspect ratio",
+            Self::Area => "area",
+            Self::SurfaceDeviation => "surface deviation",
+        }
+    }
+
+    /// Compute a color for each triangle of the mesh
+    pub fn colors(&self, mesh: &Mesh<Point<3>>) -> Vec<Color> {
+        let values: Vec<_> = match self {
+            Self::AspectRatio => mesh
+                .triangles()
+                .map(|triangle| {
+                    let aspect_ratio =
+                        Triangle::from(triangle.points).aspect_ratio();
+                    (aspect_ratio - Scalar::ONE) / 4.
+                })
+                .collect(),
+            Self::Area => relative(
+                mesh.triangles()
+                    .map(|triangle| Triangle::from(triangle.points).area()),
+            ),
+            Self::SurfaceDeviation => {
+                match mesh.channel(&Attribute::SurfaceDeviation) {
+                    // All corners of a triangle have the same value.
+                    Some(Channel::Scalars(deviations)) => {
+                        relative(deviations.iter().step_by(3).copied())
+                    }
+                    // The deviation is not known, for example because the
+                    // mesh has been decimated.
+                    _ => return vec![UNKNOWN; mesh.triangles().count()],
+                }
+            }
+        };
+
+        values.into_iter().map(color).collect()
+    }
+}
+
+/// Color for triangles whose quality can't be determined
+const UNKNOWN: Color = [127, 127, 127, 255];
+
+fn relative(values: impl Iterator<Item = Scalar>) -> Vec<Scalar> {
+    let values: Vec<_> = values.collect();
+
+    let max = values.iter().copied().max().unwrap_or(Scalar::ZERO);
+    if max == Scalar::ZERO {
+        return vec![Scalar::ZERO; values.len()];
+    }
+
+    values.into_iter().map(|value| value / max).collect()
+}
+
+fn color(value: Scalar) -> Color {
+    let value = value.max(Scalar::ZERO).into_f64().min(1.);
+
+    let red = (value * 255.) as u8;
+    let green = ((1. - value) * 255.) as u8;
+
+    [red, green, 0, 255]
+}
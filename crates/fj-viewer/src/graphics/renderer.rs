@@ -1,44 +1,94 @@
-use std::{io, mem::size_of};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    mem::size_of,
+    num::NonZeroU64,
+    rc::Rc,
+};
 
-use fj_math::{Aabb, Point};
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Aabb, Point, Vector};
+use fj_operations::{instances::Instances, level_of_detail::LevelOfDetail};
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, debug_span, trace_span};
 use wgpu::util::DeviceExt as _;
 use wgpu_glyph::ab_glyph::InvalidFont;
 use winit::dpi::PhysicalSize;
 
-use crate::{camera::Camera, window::Window};
+use crate::{
+    camera::Camera, input, navigation_cube, profiler::Profile,
+    viewer::SurfaceTarget, viewport::Viewport,
+};
 
 use super::{
-    config_ui::ConfigUi, draw_config::DrawConfig, drawables::Drawables,
-    geometries::Geometries, pipelines::Pipelines, transform::Transform,
-    uniforms::Uniforms, vertices::Vertices, DEPTH_FORMAT,
+    backend::Backend,
+    config_ui::{ConfigUi, Status},
+    draw_config::DrawConfig,
+    drawables::{Drawable, Drawables},
+    geometries::{Geometries, Geometry, Level},
+    gpu::Gpu,
+    pipelines::Pipelines,
+    theme::Background,
+    transform::Transform,
+    uniforms::Uniforms,
+    vertices::{Surface, Vertices},
+    DEPTH_FORMAT, NORMAL_FORMAT, SHADOW_FORMAT,
 };
 
+/// Versions of a mesh that are faster to draw, but look the same
+///
+/// Please refer to [`Renderer::update_geometry`].
+#[derive(Clone, Copy, Default)]
+pub struct Shortcuts<'r> {
+    /// Simplified versions of the mesh
+    ///
+    /// They are drawn instead of the mesh, when the camera is too far away to
+    /// make out the difference.
+    pub levels_of_detail: &'r [LevelOfDetail],
+
+    /// Parts of the mesh that are copies of each other
+    ///
+    /// Only the triangles of the original part are uploaded, and drawn once for
+    /// each copy.
+    pub instances: &'r [Instances],
+}
+
 /// Graphics rendering state and target abstraction
 #[derive(Debug)]
 pub struct Renderer {
     surface: wgpu::Surface,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
+    gpu: Rc<Gpu>,
 
     surface_config: wgpu::SurfaceConfiguration,
     depth_view: wgpu::TextureView,
 
     uniform_buffer: wgpu::Buffer,
+    uniform_stride: u64,
     bind_group: wgpu::BindGroup,
 
+    shadow_view: wgpu::TextureView,
+    shadow_bind_group: wgpu::BindGroup,
+
+    normal_view: wgpu::TextureView,
+    outline_bind_group_layout: wgpu::BindGroupLayout,
+    outline_bind_group: wgpu::BindGroup,
+
     geometries: Geometries,
+    navigation_cube: Geometry,
     pipelines: Pipelines,
 
     config_ui: ConfigUi,
 }
 
 impl Renderer {
+    /// The maximum number of viewports that can be drawn at once
+    pub const MAX_VIEWPORTS: usize = 4;
+
     /// Returns a new `Renderer`.
     ///
     /// # Arguments
-    /// - `window` - a `crate::window::Window` with a surface to render onto.
+    /// - `surface_target` - a window, or anything else with a surface to
+    ///   render onto.
     ///
     /// # Examples
     /// ```rust no_run
@@ -46,122 +96,273 @@ impl Renderer {
     ///
     /// // Create window
     /// let event_loop = winit::event_loop::EventLoop::new();
-    /// let window = window::Window::new(&event_loop);
+    /// let window = window::Window::new(&event_loop, None);
     ///
     /// // Attach renderer to the window
     /// let mut renderer = graphics::Renderer::new(&window);
     /// ```
-    pub async fn new(window: &Window) -> Result<Self, InitError> {
-        let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
+    pub async fn new(
+        surface_target: &impl SurfaceTarget,
+    ) -> Result<Self, InitError> {
+        Self::with_backend(surface_target, None).await
+    }
 
-        // This is sound, as `window` is an object to create a surface upon.
-        let surface = unsafe { instance.create_surface(window.inner()) };
+    /// Returns a new `Renderer` that uses the given backend
+    ///
+    /// If no backend is given, the best available one is used, like with
+    /// [`Renderer::new`].
+    pub async fn with_backend(
+        surface_target: &impl SurfaceTarget,
+        backend: Option<Backend>,
+    ) -> Result<Self, InitError> {
+        // This is sound, as `surface_target` is an object to create a surface
+        // upon.
+        let (gpu, surface) = Gpu::new(backend, |instance| unsafe {
+            instance.create_surface(surface_target)
+        })
+        .await?;
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
-            })
-            .await
-            .ok_or(InitError::RequestAdapter)?;
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    // Don't just blindly assume that we can request this
-                    // feature. If it isn't available, that might cause a panic,
-                    // or an error to be returned here.
-                    //
-                    // See this issue:
-                    // https://github.com/hannobraun/fornjot/issues/33
-                    features: wgpu::Features::POLYGON_MODE_LINE,
-                    limits: wgpu::Limits::default(),
-                },
-                None,
-            )
-            .await?;
+        Self::with_surface(surface_target, surface, Rc::new(gpu))
+    }
+
+    /// Returns a new `Renderer` that draws with the GPU of another one
+    ///
+    /// Use [`Renderer::gpu`] to get the GPU of an existing renderer. Returns an
+    /// error, if that GPU can't draw to `surface_target`.
+    pub fn with_gpu(
+        surface_target: &impl SurfaceTarget,
+        gpu: Rc<Gpu>,
+    ) -> Result<Self, InitError> {
+        // This is sound, as `surface_target` is an object to create a surface
+        // upon.
+        let surface = unsafe { gpu.instance.create_surface(surface_target) };
+        if !gpu.adapter.is_surface_supported(&surface) {
+            return Err(InitError::UnsupportedSurface);
+        }
+
+        Self::with_surface(surface_target, surface, gpu)
+    }
+
+    /// Returns the GPU that this renderer draws with
+    pub fn gpu(&self) -> Rc<Gpu> {
+        self.gpu.clone()
+    }
+
+    fn with_surface(
+        surface_target: &impl SurfaceTarget,
+        surface: wgpu::Surface,
+        gpu: Rc<Gpu>,
+    ) -> Result<Self, InitError> {
+        let device = &gpu.device;
+        let queue = &gpu.queue;
+        let indirect = gpu.indirect;
+        let features = gpu.features;
 
         let color_format = surface
-            .get_preferred_format(&adapter)
+            .get_preferred_format(&gpu.adapter)
             .expect("Error determining preferred color format");
 
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: color_format,
-            width: window.width(),
-            height: window.height(),
-            present_mode: wgpu::PresentMode::Mailbox,
+            width: surface_target.size()[0],
+            height: surface_target.size()[1],
+            present_mode: PRESENT_MODE,
         };
-        surface.configure(&device, &surface_config);
+        surface.configure(device, &surface_config);
+
+        let depth_view = Self::create_depth_buffer(device, &surface_config);
 
-        let depth_view = Self::create_depth_buffer(&device, &surface_config);
+        // Each viewport gets its own slot in the uniform buffer, which is
+        // selected using a dynamic offset. Those offsets need to be aligned.
+        let uniform_stride = {
+            let size = size_of::<Uniforms>() as u64;
+            let alignment =
+                device.limits().min_uniform_buffer_offset_alignment as u64;
 
+            (size + alignment - 1) / alignment * alignment
+        };
+        // There's an additional slot for the navigation cube.
+        let uniforms = {
+            let mut uniforms =
+                vec![0; uniform_stride as usize * (Self::MAX_VIEWPORTS + 1)];
+            for slot in uniforms.chunks_mut(uniform_stride as usize) {
+                slot[..size_of::<Uniforms>()].copy_from_slice(
+                    bytemuck::cast_slice(&[Uniforms::default()]),
+                );
+            }
+            uniforms
+        };
         let uniform_buffer =
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: None,
-                contents: bytemuck::cast_slice(&[Uniforms::default()]),
+                contents: &uniforms,
                 usage: wgpu::BufferUsages::UNIFORM
                     | wgpu::BufferUsages::COPY_DST,
             });
+        let uniforms_layout_entry = wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::all(),
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: wgpu::BufferSize::new(
+                    size_of::<Uniforms>() as u64
+                ),
+            },
+            count: None,
+        };
         let bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    uniforms_layout_entry,
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Depth,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(
+                            wgpu::SamplerBindingType::Comparison,
+                        ),
+                        count: None,
+                    },
+                ],
+                label: None,
+            });
+        let shadow_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[uniforms_layout_entry],
+                label: None,
+            });
+
+        let outline_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 entries: &[wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::all(),
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: wgpu::BufferSize::new(size_of::<
-                            Uniforms,
-                        >(
-                        )
-                            as u64),
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float {
+                            filterable: false,
+                        },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
                     },
                     count: None,
                 }],
                 label: None,
             });
+        let normal_view = Self::create_normal_buffer(device, &surface_config);
+        let outline_bind_group = Self::create_outline_bind_group(
+            device,
+            &outline_bind_group_layout,
+            &normal_view,
+        );
+
+        let shadow_view = Self::create_shadow_map(device);
+        let shadow_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: None,
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            // Linear filtering compares the four closest texels, which
+            // already softens the shadows a bit.
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..wgpu::SamplerDescriptor::default()
+        });
+
+        let uniforms_entry = wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: &uniform_buffer,
+                offset: 0,
+                size: NonZeroU64::new(size_of::<Uniforms>() as u64),
+            }),
+        };
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                    buffer: &uniform_buffer,
-                    offset: 0,
-                    size: None,
-                }),
-            }],
+            entries: &[
+                uniforms_entry.clone(),
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&shadow_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&shadow_sampler),
+                },
+            ],
             label: None,
         });
+        let shadow_bind_group =
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &shadow_bind_group_layout,
+                entries: &[uniforms_entry],
+                label: None,
+            });
 
-        let geometries = Geometries::new(
-            &device,
-            &Vertices::empty(),
-            &Vertices::empty(),
-            Aabb {
+        let geometries = Geometries {
+            mesh: Geometry::new(device, queue, indirect, &Vertices::empty()),
+            levels: Vec::new(),
+            lines: Geometry::new(device, queue, indirect, &Vertices::empty()),
+            labels: Vec::new(),
+            aabb: Aabb {
                 min: Point::from([0.0, 0.0, 0.0]),
                 max: Point::from([0.0, 0.0, 0.0]),
             },
+        };
+        let navigation_cube = {
+            let config = DrawConfig {
+                smooth_normals: false,
+                ..DrawConfig::default()
+            };
+            let vertices =
+                Vertices::from_mesh(&navigation_cube::mesh(), &[], &config);
+
+            Geometry::new(device, queue, indirect, &vertices)
+        };
+        let pipelines = Pipelines::new(
+            device,
+            &bind_group_layout,
+            &shadow_bind_group_layout,
+            &outline_bind_group_layout,
+            color_format,
+            features.contains(wgpu::Features::POLYGON_MODE_LINE),
         );
-        let pipelines =
-            Pipelines::new(&device, &bind_group_layout, color_format);
 
-        let config_ui = ConfigUi::new(&device, color_format)?;
+        let mut config_ui = ConfigUi::new(device, color_format)?;
+        config_ui.set_scale_factor(surface_target.scale_factor() as f32);
 
         Ok(Self {
             surface,
-            device,
-            queue,
+            gpu,
 
             surface_config,
             depth_view,
 
             uniform_buffer,
+            uniform_stride,
             bind_group,
 
+            shadow_view,
+            shadow_bind_group,
+
+            normal_view,
+            outline_bind_group_layout,
+            outline_bind_group,
+
             geometries,
+            navigation_cube,
             pipelines,
 
             config_ui,
@@ -169,13 +370,110 @@ impl Renderer {
     }
 
     /// Updates the geometry of the model being rendered.
+    ///
+    /// `shortcuts` are used to draw `mesh` faster, where that doesn't change
+    /// how it looks. `surfaces` contains the surface of each face of the mesh,
+    /// indexed by its ID. Needs to be called again, if the normals, quality
+    /// metric, selected face, or debug categories in `config` change.
     pub fn update_geometry(
         &mut self,
-        mesh: Vertices,
-        lines: Vertices,
+        mesh: &Mesh<Point<3>>,
+        shortcuts: Shortcuts,
+        surfaces: &[Surface],
+        debug_info: &DebugInfo,
         aabb: Aabb<3>,
+        config: &DrawConfig,
     ) {
-        self.geometries = Geometries::new(&self.device, &mesh, &lines, aabb);
+        let _span = debug_span!("update_geometry").entered();
+
+        // Copies are drawn from the triangles of the part they're copied
+        // from. That doesn't work, if the triangles of some faces are colored
+        // or left out individually.
+        let instances: &[Instances] =
+            if config.quality.is_some() || config.selected_face.is_some() {
+                &[]
+            } else {
+                shortcuts.instances
+            };
+        let batches = if instances.is_empty() {
+            let vertices = Vertices::from_mesh(mesh, surfaces, config);
+            vec![(vertices, vec![fj_math::Transform::identity()])]
+        } else {
+            batches(mesh, instances)
+                .into_iter()
+                .map(|(mesh, transforms)| {
+                    (Vertices::from_mesh(&mesh, surfaces, config), transforms)
+                })
+                .collect()
+        };
+
+        // The quality metric is about the triangles of the full mesh. Drawing
+        // simplified ones instead would be misleading.
+        let levels = if config.quality.is_some() {
+            Vec::new()
+        } else {
+            shortcuts
+                .levels_of_detail
+                .iter()
+                .map(|level| {
+                    let vertices =
+                        Vertices::from_mesh(&level.mesh, surfaces, config);
+
+                    Level {
+                        geometry: Geometry::new(
+                            &self.gpu.device,
+                            &self.gpu.queue,
+                            self.gpu.indirect,
+                            &vertices,
+                        ),
+                        error: level.error.into_f64(),
+                    }
+                })
+                .collect()
+        };
+
+        let lines =
+            Vertices::from_debug_info(debug_info, &config.debug_categories);
+
+        let labels = debug_info
+            .geometry
+            .iter()
+            .filter(|geometry| {
+                config.debug_categories.contains(&geometry.category)
+            })
+            .filter_map(|geometry| {
+                let label = geometry.label.clone()?;
+                let anchor = geometry.anchor()?;
+                Some((anchor, label))
+            })
+            .collect();
+
+        self.geometries = Geometries {
+            mesh: Geometry::instanced(
+                &self.gpu.device,
+                &self.gpu.queue,
+                self.gpu.indirect,
+                &batches,
+            ),
+            levels,
+            lines: Geometry::new(
+                &self.gpu.device,
+                &self.gpu.queue,
+                self.gpu.indirect,
+                &lines,
+            ),
+            labels,
+            aabb,
+        };
+    }
+
+    /// Changes the number of physical pixels per logical pixel
+    ///
+    /// Text is scaled by this, so it stays the same size on displays with
+    /// different pixel densities. The render surface itself is resized by
+    /// [`Renderer::handle_resize`].
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.config_ui.set_scale_factor(scale_factor as f32);
     }
 
     /// Resizes the render surface.
@@ -186,83 +484,288 @@ impl Renderer {
         self.surface_config.width = size.width;
         self.surface_config.height = size.height;
 
-        self.surface.configure(&self.device, &self.surface_config);
+        self.surface
+            .configure(&self.gpu.device, &self.surface_config);
 
         let depth_view =
-            Self::create_depth_buffer(&self.device, &self.surface_config);
+            Self::create_depth_buffer(&self.gpu.device, &self.surface_config);
         self.depth_view = depth_view;
+
+        self.normal_view =
+            Self::create_normal_buffer(&self.gpu.device, &self.surface_config);
+        self.outline_bind_group = Self::create_outline_bind_group(
+            &self.gpu.device,
+            &self.outline_bind_group_layout,
+            &self.normal_view,
+        );
     }
 
     /// Draws the renderer, camera, and config state to the window.
+    ///
+    /// The model is drawn once for each of the `views`, using the camera into
+    /// the viewport it is paired with. At most [`Renderer::MAX_VIEWPORTS`]
+    /// views are drawn. The `navigation_cube` is drawn on top of them, if
+    /// provided.
+    ///
+    /// Any text in `status` is displayed at the bottom of the window.
+    /// `profile` is only displayed, if the profiler overlay is enabled in
+    /// `config`.
     pub fn draw(
         &mut self,
-        camera: &Camera,
+        views: &[(&Camera, Viewport)],
+        navigation_cube: Option<(&Camera, Viewport)>,
         config: &DrawConfig,
+        status: Status,
+        profile: &Profile,
+        input: &input::Handler,
     ) -> Result<(), DrawError> {
-        let aspect_ratio = self.surface_config.width as f64
-            / self.surface_config.height as f64;
-        let uniforms = Uniforms {
-            transform: Transform::for_vertices(camera, aspect_ratio),
-            transform_normals: Transform::for_normals(camera),
-        };
+        let _span = trace_span!("draw").entered();
 
-        self.queue.write_buffer(
-            &self.uniform_buffer,
-            0,
-            bytemuck::cast_slice(&[uniforms]),
-        );
+        // Viewports without any area can't be drawn into. This happens, while
+        // the window is minimized.
+        let views = views
+            .iter()
+            .filter(|(_, viewport)| viewport.width > 0 && viewport.height > 0)
+            .take(Self::MAX_VIEWPORTS);
+
+        let [background_top, background_bottom] =
+            config.theme.background.colors();
+        let edge_color = config.theme.edge_color.unwrap_or([0.0; 4]);
+        let outline_color = config.theme.outline_color;
+
+        let shadows = config.draw_model && config.draw_shadows;
+        let [x, y, z] = KEY_LIGHT.map(|s| s as f32);
+        let key_light = [x, y, z, if shadows { 1.0 } else { 0.0 }];
+
+        let mut transforms = Vec::new();
+        for (i, (camera, viewport)) in views.enumerate() {
+            let transform =
+                Transform::for_vertices(camera, viewport.aspect_ratio());
+            let uniforms = Uniforms {
+                transform,
+                transform_normals: Transform::for_normals(camera),
+                transform_shadows: Transform::for_shadows(
+                    camera,
+                    &self.geometries.aabb,
+                    Vector::from(KEY_LIGHT),
+                ),
+                edge_color,
+                background_top,
+                background_bottom,
+                key_light,
+                outline_color,
+            };
+
+            let offset = self.uniform_stride * i as u64;
+            self.gpu.queue.write_buffer(
+                &self.uniform_buffer,
+                offset,
+                bytemuck::cast_slice(&[uniforms]),
+            );
+
+            // The wireframe shows the triangles of the full mesh, so the model
+            // needs to match it.
+            let mesh = if config.draw_mesh {
+                &self.geometries.mesh
+            } else {
+                self.geometries.mesh_for(camera, viewport)
+            };
+
+            transforms.push((transform, *viewport, offset as u32, mesh));
+        }
 
         let surface_texture = self.surface.get_current_texture()?;
         let color_view = surface_texture
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        let mut encoder = self.device.create_command_encoder(
+        let mut encoder = self.gpu.device.create_command_encoder(
             &wgpu::CommandEncoderDescriptor { label: None },
         );
 
-        self.clear_views(&mut encoder, &color_view);
+        self.clear_views(&mut encoder, &color_view, background_bottom);
+
+        // The background colors are the same in each slot, so the gradient can
+        // use the first one.
+        if let Background::Gradient { .. } = config.theme.background {
+            if !transforms.is_empty() {
+                self.draw_background(&mut encoder, &color_view);
+            }
+        }
+
+        for (transform, viewport, offset, mesh) in &transforms {
+            let drawables =
+                Drawables::new(&self.geometries, mesh, &self.pipelines);
+
+            // All viewports share the same shadow map, as the light moves
+            // along with each camera. So it's drawn again for each of them.
+            if shadows {
+                self.draw_shadows(&mut encoder, *offset, mesh);
+            }
+
+            if config.draw_model {
+                drawables.model.draw(
+                    &mut encoder,
+                    &color_view,
+                    &self.depth_view,
+                    &self.bind_group,
+                    *offset,
+                    viewport,
+                    transform,
+                );
+
+                // The outlines go on top of the model, but below everything
+                // else.
+                if config.draw_outlines {
+                    self.clear_normals(&mut encoder);
+                    drawables.normals.draw(
+                        &mut encoder,
+                        &self.normal_view,
+                        &self.depth_view,
+                        &self.bind_group,
+                        *offset,
+                        viewport,
+                        transform,
+                    );
+                    self.draw_outlines(
+                        &mut encoder,
+                        &color_view,
+                        *offset,
+                        viewport,
+                    );
+                }
+            }
 
-        let drawables = Drawables::new(&self.geometries, &self.pipelines);
+            let mut drawn = Vec::new();
+            if config.draw_mesh {
+                drawn.extend(&drawables.mesh);
+            }
+            if config.draw_debug {
+                drawn.push(&drawables.lines);
+            }
 
-        if config.draw_model {
-            drawables.model.draw(
+            for drawable in drawn {
+                drawable.draw(
+                    &mut encoder,
+                    &color_view,
+                    &self.depth_view,
+                    &self.bind_group,
+                    *offset,
+                    viewport,
+                    transform,
+                );
+            }
+
+            let size = [viewport.width, viewport.height].map(|s| s as f32);
+            let origin = [viewport.x, viewport.y].map(|s| s as f32);
+            let project = |anchor: Point<3>| {
+                let [x, y] = transform.project(anchor, size)?;
+                Some([origin[0] + x, origin[1] + y])
+            };
+
+            if config.draw_debug {
+                self.config_ui.queue_labels(
+                    self.geometries.labels.iter().filter_map(
+                        |(anchor, label)| {
+                            Some((project(*anchor)?, label.as_str()))
+                        },
+                    ),
+                );
+            }
+            self.config_ui
+                .queue_labels(status.annotations.iter().filter_map(
+                    |&(anchor, label)| Some((project(anchor)?, label)),
+                ));
+        }
+
+        if let Some((camera, viewport)) = navigation_cube {
+            let transform =
+                Transform::for_vertices(camera, viewport.aspect_ratio());
+            let uniforms = Uniforms {
+                transform,
+                transform_normals: Transform::for_normals(camera),
+                transform_shadows: Transform::identity(),
+                edge_color,
+                background_top,
+                background_bottom,
+                // The cube doesn't cast shadows.
+                key_light: [x, y, z, 0.0],
+                outline_color,
+            };
+
+            let offset = self.uniform_stride * Self::MAX_VIEWPORTS as u64;
+            self.gpu.queue.write_buffer(
+                &self.uniform_buffer,
+                offset,
+                bytemuck::cast_slice(&[uniforms]),
+            );
+
+            // The cube is an overlay, so it must not be hidden by the model.
+            self.clear_depth(&mut encoder, &color_view);
+
+            Drawable::new(&self.navigation_cube, &self.pipelines.model).draw(
                 &mut encoder,
                 &color_view,
                 &self.depth_view,
                 &self.bind_group,
+                offset as u32,
+                &viewport,
+                &transform,
             );
         }
-        if config.draw_mesh {
-            drawables.mesh.draw(
-                &mut encoder,
-                &color_view,
-                &self.depth_view,
-                &self.bind_group,
+
+        self.config_ui.set_text_color(config.theme.text_color);
+
+        if let Some(progress) = status.progress {
+            self.config_ui
+                .queue_status(progress, self.surface_config.height as f32);
+        }
+        if let Some(status_bar) = status.status_bar {
+            self.config_ui.queue_status_bar(
+                status_bar,
+                self.surface_config.height as f32,
             );
         }
-        if config.draw_debug {
-            drawables.lines.draw(
-                &mut encoder,
-                &color_view,
-                &self.depth_view,
-                &self.bind_group,
+        if let Some(models) = status.models {
+            self.config_ui.queue_models(
+                models,
+                [self.surface_config.width, self.surface_config.height]
+                    .map(|s| s as f32),
+            );
+        }
+        if let Some(bom) = status.bom {
+            self.config_ui.queue_bom(
+                bom,
+                [self.surface_config.width, self.surface_config.height]
+                    .map(|s| s as f32),
             );
         }
+        if let Some(error) = status.error {
+            self.config_ui.queue_error(
+                error,
+                [self.surface_config.width, self.surface_config.height]
+                    .map(|s| s as f32),
+            );
+        }
+        if config.draw_profiler {
+            self.config_ui
+                .queue_profile(profile, self.surface_config.width as f32);
+        }
 
         self.config_ui
             .draw(
-                &self.device,
+                &self.gpu.device,
                 &mut encoder,
                 &color_view,
                 &self.surface_config,
                 &self.geometries.aabb,
                 config,
+                input,
             )
             .map_err(DrawError::Text)?;
 
         let command_buffer = encoder.finish();
-        self.queue.submit(Some(command_buffer));
+        self.gpu.queue.submit(Some(command_buffer));
 
         debug!("Presenting...");
         surface_texture.present();
@@ -292,18 +795,246 @@ impl Renderer {
         texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
 
+    fn create_normal_buffer(
+        device: &wgpu::Device,
+        surface_config: &wgpu::SurfaceConfiguration,
+    ) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: surface_config.width,
+                height: surface_config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: NORMAL_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    fn create_outline_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        normal_view: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(normal_view),
+            }],
+            label: None,
+        })
+    }
+
+    fn create_shadow_map(device: &wgpu::Device) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: SHADOW_MAP_SIZE,
+                height: SHADOW_MAP_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SHADOW_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Draw `mesh` into the shadow map, as seen from the key light
+    ///
+    /// Uses the shadow transform in the uniforms at `offset`.
+    fn draw_shadows(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        offset: u32,
+        mesh: &Geometry,
+    ) {
+        let mut render_pass =
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[],
+                depth_stencil_attachment: Some(
+                    wgpu::RenderPassDepthStencilAttachment {
+                        view: &self.shadow_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: true,
+                        }),
+                        stencil_ops: None,
+                    },
+                ),
+            });
+
+        render_pass.set_pipeline(&self.pipelines.shadow.0);
+        render_pass.set_bind_group(0, &self.shadow_bind_group, &[offset]);
+
+        // The shadow map covers the whole model, so there's nothing to cull.
+        mesh.draw(&mut render_pass, None);
+    }
+
+    /// Clear the normal buffer, so pixels without a model are recognized
+    fn clear_normals(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: &self.normal_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+    }
+
+    /// Draw outlines where the normal buffer has edges
+    ///
+    /// Expects the model to have been drawn into the normal buffer, using the
+    /// uniforms at `offset`.
+    fn draw_outlines(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        offset: u32,
+        viewport: &Viewport,
+    ) {
+        let mut render_pass =
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: Some(
+                    wgpu::RenderPassDepthStencilAttachment {
+                        view: &self.depth_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: true,
+                        }),
+                        stencil_ops: None,
+                    },
+                ),
+            });
+
+        render_pass.set_viewport(
+            viewport.x as f32,
+            viewport.y as f32,
+            viewport.width as f32,
+            viewport.height as f32,
+            0.,
+            1.,
+        );
+        render_pass.set_scissor_rect(
+            viewport.x,
+            viewport.y,
+            viewport.width,
+            viewport.height,
+        );
+
+        render_pass.set_pipeline(&self.pipelines.outline.0);
+        render_pass.set_bind_group(0, &self.bind_group, &[offset]);
+        render_pass.set_bind_group(1, &self.outline_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn clear_depth(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+    ) {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: Some(
+                wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                },
+            ),
+        });
+    }
+
+    fn draw_background(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+    ) {
+        let mut render_pass =
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: Some(
+                    wgpu::RenderPassDepthStencilAttachment {
+                        view: &self.depth_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: true,
+                        }),
+                        stencil_ops: None,
+                    },
+                ),
+            });
+
+        render_pass.set_pipeline(&self.pipelines.background.0);
+        render_pass.set_bind_group(0, &self.bind_group, &[0]);
+        render_pass.draw(0..3, 0..1);
+    }
+
     fn clear_views(
         &self,
         encoder: &mut wgpu::CommandEncoder,
         view: &wgpu::TextureView,
+        [r, g, b, a]: [f32; 4],
     ) {
+        let color = wgpu::Color {
+            r: r.into(),
+            g: g.into(),
+            b: b.into(),
+            a: a.into(),
+        };
+
         encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: None,
             color_attachments: &[wgpu::RenderPassColorAttachment {
                 view,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                    load: wgpu::LoadOp::Clear(color),
                     store: true,
                 },
             }],
@@ -321,6 +1052,21 @@ impl Renderer {
     }
 }
 
+/// The direction towards the key light, in view space
+///
+/// The key light is above and to the right of the camera, so the shadows it
+/// casts are visible. The other lights are defined in the shader.
+const KEY_LIGHT: [f64; 3] = [0.36, 0.48, 0.8];
+
+/// The width and height of the shadow map, in texels
+const SHADOW_MAP_SIZE: u32 = 2048;
+
+// Browsers only support waiting for the vertical blank.
+#[cfg(not(target_arch = "wasm32"))]
+const PRESENT_MODE: wgpu::PresentMode = wgpu::PresentMode::Mailbox;
+#[cfg(target_arch = "wasm32")]
+const PRESENT_MODE: wgpu::PresentMode = wgpu::PresentMode::Fifo;
+
 /// Error describing the set of render surface initialization errors
 #[derive(Error, Debug)]
 pub enum InitError {
@@ -332,6 +1078,12 @@ pub enum InitError {
     /// Graphics accelerator acquisition error
     RequestAdapter,
 
+    #[error("GPU can't draw to surface")]
+    /// The GPU of another renderer is not compatible with the surface
+    ///
+    /// See [`Renderer::with_gpu`].
+    UnsupportedSurface,
+
     #[error("Error requesting device: {0}")]
     /// Device request errors
     ///
@@ -360,3 +1112,62 @@ pub enum DrawError {
     /// Text rasterisation error.
     Text(String),
 }
+
+/// Split a mesh into batches of triangles, and the transforms to draw them with
+///
+/// The first batch contains all triangles that aren't part of any `instances`,
+/// and is drawn once. Each of the others contains the triangles of a part that
+/// is copied, and is drawn for the part, as well as for each of its copies.
+fn batches(
+    mesh: &Mesh<Point<3>>,
+    instances: &[Instances],
+) -> Vec<(Mesh<Point<3>>, Vec<fj_math::Transform>)> {
+    let mut batch_of_face = BTreeMap::new();
+    let mut copied = BTreeSet::new();
+    for (i, instances) in instances.iter().enumerate() {
+        batch_of_face.extend(instances.faces.iter().map(|&face| (face, i + 1)));
+        for copy in &instances.copies {
+            copied.extend(copy.faces.iter().copied());
+        }
+    }
+
+    let mut meshes: Vec<_> =
+        (0..=instances.len()).map(|_| Mesh::new()).collect();
+    let mut kept = vec![Vec::new(); meshes.len()];
+
+    for (i, triangle) in mesh.triangles().enumerate() {
+        let face = triangle.face;
+        if face.map_or(false, |face| copied.contains(&face)) {
+            continue;
+        }
+
+        let batch = face
+            .and_then(|face| batch_of_face.get(&face).copied())
+            .unwrap_or(0);
+        meshes[batch].push_triangle_with_face(
+            triangle.points,
+            triangle.color,
+            triangle.face,
+        );
+        kept[batch].push(i);
+    }
+
+    // Channels are removed by adding triangles, so they go in last.
+    for (batch, kept) in meshes.iter_mut().zip(&kept) {
+        for (attribute, channel) in mesh.channels() {
+            batch
+                .set_channel(attribute.clone(), channel.select_triangles(kept));
+        }
+    }
+
+    let transforms = Some(vec![fj_math::Transform::identity()])
+        .into_iter()
+        .chain(instances.iter().map(|instances| {
+            Some(fj_math::Transform::identity())
+                .into_iter()
+                .chain(instances.copies.iter().map(|copy| copy.transform))
+                .collect()
+        }));
+
+    meshes.into_iter().zip(transforms).collect()
+}
@@ -2,51 +2,250 @@ struct VertexOutput {
     [[builtin(position)]] position: vec4<f32>;
     [[location(0)]] normal: vec3<f32>;
     [[location(1)]] color: vec4<f32>;
+    [[location(2)]] metallic_roughness: vec2<f32>;
+    [[location(3)]] shadow_position: vec3<f32>;
+    [[location(4)]] view_depth: f32;
 };
 
 struct Uniforms {
     transform: mat4x4<f32>;
     transform_normals: mat4x4<f32>;
+    transform_shadows: mat4x4<f32>;
+    edge_color: vec4<f32>;
+    background_top: vec4<f32>;
+    background_bottom: vec4<f32>;
+    key_light: vec4<f32>;
+    outline_color: vec4<f32>;
 };
 
 [[group(0), binding(0)]]
 var<uniform> uniforms: Uniforms;
 
+[[group(0), binding(1)]]
+var shadow_map: texture_depth_2d;
+[[group(0), binding(2)]]
+var shadow_sampler: sampler_comparison;
+
+// The normals of the model in view space, and the inverse of their depth
+//
+// Pixels that don't show the model are all zeros.
+[[group(1), binding(0)]]
+var normal_buffer: texture_2d<f32>;
+
 [[stage(vertex)]]
 fn vertex(
     [[location(0)]] position: vec3<f32>,
     [[location(1)]] normal: vec3<f32>,
     [[location(2)]] color: vec4<f32>,
+    [[location(3)]] metallic_roughness: vec2<f32>,
+    [[location(4)]] instance_0: vec4<f32>,
+    [[location(5)]] instance_1: vec4<f32>,
+    [[location(6)]] instance_2: vec4<f32>,
+    [[location(7)]] instance_3: vec4<f32>,
 )
     -> VertexOutput
 {
+    // Instances are only ever moved rigidly, so this works for the normals
+    // too.
+    let instance = mat4x4<f32>(instance_0, instance_1, instance_2, instance_3);
+    let model_position = instance * vec4<f32>(position, 1.0);
+    let model_normal = instance * vec4<f32>(normal, 0.0);
+
     var out: VertexOutput;
-    out.normal = (uniforms.transform_normals * vec4<f32>(normal, 0.0)).xyz;
-    out.position = uniforms.transform * vec4<f32>(position, 1.0);
+    out.normal = (uniforms.transform_normals * model_normal).xyz;
+    out.position = uniforms.transform * model_position;
+    // For a perspective projection, that's the distance from the camera
+    // plane.
+    out.view_depth = out.position.w;
     // We use premultiplied alpha blending.
     out.color = vec4<f32>(color.rgb * color.a, color.a);
+    out.metallic_roughness = metallic_roughness;
+    out.shadow_position = (uniforms.transform_shadows * model_position).xyz;
 
     return out;
 }
 
+[[stage(vertex)]]
+fn vertex_shadow(
+    [[location(0)]] position: vec3<f32>,
+    [[location(4)]] instance_0: vec4<f32>,
+    [[location(5)]] instance_1: vec4<f32>,
+    [[location(6)]] instance_2: vec4<f32>,
+    [[location(7)]] instance_3: vec4<f32>,
+)
+    -> [[builtin(position)]] vec4<f32>
+{
+    let instance = mat4x4<f32>(instance_0, instance_1, instance_2, instance_3);
+    return uniforms.transform_shadows * instance * vec4<f32>(position, 1.0);
+}
+
 let pi: f32 = 3.14159265359;
 
+// The model is shaded using the metallic/roughness model of glTF, with a
+// Cook-Torrance BRDF.
+//
+// It's lit by a key light and a fill light, which move along with the camera,
+// and by an analytic environment, a bright sky above a darker floor. Only the
+// key light casts shadows. All directions are in view space, where the viewer
+// is on the positive z-axis. The viewer is treated as being infinitely far
+// away, which is good enough for highlights.
+
+// The GGX/Trowbridge-Reitz normal distribution function
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+
+    return a2 / (pi * d * d);
+}
+
+// The Schlick-GGX geometry function, for one of the light or view directions
+fn geometry_schlick_ggx(n_dot_x: f32, roughness: f32) -> f32 {
+    let r = roughness + 1.0;
+    let k = r * r / 8.0;
+
+    return n_dot_x / (n_dot_x * (1.0 - k) + k);
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    let f = pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+    return f0 + (vec3<f32>(1.0, 1.0, 1.0) - f0) * f;
+}
+
+// A rough approximation of the integrated specular BRDF
+//
+// Stands in for the lookup table that's usually used with image-based
+// lighting. See "Physically Based Shading on Mobile", by Brian Karis.
+fn environment_brdf(
+    f0: vec3<f32>,
+    roughness: f32,
+    n_dot_v: f32,
+) -> vec3<f32> {
+    let c0 = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
+    let c1 = vec4<f32>(1.0, 0.0425, 1.04, -0.04);
+    let r = c0 * roughness + c1;
+    let a004 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    let ab = vec2<f32>(-1.04, 1.04) * a004 + r.zw;
+
+    return f0 * ab.x + vec3<f32>(ab.y, ab.y, ab.y);
+}
+
+// The light that comes from the environment, from a direction
+//
+// Rougher surfaces see a blurrier environment, so the horizon gets softer.
+fn environment(direction: vec3<f32>, blur: f32) -> vec3<f32> {
+    let sky = vec3<f32>(0.55, 0.58, 0.62);
+    let ground = vec3<f32>(0.20, 0.19, 0.18);
+
+    let softness = blur + 0.05;
+    let t = smoothstep(-softness, softness, direction.y);
+    return ground + (sky - ground) * t;
+}
+
+// How much of the key light reaches a point, from `0.0` to `1.0`
+//
+// Averages several samples of the shadow map around the point, which softens
+// the edges of the shadows.
+fn key_light_visibility(shadow_position: vec3<f32>) -> f32 {
+    let uv = shadow_position.xy * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5, 0.5);
+    // Keeps surfaces from shadowing themselves.
+    let depth = shadow_position.z - 0.002;
+    let texel = 1.5 / f32(textureDimensions(shadow_map).x);
+
+    var visibility = 0.0;
+    for (var x: i32 = -1; x <= 1; x = x + 1) {
+        for (var y: i32 = -1; y <= 1; y = y + 1) {
+            let offset = vec2<f32>(f32(x), f32(y)) * texel;
+            visibility = visibility + textureSampleCompareLevel(
+                shadow_map,
+                shadow_sampler,
+                uv + offset,
+                depth,
+            );
+        }
+    }
+
+    return visibility / 9.0;
+}
+
+fn direct_light(
+    n: vec3<f32>,
+    v: vec3<f32>,
+    l: vec3<f32>,
+    radiance: f32,
+    albedo: vec3<f32>,
+    f0: vec3<f32>,
+    metallic: f32,
+    roughness: f32,
+) -> vec3<f32> {
+    let h = normalize(v + l);
+    let n_dot_l = max(dot(n, l), 0.0);
+    let n_dot_v = max(dot(n, v), 0.0);
+    let n_dot_h = max(dot(n, h), 0.0);
+
+    let f = fresnel_schlick(max(dot(h, v), 0.0), f0);
+    let d = distribution_ggx(n_dot_h, roughness);
+    let g = geometry_schlick_ggx(n_dot_v, roughness)
+        * geometry_schlick_ggx(n_dot_l, roughness);
+    let specular = f * d * g / (4.0 * n_dot_v * n_dot_l + 0.0001);
+
+    // Metals don't have a diffuse component. Whatever isn't reflected by
+    // them is absorbed.
+    let diffuse =
+        (vec3<f32>(1.0, 1.0, 1.0) - f) * (1.0 - metallic) * albedo / pi;
+
+    return (diffuse + specular) * radiance * n_dot_l;
+}
+
 [[stage(fragment)]]
 fn frag_model(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    let light = vec3<f32>(0.0, 0.0, -1.0);
+    let n = normalize(in.normal);
+    let v = vec3<f32>(0.0, 0.0, 1.0);
 
-    let angle = acos(dot(light, -in.normal));
-    let f_angle = angle / (pi / 2.0);
+    let metallic = clamp(in.metallic_roughness.x, 0.0, 1.0);
+    // Perfectly smooth surfaces would have infinitely small highlights.
+    let roughness = clamp(in.metallic_roughness.y, 0.04, 1.0);
 
-    let f_normal = max(1.0 - f_angle, 0.0);
+    // The color is premultiplied, but needs to be shaded without alpha.
+    let albedo = in.color.rgb / max(in.color.a, 0.0001);
+    // Non-metals reflect about 4% of the light at normal incidence.
+    let dielectric = vec3<f32>(0.04, 0.04, 0.04);
+    let f0 = dielectric + (albedo - dielectric) * metallic;
 
-    let color = vec4<f32>(in.color.rgb * f_normal, in.color.a);
+    var key_radiance = 2.0;
+    if (uniforms.key_light.w > 0.0) {
+        key_radiance = key_radiance * key_light_visibility(in.shadow_position);
+    }
 
-    return color;
+    let key = normalize(uniforms.key_light.xyz);
+    let fill = normalize(vec3<f32>(-0.6, -0.1, 0.8));
+
+    var color =
+        direct_light(n, v, key, key_radiance, albedo, f0, metallic, roughness)
+        + direct_light(n, v, fill, 0.8, albedo, f0, metallic, roughness);
+
+    let n_dot_v = max(dot(n, v), 0.0);
+    let r = reflect(-v, n);
+    color = color
+        + environment(n, 1.0) * albedo * (1.0 - metallic)
+        + environment(r, roughness) * environment_brdf(f0, roughness, n_dot_v);
+
+    return vec4<f32>(color * in.color.a, in.color.a);
+}
+
+[[stage(fragment)]]
+fn frag_normals(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // The inverse of the depth changes linearly across flat surfaces, from
+    // pixel to pixel, which makes it easy to detect jumps.
+    return vec4<f32>(normalize(in.normal), 1.0 / in.view_depth);
 }
 
 [[stage(fragment)]]
 fn frag_mesh(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    if (uniforms.edge_color.a > 0.0) {
+        return uniforms.edge_color;
+    }
+
     return vec4<f32>(1.0 - in.color.rgb, in.color.a);
 }
 
@@ -54,3 +253,79 @@ fn frag_mesh(in: VertexOutput) -> [[location(0)]] vec4<f32> {
 fn frag_lines(in: VertexOutput) -> [[location(0)]] vec4<f32> {
     return vec4<f32>(in.color.rgb, in.color.a);
 }
+
+struct BackgroundOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] color: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vertex_background([[builtin(vertex_index)]] index: u32)
+    -> BackgroundOutput
+{
+    // A single triangle that covers the whole screen.
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+
+    var out: BackgroundOutput;
+    out.position = vec4<f32>(x, y, 1.0, 1.0);
+    out.color = mix(
+        uniforms.background_bottom,
+        uniforms.background_top,
+        (y + 1.0) / 2.0,
+    );
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn frag_background(in: BackgroundOutput) -> [[location(0)]] vec4<f32> {
+    return in.color;
+}
+
+fn load_normal(pixel: vec2<i32>) -> vec4<f32> {
+    let last = textureDimensions(normal_buffer) - vec2<i32>(1, 1);
+    return textureLoad(normal_buffer, clamp(pixel, vec2<i32>(0, 0), last), 0);
+}
+
+// Draws outlines where the depth of the model jumps, at its silhouette, and
+// where its normals change sharply, at creases
+[[stage(fragment)]]
+fn frag_outline(in: BackgroundOutput) -> [[location(0)]] vec4<f32> {
+    let pixel = vec2<i32>(in.position.xy);
+    let center = load_normal(pixel);
+
+    // The outlines are drawn on the model, not next to it.
+    if (center.w <= 0.0) {
+        discard;
+    }
+
+    let left = load_normal(pixel - vec2<i32>(1, 0));
+    let right = load_normal(pixel + vec2<i32>(1, 0));
+    let up = load_normal(pixel - vec2<i32>(0, 1));
+    let down = load_normal(pixel + vec2<i32>(0, 1));
+
+    // On flat surfaces, the inverse depth of the neighbors averages out to
+    // that of the center. Pixels without a model have a depth of zero, so
+    // the silhouette is detected here too.
+    let jump = max(
+        abs(left.w + right.w - 2.0 * center.w),
+        abs(up.w + down.w - 2.0 * center.w),
+    );
+    let depth_edge = jump / center.w > 0.05;
+
+    let n = center.xyz;
+    let crease = max(
+        max(1.0 - dot(n, left.xyz), 1.0 - dot(n, right.xyz)),
+        max(1.0 - dot(n, up.xyz), 1.0 - dot(n, down.xyz)),
+    );
+    let normal_edge = crease > 0.15;
+
+    if (!depth_edge && !normal_edge) {
+        discard;
+    }
+
+    // We use premultiplied alpha blending.
+    let color = uniforms.outline_color;
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
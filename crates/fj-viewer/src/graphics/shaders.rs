@@ -18,6 +18,8 @@ impl Shaders {
     pub fn model(&self) -> Shader {
         Shader {
             module: &self.0,
+            vertex_entry: "vertex",
+            vertex_buffer: true,
             frag_entry: "frag_model",
         }
     }
@@ -25,6 +27,8 @@ impl Shaders {
     pub fn mesh(&self) -> Shader {
         Shader {
             module: &self.0,
+            vertex_entry: "vertex",
+            vertex_buffer: true,
             frag_entry: "frag_mesh",
         }
     }
@@ -32,13 +36,51 @@ impl Shaders {
     pub fn lines(&self) -> Shader {
         Shader {
             module: &self.0,
+            vertex_entry: "vertex",
+            vertex_buffer: true,
             frag_entry: "frag_lines",
         }
     }
+
+    pub fn normals(&self) -> Shader {
+        Shader {
+            module: &self.0,
+            vertex_entry: "vertex",
+            vertex_buffer: true,
+            frag_entry: "frag_normals",
+        }
+    }
+
+    /// The outline shader covers the viewport, like the background shader
+    pub fn outline(&self) -> Shader {
+        Shader {
+            module: &self.0,
+            vertex_entry: "vertex_background",
+            vertex_buffer: false,
+            frag_entry: "frag_outline",
+        }
+    }
+
+    /// The shadow shader only has a vertex stage, as it only writes depth
+    pub fn shadow(&self) -> (&wgpu::ShaderModule, &'static str) {
+        (&self.0, "vertex_shadow")
+    }
+
+    /// The background shader generates its own vertices
+    pub fn background(&self) -> Shader {
+        Shader {
+            module: &self.0,
+            vertex_entry: "vertex_background",
+            vertex_buffer: false,
+            frag_entry: "frag_background",
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
 pub struct Shader<'r> {
     pub module: &'r wgpu::ShaderModule,
+    pub vertex_entry: &'static str,
+    pub vertex_buffer: bool,
     pub frag_entry: &'static str,
 }
@@ -0,0 +1,189 @@
+//! Colors of the viewer, that don't come from the model
+
+use serde::Deserialize;
+
+/// The colors that the viewer is drawn with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    /// The preset that this theme is based on
+    pub preset: ThemePreset,
+
+    /// What is drawn behind the model
+    pub background: Background,
+
+    /// The color of the model's wireframe
+    ///
+    /// If this is `None`, each edge is drawn in the inverse color of its
+    /// triangle.
+    pub edge_color: Option<[f32; 4]>,
+
+    /// The color of the outlines of the model
+    pub outline_color: [f32; 4],
+
+    /// The color of text
+    pub text_color: [f32; 4],
+}
+
+impl Theme {
+    /// The theme of a preset, without any customization
+    pub fn for_preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Light => Self {
+                preset,
+                background: Background::Solid([1.0, 1.0, 1.0, 1.0]),
+                edge_color: None,
+                outline_color: [0.1, 0.1, 0.1, 1.0],
+                text_color: [0.0, 0.0, 0.0, 1.0],
+            },
+            ThemePreset::Dark => Self {
+                preset,
+                background: Background::Gradient {
+                    top: [0.12, 0.12, 0.15, 1.0],
+                    bottom: [0.02, 0.02, 0.03, 1.0],
+                },
+                edge_color: None,
+                outline_color: [0.75, 0.75, 0.8, 1.0],
+                text_color: [0.9, 0.9, 0.9, 1.0],
+            },
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::for_preset(ThemePreset::default())
+    }
+}
+
+/// What is drawn behind the model
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Background {
+    /// A single color
+    Solid([f32; 4]),
+
+    /// A vertical gradient between two colors
+    Gradient {
+        /// The color at the top edge of the window
+        top: [f32; 4],
+
+        /// The color at the bottom edge of the window
+        bottom: [f32; 4],
+    },
+}
+
+impl Background {
+    /// The colors at the top and bottom edges of the window
+    pub fn colors(&self) -> [[f32; 4]; 2] {
+        match *self {
+            Self::Solid(color) => [color, color],
+            Self::Gradient { top, bottom } => [top, bottom],
+        }
+    }
+}
+
+/// A predefined theme, that can be customized
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    /// Dark on light
+    Light,
+
+    /// Light on dark
+    Dark,
+}
+
+impl ThemePreset {
+    /// Returns the next preset, wrapping around after the last one
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::Light,
+        }
+    }
+
+    /// Returns a human-readable name of the preset
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+impl Default for ThemePreset {
+    fn default() -> Self {
+        Self::Light
+    }
+}
+
+/// Theme customization, as loaded from a configuration file
+///
+/// Colors are given as RGB, with each component between 0 and 255. Any color
+/// left out is taken from the preset.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+pub struct ThemeConfig {
+    /// The preset to start from
+    pub preset: Option<ThemePreset>,
+
+    /// The background color, or the top color of the gradient
+    pub background: Option<[u8; 3]>,
+
+    /// The bottom color of the background gradient
+    ///
+    /// The background is drawn as a gradient, if this is given. Only used, if
+    /// `background` is given too.
+    pub background_bottom: Option<[u8; 3]>,
+
+    /// The color of the model's wireframe
+    pub edge_color: Option<[u8; 3]>,
+
+    /// The color of the model's outlines
+    pub outline_color: Option<[u8; 3]>,
+
+    /// The color of text
+    pub text_color: Option<[u8; 3]>,
+}
+
+impl ThemeConfig {
+    /// The theme, with this configuration applied to the configured preset
+    pub fn theme(&self) -> Theme {
+        self.theme_for(self.preset.unwrap_or_default())
+    }
+
+    /// The theme, with this configuration applied to the given preset
+    ///
+    /// The configuration is only applied, if the preset is the configured one.
+    /// Custom colors would look out of place with another preset.
+    pub fn theme_for(&self, preset: ThemePreset) -> Theme {
+        let mut theme = Theme::for_preset(preset);
+        if self.preset.unwrap_or_default() != preset {
+            return theme;
+        }
+
+        if let Some(top) = self.background {
+            theme.background = match self.background_bottom {
+                Some(bottom) => Background::Gradient {
+                    top: color(top),
+                    bottom: color(bottom),
+                },
+                None => Background::Solid(color(top)),
+            };
+        }
+        if let Some(edge_color) = self.edge_color {
+            theme.edge_color = Some(color(edge_color));
+        }
+        if let Some(outline_color) = self.outline_color {
+            theme.outline_color = color(outline_color);
+        }
+        if let Some(text_color) = self.text_color {
+            theme.text_color = color(text_color);
+        }
+
+        theme
+    }
+}
+
+fn color([r, g, b]: [u8; 3]) -> [f32; 4] {
+    let [r, g, b] = [r, g, b].map(|c| f32::from(c) / 255.);
+    [r, g, b, 1.0]
+}
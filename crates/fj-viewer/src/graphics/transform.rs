@@ -1,5 +1,7 @@
 use bytemuck::{Pod, Zeroable};
 
+use fj_math::{Aabb, Point, Vector};
+
 use crate::camera::Camera;
 
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -27,6 +29,58 @@ impl Transform {
         Self(transform.map(|scalar| scalar.into_f32()))
     }
 
+    /// Project a point in model space to a position on the screen
+    ///
+    /// Expects a transform created by [`Transform::for_vertices`]. Returns
+    /// `None`, if the point is behind the camera.
+    pub fn project(&self, point: Point<3>, size: [f32; 2]) -> Option<[f32; 2]> {
+        let [x, y, _, w] = self.to_clip_space(point);
+        if w <= 0. {
+            return None;
+        }
+
+        Some([(x / w + 1.) / 2. * size[0], (1. - y / w) / 2. * size[1]])
+    }
+
+    /// Determine whether any part of `aabb` might be in view
+    ///
+    /// Expects a transform created by [`Transform::for_vertices`]. The box is
+    /// out of view, if all of its corners are outside of the same plane of the
+    /// view frustum.
+    pub fn is_visible(&self, aabb: &Aabb<3>) -> bool {
+        let corners = aabb.vertices().map(|point| self.to_clip_space(point));
+
+        // The planes of the frustum, in clip space, as a function that is
+        // positive outside of them. The projection maps depth to `-w..w`,
+        // like OpenGL, so that's what the near plane is tested against.
+        let planes: [fn([f32; 4]) -> f32; 6] = [
+            |[x, _, _, w]| -w - x,
+            |[x, _, _, w]| x - w,
+            |[_, y, _, w]| -w - y,
+            |[_, y, _, w]| y - w,
+            |[_, _, z, w]| -w - z,
+            |[_, _, z, w]| z - w,
+        ];
+
+        planes
+            .iter()
+            .all(|plane| corners.iter().any(|&corner| plane(corner) <= 0.))
+    }
+
+    /// Transform a point in model space into clip space
+    ///
+    /// This is what the vertex shader does with each vertex, so anything that
+    /// needs to agree with what's on screen should go through here.
+    fn to_clip_space(&self, point: Point<3>) -> [f32; 4] {
+        let [x, y, z] = point.coords.components.map(|s| s.into_f32());
+        let m = &self.0;
+
+        // The matrix is stored in column-major order.
+        [0, 1, 2, 3].map(|row| {
+            m[row] * x + m[4 + row] * y + m[8 + row] * z + m[12 + row]
+        })
+    }
+
     /// Compute transform used for normals
     ///
     /// This method is only relevant for the graphics code. The returned
@@ -36,6 +90,56 @@ impl Transform {
 
         Self::from(&transform)
     }
+
+    /// Compute transform used for the shadow map
+    ///
+    /// `light` is the direction towards the light, in view space. The returned
+    /// transform projects the model along that direction, from a distance that
+    /// fits all of `aabb` into the shadow map. Depth increases away from the
+    /// light.
+    pub fn for_shadows(
+        camera: &Camera,
+        aabb: &Aabb<3>,
+        light: Vector<3>,
+    ) -> Self {
+        let light = camera
+            .camera_to_model()
+            .inverse()
+            .transform_vector(&light)
+            .normalize();
+
+        // Any direction that isn't parallel to the light will do, as the
+        // shadow map doesn't need a specific orientation.
+        let up = if light.dot(&Vector::unit_y()).abs() < 0.9 {
+            Vector::unit_y()
+        } else {
+            Vector::unit_x()
+        };
+        let right = up.cross(&light).normalize();
+        let up = light.cross(&right);
+
+        // The bounding sphere of the model fits into any orientation.
+        let center = aabb.center().coords;
+        let radius = (aabb.size().magnitude().into_f64() / 2.).max(1e-6);
+
+        let rows = [
+            (right, 1. / radius, 0.),
+            (up, 1. / radius, 0.),
+            (light, -0.5 / radius, 0.5),
+        ];
+
+        // The matrix is stored in column-major order.
+        let mut native = [0.0; 16];
+        for (row, (axis, scale, offset)) in rows.into_iter().enumerate() {
+            for (column, component) in axis.components.iter().enumerate() {
+                native[column * 4 + row] = component.into_f64() * scale;
+            }
+            native[12 + row] = offset - axis.dot(&center).into_f64() * scale;
+        }
+        native[15] = 1.;
+
+        Self(native.map(|val| val as f32))
+    }
 }
 
 impl From<&fj_math::Transform> for Transform {
@@ -46,3 +150,102 @@ impl From<&fj_math::Transform> for Transform {
         Self(native.map(|val| val as f32))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Vector};
+
+    use crate::camera::Camera;
+
+    use super::Transform;
+
+    const SIZE: [f32; 2] = [800., 600.];
+
+    #[test]
+    fn project() {
+        let transform = Transform::for_vertices(&camera(), aspect_ratio());
+
+        // The point that the camera looks at is in the center of the view.
+        let [x, y] = transform.project(Point::origin(), SIZE).unwrap();
+        assert!((x - SIZE[0] / 2.).abs() < 1e-3);
+        assert!((y - SIZE[1] / 2.).abs() < 1e-3);
+
+        // Right is right, and up is up, which is towards the top of the
+        // screen.
+        let [x, y] =
+            transform.project(Point::from([1., 1., 0.]), SIZE).unwrap();
+        assert!(x > SIZE[0] / 2. && x < SIZE[0]);
+        assert!(y < SIZE[1] / 2. && y > 0.);
+
+        // Points behind the camera are not on the screen.
+        assert!(transform
+            .project(Point::from([0., 0., 20.]), SIZE)
+            .is_none());
+    }
+
+    #[test]
+    fn is_visible() {
+        let transform = Transform::for_vertices(&camera(), aspect_ratio());
+        let aabb = |min: [f64; 3], max: [f64; 3]| {
+            Aabb::<3>::from_points([min, max].map(Point::from))
+        };
+
+        assert!(transform.is_visible(&aabb([-1., -1., -1.], [1., 1., 1.])));
+
+        // Partly in view
+        assert!(transform.is_visible(&aabb([5., -1., -1.], [50., 1., 1.])));
+
+        // Behind the camera, beside the view, and beyond the far plane
+        assert!(!transform.is_visible(&aabb([-1., -1., 20.], [1., 1., 21.])));
+        assert!(!transform.is_visible(&aabb([50., -1., -1.], [51., 1., 1.])));
+        assert!(
+            !transform.is_visible(&aabb([-1., -1., -2000.], [1., 1., -1990.]))
+        );
+    }
+
+    #[test]
+    fn for_normals() {
+        // Looking along the negative x axis, with z pointing up
+        let camera = Camera::look_at(
+            Point::from([10., 2., 3.]),
+            Point::from([0., 2., 3.]),
+            Vector::unit_z(),
+        );
+        let transform = Transform::for_normals(&camera);
+
+        // Normals are rotated into camera space, but not translated.
+        let expected = [
+            ([1., 0., 0.], [0., 0., 1.]),
+            ([0., 0., 1.], [0., 1., 0.]),
+            ([0., 1., 0.], [1., 0., 0.]),
+        ];
+        for (normal, expected) in expected {
+            let normal = transform_normal(&transform, normal);
+            for (a, b) in normal.into_iter().zip(expected) {
+                assert!((a - b).abs() < 1e-6, "{:?}", normal);
+            }
+        }
+    }
+
+    /// A camera at `[0, 0, 10]`, looking at the origin, with y pointing up
+    fn camera() -> Camera {
+        Camera::look_at(
+            Point::from([0., 0., 10.]),
+            Point::origin(),
+            Vector::unit_y(),
+        )
+    }
+
+    fn aspect_ratio() -> f64 {
+        f64::from(SIZE[0] / SIZE[1])
+    }
+
+    fn transform_normal(transform: &Transform, normal: [f32; 3]) -> [f32; 3] {
+        let m = &transform.0;
+
+        // The matrix is stored in column-major order.
+        [0, 1, 2].map(|row| {
+            m[row] * normal[0] + m[4 + row] * normal[1] + m[8 + row] * normal[2]
+        })
+    }
+}
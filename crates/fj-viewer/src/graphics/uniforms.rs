@@ -7,6 +7,26 @@ use super::transform::Transform;
 pub struct Uniforms {
     pub transform: Transform,
     pub transform_normals: Transform,
+
+    /// Projects the model into the shadow map
+    pub transform_shadows: Transform,
+
+    /// The color of the wireframe
+    ///
+    /// An alpha of zero means that edges are drawn in the inverse color of
+    /// their triangle.
+    pub edge_color: [f32; 4],
+
+    pub background_top: [f32; 4],
+    pub background_bottom: [f32; 4],
+
+    /// The direction towards the key light, in view space
+    ///
+    /// The model only casts shadows, if the fourth component is positive.
+    pub key_light: [f32; 4],
+
+    /// The color of the outlines that are drawn around the model
+    pub outline_color: [f32; 4],
 }
 
 impl Default for Uniforms {
@@ -14,6 +34,12 @@ impl Default for Uniforms {
         Self {
             transform: Transform::identity(),
             transform_normals: Transform::identity(),
+            transform_shadows: Transform::identity(),
+            edge_color: [0.0; 4],
+            background_top: [0.0; 4],
+            background_bottom: [0.0; 4],
+            key_light: [0.0; 4],
+            outline_color: [0.0; 4],
         }
     }
 }
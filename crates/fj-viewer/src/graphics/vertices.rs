@@ -1,14 +1,25 @@
+use std::{collections::BTreeSet, ops::Range};
+
 use bytemuck::{Pod, Zeroable};
 use fj_interop::{
-    debug::DebugInfo,
+    debug::{DebugCategory, DebugGeometryKind, DebugInfo},
     mesh::{Index, Mesh},
 };
-use fj_math::{Point, Vector};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use super::DrawConfig;
+
+/// The fewest indices in a group, unless it's the last one
+///
+/// Groups are culled as a whole, so small groups make culling more precise.
+/// But each group that isn't culled is a separate draw call.
+const MIN_GROUP_INDICES: usize = 3 * 1024;
 
 #[derive(Debug)]
 pub struct Vertices {
     vertices: Vec<Vertex>,
     indices: Vec<Index>,
+    groups: Vec<Group>,
 }
 
 impl Vertices {
@@ -16,6 +27,7 @@ impl Vertices {
         Self {
             vertices: Vec::new(),
             indices: Vec::new(),
+            groups: Vec::new(),
         }
     }
 
@@ -27,6 +39,14 @@ impl Vertices {
         self.indices.as_slice()
     }
 
+    /// Access the groups of triangles that can be culled together
+    ///
+    /// Empty, if the vertices are not grouped, in which case they can't be
+    /// culled.
+    pub fn groups(&self) -> &[Group] {
+        self.groups.as_slice()
+    }
+
     pub fn push_line(
         &mut self,
         line: [Point<3>; 2],
@@ -37,6 +57,7 @@ impl Vertices {
             position: point.coords.components.map(|scalar| scalar.into_f32()),
             normal,
             color,
+            metallic_roughness: Surface::default().into(),
         });
 
         self.vertices.extend(line);
@@ -70,45 +91,134 @@ impl Vertices {
             color,
         );
     }
-}
 
-impl From<&Mesh<fj_math::Point<3>>> for Vertices {
-    fn from(mesh: &Mesh<fj_math::Point<3>>) -> Self {
+    /// Create vertices from a triangle mesh
+    ///
+    /// `surfaces` contains the surface of each face of the mesh, indexed by
+    /// its ID. Faces that aren't in there get the default surface.
+    pub fn from_mesh(
+        mesh: &Mesh<fj_math::Point<3>>,
+        surfaces: &[Surface],
+        config: &DrawConfig,
+    ) -> Self {
         let mut m = Mesh::new();
 
-        for triangle in mesh.triangles() {
-            let [a, b, c] = triangle.points;
+        let normals = mesh.normals(config.normals());
+        let colors = match config.quality {
+            Some(metric) => metric.colors(mesh),
+            None => mesh.triangles().map(|triangle| triangle.color).collect(),
+        };
+
+        let mut triangles: Vec<_> = mesh
+            .triangles()
+            .zip(normals)
+            .zip(colors)
+            .filter(|((triangle, _), _)| {
+                config.selected_face.is_none()
+                    || triangle.face == config.selected_face
+            })
+            .collect();
+
+        // The triangles of a face are close to each other, which makes them a
+        // good group for culling. They need to be next to each other in the
+        // index buffer for that.
+        triangles.sort_by_key(|((triangle, _), _)| triangle.face);
+
+        let mut groups: Vec<Group> = Vec::new();
+        let mut face = None;
+        let mut start = 0;
+
+        for ((triangle, normals), color) in triangles {
+            let aabb = Aabb::<3>::from_points(triangle.points);
+
+            match groups.last_mut() {
+                Some(group)
+                    if triangle.face == face
+                        || group.indices.len() < MIN_GROUP_INDICES =>
+                {
+                    group.aabb = group.aabb.merged(&aabb);
+                    group.indices.end = start + 3;
+                }
+                _ => groups.push(Group {
+                    indices: start..start + 3,
+                    aabb,
+                }),
+            }
+            face = triangle.face;
+            start += 3;
 
-            let normal = (b - a).cross(&(c - a)).normalize();
-            let color = triangle.color;
+            let surface = triangle
+                .face
+                .and_then(|face| surfaces.get(face.0 as usize))
+                .copied()
+                .unwrap_or_default();
 
-            m.push_vertex((a, normal, color));
-            m.push_vertex((b, normal, color));
-            m.push_vertex((c, normal, color));
+            for (point, normal) in triangle.points.into_iter().zip(normals) {
+                m.push_vertex((point, normal, color, surface));
+            }
         }
 
         let vertices = m
             .vertices()
-            .map(|(vertex, normal, color)| Vertex {
+            .map(|(vertex, normal, color, surface)| Vertex {
                 position: vertex.into(),
                 normal: normal.into(),
                 color: color.map(|v| f32::from(v) / 255.0),
+                metallic_roughness: surface.into(),
             })
             .collect();
 
         let indices = m.indices().collect();
 
-        Self { vertices, indices }
+        Self {
+            vertices,
+            indices,
+            groups,
+        }
     }
-}
 
-impl From<&DebugInfo> for Vertices {
-    fn from(debug_info: &DebugInfo) -> Self {
+    pub fn from_debug_info(
+        debug_info: &DebugInfo,
+        categories: &BTreeSet<DebugCategory>,
+    ) -> Self {
         let mut self_ = Self::empty();
+        let normal = [0.; 3];
 
-        for triangle_edge_check in &debug_info.triangle_edge_checks {
-            let normal = [0.; 3];
+        for geometry in &debug_info.geometry {
+            if !categories.contains(&geometry.category) {
+                continue;
+            }
+
+            let color = category_color(geometry.category);
 
+            match &geometry.kind {
+                DebugGeometryKind::Point(point) => {
+                    self_.push_cross(*point, normal, color);
+                }
+                DebugGeometryKind::Segment(segment) => {
+                    self_.push_line(segment.points(), normal, color);
+                }
+                DebugGeometryKind::Polyline { points, closed } => {
+                    for line in points.windows(2) {
+                        self_.push_line([line[0], line[1]], normal, color);
+                    }
+                    if let (true, Some(&first), Some(&last)) =
+                        (closed, points.first(), points.last())
+                    {
+                        self_.push_line([last, first], normal, color);
+                    }
+                }
+                DebugGeometryKind::Text(_) => {
+                    // Text is rendered separately, as part of the UI.
+                }
+            }
+        }
+
+        if !categories.contains(&DebugCategory::Triangulation) {
+            return self_;
+        }
+
+        for triangle_edge_check in &debug_info.triangle_edge_checks {
             let red = [1., 0., 0., 1.];
             let green = [0., 1., 0., 1.];
 
@@ -132,10 +242,82 @@ impl From<&DebugInfo> for Vertices {
     }
 }
 
+fn category_color(category: DebugCategory) -> [f32; 4] {
+    match category {
+        DebugCategory::Approximation => [0., 0., 1., 1.],
+        DebugCategory::Triangulation => [0., 0.5, 0., 1.],
+        DebugCategory::Intersection => [1., 0., 1., 1.],
+        DebugCategory::Sweep => [1., 0.5, 0., 1.],
+        DebugCategory::Analysis => [1., 0., 0., 1.],
+        DebugCategory::Other => [0., 0., 0., 1.],
+    }
+}
+
+/// Triangles that are drawn, or culled, together
+#[derive(Clone, Debug)]
+pub struct Group {
+    /// The range of the triangles' indices
+    pub indices: Range<u32>,
+
+    /// The bounding box of the triangles
+    pub aabb: Aabb<3>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
 #[repr(C)]
 pub struct Vertex {
     pub position: [f32; 3],
     pub normal: [f32; 3],
     pub color: [f32; 4],
+    pub metallic_roughness: [f32; 2],
+}
+
+/// The transform of one instance of a mesh
+///
+/// Instances are drawn from the same vertices, each moved by its transform.
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct Instance {
+    /// The transform, as a column-major matrix
+    pub transform: [f32; 16],
+}
+
+impl From<&fj_math::Transform> for Instance {
+    fn from(transform: &fj_math::Transform) -> Self {
+        let mut data = [0.; 16];
+        for (value, &x) in data.iter_mut().zip(transform.data()) {
+            *value = x as f32;
+        }
+
+        Self { transform: data }
+    }
+}
+
+/// How the surface of a face reflects light
+///
+/// Both values range from `0.` to `1.`, like in the metallic/roughness model
+/// of glTF.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Surface {
+    /// How metallic the surface is
+    pub metallic: Scalar,
+
+    /// How rough the surface is
+    pub roughness: Scalar,
+}
+
+impl Default for Surface {
+    fn default() -> Self {
+        // A plastic that's neither glossy nor matte.
+        Self {
+            metallic: Scalar::ZERO,
+            roughness: Scalar::from_f64(0.5),
+        }
+    }
+}
+
+impl From<Surface> for [f32; 2] {
+    fn from(surface: Surface) -> Self {
+        [surface.metallic.into_f32(), surface.roughness.into_f32()]
+    }
 }
@@ -0,0 +1,51 @@
+//! Camera navigation with devices that report a deflection per axis
+//!
+//! Shared by the gamepad and 6-DoF device support.
+
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::camera::Camera;
+
+use super::{movement::Movement, rotation::Rotation};
+
+/// Camera rotation in radians per second, at full deflection
+const ROTATION_SPEED: f64 = 2.0;
+
+/// Camera movement in distances to the origin per second, at full deflection
+const TRANSLATION_SPEED: f64 = 1.0;
+
+/// The deflection of each navigation axis, between `-1` and `1`
+///
+/// Translation is along the x- and y-axes of the view, and along the z-axis
+/// towards the camera, which zooms out. Rotation is around the same axes.
+pub struct Deflection {
+    pub translation: [f64; 3],
+    pub rotation: [f64; 3],
+}
+
+impl Deflection {
+    /// Move the camera according to the deflection
+    ///
+    /// `delta_t` is the time since the last update, in seconds.
+    pub fn apply(&self, delta_t: f64, camera: &mut Camera) {
+        // Movement is scaled with the distance to the model, so it feels the
+        // same, regardless of the model's size.
+        let distance =
+            Point::distance(&camera.position(), &Point::origin()).into_f64();
+        let [x, y, z] = self
+            .translation
+            .map(|value| value * TRANSLATION_SPEED * distance * delta_t);
+
+        Movement::translate(Vector::from([x, y, 0.]), camera);
+        camera.translation = camera.translation
+            * Transform::translation(Vector::from([
+                Scalar::ZERO,
+                Scalar::ZERO,
+                Scalar::from_f64(z),
+            ]));
+
+        let angles =
+            self.rotation.map(|value| value * ROTATION_SPEED * delta_t);
+        Rotation::rotate(Point::origin(), angles, camera);
+    }
+}
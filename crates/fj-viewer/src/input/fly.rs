@@ -0,0 +1,142 @@
+use fj_math::{Aabb, Point, Transform, Vector};
+use winit::event::{ElementState, VirtualKeyCode};
+
+use crate::camera::Camera;
+
+use super::{rotation::Rotation, Settings};
+
+/// First-person navigation through the model
+///
+/// WASD moves the camera forward, left, back, and right, Q and E move it
+/// down and up. While the rotate button is held, the mouse turns the camera in
+/// place, instead of rotating the model.
+pub struct Fly {
+    looking: bool,
+
+    forward: bool,
+    back: bool,
+    left: bool,
+    right: bool,
+    up: bool,
+    down: bool,
+}
+
+impl Fly {
+    pub fn new() -> Self {
+        Self {
+            looking: false,
+
+            forward: false,
+            back: false,
+            left: false,
+            right: false,
+            up: false,
+            down: false,
+        }
+    }
+
+    /// Track the state of the movement keys
+    ///
+    /// Returns `false`, if the key is not used for flying.
+    pub fn handle_key(
+        &mut self,
+        key: VirtualKeyCode,
+        state: ElementState,
+    ) -> bool {
+        let pressed = state == ElementState::Pressed;
+
+        let flag = match key {
+            VirtualKeyCode::W => &mut self.forward,
+            VirtualKeyCode::S => &mut self.back,
+            VirtualKeyCode::A => &mut self.left,
+            VirtualKeyCode::D => &mut self.right,
+            VirtualKeyCode::E => &mut self.up,
+            VirtualKeyCode::Q => &mut self.down,
+            _ => return false,
+        };
+        *flag = pressed;
+
+        true
+    }
+
+    pub fn start_looking(&mut self) {
+        self.looking = true;
+    }
+
+    pub fn stop_looking(&mut self) {
+        self.looking = false;
+    }
+
+    /// Stop all movement
+    ///
+    /// Key releases are not received while flying is disabled, so keys that
+    /// are held while switching would otherwise get stuck.
+    pub fn stop(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Indicate whether any of the movement keys are held
+    pub fn is_moving(&self) -> bool {
+        self.forward
+            || self.back
+            || self.left
+            || self.right
+            || self.up
+            || self.down
+    }
+
+    /// Turn the camera in place
+    pub fn look(
+        &self,
+        diff_x: f64,
+        diff_y: f64,
+        settings: &Settings,
+        camera: &mut Camera,
+    ) {
+        if !self.looking {
+            return;
+        }
+
+        let f = settings.rotation_sensitivity;
+        let fx = if settings.invert_x { -f } else { f };
+        let fy = if settings.invert_y { -f } else { f };
+
+        // Rotating the model around the camera turns the camera in place. The
+        // rotation is applied before the translation, so the camera needs to
+        // be located in that space.
+        let camera_position =
+            camera.translation.inverse_transform_point(&Point::origin());
+
+        Rotation::rotate(
+            camera_position,
+            [diff_y * fy, diff_x * fx, 0.],
+            camera,
+        );
+    }
+
+    /// Move the camera according to the keys being held
+    ///
+    /// The speed depends on the size of the model, so crossing it takes a few
+    /// seconds, regardless of its scale.
+    pub fn apply(&self, delta_t: f64, aabb: &Aabb<3>, camera: &mut Camera) {
+        let axis = |positive: bool, negative: bool| {
+            (positive as i8 - negative as i8) as f64
+        };
+
+        // The camera looks along the negative z axis. Since the model is moved,
+        // rather than the camera, all directions are reversed.
+        let direction = Vector::from([
+            axis(self.left, self.right),
+            axis(self.down, self.up),
+            axis(self.forward, self.back),
+        ]);
+        if direction == Vector::from([0., 0., 0.]) {
+            return;
+        }
+
+        let speed = aabb.size().magnitude() * 0.25;
+
+        camera.translation = camera.translation
+            * Transform::translation(direction.normalize() * speed * delta_t);
+    }
+}
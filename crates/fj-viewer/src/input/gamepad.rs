@@ -0,0 +1,79 @@
+//! Support for navigating the camera with a gamepad
+//!
+//! Only available with the `gamepad` feature.
+
+use gilrs::{Axis, Button, Gilrs};
+use tracing::info;
+
+use crate::camera::Camera;
+
+use super::deflection::Deflection;
+
+/// Stick deflections below this are ignored, as sticks rarely center exactly
+const DEAD_ZONE: f32 = 0.1;
+
+/// Navigates the camera with the first connected gamepad
+///
+/// The left stick orbits the camera, the right stick moves it, and the
+/// triggers zoom in and out.
+pub struct Gamepads {
+    gilrs: Gilrs,
+}
+
+impl Gamepads {
+    /// Initialize gamepad support
+    ///
+    /// Returns `None`, if gamepads aren't supported on this platform.
+    pub fn new() -> Option<Self> {
+        match Gilrs::new() {
+            Ok(gilrs) => Some(Self { gilrs }),
+            Err(err) => {
+                info!("Gamepad support not available: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Apply the state of the first connected gamepad to the camera
+    ///
+    /// `delta_t` is the time since the last update, in seconds.
+    pub fn apply(&mut self, delta_t: f64, camera: &mut Camera) {
+        // Processing the events updates the state of the gamepads. We only
+        // care about that state, not the events themselves.
+        while self.gilrs.next_event().is_some() {}
+
+        let gamepad = match self.gilrs.gamepads().next() {
+            Some((_, gamepad)) => gamepad,
+            None => return,
+        };
+
+        let axis = |axis| {
+            let value = gamepad.value(axis);
+            if value.abs() < DEAD_ZONE {
+                return 0.;
+            }
+            f64::from(value)
+        };
+        let trigger = |button| {
+            gamepad
+                .button_data(button)
+                .map(|data| f64::from(data.value()))
+                .unwrap_or(0.)
+        };
+
+        let orbit = [axis(Axis::LeftStickY), axis(Axis::LeftStickX)];
+        let pan = [axis(Axis::RightStickX), axis(Axis::RightStickY)];
+        let zoom =
+            trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2);
+
+        if orbit == [0., 0.] && pan == [0., 0.] && zoom == 0. {
+            return;
+        }
+
+        Deflection {
+            translation: [pan[0], pan[1], -zoom],
+            rotation: [-orbit[0], orbit[1], 0.],
+        }
+        .apply(delta_t, camera);
+    }
+}
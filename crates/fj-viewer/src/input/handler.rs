@@ -1,28 +1,47 @@
-use std::time::Instant;
-
-use fj_interop::mesh::Mesh;
-use fj_math::{Point, Transform, Vector};
+use fj_interop::{debug::DebugCategory, mesh::Mesh};
+use fj_math::{Aabb, Point, Transform, Vector};
+use instant::Instant;
 use winit::{
     dpi::PhysicalPosition,
     event::{
-        ElementState, KeyboardInput, MouseButton, MouseScrollDelta,
-        VirtualKeyCode,
+        ElementState, KeyboardInput, MouseButton, MouseScrollDelta, Touch,
+        TouchPhase, VirtualKeyCode,
     },
 };
 
 use crate::{
-    camera::{Camera, FocusPoint},
-    window::Window,
+    camera::{Camera, FocusPoint, StandardView},
+    viewport::Viewport,
 };
 
-use super::{movement::Movement, rotation::Rotation, zoom::Zoom};
+use super::{
+    fly::Fly, movement::Movement, rotation::Rotation, touch::Touches,
+    zoom::Zoom, Settings,
+};
 
 /// Input handling abstraction
 ///
 /// Takes user input and applies them to application state.
 pub struct Handler {
+    settings: Settings,
+    behavior: Behavior,
     cursor: Option<PhysicalPosition<f64>>,
+    touches: Touches,
+
+    // Cursor movement is measured in physical pixels, but is applied in
+    // logical ones, so navigation feels the same on any display.
+    scale_factor: f64,
+
+    // Whether the window has the keyboard focus. Devices that don't send
+    // window events only apply to the focused window, if there are several.
+    focused: bool,
 
+    #[cfg(feature = "gamepad")]
+    gamepads: Option<super::gamepad::Gamepads>,
+    #[cfg(feature = "spacemouse")]
+    space_mouse: Option<super::spacemouse::SpaceMouse>,
+
+    fly: Fly,
     movement: Movement,
     rotation: Rotation,
     zoom: Zoom,
@@ -35,18 +54,71 @@ impl Handler {
     /// ```rust no_run
     /// // Store initialization time for camera zoom calculations
     /// let instant = std::time::Instant::now();
-    /// let input_handler = fj_viewer::input::Handler::new(instant);
+    /// let settings = fj_viewer::input::Settings::default();
+    /// let input_handler = fj_viewer::input::Handler::new(instant, settings);
     /// ```
-    pub fn new(now: Instant) -> Self {
+    pub fn new(now: Instant, settings: Settings) -> Self {
         Self {
+            settings,
+            behavior: Behavior::default(),
             cursor: None,
+            touches: Touches::new(),
+            scale_factor: 1.0,
+            focused: true,
+
+            #[cfg(feature = "gamepad")]
+            gamepads: super::gamepad::Gamepads::new(),
+            #[cfg(feature = "spacemouse")]
+            space_mouse: super::spacemouse::SpaceMouse::open(),
 
+            fly: Fly::new(),
             movement: Movement::new(),
             rotation: Rotation::new(),
             zoom: Zoom::new(now),
         }
     }
 
+    /// Returns the settings that input is applied with.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Replaces the settings that input is applied with.
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+    }
+
+    /// Changes the number of physical pixels per logical pixel
+    ///
+    /// Call this, when the window is moved to a display with a different
+    /// pixel density.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// Set whether the window has the keyboard focus
+    ///
+    /// Input from gamepads and 6-DoF input devices is ignored, while it
+    /// doesn't.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Returns the way that input navigates the camera.
+    pub fn behavior(&self) -> Behavior {
+        self.behavior
+    }
+
+    /// Changes the way that input navigates the camera.
+    pub fn set_behavior(&mut self, behavior: Behavior) {
+        self.behavior = behavior;
+
+        // Any ongoing navigation doesn't carry over.
+        self.fly.stop();
+        self.rotation.stop();
+        self.movement.stop();
+    }
+
     /// Returns the state of the cursor position.
     pub fn cursor(&self) -> Option<PhysicalPosition<f64>> {
         self.cursor
@@ -58,6 +130,19 @@ impl Handler {
         input: KeyboardInput,
         actions: &mut Actions,
     ) {
+        if let KeyboardInput {
+            state,
+            virtual_keycode: Some(virtual_key_code),
+            ..
+        } = input
+        {
+            if self.behavior == Behavior::Fly
+                && self.fly.handle_key(virtual_key_code, state)
+            {
+                return;
+            }
+        }
+
         if let KeyboardInput {
             state: ElementState::Pressed,
             virtual_keycode: Some(virtual_key_code),
@@ -66,10 +151,81 @@ impl Handler {
         {
             match virtual_key_code {
                 VirtualKeyCode::Escape => actions.exit = true,
+                VirtualKeyCode::Return => actions.dismiss_error = true,
 
                 VirtualKeyCode::Key1 => actions.toggle_model = true,
                 VirtualKeyCode::Key2 => actions.toggle_mesh = true,
                 VirtualKeyCode::Key3 => actions.toggle_debug = true,
+                VirtualKeyCode::Key4 => actions.toggle_smooth_normals = true,
+                VirtualKeyCode::Key5 => actions.cycle_quality = true,
+                VirtualKeyCode::Key6 => actions.toggle_profiler = true,
+                VirtualKeyCode::Key7 => actions.switch_input_device = true,
+                VirtualKeyCode::Key8 => actions.switch_behavior = true,
+                VirtualKeyCode::Key9 => actions.switch_layout = true,
+                VirtualKeyCode::Key0 => actions.switch_theme = true,
+
+                VirtualKeyCode::F => actions.zoom_to_fit = true,
+                VirtualKeyCode::Numpad7 => {
+                    actions.show_view = Some(StandardView::Top)
+                }
+                VirtualKeyCode::Numpad1 => {
+                    actions.show_view = Some(StandardView::Front)
+                }
+                VirtualKeyCode::Numpad3 => {
+                    actions.show_view = Some(StandardView::Right)
+                }
+                VirtualKeyCode::Numpad0 => {
+                    actions.show_view = Some(StandardView::Isometric)
+                }
+
+                VirtualKeyCode::PageDown => actions.next_model = true,
+                VirtualKeyCode::PageUp => actions.previous_model = true,
+
+                VirtualKeyCode::B => actions.toggle_bom = true,
+                VirtualKeyCode::C => actions.copy_pointer = true,
+                VirtualKeyCode::M => actions.measure = true,
+                VirtualKeyCode::V => actions.copy_properties = true,
+                VirtualKeyCode::P => actions.toggle_probe = true,
+                VirtualKeyCode::N => actions.annotate = true,
+                VirtualKeyCode::Delete => actions.remove_annotation = true,
+                VirtualKeyCode::K => actions.save_viewpoint = true,
+                VirtualKeyCode::Period => actions.next_viewpoint = true,
+                VirtualKeyCode::Comma => actions.previous_viewpoint = true,
+                VirtualKeyCode::H => actions.toggle_shadows = true,
+                VirtualKeyCode::O => actions.toggle_outlines = true,
+                VirtualKeyCode::X => actions.toggle_explosion = true,
+                VirtualKeyCode::Equals | VirtualKeyCode::NumpadAdd => {
+                    actions.increase_explosion = true
+                }
+                VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => {
+                    actions.decrease_explosion = true
+                }
+
+                VirtualKeyCode::RBracket => actions.next_face = true,
+                VirtualKeyCode::LBracket => actions.previous_face = true,
+
+                VirtualKeyCode::F1 => {
+                    actions.toggle_debug_category =
+                        Some(DebugCategory::Approximation)
+                }
+                VirtualKeyCode::F2 => {
+                    actions.toggle_debug_category =
+                        Some(DebugCategory::Triangulation)
+                }
+                VirtualKeyCode::F3 => {
+                    actions.toggle_debug_category =
+                        Some(DebugCategory::Intersection)
+                }
+                VirtualKeyCode::F4 => {
+                    actions.toggle_debug_category = Some(DebugCategory::Sweep)
+                }
+                VirtualKeyCode::F5 => {
+                    actions.toggle_debug_category = Some(DebugCategory::Other)
+                }
+                VirtualKeyCode::F6 => {
+                    actions.toggle_debug_category =
+                        Some(DebugCategory::Analysis)
+                }
 
                 _ => (),
             }
@@ -81,14 +237,21 @@ impl Handler {
         &mut self,
         cursor: PhysicalPosition<f64>,
         camera: &mut Camera,
-        window: &Window,
+        viewport: &Viewport,
     ) {
         if let Some(previous) = self.cursor {
-            let diff_x = cursor.x - previous.x;
-            let diff_y = cursor.y - previous.y;
+            let diff_x = (cursor.x - previous.x) / self.scale_factor;
+            let diff_y = (cursor.y - previous.y) / self.scale_factor;
 
-            self.movement.apply(self.cursor, camera, window);
-            self.rotation.apply(diff_x, diff_y, camera);
+            match self.behavior {
+                Behavior::Orbit => {
+                    self.movement.apply(self.cursor, camera, viewport);
+                    self.rotation.apply(diff_x, diff_y, &self.settings, camera);
+                }
+                Behavior::Fly => {
+                    self.fly.look(diff_x, diff_y, &self.settings, camera);
+                }
+            }
         }
 
         self.cursor = Some(cursor);
@@ -101,17 +264,34 @@ impl Handler {
         state: ElementState,
         focus_point: FocusPoint,
     ) {
-        match (button, state) {
-            (MouseButton::Left, ElementState::Pressed) => {
+        let rotate = MouseButton::from(self.settings.rotate_button);
+        let mov = MouseButton::from(self.settings.move_button);
+
+        if self.behavior == Behavior::Fly {
+            match state {
+                ElementState::Pressed if button == rotate => {
+                    self.fly.start_looking();
+                }
+                ElementState::Released if button == rotate => {
+                    self.fly.stop_looking();
+                }
+                _ => {}
+            }
+
+            return;
+        }
+
+        match state {
+            ElementState::Pressed if button == rotate => {
                 self.rotation.start(focus_point);
             }
-            (MouseButton::Left, ElementState::Released) => {
+            ElementState::Released if button == rotate => {
                 self.rotation.stop();
             }
-            (MouseButton::Right, ElementState::Pressed) => {
+            ElementState::Pressed if button == mov => {
                 self.movement.start(focus_point, self.cursor);
             }
-            (MouseButton::Right, ElementState::Released) => {
+            ElementState::Released if button == mov => {
                 self.movement.stop();
             }
             _ => {}
@@ -126,10 +306,105 @@ impl Handler {
     ) {
         let delta = match delta {
             MouseScrollDelta::LineDelta(_, y) => y as f64 * 10.0,
-            MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => y,
+            MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => {
+                y / self.scale_factor
+            }
         };
+        self.zoom.push_input_delta(self.zoom_delta(delta), now);
+    }
+
+    /// Applies touch input to `camera`.
+    ///
+    /// One finger rotates the camera, two fingers move it. Moving two fingers
+    /// apart or together zooms.
+    pub fn handle_touch(
+        &mut self,
+        touch: Touch,
+        now: Instant,
+        camera: &mut Camera,
+        viewport: &Viewport,
+        mesh: &Mesh<Point<3>>,
+        aabb: &Aabb<3>,
+    ) {
+        let previous_spread = self.touches.spread();
+        let previous = self.touches.update(&touch);
 
-        self.zoom.push_input_delta(delta, now);
+        match (touch.phase, self.touches.len()) {
+            (TouchPhase::Started, 1) => {
+                let focus_point = camera.focus_point(
+                    viewport,
+                    Some(touch.location),
+                    mesh,
+                    aabb,
+                );
+                self.rotation.start(focus_point);
+            }
+            (TouchPhase::Started, 2) => {
+                self.rotation.stop();
+
+                let centroid = self.touches.centroid();
+                let focus_point =
+                    camera.focus_point(viewport, centroid, mesh, aabb);
+                self.movement.start(focus_point, centroid);
+            }
+            (TouchPhase::Moved, 1) => {
+                if let Some(previous) = previous {
+                    let diff_x =
+                        (touch.location.x - previous.x) / self.scale_factor;
+                    let diff_y =
+                        (touch.location.y - previous.y) / self.scale_factor;
+
+                    self.rotation.apply(diff_x, diff_y, &self.settings, camera);
+                }
+            }
+            (TouchPhase::Moved, 2) => {
+                self.movement
+                    .apply(self.touches.centroid(), camera, viewport);
+
+                if let (Some(previous), Some(spread)) =
+                    (previous_spread, self.touches.spread())
+                {
+                    let delta = self
+                        .zoom_delta((spread - previous) / self.scale_factor);
+                    self.zoom.push_input_delta(delta, now);
+                }
+            }
+            (TouchPhase::Ended | TouchPhase::Cancelled, _) => {
+                // Gestures don't carry over to a different number of fingers.
+                // The camera would jump otherwise.
+                self.rotation.stop();
+                self.movement.stop();
+            }
+            _ => {}
+        }
+    }
+
+    fn zoom_delta(&self, delta: f64) -> f64 {
+        let delta = delta * self.settings.zoom_sensitivity;
+        if self.settings.invert_zoom {
+            -delta
+        } else {
+            delta
+        }
+    }
+
+    /// Indicate whether input keeps moving the camera, without further events
+    ///
+    /// This is the case while zooming slows down, or while flying. Gamepads
+    /// and 6-DoF input devices don't send window events at all, so if one is
+    /// available, this is always `true`.
+    pub fn is_active(&self) -> bool {
+        #[cfg(feature = "gamepad")]
+        if self.focused && self.gamepads.is_some() {
+            return true;
+        }
+        #[cfg(feature = "spacemouse")]
+        if self.focused && self.space_mouse.is_some() {
+            return true;
+        }
+
+        self.zoom.is_active()
+            || (self.behavior == Behavior::Fly && self.fly.is_moving())
     }
 
     /// Update application state from user input.
@@ -138,10 +413,11 @@ impl Handler {
         delta_t: f64,
         now: Instant,
         camera: &mut Camera,
-        window: &Window,
+        viewport: &Viewport,
         mesh: &Mesh<Point<3>>,
+        aabb: &Aabb<3>,
     ) {
-        let focus_point = camera.focus_point(window, self.cursor, mesh);
+        let focus_point = camera.focus_point(viewport, self.cursor, mesh, aabb);
 
         self.zoom.discard_old_events(now);
         self.zoom.update_speed(now, delta_t, focus_point, camera);
@@ -152,6 +428,23 @@ impl Handler {
                 0.0,
                 -self.zoom.speed(),
             ]));
+
+        if self.behavior == Behavior::Fly {
+            self.fly.apply(delta_t, aabb, camera);
+        }
+
+        if !self.focused {
+            return;
+        }
+
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepads) = &mut self.gamepads {
+            gamepads.apply(delta_t, camera);
+        }
+        #[cfg(feature = "spacemouse")]
+        if let Some(space_mouse) = &self.space_mouse {
+            space_mouse.apply(delta_t, camera);
+        }
     }
 }
 
@@ -162,6 +455,8 @@ impl Handler {
 pub struct Actions {
     /// Application exit state.
     pub exit: bool,
+    /// Dismiss the error overlay.
+    pub dismiss_error: bool,
 
     /// Toggle for the shaded display of the model.
     pub toggle_model: bool,
@@ -169,6 +464,105 @@ pub struct Actions {
     pub toggle_mesh: bool,
     /// Toggle for debug information.
     pub toggle_debug: bool,
+    /// Toggle for smooth shading.
+    pub toggle_smooth_normals: bool,
+    /// Toggle for shadows.
+    pub toggle_shadows: bool,
+    /// Toggle for outlines.
+    pub toggle_outlines: bool,
+    /// Toggle for the profiler overlay.
+    pub toggle_profiler: bool,
+    /// Toggle for the bill of materials.
+    pub toggle_bom: bool,
+    /// Switch the input settings to the next input device.
+    pub switch_input_device: bool,
+    /// Switch to the next navigation behavior.
+    pub switch_behavior: bool,
+    /// Switch to the next arrangement of viewports.
+    pub switch_layout: bool,
+    /// Switch to the next theme preset.
+    pub switch_theme: bool,
+    /// Toggle for a category of debug information.
+    pub toggle_debug_category: Option<DebugCategory>,
+
+    /// Switch to the next triangle quality metric.
+    pub cycle_quality: bool,
+    /// Only display the next face of the model.
+    pub next_face: bool,
+    /// Only display the previous face of the model.
+    pub previous_face: bool,
+
+    /// Explode the model fully, or assemble it again.
+    pub toggle_explosion: bool,
+    /// Move the parts of the model further apart.
+    pub increase_explosion: bool,
+    /// Move the parts of the model closer together.
+    pub decrease_explosion: bool,
+
+    /// Switch to the next model of the workspace.
+    pub next_model: bool,
+    /// Switch to the previous model of the workspace.
+    pub previous_model: bool,
+
+    /// Copy the coordinates of the point under the cursor to the clipboard.
+    pub copy_pointer: bool,
+    /// Start measuring from the point under the cursor, or copy the distance
+    /// to it to the clipboard.
+    pub measure: bool,
+    /// Copy the volume, area, size, and mass of the model to the clipboard.
+    pub copy_properties: bool,
+    /// Toggle probe mode, in which the pointer snaps to features of faces.
+    pub toggle_probe: bool,
+
+    /// Enter the text of an annotation at the point under the cursor.
+    pub annotate: bool,
+    /// Remove the annotation closest to the point under the cursor.
+    pub remove_annotation: bool,
+    /// Enter a name, to save the view of the main camera under.
+    pub save_viewpoint: bool,
+    /// Move the main camera to the next saved viewpoint.
+    pub next_viewpoint: bool,
+    /// Move the main camera to the previous saved viewpoint.
+    pub previous_viewpoint: bool,
+
+    /// Move the model, so it fills the view.
+    pub zoom_to_fit: bool,
+    /// Rotate the model into a standard view.
+    pub show_view: Option<StandardView>,
+}
+
+/// The way that input navigates the camera
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Behavior {
+    /// Rotate and move the model in front of the camera
+    Orbit,
+
+    /// Fly the camera through the model, using WASD and the mouse
+    Fly,
+}
+
+impl Behavior {
+    /// Returns the next behavior, wrapping around after the last one
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Orbit => Self::Fly,
+            Self::Fly => Self::Orbit,
+        }
+    }
+
+    /// Returns a human-readable name of the behavior
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Orbit => "orbit",
+            Self::Fly => "fly",
+        }
+    }
+}
+
+impl Default for Behavior {
+    fn default() -> Self {
+        Self::Orbit
+    }
 }
 
 impl Actions {
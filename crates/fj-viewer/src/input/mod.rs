@@ -1,8 +1,20 @@
 //! User input parsing and propagation.
 
+#[cfg(any(feature = "gamepad", feature = "spacemouse"))]
+mod deflection;
+mod fly;
+#[cfg(feature = "gamepad")]
+mod gamepad;
 mod handler;
 mod movement;
 mod rotation;
+mod settings;
+#[cfg(feature = "spacemouse")]
+mod spacemouse;
+mod touch;
 mod zoom;
 
-pub use self::handler::{Actions, Handler};
+pub use self::{
+    handler::{Actions, Behavior, Handler},
+    settings::{Button, Config, Device, Settings},
+};
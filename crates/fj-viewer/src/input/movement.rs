@@ -3,7 +3,7 @@ use winit::dpi::PhysicalPosition;
 
 use crate::{
     camera::{Camera, FocusPoint},
-    window::Window,
+    viewport::Viewport,
 };
 
 pub struct Movement {
@@ -36,28 +36,36 @@ impl Movement {
         &mut self,
         cursor: Option<PhysicalPosition<f64>>,
         camera: &mut Camera,
-        window: &Window,
+        viewport: &Viewport,
     ) {
         if let (Some(previous), Some(cursor)) = (self.cursor, cursor) {
-            let previous = camera.cursor_to_model_space(previous, window);
-            let cursor = camera.cursor_to_model_space(cursor, window);
+            let previous = camera.cursor_to_model_space(previous, viewport);
+            let cursor = camera.cursor_to_model_space(cursor, viewport);
 
             if let Some(focus_point) = self.focus_point.0 {
                 let d1 = Point::distance(&camera.position(), &cursor);
                 let d2 = Point::distance(&camera.position(), &focus_point);
 
                 let diff = (cursor - previous) * d2 / d1;
-                let offset = camera.camera_to_model().transform_vector(&diff);
-
-                camera.translation = camera.translation
-                    * Transform::translation(Vector::from([
-                        offset.x,
-                        offset.y,
-                        Scalar::ZERO,
-                    ]));
+                Self::translate(diff, camera);
             }
         }
 
         self.cursor = cursor;
     }
+
+    /// Move the camera parallel to the screen
+    ///
+    /// Expects `offset` in the same space as the positions returned by
+    /// [`Camera::cursor_to_model_space`]. Only its x and y components are used.
+    pub fn translate(offset: Vector<3>, camera: &mut Camera) {
+        let offset = camera.camera_to_model().transform_vector(&offset);
+
+        camera.translation = camera.translation
+            * Transform::translation(Vector::from([
+                offset.x,
+                offset.y,
+                Scalar::ZERO,
+            ]));
+    }
 }
@@ -2,6 +2,8 @@ use fj_math::{Point, Transform, Vector};
 
 use crate::camera::{Camera, FocusPoint};
 
+use super::Settings;
+
 pub struct Rotation {
     active: bool,
     focus_point: FocusPoint,
@@ -24,26 +26,47 @@ impl Rotation {
         self.active = false;
     }
 
-    pub fn apply(&self, diff_x: f64, diff_y: f64, camera: &mut Camera) {
+    pub fn apply(
+        &self,
+        diff_x: f64,
+        diff_y: f64,
+        settings: &Settings,
+        camera: &mut Camera,
+    ) {
         if self.active {
-            let rotate_around: Vector<3> =
-                self.focus_point.0.unwrap_or_else(Point::origin).coords;
+            let rotate_around =
+                self.focus_point.0.unwrap_or_else(Point::origin);
 
-            let f = 0.005;
+            let f = settings.rotation_sensitivity;
+            let fx = if settings.invert_x { -f } else { f };
+            let fy = if settings.invert_y { -f } else { f };
 
-            let angle_x = diff_y * f;
-            let angle_y = diff_x * f;
+            let angle_x = diff_y * fy;
+            let angle_y = diff_x * fx;
 
-            let trans = Transform::translation(rotate_around);
+            Self::rotate(rotate_around, [angle_x, angle_y, 0.], camera);
+        }
+    }
 
-            let aa_x = Vector::unit_x() * angle_x;
-            let aa_y = Vector::unit_y() * angle_y;
-            let rot_x = Transform::rotation(aa_x);
-            let rot_y = Transform::rotation(aa_y);
+    /// Rotate the camera around a point
+    ///
+    /// The angles are given in radians, around the x, y, and z axes.
+    pub fn rotate(
+        rotate_around: Point<3>,
+        [angle_x, angle_y, angle_z]: [f64; 3],
+        camera: &mut Camera,
+    ) {
+        let trans = Transform::translation(rotate_around.coords);
 
-            let inv = trans.inverse();
+        let aa_x = Vector::unit_x() * angle_x;
+        let aa_y = Vector::unit_y() * angle_y;
+        let aa_z = Vector::unit_z() * angle_z;
+        let rot_x = Transform::rotation(aa_x);
+        let rot_y = Transform::rotation(aa_y);
+        let rot_z = Transform::rotation(aa_z);
 
-            camera.rotation = trans * rot_y * rot_x * inv * camera.rotation;
-        }
+        let inv = trans.inverse();
+
+        camera.rotation = trans * rot_z * rot_y * rot_x * inv * camera.rotation;
     }
 }
@@ -0,0 +1,180 @@
+use serde::Deserialize;
+use winit::event::MouseButton;
+
+/// Settings that control how user input is applied to the camera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    /// The input device that these settings are tuned for
+    pub device: Device,
+
+    /// Rotation of the camera, in radians per pixel of cursor movement
+    pub rotation_sensitivity: f64,
+
+    /// Factor that is applied to zoom input from the mouse wheel or touchpad
+    pub zoom_sensitivity: f64,
+
+    /// Invert rotation around the vertical axis
+    pub invert_x: bool,
+
+    /// Invert rotation around the horizontal axis
+    pub invert_y: bool,
+
+    /// Invert the zoom direction
+    pub invert_zoom: bool,
+
+    /// The mouse button that rotates the camera, while it is held
+    pub rotate_button: Button,
+
+    /// The mouse button that moves the camera, while it is held
+    pub move_button: Button,
+}
+
+impl Settings {
+    /// The default settings for an input device
+    pub fn for_device(device: Device) -> Self {
+        match device {
+            Device::Mouse => Self {
+                device,
+                rotation_sensitivity: 0.005,
+                zoom_sensitivity: 1.0,
+                invert_x: false,
+                invert_y: false,
+                invert_zoom: false,
+                rotate_button: Button::Left,
+                move_button: Button::Right,
+            },
+            // Touchpads provide less room for cursor movement, and send a lot
+            // more scroll events than a mouse wheel.
+            Device::Touchpad => Self {
+                rotation_sensitivity: 0.01,
+                zoom_sensitivity: 0.25,
+                ..Self::for_device(Device::Mouse)
+            },
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::for_device(Device::default())
+    }
+}
+
+/// An input device, that [`Settings`] can be tuned for
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Device {
+    /// A mouse with a scroll wheel
+    Mouse,
+
+    /// A touchpad
+    Touchpad,
+}
+
+impl Device {
+    /// Switch to the other device
+    pub fn next(self) -> Self {
+        match self {
+            Self::Mouse => Self::Touchpad,
+            Self::Touchpad => Self::Mouse,
+        }
+    }
+
+    /// Return a human-readable name of the device
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mouse => "mouse",
+            Self::Touchpad => "touchpad",
+        }
+    }
+}
+
+impl Default for Device {
+    fn default() -> Self {
+        Self::Mouse
+    }
+}
+
+/// A mouse button that can be assigned to an action
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Button {
+    /// The left mouse button
+    Left,
+
+    /// The right mouse button
+    Right,
+
+    /// The middle mouse button
+    Middle,
+}
+
+impl From<Button> for MouseButton {
+    fn from(button: Button) -> Self {
+        match button {
+            Button::Left => Self::Left,
+            Button::Right => Self::Right,
+            Button::Middle => Self::Middle,
+        }
+    }
+}
+
+/// Input settings, as loaded from a configuration file
+///
+/// All values are optional. Any value that is not set is taken from the
+/// default [`Settings`] for the configured device.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Config {
+    /// The input device that the settings should be tuned for
+    pub device: Option<Device>,
+
+    /// See [`Settings::rotation_sensitivity`]
+    pub rotation_sensitivity: Option<f64>,
+
+    /// See [`Settings::zoom_sensitivity`]
+    pub zoom_sensitivity: Option<f64>,
+
+    /// See [`Settings::invert_x`]
+    pub invert_x: Option<bool>,
+
+    /// See [`Settings::invert_y`]
+    pub invert_y: Option<bool>,
+
+    /// See [`Settings::invert_zoom`]
+    pub invert_zoom: Option<bool>,
+
+    /// See [`Settings::rotate_button`]
+    pub rotate_button: Option<Button>,
+
+    /// See [`Settings::move_button`]
+    pub move_button: Option<Button>,
+}
+
+impl Config {
+    /// Compute the settings for the configured device
+    pub fn settings(&self) -> Settings {
+        self.settings_for(self.device.unwrap_or_default())
+    }
+
+    /// Compute the settings for the given device
+    ///
+    /// Overrides the device from the configuration, if one is set.
+    pub fn settings_for(&self, device: Device) -> Settings {
+        let defaults = Settings::for_device(device);
+
+        Settings {
+            device,
+            rotation_sensitivity: self
+                .rotation_sensitivity
+                .unwrap_or(defaults.rotation_sensitivity),
+            zoom_sensitivity: self
+                .zoom_sensitivity
+                .unwrap_or(defaults.zoom_sensitivity),
+            invert_x: self.invert_x.unwrap_or(defaults.invert_x),
+            invert_y: self.invert_y.unwrap_or(defaults.invert_y),
+            invert_zoom: self.invert_zoom.unwrap_or(defaults.invert_zoom),
+            rotate_button: self.rotate_button.unwrap_or(defaults.rotate_button),
+            move_button: self.move_button.unwrap_or(defaults.move_button),
+        }
+    }
+}
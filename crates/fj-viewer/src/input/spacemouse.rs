@@ -0,0 +1,172 @@
+//! Support for 3Dconnexion-style 6-DoF input devices
+//!
+//! Only available with the `spacemouse` feature.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+};
+
+use tracing::{info, warn};
+
+use crate::camera::Camera;
+
+use super::deflection::Deflection;
+
+/// USB vendor IDs of 3Dconnexion devices
+///
+/// Older devices were sold under the Logitech vendor ID.
+const VENDOR_IDS: [u16; 2] = [0x256f, 0x046d];
+
+/// The usage page and usage of a multi-axis controller, as per the HID spec
+const USAGE_PAGE: u16 = 0x01;
+const USAGE: u16 = 0x08;
+
+/// The value that devices report, if an axis is fully deflected
+///
+/// This varies a bit between devices, but is close enough for all of them.
+const MAX_DEFLECTION: f64 = 350.0;
+
+/// A connected 6-DoF input device
+///
+/// Reads the device on a background thread, which is stopped, once this is
+/// dropped.
+pub struct SpaceMouse {
+    axes: Arc<Mutex<Axes>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl SpaceMouse {
+    /// Open the first connected device
+    ///
+    /// Returns `None`, if no device is connected, or it can't be opened.
+    pub fn open() -> Option<Self> {
+        let axes = Arc::new(Mutex::new(Axes::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // The device is opened on the background thread, as it can't be sent
+        // between threads on all platforms.
+        let (opened_tx, opened_rx) = mpsc::channel();
+
+        let thread_axes = axes.clone();
+        let thread_stop = stop.clone();
+        thread::spawn(move || {
+            let device = match open_device() {
+                Ok(device) => device,
+                Err(err) => {
+                    let _ = opened_tx.send(Err(err));
+                    return;
+                }
+            };
+            let _ = opened_tx.send(Ok(()));
+
+            let mut report = [0; 64];
+            while !thread_stop.load(Ordering::SeqCst) {
+                match device.read_timeout(&mut report, 100) {
+                    Ok(0) => {}
+                    Ok(len) => {
+                        thread_axes.lock().unwrap().update(&report[..len]);
+                    }
+                    Err(err) => {
+                        warn!("Error reading from 6-DoF device: {}", err);
+                        *thread_axes.lock().unwrap() = Axes::default();
+                        break;
+                    }
+                }
+            }
+        });
+
+        match opened_rx.recv() {
+            Ok(Ok(())) => {
+                info!("Using 6-DoF input device");
+                Some(Self { axes, stop })
+            }
+            Ok(Err(err)) => {
+                info!("No 6-DoF input device available: {}", err);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Apply the current state of the device to the camera
+    ///
+    /// `delta_t` is the time since the last update, in seconds.
+    pub fn apply(&self, delta_t: f64, camera: &mut Camera) {
+        let axes = *self.axes.lock().unwrap();
+        if axes == Axes::default() {
+            return;
+        }
+
+        let [x, y, z] = axes.translation;
+        Deflection {
+            translation: [x, -y, z],
+            rotation: axes.rotation,
+        }
+        .apply(delta_t, camera);
+    }
+}
+
+impl Drop for SpaceMouse {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// The deflection of each axis of the device, between `-1` and `1`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Axes {
+    translation: [f64; 3],
+    rotation: [f64; 3],
+}
+
+impl Axes {
+    /// Update the axes from an input report
+    ///
+    /// Most devices send translation and rotation in separate reports, with
+    /// the IDs `1` and `2`. Newer ones send all axes in report `1`. Other
+    /// reports, like button presses, are ignored.
+    fn update(&mut self, report: &[u8]) {
+        let values = |bytes: &[u8]| {
+            [0, 1, 2].map(|i| {
+                let value =
+                    i16::from_le_bytes([bytes[i * 2], bytes[i * 2 + 1]]);
+                (f64::from(value) / MAX_DEFLECTION).clamp(-1., 1.)
+            })
+        };
+
+        match report {
+            [1, bytes @ ..] if bytes.len() >= 12 => {
+                self.translation = values(&bytes[..6]);
+                self.rotation = values(&bytes[6..12]);
+            }
+            [1, bytes @ ..] if bytes.len() >= 6 => {
+                self.translation = values(bytes);
+            }
+            [2, bytes @ ..] if bytes.len() >= 6 => {
+                self.rotation = values(bytes);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn open_device() -> Result<hidapi::HidDevice, hidapi::HidError> {
+    let api = hidapi::HidApi::new()?;
+
+    let info = api
+        .device_list()
+        .find(|info| {
+            VENDOR_IDS.contains(&info.vendor_id())
+                && info.usage_page() == USAGE_PAGE
+                && info.usage() == USAGE
+        })
+        .ok_or_else(|| hidapi::HidError::HidApiError {
+            message: "No device found".to_owned(),
+        })?;
+
+    info.open_device(&api)
+}
@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+
+use winit::{
+    dpi::PhysicalPosition,
+    event::{Touch, TouchPhase},
+};
+
+/// Tracks the touches that are currently in contact with the screen
+pub struct Touches {
+    positions: BTreeMap<u64, PhysicalPosition<f64>>,
+}
+
+impl Touches {
+    pub fn new() -> Self {
+        Self {
+            positions: BTreeMap::new(),
+        }
+    }
+
+    /// Update the tracked touches
+    ///
+    /// Returns the previous position of the touch, if it was already tracked.
+    pub fn update(&mut self, touch: &Touch) -> Option<PhysicalPosition<f64>> {
+        match touch.phase {
+            TouchPhase::Started | TouchPhase::Moved => {
+                self.positions.insert(touch.id, touch.location)
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.positions.remove(&touch.id)
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// The point in the middle of all touches
+    pub fn centroid(&self) -> Option<PhysicalPosition<f64>> {
+        if self.positions.is_empty() {
+            return None;
+        }
+
+        let n = self.positions.len() as f64;
+        let (x, y) =
+            self.positions.values().fold((0., 0.), |(x, y), position| {
+                (x + position.x, y + position.y)
+            });
+
+        Some(PhysicalPosition::new(x / n, y / n))
+    }
+
+    /// The distance between the first two touches
+    pub fn spread(&self) -> Option<f64> {
+        let mut positions = self.positions.values();
+        let a = positions.next()?;
+        let b = positions.next()?;
+
+        Some((a.x - b.x).hypot(a.y - b.y))
+    }
+}
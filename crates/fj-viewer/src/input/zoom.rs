@@ -1,9 +1,7 @@
-use std::{
-    collections::VecDeque,
-    time::{Duration, Instant},
-};
+use std::{collections::VecDeque, time::Duration};
 
 use fj_math::Point;
+use instant::Instant;
 
 use crate::camera::{Camera, FocusPoint};
 
@@ -131,6 +129,11 @@ impl Zoom {
     pub fn speed(&self) -> f64 {
         self.current_speed
     }
+
+    /// Indicate whether the camera is zooming, or is about to
+    pub fn is_active(&self) -> bool {
+        self.current_speed != 0.0 || !self.events.is_empty()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -8,14 +8,37 @@
 //! users that just want to create CAD models.
 //!
 //! This library provides a model viewer which allows basic navigation and
-//! rendering of generated models.
+//! rendering of generated models. The model viewer can either run as its own
+//! application, using [`run::run`], or be embedded into another winit
+//! application, using [`viewer::Viewer`]. When compiled to WebAssembly, the
+//! `web` module makes the viewer available to JavaScript.
 //!
 //! [Fornjot]: https://www.fornjot.app/
 
 #![warn(missing_docs)]
 
 pub mod camera;
+pub mod frame_pacing;
 pub mod graphics;
 pub mod input;
+pub mod navigation_cube;
+pub mod probe;
+pub mod profiler;
+pub mod review;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod run;
+pub mod status_bar;
+pub mod view_state;
+pub mod viewer;
+pub mod viewport;
+#[cfg(target_arch = "wasm32")]
+pub mod web;
 pub mod window;
+
+mod clipboard;
+mod exploded_view;
+mod prompt;
+#[cfg(not(target_arch = "wasm32"))]
+mod worker;
+#[cfg(not(target_arch = "wasm32"))]
+mod workspace;
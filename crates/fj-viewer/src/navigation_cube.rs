@@ -0,0 +1,140 @@
+//! On-screen orientation cube
+//!
+//! A small cube in the corner of the main viewport, which is rotated along
+//! with the model. Clicking one of its faces, edges, or corners rotates the
+//! model, so it is viewed from that direction.
+
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::{Aabb, Point, Scalar, Transform, Vector};
+use winit::dpi::PhysicalPosition;
+
+use crate::{camera::Camera, viewport::Viewport};
+
+/// The width and height of the cube's viewport, in logical pixels
+const SIZE: u32 = 150;
+
+/// The distance of the cube's viewport from the corner of the main viewport,
+/// in logical pixels
+const MARGIN: u32 = 20;
+
+/// The part of a face, measured from its edge, that counts as the edge
+///
+/// The cube extends from -1 to 1 on each axis.
+const EDGE_WIDTH: f64 = 0.3;
+
+/// The mesh of the cube
+///
+/// Each pair of opposite faces has its own color, so the orientation can be
+/// told at a glance.
+pub fn mesh() -> Mesh<Point<3>> {
+    let mut mesh = Mesh::new();
+
+    let colors: [[Color; 2]; 3] = [
+        [[230, 160, 160, 255], [190, 120, 120, 255]],
+        [[160, 230, 160, 255], [120, 190, 120, 255]],
+        [[160, 160, 230, 255], [120, 120, 190, 255]],
+    ];
+
+    for (axis, [positive, negative]) in colors.into_iter().enumerate() {
+        for (sign, color) in [(1., positive), (-1., negative)] {
+            // Choose the tangents, so the quad winds counter-clockwise when
+            // viewed from the outside.
+            let mut tangents = [(axis + 1) % 3, (axis + 2) % 3];
+            if sign < 0. {
+                tangents.reverse();
+            }
+
+            let corner = |u: f64, v: f64| {
+                let mut components = [0.; 3];
+                components[axis] = sign;
+                components[tangents[0]] = u;
+                components[tangents[1]] = v;
+                Point::from(components)
+            };
+
+            let [a, b, c, d] = [
+                corner(-1., -1.),
+                corner(1., -1.),
+                corner(1., 1.),
+                corner(-1., 1.),
+            ];
+
+            mesh.push_triangle([a, b, c], color);
+            mesh.push_triangle([a, c, d], color);
+        }
+    }
+
+    mesh
+}
+
+/// The viewport that the cube is drawn into
+///
+/// It is located in the bottom-right corner of the main viewport. Its size is
+/// scaled by `scale_factor`, the number of physical pixels per logical pixel.
+pub fn viewport(main: &Viewport, scale_factor: f64) -> Viewport {
+    let [size, margin] =
+        [SIZE, MARGIN].map(|pixels| (pixels as f64 * scale_factor) as u32);
+    let size = size.min(main.width).min(main.height);
+
+    Viewport {
+        x: main.x + main.width.saturating_sub(size + margin),
+        y: main.y + main.height.saturating_sub(size + margin),
+        width: size,
+        height: size,
+    }
+}
+
+/// The camera that the cube is drawn with
+///
+/// The cube is rotated like the model, but neither moved nor zoomed.
+pub fn camera(main: &Camera) -> Camera {
+    let mut camera = Camera::new(&aabb());
+
+    // The rotation of the main camera might also include a translation, if it
+    // rotated around a point other than the origin.
+    let offset = main.rotation.transform_point(&Point::origin());
+    camera.rotation = Transform::translation(-offset.coords) * main.rotation;
+
+    camera.update_planes(&aabb());
+    camera
+}
+
+/// Determine the direction the user clicked on
+///
+/// Returns the direction from the center of the cube towards the face, edge,
+/// or corner under the cursor. Returns `None`, if the cursor isn't pointing
+/// at the cube.
+pub fn pick(
+    main_camera: &Camera,
+    main_viewport: &Viewport,
+    cursor: PhysicalPosition<f64>,
+    scale_factor: f64,
+) -> Option<Vector<3>> {
+    let viewport = viewport(main_viewport, scale_factor);
+    if !viewport.contains(cursor) {
+        return None;
+    }
+
+    let point = camera(main_camera)
+        .focus_point(&viewport, Some(cursor), &mesh(), &aabb())
+        .0?;
+
+    let direction = point.coords.components.map(|component| {
+        if component >= Scalar::from_f64(1. - EDGE_WIDTH) {
+            Scalar::ONE
+        } else if component <= Scalar::from_f64(EDGE_WIDTH - 1.) {
+            -Scalar::ONE
+        } else {
+            Scalar::ZERO
+        }
+    });
+
+    Some(Vector::from(direction))
+}
+
+fn aabb() -> Aabb<3> {
+    Aabb {
+        min: Point::from([-1., -1., -1.]),
+        max: Point::from([1., 1., 1.]),
+    }
+}
@@ -0,0 +1,180 @@
+//! Reading coordinates off the model
+//!
+//! While probing, the point under the cursor snaps to nearby features of the
+//! face it is on: the vertices and the midpoints of the face's edges, and its
+//! center. Those are points that are worth knowing the exact coordinates of,
+//! when deriving dimensions for further modeling.
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::{Mesh, Triangle};
+use fj_math::{Point, Scalar};
+use winit::dpi::PhysicalPosition;
+
+use crate::{camera::Camera, viewport::Viewport};
+
+/// The distance in logical pixels, within which the cursor snaps to a feature
+const SNAP_DISTANCE: f64 = 10.;
+
+/// A point on the model, as read off by the cursor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Probe {
+    /// The point
+    pub point: Point<3>,
+
+    /// The feature that the point has snapped to, if any
+    pub snap: Option<Snap>,
+}
+
+/// A feature of a face, that the probe can snap to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Snap {
+    /// A vertex of one of the face's edges
+    Vertex,
+
+    /// The midpoint of one of the face's edges
+    EdgeMidpoint,
+
+    /// The center of the face, weighted by area
+    FaceCenter,
+}
+
+impl Snap {
+    /// Returns a human-readable name of the feature
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Vertex => "vertex",
+            Self::EdgeMidpoint => "edge midpoint",
+            Self::FaceCenter => "face center",
+        }
+    }
+}
+
+/// Find the point on the model that the cursor points at
+///
+/// If `snap` is `true`, the point snaps to the closest feature of the face it
+/// is on, if that is within [`SNAP_DISTANCE`] on screen. Vertices take
+/// precedence over edge midpoints, which take precedence over the center.
+pub fn probe(
+    camera: &Camera,
+    viewport: &Viewport,
+    cursor: PhysicalPosition<f64>,
+    mesh: &Mesh<Point<3>>,
+    scale_factor: f64,
+    snap: bool,
+) -> Option<Probe> {
+    let (point, triangle) = camera.pick_triangle(viewport, cursor, mesh)?;
+    if !snap {
+        return Some(Probe { point, snap: None });
+    }
+
+    let radius = snap_radius(camera, viewport, point, scale_factor);
+
+    let triangles: Vec<_> = match triangle.face {
+        Some(face) => mesh.triangles_of_face(face).collect(),
+        None => vec![triangle],
+    };
+    let edges = boundary(&triangles);
+
+    let vertices = edges
+        .iter()
+        .flat_map(|&[a, b]| [a, b])
+        .map(|vertex| (vertex, Snap::Vertex));
+    let midpoints = edges.iter().map(|&[a, b]| {
+        (a + (b - a) * Scalar::from_f64(0.5), Snap::EdgeMidpoint)
+    });
+    let center = center(&triangles).map(|center| (center, Snap::FaceCenter));
+
+    let probe = closest(point, radius, vertices)
+        .or_else(|| closest(point, radius, midpoints))
+        .or_else(|| closest(point, radius, center))
+        .unwrap_or(Probe { point, snap: None });
+
+    Some(probe)
+}
+
+/// The size of [`SNAP_DISTANCE`] at `point`, in model units
+pub fn snap_radius(
+    camera: &Camera,
+    viewport: &Viewport,
+    point: Point<3>,
+    scale_factor: f64,
+) -> Scalar {
+    let distance = (point - camera.position()).magnitude().into_f64();
+    let pixel = 2. * distance * (camera.field_of_view_in_x() / 2.).tan()
+        / f64::from(viewport.width);
+
+    Scalar::from_f64(SNAP_DISTANCE * scale_factor * pixel)
+}
+
+/// Find the candidate that is closest to `point`, within `radius`
+fn closest(
+    point: Point<3>,
+    radius: Scalar,
+    candidates: impl IntoIterator<Item = (Point<3>, Snap)>,
+) -> Option<Probe> {
+    candidates
+        .into_iter()
+        .map(|(candidate, snap)| {
+            ((candidate - point).magnitude(), candidate, snap)
+        })
+        .filter(|&(distance, _, _)| distance <= radius)
+        .min_by_key(|&(distance, _, _)| distance)
+        .map(|(_, point, snap)| Probe {
+            point,
+            snap: Some(snap),
+        })
+}
+
+/// Find the edges of the triangles that are on the boundary of the face
+///
+/// Those are the edges that are not shared with another triangle of the face.
+/// Edges between the triangles are an artifact of the triangulation, and not
+/// worth snapping to.
+fn boundary(triangles: &[Triangle]) -> Vec<[Point<3>; 2]> {
+    let mut edges: BTreeMap<[Point<3>; 2], usize> = BTreeMap::new();
+
+    for triangle in triangles {
+        let [a, b, c] = triangle.points;
+
+        for [a, b] in [[a, b], [b, c], [c, a]] {
+            let edge = if a <= b { [a, b] } else { [b, a] };
+            *edges.entry(edge).or_default() += 1;
+        }
+    }
+
+    edges
+        .into_iter()
+        .filter(|&(_, count)| count == 1)
+        .map(|(edge, _)| edge)
+        .collect()
+}
+
+/// Compute the center of the triangles, weighted by area
+///
+/// Returns `None`, if all triangles are degenerate.
+fn center(triangles: &[Triangle]) -> Option<Point<3>> {
+    let mut sum = [0.; 3];
+    let mut total_area = 0.;
+
+    for triangle in triangles {
+        let [a, b, c] = triangle.points;
+        let area = (b - a).cross(&(c - a)).magnitude().into_f64() / 2.;
+
+        for (i, sum) in sum.iter_mut().enumerate() {
+            let centroid = (a.coords.components[i]
+                + b.coords.components[i]
+                + c.coords.components[i])
+                .into_f64()
+                / 3.;
+            *sum += centroid * area;
+        }
+        total_area += area;
+    }
+
+    if total_area == 0. {
+        return None;
+    }
+
+    Some(Point::from(sum.map(|sum| sum / total_area)))
+}
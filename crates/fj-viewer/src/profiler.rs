@@ -0,0 +1,125 @@
+//! Performance metrics for the profiler overlay
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use instant::Instant;
+use tracing::{span, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// The durations of the most recently closed spans, by span name
+///
+/// Register the layer returned by [`Timings::layer`] with the tracing
+/// subscriber, to make span durations available to the profiler overlay.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    last: Arc<Mutex<HashMap<&'static str, Duration>>>,
+}
+
+impl Timings {
+    /// Construct a new instance of `Timings`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a tracing layer that records span durations into this instance
+    pub fn layer(&self) -> TimingsLayer {
+        TimingsLayer {
+            timings: self.clone(),
+        }
+    }
+
+    /// The duration of the most recently closed span with the given name
+    ///
+    /// Returns `None`, if no such span has been closed yet.
+    pub fn last(&self, name: &str) -> Option<Duration> {
+        self.last.lock().unwrap().get(name).copied()
+    }
+}
+
+/// Tracing layer that records span durations into [`Timings`]
+///
+/// A span's duration is the time from its creation until it is closed.
+pub struct TimingsLayer {
+    timings: Timings,
+}
+
+impl<S> Layer<S> for TimingsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        _: &span::Attributes<'_>,
+        id: &span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Created(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(Created(created)) = span.extensions().get::<Created>() {
+                self.timings
+                    .last
+                    .lock()
+                    .unwrap()
+                    .insert(span.name(), created.elapsed());
+            }
+        }
+    }
+}
+
+struct Created(Instant);
+
+/// The metrics that are displayed by the profiler overlay
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Profile {
+    /// The time it took to render the last frame
+    pub frame_time: Duration,
+
+    /// The number of triangles in the model
+    pub triangles: usize,
+
+    /// The time it took to evaluate the model, the last time it was loaded
+    pub evaluation: Option<Duration>,
+
+    /// The time it took to triangulate the model, the last time it was loaded
+    pub triangulation: Option<Duration>,
+}
+
+impl Profile {
+    /// Read the durations of model evaluation and triangulation from `timings`
+    pub fn with_timings(self, timings: &Timings) -> Self {
+        // These are the names of spans defined in `fj-host` and `fj-kernel`.
+        Self {
+            evaluation: timings.last("evaluate_model"),
+            triangulation: timings.last("triangulate"),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn millis(duration: Option<Duration>) -> String {
+            match duration {
+                Some(duration) => {
+                    format!("{:.1} ms", duration.as_secs_f64() * 1000.)
+                }
+                None => "n/a".to_owned(),
+            }
+        }
+
+        writeln!(f, "Frame time: {}", millis(Some(self.frame_time)))?;
+        writeln!(f, "Triangles: {}", self.triangles)?;
+        writeln!(f, "Model evaluation: {}", millis(self.evaluation))?;
+        writeln!(f, "Triangulation: {}", millis(self.triangulation))
+    }
+}
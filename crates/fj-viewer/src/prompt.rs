@@ -0,0 +1,105 @@
+//! Text input in the status bar
+//!
+//! The viewer doesn't have a UI toolkit, so text is entered into a prompt that
+//! replaces the status bar, until it is confirmed or cancelled.
+
+use fj_math::Point;
+use winit::event::VirtualKeyCode;
+
+/// Text that is being entered
+pub struct Prompt {
+    purpose: Purpose,
+    text: String,
+
+    // The key that opened the prompt is received as a character right after,
+    // but it's not meant to be part of the text.
+    skip_character: bool,
+}
+
+impl Prompt {
+    /// Open a prompt for the given purpose
+    ///
+    /// Expects to be called when the key that opens it is pressed.
+    pub fn new(purpose: Purpose) -> Self {
+        Self {
+            purpose,
+            text: String::new(),
+            skip_character: true,
+        }
+    }
+
+    /// Access the purpose of the prompt
+    pub fn purpose(&self) -> Purpose {
+        self.purpose
+    }
+
+    /// Access the text that has been entered, without surrounding whitespace
+    pub fn text(&self) -> &str {
+        self.text.trim()
+    }
+
+    /// Add a character that has been typed
+    pub fn handle_character(&mut self, character: char) {
+        if std::mem::take(&mut self.skip_character) {
+            return;
+        }
+
+        if !character.is_control() {
+            self.text.push(character);
+        }
+    }
+
+    /// Handle a key press
+    ///
+    /// Returns whether the prompt is finished, and how.
+    pub fn handle_key(&mut self, key: VirtualKeyCode) -> Option<Outcome> {
+        // Any key that is pressed after the one that opened the prompt means
+        // that no character is coming for it anymore.
+        self.skip_character = false;
+
+        match key {
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                Some(Outcome::Confirmed)
+            }
+            VirtualKeyCode::Escape => Some(Outcome::Cancelled),
+            VirtualKeyCode::Back => {
+                self.text.pop();
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Format the prompt for the status bar
+    pub fn line(&self) -> String {
+        let label = match self.purpose {
+            Purpose::Annotate(_) => "Annotation",
+            Purpose::SaveViewpoint => "Viewpoint name",
+        };
+
+        format!(
+            "{}: {}_ (confirm with Enter, cancel with Escape)",
+            label, self.text
+        )
+    }
+}
+
+/// What the text of a [`Prompt`] is for
+#[derive(Clone, Copy, Debug)]
+pub enum Purpose {
+    /// The text of an annotation at the given point
+    Annotate(Point<3>),
+
+    /// The name of a viewpoint for the current camera pose
+    SaveViewpoint,
+}
+
+/// How a [`Prompt`] has finished
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// The text has been entered
+    Confirmed,
+
+    /// The text has been discarded
+    Cancelled,
+}
@@ -0,0 +1,169 @@
+//! Annotations and saved viewpoints, for design reviews
+//!
+//! Both are stored in a sidecar file next to the model, unlike the view state,
+//! which lives in the user's cache directory. That way, the sidecar file can be
+//! committed along with the model, and everyone looking at the model sees the
+//! same notes.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use fj_math::{Point, Scalar};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::view_state::CameraPose;
+
+/// The annotations and viewpoints of a model
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Review {
+    /// Notes that are anchored to points on the model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+
+    /// Camera poses that can be returned to by name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub viewpoints: Vec<Viewpoint>,
+}
+
+impl Review {
+    /// Save a viewpoint, replacing any viewpoint with the same name
+    ///
+    /// Returns the index of the viewpoint.
+    pub fn save_viewpoint(&mut self, name: String, pose: CameraPose) -> usize {
+        match self
+            .viewpoints
+            .iter()
+            .position(|viewpoint| viewpoint.name == name)
+        {
+            Some(index) => {
+                self.viewpoints[index].pose = pose;
+                index
+            }
+            None => {
+                self.viewpoints.push(Viewpoint { name, pose });
+                self.viewpoints.len() - 1
+            }
+        }
+    }
+}
+
+/// A note that is anchored to a point on the model
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Annotation {
+    /// The point on the assembled model, in model coordinates
+    pub anchor: [f64; 3],
+
+    /// The text of the note
+    pub text: String,
+}
+
+impl Annotation {
+    /// Create an annotation at a point on the assembled model
+    pub fn new(anchor: Point<3>, text: String) -> Self {
+        Self {
+            anchor: anchor.coords.components.map(Scalar::into_f64),
+            text,
+        }
+    }
+
+    /// Access the anchor as a point
+    pub fn anchor(&self) -> Point<3> {
+        Point::from(self.anchor)
+    }
+}
+
+/// A named camera pose
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Viewpoint {
+    /// The name that the viewpoint is referred to by
+    pub name: String,
+
+    /// The pose of the main camera
+    pub pose: CameraPose,
+}
+
+/// The sidecar file that the review of a model is stored in
+#[derive(Clone, Debug)]
+pub struct Sidecar {
+    path: PathBuf,
+}
+
+impl Sidecar {
+    /// The sidecar file of a model
+    ///
+    /// That's `review.toml` within the directory of a Rust crate, or a file
+    /// with the `review.toml` extension next to a script or OpenSCAD model.
+    pub fn for_model(model: &Path) -> Self {
+        let path = if model.is_dir() {
+            model.join("review.toml")
+        } else {
+            model.with_extension("review.toml")
+        };
+
+        Self { path }
+    }
+
+    /// Access the path of the sidecar file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the stored review
+    ///
+    /// Returns `None`, if nothing has been stored yet. A review that can't be
+    /// read is logged and ignored, like a view state would be.
+    pub fn load(&self) -> Option<Review> {
+        let review = match fs::read_to_string(&self.path) {
+            Ok(review) => review,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                warn!("Failed to read {}: {}", self.path.display(), err);
+                return None;
+            }
+        };
+
+        match toml::from_str(&review) {
+            Ok(review) => Some(review),
+            Err(err) => {
+                warn!("Failed to parse {}: {}", self.path.display(), err);
+                None
+            }
+        }
+    }
+
+    /// Store a review, replacing any previously stored one
+    ///
+    /// An empty review removes the sidecar file instead, so models that have
+    /// never been reviewed don't get one.
+    pub fn save(&self, review: &Review) -> Result<(), SaveError> {
+        if review == &Review::default() {
+            return match fs::remove_file(&self.path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                    Err(err.into())
+                }
+                _ => Ok(()),
+            };
+        }
+
+        let review = toml::to_string(review)?;
+        fs::write(&self.path, review)?;
+
+        Ok(())
+    }
+}
+
+/// Error storing a [`Review`]
+#[derive(Error, Debug)]
+pub enum SaveError {
+    /// The review could not be serialized
+    #[error("Error serializing review: {0}")]
+    Serialize(#[from] toml::ser::Error),
+
+    /// The sidecar file could not be written
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
@@ -1,13 +1,12 @@
 //! Model viewer initialization and event processing
 //!
-//! Provides the functionality to create a window and perform basic viewing
+//! Provides the functionality to create windows and perform basic viewing
 //! with programmed models.
 
-use std::time::Instant;
-
-use fj_host::Watcher;
-use fj_operations::shape_processor::ShapeProcessor;
+use fj_host::{Model, Parameters, Watcher};
+use fj_operations::shape_processor::{ProcessedShape, ShapeProcessor};
 use futures::executor::block_on;
+use instant::Instant;
 use tracing::{trace, warn};
 use winit::{
     event::{Event, WindowEvent},
@@ -15,125 +14,141 @@ use winit::{
 };
 
 use crate::{
-    camera::Camera,
-    graphics::{self, DrawConfig, Renderer},
-    input,
+    graphics, input, profiler::Timings, view_state, viewer::Viewer,
     window::Window,
 };
 
-/// Initializes a model viewer for a given model and enters its process loop.
+/// The models that [`run`] displays
+pub enum Models {
+    /// A single model, that is already being watched
+    Single(Watcher),
+
+    /// Several models, that the user can switch between
+    ///
+    /// See [`Viewer::watch_workspace`].
+    Workspace(Vec<(String, Model)>, Parameters),
+
+    /// A single model, that is compared to a baseline
+    ///
+    /// See [`Viewer::watch_diff`].
+    Diff(Watcher, Box<ProcessedShape>),
+}
+
+/// A window that [`run`] opens
+pub struct ViewerWindow {
+    /// The models that are displayed in the window
+    pub models: Models,
+
+    /// Where the view of the window is restored from, and saved to when it is
+    /// closed
+    pub store: Option<view_state::Store>,
+}
+
+/// Initializes a model viewer for each of the given windows and enters the
+/// process loop.
+///
+/// Each window has its own camera and input state. They all render with the
+/// same GPU. The process loop ends, once all windows have been closed.
+///
+/// The profiler overlay reads the durations of model evaluation and
+/// triangulation from `timings`. User input is applied according to `input`.
+/// If a `backend` is given, it is used instead of the best available one.
+///
+/// See [`Viewer`], for embedding the viewer into another application instead.
 pub fn run(
-    watcher: Watcher,
+    windows: Vec<ViewerWindow>,
     shape_processor: ShapeProcessor,
+    timings: Timings,
+    input: input::Config,
+    theme: graphics::ThemeConfig,
+    backend: Option<graphics::Backend>,
 ) -> Result<(), graphics::InitError> {
     let event_loop = EventLoop::new();
-    let window = Window::new(&event_loop);
-
-    let mut previous_time = Instant::now();
-
-    let mut input_handler = input::Handler::new(previous_time);
-    let mut renderer = block_on(Renderer::new(&window))?;
 
-    let mut draw_config = DrawConfig::default();
+    let mut open: Vec<OpenWindow> = Vec::new();
+    for ViewerWindow { models, store } in windows {
+        let view_state = store
+            .as_ref()
+            .and_then(|store| store.load())
+            .unwrap_or_default();
+
+        let window = Window::new(&event_loop, view_state.window_size);
+
+        let mut viewer = match open.first() {
+            Some(first) => Viewer::with_gpu(&window, first.viewer.gpu())?,
+            None => block_on(Viewer::with_backend(&window, backend))?,
+        };
+        viewer.set_input_config(input.clone());
+        viewer.set_theme(theme);
+        viewer.set_timings(timings.clone());
+        viewer.restore(&view_state);
+        match models {
+            Models::Single(watcher) => viewer.watch(watcher, shape_processor),
+            Models::Workspace(models, parameters) => {
+                viewer.watch_workspace(models, parameters, shape_processor)
+            }
+            Models::Diff(watcher, baseline) => {
+                viewer.watch_diff(watcher, *baseline, shape_processor)
+            }
+        }
 
-    let mut shape = None;
-    let mut camera = None;
+        open.push(OpenWindow {
+            viewer,
+            window,
+            store,
+        });
+    }
 
     event_loop.run(move |event, _, control_flow| {
         trace!("Handling event: {:?}", event);
 
-        let mut actions = input::Actions::new();
-
-        let now = Instant::now();
-
-        if let Some(new_shape) = watcher.receive() {
-            let new_shape = shape_processor.process(&new_shape);
-            renderer.update_geometry(
-                (&new_shape.mesh).into(),
-                (&new_shape.debug_info).into(),
-                new_shape.aabb,
-            );
-
-            if camera.is_none() {
-                camera = Some(Camera::new(&new_shape.aabb));
-            }
-
-            shape = Some(new_shape);
-        }
-
         match event {
-            Event::WindowEvent {
-                event: WindowEvent::CloseRequested,
-                ..
-            } => {
-                *control_flow = ControlFlow::Exit;
-            }
-            Event::WindowEvent {
-                event: WindowEvent::Resized(size),
-                ..
-            } => {
-                renderer.handle_resize(size);
-            }
-            Event::WindowEvent {
-                event: WindowEvent::KeyboardInput { input, .. },
-                ..
-            } => {
-                input_handler.handle_keyboard_input(input, &mut actions);
-            }
-            Event::WindowEvent {
-                event: WindowEvent::CursorMoved { position, .. },
-                ..
-            } => {
-                if let Some(camera) = &mut camera {
-                    input_handler
-                        .handle_cursor_moved(position, camera, &window);
+            Event::WindowEvent { window_id, event } => {
+                let index = open
+                    .iter()
+                    .position(|window| window.window.inner().id() == window_id);
+
+                if let Some(index) = index {
+                    if let WindowEvent::CloseRequested = event {
+                        open.remove(index).close();
+                    } else {
+                        open[index].viewer.handle_event(&event);
+                    }
                 }
             }
-            Event::WindowEvent {
-                event: WindowEvent::MouseInput { state, button, .. },
-                ..
-            } => {
-                if let (Some(shape), Some(camera)) = (&shape, &camera) {
-                    let focus_point = camera.focus_point(
-                        &window,
-                        input_handler.cursor(),
-                        &shape.mesh,
-                    );
-
-                    input_handler.handle_mouse_input(
-                        button,
-                        state,
-                        focus_point,
-                    );
+            Event::LoopDestroyed => {
+                for window in open.drain(..) {
+                    window.close();
                 }
             }
-            Event::WindowEvent {
-                event: WindowEvent::MouseWheel { delta, .. },
-                ..
-            } => {
-                input_handler.handle_mouse_wheel(delta, now);
-            }
             Event::MainEventsCleared => {
-                let delta_t = now.duration_since(previous_time);
-                previous_time = now;
-
-                if let (Some(shape), Some(camera)) = (&shape, &mut camera) {
-                    input_handler.update(
-                        delta_t.as_secs_f64(),
-                        now,
-                        camera,
-                        &window,
-                        &shape.mesh,
-                    );
+                let now = Instant::now();
+
+                // Wake up for the window that needs it first.
+                let mut wake_up: Option<Instant> = None;
+                for window in &mut open {
+                    let schedule = window.viewer.schedule(now);
+                    if schedule.redraw {
+                        window.window.inner().request_redraw();
+                    }
+
+                    wake_up = Some(match wake_up {
+                        Some(wake_up) => wake_up.min(schedule.wake_up),
+                        None => schedule.wake_up,
+                    });
                 }
 
-                window.inner().request_redraw();
+                if let Some(wake_up) = wake_up {
+                    *control_flow = ControlFlow::WaitUntil(wake_up);
+                }
             }
-            Event::RedrawRequested(_) => {
-                if let (Some(shape), Some(camera)) = (&shape, &mut camera) {
-                    camera.update_planes(&shape.aabb);
+            Event::RedrawRequested(window_id) => {
+                let window = open
+                    .iter_mut()
+                    .find(|window| window.window.inner().id() == window_id);
 
-                    if let Err(err) = renderer.draw(camera, &draw_config) {
+                if let Some(window) = window {
+                    if let Err(err) = window.viewer.render() {
                         warn!("Draw error: {}", err);
                     }
                 }
@@ -141,17 +156,34 @@ pub fn run(
             _ => {}
         }
 
-        if actions.exit {
-            *control_flow = ControlFlow::Exit;
-        }
-        if actions.toggle_model {
-            draw_config.draw_model = !draw_config.draw_model;
+        while let Some(index) = open
+            .iter()
+            .position(|window| window.viewer.exit_requested())
+        {
+            open.remove(index).close();
         }
-        if actions.toggle_mesh {
-            draw_config.draw_mesh = !draw_config.draw_mesh;
-        }
-        if actions.toggle_debug {
-            draw_config.draw_debug = !draw_config.draw_debug;
+
+        if open.is_empty() {
+            *control_flow = ControlFlow::Exit;
         }
     });
 }
+
+/// A window that has been opened by [`run`]
+struct OpenWindow {
+    // The viewer renders to the window, so it needs to be dropped first.
+    viewer: Viewer,
+    window: Window,
+    store: Option<view_state::Store>,
+}
+
+impl OpenWindow {
+    /// Save the view of the window, then close it
+    fn close(self) {
+        if let Some(store) = &self.store {
+            if let Err(err) = store.save(&self.viewer.view_state()) {
+                warn!("Failed to save view state: {}", err);
+            }
+        }
+    }
+}
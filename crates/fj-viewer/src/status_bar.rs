@@ -0,0 +1,168 @@
+//! The status bar at the bottom of the window
+//!
+//! Shows statistics about the current model, and the coordinates of the point
+//! on the model that the cursor points at. The same values can be copied to
+//! the clipboard, formatted by [`ModelStatistics`].
+
+use std::collections::BTreeSet;
+
+use fj_math::{Point, Scalar, Vector};
+use fj_operations::{bom::BillOfMaterials, shape_processor::ProcessedShape};
+
+use crate::probe::Probe;
+
+/// Statistics about a processed model
+///
+/// These only change when the model is reloaded, so they are computed once,
+/// rather than on every frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModelStatistics {
+    /// The number of triangles in the mesh
+    pub triangles: usize,
+
+    /// The number of distinct faces that the triangles belong to
+    pub faces: usize,
+
+    /// The tolerance that the mesh was created with
+    pub tolerance: Scalar,
+
+    /// The size of the model's bounding box
+    pub size: Vector<3>,
+
+    /// The volume of the model
+    pub volume: f64,
+
+    /// The surface area of the model
+    pub area: f64,
+
+    /// The mass of the model in grams, if all of its parts have a material
+    pub mass: Option<f64>,
+}
+
+impl ModelStatistics {
+    /// Compute the statistics of a processed model
+    pub fn new(shape: &ProcessedShape) -> Self {
+        let faces: BTreeSet<_> = shape
+            .mesh
+            .triangles()
+            .filter_map(|triangle| triangle.face)
+            .collect();
+
+        let bom = BillOfMaterials::new(shape, None);
+
+        Self {
+            triangles: shape.mesh.triangles().count(),
+            faces: faces.len(),
+            tolerance: shape.tolerance.inner(),
+            size: shape.aabb.size(),
+            volume: bom.total_volume(),
+            area: bom.total_area(),
+            mass: bom.total_mass(),
+        }
+    }
+
+    /// Format the status line
+    ///
+    /// `pointer` is the point on the model that the cursor points at, if any.
+    /// While a distance is measured from `measure_from`, the distance to the
+    /// pointer is shown. While `probing`, the feature that the pointer has
+    /// snapped to is shown too.
+    pub fn status_line(
+        &self,
+        pointer: Option<Probe>,
+        measure_from: Option<Point<3>>,
+        probing: bool,
+    ) -> String {
+        let [x, y, z] = self.size.components.map(Scalar::into_f64);
+
+        let mut line = format!(
+            "Triangles: {} | Faces: {} | Tolerance: {} | Size: {:.3} x {:.3} x \
+            {:.3}",
+            self.triangles, self.faces, self.tolerance, x, y, z,
+        );
+
+        if let Some(mass) = self.mass {
+            line.push_str(&format!(" | Mass: {:.1} g", mass));
+        }
+        if probing {
+            line.push_str(" | Probing");
+        }
+        if let Some(probe) = pointer {
+            let [x, y, z] = probe.point.coords.components.map(Scalar::into_f64);
+            line.push_str(&format!(
+                " | Pointer: ({:.3}, {:.3}, {:.3})",
+                x, y, z
+            ));
+            if let Some(snap) = probe.snap {
+                line.push_str(&format!(" [{}]", snap.name()));
+            }
+        }
+        let pointer = pointer.map(|probe| probe.point);
+        if let Some(from) = measure_from {
+            match pointer {
+                Some(pointer) => line.push_str(&format!(
+                    " | Distance: {:.3}",
+                    (pointer - from).magnitude().into_f64()
+                )),
+                None => line.push_str(" | Distance: -"),
+            }
+        }
+
+        line
+    }
+
+    /// Format the coordinates of a point, for pasting into source code
+    ///
+    /// Digits that are below the tolerance are left out, as they are just
+    /// noise from the approximation.
+    pub fn format_point(&self, point: Point<3>) -> String {
+        let [x, y, z] = point.coords.components.map(Scalar::into_f64);
+        let decimals = self.decimals();
+
+        format!(
+            "[{:.*}, {:.*}, {:.*}]",
+            decimals, x, decimals, y, decimals, z
+        )
+    }
+
+    /// Format a distance, for pasting into source code
+    ///
+    /// See [`ModelStatistics::format_point`].
+    pub fn format_distance(&self, a: Point<3>, b: Point<3>) -> String {
+        format!("{:.*}", self.decimals(), (b - a).magnitude().into_f64())
+    }
+
+    /// Format the mass properties of the model, for pasting into source code
+    ///
+    /// Each property is on its own line, as `name = value`. The mass is only
+    /// included, if it is known.
+    pub fn format_properties(&self) -> String {
+        let decimals = self.decimals();
+        let [x, y, z] = self.size.components.map(Scalar::into_f64);
+
+        let mut properties = format!(
+            "volume = {:.*}\narea = {:.*}\nsize = [{:.*}, {:.*}, {:.*}]\n",
+            decimals,
+            self.volume,
+            decimals,
+            self.area,
+            decimals,
+            x,
+            decimals,
+            y,
+            decimals,
+            z,
+        );
+        if let Some(mass) = self.mass {
+            properties.push_str(&format!("mass = {:.1}\n", mass));
+        }
+
+        properties
+    }
+
+    /// The number of decimals that are significant at the tolerance
+    fn decimals(&self) -> usize {
+        let decimals = -self.tolerance.into_f64().log10().floor();
+        decimals.max(0.) as usize + 1
+    }
+}
@@ -0,0 +1,202 @@
+//! Persistence of the view between sessions
+//!
+//! The camera pose, render settings, and window size are stored per model, so
+//! reopening a model returns to the previous view.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use fj_math::Transform;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::{camera::Camera, graphics::DrawConfig};
+
+/// The state of the view that is restored when reopening a model
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ViewState {
+    /// The inner size of the window, in physical pixels
+    pub window_size: Option<[u32; 2]>,
+
+    /// The camera pose, if the model has been displayed before
+    pub camera: Option<CameraPose>,
+
+    /// The render settings that can be toggled by the user
+    pub render: Option<RenderSettings>,
+}
+
+impl ViewState {
+    /// Capture the current state of the view
+    pub fn capture(
+        camera: Option<&Camera>,
+        draw_config: &DrawConfig,
+        window_size: [u32; 2],
+    ) -> Self {
+        Self {
+            window_size: Some(window_size),
+            camera: camera.map(Camera::pose),
+            render: Some(RenderSettings::from_draw_config(draw_config)),
+        }
+    }
+}
+
+/// The transform of the camera
+///
+/// See [`Camera`] for the meaning of the two parts. Returned by
+/// [`Camera::pose`], and applied by [`Camera::set_pose`].
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CameraPose {
+    /// The data of [`Camera::rotation`], as returned by [`Transform::data`]
+    pub rotation: [f64; 16],
+
+    /// The data of [`Camera::translation`], as returned by [`Transform::data`]
+    pub translation: [f64; 16],
+}
+
+impl CameraPose {
+    /// Capture the pose of a camera
+    pub fn from_camera(camera: &Camera) -> Self {
+        let data = |transform: &Transform| {
+            let mut data = [0.; 16];
+            data.copy_from_slice(transform.data());
+            data
+        };
+
+        Self {
+            rotation: data(&camera.rotation),
+            translation: data(&camera.translation),
+        }
+    }
+
+    /// Move a camera into this pose
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.rotation = Transform::from_data(self.rotation);
+        camera.translation = Transform::from_data(self.translation);
+    }
+}
+
+/// The parts of [`DrawConfig`] that are worth restoring
+///
+/// Settings that depend on the model's current geometry, like the selected
+/// face, are not included.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RenderSettings {
+    /// See [`DrawConfig::draw_model`]
+    pub draw_model: bool,
+
+    /// See [`DrawConfig::draw_mesh`]
+    pub draw_mesh: bool,
+
+    /// See [`DrawConfig::draw_debug`]
+    pub draw_debug: bool,
+
+    /// See [`DrawConfig::smooth_normals`]
+    pub smooth_normals: bool,
+
+    /// See [`DrawConfig::draw_profiler`]
+    pub draw_profiler: bool,
+}
+
+impl RenderSettings {
+    /// Capture the render settings of a draw configuration
+    pub fn from_draw_config(draw_config: &DrawConfig) -> Self {
+        Self {
+            draw_model: draw_config.draw_model,
+            draw_mesh: draw_config.draw_mesh,
+            draw_debug: draw_config.draw_debug,
+            smooth_normals: draw_config.smooth_normals,
+            draw_profiler: draw_config.draw_profiler,
+        }
+    }
+
+    /// Apply these render settings to a draw configuration
+    pub fn apply(&self, draw_config: &mut DrawConfig) {
+        draw_config.draw_model = self.draw_model;
+        draw_config.draw_mesh = self.draw_mesh;
+        draw_config.draw_debug = self.draw_debug;
+        draw_config.smooth_normals = self.smooth_normals;
+        draw_config.draw_profiler = self.draw_profiler;
+    }
+}
+
+/// Location where the view state of a model is stored
+#[derive(Clone, Debug)]
+pub struct Store {
+    path: PathBuf,
+}
+
+impl Store {
+    /// The store for a model, located in the user's cache directory
+    ///
+    /// Returns `None`, if no cache directory could be determined.
+    pub fn for_model(model: &Path) -> Option<Self> {
+        let model = model.canonicalize().unwrap_or_else(|_| model.to_owned());
+
+        // Use the full model path as the file name, so models with the same
+        // name in different locations don't overwrite each other's state.
+        let name: String = model
+            .to_string_lossy()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        let path = dirs::cache_dir()?
+            .join("fornjot")
+            .join("views")
+            .join(name)
+            .with_extension("toml");
+
+        Some(Self { path })
+    }
+
+    /// Load the stored view state
+    ///
+    /// Returns `None`, if no state has been stored yet. A state that can't be
+    /// read is logged and ignored, as there's nothing the user could do about
+    /// it anyway.
+    pub fn load(&self) -> Option<ViewState> {
+        let state = match fs::read_to_string(&self.path) {
+            Ok(state) => state,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                warn!("Failed to read view state: {}", err);
+                return None;
+            }
+        };
+
+        match toml::from_str(&state) {
+            Ok(state) => Some(state),
+            Err(err) => {
+                warn!("Failed to parse view state: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Store a view state, replacing any previously stored one
+    pub fn save(&self, state: &ViewState) -> Result<(), SaveError> {
+        let state = toml::to_string(state)?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, state)?;
+
+        Ok(())
+    }
+}
+
+/// Error storing a [`ViewState`]
+#[derive(Error, Debug)]
+pub enum SaveError {
+    /// The view state could not be serialized
+    #[error("Error serializing view state: {0}")]
+    Serialize(#[from] toml::ser::Error),
+
+    /// The view state could not be written
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
@@ -0,0 +1,1232 @@
+//! The model viewer, ready to be embedded into an application
+//!
+//! [`Viewer`] contains the render loop, cameras, and input handling. It
+//! doesn't own a window or an event loop, so it can be driven by any winit
+//! application. [`crate::run::run`] is one such application.
+
+use std::rc::Rc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use fj_host::{Model, Parameters, Watcher};
+use fj_math::{Aabb, Point, Scalar};
+#[cfg(not(target_arch = "wasm32"))]
+use fj_operations::shape_processor::ShapeProcessor;
+use fj_operations::{
+    bom::BillOfMaterials, level_of_detail::LevelOfDetail,
+    shape_processor::ProcessedShape,
+};
+use instant::Instant;
+use raw_window_handle::HasRawWindowHandle;
+use tracing::warn;
+use winit::{
+    dpi::PhysicalPosition,
+    event::{ElementState, MouseButton, WindowEvent},
+};
+
+use crate::{
+    camera::Camera,
+    clipboard::Clipboard,
+    exploded_view::ExplodedView,
+    frame_pacing::{Schedule, MAX_FRAME_TIME},
+    graphics::{
+        Backend, DrawConfig, DrawError, Gpu, InitError, QualityMetric,
+        Renderer, Status, Surface, ThemeConfig,
+    },
+    input, navigation_cube,
+    probe::{self, Probe},
+    profiler::{Profile, Timings},
+    prompt::{Outcome, Prompt, Purpose},
+    review::{Annotation, Review, Sidecar},
+    status_bar::ModelStatistics,
+    view_state::{CameraPose, ViewState},
+    viewport::{Layout, Viewport},
+    window::Window,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    worker::Worker,
+    workspace::{Parked, Workspace},
+};
+
+/// A surface that the viewer can render to
+///
+/// This is implemented for winit's window, as well as for [`Window`]. Anything
+/// else that provides a raw window handle can implement it too.
+pub trait SurfaceTarget: HasRawWindowHandle {
+    /// The size of the surface in physical pixels, as width and height
+    fn size(&self) -> [u32; 2];
+
+    /// The number of physical pixels per logical pixel
+    ///
+    /// Defaults to `1.0`, for surfaces that don't know about HiDPI displays.
+    fn scale_factor(&self) -> f64 {
+        1.0
+    }
+}
+
+impl SurfaceTarget for winit::window::Window {
+    fn size(&self) -> [u32; 2] {
+        let size = self.inner_size();
+        [size.width, size.height]
+    }
+
+    fn scale_factor(&self) -> f64 {
+        winit::window::Window::scale_factor(self)
+    }
+}
+
+impl SurfaceTarget for Window {
+    fn size(&self) -> [u32; 2] {
+        [self.width(), self.height()]
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.inner().scale_factor()
+    }
+}
+
+/// The model viewer
+///
+/// Forward window events to [`Viewer::handle_event`], and call
+/// [`Viewer::render`] whenever the window needs to be redrawn. The viewer only
+/// needs to be redrawn, when something has changed. Ask it using
+/// [`Viewer::schedule`], whenever the event loop is about to wait. The viewer
+/// rebuilds the model in the background, once it has been pointed at one
+/// using [`Viewer::watch`]. Alternatively, an already processed shape can be
+/// displayed using [`Viewer::show_shape`].
+///
+/// # Examples
+/// ```rust no_run
+/// use fj_viewer::viewer::Viewer;
+/// use futures::executor::block_on;
+/// use instant::Instant;
+/// use winit::{
+///     event::{Event, WindowEvent},
+///     event_loop::{ControlFlow, EventLoop},
+///     window::Window,
+/// };
+///
+/// let event_loop = EventLoop::new();
+/// let window = Window::new(&event_loop).unwrap();
+///
+/// let mut viewer = block_on(Viewer::new(&window)).unwrap();
+///
+/// event_loop.run(move |event, _, control_flow| match event {
+///     Event::WindowEvent {
+///         event: WindowEvent::CloseRequested,
+///         ..
+///     } => *control_flow = ControlFlow::Exit,
+///     Event::WindowEvent { event, .. } => viewer.handle_event(&event),
+///     Event::MainEventsCleared => {
+///         let schedule = viewer.schedule(Instant::now());
+///         if schedule.redraw {
+///             window.request_redraw();
+///         }
+///         *control_flow = ControlFlow::WaitUntil(schedule.wake_up);
+///     }
+///     Event::RedrawRequested(_) => viewer.render().unwrap(),
+///     _ => {}
+/// });
+/// ```
+pub struct Viewer {
+    renderer: Renderer,
+    input_handler: input::Handler,
+    input_config: input::Config,
+    theme: ThemeConfig,
+    draw_config: DrawConfig,
+    timings: Timings,
+
+    // Models are rebuilt in the background, so the window stays responsive in
+    // the meantime. There are no threads to do that on the web.
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: Option<Worker>,
+
+    // The models the user can switch between, if there are several. The state
+    // of the active one is owned by the viewer, like that of a single model.
+    #[cfg(not(target_arch = "wasm32"))]
+    workspace: Option<Workspace>,
+
+    shape: Option<ProcessedShape>,
+    statistics: Option<ModelStatistics>,
+    bom: Option<String>,
+
+    // What's displayed of the shape, which differs from the shape itself while
+    // it's exploded.
+    exploded_view: ExplodedView,
+
+    // The error of the last failed rebuild, until the user dismisses it.
+    error: Option<String>,
+
+    clipboard: Clipboard,
+
+    // The point that a distance is being measured from, if any.
+    measure_from: Option<Point<3>>,
+
+    // Whether the pointer snaps to features of faces, and clicks copy it.
+    probing: bool,
+
+    // The annotations and viewpoints of the model, and where they are stored.
+    review: Review,
+    sidecar: Option<Sidecar>,
+
+    // The viewpoint that the main camera was last moved to, if any.
+    viewpoint: Option<usize>,
+
+    // Text that is being entered. Key presses go there, instead of to the
+    // input handler, until it is confirmed or cancelled.
+    prompt: Option<Prompt>,
+
+    // One camera per viewport. The first one is the main camera, which the
+    // other ones are created relative to.
+    cameras: Vec<Camera>,
+
+    // The viewport that input is applied to.
+    active: usize,
+
+    // The pose the main camera starts in, once the first model is loaded.
+    initial_pose: Option<CameraPose>,
+
+    size: [u32; 2],
+
+    // The number of physical pixels per logical pixel. Everything that's
+    // measured in pixels on screen, like text, is scaled by this.
+    scale_factor: f64,
+
+    exit_requested: bool,
+
+    profile: Profile,
+    previous_frame: Instant,
+
+    // Whether the next frame would look different from the previous one.
+    changed: bool,
+}
+
+impl Viewer {
+    /// Create a viewer that renders to the given surface
+    ///
+    /// The viewer starts out without a model. Call [`Viewer::watch`] to
+    /// display one.
+    pub async fn new(
+        surface_target: &impl SurfaceTarget,
+    ) -> Result<Self, InitError> {
+        Self::with_backend(surface_target, None).await
+    }
+
+    /// Create a viewer that renders to the given surface, using the given
+    /// backend
+    ///
+    /// See [`Renderer::with_backend`].
+    pub async fn with_backend(
+        surface_target: &impl SurfaceTarget,
+        backend: Option<Backend>,
+    ) -> Result<Self, InitError> {
+        let renderer = Renderer::with_backend(surface_target, backend).await?;
+        Ok(Self::with_renderer(surface_target, renderer))
+    }
+
+    /// Create a viewer that renders to the given surface, using the GPU of
+    /// another viewer
+    ///
+    /// This is how additional windows are opened. Each viewer has its own
+    /// surface, cameras, and input state. See [`Renderer::with_gpu`].
+    pub fn with_gpu(
+        surface_target: &impl SurfaceTarget,
+        gpu: Rc<Gpu>,
+    ) -> Result<Self, InitError> {
+        let renderer = Renderer::with_gpu(surface_target, gpu)?;
+        Ok(Self::with_renderer(surface_target, renderer))
+    }
+
+    /// Returns the GPU that the viewer renders with
+    ///
+    /// Pass it to [`Viewer::with_gpu`], to open another window.
+    pub fn gpu(&self) -> Rc<Gpu> {
+        self.renderer.gpu()
+    }
+
+    fn with_renderer(
+        surface_target: &impl SurfaceTarget,
+        renderer: Renderer,
+    ) -> Self {
+        let now = Instant::now();
+
+        let input_config = input::Config::default();
+        let theme = ThemeConfig::default();
+
+        let mut input_handler =
+            input::Handler::new(now, input_config.settings());
+        input_handler.set_scale_factor(surface_target.scale_factor());
+        let draw_config = DrawConfig {
+            theme: theme.theme(),
+            ..DrawConfig::default()
+        };
+
+        Self {
+            renderer,
+            input_handler,
+            input_config,
+            theme,
+            draw_config,
+            timings: Timings::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            worker: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            workspace: None,
+            shape: None,
+            statistics: None,
+            bom: None,
+            exploded_view: ExplodedView::default(),
+            error: None,
+            clipboard: Clipboard::default(),
+            measure_from: None,
+            probing: false,
+            review: Review::default(),
+            sidecar: None,
+            viewpoint: None,
+            prompt: None,
+            cameras: Vec::new(),
+            active: 0,
+            initial_pose: None,
+            size: surface_target.size(),
+            scale_factor: surface_target.scale_factor(),
+            exit_requested: false,
+            profile: Profile::default(),
+            previous_frame: now,
+            changed: true,
+        }
+    }
+
+    /// Apply user input according to the given configuration
+    pub fn set_input_config(&mut self, config: input::Config) {
+        self.input_handler.set_settings(config.settings());
+        self.input_config = config;
+        self.changed = true;
+    }
+
+    /// Draw the viewer with the given theme
+    pub fn set_theme(&mut self, theme: ThemeConfig) {
+        self.draw_config.theme = theme.theme();
+        self.theme = theme;
+        self.changed = true;
+    }
+
+    /// Show the durations of model evaluation and triangulation from `timings`
+    /// in the profiler overlay
+    pub fn set_timings(&mut self, timings: Timings) {
+        self.timings = timings;
+    }
+
+    /// Restore a previously captured view state
+    ///
+    /// The camera pose is applied once the first model is loaded. The window
+    /// size is up to the application, and is ignored here.
+    pub fn restore(&mut self, state: &ViewState) {
+        if let Some(render) = state.render {
+            render.apply(&mut self.draw_config);
+        }
+        if let Some(pose) = state.camera {
+            match self.cameras.first_mut() {
+                Some(camera) => pose.apply(camera),
+                None => self.initial_pose = Some(pose),
+            }
+        }
+
+        self.changed = true;
+    }
+
+    /// Capture the current state of the view
+    pub fn view_state(&self) -> ViewState {
+        ViewState::capture(self.cameras.first(), &self.draw_config, self.size)
+    }
+
+    /// Display the model of the given watcher
+    ///
+    /// The model is rebuilt on a background thread, whenever it changes. Its
+    /// annotations and viewpoints are loaded from its sidecar file, and saved
+    /// there whenever they change. See [`Sidecar`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch(&mut self, watcher: Watcher, shape_processor: ShapeProcessor) {
+        self.start_worker(watcher, shape_processor, None);
+    }
+
+    /// Display how the model of the given watcher differs from `baseline`
+    ///
+    /// Like [`Viewer::watch`], but each version of the model is compared to
+    /// the baseline, which is usually an older version of it. Added and
+    /// removed volume are colored differently. See [`diff_shapes`].
+    ///
+    /// [`diff_shapes`]: fj_operations::diff::diff_shapes
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch_diff(
+        &mut self,
+        watcher: Watcher,
+        baseline: ProcessedShape,
+        shape_processor: ShapeProcessor,
+    ) {
+        self.start_worker(watcher, shape_processor, Some(baseline));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_worker(
+        &mut self,
+        watcher: Watcher,
+        shape_processor: ShapeProcessor,
+        baseline: Option<ProcessedShape>,
+    ) {
+        let sidecar = Sidecar::for_model(watcher.model().path());
+        self.review = sidecar.load().unwrap_or_default();
+        self.sidecar = Some(sidecar);
+        self.viewpoint = None;
+
+        self.worker = Some(Worker::start(watcher, shape_processor, baseline));
+    }
+
+    /// Display the first of the given models, and let the user switch between
+    /// them
+    ///
+    /// Each model is watched, once it is displayed for the first time. The
+    /// same parameters are passed to all of them.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch_workspace(
+        &mut self,
+        models: Vec<(String, Model)>,
+        parameters: Parameters,
+        shape_processor: ShapeProcessor,
+    ) {
+        if models.is_empty() {
+            self.error = Some("No models found".to_owned());
+            return;
+        }
+
+        self.workspace =
+            Some(Workspace::new(models, parameters, shape_processor));
+        self.switch_model(0);
+    }
+
+    /// Display an already processed shape
+    ///
+    /// Replaces the current model, if any. The shape is always drawn in full
+    /// detail. Models that are watched are also simplified, so they can be
+    /// drawn faster from afar.
+    pub fn show_shape(&mut self, shape: ProcessedShape) {
+        self.display(shape, Vec::new());
+    }
+
+    fn display(
+        &mut self,
+        shape: ProcessedShape,
+        levels_of_detail: Vec<LevelOfDetail>,
+    ) {
+        // A new version of the model is shown right away, at the current
+        // explosion, without moving its parts there.
+        self.exploded_view = ExplodedView::new(
+            &shape,
+            self.draw_config.explosion,
+            levels_of_detail,
+        );
+        self.renderer.update_geometry(
+            self.exploded_view.mesh(&shape),
+            self.exploded_view.shortcuts(),
+            &surfaces(&shape),
+            &shape.debug_info,
+            self.exploded_view.aabb(&shape),
+            &self.draw_config,
+        );
+
+        if self.cameras.is_empty() {
+            let mut camera = Camera::new(&shape.aabb);
+            if let Some(pose) = self.initial_pose.take() {
+                camera.set_pose(&pose);
+            }
+
+            self.cameras.push(camera);
+            set_up_views(
+                &mut self.cameras,
+                self.draw_config.layout,
+                &shape.aabb,
+                Instant::now(),
+            );
+        }
+
+        self.profile.triangles = shape.mesh.triangles().count();
+        self.statistics = Some(ModelStatistics::new(&shape));
+        self.measure_from = None;
+        self.bom = Some(bom_panel(&BillOfMaterials::new(&shape, None)));
+        self.shape = Some(shape);
+        self.error = None;
+        self.changed = true;
+    }
+
+    /// Indicate whether the user asked to exit the viewer
+    ///
+    /// It's up to the application to act on this.
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    /// Decide when to draw the next frame
+    ///
+    /// Call this whenever the event loop is about to wait. See [`Schedule`].
+    pub fn schedule(&mut self, now: Instant) -> Schedule {
+        // A model that was rebuilt in the background is a change, but it
+        // doesn't come with a window event.
+        #[cfg(not(target_arch = "wasm32"))]
+        self.receive_shape();
+
+        Schedule::new(self.changed, self.previous_frame, now)
+    }
+
+    /// Handle an event of the window that the viewer renders to
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        let mut actions = input::Actions::new();
+
+        // Pretty much any event changes what's displayed, if only the status
+        // bar. Redrawing is cheap enough, that it's not worth finding out.
+        self.changed = true;
+
+        let now = Instant::now();
+        let viewports = self.viewports();
+
+        match *event {
+            WindowEvent::Resized(size) => {
+                self.renderer.handle_resize(size);
+                self.size = [size.width, size.height];
+            }
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                ref new_inner_size,
+            } => {
+                // The window is moved to a display with a different pixel
+                // density, or the settings of the display have changed.
+                let size = **new_inner_size;
+
+                self.renderer.handle_resize(size);
+                self.renderer.set_scale_factor(scale_factor);
+                self.input_handler.set_scale_factor(scale_factor);
+
+                self.size = [size.width, size.height];
+                self.scale_factor = scale_factor;
+            }
+            WindowEvent::Focused(focused) => {
+                self.input_handler.set_focused(focused);
+            }
+            WindowEvent::KeyboardInput { input, .. } => {
+                // Releases still go to the input handler, so keys that were
+                // held when the prompt opened don't get stuck.
+                let pressed = input
+                    .virtual_keycode
+                    .filter(|_| input.state == ElementState::Pressed);
+
+                match (&mut self.prompt, pressed) {
+                    (Some(prompt), Some(key)) => {
+                        if let Some(outcome) = prompt.handle_key(key) {
+                            self.finish_prompt(outcome);
+                        }
+                    }
+                    _ => self
+                        .input_handler
+                        .handle_keyboard_input(input, &mut actions),
+                }
+            }
+            WindowEvent::ReceivedCharacter(character) => {
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.handle_character(character);
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                if let Some(camera) = self.cameras.get_mut(self.active) {
+                    self.input_handler.handle_cursor_moved(
+                        position,
+                        camera,
+                        &viewports[self.active],
+                    );
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let cursor = self.input_handler.cursor();
+
+                if state == ElementState::Pressed {
+                    self.active = viewport_at(&viewports, cursor);
+                }
+
+                // Clicks on the navigation cube don't go to the input handler,
+                // as they are not meant to start rotating or moving the model.
+                let direction = match (self.cameras.first(), cursor) {
+                    (Some(camera), Some(cursor))
+                        if state == ElementState::Pressed =>
+                    {
+                        navigation_cube::pick(
+                            camera,
+                            &viewports[0],
+                            cursor,
+                            self.scale_factor,
+                        )
+                    }
+                    _ => None,
+                };
+
+                if let (Some(shape), Some(direction)) = (&self.shape, direction)
+                {
+                    let aabb = self.exploded_view.aabb(shape);
+                    self.cameras[0].look_from(direction, &aabb, now);
+                } else if self.probing
+                    && button == MouseButton::Left
+                    && state == ElementState::Pressed
+                {
+                    // While probing, clicks copy the point, instead of
+                    // rotating the model.
+                    actions.copy_pointer = true;
+                } else if let (Some(shape), Some(camera)) =
+                    (&self.shape, self.cameras.get(self.active))
+                {
+                    let focus_point = camera.focus_point(
+                        &viewports[self.active],
+                        cursor,
+                        self.exploded_view.mesh(shape),
+                        &self.exploded_view.aabb(shape),
+                    );
+
+                    self.input_handler.handle_mouse_input(
+                        button,
+                        state,
+                        focus_point,
+                    );
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.active =
+                    viewport_at(&viewports, self.input_handler.cursor());
+                self.input_handler.handle_mouse_wheel(delta, now);
+            }
+            WindowEvent::Touch(touch) => {
+                if let (Some(shape), Some(camera)) =
+                    (&self.shape, self.cameras.get_mut(self.active))
+                {
+                    self.input_handler.handle_touch(
+                        touch,
+                        now,
+                        camera,
+                        &viewports[self.active],
+                        self.exploded_view.mesh(shape),
+                        &self.exploded_view.aabb(shape),
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        self.apply_actions(&actions, now);
+    }
+
+    /// Render the current state of the viewer
+    ///
+    /// This also advances any ongoing camera movement. Use
+    /// [`Viewer::schedule`], to find out when it should be called.
+    pub fn render(&mut self) -> Result<(), DrawError> {
+        let now = Instant::now();
+        let frame_time = now.duration_since(self.previous_frame);
+        let delta_t = frame_time.min(MAX_FRAME_TIME);
+        self.previous_frame = now;
+
+        let mut animating = false;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.receive_shape();
+
+        let viewports = self.viewports();
+
+        for camera in &mut self.cameras {
+            camera.update(now);
+        }
+
+        // There's nothing to draw before the first model has been loaded, but
+        // any error or progress should be visible anyway.
+        if let Some(shape) = &self.shape {
+            let exploded = self.exploded_view.update(
+                shape,
+                self.draw_config.explosion,
+                delta_t.as_secs_f64(),
+            );
+            if exploded {
+                animating = true;
+                self.renderer.update_geometry(
+                    self.exploded_view.mesh(shape),
+                    self.exploded_view.shortcuts(),
+                    &surfaces(shape),
+                    &shape.debug_info,
+                    self.exploded_view.aabb(shape),
+                    &self.draw_config,
+                );
+            }
+
+            let mesh = self.exploded_view.mesh(shape);
+            let aabb = self.exploded_view.aabb(shape);
+
+            if let Some(camera) = self.cameras.get_mut(self.active) {
+                self.input_handler.update(
+                    delta_t.as_secs_f64(),
+                    now,
+                    camera,
+                    &viewports[self.active],
+                    mesh,
+                    &aabb,
+                );
+            }
+
+            for camera in &mut self.cameras {
+                camera.update_planes(&aabb);
+            }
+        }
+
+        animating |= self.input_handler.is_active()
+            || self.cameras.iter().any(Camera::is_animating);
+
+        // The profiler shows the frame time, which only makes sense, if frames
+        // are drawn continuously.
+        animating |= self.draw_config.draw_profiler;
+
+        self.profile.frame_time = frame_time;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let status = self.worker.as_ref().and_then(|worker| {
+            let progress = worker.progress()?;
+
+            let status = match progress.fraction() {
+                Some(fraction) => {
+                    let done = (fraction * 20.) as usize;
+                    format!(
+                        "Rebuilding model... [{}{}] {:.0}%",
+                        "#".repeat(done),
+                        "-".repeat(20 - done),
+                        fraction * 100.
+                    )
+                }
+                // No work has been added yet, which means the model is still
+                // being compiled.
+                None => "Rebuilding model...".to_owned(),
+            };
+
+            Some(status)
+        });
+        #[cfg(target_arch = "wasm32")]
+        let status: Option<String> = None;
+
+        // Keep the progress of a rebuild up to date.
+        animating |= status.is_some();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let models = self.workspace.as_ref().map(Workspace::list);
+        #[cfg(target_arch = "wasm32")]
+        let models: Option<String> = None;
+
+        let pointer = self.pointer(&viewports);
+        let status_bar = match &self.prompt {
+            Some(prompt) => Some(prompt.line()),
+            None => self.statistics.map(|statistics| {
+                statistics.status_line(pointer, self.measure_from, self.probing)
+            }),
+        };
+
+        // Annotations are anchored to the assembled model, so they would be
+        // in the wrong place while it is exploded.
+        let annotations: Vec<_> = if self.exploded_view.is_exploded() {
+            Vec::new()
+        } else {
+            self.review
+                .annotations
+                .iter()
+                .map(|annotation| {
+                    (annotation.anchor(), annotation.text.as_str())
+                })
+                .collect()
+        };
+
+        let profile = self.profile.with_timings(&self.timings);
+
+        let views: Vec<_> =
+            self.cameras.iter().zip(viewports.iter().copied()).collect();
+
+        let cube = self.cameras.first().map(|camera| {
+            (
+                navigation_cube::camera(camera),
+                navigation_cube::viewport(&viewports[0], self.scale_factor),
+            )
+        });
+        let cube = cube.as_ref().map(|(camera, viewport)| (camera, *viewport));
+
+        self.changed = animating;
+
+        self.renderer.draw(
+            &views,
+            cube,
+            &self.draw_config,
+            Status {
+                progress: status.as_deref(),
+                status_bar: status_bar.as_deref(),
+                error: self.error.as_deref(),
+                models: models.as_deref(),
+                bom: self.bom.as_deref().filter(|_| self.draw_config.draw_bom),
+                annotations: &annotations,
+            },
+            &profile,
+            &self.input_handler,
+        )
+    }
+
+    /// Find the point on the model that the cursor points at, if any
+    ///
+    /// While probing, the point snaps to nearby features of the face.
+    fn pointer(&self, viewports: &[Viewport]) -> Option<Probe> {
+        // The navigation cube is drawn on top of the model, so pointing at it
+        // doesn't point at the model.
+        self.shape
+            .as_ref()
+            .zip(self.input_handler.cursor())
+            .and_then(|(shape, cursor)| {
+                if navigation_cube::viewport(&viewports[0], self.scale_factor)
+                    .contains(cursor)
+                {
+                    return None;
+                }
+
+                let index = viewport_at(viewports, Some(cursor));
+                probe::probe(
+                    self.cameras.get(index)?,
+                    &viewports[index],
+                    cursor,
+                    self.exploded_view.mesh(shape),
+                    self.scale_factor,
+                    self.probing,
+                )
+            })
+    }
+
+    fn viewports(&self) -> Vec<Viewport> {
+        let [width, height] = self.size;
+        self.draw_config.layout.viewports(width, height)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn switch_model(&mut self, index: usize) {
+        let workspace = match &mut self.workspace {
+            Some(workspace) => workspace,
+            None => return,
+        };
+
+        let current = Parked {
+            worker: self.worker.take(),
+            shape: self.shape.take(),
+            levels_of_detail: std::mem::take(&mut self.exploded_view)
+                .into_levels_of_detail(),
+            camera: self.cameras.drain(..).next(),
+            review: std::mem::take(&mut self.review),
+            sidecar: self.sidecar.take(),
+        };
+        self.statistics = None;
+        self.measure_from = None;
+        self.viewpoint = None;
+        self.prompt = None;
+        self.bom = None;
+        self.error = None;
+        self.active = 0;
+
+        match workspace.switch(index, current) {
+            Ok(Parked {
+                worker,
+                shape,
+                levels_of_detail,
+                camera,
+                review,
+                sidecar,
+            }) => {
+                self.worker = worker;
+                self.review = review;
+                self.sidecar = sidecar;
+
+                if let Some(shape) = shape {
+                    if let Some(camera) = camera {
+                        self.cameras.push(camera);
+                        set_up_views(
+                            &mut self.cameras,
+                            self.draw_config.layout,
+                            &shape.aabb,
+                            Instant::now(),
+                        );
+                    }
+                    self.display(shape, levels_of_detail);
+                }
+            }
+            Err(err) => {
+                self.error = Some(err);
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn receive_shape(&mut self) {
+        let worker = match &mut self.worker {
+            Some(worker) => worker,
+            None => return,
+        };
+
+        let new_shape = worker.receive();
+        let error = worker.take_error();
+
+        if let Some((new_shape, levels_of_detail)) = new_shape {
+            self.display(new_shape, levels_of_detail);
+        }
+        if let Some(error) = error {
+            self.error = Some(error);
+            self.changed = true;
+        }
+    }
+
+    fn apply_actions(&mut self, actions: &input::Actions, now: Instant) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let index = self.workspace.as_ref().and_then(|workspace| {
+                if actions.next_model {
+                    Some(workspace.next())
+                } else if actions.previous_model {
+                    Some(workspace.previous())
+                } else {
+                    None
+                }
+            });
+            if let Some(index) = index {
+                self.switch_model(index);
+            }
+        }
+
+        let draw_config = &mut self.draw_config;
+
+        if actions.exit {
+            self.exit_requested = true;
+        }
+
+        if actions.dismiss_error {
+            self.error = None;
+        }
+
+        if actions.toggle_model {
+            draw_config.draw_model = !draw_config.draw_model;
+        }
+        if actions.toggle_mesh {
+            draw_config.draw_mesh = !draw_config.draw_mesh;
+        }
+        if actions.toggle_debug {
+            draw_config.draw_debug = !draw_config.draw_debug;
+        }
+        if actions.toggle_smooth_normals {
+            draw_config.smooth_normals = !draw_config.smooth_normals;
+        }
+        if actions.toggle_shadows {
+            draw_config.draw_shadows = !draw_config.draw_shadows;
+        }
+        if actions.toggle_outlines {
+            draw_config.draw_outlines = !draw_config.draw_outlines;
+        }
+        if actions.toggle_profiler {
+            draw_config.draw_profiler = !draw_config.draw_profiler;
+        }
+        if actions.toggle_bom {
+            draw_config.draw_bom = !draw_config.draw_bom;
+        }
+        if actions.switch_input_device {
+            let device = self.input_handler.settings().device.next();
+            self.input_handler
+                .set_settings(self.input_config.settings_for(device));
+        }
+        if actions.switch_behavior {
+            self.input_handler
+                .set_behavior(self.input_handler.behavior().next());
+        }
+        if let Some(category) = actions.toggle_debug_category {
+            draw_config.toggle_debug_category(category);
+        }
+        if actions.cycle_quality {
+            draw_config.quality = QualityMetric::next(draw_config.quality);
+        }
+        if actions.switch_theme {
+            draw_config.theme =
+                self.theme.theme_for(draw_config.theme.preset.next());
+        }
+        if actions.switch_layout {
+            draw_config.layout = draw_config.layout.next();
+            self.active = 0;
+
+            if let Some(shape) = &self.shape {
+                set_up_views(
+                    &mut self.cameras,
+                    draw_config.layout,
+                    &shape.aabb,
+                    now,
+                );
+            }
+        }
+        if actions.toggle_probe {
+            self.probing = !self.probing;
+        }
+        if actions.toggle_explosion {
+            draw_config.toggle_explosion();
+        }
+        if actions.increase_explosion {
+            draw_config.change_explosion(0.1);
+        }
+        if actions.decrease_explosion {
+            draw_config.change_explosion(-0.1);
+        }
+        if let (Some(shape), Some(camera)) =
+            (&self.shape, self.cameras.get_mut(self.active))
+        {
+            let aabb = self.exploded_view.aabb(shape);
+
+            if actions.zoom_to_fit {
+                camera.zoom_to_fit(&aabb, now);
+            }
+            if let Some(view) = actions.show_view {
+                camera.show_view(view, &aabb, now);
+            }
+        }
+        if let Some(shape) = &self.shape {
+            if actions.next_face {
+                draw_config.select_next_face(&shape.mesh);
+            }
+            if actions.previous_face {
+                draw_config.select_previous_face(&shape.mesh);
+            }
+        }
+
+        // All of these are baked into the geometry, so it needs to be updated
+        // for any changes to become visible.
+        if actions.toggle_smooth_normals
+            || actions.toggle_debug_category.is_some()
+            || actions.cycle_quality
+            || actions.next_face
+            || actions.previous_face
+        {
+            if let Some(shape) = &self.shape {
+                self.renderer.update_geometry(
+                    self.exploded_view.mesh(shape),
+                    self.exploded_view.shortcuts(),
+                    &surfaces(shape),
+                    &shape.debug_info,
+                    self.exploded_view.aabb(shape),
+                    draw_config,
+                );
+            }
+        }
+
+        if let Some(statistics) = self.statistics {
+            let viewports = self.viewports();
+            let pointer = self.pointer(&viewports).map(|probe| probe.point);
+
+            if let Some(pointer) = pointer.filter(|_| actions.copy_pointer) {
+                self.clipboard.copy(statistics.format_point(pointer));
+            }
+            if actions.measure {
+                // Pressing the key without pointing at the model cancels the
+                // measurement.
+                match (self.measure_from.take(), pointer) {
+                    (Some(from), Some(to)) => self
+                        .clipboard
+                        .copy(statistics.format_distance(from, to)),
+                    (None, Some(pointer)) => self.measure_from = Some(pointer),
+                    (_, None) => {}
+                }
+            }
+            if actions.copy_properties {
+                self.clipboard.copy(statistics.format_properties());
+            }
+        }
+
+        if actions.annotate || actions.remove_annotation {
+            let viewports = self.viewports();
+            let index = viewport_at(&viewports, self.input_handler.cursor());
+
+            // Annotations are anchored to the assembled model.
+            let pointer = self
+                .pointer(&viewports)
+                .filter(|_| !self.exploded_view.is_exploded())
+                .map(|probe| probe.point);
+
+            if let (Some(point), Some(camera)) =
+                (pointer, self.cameras.get(index))
+            {
+                if actions.annotate {
+                    self.prompt = Some(Prompt::new(Purpose::Annotate(point)));
+                }
+                if actions.remove_annotation {
+                    let radius = probe::snap_radius(
+                        camera,
+                        &viewports[index],
+                        point,
+                        self.scale_factor,
+                    );
+                    self.remove_annotation(point, radius);
+                }
+            }
+        }
+
+        if actions.save_viewpoint && !self.cameras.is_empty() {
+            self.prompt = Some(Prompt::new(Purpose::SaveViewpoint));
+        }
+        let num_viewpoints = self.review.viewpoints.len();
+        let viewpoint = if num_viewpoints == 0 {
+            None
+        } else if actions.next_viewpoint {
+            Some(
+                self.viewpoint
+                    .map_or(0, |index| (index + 1) % num_viewpoints),
+            )
+        } else if actions.previous_viewpoint {
+            Some(self.viewpoint.map_or(num_viewpoints - 1, |index| {
+                (index + num_viewpoints - 1) % num_viewpoints
+            }))
+        } else {
+            None
+        };
+        if let (Some(index), Some(camera)) =
+            (viewpoint, self.cameras.first_mut())
+        {
+            camera.set_pose(&self.review.viewpoints[index].pose);
+            self.viewpoint = Some(index);
+        }
+    }
+
+    /// Act on the text of the prompt, unless it has been cancelled
+    fn finish_prompt(&mut self, outcome: Outcome) {
+        let prompt = match self.prompt.take() {
+            Some(prompt) => prompt,
+            None => return,
+        };
+        if outcome == Outcome::Cancelled {
+            return;
+        }
+
+        let text = prompt.text().to_owned();
+        match prompt.purpose() {
+            Purpose::Annotate(anchor) => {
+                if text.is_empty() {
+                    return;
+                }
+                self.review.annotations.push(Annotation::new(anchor, text));
+            }
+            Purpose::SaveViewpoint => {
+                let pose = match self.cameras.first() {
+                    Some(camera) => camera.pose(),
+                    None => return,
+                };
+                let name = if text.is_empty() {
+                    format!("Viewpoint {}", self.review.viewpoints.len() + 1)
+                } else {
+                    text
+                };
+
+                self.viewpoint = Some(self.review.save_viewpoint(name, pose));
+            }
+        }
+
+        self.save_review();
+    }
+
+    /// Remove the annotation closest to `point`, if it is within `radius`
+    fn remove_annotation(&mut self, point: Point<3>, radius: Scalar) {
+        let closest = self
+            .review
+            .annotations
+            .iter()
+            .enumerate()
+            .map(|(index, annotation)| {
+                ((annotation.anchor() - point).magnitude(), index)
+            })
+            .filter(|&(distance, _)| distance <= radius)
+            .min();
+
+        if let Some((_, index)) = closest {
+            self.review.annotations.remove(index);
+            self.save_review();
+        }
+    }
+
+    fn save_review(&self) {
+        if let Some(sidecar) = &self.sidecar {
+            if let Err(err) = sidecar.save(&self.review) {
+                warn!("Failed to save {}: {}", sidecar.path().display(), err);
+            }
+        }
+    }
+}
+
+/// Create a camera for each of the standard views of the layout
+///
+/// Expects the main camera to exist already. Any cameras for the standard views
+/// of a previous layout are replaced.
+fn set_up_views(
+    cameras: &mut Vec<Camera>,
+    layout: Layout,
+    aabb: &Aabb<3>,
+    now: Instant,
+) {
+    cameras.truncate(1);
+
+    for &view in layout.views() {
+        let mut camera = Camera::new(aabb);
+        camera.show_view(view, aabb, now);
+        cameras.push(camera);
+    }
+}
+
+/// Determine the surface of each face of the shape, from its material
+fn surfaces(shape: &ProcessedShape) -> Vec<Surface> {
+    shape
+        .face_attributes
+        .iter()
+        .map(|attributes| match attributes.material() {
+            Some(material) => Surface {
+                metallic: material.metallic,
+                roughness: material.roughness,
+            },
+            None => Surface::default(),
+        })
+        .collect()
+}
+
+/// Format the bill of materials, one line per entry
+fn bom_panel(bom: &BillOfMaterials) -> String {
+    let mut panel = String::from("Bill of materials\n");
+
+    for entry in &bom.entries {
+        let name = entry.name.as_deref().unwrap_or("(unnamed)");
+        panel.push_str(&format!("{} x {}", entry.count, name));
+
+        if let Some(material) = &entry.material {
+            panel.push_str(&format!(", {}", material));
+        }
+        match entry.mass {
+            Some(mass) => panel.push_str(&format!(", {:.1} g each\n", mass)),
+            None => {
+                panel.push_str(&format!(", {:.1} mm³ each\n", entry.volume))
+            }
+        }
+    }
+
+    panel
+}
+
+/// Find the viewport that contains the cursor
+///
+/// Falls back to the main viewport, if there's no cursor.
+fn viewport_at(
+    viewports: &[Viewport],
+    cursor: Option<PhysicalPosition<f64>>,
+) -> usize {
+    cursor
+        .and_then(|cursor| {
+            viewports
+                .iter()
+                .position(|viewport| viewport.contains(cursor))
+        })
+        .unwrap_or(0)
+}
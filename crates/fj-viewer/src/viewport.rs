@@ -0,0 +1,160 @@
+//! Splitting the window into multiple viewports
+
+use winit::dpi::PhysicalPosition;
+
+use crate::{camera::StandardView, window::Window};
+
+/// A rectangular area of the window, that a camera renders into
+///
+/// All values are in physical pixels, relative to the top-left corner of the
+/// window.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Viewport {
+    /// The horizontal position of the left edge
+    pub x: u32,
+
+    /// The vertical position of the top edge
+    pub y: u32,
+
+    /// The width of the viewport
+    pub width: u32,
+
+    /// The height of the viewport
+    pub height: u32,
+}
+
+impl Viewport {
+    /// The viewport that covers the whole window
+    pub fn from_window(window: &Window) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width: window.width(),
+            height: window.height(),
+        }
+    }
+
+    /// The ratio of width to height
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+
+    /// Indicate whether a position in the window is located in the viewport
+    pub fn contains(&self, position: PhysicalPosition<f64>) -> bool {
+        let x = position.x - self.x as f64;
+        let y = position.y - self.y as f64;
+
+        x >= 0. && x < self.width as f64 && y >= 0. && y < self.height as f64
+    }
+
+    /// Convert a position in the window into a position in the viewport
+    pub fn to_local(
+        &self,
+        position: PhysicalPosition<f64>,
+    ) -> PhysicalPosition<f64> {
+        PhysicalPosition::new(
+            position.x - self.x as f64,
+            position.y - self.y as f64,
+        )
+    }
+}
+
+/// The arrangement of viewports in the window
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Layout {
+    /// A single viewport that covers the whole window
+    Single,
+
+    /// The main view on the left, the front view on the right
+    Double,
+
+    /// Top, front, and right views, with the main view in the top-right
+    ///
+    /// The standard views are arranged like in a technical drawing.
+    Quad,
+}
+
+impl Layout {
+    /// Cycle through the layouts
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Single => Self::Double,
+            Self::Double => Self::Quad,
+            Self::Quad => Self::Single,
+        }
+    }
+
+    /// Return a human-readable name of the layout
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Single => "single",
+            Self::Double => "double",
+            Self::Quad => "quad",
+        }
+    }
+
+    /// The standard views that are shown next to the main view
+    ///
+    /// These come in the same order as the viewports returned by
+    /// [`Layout::viewports`], after the main one.
+    pub fn views(&self) -> &'static [StandardView] {
+        match self {
+            Self::Single => &[],
+            Self::Double => &[StandardView::Front],
+            Self::Quad => {
+                &[StandardView::Top, StandardView::Front, StandardView::Right]
+            }
+        }
+    }
+
+    /// Split a window of the given size into viewports
+    ///
+    /// The viewport of the main view comes first.
+    pub fn viewports(&self, width: u32, height: u32) -> Vec<Viewport> {
+        let half_width = width / 2;
+        let half_height = height / 2;
+
+        // Cells of a 2x2 grid. The right column and bottom row take up any
+        // pixel left over by the division.
+        let cell = |column: u32, row: u32| Viewport {
+            x: column * half_width,
+            y: row * half_height,
+            width: if column == 0 {
+                half_width
+            } else {
+                width - half_width
+            },
+            height: if row == 0 {
+                half_height
+            } else {
+                height - half_height
+            },
+        };
+
+        match self {
+            Self::Single => vec![Viewport {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            }],
+            Self::Double => vec![
+                Viewport {
+                    height,
+                    ..cell(0, 0)
+                },
+                Viewport {
+                    height,
+                    ..cell(1, 0)
+                },
+            ],
+            Self::Quad => vec![cell(1, 0), cell(0, 0), cell(0, 1), cell(1, 1)],
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::Single
+    }
+}
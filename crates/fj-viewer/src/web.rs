@@ -0,0 +1,165 @@
+//! Support for running the viewer in a web page
+//!
+//! Only available when compiling to WebAssembly. Models can't be compiled or
+//! reloaded in the browser, so unlike the native viewer, this displays meshes
+//! that have been created elsewhere.
+//!
+//! From JavaScript, the viewer is used like this:
+//!
+//! ``` js
+//! const viewer = await WebViewer.create(canvas);
+//! viewer.load_mesh(positions, indices);
+//! viewer.run();
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::Point;
+use fj_operations::shape_processor::ProcessedShape;
+use instant::Instant;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlCanvasElement;
+use winit::{
+    dpi::PhysicalSize,
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    platform::web::WindowBuilderExtWebSys,
+    window::{Window, WindowBuilder},
+};
+
+use crate::viewer::Viewer;
+
+/// The color of meshes that are loaded from JavaScript
+const MESH_COLOR: Color = [200, 200, 200, 255];
+
+/// The viewer, as exposed to JavaScript
+#[wasm_bindgen]
+pub struct WebViewer {
+    viewer: Rc<RefCell<Viewer>>,
+    window: Rc<Window>,
+    event_loop: Option<EventLoop<()>>,
+}
+
+#[wasm_bindgen]
+impl WebViewer {
+    /// Create a viewer that renders into the given canvas
+    pub async fn create(
+        canvas: HtmlCanvasElement,
+    ) -> Result<WebViewer, JsValue> {
+        let event_loop = EventLoop::new();
+
+        let size = PhysicalSize::new(canvas.width(), canvas.height());
+        let window = WindowBuilder::new()
+            .with_canvas(Some(canvas))
+            .with_inner_size(size)
+            .build(&event_loop)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        let viewer = Viewer::new(&window)
+            .await
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        Ok(Self {
+            viewer: Rc::new(RefCell::new(viewer)),
+            window: Rc::new(window),
+            event_loop: Some(event_loop),
+        })
+    }
+
+    /// Display a triangle mesh, replacing the current one
+    ///
+    /// `positions` contains the x, y, and z coordinates of each vertex.
+    /// `indices` contains three vertex indices for each triangle.
+    pub fn load_mesh(
+        &self,
+        positions: &[f64],
+        indices: &[u32],
+    ) -> Result<(), JsValue> {
+        if positions.len() % 3 != 0 {
+            return Err(JsValue::from_str(
+                "Number of coordinates is not a multiple of 3",
+            ));
+        }
+        if indices.len() % 3 != 0 {
+            return Err(JsValue::from_str(
+                "Number of indices is not a multiple of 3",
+            ));
+        }
+        if indices.is_empty() {
+            return Err(JsValue::from_str("Mesh has no triangles"));
+        }
+
+        let vertices: Vec<_> = positions
+            .chunks_exact(3)
+            .map(|point| Point::from([point[0], point[1], point[2]]))
+            .collect();
+
+        let mut mesh = Mesh::new();
+        for triangle in indices.chunks_exact(3) {
+            let mut points = [Point::origin(); 3];
+            for (point, &index) in points.iter_mut().zip(triangle) {
+                *point = *vertices.get(index as usize).ok_or_else(|| {
+                    JsValue::from_str(&format!(
+                        "Index {} is out of bounds",
+                        index
+                    ))
+                })?;
+            }
+
+            mesh.push_triangle(points, MESH_COLOR);
+        }
+
+        self.viewer
+            .borrow_mut()
+            .show_shape(ProcessedShape::from_mesh(mesh));
+
+        Ok(())
+    }
+
+    /// Start rendering and handling input
+    ///
+    /// Like winit's event loop on the web, this throws an exception to hand
+    /// control back to the browser. That exception is not an error.
+    pub fn run(&mut self) -> Result<(), JsValue> {
+        let event_loop = self
+            .event_loop
+            .take()
+            .ok_or_else(|| JsValue::from_str("Viewer is already running"))?;
+
+        let viewer = self.viewer.clone();
+        let window = self.window.clone();
+
+        event_loop.run(move |event, _, control_flow| {
+            let mut viewer = viewer.borrow_mut();
+
+            match event {
+                Event::WindowEvent {
+                    event: WindowEvent::CloseRequested,
+                    ..
+                } => {
+                    *control_flow = ControlFlow::Exit;
+                }
+                Event::WindowEvent { event, .. } => {
+                    viewer.handle_event(&event);
+                }
+                Event::MainEventsCleared => {
+                    let schedule = viewer.schedule(Instant::now());
+                    if schedule.redraw {
+                        window.request_redraw();
+                    }
+                    *control_flow = ControlFlow::WaitUntil(schedule.wake_up);
+                }
+                Event::RedrawRequested(_) => {
+                    if let Err(err) = viewer.render() {
+                        web_sys::console::warn_1(&JsValue::from_str(&format!(
+                            "Draw error: {}",
+                            err
+                        )));
+                    }
+                }
+                _ => {}
+            }
+        })
+    }
+}
@@ -1,6 +1,7 @@
 //! CAD viewer utility windowing abstraction
 
-use winit::{event_loop::EventLoop, window::WindowBuilder};
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use winit::{dpi::PhysicalSize, event_loop::EventLoop, window::WindowBuilder};
 
 /// Window abstraction providing details such as the width or height and easing initialization.
 pub struct Window(winit::window::Window);
@@ -8,19 +9,24 @@ pub struct Window(winit::window::Window);
 impl Window {
     /// Returns a new window with the given `EventLoop`.
     ///
+    /// The window is maximized, unless a `size` is given.
+    ///
     /// # Examples
     /// ```rust no_run
     /// let event_loop = winit::event_loop::EventLoop::new();
-    /// let window = fj_viewer::window::Window::new(&event_loop);
+    /// let window = fj_viewer::window::Window::new(&event_loop, None);
     /// ```
-    pub fn new(event_loop: &EventLoop<()>) -> Self {
-        let window = WindowBuilder::new()
+    pub fn new(event_loop: &EventLoop<()>, size: Option<[u32; 2]>) -> Self {
+        let mut builder = WindowBuilder::new()
             .with_title("Fornjot")
-            .with_maximized(true)
+            .with_maximized(size.is_none())
             .with_decorations(true)
-            .with_transparent(false)
-            .build(event_loop)
-            .unwrap();
+            .with_transparent(false);
+        if let Some([width, height]) = size {
+            builder = builder.with_inner_size(PhysicalSize::new(width, height));
+        }
+
+        let window = builder.build(event_loop).unwrap();
 
         Self(window)
     }
@@ -40,3 +46,11 @@ impl Window {
         self.0.inner_size().height
     }
 }
+
+// Sound, as the handle is that of the wrapped window, which lives as long as
+// `self`.
+unsafe impl HasRawWindowHandle for Window {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        self.0.raw_window_handle()
+    }
+}
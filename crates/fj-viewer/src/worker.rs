@@ -0,0 +1,190 @@
+//! Evaluation of models on a background thread
+
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+};
+
+use fj_host::Watcher;
+use fj_interop::progress::Progress;
+use fj_operations::{
+    diff::diff_shapes,
+    level_of_detail::{levels_of_detail, LevelOfDetail},
+    shape_processor::{ProcessedShape, ShapeProcessor},
+};
+use tracing::info;
+
+/// Reloads and processes a model on a background thread
+///
+/// This keeps the event loop responsive, while the model is being rebuilt.
+pub struct Worker {
+    events: mpsc::Receiver<Event>,
+    progress: Option<Progress>,
+    error: Option<String>,
+}
+
+impl Worker {
+    /// Start the background thread
+    ///
+    /// If a `baseline` is given, each version of the model is compared to it,
+    /// and the comparison is displayed instead. See [`diff_shapes`].
+    pub fn start(
+        watcher: Watcher,
+        shape_processor: ShapeProcessor,
+        baseline: Option<ProcessedShape>,
+    ) -> Self {
+        let (tx, events) = mpsc::channel();
+
+        thread::spawn(move || loop {
+            watcher.wait_for_change();
+
+            let progress = Progress::new();
+            if tx.send(Event::Rebuilding(progress.clone())).is_err() {
+                // The receiving end has been dropped, which means the viewer
+                // is shutting down.
+                break;
+            }
+
+            // Panics in the model, or while processing an invalid shape,
+            // shouldn't take down the viewer. They are reported to the user
+            // instead, who can fix the model and try again.
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let shape = watcher.reload().ok_or_else(|| {
+                    "Error loading model (see terminal for details)".to_owned()
+                })?;
+
+                let cancelled = |_| "Rebuild was cancelled".to_owned();
+
+                let shape = shape_processor
+                    .process_with_progress(&shape, &progress)
+                    .map_err(cancelled)?;
+
+                let shape = match &baseline {
+                    Some(baseline) => {
+                        let diff = diff_shapes(baseline, &shape);
+                        info!(
+                            "Added volume: {:.3}, removed volume: {:.3}",
+                            diff.added.into_f64(),
+                            diff.removed.into_f64(),
+                        );
+                        diff.shape
+                    }
+                    None => shape,
+                };
+
+                // Simplifying huge models takes a while, so this happens here,
+                // rather than in the viewer.
+                let levels = levels_of_detail(&shape.mesh, &progress)
+                    .map_err(cancelled)?;
+
+                Ok((shape, levels))
+            }));
+
+            let event = match result {
+                Ok(Ok(finished)) => Event::Finished(Box::new(finished)),
+                Ok(Err(err)) => Event::Failed(err),
+                Err(payload) => Event::Failed(panic_message(payload)),
+            };
+            if tx.send(event).is_err() {
+                break;
+            }
+        });
+
+        Self {
+            events,
+            progress: None,
+            error: None,
+        }
+    }
+
+    /// Receive a newly processed shape and its levels of detail, if one is
+    /// available
+    ///
+    /// Never blocks. Returns `None`, if no new shape is available yet.
+    pub fn receive(&mut self) -> Option<(ProcessedShape, Vec<LevelOfDetail>)> {
+        let mut shape = None;
+
+        loop {
+            let event = match self.events.try_recv() {
+                Ok(event) => event,
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    // The background thread is gone, without having reported
+                    // the end of the rebuild. Let's not claim it's still in
+                    // progress.
+                    if self.progress.take().is_some() {
+                        self.error = Some(
+                            "Rebuild stopped unexpectedly (see terminal for \
+                            details)"
+                                .to_owned(),
+                        );
+                    }
+                    break;
+                }
+            };
+
+            match event {
+                Event::Rebuilding(progress) => {
+                    self.progress = Some(progress);
+                }
+                Event::Finished(processed) => {
+                    self.progress = None;
+                    self.error = None;
+                    shape = Some(*processed);
+                }
+                Event::Failed(err) => {
+                    self.progress = None;
+                    self.error = Some(err);
+                }
+            }
+        }
+
+        shape
+    }
+
+    /// Take the error of the last rebuild, if it failed
+    ///
+    /// Returns `None`, if the last rebuild succeeded, or the error has already
+    /// been taken.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    /// The progress of the current rebuild, if the model is being rebuilt
+    pub fn progress(&self) -> Option<&Progress> {
+        self.progress.as_ref()
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // There's no point in finishing a rebuild that nobody is waiting for.
+        if let Some(progress) = &self.progress {
+            progress.cancel();
+        }
+    }
+}
+
+enum Event {
+    Rebuilding(Progress),
+    Finished(Box<(ProcessedShape, Vec<LevelOfDetail>)>),
+    Failed(String),
+}
+
+/// Extract the message from the payload of a panic
+///
+/// Panics created with a message, which is the usual case, carry a `&str` or a
+/// `String`. There's no message to extract from anything else.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => (*message).to_owned(),
+            Err(_) => "Unknown error".to_owned(),
+        },
+    };
+
+    format!("Panic while rebuilding model: {}", message)
+}
@@ -0,0 +1,164 @@
+//! Switching between several models
+
+use fj_host::{Model, Parameters};
+use fj_operations::{
+    level_of_detail::LevelOfDetail,
+    shape_processor::{ProcessedShape, ShapeProcessor},
+};
+
+use crate::{
+    camera::Camera,
+    review::{Review, Sidecar},
+    worker::Worker,
+};
+
+/// A collection of models that the user can switch between
+///
+/// Models are only watched, once they are displayed for the first time. After
+/// that, they keep being watched and rebuilt in the background, so switching
+/// back to them is instant.
+pub struct Workspace {
+    models: Vec<Entry>,
+    active: Option<usize>,
+    parameters: Parameters,
+    shape_processor: ShapeProcessor,
+}
+
+impl Workspace {
+    pub fn new(
+        models: Vec<(String, Model)>,
+        parameters: Parameters,
+        shape_processor: ShapeProcessor,
+    ) -> Self {
+        let models = models
+            .into_iter()
+            .map(|(name, model)| Entry {
+                name,
+                state: State::Unwatched(model),
+            })
+            .collect();
+
+        Self {
+            models,
+            active: None,
+            parameters,
+            shape_processor,
+        }
+    }
+
+    /// The index of the model after the active one, wrapping around
+    pub fn next(&self) -> usize {
+        self.active
+            .map_or(0, |active| (active + 1) % self.models.len())
+    }
+
+    /// The index of the model before the active one, wrapping around
+    pub fn previous(&self) -> usize {
+        self.active.map_or(0, |active| {
+            (active + self.models.len() - 1) % self.models.len()
+        })
+    }
+
+    /// Switch to the model at `index`
+    ///
+    /// `current` is the state of the previously active model, which is kept
+    /// until the user switches back to it. Returns the state of the model that
+    /// was switched to, or an error, if it could not be watched.
+    pub fn switch(
+        &mut self,
+        index: usize,
+        current: Parked,
+    ) -> Result<Parked, String> {
+        if let Some(active) = self.active {
+            self.models[active].state = State::Parked(current);
+        }
+        self.active = Some(index);
+
+        let entry = &mut self.models[index];
+        match std::mem::replace(&mut entry.state, State::Active) {
+            State::Unwatched(model) => {
+                let sidecar = Sidecar::for_model(model.path());
+                let review = sidecar.load().unwrap_or_default();
+
+                match model.load_and_watch(self.parameters.clone()) {
+                    Ok(watcher) => Ok(Parked {
+                        worker: Some(Worker::start(
+                            watcher,
+                            self.shape_processor,
+                            None,
+                        )),
+                        shape: None,
+                        levels_of_detail: Vec::new(),
+                        camera: None,
+                        review,
+                        sidecar: Some(sidecar),
+                    }),
+                    Err(err) => {
+                        entry.state = State::Failed;
+                        Err(format!(
+                            "Error watching model `{}`: {}",
+                            entry.name, err
+                        ))
+                    }
+                }
+            }
+            State::Parked(parked) => Ok(parked),
+            State::Active => Ok(Parked::default()),
+            State::Failed => {
+                entry.state = State::Failed;
+                Err(format!("Model `{}` could not be watched", entry.name))
+            }
+        }
+    }
+
+    /// List the models, marking the active one
+    pub fn list(&self) -> String {
+        let mut list =
+            "Models (switch with Page Up and Page Down)\n".to_owned();
+
+        for (i, entry) in self.models.iter().enumerate() {
+            let marker = if Some(i) == self.active { "> " } else { "" };
+            list.push_str(&format!("{}{}\n", marker, entry.name));
+        }
+
+        list
+    }
+}
+
+/// What the viewer keeps of a model that is not displayed
+#[derive(Default)]
+pub struct Parked {
+    pub worker: Option<Worker>,
+    pub shape: Option<ProcessedShape>,
+
+    /// The simplified versions of the shape's mesh
+    pub levels_of_detail: Vec<LevelOfDetail>,
+
+    /// The main camera, if the model has been displayed
+    pub camera: Option<Camera>,
+
+    /// The annotations and viewpoints of the model
+    pub review: Review,
+
+    /// Where the review is stored
+    pub sidecar: Option<Sidecar>,
+}
+
+struct Entry {
+    name: String,
+    state: State,
+}
+
+enum State {
+    /// The model has not been displayed yet
+    Unwatched(Model),
+
+    /// The model has been displayed before, but another one is displayed now
+    Parked(Parked),
+
+    /// The model is displayed, and its state is owned by the viewer
+    Active,
+
+    /// The model could not be watched
+    Failed,
+}
@@ -0,0 +1,47 @@
+use crate::Shape;
+
+/// A design rule that the evaluated shape must satisfy
+///
+/// Assertions are checked after the shape has been evaluated. If they fail,
+/// the Fornjot application reports an error, and refuses to export the shape.
+/// This is useful for parametric models that are shared with others, as it
+/// catches parameters that would result in an unusable part.
+///
+/// All values are in model units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub enum Assertion {
+    /// The walls of the shape must be at least this thick
+    MinWallThickness(f64),
+
+    /// The bounding box of the shape must fit within this size
+    MaxSize([f64; 3]),
+
+    /// The volume of the shape must be within this range
+    VolumeRange {
+        /// The minimum volume
+        min: f64,
+
+        /// The maximum volume
+        max: f64,
+    },
+}
+
+/// A shape with an assertion attached
+///
+/// Shapes can have any number of assertions, by wrapping them repeatedly.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Asserted {
+    /// The shape that the assertion applies to
+    pub shape: Shape,
+
+    /// The assertion
+    pub assertion: Assertion,
+}
+
+impl From<Asserted> for Shape {
+    fn from(shape: Asserted) -> Self {
+        Self::Asserted(Box::new(shape))
+    }
+}
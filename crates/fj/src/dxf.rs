@@ -0,0 +1,583 @@
+//! Creation of sketches from DXF files
+//!
+//! See [`SketchFromDxf`].
+
+use std::{error, f64::consts::PI, fmt, fs, io, path::PathBuf};
+
+use crate::{svg::signed_area, Sketch};
+
+/// Creates sketches from the closed outlines in a DXF file
+///
+/// DXF is the format that laser cutters and CNC routers usually work with, so
+/// this makes it possible to reuse existing 2D profiles. Each closed outline
+/// becomes a separate [`Sketch`].
+///
+/// ``` no_run
+/// use fj::{syntax::*, SketchFromDxf};
+///
+/// let sketches = SketchFromDxf::new("bracket.dxf").load().unwrap();
+/// let bracket = sketches[0].sweep([0., 0., 3.]);
+/// ```
+///
+/// The following entities are read:
+///
+/// - `LWPOLYLINE` and `POLYLINE`, including arc segments
+/// - `LINE` and `ARC`, which are chained into outlines where their end points
+///   meet
+/// - `CIRCLE`
+///
+/// Since sketches are made of straight lines, arcs and circles are
+/// approximated. The tolerance defines how far the approximation may deviate
+/// from the curve.
+///
+/// # Limitations
+///
+/// Only entities in the `ENTITIES` section are read. Blocks are ignored, as
+/// are layers, units, and the z coordinates of all entities. Open outlines,
+/// like construction lines, are skipped.
+///
+/// Holes are returned as separate sketches. They can be cut out of the
+/// surrounding sketch with a [`Difference2d`].
+///
+/// [`Difference2d`]: crate::Difference2d
+#[derive(Clone, Debug)]
+pub struct SketchFromDxf {
+    path: PathBuf,
+    tolerance: f64,
+    scale: f64,
+}
+
+impl SketchFromDxf {
+    /// Prepare loading sketches from the DXF file at the given path
+    ///
+    /// Uses a tolerance of `0.1`, and a scale of `1.0`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            tolerance: 0.1,
+            scale: 1.,
+        }
+    }
+
+    /// Set the curve tolerance
+    ///
+    /// The tolerance is given in model units, which means it is applied after
+    /// scaling.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the factor that DXF coordinates are multiplied with
+    ///
+    /// Useful, if the DXF file is not drawn in the units of the model.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Load the DXF file, and create a sketch for each closed outline
+    pub fn load(&self) -> Result<Vec<Sketch>, DxfError> {
+        let dxf = fs::read_to_string(&self.path)?;
+        self.parse(&dxf)
+    }
+
+    /// Create a sketch for each closed outline in the given DXF document
+    ///
+    /// Like [`SketchFromDxf::load`], but the document is passed directly. The
+    /// path that this instance was created with is ignored.
+    pub fn parse(&self, dxf: &str) -> Result<Vec<Sketch>, DxfError> {
+        if self.tolerance.is_nan() || self.tolerance <= 0. {
+            return Err(DxfError::Invalid(format!(
+                "Tolerance must be positive, not {}",
+                self.tolerance
+            )));
+        }
+
+        // Curves are approximated in DXF coordinates, so the tolerance needs
+        // to be scaled the other way.
+        let tolerance = self.tolerance / self.scale.abs();
+
+        let mut sketches = Vec::new();
+        for mut points in outlines(&entities(dxf)?, tolerance)? {
+            for [x, y] in &mut points {
+                *x *= self.scale;
+                *y *= self.scale;
+            }
+
+            // `Sketch` should always be counter-clockwise.
+            if signed_area(&points) < 0. {
+                points.reverse();
+            }
+
+            sketches.push(Sketch::from_points(points));
+        }
+
+        if sketches.is_empty() {
+            return Err(DxfError::NoOutlines);
+        }
+
+        Ok(sketches)
+    }
+}
+
+/// An error that can occur when creating sketches from a DXF file
+#[derive(Debug)]
+pub enum DxfError {
+    /// The file could not be read
+    Io(io::Error),
+
+    /// The file is not a valid DXF file
+    Invalid(String),
+
+    /// The file contains no closed outlines that could be turned into sketches
+    NoOutlines,
+}
+
+impl fmt::Display for DxfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "Error reading DXF file"),
+            Self::Invalid(message) => write!(f, "Invalid DXF: {}", message),
+            Self::NoOutlines => {
+                write!(f, "DXF file contains no closed outlines")
+            }
+        }
+    }
+}
+
+impl error::Error for DxfError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DxfError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// End points that are closer than this are considered to be connected
+const EPSILON: f64 = 1e-6;
+
+/// An entity, with the group codes and values that make it up
+#[derive(Debug)]
+struct Entity<'r> {
+    kind: &'r str,
+    groups: Vec<(u16, &'r str)>,
+}
+
+impl Entity<'_> {
+    fn value(&self, code: u16) -> Result<f64, DxfError> {
+        self.groups
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map_or(Ok(0.), |(_, value)| number(value))
+    }
+
+    fn flags(&self) -> Result<u32, DxfError> {
+        Ok(self.value(70)? as u32)
+    }
+}
+
+/// Read the entities from the `ENTITIES` section
+fn entities(dxf: &str) -> Result<Vec<Entity>, DxfError> {
+    let mut lines = dxf.lines();
+    let mut groups = Vec::new();
+    while let Some(code) = lines.next() {
+        let code = code.trim();
+        if code.is_empty() {
+            continue;
+        }
+
+        let code = code.parse().map_err(|_| {
+            DxfError::Invalid(format!("Invalid group code `{}`", code))
+        })?;
+        let value = lines.next().ok_or_else(|| {
+            DxfError::Invalid(format!("Missing value for group code {}", code))
+        })?;
+
+        groups.push((code, value.trim()));
+    }
+
+    let mut entities = Vec::new();
+    let mut in_entities = false;
+
+    let mut groups = groups.into_iter().peekable();
+    while let Some((code, value)) = groups.next() {
+        if code != 0 {
+            continue;
+        }
+
+        match value {
+            "SECTION" => {
+                in_entities = groups.peek() == Some(&(2, "ENTITIES"));
+            }
+            "ENDSEC" | "EOF" => {
+                in_entities = false;
+            }
+            kind if in_entities => {
+                let mut entity = Entity {
+                    kind,
+                    groups: Vec::new(),
+                };
+                while let Some(&(code, value)) = groups.peek() {
+                    if code == 0 {
+                        break;
+                    }
+                    entity.groups.push((code, value));
+                    groups.next();
+                }
+
+                entities.push(entity);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(entities)
+}
+
+/// Turn the entities into closed outlines
+fn outlines(
+    entities: &[Entity],
+    tolerance: f64,
+) -> Result<Vec<Vec<[f64; 2]>>, DxfError> {
+    let mut outlines = Vec::new();
+    let mut pieces = Vec::new();
+
+    let mut entities = entities.iter();
+    while let Some(entity) = entities.next() {
+        match entity.kind {
+            "LINE" => {
+                let start = [entity.value(10)?, entity.value(20)?];
+                let end = [entity.value(11)?, entity.value(21)?];
+                pieces.push(vec![start, end]);
+            }
+            "ARC" => {
+                let center = [entity.value(10)?, entity.value(20)?];
+                let radius = entity.value(40)?;
+                let start = entity.value(50)?.to_radians();
+                let mut end = entity.value(51)?.to_radians();
+
+                // Arcs always go counter-clockwise, from start to end.
+                while end <= start {
+                    end += 2. * PI;
+                }
+
+                let mut piece = vec![on_circle(center, radius, start)];
+                piece.extend(flatten_arc(
+                    center,
+                    radius,
+                    start,
+                    end - start,
+                    tolerance,
+                ));
+                pieces.push(piece);
+            }
+            "CIRCLE" => {
+                let center = [entity.value(10)?, entity.value(20)?];
+                let radius = entity.value(40)?;
+
+                let mut outline =
+                    flatten_arc(center, radius, 0., 2. * PI, tolerance);
+                outline.pop();
+                outlines.push(outline);
+            }
+            "LWPOLYLINE" => {
+                let mut vertices: Vec<([f64; 2], f64)> = Vec::new();
+                for &(code, value) in &entity.groups {
+                    match code {
+                        10 => vertices.push(([number(value)?, 0.], 0.)),
+                        20 | 42 => {
+                            let (point, bulge) =
+                                vertices.last_mut().ok_or_else(|| {
+                                    DxfError::Invalid(
+                                        "Polyline value before first vertex"
+                                            .to_owned(),
+                                    )
+                                })?;
+                            if code == 20 {
+                                point[1] = number(value)?;
+                            } else {
+                                *bulge = number(value)?;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                let closed = entity.flags()? & 1 != 0;
+                polyline(
+                    &vertices,
+                    closed,
+                    tolerance,
+                    &mut outlines,
+                    &mut pieces,
+                );
+            }
+            "POLYLINE" => {
+                let closed = entity.flags()? & 1 != 0;
+
+                // The vertices follow as separate entities.
+                let mut vertices = Vec::new();
+                for vertex in entities.by_ref() {
+                    match vertex.kind {
+                        "VERTEX" => vertices.push((
+                            [vertex.value(10)?, vertex.value(20)?],
+                            vertex.value(42)?,
+                        )),
+                        _ => break,
+                    }
+                }
+
+                polyline(
+                    &vertices,
+                    closed,
+                    tolerance,
+                    &mut outlines,
+                    &mut pieces,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    outlines.extend(chain(pieces));
+    outlines.retain(|outline| outline.len() >= 3);
+
+    Ok(outlines)
+}
+
+/// Approximate a polyline, whose vertices may have bulges
+///
+/// A bulge defines an arc segment from the vertex to the next one. It is the
+/// tangent of a quarter of the included angle; negative if the arc goes
+/// clockwise.
+fn polyline(
+    vertices: &[([f64; 2], f64)],
+    closed: bool,
+    tolerance: f64,
+    outlines: &mut Vec<Vec<[f64; 2]>>,
+    pieces: &mut Vec<Vec<[f64; 2]>>,
+) {
+    let (first, _) = match vertices.first() {
+        Some(vertex) => *vertex,
+        None => return,
+    };
+
+    let mut points = vec![first];
+    let segments = if closed {
+        vertices.len()
+    } else {
+        vertices.len() - 1
+    };
+    for i in 0..segments {
+        let (start, bulge) = vertices[i];
+        let (end, _) = vertices[(i + 1) % vertices.len()];
+
+        points.extend(flatten_bulge(start, end, bulge, tolerance));
+    }
+
+    if closed {
+        points.pop();
+        outlines.push(points);
+    } else {
+        pieces.push(points);
+    }
+}
+
+/// Approximate a polyline segment with the given bulge
+///
+/// Returns the points after `start`.
+fn flatten_bulge(
+    start: [f64; 2],
+    end: [f64; 2],
+    bulge: f64,
+    tolerance: f64,
+) -> Vec<[f64; 2]> {
+    let d = [end[0] - start[0], end[1] - start[1]];
+    let chord = (d[0] * d[0] + d[1] * d[1]).sqrt();
+    if bulge == 0. || chord == 0. {
+        return vec![end];
+    }
+
+    let sweep = 4. * bulge.atan();
+    let radius = chord / 2. / (sweep / 2.).sin().abs();
+
+    // The center lies on the perpendicular bisector of the chord, to its left
+    // if the arc goes counter-clockwise.
+    let offset = chord / 2. / (sweep / 2.).tan();
+    let center = [
+        (start[0] + end[0]) / 2. - d[1] / chord * offset,
+        (start[1] + end[1]) / 2. + d[0] / chord * offset,
+    ];
+
+    let angle = (start[1] - center[1]).atan2(start[0] - center[0]);
+
+    let mut points = flatten_arc(center, radius, angle, sweep, tolerance);
+    points.pop();
+    points.push(end);
+
+    points
+}
+
+/// Approximate an arc with straight lines, returning the points after its
+/// start
+fn flatten_arc(
+    center: [f64; 2],
+    radius: f64,
+    start: f64,
+    sweep: f64,
+    tolerance: f64,
+) -> Vec<[f64; 2]> {
+    // The maximum angle a segment can span, without deviating from the arc by
+    // more than the tolerance.
+    let max_angle = if tolerance < radius {
+        2. * (1. - tolerance / radius).acos()
+    } else {
+        PI / 2.
+    };
+    let n = (sweep.abs() / max_angle).ceil().max(1.) as usize;
+
+    (1..=n)
+        .map(|i| on_circle(center, radius, start + sweep * i as f64 / n as f64))
+        .collect()
+}
+
+fn on_circle(center: [f64; 2], radius: f64, angle: f64) -> [f64; 2] {
+    let (sin, cos) = angle.sin_cos();
+    [center[0] + radius * cos, center[1] + radius * sin]
+}
+
+/// Connect open pieces with shared end points into closed outlines
+///
+/// Pieces that don't end up in a closed outline are dropped.
+fn chain(mut pieces: Vec<Vec<[f64; 2]>>) -> Vec<Vec<[f64; 2]>> {
+    let connected = |a: [f64; 2], b: [f64; 2]| {
+        (a[0] - b[0]).abs() <= EPSILON && (a[1] - b[1]).abs() <= EPSILON
+    };
+
+    let mut outlines = Vec::new();
+    while let Some(mut outline) = pieces.pop() {
+        loop {
+            let first = outline[0];
+            let last = *outline.last().unwrap();
+
+            if outline.len() > 2 && connected(first, last) {
+                outline.pop();
+                outlines.push(outline);
+                break;
+            }
+
+            let next = pieces.iter().position(|piece| {
+                connected(piece[0], last)
+                    || connected(*piece.last().unwrap(), last)
+            });
+            let mut next = match next {
+                Some(next) => pieces.swap_remove(next),
+                None => break,
+            };
+
+            if !connected(next[0], last) {
+                next.reverse();
+            }
+            outline.extend(next.into_iter().skip(1));
+        }
+    }
+
+    outlines
+}
+
+fn number(value: &str) -> Result<f64, DxfError> {
+    value
+        .parse()
+        .map_err(|_| DxfError::Invalid(format!("Invalid number `{}`", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{entities, outlines, SketchFromDxf};
+
+    fn dxf(entities: &str) -> String {
+        let groups: Vec<_> = entities.split_whitespace().collect();
+        let mut dxf = String::from("0\nSECTION\n2\nENTITIES\n");
+        for pair in groups.chunks(2) {
+            dxf.push_str(&format!("{}\n{}\n", pair[0], pair[1]));
+        }
+        dxf.push_str("0\nENDSEC\n0\nEOF\n");
+        dxf
+    }
+
+    #[test]
+    fn chain_lines() {
+        // The lines are in no particular order or direction.
+        let dxf = dxf("
+            0 LINE 10 0 20 0 11 1 21 0
+            0 LINE 10 0 20 1 11 1 21 1
+            0 LINE 10 0 20 0 11 0 21 1
+            0 LINE 10 1 20 1 11 1 21 0
+            0 LINE 10 5 20 5 11 6 21 5
+        ");
+
+        let outlines = outlines(&entities(&dxf).unwrap(), 0.1).unwrap();
+
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].len(), 4);
+    }
+
+    #[test]
+    fn bulges() {
+        // A closed polyline with two half circles, which is a full circle.
+        let dxf = dxf("
+            0 LWPOLYLINE 90 2 70 1
+            10 -1 20 0 42 1
+            10 1 20 0 42 1
+        ");
+
+        let tolerance = 0.01;
+        let outlines = outlines(&entities(&dxf).unwrap(), tolerance).unwrap();
+
+        assert_eq!(outlines.len(), 1);
+        for [x, y] in &outlines[0] {
+            let distance = (x * x + y * y).sqrt();
+            assert!((distance - 1.).abs() < 1e-9);
+        }
+        assert!(outlines[0].iter().any(|&[_, y]| y < -0.99));
+        assert!(outlines[0].iter().any(|&[_, y]| y > 0.99));
+    }
+
+    #[test]
+    fn entities_outside_of_entities_section() {
+        let dxf = "
+            0\nSECTION\n2\nBLOCKS\n
+            0\nCIRCLE\n10\n0\n20\n0\n40\n1\n
+            0\nENDSEC\n0\nEOF\n
+        ";
+
+        assert!(entities(dxf).unwrap().is_empty());
+    }
+
+    #[test]
+    fn scaled_sketches() {
+        let dxf = dxf("
+            0 POLYLINE 66 1 70 1
+            0 VERTEX 10 0 20 0
+            0 VERTEX 10 0 20 1
+            0 VERTEX 10 1 20 0
+            0 SEQEND
+        ");
+
+        let sketches = SketchFromDxf::new("unused.dxf")
+            .with_scale(2.)
+            .parse(&dxf)
+            .unwrap();
+
+        // The outline is clockwise, so it has been reversed.
+        assert_eq!(sketches[0].to_points(), vec![[2., 0.], [0., 2.], [0., 0.]]);
+    }
+}
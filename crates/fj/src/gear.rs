@@ -0,0 +1,363 @@
+use std::f64::consts::PI;
+
+use crate::{Angle, Implicit, Sketch, Sweep};
+
+/// An involute gear
+///
+/// The teeth have the standard proportions of ISO 53: their addendum is one
+/// module, their dedendum a quarter module more. There's no profile shift and
+/// no backlash, and the root fillets are left sharp.
+///
+/// Spur gears are created as a [`Sketch`] of the profile, which is swept to
+/// the width of the gear. Helical gears are created as [`Implicit`] shapes, as
+/// the profile needs to twist along the width.
+///
+/// # Limitations
+///
+/// Gears with few teeth are undercut, when cut by a rack. That's not modeled,
+/// so the profile of such gears is too thick near the root, and might not mesh
+/// properly. With a pressure angle of 20°, this affects gears with fewer than
+/// 17 teeth.
+///
+/// # Example
+///
+/// ```
+/// let gear = fj::Gear::from_module(1., 20);
+/// assert_eq!(gear.pitch_diameter(), 20.);
+///
+/// let spur = gear.spur(5.);
+/// let helical = gear.helical(5., fj::Angle::deg(15.));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gear {
+    module: f64,
+    teeth: u32,
+    pressure_angle: Angle,
+    flank_points: usize,
+}
+
+impl Gear {
+    /// Create a gear from its module and number of teeth
+    ///
+    /// The module is the pitch diameter divided by the number of teeth. The
+    /// pressure angle defaults to 20°.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `teeth` is zero.
+    pub fn from_module(module: f64, teeth: u32) -> Self {
+        assert!(teeth > 0, "Gear must have at least one tooth");
+
+        Self {
+            module,
+            teeth,
+            pressure_angle: Angle::deg(20.),
+            flank_points: 16,
+        }
+    }
+
+    /// Set the pressure angle
+    ///
+    /// Gears only mesh, if they have the same module and pressure angle.
+    pub fn with_pressure_angle(mut self, pressure_angle: Angle) -> Self {
+        self.pressure_angle = pressure_angle;
+        self
+    }
+
+    /// Set the number of points that approximate each flank of a tooth
+    ///
+    /// Defaults to 16. The flanks are involute curves, which are approximated
+    /// with straight lines.
+    pub fn with_flank_points(mut self, flank_points: usize) -> Self {
+        self.flank_points = flank_points.max(1);
+        self
+    }
+
+    /// Access the module
+    pub fn module(&self) -> f64 {
+        self.module
+    }
+
+    /// Access the number of teeth
+    pub fn teeth(&self) -> u32 {
+        self.teeth
+    }
+
+    /// Access the pressure angle
+    pub fn pressure_angle(&self) -> Angle {
+        self.pressure_angle
+    }
+
+    /// Compute the diameter of the pitch circle
+    ///
+    /// This is where the pitch circles of meshing gears touch, so the distance
+    /// between the axes of two gears is the mean of their pitch diameters.
+    pub fn pitch_diameter(&self) -> f64 {
+        self.module * f64::from(self.teeth)
+    }
+
+    /// Compute the diameter of the base circle, from which the flanks unwind
+    pub fn base_diameter(&self) -> f64 {
+        self.pitch_diameter() * self.pressure_angle.to_rad().cos()
+    }
+
+    /// Compute the diameter of the circle through the tips of the teeth
+    pub fn tip_diameter(&self) -> f64 {
+        self.pitch_diameter() + 2. * self.module
+    }
+
+    /// Compute the diameter of the circle through the roots of the teeth
+    pub fn root_diameter(&self) -> f64 {
+        self.pitch_diameter() - 2.5 * self.module
+    }
+
+    /// Compute the profile of the gear, counter-clockwise
+    ///
+    /// The gear is centered on the origin, with the first tooth on the
+    /// positive x-axis.
+    pub fn to_points(&self) -> Vec<[f64; 2]> {
+        let sector = PI * 2. / f64::from(self.teeth);
+        let tooth = self.tooth();
+        let root = self.root_arc();
+
+        let mut points = Vec::new();
+        for i in 0..self.teeth {
+            let offset = sector * f64::from(i);
+            for &(radius, angle) in tooth.iter().chain(&root) {
+                let (sin, cos) = (angle + offset).sin_cos();
+                points.push([radius * cos, radius * sin]);
+            }
+        }
+
+        points
+    }
+
+    /// Create a sketch of the profile of the gear
+    ///
+    /// See [`Gear::to_points`].
+    pub fn sketch(&self) -> Sketch {
+        Sketch::from_points(self.to_points())
+    }
+
+    /// Create a spur gear, from z = 0 to `width`
+    pub fn spur(&self, width: f64) -> Sweep {
+        Sweep::from_path(self.sketch().into(), [0., 0., width])
+    }
+
+    /// Create a helical gear, from z = 0 to `width`
+    ///
+    /// The helix angle is measured at the pitch circle. Positive angles create
+    /// a right-handed gear, which meshes with a left-handed one.
+    pub fn helical(&self, width: f64, helix_angle: Angle) -> Implicit {
+        let sector = PI * 2. / f64::from(self.teeth);
+        let twist = helix_angle.to_rad().tan() / (self.pitch_diameter() / 2.);
+
+        // All teeth have the same shape, so it's enough to look at the one
+        // that's closest to a point, together with the root on either side.
+        let root = self.root_diameter() / 2.;
+        let after: Vec<_> = self
+            .root_arc()
+            .into_iter()
+            .filter(|&(_, angle)| angle < sector / 2.)
+            .chain([(root, sector / 2.)])
+            .collect();
+        let before =
+            after.iter().rev().map(|&(radius, angle)| (radius, -angle));
+
+        let outline: Vec<_> = before
+            .chain(self.tooth())
+            .chain(after.iter().copied())
+            .map(|(radius, angle)| {
+                let (sin, cos) = angle.sin_cos();
+                [radius * cos, radius * sin]
+            })
+            .collect();
+
+        let radius = self.tip_diameter() / 2.;
+        let min = [-radius, -radius, 0.];
+        let max = [radius, radius, width];
+
+        Implicit::from_fn(min, max, move |[x, y, z]| {
+            let angle = y.atan2(x) - twist * z;
+            let angle = (angle + sector / 2.).rem_euclid(sector) - sector / 2.;
+
+            let radius = (x * x + y * y).sqrt();
+            let (sin, cos) = angle.sin_cos();
+            let distance =
+                profile_distance([radius * cos, radius * sin], &outline);
+
+            distance.max(-z).max(z - width)
+        })
+        .with_resolution(self.module / 8.)
+    }
+
+    /// The outline of the first tooth, in polar coordinates
+    ///
+    /// Starts at the root of its clockwise flank, and ends at the root of its
+    /// counter-clockwise one.
+    fn tooth(&self) -> Vec<(f64, f64)> {
+        let base = self.base_diameter() / 2.;
+        let root = self.root_diameter() / 2.;
+        let tip = self.tip_diameter() / 2.;
+
+        // Below the base circle, there's no involute. The flank continues
+        // towards the center instead.
+        let start = root.max(base);
+        let mut flank = Vec::new();
+        if root < start {
+            flank.push((root, self.flank_angle(start)));
+        }
+        for i in 0..=self.flank_points {
+            let t = i as f64 / self.flank_points as f64;
+            let radius = start + (tip - start) * t;
+            flank.push((radius, self.flank_angle(radius)));
+        }
+
+        let tip_angle = self.flank_angle(tip);
+        let tip_arc = (1..ARC_SEGMENTS).map(|i| {
+            let t = i as f64 / ARC_SEGMENTS as f64;
+            (tip, -tip_angle + 2. * tip_angle * t)
+        });
+
+        flank
+            .iter()
+            .map(|&(radius, angle)| (radius, -angle))
+            .chain(tip_arc)
+            .chain(flank.iter().rev().copied())
+            .collect()
+    }
+
+    /// The root between the first tooth and the next, in polar coordinates
+    ///
+    /// Doesn't include the points where the root meets the teeth.
+    fn root_arc(&self) -> Vec<(f64, f64)> {
+        let sector = PI * 2. / f64::from(self.teeth);
+        let root = self.root_diameter() / 2.;
+        let start = self.flank_angle(root);
+
+        (1..ARC_SEGMENTS)
+            .map(|i| {
+                let t = i as f64 / ARC_SEGMENTS as f64;
+                (root, start + (sector - 2. * start) * t)
+            })
+            .collect()
+    }
+
+    /// The angle between the center of a tooth and its flank, at a radius
+    ///
+    /// Never negative, so a tooth that would come to a point before it reaches
+    /// its tip ends in a point instead.
+    fn flank_angle(&self, radius: f64) -> f64 {
+        let base = self.base_diameter() / 2.;
+
+        // At the pitch circle, teeth and gaps are equally wide.
+        let half_thickness = PI / (2. * f64::from(self.teeth));
+
+        let pressure_angle = self.pressure_angle.to_rad();
+        let angle = (base / radius.max(base)).acos();
+
+        (half_thickness + involute(pressure_angle) - involute(angle)).max(0.)
+    }
+}
+
+/// The number of segments that approximate a circular arc of the profile
+const ARC_SEGMENTS: usize = 4;
+
+/// The angle that the involute of a circle goes around, at a pressure angle
+fn involute(pressure_angle: f64) -> f64 {
+    pressure_angle.tan() - pressure_angle
+}
+
+/// The signed distance of a point from the outline of a tooth
+///
+/// The outline goes around the tooth counter-clockwise, from the middle of the
+/// root on one side to the middle of the root on the other. It's closed by way
+/// of the origin, so points are inside, if they're between the outline and the
+/// origin.
+fn profile_distance(point: [f64; 2], outline: &[[f64; 2]]) -> f64 {
+    let [x, y] = point;
+
+    let mut distance = f64::MAX;
+    for segment in outline.windows(2) {
+        let [[ax, ay], [bx, by]] = [segment[0], segment[1]];
+        let [dx, dy] = [bx - ax, by - ay];
+
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared > 0. {
+            (((x - ax) * dx + (y - ay) * dy) / length_squared).clamp(0., 1.)
+        } else {
+            0.
+        };
+
+        let [cx, cy] = [ax + dx * t, ay + dy * t];
+        distance = distance.min(((x - cx).powi(2) + (y - cy).powi(2)).sqrt());
+    }
+
+    // A ray that leaves a closed outline has to cross it an odd number of
+    // times.
+    let mut inside = false;
+    let closed = [0., 0.];
+    let points = outline.iter().chain([&closed]);
+    for (a, b) in points.clone().zip(points.skip(1).chain(outline.first())) {
+        let [[ax, ay], [bx, by]] = [*a, *b];
+        if (ay > y) != (by > y) {
+            let crossing = ax + (y - ay) / (by - ay) * (bx - ax);
+            if crossing > x {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        -distance
+    } else {
+        distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::Angle;
+
+    use super::Gear;
+
+    #[test]
+    fn standard_proportions() {
+        let gear = Gear::from_module(2., 20);
+
+        assert_eq!(gear.pitch_diameter(), 40.);
+        assert_eq!(gear.tip_diameter(), 44.);
+        assert_eq!(gear.root_diameter(), 35.);
+
+        let points = gear.to_points();
+        for [x, y] in &points {
+            let radius = (x * x + y * y).sqrt();
+            assert!((17.5 - 1e-9..=22. + 1e-9).contains(&radius));
+        }
+
+        // At the pitch circle, teeth and gaps are equally wide.
+        let angle = gear.flank_angle(20.);
+        assert!((angle - PI / 40.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn helical_gear() {
+        let gear = Gear::from_module(1., 20);
+        let helical = gear.helical(10., Angle::deg(30.));
+
+        // The first tooth is on the positive x-axis, at the bottom.
+        assert!(helical.distance([10.5, 0., 0.1]) < 0.);
+        assert!(helical.distance([0.5, 0., 5.]) < 0.);
+
+        // Half a tooth further around, there's a gap between two teeth.
+        let (sin, cos) = (PI / 20.).sin_cos();
+        assert!(helical.distance([10.5 * cos, 10.5 * sin, 0.1]) > 0.);
+
+        // Higher up, the tooth has moved around by half a tooth.
+        let z = PI / 20. / (30_f64.to_radians().tan() / 10.);
+        assert!(helical.distance([10.5 * cos, 10.5 * sin, z]) < 0.);
+        assert!(helical.distance([10.5, 0., z]) > 0.);
+    }
+}
@@ -0,0 +1,172 @@
+use std::{ffi::c_void, fmt};
+
+use crate::{Shape, Shape3d};
+
+/// A 3-dimensional shape, defined by a signed distance function
+///
+/// The function returns the distance of a point from the surface of the
+/// shape, which is negative inside of it. This makes it easy to define shapes
+/// that can't be built from sketches, like spheres, or lattices.
+///
+/// The function is sampled on a grid, and the surface is reconstructed from
+/// those samples as a triangle mesh. Only the region within the bounding box
+/// is sampled, and detail that is smaller than the resolution gets lost. The
+/// result can be used like any other 3-dimensional shape.
+///
+/// # Example
+///
+/// ```
+/// let radius = 2.;
+/// let sphere = fj::Implicit::from_fn([-radius; 3], [radius; 3], move |p| {
+///     let [x, y, z] = p;
+///     (x * x + y * y + z * z).sqrt() - radius
+/// });
+///
+/// assert_eq!(sphere.distance([0., 0., 0.]), -radius);
+/// ```
+#[repr(C)]
+pub struct Implicit {
+    // `Implicit` needs to be FFI-safe, meaning it can't store a `Box<dyn Fn>`
+    // directly. It stores a pointer to the function instead, together with
+    // functions that know the function's type.
+    function: *mut c_void,
+    distance: unsafe extern "C" fn(*const c_void, *const [f64; 3]) -> f64,
+    clone: unsafe extern "C" fn(*const c_void) -> *mut c_void,
+    drop: unsafe extern "C" fn(*mut c_void),
+
+    min: [f64; 3],
+    max: [f64; 3],
+    resolution: f64,
+    // The color of the shape in RGBA
+    color: [u8; 4],
+}
+
+impl Implicit {
+    /// Create an implicit shape from a signed distance function
+    ///
+    /// `min` and `max` are the corners of the bounding box. The resolution
+    /// defaults to 1/64 of the largest extent of the bounding box.
+    pub fn from_fn<F>(min: [f64; 3], max: [f64; 3], function: F) -> Self
+    where
+        F: Fn([f64; 3]) -> f64 + Clone + Send + 'static,
+    {
+        let extent = (0..3).map(|i| max[i] - min[i]).fold(0., f64::max);
+
+        Self {
+            function: Box::into_raw(Box::new(function)).cast(),
+            distance: distance_of::<F>,
+            clone: clone_function::<F>,
+            drop: drop_function::<F>,
+            min,
+            max,
+            resolution: extent / 64.,
+            color: [255, 0, 0, 255],
+        }
+    }
+
+    /// Set the distance between the samples of the function
+    pub fn with_resolution(mut self, resolution: f64) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Set the rendering color of the shape in RGBA
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Evaluate the signed distance function at a point
+    pub fn distance(&self, point: [f64; 3]) -> f64 {
+        // This is sound, as `self.distance` was created for the type that
+        // `self.function` points to.
+        unsafe { (self.distance)(self.function, &point) }
+    }
+
+    /// Access the corner of the bounding box with the smallest coordinates
+    pub fn min(&self) -> [f64; 3] {
+        self.min
+    }
+
+    /// Access the corner of the bounding box with the largest coordinates
+    pub fn max(&self) -> [f64; 3] {
+        self.max
+    }
+
+    /// Access the distance between the samples of the function
+    pub fn resolution(&self) -> f64 {
+        self.resolution
+    }
+
+    /// Get the rendering color of the shape in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.color
+    }
+}
+
+impl Clone for Implicit {
+    fn clone(&self) -> Self {
+        Self {
+            // This is sound, for the same reason as in `Implicit::distance`.
+            function: unsafe { (self.clone)(self.function) },
+            ..*self
+        }
+    }
+}
+
+impl Drop for Implicit {
+    fn drop(&mut self) {
+        // This is sound, for the same reason as in `Implicit::distance`. The
+        // function is not used again after this.
+        unsafe { (self.drop)(self.function) }
+    }
+}
+
+impl fmt::Debug for Implicit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Implicit")
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("resolution", &self.resolution)
+            .field("color", &self.color)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<Implicit> for Shape {
+    fn from(shape: Implicit) -> Self {
+        Self::Shape3d(shape.into())
+    }
+}
+
+impl From<Implicit> for Shape3d {
+    fn from(shape: Implicit) -> Self {
+        Self::Implicit(shape)
+    }
+}
+
+// `Implicit` can be `Send`, because it only accepts functions that are `Send`.
+unsafe impl Send for Implicit {}
+
+unsafe extern "C" fn distance_of<F>(
+    function: *const c_void,
+    point: *const [f64; 3],
+) -> f64
+where
+    F: Fn([f64; 3]) -> f64,
+{
+    let function = &*function.cast::<F>();
+    function(*point)
+}
+
+unsafe extern "C" fn clone_function<F>(function: *const c_void) -> *mut c_void
+where
+    F: Clone,
+{
+    let function = &*function.cast::<F>();
+    Box::into_raw(Box::new(function.clone())).cast()
+}
+
+unsafe extern "C" fn drop_function<F>(function: *mut c_void) {
+    drop(Box::from_raw(function.cast::<F>()));
+}
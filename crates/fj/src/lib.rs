@@ -18,12 +18,26 @@
 
 #![deny(missing_docs)]
 
+pub mod selection;
 pub mod syntax;
 
+mod assertion;
+mod dxf;
+mod gear;
+mod implicit;
+mod material;
 mod shape_2d;
 mod shape_3d;
+mod svg;
+mod texture;
+mod thread;
+mod topology;
+mod units;
 
-pub use self::{shape_2d::*, shape_3d::*};
+pub use self::{
+    assertion::*, dxf::*, gear::*, implicit::*, material::*, shape_2d::*,
+    shape_3d::*, svg::*, texture::*, thread::*, topology::*, units::*,
+};
 
 /// A shape
 #[derive(Clone, Debug)]
@@ -34,4 +48,41 @@ pub enum Shape {
 
     /// A 3D shape
     Shape3d(Shape3d),
+
+    /// A shape that is explicitly marked as a specific kind of object
+    Marked(Box<Marked>),
+
+    /// A shape with an assertion attached
+    Asserted(Box<Asserted>),
+}
+
+impl Shape {
+    /// Access the kind of object the shape is meant to be
+    pub fn topology(&self) -> Topology {
+        match self {
+            Self::Shape2d(_) => Topology::Sheet,
+            Self::Shape3d(_) => Topology::Solid,
+            Self::Marked(shape) => shape.topology,
+            Self::Asserted(shape) => shape.shape.topology(),
+        }
+    }
+
+    /// Access the assertions attached to the shape, outermost first
+    pub fn assertions(&self) -> Vec<Assertion> {
+        let mut assertions = Vec::new();
+
+        let mut shape = self;
+        loop {
+            shape = match shape {
+                Self::Shape2d(_) | Self::Shape3d(_) => break,
+                Self::Marked(marked) => &marked.shape,
+                Self::Asserted(asserted) => {
+                    assertions.push(asserted.assertion);
+                    &asserted.shape
+                }
+            };
+        }
+
+        assertions
+    }
 }
@@ -0,0 +1,148 @@
+use crate::{Shape, Shape3d};
+
+/// A 3-dimensional shape that is made of a specific material
+///
+/// The material applies to all faces of the shape that don't have a material
+/// yet, so assigning a material to an assembly only affects the parts within
+/// it that don't have their own.
+///
+/// # Example
+///
+/// ```
+/// use fj::syntax::*;
+///
+/// let spacer = fj::Circle::from_radius(10.)
+///     .sweep([0., 0., 5.])
+///     .material(fj::Material::pla());
+/// ```
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct WithMaterial {
+    /// The shape that the material is assigned to
+    pub shape: Shape3d,
+
+    /// The material the shape is made of
+    pub material: Material,
+}
+
+impl From<WithMaterial> for Shape {
+    fn from(shape: WithMaterial) -> Self {
+        Self::Shape3d(Shape3d::WithMaterial(Box::new(shape)))
+    }
+}
+
+impl From<WithMaterial> for Shape3d {
+    fn from(shape: WithMaterial) -> Self {
+        Self::WithMaterial(Box::new(shape))
+    }
+}
+
+/// A material that a shape is made of
+///
+/// The density is used to compute the mass of parts, for example in a bill of
+/// materials. Color, metalness, and roughness determine how the shape is
+/// displayed, and the color is exported along with the name, where the format
+/// supports it.
+#[derive(Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct Material {
+    /// The name of the material
+    pub name: String,
+
+    /// The density of the material, in g/cm³
+    pub density: f64,
+
+    /// The color of the material, as RGBA
+    pub color: [u8; 4],
+
+    /// Whether the material is a metal, from `0.` to `1.`
+    ///
+    /// Metals reflect their surroundings in their own color, while other
+    /// materials have a colored, diffuse surface with white highlights. Values
+    /// in between are mostly useful for surfaces that are partially covered
+    /// in dirt or paint.
+    pub metallic: f64,
+
+    /// How rough the surface of the material is, from `0.` to `1.`
+    ///
+    /// Smooth surfaces have sharp highlights, rough surfaces dull ones.
+    pub roughness: f64,
+}
+
+impl Material {
+    /// Create a material with the given name and density
+    ///
+    /// The material is a light gray, moderately rough, non-metal.
+    pub fn new(name: impl Into<String>, density: f64) -> Self {
+        Self {
+            name: name.into(),
+            density,
+            color: [200, 200, 200, 255],
+            metallic: 0.,
+            roughness: 0.5,
+        }
+    }
+
+    /// Set the color of the material
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set how metallic the material is
+    ///
+    /// The value is clamped to the range from `0.` to `1.`.
+    pub fn with_metallic(mut self, metallic: f64) -> Self {
+        self.metallic = metallic.clamp(0., 1.);
+        self
+    }
+
+    /// Set the roughness of the material
+    ///
+    /// The roughness is clamped to the range from `0.` to `1.`.
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.roughness = roughness.clamp(0., 1.);
+        self
+    }
+
+    /// Polylactic acid, the most common material for 3D printing
+    pub fn pla() -> Self {
+        Self::new("PLA", 1.24).with_color([240, 240, 240, 255])
+    }
+
+    /// Acrylonitrile butadiene styrene, a tough plastic
+    pub fn abs() -> Self {
+        Self::new("ABS", 1.04).with_color([40, 40, 40, 255])
+    }
+
+    /// Polyethylene terephthalate glycol, a plastic for 3D printing
+    pub fn petg() -> Self {
+        Self::new("PETG", 1.27)
+            .with_color([80, 140, 200, 255])
+            .with_roughness(0.3)
+    }
+
+    /// Aluminum
+    pub fn aluminum() -> Self {
+        Self::new("Aluminum", 2.7)
+            .with_color([200, 202, 205, 255])
+            .with_metallic(1.)
+            .with_roughness(0.4)
+    }
+
+    /// Steel
+    pub fn steel() -> Self {
+        Self::new("Steel", 7.85)
+            .with_color([140, 145, 150, 255])
+            .with_metallic(1.)
+            .with_roughness(0.3)
+    }
+
+    /// Brass
+    pub fn brass() -> Self {
+        Self::new("Brass", 8.5)
+            .with_color([200, 160, 60, 255])
+            .with_metallic(1.)
+            .with_roughness(0.3)
+    }
+}
@@ -0,0 +1,117 @@
+//! Selection of faces and edges
+//!
+//! Operations like fillets, chamfers, or coloring apply to specific faces or
+//! edges of a shape. Since a model can't know how its shape is going to be
+//! represented after evaluation, it can't refer to those faces or edges
+//! directly. Instead, it describes how to find them:
+//!
+//! ``` rust
+//! use fj::selection::{edges, faces};
+//!
+//! // The top face of the shape.
+//! let top = faces().parallel_to([0., 0., 1.]).topmost();
+//!
+//! // The long edges of the top face.
+//! let long_edges = edges().of_face(top).with_length_gt(5.);
+//! ```
+//!
+//! A selection starts with all faces or edges of a shape, and every method
+//! narrows it down further. Selections are resolved, once the shape they are
+//! applied to has been evaluated.
+
+/// Start a selection of faces
+///
+/// The returned selection contains all faces of a shape.
+pub fn faces() -> FaceSelection {
+    FaceSelection::All
+}
+
+/// Start a selection of edges
+///
+/// The returned selection contains all edges of a shape.
+pub fn edges() -> EdgeSelection {
+    EdgeSelection::All
+}
+
+/// A description of a set of faces
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum FaceSelection {
+    /// All faces
+    All,
+
+    /// The faces of a selection whose normal is parallel to a direction
+    ///
+    /// The normal can point in the same or in the opposite direction. Only
+    /// planar faces have a normal, so curved faces are never selected.
+    ParallelTo(Box<FaceSelection>, [f64; 3]),
+
+    /// The faces of a selection that extend furthest in a direction
+    ///
+    /// More than one face is selected, if they extend equally far.
+    Furthest(Box<FaceSelection>, [f64; 3]),
+}
+
+impl FaceSelection {
+    /// Select the faces whose normal is parallel to `direction`
+    pub fn parallel_to(self, direction: [f64; 3]) -> Self {
+        Self::ParallelTo(Box::new(self), direction)
+    }
+
+    /// Select the faces that extend furthest in `direction`
+    pub fn furthest(self, direction: [f64; 3]) -> Self {
+        Self::Furthest(Box::new(self), direction)
+    }
+
+    /// Select the faces that extend furthest along the positive z-axis
+    pub fn topmost(self) -> Self {
+        self.furthest([0., 0., 1.])
+    }
+
+    /// Select the faces that extend furthest along the negative z-axis
+    pub fn bottommost(self) -> Self {
+        self.furthest([0., 0., -1.])
+    }
+}
+
+/// A description of a set of edges
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub enum EdgeSelection {
+    /// All edges
+    All,
+
+    /// The edges of a selection that bound one of the selected faces
+    OfFace(Box<EdgeSelection>, FaceSelection),
+
+    /// The edges of a selection that are straight and parallel to a direction
+    ParallelTo(Box<EdgeSelection>, [f64; 3]),
+
+    /// The edges of a selection that are longer than a length
+    LengthGreaterThan(Box<EdgeSelection>, f64),
+
+    /// The edges of a selection that are shorter than a length
+    LengthLessThan(Box<EdgeSelection>, f64),
+}
+
+impl EdgeSelection {
+    /// Select the edges that bound one of the faces selected by `faces`
+    pub fn of_face(self, faces: FaceSelection) -> Self {
+        Self::OfFace(Box::new(self), faces)
+    }
+
+    /// Select the straight edges that are parallel to `direction`
+    pub fn parallel_to(self, direction: [f64; 3]) -> Self {
+        Self::ParallelTo(Box::new(self), direction)
+    }
+
+    /// Select the edges that are longer than `length`
+    pub fn with_length_gt(self, length: f64) -> Self {
+        Self::LengthGreaterThan(Box::new(self), length)
+    }
+
+    /// Select the edges that are shorter than `length`
+    pub fn with_length_lt(self, length: f64) -> Self {
+        Self::LengthLessThan(Box::new(self), length)
+    }
+}
@@ -1,4 +1,4 @@
-use std::mem;
+use std::{error, f64::consts::TAU, fmt, mem};
 
 use crate::Shape;
 
@@ -12,6 +12,9 @@ pub enum Shape2d {
     /// A difference between two shapes
     Difference(Box<Difference2d>),
 
+    /// A Minkowski sum of two shapes
+    Minkowski(Box<Minkowski2d>),
+
     /// A sketch
     Sketch(Sketch),
 }
@@ -23,6 +26,31 @@ impl Shape2d {
             Shape2d::Circle(c) => c.color(),
             Shape2d::Sketch(s) => s.color(),
             Shape2d::Difference(d) => d.color(),
+            Shape2d::Minkowski(m) => m.color(),
+        }
+    }
+
+    /// Set the rendering color in RGBA
+    ///
+    /// Differences and Minkowski sums are rendered in the color of their first
+    /// shape, so that's the one that is recolored.
+    pub fn with_color(self, color: [u8; 4]) -> Self {
+        match self {
+            Shape2d::Circle(circle) => circle.with_color(color).into(),
+            Shape2d::Sketch(sketch) => sketch.with_color(color).into(),
+            Shape2d::Difference(difference) => {
+                let [a, b] = difference.shapes().clone();
+                Difference2d::from_shapes([a.with_color(color), b]).into()
+            }
+            Shape2d::Minkowski(minkowski) => {
+                // Recoloring a shape doesn't change whether it's convex, so
+                // there's no need to check again.
+                let [a, b] = minkowski.shapes().clone();
+                Minkowski2d {
+                    shapes: [a.with_color(color), b],
+                }
+                .into()
+            }
         }
     }
 }
@@ -116,6 +144,116 @@ impl From<Difference2d> for Shape2d {
     }
 }
 
+/// A Minkowski sum of two shapes
+///
+/// The Minkowski sum contains every point that can be reached by adding a
+/// point of one shape to a point of the other. Summing a polygon with a circle
+/// rounds the polygon's corners and grows it by the circle's radius, for
+/// example.
+///
+/// Both shapes must be convex, which includes circles, convex sketches, and
+/// other Minkowski sums, but not differences. Circles are approximated
+/// according to the tolerance, so the rounded corners are polygonal.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Minkowski2d {
+    shapes: [Shape2d; 2],
+}
+
+impl Minkowski2d {
+    /// Create a `Minkowski2d` from two shapes
+    ///
+    /// Returns an error, if either of the shapes is not convex.
+    pub fn from_shapes(shapes: [Shape2d; 2]) -> Result<Self, NotConvex> {
+        for (operand, shape) in shapes.iter().enumerate() {
+            let is_convex = match shape {
+                Shape2d::Circle(_) | Shape2d::Minkowski(_) => true,
+                Shape2d::Difference(_) => false,
+                Shape2d::Sketch(sketch) => is_convex(&sketch.to_points()),
+            };
+
+            if !is_convex {
+                return Err(NotConvex { operand });
+            }
+        }
+
+        Ok(Self { shapes })
+    }
+
+    /// Get the rendering color of the first shape in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.shapes[0].color()
+    }
+
+    /// Access the shapes that are summed
+    pub fn shapes(&self) -> &[Shape2d; 2] {
+        &self.shapes
+    }
+}
+
+/// An error that can occur when creating a [`Minkowski2d`]
+#[derive(Debug)]
+pub struct NotConvex {
+    /// The index of the shape that is not convex
+    pub operand: usize,
+}
+
+impl fmt::Display for NotConvex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Minkowski sum requires convex shapes, but shape {} is not",
+            self.operand
+        )
+    }
+}
+
+impl error::Error for NotConvex {}
+
+/// Check whether a polygon is convex, in either orientation
+///
+/// Collinear points are allowed. A polygon that turns the same way at every
+/// point can still intersect itself, like a star, which is why the turns must
+/// also add up to a single full turn.
+fn is_convex(points: &[[f64; 2]]) -> bool {
+    if points.len() < 3 {
+        return false;
+    }
+
+    let mut left = false;
+    let mut right = false;
+    let mut turns = 0.;
+
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let c = points[(i + 2) % points.len()];
+
+        let u = [b[0] - a[0], b[1] - a[1]];
+        let v = [c[0] - b[0], c[1] - b[1]];
+
+        let cross = u[0] * v[1] - u[1] * v[0];
+        let dot = u[0] * v[0] + u[1] * v[1];
+
+        left |= cross > 0.;
+        right |= cross < 0.;
+        turns += cross.atan2(dot);
+    }
+
+    !(left && right) && (turns.abs() - TAU).abs() < 1e-6
+}
+
+impl From<Minkowski2d> for Shape {
+    fn from(shape: Minkowski2d) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Minkowski2d> for Shape2d {
+    fn from(shape: Minkowski2d) -> Self {
+        Self::Minkowski(Box::new(shape))
+    }
+}
+
 /// A sketch
 ///
 /// Sketches are currently limited to a single cycle of straight lines,
@@ -210,3 +348,62 @@ impl From<Sketch> for Shape2d {
 // `Sketch` can be `Send`, because it encapsulates the raw pointer it contains,
 // making sure memory ownership rules are observed.
 unsafe impl Send for Sketch {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Circle, Difference2d, Minkowski2d, Sketch};
+
+    #[test]
+    fn minkowski_of_convex_shapes() {
+        let square =
+            Sketch::from_points(vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]]);
+        let circle = Circle::from_radius(0.1);
+
+        // Both orientations are fine, and so are collinear points.
+        let clockwise = Sketch::from_points(vec![
+            [0., 0.],
+            [0., 1.],
+            [1., 1.],
+            [1., 0.5],
+            [1., 0.],
+        ]);
+
+        let sum =
+            Minkowski2d::from_shapes([square.into(), circle.clone().into()])
+                .unwrap();
+        Minkowski2d::from_shapes([sum.into(), clockwise.into()]).unwrap();
+        Minkowski2d::from_shapes([circle.clone().into(), circle.into()])
+            .unwrap();
+    }
+
+    #[test]
+    fn minkowski_of_non_convex_shapes() {
+        let circle = Circle::from_radius(0.1);
+
+        let l_shape = Sketch::from_points(vec![
+            [0., 0.],
+            [2., 0.],
+            [2., 1.],
+            [1., 1.],
+            [1., 2.],
+            [0., 2.],
+        ]);
+        let star = Sketch::from_points(vec![
+            [0., 1.],
+            [0.6, -0.8],
+            [-0.95, 0.3],
+            [0.95, 0.3],
+            [-0.6, -0.8],
+        ]);
+        let difference = Difference2d::from_shapes([
+            Circle::from_radius(1.).into(),
+            circle.clone().into(),
+        ]);
+
+        for shape in [l_shape.into(), star.into(), difference.into()] {
+            let err = Minkowski2d::from_shapes([circle.clone().into(), shape])
+                .unwrap_err();
+            assert_eq!(err.operand, 1);
+        }
+    }
+}
@@ -1,17 +1,35 @@
-use crate::{Shape, Shape2d};
+use crate::{Implicit, Shape, Shape2d, Textured, WithMaterial};
 
 /// A 3-dimensional shape
 #[derive(Clone, Debug)]
 #[repr(C)]
 pub enum Shape3d {
+    /// A 3-dimensional shape that moves in a specific direction, when exploded
+    Exploded(Box<Exploded>),
+
     /// A group of two 3-dimensional shapes
     Group(Box<Group>),
 
+    /// A shape defined by a signed distance function
+    Implicit(Implicit),
+
+    /// A 3-dimensional shape with a name
+    Named(Box<Named>),
+
+    /// A 3-dimensional shape, grown by a sphere
+    Rounded(Box<Rounded>),
+
     /// A sweep of 2-dimensional shape along the z-axis
     Sweep(Sweep),
 
+    /// A 3-dimensional shape with a texture applied to some of its faces
+    Textured(Box<Textured>),
+
     /// A transformed 3-dimensional shape
     Transform(Box<Transform>),
+
+    /// A 3-dimensional shape that is made of a specific material
+    WithMaterial(Box<WithMaterial>),
 }
 
 impl From<Shape3d> for Shape {
@@ -50,6 +68,137 @@ impl From<Group> for Shape3d {
     }
 }
 
+/// A 3-dimensional shape that moves in a specific direction, when exploded
+///
+/// Groups are treated as assemblies, whose members can be pulled apart in an
+/// exploded view. By default, each member moves away from the center of the
+/// assembly. This overrides that direction for all faces of the shape.
+///
+/// `direction` is how far the shape moves, when the assembly is fully
+/// exploded. Exploded shapes within exploded shapes add up their directions, so
+/// a part moves together with its sub-assembly.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Exploded {
+    /// The shape that is exploded
+    pub shape: Shape3d,
+
+    /// The direction and distance the shape moves
+    pub direction: [f64; 3],
+}
+
+impl From<Exploded> for Shape {
+    fn from(shape: Exploded) -> Self {
+        Self::Shape3d(Shape3d::Exploded(Box::new(shape)))
+    }
+}
+
+impl From<Exploded> for Shape3d {
+    fn from(shape: Exploded) -> Self {
+        Self::Exploded(Box::new(shape))
+    }
+}
+
+/// A 3-dimensional shape with a name
+///
+/// Names identify the parts of an assembly, like in a bill of materials. They
+/// apply to all faces of the shape that don't have a name yet, so naming an
+/// assembly doesn't rename the parts within it.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Named {
+    /// The shape that is named
+    pub shape: Shape3d,
+
+    /// The name of the shape
+    pub name: String,
+}
+
+impl From<Named> for Shape {
+    fn from(shape: Named) -> Self {
+        Self::Shape3d(Shape3d::Named(Box::new(shape)))
+    }
+}
+
+impl From<Named> for Shape3d {
+    fn from(shape: Named) -> Self {
+        Self::Named(Box::new(shape))
+    }
+}
+
+/// A 3-dimensional shape, grown by a sphere
+///
+/// This is the Minkowski sum of the shape with a sphere of the given radius:
+/// Every point within `radius` of the shape is part of the result. Flat faces
+/// move outward by `radius`, while convex edges and corners become round. To
+/// round a shape without changing its size, make it smaller by `radius` first.
+///
+/// Like an [`Implicit`] shape, the result is reconstructed from samples of the
+/// distance to the shape, taken on a grid. Detail that is smaller than the
+/// resolution gets lost. Sampling takes time proportional to the number of
+/// samples times the number of triangles of the shape, so a fine resolution
+/// gets slow quickly.
+///
+/// [`Implicit`]: crate::Implicit
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Rounded {
+    /// The shape being rounded
+    shape: Shape3d,
+
+    /// The radius of the sphere
+    radius: f64,
+
+    /// The distance between the samples, if set
+    resolution: Option<f64>,
+}
+
+impl Rounded {
+    /// Create a `Rounded` from a shape and the radius of the sphere
+    ///
+    /// The resolution defaults to 1/64 of the largest extent of the result.
+    pub fn from_shape(shape: Shape3d, radius: f64) -> Self {
+        Self {
+            shape,
+            radius,
+            resolution: None,
+        }
+    }
+
+    /// Set the distance between the samples of the distance to the shape
+    pub fn with_resolution(mut self, resolution: f64) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+
+    /// Access the shape being rounded
+    pub fn shape(&self) -> &Shape3d {
+        &self.shape
+    }
+
+    /// Access the radius of the sphere
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Access the distance between the samples, if it has been set
+    pub fn resolution(&self) -> Option<f64> {
+        self.resolution
+    }
+}
+
+impl From<Rounded> for Shape {
+    fn from(shape: Rounded) -> Self {
+        Self::Shape3d(Shape3d::Rounded(Box::new(shape)))
+    }
+}
+
+impl From<Rounded> for Shape3d {
+    fn from(shape: Rounded) -> Self {
+        Self::Rounded(Box::new(shape))
+    }
+}
+
 /// A transformed 3-dimensional shape
 ///
 /// # Limitations
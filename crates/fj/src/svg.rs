@@ -0,0 +1,775 @@
+//! Creation of sketches from SVG files
+//!
+//! See [`SketchFromSvg`].
+
+use std::{error, f64::consts::PI, fmt, fs, io, path::PathBuf};
+
+use crate::Sketch;
+
+/// Creates sketches from the paths in an SVG file
+///
+/// This makes it possible to extrude logos or profiles that were drawn in an
+/// editor like Inkscape. Each closed part of a path (each sub-path, in SVG
+/// terms) becomes a separate [`Sketch`].
+///
+/// ``` no_run
+/// use fj::{syntax::*, SketchFromSvg};
+///
+/// let sketches = SketchFromSvg::new("logo.svg")
+///     .with_tolerance(0.05)
+///     .load()
+///     .unwrap();
+/// let logo = sketches[0].sweep([0., 0., 1.]);
+/// ```
+///
+/// Since sketches are made of straight lines, curves are approximated. The
+/// tolerance defines how far the approximation may deviate from the curve.
+///
+/// SVG's y-axis points down. It is flipped, so the sketches appear the same
+/// way they do in the editor, when looking at them from above.
+///
+/// # Limitations
+///
+/// Only `<path>` elements are read, and their `transform` attributes, as well
+/// as those of the groups containing them, are ignored. Paths within `<defs>`
+/// are skipped.
+///
+/// Holes are returned as separate sketches. They can be cut out of the
+/// surrounding sketch with a [`Difference2d`].
+///
+/// [`Difference2d`]: crate::Difference2d
+#[derive(Clone, Debug)]
+pub struct SketchFromSvg {
+    path: PathBuf,
+    tolerance: f64,
+    scale: f64,
+}
+
+impl SketchFromSvg {
+    /// Prepare loading sketches from the SVG file at the given path
+    ///
+    /// Uses a tolerance of `0.1`, and a scale of `1.0`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            tolerance: 0.1,
+            scale: 1.,
+        }
+    }
+
+    /// Set the curve tolerance
+    ///
+    /// The tolerance is given in model units, which means it is applied after
+    /// scaling.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the factor that SVG coordinates are multiplied with
+    ///
+    /// Useful, if the SVG file is not drawn in the units of the model. The
+    /// scale must be finite and not zero, or loading fails. A negative scale
+    /// mirrors the sketches.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Load the SVG file, and create a sketch for each sub-path
+    pub fn load(&self) -> Result<Vec<Sketch>, SvgError> {
+        let svg = fs::read_to_string(&self.path)?;
+        self.parse(&svg)
+    }
+
+    /// Create a sketch for each sub-path in the given SVG document
+    ///
+    /// Like [`SketchFromSvg::load`], but the document is passed directly. The
+    /// path that this instance was created with is ignored.
+    pub fn parse(&self, svg: &str) -> Result<Vec<Sketch>, SvgError> {
+        if self.tolerance.is_nan() || self.tolerance <= 0. {
+            return Err(SvgError::Invalid(format!(
+                "Tolerance must be positive, not {}",
+                self.tolerance
+            )));
+        }
+
+        if !self.scale.is_finite() || self.scale == 0. {
+            return Err(SvgError::Invalid(format!(
+                "Scale must be finite and not zero, not {}",
+                self.scale
+            )));
+        }
+
+        // Curves are approximated in SVG coordinates, so the tolerance needs
+        // to be scaled the other way.
+        let tolerance = self.tolerance / self.scale.abs();
+
+        let mut sketches = Vec::new();
+        for data in path_data(svg) {
+            for mut points in parse_path_data(&data, tolerance)? {
+                for [x, y] in &mut points {
+                    *x *= self.scale;
+                    *y *= -self.scale;
+                }
+
+                // Orientation is not consistent in SVG files, but `Sketch`
+                // should always be counter-clockwise.
+                if signed_area(&points) < 0. {
+                    points.reverse();
+                }
+
+                sketches.push(Sketch::from_points(points));
+            }
+        }
+
+        if sketches.is_empty() {
+            return Err(SvgError::NoPaths);
+        }
+
+        Ok(sketches)
+    }
+}
+
+/// An error that can occur when creating sketches from an SVG file
+#[derive(Debug)]
+pub enum SvgError {
+    /// The file could not be read
+    Io(io::Error),
+
+    /// The file contains invalid path data
+    Invalid(String),
+
+    /// The file contains no paths that could be turned into sketches
+    NoPaths,
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "Error reading SVG file"),
+            Self::Invalid(message) => write!(f, "Invalid SVG: {}", message),
+            Self::NoPaths => write!(f, "SVG file contains no closed paths"),
+        }
+    }
+}
+
+impl error::Error for SvgError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SvgError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Extract the `d` attributes of all `<path>` elements
+///
+/// This is not a full XML parser, but it handles what editors typically write.
+fn path_data(svg: &str) -> Vec<String> {
+    let mut data = Vec::new();
+    let mut rest = svg;
+    let mut in_defs = false;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        // Closing tags keep their leading slash, empty tags lose their
+        // trailing one.
+        let name = tag
+            .split(char::is_whitespace)
+            .next()
+            .unwrap_or("")
+            .trim_end_matches('/');
+        match name {
+            "defs" if !tag.ends_with('/') => in_defs = true,
+            "/defs" => in_defs = false,
+            "path" if !in_defs => {
+                if let Some(d) = attribute(&tag[name.len()..], "d") {
+                    data.push(d.to_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    data
+}
+
+/// Find the value of an attribute within the inside of a tag
+fn attribute<'r>(mut attributes: &'r str, name: &str) -> Option<&'r str> {
+    loop {
+        attributes = attributes.trim_start();
+
+        let (attribute, rest) = attributes.split_once('=')?;
+        let rest = rest.trim_start();
+
+        let quote = rest.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let (value, rest) = rest[1..].split_once(quote)?;
+
+        if attribute.trim() == name {
+            return Some(value);
+        }
+        attributes = rest;
+    }
+}
+
+/// Parse path data into sub-paths, approximating curves with straight lines
+fn parse_path_data(
+    data: &str,
+    tolerance: f64,
+) -> Result<Vec<Vec<[f64; 2]>>, SvgError> {
+    let mut lexer = Lexer {
+        data: data.as_bytes(),
+        position: 0,
+    };
+    let mut path = PathBuilder::default();
+
+    let mut command = None;
+
+    // The last control point of the previous command, if it was a cubic (`C`)
+    // or quadratic (`Q`) curve. Smooth curves reflect it.
+    let mut previous_control: Option<(u8, [f64; 2])> = None;
+
+    while !lexer.at_end() {
+        // Commands may repeat implicitly, if more arguments follow.
+        if let Some(next) = lexer.command() {
+            command = Some(next);
+        }
+        let c = command.ok_or_else(|| {
+            SvgError::Invalid("Expected command in path data".to_owned())
+        })?;
+
+        let relative = c.is_ascii_lowercase();
+        let origin = if relative { path.position } else { [0., 0.] };
+        let position = path.position;
+
+        let mut control = None;
+
+        match c.to_ascii_uppercase() {
+            b'M' => {
+                let point = lexer.point(origin)?;
+                path.move_to(point);
+
+                // Further coordinate pairs are implicit line commands.
+                command = Some(if relative { b'l' } else { b'L' });
+            }
+            b'L' => {
+                let point = lexer.point(origin)?;
+                path.line_to(point);
+            }
+            b'H' => {
+                let x = lexer.number()? + origin[0];
+                path.line_to([x, position[1]]);
+            }
+            b'V' => {
+                let y = lexer.number()? + origin[1];
+                path.line_to([position[0], y]);
+            }
+            b'C' | b'S' => {
+                let c1 = if c.to_ascii_uppercase() == b'C' {
+                    lexer.point(origin)?
+                } else {
+                    reflect(previous_control, b'C', position)
+                };
+                let c2 = lexer.point(origin)?;
+                let end = lexer.point(origin)?;
+
+                path.cubic_to(c1, c2, end, tolerance);
+                control = Some((b'C', c2));
+            }
+            b'Q' | b'T' => {
+                let c1 = if c.to_ascii_uppercase() == b'Q' {
+                    lexer.point(origin)?
+                } else {
+                    reflect(previous_control, b'Q', position)
+                };
+                let end = lexer.point(origin)?;
+
+                // A quadratic curve is a special case of a cubic one.
+                let to_cubic = |p: [f64; 2]| {
+                    [
+                        p[0] + 2. / 3. * (c1[0] - p[0]),
+                        p[1] + 2. / 3. * (c1[1] - p[1]),
+                    ]
+                };
+                path.cubic_to(
+                    to_cubic(position),
+                    to_cubic(end),
+                    end,
+                    tolerance,
+                );
+                control = Some((b'Q', c1));
+            }
+            b'A' => {
+                let radii = [lexer.number()?, lexer.number()?];
+                let rotation = lexer.number()?;
+                let large_arc = lexer.flag()?;
+                let sweep = lexer.flag()?;
+                let end = lexer.point(origin)?;
+
+                let arc = Arc {
+                    radii,
+                    rotation,
+                    large_arc,
+                    sweep,
+                };
+                path.arc_to(arc, end, tolerance);
+            }
+            b'Z' => {
+                path.close();
+
+                // `Z` takes no arguments, so it can't be repeated.
+                command = None;
+            }
+            other => {
+                return Err(SvgError::Invalid(format!(
+                    "Unknown path command `{}`",
+                    other as char
+                )))
+            }
+        }
+
+        previous_control = control;
+    }
+
+    path.close();
+    Ok(path.subpaths)
+}
+
+/// Reflect the previous control point, for a smooth curve
+///
+/// If the previous command was not a curve of the same kind, the current
+/// position is used instead, as the SVG specification requires.
+fn reflect(
+    previous: Option<(u8, [f64; 2])>,
+    kind: u8,
+    position: [f64; 2],
+) -> [f64; 2] {
+    match previous {
+        Some((previous_kind, [x, y])) if previous_kind == kind => {
+            [2. * position[0] - x, 2. * position[1] - y]
+        }
+        _ => position,
+    }
+}
+
+#[derive(Default)]
+struct PathBuilder {
+    subpaths: Vec<Vec<[f64; 2]>>,
+    current: Vec<[f64; 2]>,
+    position: [f64; 2],
+    start: [f64; 2],
+}
+
+impl PathBuilder {
+    fn move_to(&mut self, point: [f64; 2]) {
+        self.close();
+
+        self.position = point;
+        self.start = point;
+    }
+
+    fn line_to(&mut self, point: [f64; 2]) {
+        // Drawing after `Z` continues from the start of the closed sub-path.
+        if self.current.is_empty() {
+            self.current.push(self.position);
+        }
+
+        self.current.push(point);
+        self.position = point;
+    }
+
+    fn cubic_to(
+        &mut self,
+        c1: [f64; 2],
+        c2: [f64; 2],
+        end: [f64; 2],
+        tolerance: f64,
+    ) {
+        let mut points = Vec::new();
+        flatten_cubic([self.position, c1, c2, end], tolerance, 16, &mut points);
+
+        for point in points {
+            self.line_to(point);
+        }
+    }
+
+    fn arc_to(&mut self, arc: Arc, end: [f64; 2], tolerance: f64) {
+        for point in arc.flatten(self.position, end, tolerance) {
+            self.line_to(point);
+        }
+    }
+
+    /// Finish the current sub-path
+    ///
+    /// Sub-paths are treated as closed, whether they end with `Z` or not, as
+    /// that's how SVG fills them. Those that don't enclose an area are dropped.
+    fn close(&mut self) {
+        let mut points = std::mem::take(&mut self.current);
+        self.position = self.start;
+
+        // The cycle is closed implicitly, so the closing point is redundant.
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        points.dedup();
+
+        if points.len() >= 3 {
+            self.subpaths.push(points);
+        }
+    }
+}
+
+/// Recursively subdivide a cubic curve, until it is flat enough
+fn flatten_cubic(
+    [p0, p1, p2, p3]: [[f64; 2]; 4],
+    tolerance: f64,
+    depth: u32,
+    points: &mut Vec<[f64; 2]>,
+) {
+    let flatness =
+        distance_to_line(p1, p0, p3).max(distance_to_line(p2, p0, p3));
+
+    if depth == 0 || flatness <= tolerance {
+        points.push(p3);
+        return;
+    }
+
+    // Split the curve in half (de Casteljau's algorithm).
+    let mid =
+        |a: [f64; 2], b: [f64; 2]| [(a[0] + b[0]) / 2., (a[1] + b[1]) / 2.];
+    let p01 = mid(p0, p1);
+    let p12 = mid(p1, p2);
+    let p23 = mid(p2, p3);
+    let p012 = mid(p01, p12);
+    let p123 = mid(p12, p23);
+    let p0123 = mid(p012, p123);
+
+    flatten_cubic([p0, p01, p012, p0123], tolerance, depth - 1, points);
+    flatten_cubic([p0123, p123, p23, p3], tolerance, depth - 1, points);
+}
+
+fn distance_to_line(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let [dx, dy] = [b[0] - a[0], b[1] - a[1]];
+    let length = (dx * dx + dy * dy).sqrt();
+
+    if length == 0. {
+        let [px, py] = [p[0] - a[0], p[1] - a[1]];
+        return (px * px + py * py).sqrt();
+    }
+
+    ((p[0] - a[0]) * dy - (p[1] - a[1]) * dx).abs() / length
+}
+
+/// The parameters of an elliptical arc, as given in path data
+struct Arc {
+    radii: [f64; 2],
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+}
+
+impl Arc {
+    /// Approximate the arc from `start` to `end` with straight lines
+    ///
+    /// Returns the points after `start`. This follows the conversion from
+    /// endpoint to center parameterization in the SVG specification:
+    /// <https://www.w3.org/TR/SVG11/implnote.html#ArcImplementationNotes>
+    fn flatten(
+        &self,
+        start: [f64; 2],
+        end: [f64; 2],
+        tolerance: f64,
+    ) -> Vec<[f64; 2]> {
+        let [mut rx, mut ry] = self.radii.map(f64::abs);
+        if start == end {
+            return Vec::new();
+        }
+        if rx == 0. || ry == 0. {
+            return vec![end];
+        }
+
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+
+        let dx = (start[0] - end[0]) / 2.;
+        let dy = (start[1] - end[1]) / 2.;
+        let x1 = cos * dx + sin * dy;
+        let y1 = -sin * dx + cos * dy;
+
+        // Radii that are too small are scaled up, until the arc fits.
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1. {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator =
+            rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coefficient = (numerator / denominator).max(0.).sqrt();
+        if self.large_arc == self.sweep {
+            coefficient = -coefficient;
+        }
+
+        let cx1 = coefficient * rx * y1 / ry;
+        let cy1 = -coefficient * ry * x1 / rx;
+
+        let cx = cos * cx1 - sin * cy1 + (start[0] + end[0]) / 2.;
+        let cy = sin * cx1 + cos * cy1 + (start[1] + end[1]) / 2.;
+
+        let u = [(x1 - cx1) / rx, (y1 - cy1) / ry];
+        let v = [(-x1 - cx1) / rx, (-y1 - cy1) / ry];
+
+        let theta = angle([1., 0.], u);
+        let mut delta = angle(u, v);
+        if !self.sweep && delta > 0. {
+            delta -= 2. * PI;
+        } else if self.sweep && delta < 0. {
+            delta += 2. * PI;
+        }
+
+        // The maximum angle a segment can span, without deviating from the
+        // arc by more than the tolerance.
+        let radius = rx.max(ry);
+        let max_angle = if tolerance < radius {
+            2. * (1. - tolerance / radius).acos()
+        } else {
+            PI / 2.
+        };
+        let n = (delta.abs() / max_angle).ceil().max(1.) as usize;
+
+        let mut points: Vec<_> = (1..n)
+            .map(|i| {
+                let t = theta + delta * i as f64 / n as f64;
+                let (sin_t, cos_t) = t.sin_cos();
+
+                [
+                    cos * rx * cos_t - sin * ry * sin_t + cx,
+                    sin * rx * cos_t + cos * ry * sin_t + cy,
+                ]
+            })
+            .collect();
+        points.push(end);
+
+        points
+    }
+}
+
+/// The signed angle between two vectors
+fn angle(u: [f64; 2], v: [f64; 2]) -> f64 {
+    (u[0] * v[1] - u[1] * v[0]).atan2(u[0] * v[0] + u[1] * v[1])
+}
+
+pub(crate) fn signed_area(points: &[[f64; 2]]) -> f64 {
+    let mut area = 0.;
+
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a[0] * b[1] - b[0] * a[1];
+    }
+
+    area / 2.
+}
+
+struct Lexer<'r> {
+    data: &'r [u8],
+    position: usize,
+}
+
+impl Lexer<'_> {
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.position >= self.data.len()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+
+        let c = *self.data.get(self.position)?;
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+
+        self.position += 1;
+        Some(c)
+    }
+
+    fn number(&mut self) -> Result<f64, SvgError> {
+        self.skip_separators();
+        let start = self.position;
+
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.position += 1;
+        }
+        self.skip_digits();
+        // A second decimal point starts the next number, as in `0.5.5`.
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.skip_digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            self.skip_digits();
+        }
+
+        // Can't fail, as only ASCII characters have been consumed.
+        let number =
+            std::str::from_utf8(&self.data[start..self.position]).unwrap();
+        number.parse().map_err(|_| {
+            SvgError::Invalid(format!(
+                "Expected number in path data, found `{}`",
+                number
+            ))
+        })
+    }
+
+    /// Parse an arc flag, which doesn't need to be separated from what follows
+    fn flag(&mut self) -> Result<bool, SvgError> {
+        self.skip_separators();
+
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => {
+                return Err(SvgError::Invalid(
+                    "Expected flag in path data".to_owned(),
+                ))
+            }
+        };
+        self.position += 1;
+
+        Ok(flag)
+    }
+
+    fn point(&mut self, origin: [f64; 2]) -> Result<[f64; 2], SvgError> {
+        let x = self.number()?;
+        let y = self.number()?;
+
+        Ok([origin[0] + x, origin[1] + y])
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    fn skip_digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_whitespace() || c == b',') {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_path_data, path_data, SketchFromSvg, SvgError};
+
+    #[test]
+    fn defs_and_comments() {
+        let svg = r#"
+            <svg xmlns="http://www.w3.org/2000/svg">
+                <defs><path id="clip" d="M 0 0 L 1 0 L 1 1 Z"/></defs>
+                <!-- <path d="M 5 5 L 6 5 L 6 6 Z"/> -->
+                <path id="shape" style="fill:red" d='M0,0 h2 v2 h-2 z' />
+            </svg>
+        "#;
+
+        assert_eq!(path_data(svg), vec!["M0,0 h2 v2 h-2 z"]);
+    }
+
+    #[test]
+    fn compact_path_data() {
+        let subpaths =
+            parse_path_data("M0-1L2.5.5l-1,1zm5,5h1v1h-1", 0.1).unwrap();
+
+        assert_eq!(
+            subpaths,
+            vec![
+                vec![[0., -1.], [2.5, 0.5], [1.5, 1.5]],
+                vec![[5., 4.], [6., 4.], [6., 5.], [5., 5.]],
+            ]
+        );
+    }
+
+    #[test]
+    fn arc_tolerance() {
+        let tolerance = 0.01;
+        let subpaths = parse_path_data(
+            "M-1,0 A1,1 0 1,0 1,0 A1,1 0 1,0 -1,0 Z",
+            tolerance,
+        )
+        .unwrap();
+
+        for [x, y] in &subpaths[0] {
+            let radius = (x * x + y * y).sqrt();
+            assert!((radius - 1.).abs() < 1e-9);
+        }
+
+        // Check the midpoint of each segment, which is furthest from the
+        // circle.
+        let points = &subpaths[0];
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            let [x, y] = [(a[0] + b[0]) / 2., (a[1] + b[1]) / 2.];
+            assert!(1. - (x * x + y * y).sqrt() <= tolerance);
+        }
+    }
+
+    #[test]
+    fn flip_and_orient() {
+        let sketches = SketchFromSvg::new("unused.svg")
+            .with_scale(2.)
+            .parse(r#"<svg><path d="M0,0 L0,1 L1,1 L1,0 Z"/></svg>"#)
+            .unwrap();
+
+        let points = sketches[0].to_points();
+        assert!(super::signed_area(&points) > 0.);
+        assert!(points.iter().all(|[_, y]| *y <= 0.));
+        assert!(points.contains(&[2., -2.]));
+    }
+
+    #[test]
+    fn invalid_scale() {
+        let svg = r#"<svg><path d="M0,0 L0,1 L1,1 Z"/></svg>"#;
+
+        for scale in [0., f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let result = SketchFromSvg::new("unused.svg")
+                .with_scale(scale)
+                .parse(svg);
+            assert!(matches!(result, Err(SvgError::Invalid(_))));
+        }
+    }
+}
@@ -28,6 +28,75 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Minkowski2d`]
+///
+/// [`fj::Minkowski2d`]: crate::Minkowski2d
+pub trait Minkowski {
+    /// Create a Minkowski sum of `self` and `other`
+    ///
+    /// Returns an error, if either of them is not convex.
+    fn minkowski<Other>(
+        &self,
+        other: &Other,
+    ) -> Result<crate::Minkowski2d, crate::NotConvex>
+    where
+        Other: Clone + Into<crate::Shape2d>;
+}
+
+impl<T> Minkowski for T
+where
+    T: Clone + Into<crate::Shape2d>,
+{
+    fn minkowski<Other>(
+        &self,
+        other: &Other,
+    ) -> Result<crate::Minkowski2d, crate::NotConvex>
+    where
+        Other: Clone + Into<crate::Shape2d>,
+    {
+        let a = self.clone().into();
+        let b = other.clone().into();
+
+        crate::Minkowski2d::from_shapes([a, b])
+    }
+}
+
+/// Convenient syntax to create an [`fj::Asserted`]
+///
+/// [`fj::Asserted`]: crate::Asserted
+pub trait Assert {
+    /// Attach an assertion to `self`
+    fn assert(&self, assertion: crate::Assertion) -> crate::Asserted;
+}
+
+impl<T> Assert for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn assert(&self, assertion: crate::Assertion) -> crate::Asserted {
+        let shape = self.clone().into();
+        crate::Asserted { shape, assertion }
+    }
+}
+
+/// Convenient syntax to create an [`fj::Exploded`]
+///
+/// [`fj::Exploded`]: crate::Exploded
+pub trait Explode {
+    /// Move `self` by `direction`, when its assembly is exploded
+    fn explode(&self, direction: [f64; 3]) -> crate::Exploded;
+}
+
+impl<T> Explode for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn explode(&self, direction: [f64; 3]) -> crate::Exploded {
+        let shape = self.clone().into();
+        crate::Exploded { shape, direction }
+    }
+}
+
 /// Convenient syntax to create an [`fj::Group`]
 ///
 /// [`fj::Group`]: crate::Group
@@ -53,6 +122,81 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Marked`]
+///
+/// [`fj::Marked`]: crate::Marked
+pub trait Mark {
+    /// Mark `self` as a specific kind of object
+    fn mark(&self, topology: crate::Topology) -> crate::Marked;
+}
+
+impl<T> Mark for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn mark(&self, topology: crate::Topology) -> crate::Marked {
+        let shape = self.clone().into();
+        crate::Marked { shape, topology }
+    }
+}
+
+/// Convenient syntax to create an [`fj::WithMaterial`]
+///
+/// [`fj::WithMaterial`]: crate::WithMaterial
+pub trait Material {
+    /// Make `self` of `material`
+    fn material(&self, material: crate::Material) -> crate::WithMaterial;
+}
+
+impl<T> Material for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn material(&self, material: crate::Material) -> crate::WithMaterial {
+        let shape = self.clone().into();
+        crate::WithMaterial { shape, material }
+    }
+}
+
+/// Convenient syntax to create an [`fj::Named`]
+///
+/// [`fj::Named`]: crate::Named
+pub trait Name {
+    /// Give `self` a name
+    fn named(&self, name: impl Into<String>) -> crate::Named;
+}
+
+impl<T> Name for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn named(&self, name: impl Into<String>) -> crate::Named {
+        let shape = self.clone().into();
+        crate::Named {
+            shape,
+            name: name.into(),
+        }
+    }
+}
+
+/// Convenient syntax to create an [`fj::Rounded`]
+///
+/// [`fj::Rounded`]: crate::Rounded
+pub trait Round {
+    /// Grow `self` by a sphere of the given radius
+    fn rounded(&self, radius: f64) -> crate::Rounded;
+}
+
+impl<T> Round for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn rounded(&self, radius: f64) -> crate::Rounded {
+        let shape = self.clone().into();
+        crate::Rounded::from_shape(shape, radius)
+    }
+}
+
 /// Convenient syntax to create an [`fj::Sketch`]
 ///
 /// [`fj::Sketch`]: crate::Sketch
@@ -91,6 +235,36 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Textured`]
+///
+/// [`fj::Textured`]: crate::Textured
+pub trait Texture {
+    /// Apply `texture` to the faces of `self` that `faces` selects
+    fn texture(
+        &self,
+        faces: crate::selection::FaceSelection,
+        texture: crate::Texture,
+    ) -> crate::Textured;
+}
+
+impl<T> Texture for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn texture(
+        &self,
+        faces: crate::selection::FaceSelection,
+        texture: crate::Texture,
+    ) -> crate::Textured {
+        let shape = self.clone().into();
+        crate::Textured {
+            shape,
+            faces,
+            texture,
+        }
+    }
+}
+
 /// Convenient syntax to create an [`fj::Transform`]
 ///
 /// [`fj::Transform`]: crate::Transform
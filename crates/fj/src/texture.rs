@@ -0,0 +1,96 @@
+use crate::{selection::FaceSelection, Shape, Shape3d};
+
+/// A 3-dimensional shape, with a texture applied to some of its faces
+///
+/// The texture is a repeating pattern, like a knurl, that is displaced out of
+/// or into the selected faces, when the shape is triangulated. This is useful
+/// for grip surfaces on printed parts, for example.
+///
+/// The boundary of each face stays where it is, so the texture fades out within
+/// an eighth of a pitch of each edge. Textures don't affect the evaluated
+/// shape, only its triangle mesh.
+///
+/// # Example
+///
+/// ```
+/// use fj::{selection::faces, syntax::*};
+///
+/// let grip = fj::Circle::from_radius(10.)
+///     .sweep([0., 0., 20.])
+///     .texture(faces(), fj::Texture::knurl(0.5, 2.));
+/// ```
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Textured {
+    /// The shape that is textured
+    pub shape: Shape3d,
+
+    /// The faces of the shape that get the texture
+    pub faces: FaceSelection,
+
+    /// The texture that is applied to the faces
+    pub texture: Texture,
+}
+
+impl From<Textured> for Shape {
+    fn from(shape: Textured) -> Self {
+        Self::Shape3d(Shape3d::Textured(Box::new(shape)))
+    }
+}
+
+impl From<Textured> for Shape3d {
+    fn from(shape: Textured) -> Self {
+        Self::Textured(Box::new(shape))
+    }
+}
+
+/// A repeating pattern, that is displaced out of or into a face
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct Texture {
+    /// The shape of the pattern
+    pub pattern: Pattern,
+
+    /// How far the pattern is displaced, at its highest point
+    ///
+    /// Positive values raise the pattern out of the face, negative values cut
+    /// it into the face.
+    pub amplitude: f64,
+
+    /// The distance over which the pattern repeats
+    pub pitch: f64,
+}
+
+impl Texture {
+    /// Create a diamond knurl
+    pub fn knurl(amplitude: f64, pitch: f64) -> Self {
+        Self {
+            pattern: Pattern::Knurl,
+            amplitude,
+            pitch,
+        }
+    }
+
+    /// Create parallel ridges
+    pub fn ripple(amplitude: f64, pitch: f64) -> Self {
+        Self {
+            pattern: Pattern::Ripple,
+            amplitude,
+            pitch,
+        }
+    }
+}
+
+/// The shape of a [`Texture`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum Pattern {
+    /// A diamond knurl, made of pyramids with diagonal grooves between them
+    Knurl,
+
+    /// Parallel ridges with a smooth profile
+    ///
+    /// On the side of a swept circle, the ridges run along the direction of
+    /// the sweep.
+    Ripple,
+}
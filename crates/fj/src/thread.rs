@@ -0,0 +1,239 @@
+use std::f64::consts::PI;
+
+use crate::Implicit;
+
+/// A screw thread, with the basic profile of ISO metric threads
+///
+/// Threads are right-handed, and run along the z-axis. They are created as
+/// [`Implicit`] shapes, either as a threaded rod, using [`Thread::external`],
+/// or as a sleeve with a threaded hole, using [`Thread::internal`]. Both can be
+/// grouped and transformed like any other shape.
+///
+/// # Example
+///
+/// ```
+/// // The shank of an M3 screw, 10 mm long.
+/// let thread = fj::Thread::metric_coarse(3.).unwrap();
+/// let shank = thread.external(10.);
+///
+/// // A matching insert, with some clearance for a printed part.
+/// let insert = thread.with_clearance(0.1).internal(10., 5.);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thread {
+    diameter: f64,
+    pitch: f64,
+    clearance: f64,
+}
+
+impl Thread {
+    /// Create an ISO metric thread with the given diameter and pitch
+    ///
+    /// This is useful for fine threads. Please refer to
+    /// [`Thread::metric_coarse`] for the more common coarse ones.
+    pub fn metric(diameter: f64, pitch: f64) -> Self {
+        Self {
+            diameter,
+            pitch,
+            clearance: 0.,
+        }
+    }
+
+    /// Create an ISO metric coarse thread, like M3 or M5
+    ///
+    /// The pitch is looked up from the diameter. Returns `None`, if the
+    /// diameter is not a standard size for coarse threads.
+    pub fn metric_coarse(diameter: f64) -> Option<Self> {
+        METRIC_COARSE
+            .iter()
+            .find(|&&(standard, _)| standard == diameter)
+            .map(|&(_, pitch)| Self::metric(diameter, pitch))
+    }
+
+    /// Set the clearance of the thread
+    ///
+    /// The clearance is how far the surface of the thread is moved away from
+    /// its basic profile, to make room for the mating thread. External
+    /// threads get thinner, internal threads wider. Parts that are printed
+    /// usually need some clearance to fit.
+    pub fn with_clearance(mut self, clearance: f64) -> Self {
+        self.clearance = clearance;
+        self
+    }
+
+    /// Access the nominal diameter of the thread
+    ///
+    /// This is the major diameter of the basic profile.
+    pub fn diameter(&self) -> f64 {
+        self.diameter
+    }
+
+    /// Access the minor diameter of the basic profile
+    pub fn minor_diameter(&self) -> f64 {
+        self.diameter - 2. * 5. / 8. * self.height()
+    }
+
+    /// Access the distance between two turns of the thread
+    pub fn pitch(&self) -> f64 {
+        self.pitch
+    }
+
+    /// Access the clearance of the thread
+    pub fn clearance(&self) -> f64 {
+        self.clearance
+    }
+
+    /// Create a threaded rod, from z = 0 to `length`
+    pub fn external(&self, length: f64) -> Implicit {
+        let thread = *self;
+        let radius = self.diameter / 2.;
+
+        let min = [-radius, -radius, 0.];
+        let max = [radius, radius, length];
+        Implicit::from_fn(min, max, move |point| {
+            let [x, y, z] = point;
+            let distance = (x * x + y * y).sqrt() - thread.radius_at(point)
+                + thread.clearance;
+
+            distance.max(-z).max(z - length)
+        })
+        .with_resolution(self.pitch / 8.)
+    }
+
+    /// Create a sleeve with a threaded hole, from z = 0 to `length`
+    ///
+    /// This is the shape of a nut, or of a threaded insert, with a round
+    /// outside.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `outer_diameter` is not larger than the diameter of the
+    /// thread, as the sleeve wouldn't have a wall.
+    pub fn internal(&self, length: f64, outer_diameter: f64) -> Implicit {
+        assert!(
+            outer_diameter > self.diameter,
+            "Sleeve must be wider than its thread"
+        );
+
+        let thread = *self;
+        let radius = outer_diameter / 2.;
+
+        let min = [-radius, -radius, 0.];
+        let max = [radius, radius, length];
+        Implicit::from_fn(min, max, move |point| {
+            let [x, y, z] = point;
+            let distance_from_axis = (x * x + y * y).sqrt();
+            let distance =
+                thread.radius_at(point) + thread.clearance - distance_from_axis;
+
+            distance
+                .max(distance_from_axis - radius)
+                .max(-z)
+                .max(z - length)
+        })
+        .with_resolution(self.pitch / 8.)
+    }
+
+    /// The height of the fundamental triangle of the thread
+    fn height(&self) -> f64 {
+        3_f64.sqrt() / 2. * self.pitch
+    }
+
+    /// The radius of the basic profile, at the angle and height of a point
+    ///
+    /// The profile is a 60° V, whose crest is cut off at an eighth of its
+    /// height, and whose root is cut off at a quarter.
+    fn radius_at(&self, [x, y, z]: [f64; 3]) -> f64 {
+        let height = self.height();
+        let major = self.diameter / 2.;
+        let minor = self.minor_diameter() / 2.;
+
+        // Where the point is within one turn of the thread, from `0` to `1`.
+        // The crest of the thread is at `0.5`.
+        let turn = y.atan2(x) / (PI * 2.);
+        let phase = (z / self.pitch - turn).rem_euclid(1.);
+
+        let sharp = major + height / 8. - (phase - 0.5).abs() * 2. * height;
+        sharp.clamp(minor, major)
+    }
+}
+
+/// The diameters of ISO metric coarse threads, and their pitches
+const METRIC_COARSE: &[(f64, f64)] = &[
+    (1., 0.25),
+    (1.2, 0.25),
+    (1.4, 0.3),
+    (1.6, 0.35),
+    (2., 0.4),
+    (2.5, 0.45),
+    (3., 0.5),
+    (3.5, 0.6),
+    (4., 0.7),
+    (5., 0.8),
+    (6., 1.),
+    (8., 1.25),
+    (10., 1.5),
+    (12., 1.75),
+    (14., 2.),
+    (16., 2.),
+    (18., 2.5),
+    (20., 2.5),
+    (22., 2.5),
+    (24., 3.),
+    (27., 3.),
+    (30., 3.5),
+    (36., 4.),
+    (42., 4.5),
+    (48., 5.),
+    (56., 5.5),
+    (64., 6.),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::Thread;
+
+    #[test]
+    fn coarse_pitch() {
+        let thread = Thread::metric_coarse(3.).unwrap();
+
+        assert_eq!(thread.pitch(), 0.5);
+        assert!((thread.minor_diameter() - 2.459).abs() < 0.001);
+        assert_eq!(Thread::metric_coarse(7.), None);
+    }
+
+    #[test]
+    fn external_thread() {
+        let thread = Thread::metric(4., 1.);
+        let rod = thread.external(10.);
+
+        assert!(rod.distance([0., 0., 5.]) < 0.);
+        assert!(rod.distance([0., 0., 11.]) > 0.);
+
+        // Along the x-axis, the crest is halfway between two whole pitches,
+        // and the root at each of them.
+        assert!(rod.distance([1.9, 0., 5.5]) < 0.);
+        assert!(rod.distance([1.9, 0., 5.]) > 0.);
+
+        // Half a turn later, crest and root have swapped.
+        assert!(rod.distance([-1.9, 0., 5.]) < 0.);
+
+        let loose = thread.with_clearance(0.2).external(10.);
+        assert!(loose.distance([1.9, 0., 5.5]) > 0.);
+    }
+
+    #[test]
+    fn internal_thread() {
+        let thread = Thread::metric(4., 1.);
+        let sleeve = thread.internal(10., 6.);
+
+        assert!(sleeve.distance([0., 0., 5.]) > 0.);
+        assert!(sleeve.distance([2.5, 0., 5.]) < 0.);
+        assert!(sleeve.distance([3.5, 0., 5.]) > 0.);
+
+        // The crest of the external thread is where the internal one has its
+        // root.
+        assert!(sleeve.distance([1.9, 0., 5.5]) > 0.);
+        assert!(sleeve.distance([1.9, 0., 5.]) < 0.);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::Shape;
+
+/// What kind of object a shape is meant to be
+///
+/// Determines which rules apply to the shape, when it is checked or exported.
+/// Shapes that aren't marked explicitly, using [`Marked`], are assumed to be
+/// sheets, if they are 2-dimensional, and solids otherwise.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum Topology {
+    /// A closed volume, whose boundary must be watertight
+    Solid,
+
+    /// A surface that doesn't enclose a volume, and may have open edges
+    Sheet,
+
+    /// Curves, without any faces
+    Wire,
+}
+
+/// A shape that is explicitly marked as a specific kind of object
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Marked {
+    /// The shape being marked
+    pub shape: Shape,
+
+    /// The kind of object the shape is meant to be
+    pub topology: Topology,
+}
+
+impl From<Marked> for Shape {
+    fn from(shape: Marked) -> Self {
+        Self::Marked(Box::new(shape))
+    }
+}
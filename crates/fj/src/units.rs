@@ -0,0 +1,617 @@
+use std::{error, fmt, iter::Peekable, ops, str::Chars, str::FromStr};
+
+/// A length
+///
+/// Lengths are stored in model units, which are millimeters. Converting from
+/// and to other units happens explicitly, so lengths in different units can be
+/// mixed freely:
+///
+/// ``` rust
+/// use fj::Length;
+///
+/// let length = Length::mm(5) + Length::inch(0.25);
+/// assert_eq!(length.to_mm(), 11.35);
+/// ```
+///
+/// Lengths can also be parsed from expressions, like `5 mm + 0.25 in`. Please
+/// refer to the implementation of [`FromStr`] for details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Length {
+    mm: f64,
+}
+
+impl Length {
+    /// Create a length in micrometers
+    pub fn um(value: impl Into<f64>) -> Self {
+        Self::mm(value.into() / 1000.)
+    }
+
+    /// Create a length in millimeters
+    pub fn mm(value: impl Into<f64>) -> Self {
+        Self { mm: value.into() }
+    }
+
+    /// Create a length in centimeters
+    pub fn cm(value: impl Into<f64>) -> Self {
+        Self::mm(value.into() * 10.)
+    }
+
+    /// Create a length in meters
+    pub fn m(value: impl Into<f64>) -> Self {
+        Self::mm(value.into() * 1000.)
+    }
+
+    /// Create a length in inches
+    pub fn inch(value: impl Into<f64>) -> Self {
+        Self::mm(value.into() * MM_PER_INCH)
+    }
+
+    /// Create a length in feet
+    pub fn ft(value: impl Into<f64>) -> Self {
+        Self::inch(value.into() * 12.)
+    }
+
+    /// Access the length in millimeters, which are the model units
+    pub fn to_mm(self) -> f64 {
+        self.mm
+    }
+
+    /// Access the length in inches
+    pub fn to_inch(self) -> f64 {
+        self.mm / MM_PER_INCH
+    }
+}
+
+impl From<Length> for f64 {
+    fn from(length: Length) -> Self {
+        length.to_mm()
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} mm", self.mm)
+    }
+}
+
+/// Parse a length from an expression
+///
+/// Expressions consist of numbers with units, which can be combined using
+/// `+`, `-`, `*`, `/`, and parentheses. Supported units are `um`, `mm`, `cm`,
+/// `m`, `in`, and `ft`. Numbers without a unit can be used as factors, like in
+/// `2 * (5 mm + 0.25 in)`.
+///
+/// Expressions that don't result in a length, like `5` or `5 mm * 2 mm`, are
+/// rejected, as are expressions that mix lengths and angles.
+impl FromStr for Length {
+    type Err = ExpressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let quantity = evaluate(s)?;
+        match quantity.dimension {
+            Dimension::Length => Ok(Self::mm(quantity.value)),
+            dimension => Err(ExpressionError::WrongDimension {
+                expected: Dimension::Length,
+                actual: dimension,
+            }),
+        }
+    }
+}
+
+impl ops::Neg for Length {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::mm(-self.mm)
+    }
+}
+
+impl ops::Add for Length {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self::mm(self.mm + other.mm)
+    }
+}
+
+impl ops::AddAssign for Length {
+    fn add_assign(&mut self, other: Self) {
+        self.mm += other.mm;
+    }
+}
+
+impl ops::Sub for Length {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self::mm(self.mm - other.mm)
+    }
+}
+
+impl ops::SubAssign for Length {
+    fn sub_assign(&mut self, other: Self) {
+        self.mm -= other.mm;
+    }
+}
+
+impl ops::Mul<f64> for Length {
+    type Output = Self;
+
+    fn mul(self, factor: f64) -> Self::Output {
+        Self::mm(self.mm * factor)
+    }
+}
+
+impl ops::Mul<Length> for f64 {
+    type Output = Length;
+
+    fn mul(self, length: Length) -> Self::Output {
+        length * self
+    }
+}
+
+impl ops::Div<f64> for Length {
+    type Output = Self;
+
+    fn div(self, divisor: f64) -> Self::Output {
+        Self::mm(self.mm / divisor)
+    }
+}
+
+impl ops::Div for Length {
+    type Output = f64;
+
+    fn div(self, other: Self) -> Self::Output {
+        self.mm / other.mm
+    }
+}
+
+/// An angle
+///
+/// Angles are stored in radians, which is what operations like
+/// [`Transform`] expect.
+///
+/// [`Transform`]: crate::Transform
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Angle {
+    rad: f64,
+}
+
+impl Angle {
+    /// Create an angle in radians
+    pub fn rad(value: impl Into<f64>) -> Self {
+        Self { rad: value.into() }
+    }
+
+    /// Create an angle in degrees
+    pub fn deg(value: impl Into<f64>) -> Self {
+        Self::rad(value.into().to_radians())
+    }
+
+    /// Access the angle in radians
+    pub fn to_rad(self) -> f64 {
+        self.rad
+    }
+
+    /// Access the angle in degrees
+    pub fn to_deg(self) -> f64 {
+        self.rad.to_degrees()
+    }
+}
+
+impl From<Angle> for f64 {
+    fn from(angle: Angle) -> Self {
+        angle.to_rad()
+    }
+}
+
+impl fmt::Display for Angle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} deg", self.to_deg())
+    }
+}
+
+/// Parse an angle from an expression
+///
+/// This works like parsing a [`Length`], except that the supported units are
+/// `deg` and `rad`.
+impl FromStr for Angle {
+    type Err = ExpressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let quantity = evaluate(s)?;
+        match quantity.dimension {
+            Dimension::Angle => Ok(Self::rad(quantity.value)),
+            dimension => Err(ExpressionError::WrongDimension {
+                expected: Dimension::Angle,
+                actual: dimension,
+            }),
+        }
+    }
+}
+
+impl ops::Neg for Angle {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::rad(-self.rad)
+    }
+}
+
+impl ops::Add for Angle {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self::rad(self.rad + other.rad)
+    }
+}
+
+impl ops::Sub for Angle {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self::rad(self.rad - other.rad)
+    }
+}
+
+impl ops::Mul<f64> for Angle {
+    type Output = Self;
+
+    fn mul(self, factor: f64) -> Self::Output {
+        Self::rad(self.rad * factor)
+    }
+}
+
+impl ops::Div<f64> for Angle {
+    type Output = Self;
+
+    fn div(self, divisor: f64) -> Self::Output {
+        Self::rad(self.rad / divisor)
+    }
+}
+
+/// What kind of quantity an expression describes
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dimension {
+    /// A plain number, without a unit
+    Number,
+
+    /// A length
+    Length,
+
+    /// An angle
+    Angle,
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Number => write!(f, "number"),
+            Self::Length => write!(f, "length"),
+            Self::Angle => write!(f, "angle"),
+        }
+    }
+}
+
+/// An error evaluating an expression
+#[derive(Debug, PartialEq)]
+pub enum ExpressionError {
+    /// The expression is malformed
+    Syntax(String),
+
+    /// The expression contains a unit that is not known
+    UnknownUnit(String),
+
+    /// The expression combines quantities that don't fit together
+    ///
+    /// This happens when adding a length to an angle, for example.
+    Incompatible {
+        /// The operation
+        operation: char,
+
+        /// The left operand
+        a: Dimension,
+
+        /// The right operand
+        b: Dimension,
+    },
+
+    /// The expression divides by zero
+    DivisionByZero,
+
+    /// The expression results in a different kind of quantity than expected
+    WrongDimension {
+        /// The kind of quantity that was expected
+        expected: Dimension,
+
+        /// The kind of quantity the expression results in
+        actual: Dimension,
+    },
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax(message) => {
+                write!(f, "Invalid expression: {}", message)
+            }
+            Self::UnknownUnit(unit) => write!(f, "Unknown unit `{}`", unit),
+            Self::Incompatible { operation, a, b } => {
+                write!(f, "Can't apply `{}` to {} and {}", operation, a, b)
+            }
+            Self::DivisionByZero => write!(f, "Division by zero"),
+            Self::WrongDimension {
+                expected,
+                actual: Dimension::Number,
+            } => {
+                write!(f, "Expected {}, but the value has no unit", expected)
+            }
+            Self::WrongDimension { expected, actual } => {
+                write!(f, "Expected {}, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl error::Error for ExpressionError {}
+
+const MM_PER_INCH: f64 = 25.4;
+
+/// The result of evaluating an expression, in millimeters or radians
+#[derive(Clone, Copy, Debug)]
+struct Quantity {
+    value: f64,
+    dimension: Dimension,
+}
+
+fn evaluate(expression: &str) -> Result<Quantity, ExpressionError> {
+    let mut parser = Parser {
+        chars: expression.chars().peekable(),
+    };
+
+    let quantity = parser.sum()?;
+
+    parser.skip_whitespace();
+    if let Some(c) = parser.chars.next() {
+        return Err(ExpressionError::Syntax(format!("Unexpected `{}`", c)));
+    }
+
+    Ok(quantity)
+}
+
+/// A recursive descent parser that evaluates expressions as it goes
+struct Parser<'r> {
+    chars: Peekable<Chars<'r>>,
+}
+
+impl Parser<'_> {
+    fn sum(&mut self) -> Result<Quantity, ExpressionError> {
+        let mut a = self.product()?;
+
+        loop {
+            self.skip_whitespace();
+
+            let operation = match self.chars.peek() {
+                Some(&c) if c == '+' || c == '-' => c,
+                _ => return Ok(a),
+            };
+            self.chars.next();
+
+            let b = self.product()?;
+            if a.dimension != b.dimension {
+                return Err(ExpressionError::Incompatible {
+                    operation,
+                    a: a.dimension,
+                    b: b.dimension,
+                });
+            }
+
+            if operation == '+' {
+                a.value += b.value;
+            } else {
+                a.value -= b.value;
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Quantity, ExpressionError> {
+        let mut a = self.factor()?;
+
+        loop {
+            self.skip_whitespace();
+
+            let operation = match self.chars.peek() {
+                Some(&c) if c == '*' || c == '/' => c,
+                _ => return Ok(a),
+            };
+            self.chars.next();
+
+            let b = self.factor()?;
+            let incompatible = ExpressionError::Incompatible {
+                operation,
+                a: a.dimension,
+                b: b.dimension,
+            };
+
+            if operation == '*' {
+                a.dimension = match (a.dimension, b.dimension) {
+                    (dimension, Dimension::Number)
+                    | (Dimension::Number, dimension) => dimension,
+                    _ => return Err(incompatible),
+                };
+                a.value *= b.value;
+            } else {
+                a.dimension = match (a.dimension, b.dimension) {
+                    (dimension, Dimension::Number) => dimension,
+                    (x, y) if x == y => Dimension::Number,
+                    _ => return Err(incompatible),
+                };
+
+                if b.value == 0. {
+                    return Err(ExpressionError::DivisionByZero);
+                }
+                a.value /= b.value;
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<Quantity, ExpressionError> {
+        self.skip_whitespace();
+
+        let quantity = match self.chars.peek() {
+            Some('-') => {
+                self.chars.next();
+
+                let mut quantity = self.factor()?;
+                quantity.value = -quantity.value;
+                return Ok(quantity);
+            }
+            Some('(') => {
+                self.chars.next();
+
+                let quantity = self.sum()?;
+
+                self.skip_whitespace();
+                if self.chars.next() != Some(')') {
+                    return Err(ExpressionError::Syntax(
+                        "Expected `)`".to_owned(),
+                    ));
+                }
+
+                quantity
+            }
+            _ => Quantity {
+                value: self.number()?,
+                dimension: Dimension::Number,
+            },
+        };
+
+        self.unit(quantity)
+    }
+
+    fn number(&mut self) -> Result<f64, ExpressionError> {
+        let mut number = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_ascii_digit() || c == '.') {
+                break;
+            }
+
+            number.push(c);
+            self.chars.next();
+        }
+
+        match self.chars.peek() {
+            _ if !number.is_empty() => {}
+            Some(c) => {
+                return Err(ExpressionError::Syntax(format!(
+                    "Unexpected `{}`",
+                    c
+                )));
+            }
+            None => {
+                return Err(ExpressionError::Syntax(
+                    "Unexpected end".to_owned(),
+                ));
+            }
+        }
+
+        number.parse().map_err(|_| {
+            ExpressionError::Syntax(format!("Invalid number `{}`", number))
+        })
+    }
+
+    /// Apply the unit that follows a number or parenthesized expression
+    fn unit(
+        &mut self,
+        quantity: Quantity,
+    ) -> Result<Quantity, ExpressionError> {
+        self.skip_whitespace();
+
+        let mut unit = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_alphabetic() {
+                break;
+            }
+
+            unit.push(c);
+            self.chars.next();
+        }
+
+        if unit.is_empty() {
+            return Ok(quantity);
+        }
+
+        let (factor, dimension) = match unit.as_str() {
+            "um" => (0.001, Dimension::Length),
+            "mm" => (1., Dimension::Length),
+            "cm" => (10., Dimension::Length),
+            "m" => (1000., Dimension::Length),
+            "in" | "inch" => (MM_PER_INCH, Dimension::Length),
+            "ft" => (MM_PER_INCH * 12., Dimension::Length),
+            "rad" => (1., Dimension::Angle),
+            "deg" => (1f64.to_radians(), Dimension::Angle),
+            _ => return Err(ExpressionError::UnknownUnit(unit)),
+        };
+
+        if quantity.dimension != Dimension::Number {
+            return Err(ExpressionError::Syntax(format!(
+                "Unit `{}` applied to a value that already has a unit",
+                unit
+            )));
+        }
+
+        Ok(Quantity {
+            value: quantity.value * factor,
+            dimension,
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Angle, Dimension, ExpressionError, Length};
+
+    #[test]
+    fn combine_lengths() {
+        let length = Length::mm(5) + Length::inch(0.25);
+        assert!((length.to_mm() - 11.35).abs() < 1e-9);
+    }
+
+    #[test]
+    fn expressions() {
+        let length: Length = "2 * (5 mm + 0.25 in) - 1cm / 2".parse().unwrap();
+        assert!((length.to_mm() - 17.7).abs() < 1e-9);
+
+        let angle: Angle = "-(90 deg) + 3.14159 rad / 3.14159".parse().unwrap();
+        assert!((angle.to_deg() + 90. - 1f64.to_degrees()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn expressions_with_mixed_up_units() {
+        assert_eq!(
+            "5".parse::<Length>(),
+            Err(ExpressionError::WrongDimension {
+                expected: Dimension::Length,
+                actual: Dimension::Number,
+            })
+        );
+        assert_eq!(
+            "5 mm + 1 deg".parse::<Length>(),
+            Err(ExpressionError::Incompatible {
+                operation: '+',
+                a: Dimension::Length,
+                b: Dimension::Angle,
+            })
+        );
+        assert!("5 mm * 2 mm".parse::<Length>().is_err());
+        assert!("5 mm / 0".parse::<Length>().is_err());
+        assert!("5 parsecs".parse::<Length>().is_err());
+        assert!("(5 mm".parse::<Length>().is_err());
+    }
+}
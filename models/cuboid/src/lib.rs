@@ -2,9 +2,9 @@ use std::collections::HashMap;
 
 #[no_mangle]
 pub extern "C" fn model(args: &HashMap<String, String>) -> fj::Shape {
-    let x: f64 = args.get("x").unwrap_or(&"3.0".to_owned()).parse().unwrap();
-    let y: f64 = args.get("y").unwrap_or(&"2.0".to_owned()).parse().unwrap();
-    let z: f64 = args.get("z").unwrap_or(&"1.0".to_owned()).parse().unwrap();
+    let x = length(args, "x", "3 mm");
+    let y = length(args, "y", "2 mm");
+    let z = length(args, "z", "1 mm");
 
     #[rustfmt::skip]
     let rectangle = fj::Sketch::from_points(vec![
@@ -18,3 +18,13 @@ pub extern "C" fn model(args: &HashMap<String, String>) -> fj::Shape {
 
     cuboid.into()
 }
+
+/// Read a length parameter, like `x=5mm` or `x=0.25in`
+fn length(args: &HashMap<String, String>, name: &str, default: &str) -> f64 {
+    let value = args.get(name).map_or(default, String::as_str);
+
+    let length: fj::Length = value
+        .parse()
+        .unwrap_or_else(|err| panic!("Invalid parameter `{}`: {}", name, err));
+    length.to_mm()
+}
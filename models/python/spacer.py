@@ -0,0 +1,17 @@
+# The spacer model, written in Python. Run it with:
+#
+#     cargo run --features python -- -m python/spacer.py
+
+import fornjot
+
+
+def model(parameters):
+    outer = parameters.float_or("outer", 1.0)
+    inner = parameters.float_or("inner", 0.5)
+    height = parameters.float_or("height", 1.0)
+
+    outer_edge = fornjot.circle(outer).with_color((0, 0, 255, 255))
+    inner_edge = fornjot.circle(inner)
+
+    footprint = fornjot.difference(outer_edge, inner_edge)
+    return fornjot.sweep(footprint, (0.0, 0.0, height))
@@ -0,0 +1,126 @@
+//! Regression tests for the example models
+//!
+//! Evaluates each model in `models/`, and compares its triangle mesh to the
+//! snapshot that is stored for it. Please refer to the README for how to use
+//! this.
+
+mod snapshot;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context as _};
+use fj_host::{Model, Parameters};
+use fj_kernel::algorithms::Tolerance;
+use fj_operations::shape_processor::ShapeProcessor;
+
+use self::snapshot::Difference;
+
+/// The directory that the snapshots are stored in
+const SNAPSHOTS: &str = "tools/fj-test/snapshots";
+
+/// The tolerance that models are triangulated with, and compared within
+const TOLERANCE: f64 = 0.001;
+
+fn main() -> anyhow::Result<()> {
+    let update = env::args().skip(1).any(|arg| arg == "--update");
+
+    // Can't panic. The tolerance is positive.
+    let tolerance = Tolerance::from_scalar(TOLERANCE).unwrap();
+    let shape_processor = ShapeProcessor {
+        tolerance: Some(tolerance),
+        decimation: None,
+        deterministic: true,
+        min_wall_thickness: None,
+        draft: None,
+    };
+
+    let mut failed = Vec::new();
+
+    for (name, model) in models()? {
+        let shape = model
+            .load_once(&Parameters::empty())
+            .with_context(|| format!("Failed to load model `{name}`"))?;
+        let mesh = shape_processor.process(&shape).mesh;
+
+        let path = PathBuf::from(SNAPSHOTS).join(format!("{name}.mesh"));
+        if update {
+            snapshot::save(&path, &mesh)?;
+            println!("{name}: snapshot written");
+            continue;
+        }
+
+        // A missing snapshot is a failure. Otherwise, forgetting to commit
+        // one would mean the model is never tested.
+        let snapshot = match snapshot::load(&path)? {
+            Some(snapshot) => snapshot,
+            None => {
+                println!("{name}: FAILED, no snapshot at {}", path.display());
+                failed.push(name);
+                continue;
+            }
+        };
+
+        match snapshot::compare(&snapshot, &mesh, tolerance) {
+            Ok(()) => println!("{name}: ok"),
+            Err(Difference::Empty) => {
+                println!(
+                    "{name}: FAILED, only one of mesh and snapshot is empty"
+                );
+                failed.push(name);
+            }
+            Err(Difference::Deviation { added, removed }) => {
+                println!(
+                    "{name}: FAILED, deviates from the snapshot by up to \
+                    {added}, and the snapshot from it by up to {removed}"
+                );
+                failed.push(name);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        bail!(
+            "Models differ from their snapshots, or don't have one: {}\n\
+            If the changes are intended, run again with `--update`.",
+            failed.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Find the example models, along with the names of their snapshots
+///
+/// Those are the Rust models in the subdirectories of `models/`, and the
+/// OpenSCAD models and scripts in `models/openscad/` and `models/scripts/`.
+/// The snapshots of the latter are named after their files, with extension, so
+/// they don't clash with the Rust models they are equivalent to.
+fn models() -> anyhow::Result<Vec<(String, Model)>> {
+    // Compiled models end up in the target directory of the workspace, as they
+    // are members of it.
+    let target_dir = Some(PathBuf::from("target"));
+
+    let mut models = Model::find_all(Path::new("models"), target_dir.clone())
+        .context("Failed to find models")?;
+
+    for dir in ["models/openscad", "models/scripts"] {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            files.push(entry?.path());
+        }
+        files.sort();
+
+        for path in files {
+            // Can't panic. `read_dir` doesn't return entries for `..`.
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let model = Model::from_path(path, target_dir.clone())?;
+
+            models.push((name, model));
+        }
+    }
+
+    Ok(models)
+}
@@ -0,0 +1,120 @@
+//! Storing meshes as snapshots, and comparing them
+//!
+//! Snapshots are text files with one triangle per line, each given by the
+//! coordinates of its three points. That's not compact, but changes to a
+//! snapshot can be reviewed in a diff.
+
+use std::{fmt::Write as _, fs, io, path::Path};
+
+use anyhow::{anyhow, Context as _};
+use fj_interop::mesh::Mesh;
+use fj_kernel::algorithms::{mesh_deviation, Tolerance};
+use fj_math::{Point, Scalar};
+
+/// Load the snapshot at `path`
+///
+/// Returns `None`, if there is no snapshot yet.
+pub fn load(path: &Path) -> anyhow::Result<Option<Mesh<Point<3>>>> {
+    let snapshot = match fs::read_to_string(path) {
+        Ok(snapshot) => snapshot,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| {
+                format!("Failed to read snapshot: {}", path.display())
+            })
+        }
+    };
+
+    let mut mesh = Mesh::new();
+
+    for (i, line) in snapshot.lines().enumerate() {
+        let coords = line
+            .split_whitespace()
+            .map(|coord| coord.parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|coords| coords.len() == 9)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid triangle in snapshot {}, line {}: `{}`",
+                    path.display(),
+                    i + 1,
+                    line
+                )
+            })?;
+
+        let [a, b, c] = [0, 3, 6]
+            .map(|i| Point::from([coords[i], coords[i + 1], coords[i + 2]]));
+        mesh.push_triangle([a, b, c], [255, 0, 0, 255]);
+    }
+
+    Ok(Some(mesh))
+}
+
+/// Store `mesh` as the snapshot at `path`, replacing any previous one
+pub fn save(path: &Path, mesh: &Mesh<Point<3>>) -> anyhow::Result<()> {
+    let mut snapshot = String::new();
+
+    for triangle in mesh.triangles() {
+        let coords: Vec<_> = triangle
+            .points
+            .iter()
+            .flat_map(|point| point.coords.components)
+            .map(|coord| coord.into_f64().to_string())
+            .collect();
+
+        // Can't fail. Writing to a `String` doesn't.
+        writeln!(snapshot, "{}", coords.join(" ")).unwrap();
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, snapshot).with_context(|| {
+        format!("Failed to write snapshot: {}", path.display())
+    })
+}
+
+/// Compare a mesh to its snapshot
+///
+/// Returns how far the meshes deviate from each other, in both directions, if
+/// that's more than `tolerance`. Differences that stay within the tolerance can
+/// come from a different, but just as valid approximation of the same shape,
+/// and don't count.
+pub fn compare(
+    snapshot: &Mesh<Point<3>>,
+    mesh: &Mesh<Point<3>>,
+    tolerance: Tolerance,
+) -> Result<(), Difference> {
+    // A mesh without triangles can't be measured against, so that case is
+    // decided by whether both are empty.
+    let (added, removed) = match (
+        mesh_deviation(mesh, snapshot),
+        mesh_deviation(snapshot, mesh),
+    ) {
+        (Some(added), Some(removed)) => (added.max, removed.max),
+        (None, None) => return Ok(()),
+        _ => return Err(Difference::Empty),
+    };
+
+    if added > tolerance.inner() || removed > tolerance.inner() {
+        return Err(Difference::Deviation { added, removed });
+    }
+
+    Ok(())
+}
+
+/// How a mesh differs from its snapshot
+pub enum Difference {
+    /// Either the mesh or the snapshot has no triangles, but not both
+    Empty,
+
+    /// The meshes deviate from each other
+    Deviation {
+        /// How far the mesh deviates from the snapshot, at most
+        added: Scalar,
+
+        /// How far the snapshot deviates from the mesh, at most
+        removed: Scalar,
+    },
+}
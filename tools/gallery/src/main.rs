@@ -0,0 +1,220 @@
+//! Generates a static HTML gallery of the example models
+//!
+//! Please refer to the README for how to use this.
+
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context as _};
+use fj_export::{export_drawing, View};
+use fj_host::{Model, Parameters};
+use fj_kernel::algorithms::mesh_volume;
+use fj_math::Scalar;
+use fj_operations::shape_processor::ShapeProcessor;
+
+fn main() -> anyhow::Result<()> {
+    let output = env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target/gallery"));
+    fs::create_dir_all(&output).with_context(|| {
+        format!("Failed to create directory: {}", output.display())
+    })?;
+
+    let shape_processor = ShapeProcessor {
+        tolerance: None,
+        decimation: None,
+        deterministic: false,
+        min_wall_thickness: None,
+        draft: None,
+    };
+
+    let mut entries = Vec::new();
+    let mut failed = Vec::new();
+
+    for (name, model) in models()? {
+        println!("Rendering {name}");
+
+        let result =
+            render(&name, &model, &shape_processor, &output).map_err(|err| {
+                eprintln!("Failed to render {name}: {err:#}");
+                failed.push(name.clone());
+                format!("{err:#}")
+            });
+
+        entries.push(Entry { name, result });
+    }
+
+    let index = output.join("index.html");
+    fs::write(&index, page(&entries))
+        .with_context(|| format!("Failed to write {}", index.display()))?;
+    println!("Gallery written to {}", index.display());
+
+    if !failed.is_empty() {
+        bail!("Failed to render models: {}", failed.join(", "));
+    }
+
+    Ok(())
+}
+
+/// A model in the gallery
+struct Entry {
+    name: String,
+
+    /// The statistics of the model, or the error that prevented rendering it
+    result: Result<Stats, String>,
+}
+
+/// What the gallery shows about a model
+struct Stats {
+    /// The file name of the drawing, relative to the gallery
+    thumbnail: String,
+
+    triangles: usize,
+    vertices: usize,
+    size: [f64; 3],
+
+    /// The enclosed volume, if the mesh is watertight
+    volume: Option<f64>,
+
+    /// The time it took to evaluate and triangulate the model
+    time: Duration,
+}
+
+/// Evaluate a model, and draw it into the output directory
+fn render(
+    name: &str,
+    model: &Model,
+    shape_processor: &ShapeProcessor,
+    output: &Path,
+) -> anyhow::Result<Stats> {
+    let start = Instant::now();
+    let shape = model.load_once(&Parameters::empty())?;
+    let shape = shape_processor.process(&shape);
+    let time = start.elapsed();
+
+    let thumbnail = format!("{name}.svg");
+    export_drawing(&shape.mesh, View::Isometric, &output.join(&thumbnail))?;
+
+    let volume = shape
+        .weld_report
+        .is_watertight()
+        .then(|| mesh_volume(&shape.mesh).into_f64());
+
+    Ok(Stats {
+        thumbnail,
+        triangles: shape.mesh.triangles().count(),
+        vertices: shape.mesh.vertices().count(),
+        size: shape.aabb.size().components.map(Scalar::into_f64),
+        volume,
+        time,
+    })
+}
+
+/// Generate the HTML page of the gallery
+fn page(entries: &[Entry]) -> String {
+    let mut page = String::from(
+        "<!DOCTYPE html>\n\
+        <html>\n\
+        <head>\n\
+        <meta charset=\"utf-8\">\n\
+        <title>Fornjot Model Gallery</title>\n\
+        <style>\n\
+        body { font-family: sans-serif; margin: 2em; }\n\
+        main { display: flex; flex-wrap: wrap; gap: 2em; }\n\
+        figure { margin: 0; width: 20em; }\n\
+        img { width: 100%; height: 15em; object-fit: contain; }\n\
+        .error { color: #c83232; white-space: pre-wrap; }\n\
+        </style>\n\
+        </head>\n\
+        <body>\n\
+        <h1>Fornjot Model Gallery</h1>\n\
+        <main>\n",
+    );
+
+    // Can't fail. Writing to a `String` doesn't.
+    for entry in entries {
+        let name = escape(&entry.name);
+        writeln!(page, "<figure>\n<h2>{name}</h2>").unwrap();
+
+        match &entry.result {
+            Ok(stats) => {
+                let [x, y, z] = stats.size;
+                let volume = match stats.volume {
+                    Some(volume) => format!("{volume:.3}"),
+                    None => String::from("not watertight"),
+                };
+
+                writeln!(
+                    page,
+                    "<img src=\"{}\" alt=\"{name}\">\n\
+                    <figcaption>\n\
+                    {} triangles, {} vertices<br>\n\
+                    Size: {x:.3} &times; {y:.3} &times; {z:.3}<br>\n\
+                    Volume: {volume}<br>\n\
+                    Evaluated in {:.2} s\n\
+                    </figcaption>",
+                    escape(&stats.thumbnail),
+                    stats.triangles,
+                    stats.vertices,
+                    stats.time.as_secs_f64(),
+                )
+                .unwrap();
+            }
+            Err(err) => {
+                writeln!(page, "<p class=\"error\">{}</p>", escape(err))
+                    .unwrap();
+            }
+        }
+
+        writeln!(page, "</figure>").unwrap();
+    }
+
+    page.push_str("</main>\n</body>\n</html>\n");
+    page
+}
+
+/// Escape text for use in HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Find the example models, along with their names
+///
+/// Those are the Rust models in the subdirectories of `models/`, and the
+/// OpenSCAD models and scripts in `models/openscad/` and `models/scripts/`,
+/// which are named after their files.
+fn models() -> anyhow::Result<Vec<(String, Model)>> {
+    // Compiled models end up in the target directory of the workspace, as they
+    // are members of it.
+    let target_dir = Some(PathBuf::from("target"));
+
+    let mut models = Model::find_all(Path::new("models"), target_dir.clone())
+        .context("Failed to find models")?;
+
+    for dir in ["models/openscad", "models/scripts"] {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            files.push(entry?.path());
+        }
+        files.sort();
+
+        for path in files {
+            // Can't panic. `read_dir` doesn't return entries for `..`.
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let model = Model::from_path(path, target_dir.clone())?;
+
+            models.push((name, model));
+        }
+    }
+
+    Ok(models)
+}